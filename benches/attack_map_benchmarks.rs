@@ -0,0 +1,76 @@
+//! Benchmarks for incrementally maintained attack maps
+//!
+//! Tracking makes every make/unmake pay for updating the maps; in return,
+//! attack queries are lookups instead of fresh attack generation. These
+//! benchmarks measure both sides of that tradeoff.
+//!
+//! cargo bench --bench attack_map_benchmarks --features legacy-tests
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use shogi_engine::bitboards::influence::InfluenceMap;
+use shogi_engine::bitboards::{attack_map::AttackMaps, BitboardBoard};
+use shogi_engine::moves::MoveGenerator;
+use shogi_engine::types::{Player, Position};
+
+const MIDDLEGAME_FEN: &str =
+    "ln1g1g1nl/1r1sk2b1/p1ppppspp/1p4p2/9/2P4P1/PP1PPPP1P/1BS4R1/LN1GKGSNL b - 1";
+
+fn board(tracked: bool) -> BitboardBoard {
+    let (mut board, _, _) = BitboardBoard::from_fen(MIDDLEGAME_FEN).unwrap();
+    if tracked {
+        board.enable_attack_maps();
+    }
+    board
+}
+
+/// Benchmark make/unmake of every legal move with and without tracking
+fn bench_make_unmake(c: &mut Criterion) {
+    let mut group = c.benchmark_group("attack_map_make_unmake");
+    let (reference, player, captured) = BitboardBoard::from_fen(MIDDLEGAME_FEN).unwrap();
+    let moves = MoveGenerator::new().generate_legal_moves(&reference, player, &captured);
+
+    for (name, tracked) in [("untracked", false), ("tracked", true)] {
+        let mut board = board(tracked);
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                for mv in &moves {
+                    let info = board.make_move_with_info(mv);
+                    board.unmake_move(black_box(&info));
+                }
+            });
+        });
+    }
+
+    group.finish();
+}
+
+/// Benchmark attack queries against tracked maps and fresh computation
+fn bench_queries(c: &mut Criterion) {
+    let mut group = c.benchmark_group("attack_map_queries");
+
+    for (name, tracked) in [("untracked", false), ("tracked", true)] {
+        let board = board(tracked);
+        group.bench_function(format!("is_square_attacked_by_{}", name), |b| {
+            b.iter(|| {
+                for idx in 0..81 {
+                    let pos = Position::from_index(idx);
+                    black_box(board.is_square_attacked_by(pos, Player::Black));
+                    black_box(board.is_square_attacked_by(pos, Player::White));
+                }
+            });
+        });
+        group.bench_function(format!("influence_map_{}", name), |b| {
+            b.iter(|| black_box(InfluenceMap::compute(&board)));
+        });
+    }
+
+    let board = board(false);
+    group.bench_function("compute_from_scratch", |b| {
+        b.iter(|| black_box(AttackMaps::compute(&board)));
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_make_unmake, bench_queries);
+criterion_main!(benches);
@@ -8,6 +8,7 @@ use shogi_engine::bitboards::BitboardBoard;
 use shogi_engine::search::move_ordering::{MoveOrdering, OrderingWeights};
 use shogi_engine::search::{ThreadSafeTranspositionTable, ThreadSafetyMode, TranspositionConfig};
 use shogi_engine::types::*;
+use std::sync::Arc;
 use std::time::Duration;
 
 /// Generate test moves for PV move ordering benchmarks
@@ -88,8 +89,10 @@ fn benchmark_pv_move_retrieval_performance(c: &mut Criterion) {
 
     // Create transposition table and move orderer
     let config = TranspositionConfig::default();
-    let mut tt =
-        ThreadSafeTranspositionTable::with_thread_mode(config, ThreadSafetyMode::SingleThreaded);
+    let tt = Arc::new(ThreadSafeTranspositionTable::with_thread_mode(
+        config,
+        ThreadSafetyMode::SingleThreaded,
+    ));
     let mut orderer = MoveOrdering::new();
     orderer.set_transposition_table(&tt);
 
@@ -153,8 +156,10 @@ fn benchmark_pv_move_storage_performance(c: &mut Criterion) {
 
     // Create transposition table and move orderer
     let config = TranspositionConfig::default();
-    let mut tt =
-        ThreadSafeTranspositionTable::with_thread_mode(config, ThreadSafetyMode::SingleThreaded);
+    let tt = Arc::new(ThreadSafeTranspositionTable::with_thread_mode(
+        config,
+        ThreadSafetyMode::SingleThreaded,
+    ));
     let mut orderer = MoveOrdering::new();
     orderer.set_transposition_table(&tt);
 
@@ -192,8 +197,10 @@ fn benchmark_pv_move_cache_performance(c: &mut Criterion) {
 
     // Create transposition table and move orderer
     let config = TranspositionConfig::default();
-    let mut tt =
-        ThreadSafeTranspositionTable::with_thread_mode(config, ThreadSafetyMode::SingleThreaded);
+    let tt = Arc::new(ThreadSafeTranspositionTable::with_thread_mode(
+        config,
+        ThreadSafetyMode::SingleThreaded,
+    ));
     let mut orderer = MoveOrdering::new();
     orderer.set_transposition_table(&tt);
 
@@ -246,8 +253,10 @@ fn benchmark_pv_move_ordering_performance(c: &mut Criterion) {
 
     // Create transposition table and move orderer
     let config = TranspositionConfig::default();
-    let mut tt =
-        ThreadSafeTranspositionTable::with_thread_mode(config, ThreadSafetyMode::SingleThreaded);
+    let tt = Arc::new(ThreadSafeTranspositionTable::with_thread_mode(
+        config,
+        ThreadSafetyMode::SingleThreaded,
+    ));
     let mut orderer = MoveOrdering::new();
     orderer.set_transposition_table(&tt);
 
@@ -305,8 +314,10 @@ fn benchmark_pv_move_hit_rate_performance(c: &mut Criterion) {
 
     // Create transposition table and move orderer
     let config = TranspositionConfig::default();
-    let mut tt =
-        ThreadSafeTranspositionTable::with_thread_mode(config, ThreadSafetyMode::SingleThreaded);
+    let tt = Arc::new(ThreadSafeTranspositionTable::with_thread_mode(
+        config,
+        ThreadSafetyMode::SingleThreaded,
+    ));
     let mut orderer = MoveOrdering::new();
     orderer.set_transposition_table(&tt);
 
@@ -351,8 +362,10 @@ fn benchmark_pv_move_memory_efficiency(c: &mut Criterion) {
 
     // Create transposition table and move orderer
     let config = TranspositionConfig::default();
-    let mut tt =
-        ThreadSafeTranspositionTable::with_thread_mode(config, ThreadSafetyMode::SingleThreaded);
+    let tt = Arc::new(ThreadSafeTranspositionTable::with_thread_mode(
+        config,
+        ThreadSafetyMode::SingleThreaded,
+    ));
     let mut orderer = MoveOrdering::new();
     orderer.set_transposition_table(&tt);
 
@@ -399,8 +412,10 @@ fn benchmark_pv_move_configuration_performance(c: &mut Criterion) {
 
     // Create transposition table
     let config = TranspositionConfig::default();
-    let mut tt =
-        ThreadSafeTranspositionTable::with_thread_mode(config, ThreadSafetyMode::SingleThreaded);
+    let tt = Arc::new(ThreadSafeTranspositionTable::with_thread_mode(
+        config,
+        ThreadSafetyMode::SingleThreaded,
+    ));
 
     // Create test position
     let board = BitboardBoard::new();
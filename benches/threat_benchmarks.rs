@@ -0,0 +1,68 @@
+//! Benchmarks for threat scoring
+//!
+//! `evaluate_threats` runs on every evaluation with the tactical component on.
+//! It reads the board's attack maps, which search boards track; the untracked
+//! case is the cost before that, when the maps were rebuilt on every call. The
+//! evaluator benchmarks show what the term costs a whole evaluation.
+//!
+//! cargo bench --bench threat_benchmarks --features legacy-tests
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use shogi_engine::bitboards::BitboardBoard;
+use shogi_engine::evaluation::integration::{IntegratedEvaluationConfig, IntegratedEvaluator};
+use shogi_engine::evaluation::threats::{evaluate_threats, ThreatConfig};
+use shogi_engine::types::{CapturedPieces, Player};
+
+const MIDDLEGAME_FEN: &str =
+    "ln1g1g1nl/1r1sk2b1/p1ppppspp/1p4p2/9/2P4P1/PP1PPPP1P/1BS4R1/LN1GKGSNL b - 1";
+
+fn board(tracked: bool) -> BitboardBoard {
+    let (mut board, _, _) = BitboardBoard::from_fen(MIDDLEGAME_FEN).unwrap();
+    if tracked {
+        board.enable_attack_maps();
+    }
+    board
+}
+
+/// Benchmark the threat term alone, with maps rebuilt per call and tracked
+fn bench_evaluate_threats(c: &mut Criterion) {
+    let mut group = c.benchmark_group("evaluate_threats");
+    let config = ThreatConfig::default();
+
+    for (name, tracked) in [("untracked", false), ("tracked", true)] {
+        let board = board(tracked);
+        group.bench_function(name, |b| {
+            b.iter(|| black_box(evaluate_threats(black_box(&board), Player::Black, &config)));
+        });
+    }
+
+    group.finish();
+}
+
+/// Benchmark a full evaluation of a search board with and without the term
+fn bench_evaluation_overhead(c: &mut Criterion) {
+    let mut group = c.benchmark_group("threats_evaluation_overhead");
+    let board = board(true);
+    let captured_pieces = CapturedPieces::new();
+
+    for (name, enabled) in [("without_threats", false), ("with_threats", true)] {
+        let mut config = IntegratedEvaluationConfig::default();
+        config.enable_eval_cache = false;
+        config.threats.enabled = enabled;
+        let mut evaluator = IntegratedEvaluator::with_config(config);
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                black_box(evaluator.evaluate(
+                    black_box(&board),
+                    Player::Black,
+                    black_box(&captured_pieces),
+                ))
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_evaluate_threats, bench_evaluation_overhead);
+criterion_main!(benches);
@@ -112,6 +112,7 @@ fn main() {
     // 8. Demonstrate move ordering integration
     println!("\n🎯 Demonstrating move ordering integration...");
     let mut move_orderer = TranspositionMoveOrderer::new();
+    let tt = std::sync::Arc::new(tt);
     move_orderer.set_transposition_table(&tt);
 
     // Create sample moves
@@ -358,7 +358,7 @@ fn integration_best_practices() {
     let config = TranspositionConfig::performance_optimized();
 
     println!("   2. Create transposition table");
-    let tt = ThreadSafeTranspositionTable::new(config);
+    let tt = std::sync::Arc::new(ThreadSafeTranspositionTable::new(config));
 
     println!("   3. Create hash calculator");
     let hash_calc = ShogiHashHandler::new(1000);
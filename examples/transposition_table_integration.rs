@@ -173,7 +173,7 @@ fn move_ordering_integration() {
 
     // Create move orderer with transposition table integration
     let mut orderer = TranspositionMoveOrderer::new();
-    let tt = ThreadSafeTranspositionTable::new(TranspositionConfig::default());
+    let tt = std::sync::Arc::new(ThreadSafeTranspositionTable::new(TranspositionConfig::default()));
     orderer.set_transposition_table(&tt);
 
     // Create a board position
@@ -322,6 +322,7 @@ fn demonstrate_move_ordering_issues() {
     );
     tt.store(tt_entry);
 
+    let tt = std::sync::Arc::new(tt);
     orderer.set_transposition_table(&tt);
 
     println!("  After proper setup:");
@@ -0,0 +1,20 @@
+#!/usr/bin/env bash
+set -euo pipefail
+
+# Build the WebAssembly bindings (src/wasm.rs) into pkg/.
+# The library is only built as a cdylib here, so native builds stay rlib-only.
+
+OUT_DIR="${1:-pkg}"
+TARGET=wasm32-unknown-unknown
+
+if ! command -v wasm-bindgen >/dev/null 2>&1; then
+  echo "[WASM] wasm-bindgen not found."
+  echo "Install with: cargo install wasm-bindgen-cli"
+  exit 1
+fi
+
+echo "[WASM] Building shogi-engine for $TARGET..."
+cargo rustc --lib --release --target "$TARGET" --features wasm --crate-type cdylib
+
+echo "[WASM] Generating JavaScript bindings in $OUT_DIR/..."
+wasm-bindgen --target web --out-dir "$OUT_DIR" "target/$TARGET/release/shogi_engine.wasm"
@@ -0,0 +1,210 @@
+/**
+ * Adjudication of engine-vs-engine games
+ * Ends a match early when its result is no longer in doubt: both engines agree
+ * one side is winning, the game has been dead level for a long time, or the
+ * endgame tablebase has solved the position.
+ */
+
+use crate::usi_info::UsiScore;
+use serde::{Deserialize, Serialize};
+use shogi_engine::tablebase::{TablebaseOutcome, TablebaseResult};
+use shogi_engine::types::Player;
+
+/// Centipawn value given to a mate score when comparing against thresholds
+const MATE_CP: i32 = 100_000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdjudicationConfig {
+    /// Adjudicate a win once both engines score the game at least this far
+    /// for the same side; `None` turns resign adjudication off
+    pub resign_score_cp: Option<i32>,
+    /// Consecutive moves each engine must agree for resign adjudication
+    pub resign_move_count: usize,
+    /// Adjudicate a draw once every score stays within this many centipawns
+    /// of zero; `None` turns draw adjudication off
+    pub draw_score_cp: Option<i32>,
+    /// Consecutive plies the scores must stay level for a draw
+    pub draw_move_count: usize,
+    /// No draw adjudication before this ply
+    pub draw_min_ply: usize,
+    /// End the game as soon as the tablebase proves the result. Off by
+    /// default: it stops games the engines might still misplay.
+    pub tablebase: bool,
+}
+
+impl Default for AdjudicationConfig {
+    fn default() -> Self {
+        Self {
+            resign_score_cp: None,
+            resign_move_count: 3,
+            draw_score_cp: None,
+            draw_move_count: 20,
+            draw_min_ply: 80,
+            tablebase: false,
+        }
+    }
+}
+
+/// An adjudicated result
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Adjudication {
+    Win { winner: Player, reason: String },
+    Draw { reason: String },
+}
+
+/// Follows the engines' scores through a game and decides when to stop it
+pub struct Adjudicator {
+    config: AdjudicationConfig,
+    /// Score after each ply, positive for black; `None` when the engine gave none
+    scores: Vec<Option<i32>>,
+}
+
+impl Adjudicator {
+    pub fn new(config: AdjudicationConfig) -> Self {
+        Self { config, scores: Vec::new() }
+    }
+
+    pub fn config(&self) -> &AdjudicationConfig {
+        &self.config
+    }
+
+    /// Record the score the engine playing `mover` reported for its move, from
+    /// its own side as USI specifies, and adjudicate if the scores now allow it
+    pub fn record(&mut self, mover: Player, score: Option<&UsiScore>) -> Option<Adjudication> {
+        let score = score.and_then(|score| match *score {
+            UsiScore::Cp(cp) => Some(cp),
+            UsiScore::Mate(Some(plies)) => Some(if plies >= 0 { MATE_CP } else { -MATE_CP }),
+            UsiScore::Mate(None) => None,
+        });
+        let black_score = match mover {
+            Player::Black => score,
+            Player::White => score.map(|score| -score),
+        };
+        self.scores.push(black_score);
+        self.resign().or_else(|| self.draw())
+    }
+
+    fn resign(&self) -> Option<Adjudication> {
+        let threshold = self.config.resign_score_cp?;
+        let recent = self.recent(self.config.resign_move_count.max(1) * 2)?;
+        let winner = if recent.iter().all(|&score| score >= threshold) {
+            Player::Black
+        } else if recent.iter().all(|&score| score <= -threshold) {
+            Player::White
+        } else {
+            return None;
+        };
+        Some(Adjudication::Win {
+            winner,
+            reason: format!(
+                "Adjudicated: both engines scored {} or more for {} over {} moves",
+                threshold,
+                if winner == Player::Black { "black" } else { "white" },
+                self.config.resign_move_count.max(1)
+            ),
+        })
+    }
+
+    fn draw(&self) -> Option<Adjudication> {
+        let threshold = self.config.draw_score_cp?;
+        if self.scores.len() < self.config.draw_min_ply {
+            return None;
+        }
+        let recent = self.recent(self.config.draw_move_count.max(1))?;
+        recent.iter().all(|score| score.abs() <= threshold).then(|| Adjudication::Draw {
+            reason: format!(
+                "Adjudicated draw: scores within {} for {} plies",
+                threshold,
+                recent.len()
+            ),
+        })
+    }
+
+    /// The last `plies` scores, if there are that many and all were reported
+    fn recent(&self, plies: usize) -> Option<Vec<i32>> {
+        if self.scores.len() < plies {
+            return None;
+        }
+        self.scores[self.scores.len() - plies..].iter().copied().collect()
+    }
+
+    /// Adjudicate from a tablebase probe of the position with `side_to_move`
+    /// to play; only exact results count, not a solver's confident guess
+    pub fn tablebase(
+        &self,
+        side_to_move: Player,
+        result: &TablebaseResult,
+    ) -> Option<Adjudication> {
+        if !self.config.tablebase || !result.exact {
+            return None;
+        }
+        let winner = match result.outcome {
+            TablebaseOutcome::Win => side_to_move,
+            TablebaseOutcome::Loss => side_to_move.opposite(),
+            TablebaseOutcome::Draw => {
+                return Some(Adjudication::Draw {
+                    reason: "Adjudicated draw: tablebase".to_string(),
+                })
+            }
+            TablebaseOutcome::Unknown => return None,
+        };
+        Some(Adjudication::Win {
+            winner,
+            reason: match result.moves_to_mate {
+                Some(moves) => format!("Adjudicated: tablebase mate in {}", moves),
+                None => "Adjudicated: tablebase win".to_string(),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resign_needs_both_engines_to_agree() {
+        let mut adjudicator = Adjudicator::new(AdjudicationConfig {
+            resign_score_cp: Some(1000),
+            resign_move_count: 2,
+            ..AdjudicationConfig::default()
+        });
+        // Gote's engine still thinks it is fine on its first move
+        assert_eq!(adjudicator.record(Player::Black, Some(&UsiScore::Cp(1200))), None);
+        assert_eq!(adjudicator.record(Player::White, Some(&UsiScore::Cp(100))), None);
+        assert_eq!(adjudicator.record(Player::Black, Some(&UsiScore::Cp(1500))), None);
+        assert_eq!(adjudicator.record(Player::White, Some(&UsiScore::Cp(-1100))), None);
+        assert_eq!(adjudicator.record(Player::Black, Some(&UsiScore::Mate(Some(9)))), None);
+        match adjudicator.record(Player::White, Some(&UsiScore::Cp(-2000))) {
+            Some(Adjudication::Win { winner, .. }) => assert_eq!(winner, Player::Black),
+            other => panic!("expected a win for black, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_draw_after_a_level_stretch() {
+        let mut adjudicator = Adjudicator::new(AdjudicationConfig {
+            draw_score_cp: Some(10),
+            draw_move_count: 4,
+            draw_min_ply: 6,
+            tablebase: true,
+            ..AdjudicationConfig::default()
+        });
+        let mut result = None;
+        for ply in 0..6 {
+            let mover = if ply % 2 == 0 { Player::Black } else { Player::White };
+            assert_eq!(result, None, "adjudicated early at ply {}", ply);
+            result = adjudicator.record(mover, Some(&UsiScore::Cp(5 - ply)));
+        }
+        assert!(matches!(result, Some(Adjudication::Draw { .. })));
+
+        // A solver's heuristic verdict is not proof
+        assert_eq!(adjudicator.tablebase(Player::White, &TablebaseResult::loss(3)), None);
+        let win = TablebaseResult::loss(3).proven();
+        match adjudicator.tablebase(Player::White, &win) {
+            Some(Adjudication::Win { winner, .. }) => assert_eq!(winner, Player::Black),
+            other => panic!("expected a tablebase win, got {:?}", other),
+        }
+    }
+}
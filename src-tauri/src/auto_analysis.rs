@@ -0,0 +1,533 @@
+//! Spare-time game analysis
+//!
+//! While no engine is searching and no match or tournament is being played, a
+//! background built-in engine works through the positions of every game
+//! enrolled for auto-analysis: all positions at a shallow depth first, then all
+//! of them again deeper, so the review graph fills in quickly and sharpens the
+//! longer the app sits idle. Results go into the session's score trend as
+//! review points; a point is only ever replaced by a deeper one. A search
+//! interrupted because the app got busy is simply run again at the next idle
+//! moment.
+//!
+//! Each position is searched twice at the pass's depth: once over all moves,
+//! then with `searchmoves` over every move but the best, so the review knows
+//! how much better the best move was than the second best and can flag the
+//! positions where only one move held as critical moments.
+
+use crate::engine_manager::EngineManager;
+use crate::engine_vs_engine::MoveReport;
+use crate::state::BackgroundGames;
+use crate::usi_info::{UsiInfo, UsiScore};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use shogi_engine::error::ShogiError;
+use shogi_engine::game_status::GameTracker;
+use shogi_engine::handicap::Handicap;
+use shogi_engine::score_trend::{ScorePoint, ScoreSource, ScoreTrend};
+use shogi_engine::search::mate_score::{mate_in, mated_in};
+use shogi_engine::types::Player;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream, ReadHalf, WriteHalf};
+use tokio::sync::RwLock;
+use tokio::time::timeout;
+
+/// Depths of the successive passes over a game
+pub const DEFAULT_AUTO_ANALYSIS_DEPTHS: [u8; 3] = [6, 10, 14];
+
+/// How long every engine must have been quiet before background analysis starts
+const IDLE_DELAY: Duration = Duration::from_secs(2);
+
+/// How often the runner checks for idleness and for engines that started thinking
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Longest one search may run before it is stopped; what it found by then is kept
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long a stopped search has to answer before the engine is taken to have hung
+const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// One position of an enrolled game
+#[derive(Debug, Clone)]
+struct QueuedPosition {
+    /// `position ...` command reaching this position
+    command: String,
+    /// Move that led here; `None` for the starting position
+    move_usi: Option<String>,
+    side_to_move: Player,
+    /// Legal moves in USI notation, for the second-best search
+    legal_moves: Vec<String>,
+    /// Deepest pass finished for this position
+    analyzed_depth: Option<u8>,
+}
+
+#[derive(Debug, Clone)]
+struct QueuedGame {
+    /// Position by ply; 0 is the starting position
+    positions: Vec<QueuedPosition>,
+    depths: Vec<u8>,
+    first_mover: Player,
+}
+
+impl QueuedGame {
+    /// Depth of the next pass a position needs, with that pass's index
+    fn next_pass(&self, position: &QueuedPosition) -> Option<(usize, u8)> {
+        self.depths
+            .iter()
+            .copied()
+            .enumerate()
+            .find(|&(_, depth)| position.analyzed_depth.map_or(true, |done| depth > done))
+    }
+}
+
+/// A position to search, handed to the runner
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoAnalysisTask {
+    pub session_id: String,
+    pub ply: u32,
+    pub command: String,
+    pub move_usi: Option<String>,
+    pub side_to_move: Player,
+    pub legal_moves: Vec<String>,
+    pub depth: u8,
+    pub first_mover: Player,
+}
+
+/// How far the background analysis of a game has come
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AutoAnalysisProgress {
+    pub session_id: String,
+    pub positions: usize,
+    /// Depth of the pass in progress; `None` once every pass is done
+    pub current_depth: Option<u8>,
+    /// Positions finished in the pass in progress
+    pub analyzed: usize,
+    pub depths: Vec<u8>,
+}
+
+/// Games waiting for spare-time analysis, by session
+#[derive(Debug, Default)]
+pub struct AutoAnalysisQueue {
+    games: HashMap<String, QueuedGame>,
+}
+
+impl AutoAnalysisQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enroll a session's game, the USI `moves` played from `sfen` (the even
+    /// starting position when omitted), or update it after more moves or a
+    /// takeback. Positions the game still shares with the last update keep
+    /// their analysis. `depths` are sorted and deduplicated.
+    pub fn enqueue(
+        &mut self,
+        session_id: &str,
+        sfen: Option<&str>,
+        moves: &[String],
+        mut depths: Vec<u8>,
+    ) -> Result<AutoAnalysisProgress, ShogiError> {
+        depths.retain(|&depth| depth > 0);
+        depths.sort_unstable();
+        depths.dedup();
+        if depths.is_empty() {
+            return Err(ShogiError::parse("No analysis depths given"));
+        }
+
+        let sfen = sfen.unwrap_or_else(|| Handicap::Even.sfen());
+        let mut tracker = GameTracker::from_sfen(sfen)?;
+        let first_mover = tracker.side_to_move();
+        let mut positions = vec![QueuedPosition {
+            command: format!("position sfen {}", sfen),
+            move_usi: None,
+            side_to_move: first_mover,
+            legal_moves: tracker.legal_moves_usi(),
+            analyzed_depth: None,
+        }];
+        for (index, usi) in moves.iter().enumerate() {
+            tracker.play_usi(usi)?;
+            positions.push(QueuedPosition {
+                command: format!("position sfen {} moves {}", sfen, moves[..=index].join(" ")),
+                move_usi: Some(usi.clone()),
+                side_to_move: tracker.side_to_move(),
+                legal_moves: tracker.legal_moves_usi(),
+                analyzed_depth: None,
+            });
+        }
+
+        if let Some(previous) = self.games.get(session_id) {
+            for (position, old) in positions.iter_mut().zip(&previous.positions) {
+                if position.command != old.command {
+                    break;
+                }
+                position.analyzed_depth = old.analyzed_depth;
+            }
+        }
+        self.games.insert(
+            session_id.to_string(),
+            QueuedGame {
+                positions,
+                depths,
+                first_mover,
+            },
+        );
+        Ok(self.progress(session_id).expect("game was just enrolled"))
+    }
+
+    /// Stop analysing a session's game
+    pub fn remove(&mut self, session_id: &str) -> bool {
+        self.games.remove(session_id).is_some()
+    }
+
+    /// The next position to search: shallower passes come first across all
+    /// games, and within a pass positions are taken in game order
+    pub fn next_task(&self) -> Option<AutoAnalysisTask> {
+        self.games
+            .iter()
+            .flat_map(|(session_id, game)| {
+                game.positions.iter().enumerate().filter_map(move |(ply, position)| {
+                    let (pass, depth) = game.next_pass(position)?;
+                    Some(((pass, ply), session_id, game, position, depth))
+                })
+            })
+            .min_by_key(|(rank, ..)| *rank)
+            .map(|((_, ply), session_id, game, position, depth)| AutoAnalysisTask {
+                session_id: session_id.clone(),
+                ply: ply as u32,
+                command: position.command.clone(),
+                move_usi: position.move_usi.clone(),
+                side_to_move: position.side_to_move,
+                legal_moves: position.legal_moves.clone(),
+                depth,
+                first_mover: game.first_mover,
+            })
+    }
+
+    /// Mark a task's position as analysed, unless the game has changed under it
+    pub fn complete(&mut self, task: &AutoAnalysisTask) {
+        let Some(position) = self
+            .games
+            .get_mut(&task.session_id)
+            .and_then(|game| game.positions.get_mut(task.ply as usize))
+        else {
+            return;
+        };
+        if position.command == task.command {
+            position.analyzed_depth = position.analyzed_depth.max(Some(task.depth));
+        }
+    }
+
+    pub fn progress(&self, session_id: &str) -> Option<AutoAnalysisProgress> {
+        let game = self.games.get(session_id)?;
+        let current_depth = game
+            .positions
+            .iter()
+            .filter_map(|position| game.next_pass(position))
+            .min()
+            .map(|(_, depth)| depth);
+        let analyzed = current_depth.map_or(game.positions.len(), |depth| {
+            game.positions
+                .iter()
+                .filter(|position| position.analyzed_depth.map_or(false, |done| done >= depth))
+                .count()
+        });
+        Some(AutoAnalysisProgress {
+            session_id: session_id.to_string(),
+            positions: game.positions.len(),
+            current_depth,
+            analyzed,
+            depths: game.depths.clone(),
+        })
+    }
+}
+
+/// Score of a finished search in the engine's internal form, for the score trend
+fn search_score(score: &UsiScore) -> Option<i32> {
+    match *score {
+        UsiScore::Cp(cp) => Some(cp),
+        UsiScore::Mate(Some(plies)) if plies >= 0 => Some(mate_in(plies.min(255) as u8)),
+        UsiScore::Mate(Some(plies)) => Some(mated_in((-plies).min(255) as u8)),
+        UsiScore::Mate(None) => None,
+    }
+}
+
+/// The background engine: an in-process built-in engine behind a pipe
+struct AnalysisEngine {
+    input: WriteHalf<DuplexStream>,
+    output: BufReader<ReadHalf<DuplexStream>>,
+}
+
+impl AnalysisEngine {
+    async fn start() -> Result<Self> {
+        let (output, input) = tokio::io::split(crate::inprocess_engine::spawn_pipe("auto-analysis")?);
+        let mut engine = Self {
+            input,
+            output: BufReader::new(output),
+        };
+        engine.send("usi").await?;
+        engine.wait_for("usiok").await?;
+        // Book moves come without a score, and one thread leaves the machine usable
+        engine.send("setoption name USI_OwnBook value false").await?;
+        engine.send("setoption name USI_Threads value 1").await?;
+        engine.send("isready").await?;
+        engine.wait_for("readyok").await?;
+        Ok(engine)
+    }
+
+    async fn send(&mut self, command: &str) -> Result<()> {
+        self.input.write_all(command.as_bytes()).await?;
+        self.input.write_all(b"\n").await?;
+        self.input.flush().await?;
+        Ok(())
+    }
+
+    async fn read_line(&mut self) -> Result<Option<String>> {
+        let mut line = String::new();
+        match timeout(POLL_INTERVAL, self.output.read_line(&mut line)).await {
+            Ok(Ok(0)) => Err(anyhow!("Analysis engine closed its output")),
+            Ok(Ok(_)) => Ok(Some(line.trim().to_string())),
+            Ok(Err(e)) => Err(anyhow!("Failed to read from analysis engine: {}", e)),
+            Err(_) => Ok(None),
+        }
+    }
+
+    async fn wait_for(&mut self, reply: &str) -> Result<()> {
+        let started = Instant::now();
+        while started.elapsed() < Duration::from_secs(10) {
+            if self.read_line().await?.as_deref() == Some(reply) {
+                return Ok(());
+            }
+        }
+        Err(anyhow!("Analysis engine did not answer '{}'", reply))
+    }
+}
+
+/// Everything the auto-analysis runner needs from the app
+#[derive(Clone)]
+pub struct AutoAnalysisRunner {
+    pub app_handle: AppHandle,
+    pub queue: Arc<RwLock<AutoAnalysisQueue>>,
+    pub engine_manager: Arc<EngineManager>,
+    pub background_games: Arc<BackgroundGames>,
+    pub score_trends: Arc<RwLock<HashMap<String, ScoreTrend>>>,
+}
+
+impl AutoAnalysisRunner {
+    /// Analyse queued positions whenever the app's engines are idle; runs for
+    /// the lifetime of the app
+    pub async fn run(self) {
+        let mut engine: Option<AnalysisEngine> = None;
+        let mut quiet_since: Option<Instant> = None;
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            if self.busy().await {
+                quiet_since = None;
+                continue;
+            }
+            if quiet_since.get_or_insert_with(Instant::now).elapsed() < IDLE_DELAY {
+                continue;
+            }
+            let Some(task) = self.queue.read().await.next_task() else {
+                continue;
+            };
+
+            if engine.is_none() {
+                match AnalysisEngine::start().await {
+                    Ok(started) => engine = Some(started),
+                    Err(e) => {
+                        log::error!("Failed to start the auto-analysis engine: {}", e);
+                        quiet_since = None;
+                        continue;
+                    }
+                }
+            }
+            let Some(running) = engine.as_mut() else {
+                continue;
+            };
+            match self.analyse(running, &task).await {
+                Ok(Some((report, second))) => self.finish(&task, &report, second.as_ref()).await,
+                // Interrupted; the position is picked again at the next idle moment
+                Ok(None) => quiet_since = None,
+                Err(e) => {
+                    log::warn!("Auto-analysis engine failed, restarting it: {}", e);
+                    engine = None;
+                }
+            }
+        }
+    }
+
+    /// Whether an engine is thinking or a match or tournament is being played
+    async fn busy(&self) -> bool {
+        self.engine_manager.any_thinking().await || self.background_games.any_running().await
+    }
+
+    /// Search a task's position, then again without the best move for the
+    /// second best when there is one. `None` means a search was interrupted.
+    async fn analyse(
+        &self,
+        engine: &mut AnalysisEngine,
+        task: &AutoAnalysisTask,
+    ) -> Result<Option<(MoveReport, Option<MoveReport>)>> {
+        let go = format!("go depth {}", task.depth);
+        let Some(report) = self.search(engine, task, &go).await? else {
+            return Ok(None);
+        };
+        let others: Vec<&str> = task
+            .legal_moves
+            .iter()
+            .map(String::as_str)
+            .filter(|usi| *usi != report.best_move)
+            .collect();
+        if others.is_empty() || others.len() == task.legal_moves.len() {
+            return Ok(Some((report, None)));
+        }
+        let go = format!("go depth {} searchmoves {}", task.depth, others.join(" "));
+        Ok(self.search(engine, task, &go).await?.map(|second| (report, Some(second))))
+    }
+
+    /// Search a task's position with the `go` command given, stopping early if
+    /// the app gets busy or the search passes `SEARCH_TIMEOUT`. `None` means the
+    /// search was interrupted.
+    async fn search(
+        &self,
+        engine: &mut AnalysisEngine,
+        task: &AutoAnalysisTask,
+        go: &str,
+    ) -> Result<Option<MoveReport>> {
+        engine.send(&task.command).await?;
+        engine.send(go).await?;
+
+        let started = Instant::now();
+        let mut report = MoveReport::default();
+        let mut interrupted = false;
+        let mut stopped_at: Option<Instant> = None;
+        loop {
+            match stopped_at {
+                None => {
+                    interrupted = self.busy().await;
+                    if interrupted || started.elapsed() > SEARCH_TIMEOUT {
+                        engine.send("stop").await?;
+                        stopped_at = Some(Instant::now());
+                    }
+                }
+                Some(at) if at.elapsed() > STOP_TIMEOUT => {
+                    return Err(anyhow!("Analysis engine did not answer 'stop'"));
+                }
+                Some(_) => {}
+            }
+            let Some(line) = engine.read_line().await? else {
+                continue;
+            };
+            if let Some(info) = UsiInfo::parse(&line) {
+                report.absorb(&info);
+            } else if let Some(best_move) = line.strip_prefix("bestmove ") {
+                if interrupted {
+                    return Ok(None);
+                }
+                report.best_move = best_move.split_whitespace().next().unwrap_or_default().to_string();
+                return Ok(Some(report));
+            }
+        }
+    }
+
+    /// Record a finished search, and the second-best search if one was run, in
+    /// the score trend and report progress
+    async fn finish(
+        &self,
+        task: &AutoAnalysisTask,
+        report: &MoveReport,
+        second: Option<&MoveReport>,
+    ) {
+        let progress = {
+            let mut queue = self.queue.write().await;
+            queue.complete(task);
+            queue.progress(&task.session_id)
+        };
+        // Terminal positions end with no score; they are done all the same
+        let Some(score) = report.score.as_ref().and_then(search_score) else {
+            return;
+        };
+
+        let series = {
+            let mut trends = self.score_trends.write().await;
+            let trend = trends
+                .entry(task.session_id.clone())
+                .or_insert_with(|| ScoreTrend::with_first_mover(task.first_mover));
+            let deeper_known = trend.get(task.ply).map_or(false, |point| {
+                point.source == ScoreSource::Review && point.depth.unwrap_or(0) > task.depth
+            });
+            if !deeper_known {
+                let point = ScorePoint::from_side_to_move(
+                    task.ply,
+                    task.move_usi.clone(),
+                    score,
+                    task.side_to_move,
+                    ScoreSource::Review,
+                    Some(task.depth),
+                );
+                let second_best =
+                    second.and_then(|second| second.score.as_ref()).and_then(search_score);
+                trend.record(match second_best {
+                    Some(second_best) => point.with_second_best(second_best, task.side_to_move),
+                    None => point,
+                });
+            }
+            trend.series()
+        };
+
+        let event_name = format!("auto-analysis::{}", task.session_id);
+        if let Err(e) = self.app_handle.emit(
+            &event_name,
+            serde_json::json!({ "progress": progress, "series": series }),
+        ) {
+            log::error!("Failed to emit auto-analysis event: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn moves(list: &[&str]) -> Vec<String> {
+        list.iter().map(|mv| mv.to_string()).collect()
+    }
+
+    #[test]
+    fn test_passes_deepen_and_survive_new_moves() {
+        let mut queue = AutoAnalysisQueue::new();
+        queue
+            .enqueue("game", None, &moves(&["7g7f", "3c3d"]), vec![10, 6])
+            .unwrap();
+
+        // The whole game at depth 6 before anything at depth 10
+        for ply in 0..3 {
+            let task = queue.next_task().unwrap();
+            assert_eq!((task.ply, task.depth), (ply, 6));
+            queue.complete(&task);
+        }
+        let task = queue.next_task().unwrap();
+        assert_eq!((task.ply, task.depth), (0, 10));
+        assert_eq!(task.side_to_move, Player::Black);
+        assert_eq!(task.legal_moves.len(), 30);
+
+        // A new move only needs its own shallow pass; a takeback drops analysis
+        queue
+            .enqueue("game", None, &moves(&["7g7f", "3c3d", "2g2f"]), vec![6, 10])
+            .unwrap();
+        let task = queue.next_task().unwrap();
+        assert_eq!((task.ply, task.depth), (3, 6));
+        assert_eq!(task.side_to_move, Player::White);
+        let progress = queue
+            .enqueue("game", None, &moves(&["7g7f", "8c8d"]), vec![6, 10])
+            .unwrap();
+        assert_eq!((progress.current_depth, progress.analyzed), (Some(6), 2));
+
+        // A task for a position the takeback removed is dropped
+        queue.complete(&task);
+        assert_eq!(queue.progress("game").unwrap().positions, 3);
+        assert!(queue.enqueue("game", None, &moves(&["7g7f", "7g7f"]), vec![6]).is_err());
+    }
+}
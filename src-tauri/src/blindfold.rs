@@ -0,0 +1,169 @@
+//! Blindfold training
+//!
+//! In a blindfold session the backend decides what the frontend may see of
+//! the position, so hiding pieces does not depend on the frontend keeping a
+//! secret it was sent. Moves are still checked against the full position.
+//! Depending on the mode the frontend gets the squares with only their owners,
+//! the board without one side's pieces, or nothing but the side to move and
+//! the move count.
+
+use serde::{Deserialize, Serialize};
+use shogi_engine::move_metadata::MoveMetadata;
+use shogi_engine::position_format::{PositionText, HAND_ORDER};
+use shogi_engine::types::{Piece, PieceType, Player, Position};
+
+/// What a blindfold session hides
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", content = "player", rename_all = "snake_case")]
+pub enum BlindfoldMode {
+    /// Everything is shown
+    #[default]
+    Off,
+    /// Occupied squares and their owners, but not which pieces stand there or
+    /// what the hands hold
+    HideIdentity,
+    /// One side's pieces, on the board and in hand
+    HideSide(Player),
+    /// The whole board and both hands
+    HideAll,
+}
+
+/// A square's piece as far as the mode allows
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VisiblePiece {
+    pub owner: Player,
+    /// SFEN letter, `None` when the identity is hidden
+    pub piece: Option<String>,
+}
+
+/// The position as the frontend of a blindfold session sees it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VisiblePosition {
+    pub mode: BlindfoldMode,
+    /// Nine rows, rank a first and file 9 first within a rank, as in SFEN
+    pub squares: Vec<Vec<Option<VisiblePiece>>>,
+    /// SFEN hand letters and counts; `None` when hidden
+    pub black_hand: Option<Vec<(String, u8)>>,
+    pub white_hand: Option<Vec<(String, u8)>>,
+    pub side_to_move: Player,
+    pub move_number: u32,
+    /// Full SFEN, only when nothing is hidden
+    pub sfen: Option<String>,
+}
+
+/// What the frontend learns of a played move
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VisibleMove {
+    pub usi: String,
+    pub player: Player,
+    pub gives_check: bool,
+    pub is_mate: bool,
+    /// Whether something was taken; the piece itself only when shown
+    pub is_capture: bool,
+    pub captured: Option<PieceType>,
+}
+
+impl BlindfoldMode {
+    /// Whether the frontend may not know which pieces `owner` has
+    pub fn hides_identity(self, owner: Player) -> bool {
+        match self {
+            BlindfoldMode::Off => false,
+            BlindfoldMode::HideIdentity | BlindfoldMode::HideAll => true,
+            BlindfoldMode::HideSide(hidden) => hidden == owner,
+        }
+    }
+
+    fn hides_presence(self, owner: Player) -> bool {
+        match self {
+            BlindfoldMode::HideAll => true,
+            BlindfoldMode::HideSide(hidden) => hidden == owner,
+            BlindfoldMode::Off | BlindfoldMode::HideIdentity => false,
+        }
+    }
+
+    /// `position` with what this mode hides taken out
+    pub fn filter(self, position: &PositionText) -> VisiblePosition {
+        let squares = (0..9)
+            .map(|row| {
+                (0..9)
+                    .map(|col| {
+                        let piece = position.board.get_piece(Position::new(row, col))?;
+                        if self.hides_presence(piece.player) {
+                            return None;
+                        }
+                        Some(VisiblePiece {
+                            owner: piece.player,
+                            piece: (!self.hides_identity(piece.player))
+                                .then(|| piece.to_fen_char()),
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+        let hand = |player: Player| {
+            if self.hides_identity(player) {
+                return None;
+            }
+            Some(
+                HAND_ORDER
+                    .into_iter()
+                    .filter_map(|piece| {
+                        let count = position.captured_pieces.count(piece, player) as u8;
+                        (count > 0).then(|| (Piece::new(piece, player).to_fen_char(), count))
+                    })
+                    .collect(),
+            )
+        };
+        VisiblePosition {
+            mode: self,
+            squares,
+            black_hand: hand(Player::Black),
+            white_hand: hand(Player::White),
+            side_to_move: position.side_to_move,
+            move_number: position.move_number,
+            sfen: (self == BlindfoldMode::Off).then(|| position.to_sfen()),
+        }
+    }
+
+    /// A played move's metadata without the captured piece when hidden
+    pub fn filter_move(self, metadata: &MoveMetadata) -> VisibleMove {
+        let captured_owner = metadata.player.opposite();
+        VisibleMove {
+            usi: metadata.usi.clone(),
+            player: metadata.player,
+            gives_check: metadata.gives_check,
+            is_mate: metadata.is_mate,
+            is_capture: metadata.captured.is_some(),
+            captured: metadata
+                .captured
+                .filter(|_| !self.hides_identity(captured_owner)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_hides_by_mode() {
+        let position = PositionText::from_sfen("4k4/9/4P4/9/9/9/9/9/4K4 b G 1").unwrap();
+
+        let shown = BlindfoldMode::Off.filter(&position);
+        assert_eq!(shown.squares[2][4].as_ref().unwrap().piece.as_deref(), Some("P"));
+        assert!(shown.sfen.is_some());
+
+        let identity = BlindfoldMode::HideIdentity.filter(&position);
+        let pawn = identity.squares[2][4].as_ref().unwrap();
+        assert_eq!((pawn.owner, pawn.piece.as_deref()), (Player::Black, None));
+        assert!(identity.black_hand.is_none());
+        assert!(identity.sfen.is_none());
+
+        let gote_hidden = BlindfoldMode::HideSide(Player::White).filter(&position);
+        assert!(gote_hidden.squares[0][4].is_none());
+        assert_eq!(gote_hidden.black_hand, Some(vec![("G".to_string(), 1)]));
+
+        let all = BlindfoldMode::HideAll.filter(&position);
+        assert!(all.squares.iter().flatten().all(Option::is_none));
+    }
+}
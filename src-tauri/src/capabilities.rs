@@ -0,0 +1,153 @@
+//! Backend capabilities
+//!
+//! A versioned manifest of what this backend offers, so the frontend can
+//! feature-detect against an older backend instead of invoking commands it
+//! lacks. `protocol_version` goes up whenever a command or event changes shape
+//! incompatibly; additions only show up in the lists. A frontend built for
+//! protocol N works with any backend where `min_protocol_version <= N`.
+
+use crate::auto_analysis::DEFAULT_AUTO_ANALYSIS_DEPTHS;
+use crate::engine_log::DEFAULT_LOG_CAPACITY;
+use crate::engine_storage::EngineStorage;
+use crate::engine_validator::EngineCapabilities;
+use serde::Serialize;
+use shogi_engine::candidates::MAX_CANDIDATES;
+use shogi_engine::hint::MAX_HINT_LEVEL;
+use shogi_engine::i18n::Locale;
+
+/// Version of the command and event interface
+pub const IPC_PROTOCOL_VERSION: u32 = 1;
+/// Oldest frontend protocol this backend still answers correctly
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+/// Events the backend emits; `::<id>` marks ones scoped to an engine, session
+/// or tournament id
+pub const EVENTS: &[&str] = &[
+    "usi-message::<engine>",
+    "usi-info::<engine>",
+    "usi-error::<engine>",
+    "session-usi-message::<session>",
+    "session-usi-info::<session>",
+    "engine-health::<engine>",
+    "engine-health-warning::<engine>",
+    "engine-vs-engine-move::<session>",
+    "engine-vs-engine-thinking::<session>",
+    "engine-vs-engine-update::<session>",
+    "engine-vs-engine-result::<session>",
+    "tournament-update::<tournament>",
+    "auto-analysis::<session>",
+    "session-event::<session>",
+    "deep-link::open",
+    "deep-link::error",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendCapabilities {
+    pub protocol_version: u32,
+    pub min_protocol_version: u32,
+    pub backend_version: &'static str,
+    pub commands: Vec<&'static str>,
+    pub events: Vec<&'static str>,
+    pub features: BackendFeatures,
+    pub limits: BackendLimits,
+    /// Locales with a message catalog, for `set_locale`
+    pub locales: Vec<Locale>,
+    /// Registered engines with what each was probed to support
+    pub engines: Vec<EngineFeatures>,
+}
+
+/// Features of the app as a whole, beyond any one engine
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendFeatures {
+    /// Several game sessions side by side
+    pub sessions: bool,
+    /// Analysis sessions with `go infinite` and per-position caching
+    pub analysis: bool,
+    /// Ranked candidate moves from the built-in engine
+    pub multipv: bool,
+    /// `go mate` tsume search in the built-in engine
+    pub mate_search: bool,
+    pub auto_analysis: bool,
+    pub engine_vs_engine: bool,
+    pub tournaments: bool,
+    pub deep_links: bool,
+    pub position_matrix: bool,
+    pub kif_export: bool,
+    /// Player-facing text in the locale chosen with `set_locale`
+    pub localization: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendLimits {
+    pub max_candidate_moves: usize,
+    pub max_hint_level: u8,
+    pub engine_log_capacity: usize,
+    pub auto_analysis_depths: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineFeatures {
+    pub id: String,
+    pub name: String,
+    pub is_builtin: bool,
+    /// `None` when the engine was registered before capabilities were probed
+    pub capabilities: Option<EngineCapabilities>,
+}
+
+impl BackendCapabilities {
+    pub fn current(engine_storage: &EngineStorage) -> Self {
+        Self {
+            protocol_version: IPC_PROTOCOL_VERSION,
+            min_protocol_version: MIN_PROTOCOL_VERSION,
+            backend_version: env!("CARGO_PKG_VERSION"),
+            commands: crate::COMMAND_NAMES.to_vec(),
+            events: EVENTS.to_vec(),
+            features: BackendFeatures {
+                sessions: true,
+                analysis: true,
+                multipv: true,
+                mate_search: true,
+                auto_analysis: true,
+                engine_vs_engine: true,
+                tournaments: true,
+                deep_links: true,
+                position_matrix: true,
+                kif_export: true,
+                localization: true,
+            },
+            limits: BackendLimits {
+                max_candidate_moves: MAX_CANDIDATES,
+                max_hint_level: MAX_HINT_LEVEL,
+                engine_log_capacity: DEFAULT_LOG_CAPACITY,
+                auto_analysis_depths: DEFAULT_AUTO_ANALYSIS_DEPTHS.to_vec(),
+            },
+            locales: Locale::ALL.to_vec(),
+            engines: engine_storage
+                .engines
+                .iter()
+                .map(|engine| EngineFeatures {
+                    id: engine.id.clone(),
+                    name: engine.name.clone(),
+                    is_builtin: engine.is_builtin,
+                    capabilities: engine
+                        .metadata
+                        .as_ref()
+                        .and_then(|metadata| metadata.capabilities.clone()),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_lists_itself() {
+        let manifest = BackendCapabilities::current(&EngineStorage::default());
+        assert!(manifest.commands.contains(&"get_backend_capabilities"));
+        assert!(manifest.min_protocol_version <= manifest.protocol_version);
+        assert!(manifest.engines.is_empty());
+    }
+}
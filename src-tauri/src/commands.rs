@@ -1,10 +1,45 @@
+use crate::adjudication::AdjudicationConfig;
+use crate::auto_analysis::DEFAULT_AUTO_ANALYSIS_DEPTHS;
+use crate::blindfold::BlindfoldMode;
+use crate::capabilities::BackendCapabilities;
+use crate::correlation;
+use crate::deep_link;
+use crate::engine_limits::EngineResourceLimits;
 use crate::engine_manager::EngineStatus;
+use crate::engine_protocol::EngineProtocol;
+use crate::engine_profiles::EngineProfile;
 use crate::engine_storage::EngineConfig;
 use crate::engine_validator;
 use crate::engine_vs_engine::{EngineVsEngineConfig, EngineVsEngineManager};
+use crate::game_session::SessionKind;
+use crate::inprocess_engine;
+use crate::session_events::{self, RepertoireSource, SessionEvent};
+use crate::session_store::SessionSnapshot;
 use crate::state::AppState;
+use crate::takeback;
+use crate::tournament::{
+    Tournament, TournamentConfig, TournamentEntrant, TournamentFormat, TournamentRunner,
+    TournamentStatus,
+};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use shogi_engine::board_diff::BoardDiff;
+use shogi_engine::board_matrix::BoardMatrix;
+use shogi_engine::error::ShogiError;
+use shogi_engine::game_status::GameTracker;
+use shogi_engine::handicap::Handicap;
+use shogi_engine::i18n::{self, Locale};
+use shogi_engine::kif_parser::KifGame;
+use shogi_engine::move_times::{MoveTime, MoveTimes};
+use shogi_engine::notation::NotationStyle;
+use shogi_engine::opening_book::repertoire::DEFAULT_REPERTOIRE_PLIES;
+use shogi_engine::opening_book::{OpeningBook, Repertoire};
+use shogi_engine::position_format::PositionText;
+use shogi_engine::score_trend::{ScorePoint, ScoreSource, ScoreTrend};
+use shogi_engine::search::perspective::EvaluationPerspective;
+use shogi_engine::sharpness::{DEFAULT_SHARPNESS_CANDIDATES, DEFAULT_SHARPNESS_TIME_MS};
+use shogi_engine::types::Player;
+use std::sync::OnceLock;
 use tauri::State;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -20,6 +55,10 @@ pub struct CommandResponse {
     pub success: bool,
     pub message: Option<String>,
     pub data: Option<serde_json::Value>,
+    /// Category and message of an engine-crate error, for callers that decide
+    /// between fixing the input and retrying
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ShogiError>,
 }
 
 impl CommandResponse {
@@ -28,6 +67,7 @@ impl CommandResponse {
             success: true,
             message: None,
             data: None,
+            error: None,
         }
     }
 
@@ -36,6 +76,7 @@ impl CommandResponse {
             success: true,
             message: None,
             data: Some(data),
+            error: None,
         }
     }
 
@@ -44,6 +85,16 @@ impl CommandResponse {
             success: false,
             message: Some(message),
             data: None,
+            error: None,
+        }
+    }
+
+    pub fn engine_error(error: ShogiError) -> Self {
+        Self {
+            success: false,
+            message: Some(error.to_string()),
+            data: None,
+            error: Some(error),
         }
     }
 }
@@ -55,6 +106,7 @@ pub async fn spawn_engine(
     name: String,
     path: String,
     temp_options: Option<std::collections::HashMap<String, String>>,
+    session_id: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<CommandResponse, String> {
     log::info!("Command: spawn_engine - id: {}, name: {}, path: {}", engine_id, name, path);
@@ -62,10 +114,28 @@ pub async fn spawn_engine(
         log::info!("Using {} temporary options for this game", opts.len());
     }
 
+    let session_id = match state.sessions.resolve(session_id.as_deref()).await {
+        Ok(id) => id,
+        Err(e) => return Ok(CommandResponse::error(e.to_string())),
+    };
+
     let manager = &state.engine_manager;
-    
-    match manager.spawn_engine(engine_id.clone(), name, path).await {
+    let (protocol, limits) = state
+        .engine_storage
+        .read()
+        .await
+        .find_for_runtime_id(&engine_id)
+        .map(|config| (config.protocol, config.limits.clone()))
+        .unwrap_or_default();
+
+    let spawned =
+        manager.spawn_engine(engine_id.clone(), name, path, session_id.clone(), protocol, limits);
+    match spawned.await {
         Ok(_) => {
+            if let Err(e) = state.sessions.attach_engine(&session_id, &engine_id).await {
+                log::warn!("Failed to attach engine {} to session {}: {}", engine_id, session_id, e);
+            }
+
             // Initialize the engine with USI protocol and send options
             // Use temp_options if provided, otherwise use saved options from storage
             if let Err(e) = manager.initialize_engine_with_temp_options(
@@ -79,7 +149,7 @@ pub async fn spawn_engine(
             }
             
             Ok(CommandResponse::success_with_data(
-                serde_json::json!({ "engine_id": engine_id })
+                serde_json::json!({ "engine_id": engine_id, "session_id": session_id })
             ))
         }
         Err(e) => {
@@ -94,10 +164,20 @@ pub async fn spawn_engine(
 pub async fn send_usi_command(
     engine_id: String,
     command: String,
+    session_id: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<CommandResponse, String> {
     log::debug!("Command: send_usi_command - engine_id: {}, command: {}", engine_id, command);
 
+    if let Some(session_id) = session_id.as_deref() {
+        if !state.sessions.owns_engine(session_id, &engine_id).await {
+            return Ok(CommandResponse::error(format!(
+                "Engine {} does not belong to session {}",
+                engine_id, session_id
+            )));
+        }
+    }
+
     let manager = &state.engine_manager;
 
     match manager.send_command(&engine_id, &command).await {
@@ -113,14 +193,27 @@ pub async fn send_usi_command(
 #[tauri::command]
 pub async fn stop_engine(
     engine_id: String,
+    session_id: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<CommandResponse, String> {
     log::info!("Command: stop_engine - engine_id: {}", engine_id);
 
+    if let Some(session_id) = session_id.as_deref() {
+        if !state.sessions.owns_engine(session_id, &engine_id).await {
+            return Ok(CommandResponse::error(format!(
+                "Engine {} does not belong to session {}",
+                engine_id, session_id
+            )));
+        }
+    }
+
     let manager = &state.engine_manager;
 
     match manager.stop_engine(&engine_id).await {
-        Ok(_) => Ok(CommandResponse::success()),
+        Ok(_) => {
+            state.sessions.detach_engine(&engine_id).await;
+            Ok(CommandResponse::success())
+        }
         Err(e) => {
             log::error!("Failed to stop engine: {}", e);
             Ok(CommandResponse::error(format!("Failed to stop engine: {}", e)))
@@ -147,10 +240,14 @@ pub async fn get_engine_status(
 /// List all active engines
 #[tauri::command]
 pub async fn list_engines(
+    session_id: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<CommandResponse, String> {
     let manager = &state.engine_manager;
-    let engine_ids = manager.list_engines().await;
+    let engine_ids = match session_id {
+        Some(session_id) => manager.list_session_engines(&session_id).await,
+        None => manager.list_engines().await,
+    };
 
     Ok(CommandResponse::success_with_data(
         serde_json::json!({ "engines": engine_ids })
@@ -167,7 +264,14 @@ pub async fn stop_all_engines(
     let manager = &state.engine_manager;
 
     match manager.stop_all_engines().await {
-        Ok(_) => Ok(CommandResponse::success()),
+        Ok(_) => {
+            for session in state.sessions.list_sessions().await {
+                for engine_id in session.engine_ids {
+                    state.sessions.detach_engine(&engine_id).await;
+                }
+            }
+            Ok(CommandResponse::success())
+        }
         Err(e) => {
             log::error!("Failed to stop all engines: {}", e);
             Ok(CommandResponse::error(format!("Failed to stop all engines: {}", e)))
@@ -232,126 +336,61 @@ pub fn find_workspace_root() -> Option<std::path::PathBuf> {
     None
 }
 
-/// Get the path to the bundled built-in engine
-#[tauri::command]
-pub async fn get_builtin_engine_path(
-    app_handle: tauri::AppHandle,
-) -> Result<CommandResponse, String> {
+/// Environment variable that runs the built-in engine as the `usi-engine`
+/// executable instead of in-process, e.g. to debug the engine on its own
+pub const EXTERNAL_ENGINE_ENV: &str = "SHOGI_EXTERNAL_ENGINE";
+
+/// The `usi-engine` executable: the workspace build in development, next to
+/// the app or in its resources in a bundle
+pub fn find_builtin_engine_binary(app_handle: &tauri::AppHandle) -> Option<std::path::PathBuf> {
     use tauri::Manager;
 
-    if cfg!(debug_assertions) {
-        // Development mode - find the engine in the workspace
-        // Try debug binary first (for faster iteration), then release
-        let workspace_root = find_workspace_root()
-            .ok_or_else(|| "Could not find workspace root".to_string())?;
-        
-        // Try debug binary first (if it exists and is recent)
-        let debug_engine_path = workspace_root.join("target/debug/usi-engine");
-        let release_engine_path = workspace_root.join("target/release/usi-engine");
-        
-        // Prefer debug binary if it exists (for development), otherwise use release
-        let engine_path = if debug_engine_path.exists() {
-            debug_engine_path
-        } else if release_engine_path.exists() {
-            release_engine_path
-        } else {
-            log::warn!("Engine not found at debug or release path. Attempting to use release...");
-            release_engine_path
-        };
-        
-        let engine_path_str = engine_path.display().to_string();
-        
-        if !engine_path.exists() {
-            log::warn!("Engine not found at: {}. Attempting to build it...", engine_path_str);
-            return Ok(CommandResponse::error(format!(
-                "Engine not found at: {}. Please run 'cargo build --bin usi-engine --release' (or --debug) first.",
-                engine_path_str
-            )));
+    let binary_name = if cfg!(target_os = "windows") { "usi-engine.exe" } else { "usi-engine" };
+    let mut candidates = Vec::new();
+    if let Some(workspace_root) = find_workspace_root() {
+        if cfg!(debug_assertions) {
+            candidates.push(workspace_root.join("target/debug").join(binary_name));
         }
-        
-        log::info!("Built-in engine path: {}", engine_path_str);
-        return Ok(CommandResponse::success_with_data(
-            serde_json::json!({ "path": engine_path_str })
-        ));
+        candidates.push(workspace_root.join("target/release").join(binary_name));
     }
-    
-    // Production mode - try to find the engine relative to the executable
-    // In a bundled Tauri app, the executable is in the app bundle
-    // The engine should be in the same directory or a resources directory
-    
-    // First, try to find it relative to the executable
-    if let Ok(exe_path) = std::env::current_exe() {
-        if let Some(exe_dir) = exe_path.parent() {
-            // Try common locations in a Tauri bundle
-            let mut possible_paths = vec![
-                exe_dir.join("usi-engine"),
-                exe_dir.join("resources").join("usi-engine"),
-            ];
-            
-            // Add macOS Resources path if it exists
-            if let Some(resources_path) = exe_dir.parent()
-                .and_then(|p| p.parent())
-                .map(|p| p.join("Resources").join("usi-engine")) {
-                possible_paths.push(resources_path);
-            }
-            
-            // On Windows, also try with .exe extension
-            #[cfg(target_os = "windows")]
-            {
-                let mut windows_paths = Vec::new();
-                for p in &possible_paths {
-                    if let Some(parent) = p.parent() {
-                        if let Some(stem) = p.file_stem().and_then(|s| s.to_str()) {
-                            windows_paths.push(parent.join(format!("{}.exe", stem)));
-                        }
-                    }
-                }
-                // Check Windows paths first
-                if let Some(path) = windows_paths.iter().find(|p| p.exists()) {
-                    log::info!("Built-in engine path: {}", path.display());
-                    return Ok(CommandResponse::success_with_data(
-                        serde_json::json!({ "path": path.display().to_string() })
-                    ));
-                }
-            }
-            
-            if let Some(path) = possible_paths.iter().find(|p| p.exists()) {
-                log::info!("Built-in engine path: {}", path.display());
-                return Ok(CommandResponse::success_with_data(
-                    serde_json::json!({ "path": path.display().to_string() })
-                ));
-            }
+    let exe_dir = std::env::current_exe().ok().and_then(|exe| exe.parent().map(|p| p.to_path_buf()));
+    if let Some(exe_dir) = exe_dir {
+        candidates.push(exe_dir.join(binary_name));
+        candidates.push(exe_dir.join("resources").join(binary_name));
+        // macOS bundles keep resources in Contents/Resources
+        if let Some(contents) = exe_dir.parent() {
+            candidates.push(contents.join("Resources").join(binary_name));
         }
     }
-    
-    // Fallback: try resource directory
     if let Ok(resource_dir) = app_handle.path().resource_dir() {
-        let engine_path = resource_dir.join("usi-engine");
-        #[cfg(target_os = "windows")]
-        let engine_path = resource_dir.join("usi-engine.exe");
-        
-        if engine_path.exists() {
-            log::info!("Built-in engine path: {}", engine_path.display());
-            return Ok(CommandResponse::success_with_data(
-                serde_json::json!({ "path": engine_path.display().to_string() })
-            ));
-        }
+        candidates.push(resource_dir.join(binary_name));
     }
-    
-    // Last resort: try workspace root (for development builds that are "release")
-    if let Some(workspace_root) = find_workspace_root() {
-        let engine_path = workspace_root.join("target/release/usi-engine");
-        if engine_path.exists() {
-            log::info!("Built-in engine path: {}", engine_path.display());
-            return Ok(CommandResponse::success_with_data(
-                serde_json::json!({ "path": engine_path.display().to_string() })
-            ));
+    candidates.into_iter().find(|path| path.exists())
+}
+
+/// Path the built-in engine runs from: the in-process sentinel, or the
+/// `usi-engine` executable when `EXTERNAL_ENGINE_ENV` is set and one is found
+pub fn builtin_engine_path(app_handle: &tauri::AppHandle) -> String {
+    if std::env::var_os(EXTERNAL_ENGINE_ENV).is_some() {
+        match find_builtin_engine_binary(app_handle) {
+            Some(path) => return path.display().to_string(),
+            None => log::warn!(
+                "{} is set but no usi-engine executable was found; running in-process",
+                EXTERNAL_ENGINE_ENV
+            ),
         }
     }
-    
-    // Could not find engine
-    Ok(CommandResponse::error(
-        "Engine binary not found in production bundle".to_string()
+    inprocess_engine::IN_PROCESS_ENGINE_PATH.to_string()
+}
+
+/// Get the path of the built-in engine. It normally runs inside the app, so
+/// this is the in-process sentinel rather than an executable on disk.
+#[tauri::command]
+pub async fn get_builtin_engine_path(
+    app_handle: tauri::AppHandle,
+) -> Result<CommandResponse, String> {
+    Ok(CommandResponse::success_with_data(
+        serde_json::json!({ "path": builtin_engine_path(&app_handle) })
     ))
 }
 
@@ -360,12 +399,14 @@ pub async fn get_builtin_engine_path(
 pub async fn add_engine(
     name: String,
     path: String,
+    protocol: Option<EngineProtocol>,
     state: State<'_, AppState>,
 ) -> Result<CommandResponse, String> {
     log::info!("Command: add_engine - name: {}, path: {}", name, path);
+    let protocol = protocol.unwrap_or_default();
 
     // Validate the engine
-    let metadata = match engine_validator::validate_engine(&path).await {
+    let metadata = match engine_validator::validate_engine_with_protocol(&path, protocol).await {
         Ok(meta) => {
             log::info!("Engine validation successful: {}", meta.name);
             Some(meta)
@@ -377,7 +418,8 @@ pub async fn add_engine(
     };
 
     // Create engine config
-    let config = EngineConfig::new(name, path, metadata, false);
+    let mut config = EngineConfig::new(name, path, metadata, false);
+    config.protocol = protocol;
     let engine_id = config.id.clone();
 
     // Add to storage
@@ -454,10 +496,12 @@ pub async fn get_engines(
 #[tauri::command]
 pub async fn validate_engine_path(
     path: String,
+    protocol: Option<EngineProtocol>,
 ) -> Result<CommandResponse, String> {
     log::info!("Command: validate_engine_path - path: {}", path);
 
-    match engine_validator::validate_engine(&path).await {
+    let protocol = protocol.unwrap_or_default();
+    match engine_validator::validate_engine_with_protocol(&path, protocol).await {
         Ok(metadata) => {
             log::info!("Engine validation successful: {}", metadata.name);
             Ok(CommandResponse::success_with_data(
@@ -489,7 +533,9 @@ pub async fn revalidate_engine_metadata(
         let engine_path = engine.path.clone();
         
         // Re-validate the engine to get latest options
-        let metadata = match engine_validator::validate_engine(&engine_path).await {
+        let validation =
+            engine_validator::validate_engine_with_protocol(&engine_path, engine.protocol).await;
+        let metadata = match validation {
             Ok(meta) => {
                 log::info!("Re-validated engine metadata for {}, found {} options", engine_id, meta.options.len());
                 Some(meta)
@@ -542,7 +588,8 @@ pub async fn register_builtin_engine(
 
     // Check if already registered - if so, update path if it's different and always re-validate metadata
     let options_count = if let Some(builtin_engine) = storage.engines.iter_mut().find(|e| e.is_builtin) {
-        let path_exists = std::path::Path::new(&builtin_engine.path).exists();
+        let path_exists = inprocess_engine::is_in_process(&builtin_engine.path)
+            || std::path::Path::new(&builtin_engine.path).exists();
         let path_is_correct = builtin_engine.path == engine_path;
         
         // Update path if incorrect or file doesn't exist
@@ -687,7 +734,7 @@ pub async fn health_check_engines(
         }
 
         log::info!("Health checking engine: {}", engine.name);
-        match engine_validator::validate_engine(&engine.path).await {
+        match engine_validator::validate_engine_with_protocol(&engine.path, engine.protocol).await {
             Ok(_) => {
                 results.push(serde_json::json!({
                     "id": engine.id,
@@ -712,102 +759,1719 @@ pub async fn health_check_engines(
     ))
 }
 
-/// Start an engine-vs-engine match
+/// Get the latest background health reports for running engines
 #[tauri::command]
-pub async fn start_engine_vs_engine(
-    app_handle: tauri::AppHandle,
+pub async fn get_engine_health(
+    engine_id: Option<String>,
     state: State<'_, AppState>,
-    engine1_id: String,
-    engine2_id: String,
-    initial_sfen: Option<String>,
-    time_per_move_ms: Option<u64>,
-    max_moves: Option<usize>,
 ) -> Result<CommandResponse, String> {
-    log::info!("Command: start_engine_vs_engine - {} vs {}", engine1_id, engine2_id);
+    let reports = state.engine_manager.get_health_reports(engine_id.as_deref()).await;
 
-    // Get engine configurations
-    let storage = state.engine_storage.read().await;
-    
-    let engine1 = storage.get_engine(&engine1_id)
-        .ok_or_else(|| "Engine 1 not found".to_string())?;
-    let engine2 = storage.get_engine(&engine2_id)
-        .ok_or_else(|| "Engine 2 not found".to_string())?;
+    Ok(CommandResponse::success_with_data(
+        serde_json::json!({ "reports": reports })
+    ))
+}
 
-    let config = EngineVsEngineConfig {
-        engine1_id: engine1_id.clone(),
-        engine1_path: engine1.path.clone(),
-        engine1_name: engine1.name.clone(),
-        engine2_id: engine2_id.clone(),
-        engine2_path: engine2.path.clone(),
-        engine2_name: engine2.name.clone(),
-        initial_sfen,
-        time_per_move_ms: time_per_move_ms.unwrap_or(5000),
-        max_moves: max_moves.unwrap_or(200),
+/// Get an engine's logged USI traffic for the engine console. Pass the `seq` of
+/// the last entry seen plus one as `since` to fetch only new lines. Without an
+/// engine id, lists the logged engines of `session_id` instead.
+#[tauri::command]
+pub async fn get_engine_log(
+    engine_id: Option<String>,
+    session_id: Option<String>,
+    since: Option<u64>,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    let Some(engine_id) = engine_id else {
+        let session_id = match state.sessions.resolve(session_id.as_deref()).await {
+            Ok(session_id) => session_id,
+            Err(e) => return Ok(CommandResponse::error(e.to_string())),
+        };
+        let engine_ids = state.engine_manager.session_engine_logs(&session_id).await;
+        return Ok(CommandResponse::success_with_data(
+            serde_json::json!({ "engine_ids": engine_ids })
+        ));
     };
 
-    drop(storage);
+    match state.engine_manager.engine_log(&engine_id, since).await {
+        Ok(entries) => Ok(CommandResponse::success_with_data(
+            serde_json::json!({ "entries": entries })
+        )),
+        Err(e) => Ok(CommandResponse::error(e.to_string())),
+    }
+}
 
-    // Spawn the game loop in a background task
-    let manager = EngineVsEngineManager::new(app_handle, config, state.engine_storage.clone());
-    
-    tokio::spawn(async move {
-        if let Err(e) = manager.run_match().await {
-            log::error!("Engine-vs-engine match error: {}", e);
-        }
-    });
+/// Save an engine's log as a text file, e.g. to attach to a bug report
+#[tauri::command]
+pub async fn export_engine_log(
+    engine_id: String,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::info!("Command: export_engine_log - engine_id: {}, path: {}", engine_id, path);
+
+    match state
+        .engine_manager
+        .export_engine_log(&engine_id, std::path::Path::new(&path))
+        .await
+    {
+        Ok(()) => Ok(CommandResponse::success()),
+        Err(e) => Ok(CommandResponse::error(format!("Failed to export engine log: {}", e))),
+    }
+}
 
-    Ok(CommandResponse::success())
+/// Clear an engine's log
+#[tauri::command]
+pub async fn clear_engine_log(
+    engine_id: String,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    match state.engine_manager.clear_engine_log(&engine_id).await {
+        Ok(()) => Ok(CommandResponse::success()),
+        Err(e) => Ok(CommandResponse::error(e.to_string())),
+    }
 }
 
-/// Save engine options
+/// Get the statistics of an engine's last search for the engine internals panel
 #[tauri::command]
-pub async fn save_engine_options(
+pub async fn get_search_statistics(
     engine_id: String,
-    options: std::collections::HashMap<String, String>,
     state: State<'_, AppState>,
 ) -> Result<CommandResponse, String> {
-    log::info!("Command: save_engine_options - engine_id: {}, options: {:?}", engine_id, options);
+    log::debug!("Command: get_search_statistics - engine_id: {}", engine_id);
+
+    match state
+        .engine_manager
+        .request_search_statistics(&engine_id, std::time::Duration::from_secs(2))
+        .await
+    {
+        Ok(statistics) => Ok(CommandResponse::success_with_data(statistics)),
+        Err(e) => Ok(CommandResponse::error(format!("Failed to get search statistics: {}", e))),
+    }
+}
 
-    let mut storage = state.engine_storage.write().await;
-    
-    match storage.save_engine_options(&engine_id, options) {
-        Ok(_) => {
-            // Save to disk
-            if let Err(e) = storage.save().await {
-                log::error!("Failed to save engine storage: {}", e);
-                return Ok(CommandResponse::error(format!("Failed to save options: {}", e)));
-            }
-            
-            log::info!("Engine options saved successfully for engine: {}", engine_id);
-            Ok(CommandResponse::success())
-        }
-        Err(e) => {
-            log::error!("Failed to save engine options: {}", e);
-            Ok(CommandResponse::error(format!("Failed to save options: {}", e)))
+/// Get an engine's aggregated statistics (search, move ordering, TT, tablebase,
+/// magic bitboards). `mode` is `delta` for counters since the last `reset`, or
+/// `reset` to start a new measurement; omit it for the cumulative snapshot.
+#[tauri::command]
+pub async fn get_statistics_hub(
+    engine_id: String,
+    mode: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: get_statistics_hub - engine_id: {}, mode: {:?}", engine_id, mode);
+
+    if let Some(ref mode) = mode {
+        if mode != "delta" && mode != "reset" {
+            return Ok(CommandResponse::error(format!("Unknown statistics mode: {}", mode)));
         }
     }
+
+    match state
+        .engine_manager
+        .request_statistics_hub(&engine_id, mode.as_deref(), std::time::Duration::from_secs(2))
+        .await
+    {
+        Ok(statistics) => Ok(CommandResponse::success_with_data(statistics)),
+        Err(e) => Ok(CommandResponse::error(format!("Failed to get statistics: {}", e))),
+    }
 }
 
-/// Get saved engine options
+/// Get the legal destinations of a square (`7g`) or hand piece (`P*`) with
+/// capture, promotion, check and exchange metadata
 #[tauri::command]
-pub async fn get_engine_options(
+pub async fn get_move_hints(
     engine_id: String,
+    source: String,
     state: State<'_, AppState>,
 ) -> Result<CommandResponse, String> {
-    log::info!("Command: get_engine_options - engine_id: {}", engine_id);
+    log::debug!("Command: get_move_hints - engine_id: {}, source: {}", engine_id, source);
+
+    match state
+        .engine_manager
+        .request_move_hints(&engine_id, &source, std::time::Duration::from_secs(2))
+        .await
+    {
+        Ok(hints) => Ok(CommandResponse::success_with_data(
+            serde_json::json!({ "hints": hints })
+        )),
+        Err(e) => Ok(CommandResponse::error(format!("Failed to get move hints: {}", e))),
+    }
+}
 
-    let storage = state.engine_storage.read().await;
-    
-    match storage.get_engine_options(&engine_id) {
-        Some(options) => {
-            log::info!("Retrieved {} saved options for engine: {}", options.len(), engine_id);
-            Ok(CommandResponse::success_with_data(serde_json::to_value(options).unwrap()))
+/// Get a suggested move with a human-readable reason for the hint button and
+/// best-move arrow. Level 1 is a shallow, learner-friendly hint; level 5 is the
+/// full-strength best move.
+#[tauri::command]
+pub async fn get_hint(
+    engine_id: String,
+    level: u8,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: get_hint - engine_id: {}, level: {}", engine_id, level);
+
+    // The reason comes back in the engine's language
+    let locale = *state.locale.read().await;
+    let language = format!("setoption name Language value {}", locale);
+    if let Err(e) = state.engine_manager.send_command(&engine_id, &language).await {
+        return Ok(CommandResponse::error(format!("Failed to get hint: {}", e)));
+    }
+    match state
+        .engine_manager
+        .request_hint(&engine_id, level, std::time::Duration::from_secs(10))
+        .await
+    {
+        Ok(hint) => Ok(CommandResponse::success_with_data(hint)),
+        Err(e) => Ok(CommandResponse::error(format!("Failed to get hint: {}", e))),
+    }
+}
+
+/// Get the top `count` candidate moves of an engine's current position, ranked,
+/// with scores and short continuations for drawing analysis arrows. The search
+/// shares `time_ms` (600 ms by default) across the ranks.
+#[tauri::command]
+pub async fn get_candidate_moves(
+    engine_id: String,
+    count: usize,
+    time_ms: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: get_candidate_moves - engine_id: {}, count: {}", engine_id, count);
+
+    let time_ms = time_ms.unwrap_or(600);
+    let timeout = std::time::Duration::from_millis(u64::from(time_ms) + 5_000);
+    match state
+        .engine_manager
+        .request_candidates(&engine_id, count, time_ms, timeout)
+        .await
+    {
+        Ok(candidates) => Ok(CommandResponse::success_with_data(
+            serde_json::json!({ "candidates": candidates })
+        )),
+        Err(e) => Ok(CommandResponse::error(format!("Failed to get candidate moves: {}", e))),
+    }
+}
+
+/// Get the mate meter of an engine's current position: each king's danger from 0
+/// to 100 with the attack summary behind it and any mate within three plies
+#[tauri::command]
+pub async fn get_mate_meter(
+    engine_id: String,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: get_mate_meter - engine_id: {}", engine_id);
+
+    match state
+        .engine_manager
+        .request_mate_meter(&engine_id, std::time::Duration::from_secs(2))
+        .await
+    {
+        Ok(meter) => Ok(CommandResponse::success_with_data(meter)),
+        Err(e) => Ok(CommandResponse::error(format!("Failed to get mate meter: {}", e))),
+    }
+}
+
+/// Get how sharp an engine's current position is, from 0 to 1000: the spread of
+/// win rates over its best `count` moves blended with the tactical density of the
+/// board, searched within `time_ms`; both default to the engine's own defaults
+#[tauri::command]
+pub async fn get_position_sharpness(
+    engine_id: String,
+    count: Option<usize>,
+    time_ms: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: get_position_sharpness - engine_id: {}", engine_id);
+
+    let count = count.unwrap_or(DEFAULT_SHARPNESS_CANDIDATES);
+    let time_ms = time_ms.unwrap_or(DEFAULT_SHARPNESS_TIME_MS);
+    let timeout = std::time::Duration::from_millis(u64::from(time_ms) + 5_000);
+    match state
+        .engine_manager
+        .request_sharpness(&engine_id, count, time_ms, timeout)
+        .await
+    {
+        Ok(sharpness) => Ok(CommandResponse::success_with_data(sharpness)),
+        Err(e) => Ok(CommandResponse::error(format!("Failed to get position sharpness: {}", e))),
+    }
+}
+
+/// Whether a game has ended and how, from its starting SFEN (the even-game start
+/// position when omitted) and the USI moves played since, with the metadata of
+/// each move (check, mate, promotion, capture) for sounds and KIF marks. An
+/// illegal move is an error naming it.
+#[tauri::command]
+pub async fn get_game_status(
+    sfen: Option<String>,
+    moves: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: get_game_status - {} moves", moves.len());
+
+    let sfen = sfen.unwrap_or_else(|| Handicap::Even.sfen().to_string());
+    let mut tracker = match GameTracker::from_sfen(&sfen) {
+        Ok(tracker) => tracker,
+        Err(e) => return Ok(CommandResponse::engine_error(e)),
+    };
+    let mut played = Vec::with_capacity(moves.len());
+    for mv in &moves {
+        match tracker.play_usi(mv) {
+            Ok(played_move) => played.push(played_move.metadata),
+            Err(e) => return Ok(CommandResponse::engine_error(e)),
         }
-        None => {
-            log::info!("No saved options found for engine: {}", engine_id);
-            Ok(CommandResponse::success_with_data(serde_json::Value::Object(serde_json::Map::new())))
+    }
+    let status = tracker.status();
+    Ok(CommandResponse::success_with_data(serde_json::json!({
+        "status": status,
+        "over": status.is_over(),
+        "winner": status.winner(),
+        "reason": status.reason(),
+        "message": status.message(*state.locale.read().await),
+        "side_to_move": tracker.side_to_move(),
+        "moves": played,
+    })))
+}
+
+/// Write the USI moves played from `sfen` (the even starting position when
+/// omitted) in `style`, one entry per move, for the move list and KIF export
+#[tauri::command]
+pub async fn format_moves(
+    sfen: Option<String>,
+    moves: Vec<String>,
+    style: NotationStyle,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: format_moves - {} moves as {:?}", moves.len(), style);
+
+    let sfen = sfen.unwrap_or_else(|| Handicap::Even.sfen().to_string());
+    let mut tracker = match GameTracker::from_sfen(&sfen) {
+        Ok(tracker) => tracker,
+        Err(e) => return Ok(CommandResponse::engine_error(e)),
+    };
+    let mut formatted = Vec::with_capacity(moves.len());
+    for mv in &moves {
+        match tracker.notation_context().convert(mv, NotationStyle::Usi, style) {
+            Ok(text) => formatted.push(text),
+            Err(e) => return Ok(CommandResponse::engine_error(e)),
+        }
+        if let Err(e) = tracker.play_usi(mv) {
+            return Ok(CommandResponse::engine_error(e));
+        }
+    }
+    Ok(CommandResponse::success_with_data(serde_json::json!(formatted)))
+}
+
+/// Rewrite one move from `from` notation to `to` notation in the position after
+/// `moves`, e.g. a typed `５八金右` to the USI move to send to the engine
+#[tauri::command]
+pub async fn convert_move_notation(
+    sfen: Option<String>,
+    moves: Vec<String>,
+    text: String,
+    from: NotationStyle,
+    to: NotationStyle,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: convert_move_notation - {} from {:?} to {:?}", text, from, to);
+
+    let sfen = sfen.unwrap_or_else(|| Handicap::Even.sfen().to_string());
+    let mut tracker = match GameTracker::from_sfen(&sfen) {
+        Ok(tracker) => tracker,
+        Err(e) => return Ok(CommandResponse::engine_error(e)),
+    };
+    for mv in &moves {
+        if let Err(e) = tracker.play_usi(mv) {
+            return Ok(CommandResponse::engine_error(e));
+        }
+    }
+    match tracker.notation_context().convert(&text, from, to) {
+        Ok(converted) => Ok(CommandResponse::success_with_data(serde_json::json!(converted))),
+        Err(e) => Ok(CommandResponse::engine_error(e)),
+    }
+}
+
+/// Write the position after `moves` from `sfen` (the even starting position
+/// when omitted) as SFEN and as a BOD board diagram, for the clipboard
+#[tauri::command]
+pub async fn export_position(
+    sfen: Option<String>,
+    moves: Vec<String>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: export_position - {} moves", moves.len());
+
+    let sfen = sfen.unwrap_or_else(|| Handicap::Even.sfen().to_string());
+    let mut tracker = match GameTracker::from_sfen(&sfen) {
+        Ok(tracker) => tracker,
+        Err(e) => return Ok(CommandResponse::engine_error(e)),
+    };
+    for mv in &moves {
+        if let Err(e) = tracker.play_usi(mv) {
+            return Ok(CommandResponse::engine_error(e));
         }
     }
+    let position = tracker.position_text();
+    Ok(CommandResponse::success_with_data(serde_json::json!({
+        "sfen": position.to_sfen(),
+        "bod": position.to_bod(),
+    })))
+}
+
+/// What changed on the board and in the hands from the position after
+/// `from_ply` of `moves` (the one before the last move when omitted) to the
+/// position after all of them, for animating moves without a full redraw
+#[tauri::command]
+pub async fn get_board_diff(
+    sfen: Option<String>,
+    moves: Vec<String>,
+    from_ply: Option<usize>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: get_board_diff - {} moves from ply {:?}", moves.len(), from_ply);
+
+    let from_ply = from_ply.unwrap_or(moves.len().saturating_sub(1)).min(moves.len());
+    let sfen = sfen.unwrap_or_else(|| Handicap::Even.sfen().to_string());
+    let mut tracker = match GameTracker::from_sfen(&sfen) {
+        Ok(tracker) => tracker,
+        Err(e) => return Ok(CommandResponse::engine_error(e)),
+    };
+    let mut before = None;
+    for (ply, mv) in moves.iter().enumerate() {
+        if ply == from_ply {
+            before = Some(tracker.position_text());
+        }
+        if let Err(e) = tracker.play_usi(mv) {
+            return Ok(CommandResponse::engine_error(e));
+        }
+    }
+    let after = tracker.position_text();
+    let diff = BoardDiff::between(before.as_ref().unwrap_or(&after), &after);
+    Ok(CommandResponse::success_with_data(serde_json::json!(diff)))
+}
+
+/// Read pasted SFEN or BOD text and check the position. Unreadable text is an
+/// error; a readable position comes back as canonical SFEN with its issues,
+/// `valid` being false when any of them rules the position out
+#[tauri::command]
+pub async fn import_position(
+    text: String,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: import_position - {} bytes", text.len());
+
+    let locale = *state.locale.read().await;
+    match PositionText::parse(&text) {
+        Ok(position) => Ok(CommandResponse::success_with_data(position_report(&position, locale))),
+        Err(e) => Ok(CommandResponse::error(format!("Invalid position: {}", e))),
+    }
+}
+
+/// Read a position given as a 9x9 grid of piece codes and two hands, as a
+/// board-photo recognizer or another tool hands it over. Cell codes are
+/// normalized; the answer is the same canonical SFEN and issues as for
+/// `import_position`
+#[tauri::command]
+pub async fn set_position_from_matrix(
+    matrix: BoardMatrix,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: set_position_from_matrix - {} rows", matrix.squares.len());
+
+    let locale = *state.locale.read().await;
+    match matrix.to_position() {
+        Ok(position) => Ok(CommandResponse::success_with_data(position_report(&position, locale))),
+        Err(e) => Ok(CommandResponse::error(format!("Invalid position: {}", e))),
+    }
+}
+
+/// Open a `shogi://` link in a new analysis session, as if the system had
+/// handed it to the app; the opened game comes back in the data
+#[tauri::command]
+pub async fn open_deep_link(
+    url: String,
+    app_handle: tauri::AppHandle,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: open_deep_link - url: {}", url);
+
+    match deep_link::open(&app_handle, &url).await {
+        Ok(link) => Ok(CommandResponse::success_with_data(
+            serde_json::to_value(&link).unwrap_or(serde_json::json!({}))
+        )),
+        Err(e) => Ok(CommandResponse::error(format!("Failed to open link: {}", e))),
+    }
+}
+
+/// Links opened before the frontend listened for `deep-link::open`, e.g. the
+/// one the app was launched with. Later links are only emitted.
+#[tauri::command]
+pub async fn take_deep_links(state: State<'_, AppState>) -> Result<CommandResponse, String> {
+    let links = state.deep_links.write().await.take_pending();
+    Ok(CommandResponse::success_with_data(serde_json::json!({ "links": links })))
+}
+
+/// Canonical SFEN of an imported position with its validation issues in `locale`
+fn position_report(position: &PositionText, locale: Locale) -> serde_json::Value {
+    let issues = position.validate();
+    let valid = !issues.iter().any(|issue| issue.is_error());
+    let issues: Vec<_> = issues
+        .iter()
+        .map(|issue| {
+            serde_json::json!({"message": issue.message(locale), "error": issue.is_error()})
+        })
+        .collect();
+    serde_json::json!({
+        "sfen": position.to_sfen(),
+        "side_to_move": position.side_to_move,
+        "issues": issues,
+        "valid": valid,
+    })
+}
+
+/// Get the named opening of an engine's current game, e.g. `{"name": "Yagura",
+/// "display_name": "矢倉", "plies": 6}` with the display name in the current
+/// locale; `null` data while the game is not in a known book line
+#[tauri::command]
+pub async fn get_opening(
+    engine_id: String,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: get_opening - engine_id: {}", engine_id);
+
+    match state
+        .engine_manager
+        .request_opening(&engine_id, std::time::Duration::from_secs(2))
+        .await
+    {
+        Ok(mut opening) => {
+            if let Some(name) = opening.get("name").and_then(|name| name.as_str()) {
+                let display_name = i18n::opening_name(*state.locale.read().await, name);
+                opening["display_name"] = serde_json::json!(display_name);
+            }
+            Ok(CommandResponse::success_with_data(opening))
+        }
+        Err(e) => Ok(CommandResponse::error(format!("Failed to get opening: {}", e))),
+    }
+}
+
+/// Get a tsume problem with its solve statistics: the one with `problem_id`, or
+/// the first unsolved built-in problem when no id is given
+#[tauri::command]
+pub async fn get_tsume_problem(
+    problem_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: get_tsume_problem - problem_id: {:?}", problem_id);
+
+    let stats = state.tsume_stats.read().await;
+    match crate::tsume_trainer::select_problem(&stats, problem_id.as_deref()) {
+        Some(problem) => Ok(CommandResponse::success_with_data(serde_json::json!({
+            "stats": stats.get(&problem.id),
+            "problem": problem,
+        }))),
+        None => Ok(CommandResponse::error(format!(
+            "Unknown tsume problem: {}",
+            problem_id.unwrap_or_default()
+        ))),
+    }
+}
+
+/// Check the moves played so far on a tsume problem. Any mating line within the
+/// problem's length is accepted; a correct attacker move is answered with the
+/// defence that holds out longest. Solved and failed attempts are recorded.
+#[tauri::command]
+pub async fn submit_tsume_moves(
+    problem_id: String,
+    moves: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: submit_tsume_moves - problem_id: {}, moves: {:?}", problem_id, moves);
+
+    let Some(problem) = shogi_engine::tsume::builtin_problems()
+        .into_iter()
+        .find(|problem| problem.id == problem_id)
+    else {
+        return Ok(CommandResponse::error(format!("Unknown tsume problem: {}", problem_id)));
+    };
+
+    // Mate searches are CPU-bound, keep them off the async runtime
+    let verdict = match tokio::task::spawn_blocking(move || problem.verify(&moves)).await {
+        Ok(Ok(verdict)) => verdict,
+        Ok(Err(e)) => return Ok(CommandResponse::error(format!("Failed to verify moves: {}", e))),
+        Err(e) => return Ok(CommandResponse::error(format!("Verification task failed: {}", e))),
+    };
+
+    let mut stats = state.tsume_stats.write().await;
+    if stats.record(&problem_id, &verdict) {
+        if let Err(e) = stats.save().await {
+            log::error!("Failed to save tsume statistics: {}", e);
+        }
+    }
+
+    Ok(CommandResponse::success_with_data(serde_json::json!({
+        "verdict": verdict,
+        "stats": stats.get(&problem_id),
+    })))
+}
+
+/// Get the solve statistics of every tsume problem
+#[tauri::command]
+pub async fn get_tsume_stats(state: State<'_, AppState>) -> Result<CommandResponse, String> {
+    log::debug!("Command: get_tsume_stats");
+
+    let stats = state.tsume_stats.read().await;
+    Ok(CommandResponse::success_with_data(serde_json::json!(stats.problems)))
+}
+
+/// Create a tournament between registered engines. Round robin schedules every
+/// game up front; Swiss pairs each round from the standings. Returns the
+/// tournament with its pairings; `start_tournament` plays it.
+#[tauri::command]
+pub async fn create_tournament(
+    state: State<'_, AppState>,
+    name: String,
+    format: TournamentFormat,
+    engine_ids: Vec<String>,
+    rounds: Option<usize>,
+    time_per_move_ms: Option<u64>,
+    max_moves: Option<usize>,
+    initial_sfen: Option<String>,
+    adjudication: Option<AdjudicationConfig>,
+    concurrency: Option<usize>,
+) -> Result<CommandResponse, String> {
+    log::info!("Command: create_tournament - {} with {} engines", name, engine_ids.len());
+
+    let storage = state.engine_storage.read().await;
+    let mut entrants = Vec::with_capacity(engine_ids.len());
+    for engine_id in &engine_ids {
+        match storage.get_engine(engine_id) {
+            Some(engine) => entrants.push(TournamentEntrant {
+                engine_id: engine.id.clone(),
+                name: engine.display_name.clone(),
+            }),
+            None => return Ok(CommandResponse::error(format!("Engine not found: {}", engine_id))),
+        }
+    }
+    drop(storage);
+
+    let config = TournamentConfig {
+        name,
+        format,
+        entrants,
+        rounds,
+        time_per_move_ms: time_per_move_ms.unwrap_or(5000),
+        max_moves: max_moves.unwrap_or(200),
+        initial_sfen,
+        adjudication: adjudication.unwrap_or_default(),
+        concurrency: concurrency.unwrap_or(1),
+    };
+    let tournament = match Tournament::new(uuid::Uuid::new_v4().to_string(), config) {
+        Ok(tournament) => tournament,
+        Err(e) => return Ok(CommandResponse::error(e.to_string())),
+    };
+    let summary = tournament.summary();
+
+    let mut tournaments = state.tournaments.write().await;
+    tournaments.tournaments.insert(tournament.id.clone(), tournament);
+    if let Err(e) = tournaments.save().await {
+        log::error!("Failed to save tournaments: {}", e);
+    }
+    Ok(CommandResponse::success_with_data(summary))
+}
+
+/// Start or resume a tournament in the background. Progress arrives as
+/// `tournament-update::<id>` events with the pairings and standings.
+#[tauri::command]
+pub async fn start_tournament(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    tournament_id: String,
+) -> Result<CommandResponse, String> {
+    log::info!("Command: start_tournament - {}", tournament_id);
+
+    let generation = {
+        let mut tournaments = state.tournaments.write().await;
+        let Some(tournament) = tournaments.tournaments.get_mut(&tournament_id) else {
+            return Ok(CommandResponse::error(format!("Tournament not found: {}", tournament_id)));
+        };
+        match tournament.status {
+            TournamentStatus::Running => {
+                return Ok(CommandResponse::error("Tournament is already running".to_string()))
+            }
+            TournamentStatus::Finished => {
+                return Ok(CommandResponse::error("Tournament is finished".to_string()))
+            }
+            TournamentStatus::Pending => tournament.start_run(),
+        }
+    };
+
+    let runner = TournamentRunner {
+        app_handle,
+        tournaments: state.tournaments.clone(),
+        sessions: state.sessions.clone(),
+        engine_storage: state.engine_storage.clone(),
+    };
+    state.background_games.spawn(runner.run(tournament_id, generation)).await;
+    Ok(CommandResponse::success())
+}
+
+/// Pause a running tournament. Games in progress are abandoned and replayed
+/// when the tournament is started again.
+#[tauri::command]
+pub async fn pause_tournament(
+    state: State<'_, AppState>,
+    tournament_id: String,
+) -> Result<CommandResponse, String> {
+    log::info!("Command: pause_tournament - {}", tournament_id);
+
+    let sessions_in_play = {
+        let mut tournaments = state.tournaments.write().await;
+        let Some(tournament) = tournaments.tournaments.get_mut(&tournament_id) else {
+            return Ok(CommandResponse::error(format!("Tournament not found: {}", tournament_id)));
+        };
+        if tournament.status == TournamentStatus::Running {
+            tournament.status = TournamentStatus::Pending;
+        }
+        tournament
+            .pairings
+            .iter()
+            .filter(|pairing| pairing.result.is_none())
+            .filter_map(|pairing| pairing.session_id.clone())
+            .collect::<Vec<_>>()
+    };
+    for session_id in sessions_in_play {
+        let _ = state.sessions.close_session(&session_id).await;
+    }
+    Ok(CommandResponse::success())
+}
+
+/// Get a tournament with its pairings and current standings
+#[tauri::command]
+pub async fn get_tournament(
+    state: State<'_, AppState>,
+    tournament_id: String,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: get_tournament - {}", tournament_id);
+
+    match state.tournaments.read().await.tournaments.get(&tournament_id) {
+        Some(tournament) => Ok(CommandResponse::success_with_data(tournament.summary())),
+        None => Ok(CommandResponse::error(format!("Tournament not found: {}", tournament_id))),
+    }
+}
+
+/// List all tournaments with their standings, newest first
+#[tauri::command]
+pub async fn list_tournaments(state: State<'_, AppState>) -> Result<CommandResponse, String> {
+    log::debug!("Command: list_tournaments");
+
+    let tournaments = state.tournaments.read().await;
+    let mut list: Vec<_> = tournaments.tournaments.values().collect();
+    list.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    let summaries: Vec<_> = list.into_iter().map(|tournament| tournament.summary()).collect();
+    Ok(CommandResponse::success_with_data(serde_json::json!(summaries)))
+}
+
+/// Delete a tournament that is not running
+#[tauri::command]
+pub async fn delete_tournament(
+    state: State<'_, AppState>,
+    tournament_id: String,
+) -> Result<CommandResponse, String> {
+    log::info!("Command: delete_tournament - {}", tournament_id);
+
+    let mut tournaments = state.tournaments.write().await;
+    match tournaments.tournaments.get(&tournament_id).map(|tournament| tournament.status) {
+        None => {
+            return Ok(CommandResponse::error(format!("Tournament not found: {}", tournament_id)))
+        }
+        Some(TournamentStatus::Running) => {
+            return Ok(CommandResponse::error("Pause the tournament first".to_string()))
+        }
+        Some(_) => {}
+    }
+    tournaments.tournaments.remove(&tournament_id);
+    if let Err(e) = tournaments.save().await {
+        log::error!("Failed to save tournaments: {}", e);
+    }
+    Ok(CommandResponse::success())
+}
+
+/// Record the evaluation after a played move for a session's advantage graph.
+/// `score` is in `perspective` (the side to move by default, as engines report
+/// it); `side_to_move` is the player to move after the move. Ply 0 is the
+/// starting position. Returns the updated series.
+#[tauri::command]
+pub async fn record_move_score(
+    session_id: Option<String>,
+    ply: u32,
+    move_usi: Option<String>,
+    score: i32,
+    side_to_move: Player,
+    source: ScoreSource,
+    depth: Option<u8>,
+    perspective: Option<EvaluationPerspective>,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: record_move_score - ply: {}, score: {}, source: {:?}", ply, score, source);
+
+    let session_id = match state.sessions.resolve(session_id.as_deref()).await {
+        Ok(id) => id,
+        Err(e) => return Ok(CommandResponse::error(e.to_string())),
+    };
+
+    // Orienting is its own inverse, so this brings the score back to the side to move
+    let score = perspective.unwrap_or_default().orient(score, side_to_move);
+    let point = ScorePoint::from_side_to_move(ply, move_usi, score, side_to_move, source, depth);
+    let mut trends = state.score_trends.write().await;
+    let trend = trends.entry(session_id).or_default();
+    trend.record(point);
+    Ok(CommandResponse::success_with_data(serde_json::json!(trend.series())))
+}
+
+/// Get a session's advantage graph: the evaluation after each move from sente's
+/// perspective, with inaccuracy, mistake and blunder markers and the critical
+/// moments found by the review
+#[tauri::command]
+pub async fn get_score_trend(
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: get_score_trend - session_id: {:?}", session_id);
+
+    let session_id = match state.sessions.resolve(session_id.as_deref()).await {
+        Ok(id) => id,
+        Err(e) => return Ok(CommandResponse::error(e.to_string())),
+    };
+
+    let trends = state.score_trends.read().await;
+    let series = trends
+        .get(&session_id)
+        .map(|trend| trend.series())
+        .unwrap_or_default();
+    Ok(CommandResponse::success_with_data(serde_json::json!(series)))
+}
+
+/// Start a new advantage graph for a session, or drop the scores after `ply`
+/// (after a takeback). A new graph records who moves first, for handicap games.
+#[tauri::command]
+pub async fn reset_score_trend(
+    session_id: Option<String>,
+    after_ply: Option<u32>,
+    first_mover: Option<Player>,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: reset_score_trend - session_id: {:?}, after_ply: {:?}", session_id, after_ply);
+
+    let session_id = match state.sessions.resolve(session_id.as_deref()).await {
+        Ok(id) => id,
+        Err(e) => return Ok(CommandResponse::error(e.to_string())),
+    };
+
+    let mut trends = state.score_trends.write().await;
+    match after_ply {
+        Some(ply) => {
+            if let Some(trend) = trends.get_mut(&session_id) {
+                trend.truncate(ply);
+            }
+        }
+        None => {
+            trends.insert(
+                session_id,
+                ScoreTrend::with_first_mover(first_mover.unwrap_or(Player::Black)),
+            );
+        }
+    }
+    Ok(CommandResponse::success())
+}
+
+/// Record how long a played move took: `go` to `bestmove` for engines, the
+/// session clock for humans. `remaining_ms` is the mover's clock after the move
+/// in games with a clock. Ply 1 is the first move.
+#[tauri::command]
+pub async fn record_move_time(
+    session_id: Option<String>,
+    ply: u32,
+    spent_ms: u64,
+    remaining_ms: Option<u64>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: record_move_time - ply: {}, spent: {} ms", ply, spent_ms);
+
+    let session_id = match state.sessions.resolve(session_id.as_deref()).await {
+        Ok(id) => id,
+        Err(e) => return Ok(CommandResponse::error(e.to_string())),
+    };
+
+    let time = MoveTime {
+        ply,
+        spent_ms,
+        remaining_ms,
+    };
+    let previous = {
+        let mut move_times = state.move_times.write().await;
+        let times = move_times.entry(session_id.clone()).or_default();
+        times.record(time);
+        ply.checked_sub(2)
+            .and_then(|earlier| times.get(earlier))
+            .and_then(|earlier| earlier.remaining_ms)
+    };
+
+    // The mover and the time control are known when the game was saved
+    let saved = state
+        .session_store
+        .read()
+        .await
+        .sessions
+        .get(&session_id)
+        .map(|persisted| persisted.snapshot.clone());
+    let mover = saved.as_ref().and_then(|snapshot| {
+        let first_mover = GameTracker::from_sfen(&snapshot.sfen).ok()?.side_to_move();
+        Some(if ply % 2 == 1 { first_mover } else { first_mover.opposite() })
+    });
+    let clocks = saved.as_ref().and_then(|snapshot| snapshot.clocks.as_ref());
+    let locale = *state.locale.read().await;
+    let events = session_events::clock_events(&time, previous, mover, clocks, locale);
+    session_events::emit(&app_handle, &session_id, &events);
+    Ok(CommandResponse::success())
+}
+
+/// Get a session's move times, with the review's markers split by time
+/// pressure so blunders under the clock stand out
+#[tauri::command]
+pub async fn get_move_times(
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: get_move_times - session_id: {:?}", session_id);
+
+    let session_id = match state.sessions.resolve(session_id.as_deref()).await {
+        Ok(id) => id,
+        Err(e) => return Ok(CommandResponse::error(e.to_string())),
+    };
+
+    let move_times = state.move_times.read().await;
+    let times = move_times.get(&session_id).cloned().unwrap_or_default();
+    let series = state
+        .score_trends
+        .read()
+        .await
+        .get(&session_id)
+        .map(|trend| trend.series())
+        .unwrap_or_default();
+    Ok(CommandResponse::success_with_data(serde_json::json!({
+        "times": times.times(),
+        "pressure": times.pressure_report(&series),
+    })))
+}
+
+/// Forget a session's move times, or only those after `ply` (after a takeback)
+#[tauri::command]
+pub async fn reset_move_times(
+    session_id: Option<String>,
+    after_ply: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: reset_move_times - session_id: {:?}, after_ply: {:?}", session_id, after_ply);
+
+    let session_id = match state.sessions.resolve(session_id.as_deref()).await {
+        Ok(id) => id,
+        Err(e) => return Ok(CommandResponse::error(e.to_string())),
+    };
+
+    let mut move_times = state.move_times.write().await;
+    match after_ply {
+        Some(ply) => {
+            if let Some(times) = move_times.get_mut(&session_id) {
+                times.truncate(ply);
+            }
+        }
+        None => {
+            move_times.insert(session_id, MoveTimes::new());
+        }
+    }
+    Ok(CommandResponse::success())
+}
+
+/// Write the USI moves played from `sfen` (the even starting position when
+/// omitted) as KIF, with the session's move times in the time column
+#[tauri::command]
+pub async fn export_kif(
+    session_id: Option<String>,
+    sfen: Option<String>,
+    moves: Vec<String>,
+    black_name: Option<String>,
+    white_name: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: export_kif - {} moves", moves.len());
+
+    let session_id = match state.sessions.resolve(session_id.as_deref()).await {
+        Ok(id) => id,
+        Err(e) => return Ok(CommandResponse::error(e.to_string())),
+    };
+
+    let mut game = match KifGame::from_usi_moves(sfen.as_deref(), &moves) {
+        Ok(game) => game,
+        Err(e) => return Ok(CommandResponse::error(e)),
+    };
+    game.metadata.player1_name = black_name.clone();
+    game.metadata.player2_name = white_name.clone();
+    for (key, name) in [("先手", black_name), ("後手", white_name)] {
+        if let Some(name) = name {
+            game.headers.push((key.to_string(), name));
+        }
+    }
+    if let Some(times) = state.move_times.read().await.get(&session_id) {
+        times.apply_to_kif(&mut game.moves);
+    }
+    Ok(CommandResponse::success_with_data(serde_json::json!(game.to_kif_string())))
+}
+
+/// Analyse a session's game in spare time: the USI moves played from `sfen`
+/// (the even starting position when omitted), every position at each of
+/// `depths` in turn (6, 10 and 14 by default) while no engine is thinking.
+/// Call again after each move or takeback; finished positions keep their
+/// analysis. Scores go into the session's advantage graph as review points,
+/// and `auto-analysis::<session_id>` events carry the progress and graph.
+#[tauri::command]
+pub async fn start_auto_analysis(
+    session_id: Option<String>,
+    sfen: Option<String>,
+    moves: Vec<String>,
+    depths: Option<Vec<u8>>,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: start_auto_analysis - {} moves", moves.len());
+
+    let session_id = match state.sessions.resolve(session_id.as_deref()).await {
+        Ok(id) => id,
+        Err(e) => return Ok(CommandResponse::error(e.to_string())),
+    };
+
+    let depths = depths.unwrap_or_else(|| DEFAULT_AUTO_ANALYSIS_DEPTHS.to_vec());
+    let mut queue = state.auto_analysis.write().await;
+    match queue.enqueue(&session_id, sfen.as_deref(), &moves, depths) {
+        Ok(progress) => Ok(CommandResponse::success_with_data(serde_json::json!(progress))),
+        Err(e) => Ok(CommandResponse::engine_error(e)),
+    }
+}
+
+/// Stop the spare-time analysis of a session's game; scores already recorded stay
+#[tauri::command]
+pub async fn stop_auto_analysis(
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: stop_auto_analysis - session_id: {:?}", session_id);
+
+    let session_id = match state.sessions.resolve(session_id.as_deref()).await {
+        Ok(id) => id,
+        Err(e) => return Ok(CommandResponse::error(e.to_string())),
+    };
+
+    state.auto_analysis.write().await.remove(&session_id);
+    Ok(CommandResponse::success())
+}
+
+/// How far the spare-time analysis of a session's game has come; no data when
+/// the game is not enrolled
+#[tauri::command]
+pub async fn get_auto_analysis_progress(
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: get_auto_analysis_progress - session_id: {:?}", session_id);
+
+    let session_id = match state.sessions.resolve(session_id.as_deref()).await {
+        Ok(id) => id,
+        Err(e) => return Ok(CommandResponse::error(e.to_string())),
+    };
+
+    match state.auto_analysis.read().await.progress(&session_id) {
+        Some(progress) => Ok(CommandResponse::success_with_data(serde_json::json!(progress))),
+        None => Ok(CommandResponse::success()),
+    }
+}
+
+/// Start an engine-vs-engine match
+#[tauri::command]
+pub async fn start_engine_vs_engine(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    engine1_id: String,
+    engine2_id: String,
+    initial_sfen: Option<String>,
+    time_per_move_ms: Option<u64>,
+    max_moves: Option<usize>,
+    session_id: Option<String>,
+    adjudication: Option<AdjudicationConfig>,
+) -> Result<CommandResponse, String> {
+    log::info!("Command: start_engine_vs_engine - {} vs {}", engine1_id, engine2_id);
+
+    // Each match runs in its own session unless the caller supplies one
+    let session = match session_id {
+        Some(session_id) => state
+            .sessions
+            .get_session(&session_id)
+            .await
+            .ok_or_else(|| format!("Session not found: {}", session_id))?,
+        None => {
+            state
+                .sessions
+                .create_session(
+                    SessionKind::EngineVsEngine,
+                    Some(format!("{} vs {}", engine1_id, engine2_id)),
+                )
+                .await
+        }
+    };
+
+    // Get engine configurations
+    let storage = state.engine_storage.read().await;
+    
+    let engine1 = storage.get_engine(&engine1_id)
+        .ok_or_else(|| "Engine 1 not found".to_string())?;
+    let engine2 = storage.get_engine(&engine2_id)
+        .ok_or_else(|| "Engine 2 not found".to_string())?;
+
+    let config = EngineVsEngineConfig {
+        session_id: session.id.clone(),
+        engine1_id: engine1_id.clone(),
+        engine1_path: engine1.path.clone(),
+        engine1_name: engine1.name.clone(),
+        engine2_id: engine2_id.clone(),
+        engine2_path: engine2.path.clone(),
+        engine2_name: engine2.name.clone(),
+        initial_sfen,
+        time_per_move_ms: time_per_move_ms.unwrap_or(5000),
+        max_moves: max_moves.unwrap_or(200),
+        adjudication: adjudication.unwrap_or_default(),
+    };
+
+    drop(storage);
+
+    // Spawn the game loop in a background task
+    let manager = EngineVsEngineManager::new(
+        app_handle,
+        config,
+        state.engine_storage.clone(),
+        session.cancel_flag(),
+    );
+    
+    let game = async move {
+        if let Err(e) = manager.run_match().await {
+            log::error!("Engine-vs-engine match error: {}", e);
+        }
+    };
+    state.background_games.spawn(game).await;
+
+    Ok(CommandResponse::success_with_data(
+        serde_json::json!({ "session_id": session.id })
+    ))
+}
+
+/// Get the versioned manifest of commands, events, features and limits this
+/// backend supports, for the frontend to feature-detect against
+#[tauri::command]
+pub async fn get_backend_capabilities(
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: get_backend_capabilities");
+
+    let storage = state.engine_storage.read().await;
+    let manifest = BackendCapabilities::current(&storage);
+    Ok(CommandResponse::success_with_data(
+        serde_json::to_value(&manifest).unwrap_or(serde_json::json!({}))
+    ))
+}
+
+/// Set the language of text the backend and built-in engine produce for
+/// players: hint reasons, game-end reasons, position issues and opening names.
+/// Takes a locale code such as `ja` or `ja-JP`.
+#[tauri::command]
+pub async fn set_locale(
+    locale: String,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: set_locale - locale: {}", locale);
+
+    match Locale::parse(&locale) {
+        Some(parsed) => {
+            *state.locale.write().await = parsed;
+            Ok(CommandResponse::success_with_data(serde_json::json!({ "locale": parsed })))
+        }
+        None => Ok(CommandResponse::error(format!("Unsupported locale: {}", locale))),
+    }
+}
+
+/// Get the current locale and the ones with a catalog
+#[tauri::command]
+pub async fn get_locale(state: State<'_, AppState>) -> Result<CommandResponse, String> {
+    let locale = *state.locale.read().await;
+    Ok(CommandResponse::success_with_data(serde_json::json!({
+        "locale": locale,
+        "available": Locale::ALL,
+    })))
+}
+
+/// Create a new game session (one board/tab in the UI)
+#[tauri::command]
+pub async fn create_session(
+    kind: SessionKind,
+    label: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::info!("Command: create_session - kind: {:?}", kind);
+
+    let session = state.sessions.create_session(kind, label).await;
+    Ok(CommandResponse::success_with_data(
+        serde_json::to_value(&session).unwrap_or(serde_json::json!({}))
+    ))
+}
+
+/// List all active game sessions
+#[tauri::command]
+pub async fn list_sessions(
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    let sessions = state.sessions.list_sessions().await;
+
+    Ok(CommandResponse::success_with_data(
+        serde_json::json!({ "sessions": sessions })
+    ))
+}
+
+/// Close a game session, stopping all of its engines and background tasks
+#[tauri::command]
+pub async fn close_session(
+    session_id: String,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::info!("Command: close_session - session_id: {}", session_id);
+
+    let session = match state.sessions.close_session(&session_id).await {
+        Ok(session) => session,
+        Err(e) => return Ok(CommandResponse::error(e.to_string())),
+    };
+
+    for engine_id in &session.engine_ids {
+        if let Err(e) = state.engine_manager.stop_engine(engine_id).await {
+            log::warn!("Failed to stop engine {} of session {}: {}", engine_id, session_id, e);
+        }
+    }
+    state.score_trends.write().await.remove(&session_id);
+    state.move_times.write().await.remove(&session_id);
+    state.auto_analysis.write().await.remove(&session_id);
+    state.session_store.write().await.remove(&session_id);
+    state.takebacks.write().await.remove(&session_id);
+    state.blindfold.write().await.remove(&session_id);
+    correlation::forget_session(&session_id);
+    state.repertoires.write().await.remove(&session_id);
+
+    Ok(CommandResponse::success())
+}
+
+/// Save a snapshot of a session's game (starting position, moves, clocks and
+/// engine seats) so it is restored after a restart. The moves must be legal.
+#[tauri::command]
+pub async fn save_session_snapshot(
+    session_id: Option<String>,
+    mut snapshot: SessionSnapshot,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: save_session_snapshot - {} moves", snapshot.moves.len());
+
+    let session_id = match state.sessions.resolve(session_id.as_deref()).await {
+        Ok(id) => id,
+        Err(e) => return Ok(CommandResponse::error(e.to_string())),
+    };
+    if let Err(e) = snapshot.validate() {
+        return Ok(CommandResponse::error(format!("Invalid snapshot: {}", e)));
+    }
+    let Some(session) = state.sessions.get_session(&session_id).await else {
+        return Ok(CommandResponse::error(format!("Session not found: {}", session_id)));
+    };
+
+    let move_times = state
+        .move_times
+        .read()
+        .await
+        .get(&session_id)
+        .cloned()
+        .unwrap_or_default();
+    state
+        .session_store
+        .write()
+        .await
+        .record(session, snapshot, &move_times);
+    Ok(CommandResponse::success())
+}
+
+/// List the sessions restored from the last run with their saved games, for
+/// the frontend to reopen their boards and respawn their engines
+#[tauri::command]
+pub async fn get_saved_sessions(
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    let store = state.session_store.read().await;
+    let sessions: Vec<_> = store.sessions.values().collect();
+    Ok(CommandResponse::success_with_data(
+        serde_json::json!({ "sessions": sessions })
+    ))
+}
+
+/// Take back the last `plies` moves (two by default) of a session's saved
+/// game: clocks are wound back, scores and move times after the new last move
+/// dropped, and the session's engines stop any search or ponder and get the
+/// new position. Refused once the game's takeback limit is used up.
+#[tauri::command]
+pub async fn take_back_moves(
+    session_id: Option<String>,
+    plies: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: take_back_moves - session_id: {:?}, plies: {:?}", session_id, plies);
+
+    let session_id = match state.sessions.resolve(session_id.as_deref()).await {
+        Ok(id) => id,
+        Err(e) => return Ok(CommandResponse::error(e.to_string())),
+    };
+    let Some(mut snapshot) = state
+        .session_store
+        .read()
+        .await
+        .sessions
+        .get(&session_id)
+        .map(|persisted| persisted.snapshot.clone())
+    else {
+        return Ok(CommandResponse::error(format!("No saved game for session {}", session_id)));
+    };
+
+    let (undone, policy) = {
+        let mut takebacks = state.takebacks.write().await;
+        let policy = takebacks.entry(session_id.clone()).or_default();
+        if let Err(e) = policy.check() {
+            return Ok(CommandResponse::error(e));
+        }
+        let mut move_times = state.move_times.write().await;
+        let times = move_times.entry(session_id.clone()).or_default();
+        let plies = plies.unwrap_or(takeback::DEFAULT_TAKEBACK_PLIES);
+        let undone = match takeback::rewind(&mut snapshot, plies, times) {
+            Ok(undone) => undone,
+            Err(e) => return Ok(CommandResponse::error(e)),
+        };
+        policy.used += 1;
+        (undone, policy.clone())
+    };
+    correlation::advance_sequence(&session_id);
+
+    let ply = snapshot.moves.len() as u32;
+    if let Some(trend) = state.score_trends.write().await.get_mut(&session_id) {
+        trend.truncate(ply);
+    }
+    {
+        let mut auto_analysis = state.auto_analysis.write().await;
+        if let Some(progress) = auto_analysis.progress(&session_id) {
+            let sfen = Some(snapshot.sfen.as_str());
+            let _ = auto_analysis.enqueue(&session_id, sfen, &snapshot.moves, progress.depths);
+        }
+    }
+
+    let position = if snapshot.moves.is_empty() {
+        format!("position sfen {}", snapshot.sfen)
+    } else {
+        format!("position sfen {} moves {}", snapshot.sfen, snapshot.moves.join(" "))
+    };
+    for engine_id in state.engine_manager.list_session_engines(&session_id).await {
+        if let Err(e) = state.engine_manager.abandon_search(&engine_id).await {
+            log::warn!("Failed to stop engine {} for takeback: {}", engine_id, e);
+        }
+        if let Err(e) = state.engine_manager.send_command(&engine_id, &position).await {
+            log::warn!("Failed to resync engine {} after takeback: {}", engine_id, e);
+        }
+    }
+
+    if let Some(session) = state.sessions.get_session(&session_id).await {
+        let times = state
+            .move_times
+            .read()
+            .await
+            .get(&session_id)
+            .cloned()
+            .unwrap_or_default();
+        state
+            .session_store
+            .write()
+            .await
+            .record(session, snapshot.clone(), &times);
+    }
+    Ok(CommandResponse::success_with_data(serde_json::json!({
+        "undone": undone,
+        "snapshot": snapshot,
+        "takebacks_used": policy.used,
+        "takebacks_remaining": policy.remaining(),
+    })))
+}
+
+/// Cap the takebacks allowed in a session's game; `null` for no limit.
+/// Takebacks already used still count.
+#[tauri::command]
+pub async fn set_takeback_limit(
+    session_id: Option<String>,
+    limit: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: set_takeback_limit - session_id: {:?}, limit: {:?}", session_id, limit);
+
+    let session_id = match state.sessions.resolve(session_id.as_deref()).await {
+        Ok(id) => id,
+        Err(e) => return Ok(CommandResponse::error(e.to_string())),
+    };
+    let mut takebacks = state.takebacks.write().await;
+    let policy = takebacks.entry(session_id).or_default();
+    policy.limit = limit;
+    Ok(CommandResponse::success_with_data(serde_json::json!(policy)))
+}
+
+/// Set what the frontend may see of a session's position: `{"mode": "off"}`,
+/// `"hide_identity"`, `"hide_all"`, or `{"mode": "hide_side", "player": ...}`
+#[tauri::command]
+pub async fn set_blindfold_mode(
+    session_id: Option<String>,
+    mode: BlindfoldMode,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: set_blindfold_mode - session_id: {:?}, mode: {:?}", session_id, mode);
+
+    let session_id = match state.sessions.resolve(session_id.as_deref()).await {
+        Ok(id) => id,
+        Err(e) => return Ok(CommandResponse::error(e.to_string())),
+    };
+    let mut blindfold = state.blindfold.write().await;
+    if mode == BlindfoldMode::Off {
+        blindfold.remove(&session_id);
+    } else {
+        blindfold.insert(session_id, mode);
+    }
+    Ok(CommandResponse::success())
+}
+
+/// Choose the opening repertoire a session's moves are checked against: `lines`
+/// of space-separated USI moves from the session's starting position, the first
+/// line through a position giving the recommended move there. `None` goes back
+/// to the opening book, which only covers even games; an empty list turns the
+/// check off. The first move leaving the lines is reported as an
+/// `opening_deviation` session event.
+#[tauri::command]
+pub async fn set_session_repertoire(
+    session_id: Option<String>,
+    lines: Option<Vec<String>>,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: set_session_repertoire - session_id: {:?}", session_id);
+
+    let session_id = match state.sessions.resolve(session_id.as_deref()).await {
+        Ok(id) => id,
+        Err(e) => return Ok(CommandResponse::error(e.to_string())),
+    };
+    let mut repertoires = state.repertoires.write().await;
+    match lines {
+        Some(lines) => {
+            let repertoire = Repertoire::from_lines(&lines);
+            let count = repertoire.len();
+            repertoires.insert(session_id, repertoire);
+            Ok(CommandResponse::success_with_data(serde_json::json!({ "lines": count })))
+        }
+        None => {
+            repertoires.remove(&session_id);
+            Ok(CommandResponse::success())
+        }
+    }
+}
+
+/// Get the current position of a session's saved game, with what its
+/// blindfold mode hides taken out
+#[tauri::command]
+pub async fn get_session_position(
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: get_session_position - session_id: {:?}", session_id);
+
+    let session_id = match state.sessions.resolve(session_id.as_deref()).await {
+        Ok(id) => id,
+        Err(e) => return Ok(CommandResponse::error(e.to_string())),
+    };
+    let tracker = match session_tracker(&state, &session_id).await {
+        Ok((tracker, _)) => tracker,
+        Err(e) => return Ok(CommandResponse::engine_error(e)),
+    };
+    let mode = state.blindfold.read().await.get(&session_id).copied().unwrap_or_default();
+    Ok(CommandResponse::success_with_data(serde_json::json!({
+        "position": mode.filter(&tracker.position_text()),
+        "status": tracker.status(),
+    })))
+}
+
+/// Play a move in a session's saved game. The move is checked against the
+/// full position, and the answer shows only what the blindfold mode allows.
+#[tauri::command]
+pub async fn play_session_move(
+    session_id: Option<String>,
+    usi: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::debug!("Command: play_session_move - session_id: {:?}, move: {}", session_id, usi);
+
+    let session_id = match state.sessions.resolve(session_id.as_deref()).await {
+        Ok(id) => id,
+        Err(e) => return Ok(CommandResponse::error(e.to_string())),
+    };
+    let (mut tracker, mut snapshot) = match session_tracker(&state, &session_id).await {
+        Ok(game) => game,
+        Err(e) => return Ok(CommandResponse::engine_error(e)),
+    };
+    let played = match tracker.play_usi(&usi) {
+        Ok(played) => played,
+        Err(e) => return Ok(CommandResponse::engine_error(e)),
+    };
+    snapshot.moves.push(usi);
+    let ply = snapshot.moves.len() as u32;
+    let player = played.metadata.player;
+    let deviation = opening_deviation(&state, &session_id, &snapshot, ply, player).await;
+
+    if let Some(session) = state.sessions.get_session(&session_id).await {
+        let times = state
+            .move_times
+            .read()
+            .await
+            .get(&session_id)
+            .cloned()
+            .unwrap_or_default();
+        state
+            .session_store
+            .write()
+            .await
+            .record(session, snapshot, &times);
+    }
+    let mode = state.blindfold.read().await.get(&session_id).copied().unwrap_or_default();
+    let locale = *state.locale.read().await;
+    let mut events =
+        session_events::move_events(ply, &played.metadata, &played.status, mode, locale);
+    if let Some(event) = deviation {
+        // Before the end of the game, which comes last
+        let at = events
+            .iter()
+            .position(|event| matches!(event, SessionEvent::GameEnd { .. }))
+            .unwrap_or(events.len());
+        events.insert(at, event);
+    }
+    session_events::emit(&app_handle, &session_id, &events);
+    Ok(CommandResponse::success_with_data(serde_json::json!({
+        "move": mode.filter_move(&played.metadata),
+        "position": mode.filter(&tracker.position_text()),
+        "status": played.status,
+    })))
+}
+
+/// The book's lines from the even-game start, built on first use
+fn book_repertoire() -> &'static Repertoire {
+    static BOOK: OnceLock<Repertoire> = OnceLock::new();
+    BOOK.get_or_init(|| match OpeningBook::embedded() {
+        Ok(mut book) => {
+            Repertoire::from_book(&mut book, Handicap::Even.sfen(), DEFAULT_REPERTOIRE_PLIES)
+        }
+        Err(e) => {
+            log::warn!("Failed to load the opening book for deviation checks: {:?}", e);
+            Repertoire::new()
+        }
+    })
+}
+
+/// The deviation event for ply `ply` of a session's game, if that move was the
+/// first to leave the session's repertoire, or the book in an even game
+/// without one
+async fn opening_deviation(
+    state: &AppState,
+    session_id: &str,
+    snapshot: &SessionSnapshot,
+    ply: u32,
+    player: Player,
+) -> Option<SessionEvent> {
+    let repertoires = state.repertoires.read().await;
+    let (repertoire, source) = match repertoires.get(session_id) {
+        Some(repertoire) => (repertoire, RepertoireSource::Repertoire),
+        None if snapshot.sfen == Handicap::Even.sfen() => {
+            (book_repertoire(), RepertoireSource::Book)
+        }
+        None => return None,
+    };
+    let deviation = repertoire.deviation(&snapshot.moves)?;
+    session_events::deviation_event(ply, player, &deviation, source)
+}
+
+/// A session's saved game replayed to its current position
+async fn session_tracker(
+    state: &AppState,
+    session_id: &str,
+) -> Result<(GameTracker, SessionSnapshot), ShogiError> {
+    let snapshot = state
+        .session_store
+        .read()
+        .await
+        .sessions
+        .get(session_id)
+        .map(|persisted| persisted.snapshot.clone())
+        .ok_or_else(|| ShogiError::resource(format!("No saved game for session {}", session_id)))?;
+    let mut tracker = GameTracker::from_sfen(&snapshot.sfen)?;
+    for usi in &snapshot.moves {
+        tracker.play_usi(usi)?;
+    }
+    Ok((tracker, snapshot))
+}
+
+/// Save engine options
+#[tauri::command]
+pub async fn save_engine_options(
+    engine_id: String,
+    options: std::collections::HashMap<String, String>,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::info!("Command: save_engine_options - engine_id: {}, options: {:?}", engine_id, options);
+
+    let mut storage = state.engine_storage.write().await;
+    
+    match storage.save_engine_options(&engine_id, options) {
+        Ok(_) => {
+            // Save to disk
+            if let Err(e) = storage.save().await {
+                log::error!("Failed to save engine storage: {}", e);
+                return Ok(CommandResponse::error(format!("Failed to save options: {}", e)));
+            }
+            
+            log::info!("Engine options saved successfully for engine: {}", engine_id);
+            Ok(CommandResponse::success())
+        }
+        Err(e) => {
+            log::error!("Failed to save engine options: {}", e);
+            Ok(CommandResponse::error(format!("Failed to save options: {}", e)))
+        }
+    }
+}
+
+/// Set an engine's resource limits; they apply from the next time it is spawned
+#[tauri::command]
+pub async fn set_engine_resource_limits(
+    engine_id: String,
+    limits: EngineResourceLimits,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::info!(
+        "Command: set_engine_resource_limits - engine_id: {}, limits: {:?}",
+        engine_id,
+        limits
+    );
+
+    let mut storage = state.engine_storage.write().await;
+    if let Err(e) = storage.set_resource_limits(&engine_id, limits) {
+        log::error!("Failed to set engine resource limits: {}", e);
+        return Ok(CommandResponse::error(format!("Failed to set resource limits: {}", e)));
+    }
+    if let Err(e) = storage.save().await {
+        log::error!("Failed to save engine storage: {}", e);
+        return Ok(CommandResponse::error(format!("Failed to save resource limits: {}", e)));
+    }
+    Ok(CommandResponse::success())
+}
+
+/// Get saved engine options
+#[tauri::command]
+pub async fn get_engine_options(
+    engine_id: String,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::info!("Command: get_engine_options - engine_id: {}", engine_id);
+
+    let storage = state.engine_storage.read().await;
+    
+    match storage.get_engine_options(&engine_id) {
+        Some(options) => {
+            log::info!("Retrieved {} saved options for engine: {}", options.len(), engine_id);
+            Ok(CommandResponse::success_with_data(serde_json::to_value(options).unwrap()))
+        }
+        None => {
+            log::info!("No saved options found for engine: {}", engine_id);
+            Ok(CommandResponse::success_with_data(serde_json::Value::Object(serde_json::Map::new())))
+        }
+    }
+}
+
+/// List the built-in and saved engine profiles
+#[tauri::command]
+pub async fn list_engine_profiles(state: State<'_, AppState>) -> Result<CommandResponse, String> {
+    let storage = state.engine_storage.read().await;
+    let profiles = storage.list_profiles();
+    Ok(CommandResponse::success_with_data(
+        serde_json::to_value(profiles).map_err(|e| e.to_string())?,
+    ))
+}
+
+/// Save a named engine profile
+#[tauri::command]
+pub async fn save_engine_profile(
+    profile: EngineProfile,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::info!("Command: save_engine_profile - name: {}", profile.name);
+
+    let mut storage = state.engine_storage.write().await;
+    let previous = storage.profiles.clone();
+
+    if let Err(e) = storage.save_profile(profile) {
+        return Ok(CommandResponse::error(format!("Failed to save profile: {}", e)));
+    }
+    if let Err(e) = storage.save().await {
+        log::error!("Failed to save engine storage: {}", e);
+        storage.profiles = previous;
+        return Ok(CommandResponse::error(format!("Failed to save profile: {}", e)));
+    }
+    Ok(CommandResponse::success())
+}
+
+/// Apply a profile to an engine's saved options. Either every option of the
+/// profile is stored or, if writing the storage fails, none is.
+#[tauri::command]
+pub async fn apply_engine_profile(
+    engine_id: String,
+    profile_name: String,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse, String> {
+    log::info!(
+        "Command: apply_engine_profile - engine_id: {}, profile: {}",
+        engine_id,
+        profile_name
+    );
+
+    let mut storage = state.engine_storage.write().await;
+    let previous = storage.get_engine(&engine_id).cloned();
+
+    let profile = match storage.apply_profile(&engine_id, &profile_name) {
+        Ok(profile) => profile,
+        Err(e) => {
+            log::error!("Failed to apply engine profile: {}", e);
+            return Ok(CommandResponse::error(format!("Failed to apply profile: {}", e)));
+        }
+    };
+    if let Err(e) = storage.save().await {
+        log::error!("Failed to save engine storage: {}", e);
+        if let (Some(previous), Some(engine)) = (previous, storage.get_engine_mut(&engine_id)) {
+            *engine = previous;
+        }
+        return Ok(CommandResponse::error(format!("Failed to apply profile: {}", e)));
+    }
+
+    log::info!("Applied profile '{}' to engine: {}", profile.name, engine_id);
+    Ok(CommandResponse::success_with_data(serde_json::json!({
+        "profile": profile,
+        "options": storage.get_engine_options(&engine_id),
+    })))
 }
 
 /// Clone an engine with a new display name
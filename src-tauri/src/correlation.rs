@@ -0,0 +1,83 @@
+//! Correlation IDs
+//!
+//! Several engines can think at once for different sessions, and their USI
+//! traffic, the backend's own log lines and the session events end up
+//! interleaved. Every move of a session gets an ID built from the session, the
+//! ply being decided and the number of takebacks so far, so anything done for
+//! that move can be picked out again: the engine console stamps each line with
+//! it, backend log lines carry it, session events include it, and the built-in
+//! engine receives it as a USI comment before the `position` command. Other
+//! engines are not sent the comment, as USI has no comment syntax and they may
+//! answer it with errors.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// Takebacks made in each session, the sequence number of its IDs
+fn sequences() -> &'static Mutex<HashMap<String, u32>> {
+    static SEQUENCES: OnceLock<Mutex<HashMap<String, u32>>> = OnceLock::new();
+    SEQUENCES.get_or_init(Default::default)
+}
+
+/// ID of the move at `ply` (1-based) of `session_id`: `<session>:<ply>.<sequence>`
+pub fn correlation_id(session_id: &str, ply: u32) -> String {
+    let sequences = sequences().lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let sequence = sequences.get(session_id).copied().unwrap_or(0);
+    let prefix: String = session_id.chars().take(8).collect();
+    format!("{}:{}.{}", prefix, ply, sequence)
+}
+
+/// Start a new sequence for `session_id` after a takeback, so the moves
+/// decided again get IDs of their own
+pub fn advance_sequence(session_id: &str) {
+    let mut sequences = sequences().lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    *sequences.entry(session_id.to_string()).or_default() += 1;
+}
+
+/// Forget the sequence of a closed session
+pub fn forget_session(session_id: &str) {
+    let mut sequences = sequences().lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    sequences.remove(session_id);
+}
+
+/// Ply an engine decides after a `position` command: the one after its moves
+pub fn ply_of_position(command: &str) -> Option<u32> {
+    let mut tokens = command.split_whitespace();
+    if tokens.next() != Some("position") {
+        return None;
+    }
+    let played = tokens.skip_while(|&token| token != "moves").skip(1).count();
+    Some(played as u32 + 1)
+}
+
+/// The USI comment carrying `id`, ignored by the built-in engine
+pub fn usi_comment(id: &str) -> String {
+    format!("# cid {}", id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_commands_map_to_the_ply_being_decided() {
+        assert_eq!(ply_of_position("position startpos"), Some(1));
+        assert_eq!(ply_of_position("position startpos moves 7g7f 3c3d"), Some(3));
+        assert_eq!(
+            ply_of_position("position sfen 4k4/9/9/9/9/9/9/9/4K4 b - 1 moves 5i5h"),
+            Some(2)
+        );
+        assert_eq!(ply_of_position("go btime 1000"), None);
+
+        let session_id = "3f2a9c1e-77aa-4c1b-9d0e-2f0b5c6d7e8f";
+        let id = correlation_id(session_id, 3);
+        assert_eq!(id, "3f2a9c1e:3.0");
+        assert_eq!(usi_comment(&id), "# cid 3f2a9c1e:3.0");
+
+        // A takeback gives the ply a new ID
+        advance_sequence(session_id);
+        assert_eq!(correlation_id(session_id, 3), "3f2a9c1e:3.1");
+        forget_session(session_id);
+        assert_eq!(correlation_id(session_id, 3), "3f2a9c1e:3.0");
+    }
+}
@@ -0,0 +1,405 @@
+//! Deep links
+//!
+//! `shogi://` links that open a position or a game in a new analysis session:
+//!
+//! - `shogi://sfen/<sfen>?moves=7g7f,3c3d` — a position, with moves played
+//!   from it. Spaces in the SFEN are written `%20` or `_`; `+` is a promotion.
+//! - `shogi://kif?url=<url>` — a KIF game fetched over HTTP(S). Links come
+//!   from outside the app, so other schemes (`file://` included) are refused,
+//!   and so are hosts on this machine or the local network, redirects included.
+//! - `shogi://kif?data=<kif>` — a percent-encoded KIF game.
+//!
+//! Everything is checked through the engine crate before a session is
+//! created: positions the validator rules out and illegal moves are rejected
+//! with the reason. The opened game reaches the frontend as a `deep-link::open`
+//! event; links that arrive before the window listens (the one the app was
+//! launched with) wait until it asks for them.
+
+use crate::game_session::SessionKind;
+use crate::state::AppState;
+use serde::Serialize;
+use shogi_engine::game_status::GameTracker;
+use shogi_engine::handicap::Handicap;
+use shogi_engine::kif_parser::KifGame;
+use shogi_engine::position_format::PositionText;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const DEEP_LINK_SCHEME: &str = "shogi";
+
+/// Largest KIF a link may fetch or carry inline
+const MAX_KIF_BYTES: usize = 1024 * 1024;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+/// Most redirects a KIF fetch follows
+const MAX_REDIRECTS: usize = 5;
+
+/// What a link asks to open
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeepLink {
+    Position { sfen: String, moves: Vec<String> },
+    KifUrl(String),
+    KifData(String),
+}
+
+impl DeepLink {
+    pub fn parse(url: &str) -> Result<Self, String> {
+        let rest = url
+            .trim()
+            .strip_prefix(DEEP_LINK_SCHEME)
+            .and_then(|rest| rest.strip_prefix("://"))
+            .ok_or_else(|| format!("Not a {}:// link", DEEP_LINK_SCHEME))?;
+        let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let param = |name: &str| -> Result<Option<String>, String> {
+            query
+                .split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| percent_decode(value))
+                .transpose()
+        };
+
+        let path = path.trim_end_matches('/');
+        if let Some(sfen) = path.strip_prefix("sfen/") {
+            let sfen = percent_decode(sfen)?.replace('_', " ");
+            let moves = param("moves")?
+                .map(|moves| {
+                    moves
+                        .split([',', ' '])
+                        .filter(|m| !m.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default();
+            return Ok(DeepLink::Position { sfen, moves });
+        }
+        match path {
+            "kif" => match (param("url")?, param("data")?) {
+                (Some(url), _) if url.starts_with("http://") || url.starts_with("https://") => {
+                    Ok(DeepLink::KifUrl(url))
+                }
+                (Some(url), _) => Err(format!("Unsupported KIF URL: {}", url)),
+                (None, Some(data)) if data.len() > MAX_KIF_BYTES => {
+                    Err(format!("Inline KIF is larger than {} bytes", MAX_KIF_BYTES))
+                }
+                (None, Some(data)) => Ok(DeepLink::KifData(data)),
+                (None, None) => Err("KIF link needs a url or data parameter".to_string()),
+            },
+            _ => Err(format!("Unknown link: {}", url)),
+        }
+    }
+}
+
+/// A game opened from a link, as the frontend receives it
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenedLink {
+    pub session_id: String,
+    pub url: String,
+    /// Canonical SFEN of the starting position
+    pub sfen: String,
+    /// Moves played from it in USI notation
+    pub moves: Vec<String>,
+    /// Position after the moves
+    pub current_sfen: String,
+    pub label: String,
+}
+
+/// Links opened before the frontend listened for them
+#[derive(Debug, Default)]
+pub struct DeepLinkInbox {
+    listening: bool,
+    pending: Vec<OpenedLink>,
+}
+
+impl DeepLinkInbox {
+    /// Keep `link` for later unless the frontend already listens; returns it
+    /// when it should be emitted now
+    pub fn deliver(&mut self, link: OpenedLink) -> Option<OpenedLink> {
+        if self.listening {
+            Some(link)
+        } else {
+            self.pending.push(link);
+            None
+        }
+    }
+
+    /// The links waiting so far; later ones are emitted as they arrive
+    pub fn take_pending(&mut self) -> Vec<OpenedLink> {
+        self.listening = true;
+        std::mem::take(&mut self.pending)
+    }
+}
+
+/// Open every link in `urls`, logging the ones that fail
+pub async fn handle_urls(app_handle: AppHandle, urls: Vec<String>) {
+    for url in urls {
+        match open(&app_handle, &url).await {
+            Ok(link) => log::info!("Opened {} in session {}", url, link.session_id),
+            Err(e) => {
+                log::warn!("Failed to open {}: {}", url, e);
+                let _ = app_handle.emit(
+                    "deep-link::error",
+                    serde_json::json!({"url": url, "error": e}),
+                );
+            }
+        }
+    }
+}
+
+/// Check the game a link names and open it in a new analysis session
+pub async fn open(app_handle: &AppHandle, url: &str) -> Result<OpenedLink, String> {
+    let (sfen, moves, label) = match DeepLink::parse(url)? {
+        DeepLink::Position { sfen, moves } => (sfen, moves, "Linked position".to_string()),
+        DeepLink::KifUrl(kif_url) => kif_game(&fetch_kif(&kif_url).await?)?,
+        DeepLink::KifData(data) => kif_game(&data)?,
+    };
+
+    let position = PositionText::parse(&sfen).map_err(|e| format!("Invalid position: {}", e))?;
+    if let Some(issue) = position.validate().into_iter().find(|issue| issue.is_error()) {
+        return Err(format!("Invalid position: {}", issue));
+    }
+    let sfen = position.to_sfen();
+    let mut tracker = GameTracker::from_sfen(&sfen)?;
+    for (ply, usi) in moves.iter().enumerate() {
+        tracker
+            .play_usi(usi)
+            .map_err(|e| format!("Move {} ({}): {}", ply + 1, usi, e))?;
+    }
+
+    let state = app_handle.state::<AppState>();
+    let session = state
+        .sessions
+        .create_session(SessionKind::Analysis, Some(label.clone()))
+        .await;
+    let link = OpenedLink {
+        session_id: session.id,
+        url: url.to_string(),
+        sfen,
+        moves,
+        current_sfen: tracker.position_text().to_sfen(),
+        label,
+    };
+    if let Some(link) = state.deep_links.write().await.deliver(link.clone()) {
+        let _ = app_handle.emit("deep-link::open", &link);
+    }
+    Ok(link)
+}
+
+/// Starting SFEN, main-line moves and a label of a KIF game
+fn kif_game(content: &str) -> Result<(String, Vec<String>, String), String> {
+    let game = KifGame::from_string(content).map_err(|e| format!("Invalid KIF: {}", e))?;
+    let sfen = match &game.initial_sfen {
+        Some(sfen) => sfen.clone(),
+        None => kif_handicap(game.metadata.game_type.as_deref())?.sfen().to_string(),
+    };
+    let moves = game
+        .moves
+        .iter()
+        .map_while(|m| m.usi_move.clone())
+        .collect();
+    let label = match (&game.metadata.player1_name, &game.metadata.player2_name) {
+        (Some(sente), Some(gote)) => format!("{} vs {}", sente, gote),
+        _ => "Linked game".to_string(),
+    };
+    Ok((sfen, moves, label))
+}
+
+/// The starting position a KIF `手合割` names
+fn kif_handicap(name: Option<&str>) -> Result<Handicap, String> {
+    Ok(match name.map(str::trim) {
+        None | Some("平手") => Handicap::Even,
+        Some("香落ち") => Handicap::Lance,
+        Some("角落ち") => Handicap::Bishop,
+        Some("飛車落ち") => Handicap::Rook,
+        Some("二枚落ち") => Handicap::TwoPiece,
+        Some("四枚落ち") => Handicap::FourPiece,
+        Some("六枚落ち") => Handicap::SixPiece,
+        Some(other) => return Err(format!("Unsupported handicap: {}", other)),
+    })
+}
+
+async fn fetch_kif(url: &str) -> Result<String, String> {
+    let mut url =
+        reqwest::Url::parse(url).map_err(|e| format!("Invalid KIF URL {}: {}", url, e))?;
+    // Redirects are followed here so every hop's host is checked
+    let mut redirects = 0;
+    let mut response = loop {
+        let response = public_client(&url)
+            .await?
+            .get(url.clone())
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+        if !response.status().is_redirection() {
+            break response
+                .error_for_status()
+                .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+        }
+        if redirects == MAX_REDIRECTS {
+            return Err(format!("Too many redirects fetching {}", url));
+        }
+        redirects += 1;
+        let location = response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .ok_or_else(|| format!("Redirect without a location from {}", url))?;
+        url = url
+            .join(location)
+            .map_err(|e| format!("Invalid redirect from {}: {}", url, e))?;
+    };
+    if response.content_length().is_some_and(|length| length > MAX_KIF_BYTES as u64) {
+        return Err(format!("KIF at {} is larger than {} bytes", url, MAX_KIF_BYTES));
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to fetch {}: {}", url, e))?
+    {
+        bytes.extend_from_slice(&chunk);
+        if bytes.len() > MAX_KIF_BYTES {
+            return Err(format!("KIF at {} is larger than {} bytes", url, MAX_KIF_BYTES));
+        }
+    }
+    decode_kif(bytes)
+}
+
+/// A client that reaches `url`'s host only at the public addresses it resolves
+/// to now, so a link can't point it at this machine or the local network
+async fn public_client(url: &reqwest::Url) -> Result<reqwest::Client, String> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!("Unsupported KIF URL: {}", url));
+    }
+    let host = url.host_str().ok_or_else(|| format!("KIF URL has no host: {}", url))?;
+    let port = url.port_or_known_default().unwrap_or(80);
+    let literal = host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>();
+    let addrs: Vec<SocketAddr> = match literal {
+        Ok(ip) => vec![SocketAddr::new(ip, port)],
+        Err(_) => tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|e| format!("Failed to resolve {}: {}", host, e))?
+            .collect(),
+    };
+    if addrs.is_empty() || addrs.iter().any(|addr| !is_public(addr.ip())) {
+        return Err(format!("Refusing to fetch a KIF from a local address: {}", url));
+    }
+
+    let mut builder = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none());
+    if literal.is_err() {
+        builder = builder.resolve_to_addrs(host, &addrs);
+    }
+    builder.build().map_err(|e| e.to_string())
+}
+
+/// Whether `ip` is reachable on the internet rather than loopback, private,
+/// link-local or another special-purpose address
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || first == 0
+                // Carrier-grade NAT, 100.64.0.0/10
+                || (first == 100 && second & 0xc0 == 64))
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public(IpAddr::V4(ip)),
+            None => {
+                let first = ip.segments()[0];
+                // Unique local fc00::/7 and link-local fe80::/10
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || first & 0xfe00 == 0xfc00
+                    || first & 0xffc0 == 0xfe80)
+            }
+        },
+    }
+}
+
+fn decode_kif(bytes: Vec<u8>) -> Result<String, String> {
+    String::from_utf8(bytes).map_err(|_| "KIF is not UTF-8 (save it as .kifu)".to_string())
+}
+
+/// Decode `%XX` escapes; `+` stays as it is, since SFEN and USI use it
+fn percent_decode(text: &str) -> Result<String, String> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let byte = text
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| format!("Bad escape in link: {}", text))?;
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| format!("Link is not UTF-8: {}", text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_links() {
+        assert_eq!(
+            DeepLink::parse("shogi://sfen/4k4/9/4P4/9/9/9/9/9/4K4%20b_G%201?moves=G*5b").unwrap(),
+            DeepLink::Position {
+                sfen: "4k4/9/4P4/9/9/9/9/9/4K4 b G 1".to_string(),
+                moves: vec!["G*5b".to_string()],
+            }
+        );
+        assert_eq!(
+            DeepLink::parse("shogi://kif?url=https%3A%2F%2Fexample.com%2Fgame.kif").unwrap(),
+            DeepLink::KifUrl("https://example.com/game.kif".to_string())
+        );
+        assert!(DeepLink::parse("shogi://kif").is_err());
+        assert!(DeepLink::parse("shogi://kif?url=file%3A%2F%2F%2Fetc%2Fpasswd").is_err());
+        let oversized = format!("shogi://kif?data={}", "a".repeat(MAX_KIF_BYTES + 1));
+        assert!(DeepLink::parse(&oversized).is_err());
+        assert!(DeepLink::parse("https://example.com").is_err());
+        assert!(DeepLink::parse("shogi://sfen/%zz").is_err());
+    }
+
+    #[test]
+    fn test_local_addresses_are_not_public() {
+        for local in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.10",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:192.168.1.1",
+        ] {
+            assert!(!is_public(local.parse().unwrap()), "{}", local);
+        }
+        for public in ["93.184.216.34", "2606:2800:220:1:248:1893:25c8:1946"] {
+            assert!(is_public(public.parse().unwrap()), "{}", public);
+        }
+    }
+
+    #[test]
+    fn test_kif_handicap() {
+        assert_eq!(kif_handicap(None).unwrap(), Handicap::Even);
+        assert_eq!(kif_handicap(Some("角落ち")).unwrap(), Handicap::Bishop);
+        assert!(kif_handicap(Some("八枚落ち")).is_err());
+    }
+}
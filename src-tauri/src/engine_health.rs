@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use sysinfo::{Pid, ProcessExt, System, SystemExt};
+
+/// Thresholds used by the background health monitor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineHealthConfig {
+    /// How often idle engines are probed with `isready`
+    pub probe_interval_ms: u64,
+    /// How long an engine may take to answer `readyok` before it is considered unresponsive
+    pub unresponsive_timeout_ms: u64,
+    /// Latency above which a probe is reported as slow
+    pub slow_latency_ms: u64,
+    /// Resident memory above which the engine is reported as bloated
+    pub memory_warning_mb: u64,
+}
+
+impl Default for EngineHealthConfig {
+    fn default() -> Self {
+        Self {
+            probe_interval_ms: 15_000,
+            unresponsive_timeout_ms: 5_000,
+            slow_latency_ms: 1_000,
+            memory_warning_mb: 2_048,
+        }
+    }
+}
+
+impl EngineHealthConfig {
+    pub fn probe_interval(&self) -> Duration {
+        Duration::from_millis(self.probe_interval_ms)
+    }
+
+    pub fn unresponsive_timeout(&self) -> Duration {
+        Duration::from_millis(self.unresponsive_timeout_ms)
+    }
+}
+
+/// Overall health classification of an engine process
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthState {
+    Healthy,
+    Slow,
+    Unresponsive,
+    Bloated,
+    Unknown,
+}
+
+/// Latest health sample for a single engine, emitted to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineHealthReport {
+    pub engine_id: String,
+    pub state: HealthState,
+    pub last_latency_ms: Option<u64>,
+    pub memory_bytes: Option<u64>,
+    pub probes_sent: u64,
+    pub probes_answered: u64,
+    pub checked_at: String,
+}
+
+impl EngineHealthReport {
+    pub fn new(engine_id: String) -> Self {
+        Self {
+            engine_id,
+            state: HealthState::Unknown,
+            last_latency_ms: None,
+            memory_bytes: None,
+            probes_sent: 0,
+            probes_answered: 0,
+            checked_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// Classify an engine from its most recent probe and memory sample.
+/// An outstanding probe that exceeded the timeout takes priority over everything else.
+pub fn classify(
+    config: &EngineHealthConfig,
+    outstanding_probe: Option<Duration>,
+    last_latency_ms: Option<u64>,
+    memory_bytes: Option<u64>,
+) -> HealthState {
+    if let Some(waiting) = outstanding_probe {
+        if waiting >= config.unresponsive_timeout() {
+            return HealthState::Unresponsive;
+        }
+    }
+
+    if let Some(bytes) = memory_bytes {
+        if bytes > config.memory_warning_mb * 1024 * 1024 {
+            return HealthState::Bloated;
+        }
+    }
+
+    match last_latency_ms {
+        Some(latency) if latency > config.slow_latency_ms => HealthState::Slow,
+        Some(_) => HealthState::Healthy,
+        None => HealthState::Unknown,
+    }
+}
+
+/// Sample the resident memory of a child process, if the platform exposes it
+pub fn sample_process_memory(system: &mut System, pid: u32) -> Option<u64> {
+    let pid = Pid::from(pid as usize);
+    if !system.refresh_process(pid) {
+        return None;
+    }
+    system.process(pid).map(|process| process.memory())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_unresponsive_takes_priority() {
+        let config = EngineHealthConfig::default();
+        let state = classify(
+            &config,
+            Some(Duration::from_millis(config.unresponsive_timeout_ms + 1)),
+            Some(10),
+            Some(u64::MAX),
+        );
+        assert_eq!(state, HealthState::Unresponsive);
+    }
+
+    #[test]
+    fn test_classify_bloated_and_slow() {
+        let config = EngineHealthConfig::default();
+        let over_limit = (config.memory_warning_mb + 1) * 1024 * 1024;
+        assert_eq!(classify(&config, None, Some(5), Some(over_limit)), HealthState::Bloated);
+        assert_eq!(
+            classify(&config, None, Some(config.slow_latency_ms + 1), Some(1024)),
+            HealthState::Slow
+        );
+        assert_eq!(classify(&config, None, Some(5), Some(1024)), HealthState::Healthy);
+        assert_eq!(classify(&config, None, None, None), HealthState::Unknown);
+    }
+}
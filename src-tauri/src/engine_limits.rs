@@ -0,0 +1,227 @@
+//! Resource limits for engine processes
+//!
+//! An external engine left to itself takes every core it is told to, as much
+//! hash as it asks for and the same scheduling priority as the app, and a
+//! runaway one can leave the desktop unresponsive for the rest of a match. Each
+//! engine's `EngineConfig` carries limits applied in three places: thread-count
+//! and hash options are clamped as they are sent, the process is reniced after
+//! spawning, and a memory guard kills the process once its resident memory
+//! passes the cap. Priority is set with `renice` on Unix and PowerShell on
+//! Windows; the guard works wherever sysinfo can read a process's memory. The
+//! in-process engine shares the app's process and is left alone.
+
+use crate::engine_health;
+use crate::engine_validator::EngineOption;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::time::Duration;
+use sysinfo::{Pid, ProcessExt, System, SystemExt};
+use tokio::process::Command;
+
+/// Option names engines use for their search thread count
+const THREAD_OPTIONS: [&str; 3] = ["Threads", "USI_Threads", "NumberOfThreads"];
+
+/// Option names engines use for their hash size in MiB
+const HASH_OPTIONS: [&str; 2] = ["USI_Hash", "Hash"];
+
+/// The hash may take at most this fraction (1/n) of the memory cap, leaving the
+/// rest for the engine's other tables
+const HASH_SHARE_OF_MEMORY: u64 = 2;
+
+/// How often the memory guard samples the engine
+const MEMORY_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Scheduling priority of an engine process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessPriority {
+    #[default]
+    Normal,
+    BelowNormal,
+    /// Runs only when nothing else wants the CPU
+    Idle,
+}
+
+impl ProcessPriority {
+    #[cfg_attr(not(unix), allow(dead_code))]
+    fn niceness(self) -> i32 {
+        match self {
+            ProcessPriority::Normal => 0,
+            ProcessPriority::BelowNormal => 10,
+            ProcessPriority::Idle => 19,
+        }
+    }
+
+    /// Name of the Windows priority class
+    #[cfg_attr(unix, allow(dead_code))]
+    fn priority_class(self) -> &'static str {
+        match self {
+            ProcessPriority::Normal => "Normal",
+            ProcessPriority::BelowNormal => "BelowNormal",
+            ProcessPriority::Idle => "Idle",
+        }
+    }
+}
+
+/// Per-engine resource limits; the default imposes none
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EngineResourceLimits {
+    /// Most search threads the engine may be given
+    pub max_threads: Option<u32>,
+    pub priority: ProcessPriority,
+    /// Resident memory in MiB past which the engine is killed. The hash option
+    /// is also held to half of it.
+    pub memory_limit_mb: Option<u64>,
+}
+
+impl EngineResourceLimits {
+    fn cap(&self, option_name: &str) -> Option<u64> {
+        if THREAD_OPTIONS.contains(&option_name) {
+            self.max_threads.map(u64::from)
+        } else if HASH_OPTIONS.contains(&option_name) {
+            self.memory_limit_mb.map(|mb| mb / HASH_SHARE_OF_MEMORY)
+        } else {
+            None
+        }
+    }
+
+    /// `value` for option `option_name`, lowered to the limit when it is a
+    /// thread count or hash size above it
+    pub fn clamp_option(&self, option_name: &str, value: &str) -> String {
+        match (self.cap(option_name), value.trim().parse::<u64>()) {
+            (Some(cap), Ok(requested)) if requested > cap => cap.max(1).to_string(),
+            _ => value.to_string(),
+        }
+    }
+
+    /// The options to send an engine declaring `declared`: `options` clamped,
+    /// plus any limited option `options` leaves at a default above the limit
+    pub fn limit_options(
+        &self,
+        options: &HashMap<String, String>,
+        declared: &[EngineOption],
+    ) -> Vec<(String, String)> {
+        let mut limited: Vec<(String, String)> = options
+            .iter()
+            .map(|(name, value)| (name.clone(), self.clamp_option(name, value)))
+            .collect();
+        for option in declared {
+            let Some(default) = option.default.as_deref() else {
+                continue;
+            };
+            let clamped = self.clamp_option(&option.name, default);
+            if !options.contains_key(&option.name) && clamped != default {
+                limited.push((option.name.clone(), clamped));
+            }
+        }
+        limited
+    }
+
+    /// Whether `memory_bytes` is over the memory cap
+    pub fn exceeds_memory(&self, memory_bytes: u64) -> bool {
+        self.memory_limit_mb.is_some_and(|mb| memory_bytes > mb * 1024 * 1024)
+    }
+}
+
+/// Set the scheduling priority of process `pid`
+pub async fn apply_priority(pid: u32, priority: ProcessPriority) -> Result<()> {
+    if priority == ProcessPriority::Normal {
+        return Ok(());
+    }
+    let mut command = priority_command(pid, priority);
+    let status = command
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await
+        .map_err(|e| anyhow!("Failed to change engine priority: {}", e))?;
+    if !status.success() {
+        return Err(anyhow!("Failed to change engine priority: {}", status));
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn priority_command(pid: u32, priority: ProcessPriority) -> Command {
+    let mut command = Command::new("renice");
+    command.args(["-n", &priority.niceness().to_string(), "-p", &pid.to_string()]);
+    command
+}
+
+#[cfg(not(unix))]
+fn priority_command(pid: u32, priority: ProcessPriority) -> Command {
+    let mut command = Command::new("powershell");
+    command.args([
+        "-NoProfile",
+        "-Command",
+        &format!("(Get-Process -Id {}).PriorityClass = '{}'", pid, priority.priority_class()),
+    ]);
+    command
+}
+
+/// Watch the resident memory of process `pid` and kill it once it passes
+/// `limits`. Resolves with the memory that tripped the guard, or `None` when
+/// the process exits (or its memory cannot be read) first.
+pub async fn guard_memory(pid: u32, limits: EngineResourceLimits) -> Option<u64> {
+    limits.memory_limit_mb?;
+    let mut system = System::new();
+    loop {
+        tokio::time::sleep(MEMORY_POLL_INTERVAL).await;
+        let memory_bytes = engine_health::sample_process_memory(&mut system, pid)?;
+        if limits.exceeds_memory(memory_bytes) {
+            if let Some(process) = system.process(Pid::from(pid as usize)) {
+                process.kill();
+            }
+            return Some(memory_bytes);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spin(name: &str, default: &str) -> EngineOption {
+        EngineOption {
+            name: name.to_string(),
+            option_type: "spin".to_string(),
+            default: Some(default.to_string()),
+            min: None,
+            max: None,
+            var: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_limit_options_clamps_threads_and_hash() {
+        let limits = EngineResourceLimits {
+            max_threads: Some(2),
+            memory_limit_mb: Some(1024),
+            ..EngineResourceLimits::default()
+        };
+        let options = HashMap::from([
+            ("Threads".to_string(), "8".to_string()),
+            ("MultiPV".to_string(), "3".to_string()),
+        ]);
+        let declared = [spin("Threads", "4"), spin("USI_Hash", "4096"), spin("Contempt", "50")];
+
+        let mut limited = limits.limit_options(&options, &declared);
+        limited.sort();
+        assert_eq!(
+            limited,
+            vec![
+                ("MultiPV".to_string(), "3".to_string()),
+                ("Threads".to_string(), "2".to_string()),
+                ("USI_Hash".to_string(), "512".to_string()),
+            ]
+        );
+        assert!(EngineResourceLimits::default()
+            .limit_options(&HashMap::new(), &declared)
+            .is_empty());
+        assert!(limits.exceeds_memory(1025 * 1024 * 1024));
+        assert!(!EngineResourceLimits::default().exceeds_memory(u64::MAX));
+    }
+}
@@ -0,0 +1,222 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Lines kept per engine before the oldest are dropped
+pub const DEFAULT_LOG_CAPACITY: usize = 5_000;
+
+/// Which way a logged line went
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogDirection {
+    /// Command written to the engine
+    Sent,
+    /// Line the engine wrote to stdout
+    Received,
+    /// Line the engine wrote to stderr
+    Stderr,
+}
+
+impl LogDirection {
+    fn marker(self) -> &'static str {
+        match self {
+            LogDirection::Sent => ">",
+            LogDirection::Received => "<",
+            LogDirection::Stderr => "!",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LogEntry {
+    /// Position in the engine's log, counting from 0 and never reused, so the
+    /// console can ask for what it has not seen yet
+    pub seq: u64,
+    /// RFC 3339 with milliseconds
+    pub timestamp: String,
+    pub direction: LogDirection,
+    pub line: String,
+    /// Move the line belongs to, see `correlation`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+}
+
+/// Rolling log of the USI traffic of one engine instance
+#[derive(Debug)]
+pub struct EngineLog {
+    pub engine_id: String,
+    pub session_id: String,
+    capacity: usize,
+    next_seq: u64,
+    entries: VecDeque<LogEntry>,
+    /// Stamped on every line sent until the next move's ID replaces it
+    correlation_id: Option<String>,
+    /// IDs of the searches started with `go` that have not yet answered with
+    /// `bestmove`, oldest first. Received lines belong to the oldest, so a
+    /// stopped search's late `bestmove` keeps its own move's ID.
+    pending_searches: VecDeque<Option<String>>,
+}
+
+/// Log shared between an engine instance and its reader tasks
+pub type SharedEngineLog = Arc<Mutex<EngineLog>>;
+
+impl EngineLog {
+    pub fn new(engine_id: String, session_id: String, capacity: usize) -> Self {
+        Self {
+            engine_id,
+            session_id,
+            capacity: capacity.max(1),
+            next_seq: 0,
+            entries: VecDeque::new(),
+            correlation_id: None,
+            pending_searches: VecDeque::new(),
+        }
+    }
+
+    pub fn shared(engine_id: String, session_id: String) -> SharedEngineLog {
+        Arc::new(Mutex::new(Self::new(engine_id, session_id, DEFAULT_LOG_CAPACITY)))
+    }
+
+    pub fn record(&mut self, direction: LogDirection, line: &str) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(LogEntry {
+            seq: self.next_seq,
+            timestamp: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            direction,
+            line: line.to_string(),
+            correlation_id: match direction {
+                LogDirection::Sent => self.correlation_id.clone(),
+                _ => self.received_correlation_id().map(str::to_string),
+            },
+        });
+        self.next_seq += 1;
+    }
+
+    /// Entries with `seq` at or after `since`; all kept entries when `None`
+    pub fn entries_since(&self, since: Option<u64>) -> Vec<LogEntry> {
+        let since = since.unwrap_or(0);
+        self.entries.iter().filter(|entry| entry.seq >= since).cloned().collect()
+    }
+
+    pub fn set_correlation_id(&mut self, correlation_id: Option<String>) {
+        self.correlation_id = correlation_id;
+    }
+
+    /// ID of the move the engine's output belongs to: the oldest unanswered
+    /// search's, or the last move's when no search is running
+    pub fn received_correlation_id(&self) -> Option<&str> {
+        match self.pending_searches.front() {
+            Some(id) => id.as_deref(),
+            None => self.correlation_id.as_deref(),
+        }
+    }
+
+    /// Note a search started for the current move
+    pub fn start_search(&mut self) {
+        self.pending_searches.push_back(self.correlation_id.clone());
+    }
+
+    /// Note the oldest search answered
+    pub fn finish_search(&mut self) {
+        self.pending_searches.pop_front();
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Plain-text form for bug reports, one line per entry:
+    /// `<timestamp> [<correlation id>] <direction> <line>` with `>` sent,
+    /// `<` received, `!` stderr; lines before the first move have no ID
+    pub fn export_text(&self) -> String {
+        let mut text = format!("# engine {} (session {})\n", self.engine_id, self.session_id);
+        for entry in &self.entries {
+            let correlation = entry
+                .correlation_id
+                .as_ref()
+                .map(|id| format!(" [{}]", id))
+                .unwrap_or_default();
+            text.push_str(&format!(
+                "{}{} {} {}\n",
+                entry.timestamp,
+                correlation,
+                entry.direction.marker(),
+                entry.line
+            ));
+        }
+        text
+    }
+}
+
+/// Record a line, tolerating a poisoned lock: losing the log is better than
+/// losing the engine
+pub fn record(log: &SharedEngineLog, direction: LogDirection, line: &str) {
+    match log.lock() {
+        Ok(mut log) => log.record(direction, line),
+        Err(poisoned) => poisoned.into_inner().record(direction, line),
+    }
+}
+
+/// Stamp the lines recorded from now on with `correlation_id`
+pub fn set_correlation_id(log: &SharedEngineLog, correlation_id: Option<String>) {
+    match log.lock() {
+        Ok(mut log) => log.set_correlation_id(correlation_id),
+        Err(poisoned) => poisoned.into_inner().set_correlation_id(correlation_id),
+    }
+}
+
+/// Run `f` on the log, tolerating a poisoned lock
+pub fn with_log<T>(log: &SharedEngineLog, f: impl FnOnce(&mut EngineLog) -> T) -> T {
+    match log.lock() {
+        Ok(mut log) => f(&mut log),
+        Err(poisoned) => f(&mut poisoned.into_inner()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rolling_log_and_export() {
+        let mut log = EngineLog::new("engine-1".to_string(), "default".to_string(), 3);
+        log.record(LogDirection::Sent, "usi");
+        log.record(LogDirection::Received, "id name Test");
+        log.record(LogDirection::Received, "usiok");
+        log.record(LogDirection::Sent, "isready");
+
+        // The oldest line made room; sequence numbers keep counting
+        let entries = log.entries_since(None);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].line, "id name Test");
+        assert_eq!(entries[2].seq, 3);
+        assert_eq!(log.entries_since(Some(3)).len(), 1);
+
+        let text = log.export_text();
+        assert!(text.starts_with("# engine engine-1 (session default)\n"));
+        assert!(text.contains(" < usiok\n"));
+        assert!(text.ends_with(" > isready\n"));
+
+        log.set_correlation_id(Some("3f2a9c1e:1.0".to_string()));
+        log.record(LogDirection::Sent, "position startpos");
+        let last = log.entries_since(Some(4));
+        assert_eq!(last[0].correlation_id.as_deref(), Some("3f2a9c1e:1.0"));
+        assert!(log.export_text().ends_with(" [3f2a9c1e:1.0] > position startpos\n"));
+
+        // A stopped search's late bestmove keeps its own move's ID
+        log.start_search();
+        log.set_correlation_id(Some("3f2a9c1e:2.0".to_string()));
+        log.record(LogDirection::Sent, "position startpos moves 7g7f");
+        log.record(LogDirection::Received, "bestmove 3c3d");
+        log.finish_search();
+        log.record(LogDirection::Received, "readyok");
+        let ids: Vec<_> = log
+            .entries_since(Some(6))
+            .into_iter()
+            .map(|entry| entry.correlation_id.unwrap())
+            .collect();
+        assert_eq!(ids, ["3f2a9c1e:2.0", "3f2a9c1e:1.0", "3f2a9c1e:2.0"]);
+    }
+}
@@ -1,14 +1,44 @@
+use crate::correlation;
+use crate::engine_health::{self, EngineHealthConfig, EngineHealthReport, HealthState};
+use crate::engine_limits::{self, EngineResourceLimits};
+use crate::engine_log::{self, EngineLog, LogDirection, LogEntry, SharedEngineLog};
+use crate::engine_protocol::EngineProtocol;
+use crate::engine_validator::EngineCapabilities;
+use crate::inprocess_engine::{self, InProcessEngine};
+use crate::usi_info::UsiInfo;
+use shogi_engine::search::perspective::EvaluationPerspective;
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::process::Stdio;
 use std::sync::Arc;
 use std::time::Duration;
 use tauri::{AppHandle, Emitter};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, ChildStdin, ChildStdout, Command};
-use tokio::sync::{mpsc, Mutex, RwLock};
-use tokio::time::timeout;
+use tokio::sync::{mpsc, oneshot, Mutex, RwLock};
+use tokio::time::{timeout, Instant};
+
+/// How long an engine gets to exit after `quit` before it is killed. Engines
+/// write learning data and hash dumps on the way out; killing them mid-write
+/// corrupts those files.
+pub const QUIT_GRACE_PERIOD: Duration = Duration::from_secs(3);
+
+/// Wait up to `grace` for an engine process that was sent `quit` to exit, and
+/// kill it only if it does not
+pub async fn wait_or_kill(process: &mut Child, label: &str, grace: Duration) {
+    match timeout(grace, process.wait()).await {
+        Ok(Ok(status)) => log::info!("Engine {} exited: {}", label, status),
+        Ok(Err(e)) => {
+            log::warn!("Failed to wait for engine {}: {}; killing it", label, e);
+            let _ = process.kill().await;
+        }
+        Err(_) => {
+            log::warn!("Engine {} did not exit within {:?} of quit; killing it", label, grace);
+            let _ = process.kill().await;
+        }
+    }
+}
 
 /// Represents the status of a USI engine
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -29,43 +59,177 @@ pub struct EngineInstance {
     pub name: String,
     #[allow(dead_code)]
     pub path: String,
+    /// Game session this engine instance belongs to
+    pub session_id: String,
     pub status: EngineStatus,
     process: Option<Child>,
     stdin: Option<ChildStdin>,
+    /// Set instead of `process`/`stdin` for the in-process built-in engine
+    in_process: Option<InProcessEngine>,
     #[allow(dead_code)]
     command_tx: mpsc::Sender<String>,
     stop_tx: mpsc::Sender<()>,
+    /// Unanswered `isready` commands, oldest first: the time a health probe was
+    /// sent, or `None` for one from the frontend. Engines answer in order, so
+    /// each `readyok` belongs to the front entry.
+    unanswered_isready: VecDeque<Option<Instant>>,
+    /// Last time a command was sent to the engine
+    last_activity: Instant,
+    /// Waiting callers of outstanding JSON queries (`stats`, `hints`), keyed by the
+    /// tag that follows `info string` in the reply
+    pending_replies: HashMap<String, oneshot::Sender<String>>,
+    /// Capabilities recorded when the engine was validated, if known
+    capabilities: Option<EngineCapabilities>,
+    /// Dialect the engine speaks; commands are translated from USI into it
+    protocol: EngineProtocol,
+    /// Perspective of the engine's scores, from the last `ScorePerspective`
+    /// option sent to an engine that declares it; USI engines report for the
+    /// side to move otherwise
+    score_perspective: EvaluationPerspective,
+    /// Everything sent to and received from the engine, for the engine console
+    log: SharedEngineLog,
+    /// Set when a search was stopped because its position was taken back, so
+    /// its `bestmove` is dropped instead of reaching the frontend
+    discard_bestmove: bool,
+    /// Move the engine is working on, set by each `position` command
+    correlation_id: Option<String>,
 }
 
 impl EngineInstance {
     /// Create a new engine instance (doesn't start the process yet)
-    pub fn new(id: String, name: String, path: String) -> Self {
+    pub fn new(id: String, name: String, path: String, session_id: String) -> Self {
         let (command_tx, _command_rx) = mpsc::channel(100);
         let (stop_tx, _stop_rx) = mpsc::channel(1);
-        
+        let log = EngineLog::shared(id.clone(), session_id.clone());
+
         Self {
             id,
             name,
             path,
+            session_id,
             status: EngineStatus::Stopped,
             process: None,
             stdin: None,
+            in_process: None,
             command_tx,
             stop_tx,
+            unanswered_isready: VecDeque::new(),
+            last_activity: Instant::now(),
+            pending_replies: HashMap::new(),
+            capabilities: None,
+            protocol: EngineProtocol::default(),
+            score_perspective: EvaluationPerspective::default(),
+            log,
+            discard_bestmove: false,
+            correlation_id: None,
         }
     }
 
     /// Send a USI command to the engine
     pub async fn send_command(&mut self, command: &str) -> Result<()> {
-        if let Some(stdin) = &mut self.stdin {
-            stdin.write_all(command.as_bytes()).await?;
-            stdin.write_all(b"\n").await?;
-            stdin.flush().await?;
-            log::debug!("Sent command to engine {}: {}", self.id, command);
-            Ok(())
-        } else {
-            Err(anyhow!("Engine stdin not available"))
+        self.write_command(command).await?;
+        if command.trim() == "isready" {
+            self.unanswered_isready.push_back(None);
+        }
+        Ok(())
+    }
+
+    /// Send an `isready` health probe; its `readyok` is not forwarded to the frontend
+    pub async fn send_probe(&mut self) -> Result<()> {
+        self.write_command("isready").await?;
+        self.unanswered_isready.push_back(Some(Instant::now()));
+        Ok(())
+    }
+
+    /// Time at which the outstanding health probe was sent, if any
+    pub fn pending_probe(&self) -> Option<Instant> {
+        self.unanswered_isready.iter().flatten().next().copied()
+    }
+
+    /// Take the `isready` a `readyok` answers; `Some` with the send time when it
+    /// was a health probe
+    fn answer_isready(&mut self) -> Option<Instant> {
+        self.unanswered_isready.pop_front().flatten()
+    }
+
+    async fn write_command(&mut self, command: &str) -> Result<()> {
+        self.check_supported(command)?;
+
+        if let Some(ply) = correlation::ply_of_position(command) {
+            let id = correlation::correlation_id(&self.session_id, ply);
+            engine_log::set_correlation_id(&self.log, Some(id.clone()));
+            if let Some(engine) = &self.in_process {
+                engine.send(&correlation::usi_comment(&id))?;
+            }
+            self.correlation_id = Some(id);
         }
+
+        let lines = self.protocol.translate_command(command)?;
+        for line in &lines {
+            if let Some(stdin) = &mut self.stdin {
+                stdin.write_all(line.as_bytes()).await?;
+                stdin.write_all(b"\n").await?;
+                stdin.flush().await?;
+            } else if let Some(engine) = &self.in_process {
+                engine.send(line)?;
+            } else {
+                return Err(anyhow!("Engine stdin not available"));
+            }
+
+            log::debug!(
+                "[{}] Sent command to engine {}: {}",
+                self.correlation_id.as_deref().unwrap_or("-"),
+                self.id,
+                line
+            );
+            engine_log::record(&self.log, LogDirection::Sent, line);
+        }
+        self.last_activity = Instant::now();
+        if command.starts_with("go") {
+            engine_log::with_log(&self.log, EngineLog::start_search);
+            self.status = EngineStatus::Thinking;
+        } else if let Some(value) = command.strip_prefix("setoption name ScorePerspective value ") {
+            let declared = self.capabilities.as_ref().is_some_and(|c| c.score_perspective);
+            if let Some(perspective) = EvaluationPerspective::parse(value).filter(|_| declared) {
+                self.score_perspective = perspective;
+            }
+        }
+        Ok(())
+    }
+
+    /// Correlation ID of the move the engine was last given
+    pub fn correlation_id(&self) -> Option<&str> {
+        self.correlation_id.as_deref()
+    }
+
+    /// Perspective of the scores in this engine's `info` lines
+    pub fn score_perspective(&self) -> EvaluationPerspective {
+        self.score_perspective
+    }
+
+    /// Refuse `go` variants the engine is known not to support
+    fn check_supported(&self, command: &str) -> Result<()> {
+        let Some(capabilities) = &self.capabilities else {
+            return Ok(());
+        };
+        let mut tokens = command.split_whitespace();
+        if tokens.next() != Some("go") {
+            return Ok(());
+        }
+        for token in tokens {
+            if token == "ponder" && !capabilities.ponder {
+                return Err(anyhow!("Engine {} does not support pondering", self.id));
+            }
+            if token == "mate" && !capabilities.mate_search {
+                return Err(anyhow!("Engine {} does not support mate search", self.id));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether this instance runs inside the app rather than as a child process
+    pub fn is_in_process(&self) -> bool {
+        self.in_process.is_some()
     }
 
     /// Stop the engine process
@@ -80,15 +244,15 @@ impl EngineInstance {
         // Signal the output reader task to stop
         let _ = self.stop_tx.send(()).await;
 
-        // Kill the process if it doesn't stop gracefully
+        // Kill the process only if it doesn't exit by itself in time
         if let Some(process) = &mut self.process {
-            tokio::time::sleep(Duration::from_millis(500)).await;
-            let _ = process.kill().await;
+            wait_or_kill(process, &self.id, QUIT_GRACE_PERIOD).await;
         }
 
         self.status = EngineStatus::Stopped;
         self.process = None;
         self.stdin = None;
+        self.in_process = None;
 
         Ok(())
     }
@@ -97,6 +261,11 @@ impl EngineInstance {
 /// Manages all USI engine instances
 pub struct EngineManager {
     engines: Arc<RwLock<HashMap<String, Arc<Mutex<EngineInstance>>>>>,
+    health: Arc<RwLock<HashMap<String, EngineHealthReport>>>,
+    health_config: EngineHealthConfig,
+    /// Traffic logs by engine id; kept after an engine stops so its log can
+    /// still be read and exported
+    logs: Arc<RwLock<HashMap<String, SharedEngineLog>>>,
     app_handle: AppHandle,
 }
 
@@ -104,22 +273,40 @@ impl EngineManager {
     pub fn new(app_handle: AppHandle) -> Self {
         Self {
             engines: Arc::new(RwLock::new(HashMap::new())),
+            health: Arc::new(RwLock::new(HashMap::new())),
+            health_config: EngineHealthConfig::default(),
+            logs: Arc::new(RwLock::new(HashMap::new())),
             app_handle,
         }
     }
 
-    /// Spawn a new engine process
+    /// Spawn a new engine process speaking `protocol`
     pub async fn spawn_engine(
         &self,
         id: String,
         name: String,
         path: String,
+        session_id: String,
+        protocol: EngineProtocol,
+        limits: EngineResourceLimits,
     ) -> Result<String> {
-        log::info!("Spawning engine: {} at path: {}", name, path);
+        log::info!(
+            "Spawning engine: {} at path: {} (session: {}, protocol: {:?})",
+            name,
+            path,
+            session_id,
+            protocol
+        );
+
+        if inprocess_engine::is_in_process(&path) {
+            return self.spawn_in_process_engine(id, name, session_id).await;
+        }
 
         // Create engine instance
-        let mut engine = EngineInstance::new(id.clone(), name.clone(), path.clone());
+        let mut engine =
+            EngineInstance::new(id.clone(), name.clone(), path.clone(), session_id.clone());
         engine.status = EngineStatus::Starting;
+        engine.protocol = protocol;
 
         // Determine working directory - use the engine's directory
         // This is critical for engines like Apery that need access to data files
@@ -146,6 +333,7 @@ impl EngineManager {
             .map_err(|e| anyhow!("Failed to spawn engine process: {}", e))?;
 
         log::info!("Engine process spawned, PID: {:?}", child.id());
+        let pid = child.id();
 
         let stdin = child.stdin.take().ok_or_else(|| anyhow!("Failed to get stdin"))?;
         let stdout = child.stdout.take().ok_or_else(|| anyhow!("Failed to get stdout"))?;
@@ -153,6 +341,8 @@ impl EngineManager {
 
         engine.process = Some(child);
         engine.stdin = Some(stdin);
+        let log = engine.log.clone();
+        self.logs.write().await.insert(id.clone(), log.clone());
 
         let engine_arc = Arc::new(Mutex::new(engine));
 
@@ -163,14 +353,22 @@ impl EngineManager {
         }
 
         // Spawn stdout reader task
-        self.spawn_output_reader(id.clone(), stdout).await;
+        let lines = Self::forward_stdout_lines(stdout);
+        self.spawn_output_reader(id.clone(), session_id, lines, log.clone(), protocol).await;
 
         // Spawn stderr reader task
-        self.spawn_error_reader(id.clone(), stderr).await;
+        self.spawn_error_reader(id.clone(), stderr, log).await;
 
         // Spawn watchdog task
         self.spawn_watchdog(id.clone()).await;
 
+        // Spawn health monitor task
+        self.spawn_health_monitor(id.clone()).await;
+
+        if let Some(pid) = pid {
+            self.apply_resource_limits(id.clone(), pid, limits).await;
+        }
+
         // Give the engine process a moment to start up before we try to communicate
         // This prevents race conditions where we try to write to stdin before the engine is ready
         tokio::time::sleep(Duration::from_millis(100)).await;
@@ -179,19 +377,89 @@ impl EngineManager {
         Ok(id)
     }
 
-    /// Spawn a task to read engine stdout and emit events
-    async fn spawn_output_reader(&self, engine_id: String, stdout: ChildStdout) {
+    /// Run the built-in engine on a thread inside the app. It is driven through
+    /// the same output reader as external engines, but has no process for the
+    /// watchdog to observe.
+    async fn spawn_in_process_engine(
+        &self,
+        id: String,
+        name: String,
+        session_id: String,
+    ) -> Result<String> {
+        let mut engine = EngineInstance::new(
+            id.clone(),
+            name,
+            inprocess_engine::IN_PROCESS_ENGINE_PATH.to_string(),
+            session_id.clone(),
+        );
+        engine.status = EngineStatus::Starting;
+
+        let (output_tx, output_rx) = mpsc::unbounded_channel();
+        engine.in_process = Some(InProcessEngine::spawn(&id, output_tx)?);
+        let log = engine.log.clone();
+        self.logs.write().await.insert(id.clone(), log.clone());
+
+        {
+            let mut engines = self.engines.write().await;
+            engines.insert(id.clone(), Arc::new(Mutex::new(engine)));
+        }
+
+        self.spawn_output_reader(id.clone(), session_id, output_rx, log, EngineProtocol::Usi)
+            .await;
+        self.spawn_health_monitor(id.clone()).await;
+
+        log::info!("In-process engine {} started", id);
+        Ok(id)
+    }
+
+    /// Forward the lines an engine process writes to stdout into a channel
+    fn forward_stdout_lines(stdout: ChildStdout) -> mpsc::UnboundedReceiver<String> {
+        let (line_tx, line_rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if line_tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        line_rx
+    }
+
+    /// Spawn a task to read engine output lines, translated from the engine's
+    /// `protocol` into USI, and emit events
+    async fn spawn_output_reader(
+        &self,
+        engine_id: String,
+        session_id: String,
+        mut lines: mpsc::UnboundedReceiver<String>,
+        log: SharedEngineLog,
+        protocol: EngineProtocol,
+    ) {
         let app_handle = self.app_handle.clone();
         let engines = self.engines.clone();
+        let health = self.health.clone();
 
         tokio::spawn(async move {
-            let reader = BufReader::new(stdout);
-            let mut lines = reader.lines();
-
             let mut line_count = 0;
-            while let Ok(Some(line)) = lines.next_line().await {
+            while let Some(line) = lines.recv().await {
                 line_count += 1;
-                log::debug!("Engine {} output: {}", engine_id, line);
+                let correlation_id = engine_log::with_log(&log, |log| {
+                    log.received_correlation_id().map(str::to_string)
+                });
+                log::debug!(
+                    "[{}] Engine {} output: {}",
+                    correlation_id.as_deref().unwrap_or("-"),
+                    engine_id,
+                    line
+                );
+                engine_log::record(&log, LogDirection::Received, &line);
+                let Some(line) = protocol.translate_output(&line) else {
+                    continue;
+                };
+                if line.starts_with("bestmove") || line.starts_with("checkmate") {
+                    engine_log::with_log(&log, EngineLog::finish_search);
+                }
 
                 // Update engine status based on output
                 if line.contains("usiok") {
@@ -199,13 +467,54 @@ impl EngineManager {
                         engine.lock().await.status = EngineStatus::Ready;
                     }
                 } else if line.contains("readyok") {
+                    let mut probe_started = None;
                     if let Some(engine) = engines.read().await.get(&engine_id) {
-                        engine.lock().await.status = EngineStatus::Ready;
+                        let mut engine_lock = engine.lock().await;
+                        // Engines also answer isready while they search
+                        if engine_lock.status != EngineStatus::Thinking {
+                            engine_lock.status = EngineStatus::Ready;
+                        }
+                        probe_started = engine_lock.answer_isready();
+                    }
+
+                    // Answers to health probes are consumed here so the frontend
+                    // never sees a readyok it did not ask for
+                    if let Some(started) = probe_started {
+                        let latency_ms = started.elapsed().as_millis() as u64;
+                        log::debug!("Engine {} answered health probe in {}ms", engine_id, latency_ms);
+                        let mut health = health.write().await;
+                        let report = health
+                            .entry(engine_id.clone())
+                            .or_insert_with(|| EngineHealthReport::new(engine_id.clone()));
+                        report.last_latency_ms = Some(latency_ms);
+                        report.probes_answered += 1;
+                        continue;
                     }
                 } else if line.starts_with("bestmove") {
+                    if let Some(engine) = engines.read().await.get(&engine_id) {
+                        let mut engine = engine.lock().await;
+                        engine.status = EngineStatus::Ready;
+                        if std::mem::take(&mut engine.discard_bestmove) {
+                            log::debug!("Engine {} dropped stale {}", engine_id, line);
+                            continue;
+                        }
+                    }
+                } else if line.starts_with("checkmate") {
                     if let Some(engine) = engines.read().await.get(&engine_id) {
                         engine.lock().await.status = EngineStatus::Ready;
                     }
+                } else if let Some(reply) = line.strip_prefix("info string ") {
+                    // Answers to JSON queries go to the waiting caller only
+                    if let Some((tag, payload)) = reply.split_once(' ') {
+                        let mut pending = None;
+                        if let Some(engine) = engines.read().await.get(&engine_id) {
+                            pending = engine.lock().await.pending_replies.remove(tag);
+                        }
+                        if let Some(reply_tx) = pending {
+                            let _ = reply_tx.send(payload.to_string());
+                            continue;
+                        }
+                    }
                 }
 
                 // Emit event to frontend
@@ -213,14 +522,52 @@ impl EngineManager {
                 if let Err(e) = app_handle.emit(&event_name, &line) {
                     log::error!("Failed to emit USI message event: {}", e);
                 }
+
+                // Session-scoped copy so a tab can follow all of its engines at once
+                let session_event_name = format!("session-usi-message::{}", session_id);
+                let _ = app_handle.emit(
+                    &session_event_name,
+                    serde_json::json!({
+                        "engine_id": engine_id,
+                        "line": line,
+                        "correlation_id": correlation_id,
+                    }),
+                );
+
+                // Typed info events so the frontend doesn't re-parse USI text
+                if let Some(mut info) = UsiInfo::parse(&line) {
+                    if info.score.is_some() {
+                        if let Some(engine) = engines.read().await.get(&engine_id) {
+                            info.perspective = engine.lock().await.score_perspective();
+                        }
+                    }
+                    let info_event_name = format!("usi-info::{}", engine_id);
+                    if let Err(e) = app_handle.emit(&info_event_name, &info) {
+                        log::error!("Failed to emit USI info event: {}", e);
+                    }
+                    let session_info_event_name = format!("session-usi-info::{}", session_id);
+                    let _ = app_handle.emit(
+                        &session_info_event_name,
+                        serde_json::json!({
+                            "engine_id": engine_id,
+                            "info": info,
+                            "correlation_id": correlation_id,
+                        }),
+                    );
+                }
             }
 
-            log::warn!("Engine {} stdout reader task ended after {} lines", engine_id, line_count);
+            log::warn!("Engine {} output reader task ended after {} lines", engine_id, line_count);
         });
     }
 
     /// Spawn a task to read engine stderr and emit error events
-    async fn spawn_error_reader(&self, engine_id: String, stderr: tokio::process::ChildStderr) {
+    async fn spawn_error_reader(
+        &self,
+        engine_id: String,
+        stderr: tokio::process::ChildStderr,
+        log: SharedEngineLog,
+    ) {
         let app_handle = self.app_handle.clone();
 
         tokio::spawn(async move {
@@ -231,6 +578,7 @@ impl EngineManager {
             while let Ok(Some(line)) = lines.next_line().await {
                 line_count += 1;
                 log::warn!("Engine {} stderr: {}", engine_id, line);
+                engine_log::record(&log, LogDirection::Stderr, &line);
 
                 // Emit error event to frontend
                 let event_name = format!("usi-error::{}", engine_id);
@@ -291,6 +639,139 @@ impl EngineManager {
         });
     }
 
+    /// Lower the engine's priority and, when it has a memory cap, spawn a task
+    /// that kills it once its memory passes the cap
+    async fn apply_resource_limits(
+        &self,
+        engine_id: String,
+        pid: u32,
+        limits: EngineResourceLimits,
+    ) {
+        if let Err(e) = engine_limits::apply_priority(pid, limits.priority).await {
+            log::warn!("Engine {}: {}", engine_id, e);
+        }
+        if limits.memory_limit_mb.is_none() {
+            return;
+        }
+
+        let engines = self.engines.clone();
+        let app_handle = self.app_handle.clone();
+        tokio::spawn(async move {
+            let Some(memory_bytes) = engine_limits::guard_memory(pid, limits).await else {
+                return;
+            };
+            let message = format!(
+                "Engine killed after using {} MiB, over its memory cap",
+                memory_bytes / (1024 * 1024)
+            );
+            log::error!("Engine {}: {}", engine_id, message);
+            if let Some(engine) = engines.read().await.get(&engine_id) {
+                engine.lock().await.status = EngineStatus::Error;
+            }
+            let event_name = format!("usi-error::{}", engine_id);
+            let _ = app_handle.emit(&event_name, message);
+        });
+    }
+
+    /// Spawn a task that periodically probes idle engines with `isready`,
+    /// samples their memory usage and emits health reports and warnings
+    async fn spawn_health_monitor(&self, engine_id: String) {
+        let engines = self.engines.clone();
+        let health = self.health.clone();
+        let config = self.health_config.clone();
+        let app_handle = self.app_handle.clone();
+
+        tokio::spawn(async move {
+            let mut system = sysinfo::System::new();
+            let mut previous_state = HealthState::Unknown;
+
+            loop {
+                tokio::time::sleep(config.unresponsive_timeout()).await;
+
+                let engine = match engines.read().await.get(&engine_id) {
+                    Some(engine) => engine.clone(),
+                    // Engine removed from manager, exit monitor
+                    None => break,
+                };
+
+                let (pid, outstanding_probe, probe_sent) = {
+                    let mut engine_lock = engine.lock().await;
+                    let pid = match &engine_lock.process {
+                        Some(process) => process.id(),
+                        // In-process engines share the app's memory, so only probe them
+                        None if engine_lock.is_in_process() => None,
+                        // Engine stopped, exit monitor
+                        None => break,
+                    };
+
+                    let idle = engine_lock.status == EngineStatus::Ready
+                        && engine_lock.last_activity.elapsed() >= config.probe_interval();
+                    let mut probe_sent = false;
+                    if idle && engine_lock.pending_probe().is_none() {
+                        match engine_lock.send_probe().await {
+                            Ok(()) => probe_sent = true,
+                            Err(e) => log::warn!("Failed to probe engine {}: {}", engine_id, e),
+                        }
+                    }
+
+                    (pid, engine_lock.pending_probe().map(|sent| sent.elapsed()), probe_sent)
+                };
+
+                let memory_bytes =
+                    pid.and_then(|pid| engine_health::sample_process_memory(&mut system, pid));
+
+                let report = {
+                    let mut health = health.write().await;
+                    let report = health
+                        .entry(engine_id.clone())
+                        .or_insert_with(|| EngineHealthReport::new(engine_id.clone()));
+                    if probe_sent {
+                        report.probes_sent += 1;
+                    }
+                    report.memory_bytes = memory_bytes;
+                    report.state = engine_health::classify(
+                        &config,
+                        outstanding_probe,
+                        report.last_latency_ms,
+                        memory_bytes,
+                    );
+                    report.checked_at = chrono::Utc::now().to_rfc3339();
+                    report.clone()
+                };
+
+                let event_name = format!("engine-health::{}", engine_id);
+                if let Err(e) = app_handle.emit(&event_name, &report) {
+                    log::error!("Failed to emit engine health event: {}", e);
+                }
+
+                if report.state != previous_state
+                    && matches!(
+                        report.state,
+                        HealthState::Slow | HealthState::Unresponsive | HealthState::Bloated
+                    )
+                {
+                    log::warn!("Engine {} health degraded: {:?}", engine_id, report.state);
+                    let event_name = format!("engine-health-warning::{}", engine_id);
+                    let _ = app_handle.emit(&event_name, &report);
+                }
+                previous_state = report.state;
+            }
+
+            health.write().await.remove(&engine_id);
+            log::info!("Engine {} health monitor task ended", engine_id);
+        });
+    }
+
+    /// Get the latest health reports, optionally restricted to a single engine
+    pub async fn get_health_reports(&self, engine_id: Option<&str>) -> Vec<EngineHealthReport> {
+        let health = self.health.read().await;
+        health
+            .values()
+            .filter(|report| engine_id.map_or(true, |id| report.engine_id.starts_with(id)))
+            .cloned()
+            .collect()
+    }
+
     /// Send a USI command to a specific engine
     /// Supports both runtime IDs (full ID) and config IDs (prefix match)
     pub async fn send_command(&self, engine_id: &str, command: &str) -> Result<()> {
@@ -312,6 +793,161 @@ impl EngineManager {
         engine_lock.send_command(command).await
     }
 
+    /// Ask an engine for the statistics of its last search (non-standard `stats`
+    /// command). Engines that don't support it are reported as a timeout.
+    pub async fn request_search_statistics(
+        &self,
+        engine_id: &str,
+        timeout_duration: Duration,
+    ) -> Result<serde_json::Value> {
+        self.query_engine_json(engine_id, "stats", "stats", timeout_duration).await
+    }
+
+    /// Ask an engine for its aggregated statistics snapshot (non-standard
+    /// `statshub` command). `mode` is `None`, `"delta"` or `"reset"`.
+    pub async fn request_statistics_hub(
+        &self,
+        engine_id: &str,
+        mode: Option<&str>,
+        timeout_duration: Duration,
+    ) -> Result<serde_json::Value> {
+        let command = match mode {
+            Some(mode) => format!("statshub {}", mode),
+            None => "statshub".to_string(),
+        };
+        self.query_engine_json(engine_id, &command, "statshub", timeout_duration)
+            .await
+    }
+
+    /// Ask an engine for the legal destinations of a square or hand piece
+    /// (non-standard `hints` command)
+    pub async fn request_move_hints(
+        &self,
+        engine_id: &str,
+        source: &str,
+        timeout_duration: Duration,
+    ) -> Result<serde_json::Value> {
+        self.query_engine_json(
+            engine_id,
+            &format!("hints {}", source),
+            "hints",
+            timeout_duration,
+        )
+        .await
+    }
+
+    /// Ask an engine for a hint at `level` (`hint <level>`)
+    pub async fn request_hint(
+        &self,
+        engine_id: &str,
+        level: u8,
+        timeout_duration: Duration,
+    ) -> Result<serde_json::Value> {
+        self.query_engine_json(
+            engine_id,
+            &format!("hint {}", level),
+            "hint",
+            timeout_duration,
+        )
+        .await
+    }
+
+    /// Ask an engine for its `count` best moves with scores and continuations,
+    /// searched within `time_ms` (`candidates <count> <time_ms>`)
+    pub async fn request_candidates(
+        &self,
+        engine_id: &str,
+        count: usize,
+        time_ms: u32,
+        timeout_duration: Duration,
+    ) -> Result<serde_json::Value> {
+        self.query_engine_json(
+            engine_id,
+            &format!("candidates {} {}", count, time_ms),
+            "candidates",
+            timeout_duration,
+        )
+        .await
+    }
+
+    /// Ask an engine for the danger to both kings in its current position (`matemeter`)
+    pub async fn request_mate_meter(
+        &self,
+        engine_id: &str,
+        timeout_duration: Duration,
+    ) -> Result<serde_json::Value> {
+        self.query_engine_json(engine_id, "matemeter", "matemeter", timeout_duration)
+            .await
+    }
+
+    /// Ask an engine how sharp its current position is, from its `count` best
+    /// moves searched within `time_ms` (`sharpness <count> <time_ms>`)
+    pub async fn request_sharpness(
+        &self,
+        engine_id: &str,
+        count: usize,
+        time_ms: u32,
+        timeout_duration: Duration,
+    ) -> Result<serde_json::Value> {
+        self.query_engine_json(
+            engine_id,
+            &format!("sharpness {} {}", count, time_ms),
+            "sharpness",
+            timeout_duration,
+        )
+        .await
+    }
+
+    /// Ask an engine for the named opening of its current game (`opening`)
+    pub async fn request_opening(
+        &self,
+        engine_id: &str,
+        timeout_duration: Duration,
+    ) -> Result<serde_json::Value> {
+        self.query_engine_json(engine_id, "opening", "opening", timeout_duration)
+            .await
+    }
+
+    /// Send a query command and wait for its `info string <tag> <json>` reply
+    async fn query_engine_json(
+        &self,
+        engine_id: &str,
+        command: &str,
+        tag: &str,
+        timeout_duration: Duration,
+    ) -> Result<serde_json::Value> {
+        let engine = {
+            let engines = self.engines.read().await;
+            engines
+                .get(engine_id)
+                .or_else(|| {
+                    engines
+                        .iter()
+                        .find(|(id, _)| id.starts_with(engine_id))
+                        .map(|(_, engine)| engine)
+                })
+                .cloned()
+                .ok_or_else(|| anyhow!("Engine not found: {}", engine_id))?
+        };
+
+        let (reply_tx, reply_rx) = oneshot::channel();
+        {
+            let mut engine_lock = engine.lock().await;
+            engine_lock.pending_replies.insert(tag.to_string(), reply_tx);
+            engine_lock.send_command(command).await?;
+        }
+
+        let payload = match timeout(timeout_duration, reply_rx).await {
+            Ok(Ok(payload)) => payload,
+            _ => {
+                engine.lock().await.pending_replies.remove(tag);
+                return Err(anyhow!("Engine {} did not answer '{}'", engine_id, tag));
+            }
+        };
+
+        serde_json::from_str(&payload).map_err(|_| anyhow!("{} unavailable: {}", tag, payload))
+    }
+
     /// Send a USI command with timeout
     pub async fn send_command_with_timeout(
         &self,
@@ -369,35 +1005,49 @@ impl EngineManager {
             tokio::time::sleep(Duration::from_millis(50)).await;
         }
 
-        // Send options (temporary or saved)
-        if let Some(options) = temp_options {
-            // Use temporary options
-            if !options.is_empty() {
-                log::info!("Sending {} temporary options to engine: {}", options.len(), engine_id);
-                for (option_name, option_value) in options {
-                    let option_command = format!("setoption name {} value {}", option_name, option_value);
-                    log::debug!("Sending option command: {}", option_command);
-                    if let Err(e) = self.send_command_with_timeout(engine_id, &option_command, Duration::from_secs(2)).await {
-                        log::warn!("Failed to send option '{}' to engine {}: {}", option_name, engine_id, e);
-                    }
-                }
+        // Send options (temporary or saved), held to the engine's resource limits
+        let storage = engine_storage.read().await;
+        let options = match temp_options {
+            Some(options) => options.clone(),
+            None => storage.get_engine_options(engine_id).cloned().unwrap_or_default(),
+        };
+        let config = storage.find_for_runtime_id(engine_id);
+        let options = match config {
+            Some(config) => {
+                let declared = config.metadata.as_ref().map_or(&[][..], |m| &m.options[..]);
+                config.limits.limit_options(&options, declared)
             }
-        } else {
-            // Use saved options from storage
-            let storage = engine_storage.read().await;
-            if let Some(options) = storage.get_engine_options(engine_id) {
-                if !options.is_empty() {
-                    log::info!("Sending {} saved options to engine: {}", options.len(), engine_id);
-                    for (option_name, option_value) in options {
-                        let option_command = format!("setoption name {} value {}", option_name, option_value);
-                        log::debug!("Sending option command: {}", option_command);
-                        if let Err(e) = self.send_command_with_timeout(engine_id, &option_command, Duration::from_secs(2)).await {
-                            log::warn!("Failed to send option '{}' to engine {}: {}", option_name, engine_id, e);
-                        }
-                    }
+            None => options.into_iter().collect(),
+        };
+        drop(storage);
+        if !options.is_empty() {
+            log::info!("Sending {} options to engine: {}", options.len(), engine_id);
+            for (name, value) in options {
+                let option_command = format!("setoption name {} value {}", name, value);
+                log::debug!("Sending option command: {}", option_command);
+                let sent = self
+                    .send_command_with_timeout(engine_id, &option_command, Duration::from_secs(2))
+                    .await;
+                if let Err(e) = sent {
+                    log::warn!("Failed to send option '{}' to engine {}: {}", name, engine_id, e);
                 }
             }
-            drop(storage);
+        }
+
+        // Remember what the engine supports so unsupported commands are refused
+        let capabilities = engine_storage
+            .read()
+            .await
+            .find_for_runtime_id(engine_id)
+            .and_then(|config| config.metadata.as_ref())
+            .and_then(|metadata| metadata.capabilities.clone());
+        {
+            let engines = self.engines.read().await;
+            let engine = engines.get(engine_id)
+                .or_else(|| engines.iter().find(|(id, _)| id.starts_with(engine_id)).map(|(_, e)| e));
+            if let Some(engine) = engine {
+                engine.lock().await.capabilities = capabilities;
+            }
         }
 
         // Send isready command
@@ -454,12 +1104,12 @@ impl EngineManager {
         };
         
         let engine = engine.ok_or_else(|| anyhow!("Engine not found: {}", engine_id))?;
+        // Stopping waits for the process to exit; don't hold up the registry meanwhile
+        drop(engines);
 
         let mut engine_lock = engine.lock().await;
         engine_lock.stop().await?;
-
         drop(engine_lock);
-        drop(engines);
 
         // Remove from manager using the actual runtime ID
         self.engines.write().await.remove(&actual_id);
@@ -489,20 +1139,121 @@ impl EngineManager {
         })
     }
 
+    /// Whether any engine is searching, so background work can hold back
+    pub async fn any_thinking(&self) -> bool {
+        let engines = self.engines.read().await;
+        for engine in engines.values() {
+            if engine.lock().await.status == EngineStatus::Thinking {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Stop an engine's search or ponder whose position no longer stands, e.g.
+    /// after a takeback; its `bestmove` is dropped. Returns whether it was
+    /// searching.
+    pub async fn abandon_search(&self, engine_id: &str) -> Result<bool> {
+        let engine = self
+            .engines
+            .read()
+            .await
+            .get(engine_id)
+            .cloned()
+            .ok_or_else(|| anyhow!("Engine not found: {}", engine_id))?;
+        let mut engine = engine.lock().await;
+        if engine.status != EngineStatus::Thinking {
+            return Ok(false);
+        }
+        engine.discard_bestmove = true;
+        engine.send_command("stop").await?;
+        Ok(true)
+    }
+
     /// Get list of all engine IDs
     pub async fn list_engines(&self) -> Vec<String> {
         self.engines.read().await.keys().cloned().collect()
     }
 
-    /// Stop all engines
+    /// Get list of engine IDs belonging to a session
+    pub async fn list_session_engines(&self, session_id: &str) -> Vec<String> {
+        let engines = self.engines.read().await;
+        let mut ids = Vec::new();
+        for (id, engine) in engines.iter() {
+            if engine.lock().await.session_id == session_id {
+                ids.push(id.clone());
+            }
+        }
+        ids
+    }
+
+    /// Find an engine's log by runtime id or config id prefix
+    async fn find_log(&self, engine_id: &str) -> Option<SharedEngineLog> {
+        let logs = self.logs.read().await;
+        logs.get(engine_id)
+            .or_else(|| logs.iter().find(|(id, _)| id.starts_with(engine_id)).map(|(_, log)| log))
+            .cloned()
+    }
+
+    /// Logged traffic of an engine from sequence number `since` on
+    pub async fn engine_log(&self, engine_id: &str, since: Option<u64>) -> Result<Vec<LogEntry>> {
+        let log = self
+            .find_log(engine_id)
+            .await
+            .ok_or_else(|| anyhow!("No log for engine: {}", engine_id))?;
+        let log = log.lock().map_err(|_| anyhow!("Engine log is poisoned"))?;
+        Ok(log.entries_since(since))
+    }
+
+    /// Runtime ids of the logged engines of a session, running or not
+    pub async fn session_engine_logs(&self, session_id: &str) -> Vec<String> {
+        let logs = self.logs.read().await;
+        logs.iter()
+            .filter(|(_, log)| log.lock().map(|log| log.session_id == session_id).unwrap_or(false))
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// Write an engine's log to `path` as plain text
+    pub async fn export_engine_log(&self, engine_id: &str, path: &std::path::Path) -> Result<()> {
+        let log = self
+            .find_log(engine_id)
+            .await
+            .ok_or_else(|| anyhow!("No log for engine: {}", engine_id))?;
+        let text = match log.lock() {
+            Ok(log) => log.export_text(),
+            Err(_) => return Err(anyhow!("Engine log is poisoned")),
+        };
+        tokio::fs::write(path, text).await?;
+        Ok(())
+    }
+
+    /// Empty an engine's log; logs of stopped engines are dropped altogether
+    pub async fn clear_engine_log(&self, engine_id: &str) -> Result<()> {
+        let log = self
+            .find_log(engine_id)
+            .await
+            .ok_or_else(|| anyhow!("No log for engine: {}", engine_id))?;
+        let id = log.lock().map_err(|_| anyhow!("Engine log is poisoned"))?.engine_id.clone();
+        if self.engines.read().await.contains_key(&id) {
+            log.lock().map_err(|_| anyhow!("Engine log is poisoned"))?.clear();
+        } else {
+            self.logs.write().await.remove(&id);
+        }
+        Ok(())
+    }
+
+    /// Stop all engines. They are sent `quit` together and waited for together,
+    /// so shutting down takes one grace period however many engines run.
     pub async fn stop_all_engines(&self) -> Result<()> {
         let engine_ids: Vec<String> = self.list_engines().await;
 
-        for engine_id in engine_ids {
-            if let Err(e) = self.stop_engine(&engine_id).await {
+        let stops = engine_ids.iter().map(|engine_id| async move {
+            if let Err(e) = self.stop_engine(engine_id).await {
                 log::error!("Failed to stop engine {}: {}", engine_id, e);
             }
-        }
+        });
+        futures::future::join_all(stops).await;
 
         Ok(())
     }
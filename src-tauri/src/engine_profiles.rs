@@ -0,0 +1,150 @@
+//! Engine option profiles
+//!
+//! A profile is a named bundle of the settings players change together:
+//! search depth, time per move, hash size, threads, playing strength, book use
+//! and contempt. Three profiles are built in; players can save their own next
+//! to the engines in `EngineStorage`, and a saved profile with a built-in's
+//! name replaces it. Applying a profile to an engine merges its USI options
+//! into the engine's saved options in one storage update, so the engine starts
+//! with the whole profile or, if saving fails, with none of it.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A named set of engine settings; unset fields leave the engine's own value
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EngineProfile {
+    pub name: String,
+    #[serde(default)]
+    pub is_builtin: bool,
+    /// `MaxDepth`; 0 lets the time limit decide
+    pub depth: Option<u8>,
+    /// Time the frontend gives each move when playing with this profile
+    pub time_per_move_ms: Option<u64>,
+    /// `USI_Hash`
+    pub hash_mb: Option<u32>,
+    /// `USI_Threads`
+    pub threads: Option<u32>,
+    /// `CoachLevel`: 0 plays full strength, higher levels play weaker moves
+    pub skill: Option<u8>,
+    /// `USI_OwnBook`
+    pub own_book: Option<bool>,
+    /// `Contempt`, for engines that have it
+    pub contempt: Option<i32>,
+    /// Any other USI options, sent as they are
+    #[serde(default)]
+    pub extra_options: HashMap<String, String>,
+}
+
+impl EngineProfile {
+    /// "Blitz", "Analysis" and "Weak sparring"
+    pub fn builtins() -> Vec<Self> {
+        vec![
+            Self {
+                name: "Blitz".to_string(),
+                is_builtin: true,
+                depth: Some(0),
+                time_per_move_ms: Some(1_000),
+                hash_mb: Some(64),
+                threads: Some(2),
+                skill: Some(0),
+                own_book: Some(true),
+                ..Self::default()
+            },
+            Self {
+                name: "Analysis".to_string(),
+                is_builtin: true,
+                depth: Some(0),
+                time_per_move_ms: None,
+                hash_mb: Some(512),
+                threads: Some(analysis_threads()),
+                skill: Some(0),
+                own_book: Some(false),
+                ..Self::default()
+            },
+            Self {
+                name: "Weak sparring".to_string(),
+                is_builtin: true,
+                depth: Some(4),
+                time_per_move_ms: Some(500),
+                hash_mb: Some(16),
+                threads: Some(1),
+                skill: Some(6),
+                own_book: Some(true),
+                ..Self::default()
+            },
+        ]
+    }
+
+    /// The profile as `setoption` name/value pairs
+    pub fn usi_options(&self) -> HashMap<String, String> {
+        let mut options = self.extra_options.clone();
+        let mut set = |name: &str, value: Option<String>| {
+            if let Some(value) = value {
+                options.insert(name.to_string(), value);
+            }
+        };
+        set("MaxDepth", self.depth.map(|v| v.to_string()));
+        set("USI_Hash", self.hash_mb.map(|v| v.to_string()));
+        set("USI_Threads", self.threads.map(|v| v.to_string()));
+        set("CoachLevel", self.skill.map(|v| v.to_string()));
+        set("USI_OwnBook", self.own_book.map(|v| v.to_string()));
+        set("Contempt", self.contempt.map(|v| v.to_string()));
+        options
+    }
+}
+
+/// Threads for the analysis profile: the machine's cores, up to the engine's maximum
+fn analysis_threads() -> u32 {
+    std::thread::available_parallelism()
+        .map(|n| n.get() as u32)
+        .unwrap_or(1)
+        .min(32)
+}
+
+/// Built-in profiles followed by the saved ones, a saved profile replacing the
+/// built-in of the same name
+pub fn merge_profiles(saved: &[EngineProfile]) -> Vec<EngineProfile> {
+    let mut profiles: Vec<EngineProfile> = EngineProfile::builtins()
+        .into_iter()
+        .filter(|builtin| !saved.iter().any(|profile| profile.name == builtin.name))
+        .collect();
+    profiles.extend(saved.iter().cloned());
+    profiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usi_options_skip_unset_fields() {
+        let profile = EngineProfile {
+            name: "Custom".to_string(),
+            depth: Some(6),
+            own_book: Some(false),
+            extra_options: HashMap::from([("Language".to_string(), "ja".to_string())]),
+            ..EngineProfile::default()
+        };
+        let options = profile.usi_options();
+        assert_eq!(options.len(), 3);
+        assert_eq!(options["MaxDepth"], "6");
+        assert_eq!(options["USI_OwnBook"], "false");
+        assert_eq!(options["Language"], "ja");
+    }
+
+    #[test]
+    fn test_saved_profile_replaces_builtin() {
+        let saved = vec![EngineProfile {
+            name: "Blitz".to_string(),
+            time_per_move_ms: Some(3_000),
+            ..EngineProfile::default()
+        }];
+        let profiles = merge_profiles(&saved);
+        assert_eq!(profiles.len(), 3);
+        let blitz: Vec<_> = profiles.iter().filter(|p| p.name == "Blitz").collect();
+        assert_eq!(blitz.len(), 1);
+        assert_eq!(blitz[0].time_per_move_ms, Some(3_000));
+        assert!(!blitz[0].is_builtin);
+    }
+}
@@ -0,0 +1,282 @@
+//! Engine protocol dialects
+//!
+//! The app speaks USI to every engine. Some cross-variant engines speak UCI
+//! instead, with shogi played as a variant in the style of Fairy-Stockfish:
+//! `uci` and `ucinewgame`, positions as FEN with the hands in brackets, squares
+//! named like chess squares from sente's side (`7g` is `c3`), drops written
+//! `P@e5`, mate distances in moves and sente playing "white". The engine
+//! manager passes every command and output line through the adapter of the
+//! engine's protocol, chosen per engine in its `EngineConfig`, so the rest of
+//! the app only ever sees USI.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+/// USI options that UCI engines know under another name
+const RENAMED_OPTIONS: [(&str, &str); 3] =
+    [("USI_Hash", "Hash"), ("USI_Ponder", "Ponder"), ("USI_OwnBook", "OwnBook")];
+
+/// `go` arguments naming a side's clock, swapped because sente is UCI's white
+const SWAPPED_CLOCKS: [(&str, &str); 4] =
+    [("btime", "wtime"), ("wtime", "btime"), ("binc", "winc"), ("winc", "binc")];
+
+/// Protocol an engine speaks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EngineProtocol {
+    #[default]
+    Usi,
+    /// UCI with shogi as a variant (`UCI_Variant shogi`)
+    UciShogi,
+}
+
+impl EngineProtocol {
+    /// The lines to send the engine for one USI command. A command with no
+    /// equivalent in the dialect becomes no lines when it can be dropped
+    /// (`gameover`) and an error when it cannot.
+    pub fn translate_command(self, command: &str) -> Result<Vec<String>> {
+        if self == EngineProtocol::Usi {
+            return Ok(vec![command.to_string()]);
+        }
+        let tokens: Vec<&str> = command.split_whitespace().collect();
+        let lines = match tokens.first().copied() {
+            Some("usi") => {
+                vec!["uci".to_string(), "setoption name UCI_Variant value shogi".to_string()]
+            }
+            Some("usinewgame") => vec!["ucinewgame".to_string()],
+            Some("gameover") => Vec::new(),
+            Some("position") => vec![uci_position(&tokens[1..])?],
+            Some("go") => vec![uci_go(&tokens[1..])?],
+            Some("setoption") => vec![rename_option(command, true)],
+            _ => vec![command.to_string()],
+        };
+        Ok(lines)
+    }
+
+    /// The USI form of a line the engine wrote; `None` for lines that have no
+    /// place in a USI conversation
+    pub fn translate_output(self, line: &str) -> Option<String> {
+        if self == EngineProtocol::Usi {
+            return Some(line.to_string());
+        }
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("uciok") => Some("usiok".to_string()),
+            // The adapter picks the variant itself
+            Some("option") if line.contains("name UCI_Variant ") => None,
+            Some("option") => Some(rename_option(line, false)),
+            Some("bestmove") => {
+                let translated: Vec<String> = tokens
+                    .map(|token| match token {
+                        "(none)" => "resign".to_string(),
+                        "ponder" => token.to_string(),
+                        mv => usi_move(mv).unwrap_or_else(|| mv.to_string()),
+                    })
+                    .collect();
+                Some(format!("bestmove {}", translated.join(" ")))
+            }
+            Some("info") if !line.starts_with("info string") => Some(usi_info(line)),
+            _ => Some(line.to_string()),
+        }
+    }
+}
+
+/// `line` with a renamed option's USI name replaced by its UCI name, or the
+/// other way round
+fn rename_option(line: &str, to_uci: bool) -> String {
+    for (usi, uci) in RENAMED_OPTIONS {
+        let (from, to) = if to_uci { (usi, uci) } else { (uci, usi) };
+        let from = format!("name {} ", from);
+        if line.contains(&from) {
+            return line.replacen(&from, &format!("name {} ", to), 1);
+        }
+    }
+    line.to_string()
+}
+
+/// UCI name of a USI square such as `7g`
+fn uci_square(square: &str) -> Option<String> {
+    let &[file @ b'1'..=b'9', rank @ b'a'..=b'i'] = square.as_bytes() else {
+        return None;
+    };
+    Some(format!("{}{}", char::from(b'a' + (b'9' - file)), char::from(b'9' - (rank - b'a'))))
+}
+
+/// USI name of a UCI square such as `c3`
+fn usi_square(square: &str) -> Option<String> {
+    let &[file @ b'a'..=b'i', rank @ b'1'..=b'9'] = square.as_bytes() else {
+        return None;
+    };
+    Some(format!("{}{}", char::from(b'9' - (file - b'a')), char::from(b'a' + (b'9' - rank))))
+}
+
+/// UCI form of a USI move: `7g7f+` is `c3c4+`, `P*5e` is `P@e5`
+fn uci_move(mv: &str) -> Option<String> {
+    let (body, promotion) = mv.strip_suffix('+').map_or((mv, ""), |body| (body, "+"));
+    if let Some((piece, to)) = body.split_once('*') {
+        return Some(format!("{}@{}", piece, uci_square(to)?));
+    }
+    let (from, to) = (body.get(..2)?, body.get(2..)?);
+    Some(format!("{}{}{}", uci_square(from)?, uci_square(to)?, promotion))
+}
+
+/// USI form of a UCI move
+fn usi_move(mv: &str) -> Option<String> {
+    let (body, promotion) = mv.strip_suffix('+').map_or((mv, ""), |body| (body, "+"));
+    if let Some((piece, to)) = body.split_once('@') {
+        return Some(format!("{}*{}", piece, usi_square(to)?));
+    }
+    let (from, to) = (body.get(..2)?, body.get(2..)?);
+    Some(format!("{}{}{}", usi_square(from)?, usi_square(to)?, promotion))
+}
+
+/// `position` for a UCI-shogi engine, from the arguments of a USI `position`
+fn uci_position(args: &[&str]) -> Result<String> {
+    let moves_at = args.iter().position(|token| *token == "moves").unwrap_or(args.len());
+    let (setup, moves) = args.split_at(moves_at);
+    let mut line = match setup {
+        ["startpos"] => "position startpos".to_string(),
+        ["sfen", board, side, hand, rest @ ..] => {
+            let ply: u32 = rest.first().and_then(|ply| ply.parse().ok()).unwrap_or(1);
+            format!(
+                "position fen {}[{}] {} - - 0 {}",
+                board,
+                uci_hand(hand)?,
+                if *side == "b" { "w" } else { "b" },
+                (ply + 1) / 2
+            )
+        }
+        _ => return Err(anyhow!("Invalid position command: position {}", args.join(" "))),
+    };
+    if !moves.is_empty() {
+        line.push_str(" moves");
+        for mv in &moves[1..] {
+            let uci = uci_move(mv).ok_or_else(|| anyhow!("Invalid move: {}", mv))?;
+            line.push(' ');
+            line.push_str(&uci);
+        }
+    }
+    Ok(line)
+}
+
+/// Hand of a SFEN (`2Pb`) written out piece by piece (`PPb`)
+fn uci_hand(hand: &str) -> Result<String> {
+    if hand == "-" {
+        return Ok("-".to_string());
+    }
+    let mut pieces = String::new();
+    let mut count = 0;
+    for c in hand.chars() {
+        if let Some(digit) = c.to_digit(10) {
+            count = count * 10 + digit;
+        } else if c.is_ascii_alphabetic() {
+            for _ in 0..count.max(1) {
+                pieces.push(c);
+            }
+            count = 0;
+        } else {
+            return Err(anyhow!("Invalid hand: {}", hand));
+        }
+    }
+    Ok(pieces)
+}
+
+/// `go` for a UCI-shogi engine. USI's `go mate` searches for a tsume within a
+/// time limit, which UCI's `go mate` (a mate within so many moves) is not.
+fn uci_go(args: &[&str]) -> Result<String> {
+    if args.first() == Some(&"mate") {
+        return Err(anyhow!("Mate search is not available over UCI"));
+    }
+    let translated: Vec<String> = args
+        .iter()
+        .map(|token| {
+            SWAPPED_CLOCKS
+                .iter()
+                .find(|(usi, _)| usi == token)
+                .map(|(_, uci)| uci.to_string())
+                .or_else(|| uci_move(token))
+                .unwrap_or_else(|| token.to_string())
+        })
+        .collect();
+    Ok(["go".to_string()].into_iter().chain(translated).collect::<Vec<_>>().join(" "))
+}
+
+/// USI form of a UCI `info` line: moves renamed and mate distances in plies
+fn usi_info(line: &str) -> String {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let mut translated = Vec::with_capacity(tokens.len());
+    let mut in_pv = false;
+    for (index, token) in tokens.iter().enumerate() {
+        let previous = index.checked_sub(1).map(|previous| tokens[previous]);
+        let value = match previous {
+            _ if in_pv => usi_move(token),
+            Some("currmove") => usi_move(token),
+            Some("mate") if tokens.get(index.wrapping_sub(2)) == Some(&"score") => {
+                token.parse::<i32>().ok().map(|moves| {
+                    let plies = if moves > 0 { 2 * moves - 1 } else { 2 * moves };
+                    plies.to_string()
+                })
+            }
+            _ => None,
+        };
+        in_pv |= *token == "pv";
+        translated.push(value.unwrap_or_else(|| token.to_string()));
+    }
+    translated.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uci_shogi_commands() {
+        let uci = EngineProtocol::UciShogi;
+        assert_eq!(uci.translate_command("usi").unwrap()[0], "uci");
+        assert_eq!(
+            uci.translate_command("position startpos moves 7g7f 3c3d 8h2b+ B*4e").unwrap(),
+            vec!["position startpos moves c3c4 g7g6 b2h8+ B@f5"]
+        );
+        assert_eq!(
+            uci.translate_command("position sfen 4k4/9/9/9/9/9/9/9/4K4 w 2Pg 12").unwrap(),
+            vec!["position fen 4k4/9/9/9/9/9/9/9/4K4[PPg] b - - 0 6"]
+        );
+        assert_eq!(
+            uci.translate_command("go btime 1000 wtime 2000 byoyomi 500").unwrap(),
+            vec!["go wtime 1000 btime 2000 byoyomi 500"]
+        );
+        assert_eq!(
+            uci.translate_command("setoption name USI_Hash value 256").unwrap(),
+            vec!["setoption name Hash value 256"]
+        );
+        assert!(uci.translate_command("gameover win").unwrap().is_empty());
+        assert!(uci.translate_command("go mate 1000").is_err());
+        assert_eq!(EngineProtocol::Usi.translate_command("go mate 1000").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_uci_shogi_output() {
+        let uci = EngineProtocol::UciShogi;
+        assert_eq!(uci.translate_output("uciok").unwrap(), "usiok");
+        assert_eq!(
+            uci.translate_output("bestmove c3c4 ponder g7g6").unwrap(),
+            "bestmove 7g7f ponder 3c3d"
+        );
+        assert_eq!(uci.translate_output("bestmove (none)").unwrap(), "bestmove resign");
+        assert_eq!(
+            uci.translate_output("info depth 5 score mate 2 currmove P@e5 pv P@e5 e9e8")
+                .unwrap(),
+            "info depth 5 score mate 3 currmove P*5e pv P*5e 5a5b"
+        );
+        assert_eq!(
+            uci.translate_output("option name Hash type spin default 16 min 1 max 1024")
+                .unwrap(),
+            "option name USI_Hash type spin default 16 min 1 max 1024"
+        );
+        assert_eq!(uci.translate_output("option name UCI_Variant type combo default chess"), None);
+        assert_eq!(
+            uci.translate_output("info string hello c3c4").unwrap(),
+            "info string hello c3c4"
+        );
+    }
+}
@@ -1,3 +1,6 @@
+use crate::engine_limits::EngineResourceLimits;
+use crate::engine_profiles::{self, EngineProfile};
+use crate::engine_protocol::EngineProtocol;
 use crate::engine_validator::EngineMetadata;
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
@@ -20,6 +23,15 @@ pub struct EngineConfig {
     pub saved_options: Option<std::collections::HashMap<String, String>>,
     #[serde(default = "default_is_favorite")]
     pub is_favorite: bool,
+    /// Name of the profile last applied to the saved options
+    #[serde(default)]
+    pub active_profile: Option<String>,
+    /// Protocol the engine speaks
+    #[serde(default)]
+    pub protocol: EngineProtocol,
+    /// Caps on the threads, priority and memory the engine may use
+    #[serde(default)]
+    pub limits: EngineResourceLimits,
 }
 
 fn default_display_name() -> String {
@@ -45,6 +57,9 @@ impl EngineConfig {
             created_at: now,
             saved_options: None,
             is_favorite: false,
+            active_profile: None,
+            protocol: EngineProtocol::default(),
+            limits: EngineResourceLimits::default(),
         }
     }
 }
@@ -54,6 +69,9 @@ impl EngineConfig {
 pub struct EngineStorage {
     pub version: String,
     pub engines: Vec<EngineConfig>,
+    /// Profiles saved by the player; the built-in ones are not stored
+    #[serde(default)]
+    pub profiles: Vec<EngineProfile>,
 }
 
 impl Default for EngineStorage {
@@ -61,6 +79,7 @@ impl Default for EngineStorage {
         Self {
             version: "1.0".to_string(),
             engines: Vec::new(),
+            profiles: Vec::new(),
         }
     }
 }
@@ -177,6 +196,16 @@ impl EngineStorage {
         self.engines.iter().find(|e| e.id == engine_id)
     }
 
+    /// The engine a running instance was spawned from. Runtime IDs are the
+    /// configuration ID with a per-game suffix, so the longest matching prefix
+    /// wins.
+    pub fn find_for_runtime_id(&self, runtime_id: &str) -> Option<&EngineConfig> {
+        self.engines
+            .iter()
+            .filter(|e| runtime_id.starts_with(&e.id))
+            .max_by_key(|e| e.id.len())
+    }
+
     /// Get a mutable reference to an engine by ID
     #[allow(dead_code)]
     pub fn get_engine_mut(&mut self, engine_id: &str) -> Option<&mut EngineConfig> {
@@ -225,6 +254,20 @@ impl EngineStorage {
         Ok(())
     }
 
+    /// Set the resource limits of an engine
+    pub fn set_resource_limits(
+        &mut self,
+        engine_id: &str,
+        limits: EngineResourceLimits,
+    ) -> Result<()> {
+        let engine = self
+            .get_engine_mut(engine_id)
+            .ok_or_else(|| anyhow!("Engine not found"))?;
+
+        engine.limits = limits;
+        Ok(())
+    }
+
     /// Get saved engine options
     pub fn get_engine_options(&self, engine_id: &str) -> Option<&std::collections::HashMap<String, String>> {
         self.get_engine(engine_id)?.saved_options.as_ref()
@@ -279,5 +322,107 @@ impl EngineStorage {
         Ok(())
     }
 
+    /// Built-in and saved profiles
+    pub fn list_profiles(&self) -> Vec<EngineProfile> {
+        engine_profiles::merge_profiles(&self.profiles)
+    }
+
+    /// Save a profile, replacing a saved one with the same name
+    pub fn save_profile(&mut self, mut profile: EngineProfile) -> Result<()> {
+        profile.name = profile.name.trim().to_string();
+        if profile.name.is_empty() {
+            return Err(anyhow!("Profile name must not be empty"));
+        }
+        profile.is_builtin = false;
+
+        match self.profiles.iter_mut().find(|p| p.name == profile.name) {
+            Some(existing) => *existing = profile,
+            None => self.profiles.push(profile),
+        }
+        Ok(())
+    }
+
+    /// Merge a profile's options into an engine's saved options and mark it active.
+    /// Options a validated engine does not declare are left out.
+    pub fn apply_profile(&mut self, engine_id: &str, profile_name: &str) -> Result<EngineProfile> {
+        let profile = self
+            .list_profiles()
+            .into_iter()
+            .find(|p| p.name == profile_name)
+            .ok_or_else(|| anyhow!("Profile not found: {}", profile_name))?;
+        let engine = self
+            .get_engine_mut(engine_id)
+            .ok_or_else(|| anyhow!("Engine not found"))?;
+
+        let mut options = profile.usi_options();
+        if let Some(metadata) = &engine.metadata {
+            options.retain(|name, _| {
+                let declared = metadata.options.iter().any(|option| option.name == *name);
+                if !declared {
+                    log::debug!("Engine '{}' has no option {}; not applied", engine.name, name);
+                }
+                declared
+            });
+        }
+        engine.saved_options.get_or_insert_with(Default::default).extend(options);
+        engine.active_profile = Some(profile.name.clone());
+        Ok(profile)
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine_validator::EngineOption;
+
+    #[test]
+    fn test_find_for_runtime_id_matches_suffixed_ids() {
+        let mut storage = EngineStorage::default();
+        let mut config = EngineConfig::new("Engine".into(), "/engines/a".into(), None, false);
+        config.protocol = EngineProtocol::UciShogi;
+        let id = storage.add_engine(config).unwrap();
+        storage
+            .add_engine(EngineConfig::new("Other".into(), "/engines/b".into(), None, false))
+            .unwrap();
+
+        let runtime_id = format!("{}-1760000000000-k3x9", id);
+        let found = storage.find_for_runtime_id(&runtime_id).unwrap();
+        assert_eq!(found.id, id);
+        assert_eq!(found.protocol, EngineProtocol::UciShogi);
+        assert!(storage.get_engine(&runtime_id).is_none());
+        assert!(storage.find_for_runtime_id("unknown-engine").is_none());
+    }
+
+    #[test]
+    fn test_apply_profile_skips_undeclared_options() {
+        let mut storage = EngineStorage::default();
+        let metadata = EngineMetadata {
+            name: "Engine".into(),
+            author: None,
+            options: ["USI_Hash", "USI_Threads"]
+                .iter()
+                .filter_map(|name| EngineOption::parse(&format!("option name {} type spin", name)))
+                .collect(),
+            capabilities: None,
+            nps: None,
+        };
+        let config =
+            EngineConfig::new("Engine".into(), "/engines/a".into(), Some(metadata), false);
+        let id = storage.add_engine(config).unwrap();
+        let unvalidated = storage
+            .add_engine(EngineConfig::new("Other".into(), "/engines/b".into(), None, false))
+            .unwrap();
+
+        storage.apply_profile(&id, "Blitz").unwrap();
+        let engine = storage.get_engine(&id).unwrap();
+        let mut applied: Vec<_> = engine.saved_options.as_ref().unwrap().keys().collect();
+        applied.sort();
+        assert_eq!(applied, ["USI_Hash", "USI_Threads"]);
+        assert_eq!(engine.active_profile.as_deref(), Some("Blitz"));
+
+        // Without metadata nothing is known, so the whole profile is applied
+        storage.apply_profile(&unvalidated, "Blitz").unwrap();
+        let engine = storage.get_engine(&unvalidated).unwrap();
+        assert!(engine.saved_options.as_ref().unwrap().contains_key("CoachLevel"));
+    }
+}
@@ -1,10 +1,17 @@
+use crate::engine_protocol::EngineProtocol;
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use std::process::Stdio;
 use std::time::Duration;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, Lines};
 use tokio::process::Command;
-use tokio::time::timeout;
+use tokio::time::{timeout, Instant};
+
+/// Mate-in-one position (G*5b) used to check for `go mate` support
+const MATE_PROBE_SFEN: &str = "4k4/9/4P4/9/9/9/9/9/4K4 b G 1";
+
+/// Search time of the NPS benchmark
+const BENCHMARK_MOVETIME_MS: u64 = 1000;
 
 /// Engine metadata extracted during validation
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,6 +19,43 @@ pub struct EngineMetadata {
     pub name: String,
     pub author: Option<String>,
     pub options: Vec<EngineOption>,
+    /// Probed features; `None` for metadata recorded before capabilities were
+    /// probed, in which case nothing should be assumed either way
+    #[serde(default)]
+    pub capabilities: Option<EngineCapabilities>,
+    /// Nodes per second measured from the start position
+    #[serde(default)]
+    pub nps: Option<u64>,
+}
+
+/// Features beyond the core USI commands, so callers can avoid sending
+/// commands an engine does not understand
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EngineCapabilities {
+    /// Declares `USI_Ponder`, so `go ponder` may be sent
+    pub ponder: bool,
+    /// Answers `go mate` with a `checkmate` line
+    pub mate_search: bool,
+    /// Declares a `MultiPV` option
+    pub multipv: bool,
+    /// Answers the non-standard `stats` query
+    pub search_stats: bool,
+    /// Declares a `ScorePerspective` option, so its scores follow the one it is sent
+    #[serde(default)]
+    pub score_perspective: bool,
+}
+
+impl EngineCapabilities {
+    /// Capabilities that can be read off the declared options
+    fn from_options(options: &[EngineOption]) -> Self {
+        let declares = |names: &[&str]| options.iter().any(|option| names.contains(&option.name.as_str()));
+        Self {
+            ponder: declares(&["USI_Ponder"]),
+            multipv: declares(&["MultiPV", "USI_MultiPV"]),
+            score_perspective: declares(&["ScorePerspective"]),
+            ..Self::default()
+        }
+    }
 }
 
 /// USI engine option
@@ -111,9 +155,27 @@ impl EngineOption {
     }
 }
 
-/// Validate a USI engine and extract its metadata
+/// Validate a USI engine and extract its metadata, probing its capabilities
+/// and measuring its speed
 pub async fn validate_engine(path: &str) -> Result<EngineMetadata> {
-    log::info!("Validating engine at path: {}", path);
+    validate_engine_with_protocol(path, EngineProtocol::Usi).await
+}
+
+/// `validate_engine` for an engine speaking `protocol`, talked to through the
+/// protocol's adapter. The in-process engine always speaks USI.
+pub async fn validate_engine_with_protocol(
+    path: &str,
+    protocol: EngineProtocol,
+) -> Result<EngineMetadata> {
+    log::info!("Validating engine at path: {} ({:?})", path, protocol);
+
+    if crate::inprocess_engine::is_in_process(path) {
+        let (stdout, mut stdin) = tokio::io::split(crate::inprocess_engine::spawn_pipe("validator")?);
+        let mut lines = BufReader::new(stdout).lines();
+        let result = probe_engine(&mut stdin, &mut lines, EngineProtocol::Usi).await;
+        let _ = stdin.write_all(b"quit\n").await;
+        return result;
+    }
 
     // Check if the file exists
     if !std::path::Path::new(path).exists() {
@@ -138,51 +200,8 @@ pub async fn validate_engine(path: &str) -> Result<EngineMetadata> {
         .take()
         .ok_or_else(|| anyhow!("Failed to get stdout"))?;
 
-    // Send "usi" command
-    stdin
-        .write_all(b"usi\n")
-        .await
-        .map_err(|e| anyhow!("Failed to write to engine: {}", e))?;
-    stdin.flush().await?;
-
-    // Read and parse the response with timeout
-    let result = timeout(Duration::from_secs(5), async {
-        let reader = BufReader::new(stdout);
-        let mut lines = reader.lines();
-
-        let mut name = String::from("Unknown Engine");
-        let mut author = None;
-        let mut options = Vec::new();
-        let mut got_usiok = false;
-
-        while let Some(line) = lines.next_line().await? {
-            log::debug!("Engine validation output: {}", line);
-
-            if line.starts_with("id name ") {
-                name = line[8..].trim().to_string();
-            } else if line.starts_with("id author ") {
-                author = Some(line[10..].trim().to_string());
-            } else if line.starts_with("option name ") {
-                if let Some(option) = EngineOption::parse(&line) {
-                    options.push(option);
-                }
-            } else if line == "usiok" {
-                got_usiok = true;
-                break;
-            }
-        }
-
-        if !got_usiok {
-            return Err(anyhow!("Engine did not respond with 'usiok'"));
-        }
-
-        Ok::<EngineMetadata, anyhow::Error>(EngineMetadata {
-            name,
-            author,
-            options,
-        })
-    })
-    .await;
+    let mut lines = BufReader::new(stdout).lines();
+    let result = probe_engine(&mut stdin, &mut lines, protocol).await;
 
     // Try to kill the process gracefully
     let _ = stdin.write_all(b"quit\n").await;
@@ -190,15 +209,187 @@ pub async fn validate_engine(path: &str) -> Result<EngineMetadata> {
     tokio::time::sleep(Duration::from_millis(100)).await;
     let _ = child.kill().await;
 
-    match result {
-        Ok(Ok(metadata)) => {
-            log::info!("Engine validation successful: {}", metadata.name);
-            Ok(metadata)
+    result
+}
+
+/// Run the `usi` handshake, then the capability probes and the NPS benchmark
+async fn probe_engine<W, R>(
+    stdin: &mut W,
+    lines: &mut Lines<BufReader<R>>,
+    protocol: EngineProtocol,
+) -> Result<EngineMetadata>
+where
+    W: AsyncWrite + Unpin,
+    R: AsyncRead + Unpin,
+{
+    let response =
+        exchange(stdin, lines, protocol, "usi", Duration::from_secs(5), |line| line == "usiok")
+            .await?;
+    if !response.iter().any(|line| line == "usiok") {
+        return Err(anyhow!("Timeout waiting for engine response (5 seconds)"));
+    }
+    let mut metadata = metadata_from_usi_response(&response);
+    let mut capabilities = EngineCapabilities::from_options(&metadata.options);
+
+    // Failed probes only cost the capability, not the validation
+    let stats_limit = Duration::from_secs(1);
+    capabilities.search_stats = exchange(stdin, lines, protocol, "stats", stats_limit, |line| {
+        line.starts_with("info string stats")
+    })
+    .await
+    .map(|response| response.iter().any(|line| line.starts_with("info string stats")))
+    .unwrap_or(false);
+    synchronize(stdin, lines, protocol).await;
+
+    let _ = send(stdin, protocol, &format!("position sfen {}", MATE_PROBE_SFEN)).await;
+    let mate_limit = Duration::from_secs(3);
+    capabilities.mate_search = exchange(stdin, lines, protocol, "go mate 1000", mate_limit, |line| {
+        line.starts_with("checkmate")
+    })
+    .await
+    .map(|response| response.iter().any(|line| line.starts_with("checkmate ")))
+    .unwrap_or(false);
+    if !capabilities.mate_search {
+        let _ = send(stdin, protocol, "stop").await;
+    }
+    synchronize(stdin, lines, protocol).await;
+
+    metadata.nps = measure_nps(stdin, lines, protocol).await;
+    metadata.capabilities = Some(capabilities);
+
+    log::info!(
+        "Engine validation successful: {} ({:?}, {:?} nps)",
+        metadata.name,
+        metadata.capabilities,
+        metadata.nps
+    );
+    Ok(metadata)
+}
+
+/// Search the start position for a fixed time and report the engine's speed,
+/// from its last `nps` figure or else from nodes over time
+async fn measure_nps<W, R>(
+    stdin: &mut W,
+    lines: &mut Lines<BufReader<R>>,
+    protocol: EngineProtocol,
+) -> Option<u64>
+where
+    W: AsyncWrite + Unpin,
+    R: AsyncRead + Unpin,
+{
+    send(stdin, protocol, "position startpos").await.ok()?;
+    let started = Instant::now();
+    let response = exchange(
+        stdin,
+        lines,
+        protocol,
+        &format!("go movetime {}", BENCHMARK_MOVETIME_MS),
+        Duration::from_millis(BENCHMARK_MOVETIME_MS + 5000),
+        |line| line.starts_with("bestmove"),
+    )
+    .await
+    .ok()?;
+    let elapsed_ms = started.elapsed().as_millis().max(1) as u64;
+
+    let info_value = |line: &str, key: &str| {
+        let mut tokens = line.split_whitespace();
+        tokens.by_ref().find(|token| *token == key)?;
+        tokens.next()?.parse::<u64>().ok()
+    };
+    let info_lines = response.iter().rev().filter(|line| line.starts_with("info "));
+    info_lines.clone().find_map(|line| info_value(line, "nps")).or_else(|| {
+        let nodes = info_lines.clone().find_map(|line| info_value(line, "nodes"))?;
+        Some(nodes * 1000 / elapsed_ms)
+    })
+}
+
+/// Wait for the engine to finish whatever it is doing
+async fn synchronize<W, R>(
+    stdin: &mut W,
+    lines: &mut Lines<BufReader<R>>,
+    protocol: EngineProtocol,
+)
+where
+    W: AsyncWrite + Unpin,
+    R: AsyncRead + Unpin,
+{
+    let limit = Duration::from_secs(5);
+    let _ = exchange(stdin, lines, protocol, "isready", limit, |line| line == "readyok").await;
+}
+
+/// Send a USI command, translated into the engine's `protocol`
+async fn send<W: AsyncWrite + Unpin>(
+    stdin: &mut W,
+    protocol: EngineProtocol,
+    command: &str,
+) -> Result<()> {
+    for line in protocol.translate_command(command)? {
+        stdin
+            .write_all(format!("{}\n", line).as_bytes())
+            .await
+            .map_err(|e| anyhow!("Failed to write to engine: {}", e))?;
+    }
+    stdin.flush().await?;
+    Ok(())
+}
+
+/// Send `command` and collect output, translated back into USI, until a line
+/// satisfies `done` or `limit` passes. Lines read so far are returned on timeout.
+async fn exchange<W, R>(
+    stdin: &mut W,
+    lines: &mut Lines<BufReader<R>>,
+    protocol: EngineProtocol,
+    command: &str,
+    limit: Duration,
+    done: impl Fn(&str) -> bool,
+) -> Result<Vec<String>>
+where
+    W: AsyncWrite + Unpin,
+    R: AsyncRead + Unpin,
+{
+    send(stdin, protocol, command).await?;
+
+    let deadline = Instant::now() + limit;
+    let mut response = Vec::new();
+    while let Ok(line) = timeout(deadline.saturating_duration_since(Instant::now()), lines.next_line()).await {
+        let Some(line) = line? else {
+            return Err(anyhow!("Engine closed connection"));
+        };
+        log::debug!("Engine validation output: {}", line);
+        let Some(line) = protocol.translate_output(line.trim()) else {
+            continue;
+        };
+        let finished = done(&line);
+        response.push(line.trim().to_string());
+        if finished {
+            break;
         }
-        Ok(Err(e)) => Err(e),
-        Err(_) => Err(anyhow!(
-            "Timeout waiting for engine response (5 seconds)"
-        )),
+    }
+    Ok(response)
+}
+
+/// Build metadata from the lines an engine printed in answer to `usi`
+fn metadata_from_usi_response(lines: &[String]) -> EngineMetadata {
+    let mut name = String::from("Unknown Engine");
+    let mut author = None;
+    let mut options = Vec::new();
+
+    for line in lines {
+        if let Some(value) = line.strip_prefix("id name ") {
+            name = value.trim().to_string();
+        } else if let Some(value) = line.strip_prefix("id author ") {
+            author = Some(value.trim().to_string());
+        } else if let Some(option) = EngineOption::parse(line) {
+            options.push(option);
+        }
+    }
+
+    EngineMetadata {
+        name,
+        author,
+        options,
+        capabilities: None,
+        nps: None,
     }
 }
 
@@ -234,5 +425,28 @@ mod tests {
         assert_eq!(option.option_type, "string");
         assert_eq!(option.default, Some("book.bin".to_string()));
     }
+
+    #[test]
+    fn test_capabilities_from_options() {
+        let options: Vec<EngineOption> = [
+            "option name USI_Ponder type check default true",
+            "option name MultiPV type spin default 1 min 1 max 10",
+        ]
+        .iter()
+        .filter_map(|line| EngineOption::parse(line))
+        .collect();
+        let capabilities = EngineCapabilities::from_options(&options);
+        assert!(capabilities.ponder);
+        assert!(capabilities.multipv);
+        assert!(!capabilities.mate_search);
+    }
+
+    #[test]
+    fn test_metadata_without_capabilities_deserializes() {
+        let json = r#"{"name":"Old Engine","author":null,"options":[]}"#;
+        let metadata: EngineMetadata = serde_json::from_str(json).unwrap();
+        assert_eq!(metadata.capabilities, None);
+        assert_eq!(metadata.nps, None);
+    }
 }
 
@@ -3,18 +3,34 @@
  * Manages automated games between two engines with spectator mode
  */
 
+use crate::adjudication::{Adjudication, AdjudicationConfig, Adjudicator};
+use crate::engine_limits;
+use crate::engine_manager::{wait_or_kill, QUIT_GRACE_PERIOD};
+use crate::engine_protocol::EngineProtocol;
+use crate::usi_info::{UsiInfo, UsiScore};
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
+use shogi_engine::board_diff::BoardDiff;
+use shogi_engine::game_status::{GameTracker, PlayedMove};
+use shogi_engine::tablebase::MicroTablebase;
+use shogi_engine::types::Player;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tauri::{AppHandle, Emitter};
-use tokio::io::{AsyncWriteExt, BufReader};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
 use tokio::process::{Child, Command};
 use tokio::sync::Mutex;
 use tokio::time::timeout;
 
+/// Command stream of a match engine: a process's stdin or an in-process pipe
+type EngineInput = Box<dyn AsyncWrite + Unpin + Send>;
+/// Output stream of a match engine
+type EngineOutput = Box<dyn AsyncRead + Unpin + Send>;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EngineVsEngineState {
+    pub session_id: String,
     pub move_number: usize,
     pub current_player: String, // "black" or "white"
     pub position_sfen: String,
@@ -25,8 +41,46 @@ pub struct EngineVsEngineState {
     pub game_result: Option<String>,
 }
 
+/// What an engine reported while choosing a move: its last principal line and
+/// how long it took, sent to spectators with the move
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MoveReport {
+    pub best_move: String,
+    /// Wall-clock time from `go` to `bestmove`
+    pub time_ms: u64,
+    pub depth: Option<u32>,
+    pub seldepth: Option<u32>,
+    pub nodes: Option<u64>,
+    /// Score of the last principal line, as the engine reported it
+    pub score: Option<UsiScore>,
+    pub pv: Vec<String>,
+}
+
+impl MoveReport {
+    /// Take the search figures from an info line; only the main line counts
+    pub(crate) fn absorb(&mut self, info: &UsiInfo) {
+        if info.multipv.unwrap_or(1) != 1 {
+            return;
+        }
+        if info.depth.is_some() {
+            self.depth = info.depth;
+            self.seldepth = info.seldepth.or(self.seldepth);
+        }
+        if info.nodes.is_some() {
+            self.nodes = info.nodes;
+        }
+        if info.score.is_some() {
+            self.score = info.score.clone();
+        }
+        if !info.pv.is_empty() {
+            self.pv = info.pv.clone();
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EngineVsEngineConfig {
+    pub session_id: String,
     pub engine1_id: String,
     pub engine1_path: String,
     pub engine1_name: String,
@@ -36,6 +90,9 @@ pub struct EngineVsEngineConfig {
     pub initial_sfen: Option<String>,
     pub time_per_move_ms: u64,
     pub max_moves: usize,
+    /// When to end the match early; `max_moves` stays the hard cap
+    #[serde(default)]
+    pub adjudication: AdjudicationConfig,
 }
 
 pub struct EngineVsEngineManager {
@@ -45,14 +102,21 @@ pub struct EngineVsEngineManager {
     engine1: Option<Child>,
     engine2: Option<Child>,
     engine_storage: Arc<tokio::sync::RwLock<crate::engine_storage::EngineStorage>>,
+    cancel_flag: Arc<AtomicBool>,
 }
 
 impl EngineVsEngineManager {
-    pub fn new(app_handle: AppHandle, config: EngineVsEngineConfig, engine_storage: Arc<tokio::sync::RwLock<crate::engine_storage::EngineStorage>>) -> Self {
+    pub fn new(
+        app_handle: AppHandle,
+        config: EngineVsEngineConfig,
+        engine_storage: Arc<tokio::sync::RwLock<crate::engine_storage::EngineStorage>>,
+        cancel_flag: Arc<AtomicBool>,
+    ) -> Self {
         let initial_sfen = config.initial_sfen.clone()
             .unwrap_or_else(|| "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1".to_string());
 
         let state = EngineVsEngineState {
+            session_id: config.session_id.clone(),
             move_number: 1,
             current_player: "black".to_string(),
             position_sfen: initial_sfen,
@@ -70,57 +134,119 @@ impl EngineVsEngineManager {
             engine1: None,
             engine2: None,
             engine_storage,
+            cancel_flag,
+        }
+    }
+
+    /// Emit an event scoped to this match's session
+    fn emit_session_event<S: Serialize + Clone>(&self, event: &str, payload: S) {
+        let event_name = format!("{}::{}", event, self.config.session_id);
+        if let Err(e) = self.app_handle.emit(&event_name, payload) {
+            log::error!("Failed to emit {} event: {}", event, e);
         }
     }
 
     /// Spawn both engines
-    async fn spawn_engines(&mut self) -> Result<()> {
+    async fn spawn_engines(&mut self) -> Result<((EngineInput, EngineOutput), (EngineInput, EngineOutput))> {
         log::info!("Spawning engines for engine-vs-engine match");
         log::info!("Engine 1 path: {}", self.config.engine1_path);
         log::info!("Engine 2 path: {}", self.config.engine2_path);
 
-        // Spawn engine 1
-        // Set working directory to the engine's directory so it can find its files
-        let engine1_dir = std::path::Path::new(&self.config.engine1_path)
-            .parent()
-            .ok_or_else(|| anyhow!("Invalid engine 1 path"))?;
-        
-        let engine1 = Command::new(&self.config.engine1_path)
-            .current_dir(engine1_dir)
-            .stdin(std::process::Stdio::piped())
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
-            .kill_on_drop(true)
-            .spawn()
-            .map_err(|e| anyhow!("Failed to spawn engine 1: {}", e))?;
+        let (engine1, engine1_stdin, engine1_stdout) = Self::spawn_engine(&self.config.engine1_path, "engine 1")?;
+        self.limit_engine(engine1.as_ref(), &self.config.engine1_id, "engine 1").await;
+        self.engine1 = engine1;
+
+        let (engine2, engine2_stdin, engine2_stdout) = Self::spawn_engine(&self.config.engine2_path, "engine 2")?;
+        self.limit_engine(engine2.as_ref(), &self.config.engine2_id, "engine 2").await;
+        self.engine2 = engine2;
+
+        Ok(((engine1_stdin, engine1_stdout), (engine2_stdin, engine2_stdout)))
+    }
+
+    /// Apply an engine's stored resource limits to its process: lower its
+    /// priority and guard its memory for the rest of the match
+    async fn limit_engine(&self, process: Option<&Child>, engine_id: &str, label: &'static str) {
+        let Some(pid) = process.and_then(Child::id) else {
+            return;
+        };
+        let limits = self
+            .engine_storage
+            .read()
+            .await
+            .find_for_runtime_id(engine_id)
+            .map(|config| config.limits.clone())
+            .unwrap_or_default();
+        if let Err(e) = engine_limits::apply_priority(pid, limits.priority).await {
+            log::warn!("Failed to set the priority of {}: {}", label, e);
+        }
+        tokio::spawn(async move {
+            if let Some(memory_bytes) = engine_limits::guard_memory(pid, limits).await {
+                let memory_mb = memory_bytes / (1024 * 1024);
+                log::error!("{} killed after using {} MiB, over its memory cap", label, memory_mb);
+            }
+        });
+    }
+
+    /// Protocol an engine speaks, from its stored configuration
+    async fn engine_protocol(&self, engine_id: &str) -> EngineProtocol {
+        self.engine_storage
+            .read()
+            .await
+            .find_for_runtime_id(engine_id)
+            .map(|config| config.protocol)
+            .unwrap_or_default()
+    }
+
+    /// Send one USI command to an engine in its own protocol
+    async fn send_command(
+        stdin: &mut EngineInput,
+        protocol: EngineProtocol,
+        command: &str,
+    ) -> Result<()> {
+        for line in protocol.translate_command(command)? {
+            stdin.write_all(line.as_bytes()).await?;
+            stdin.write_all(b"\n").await?;
+        }
+        stdin.flush().await?;
+        Ok(())
+    }
 
-        log::info!("Engine 1 spawned successfully with working dir: {:?}", engine1_dir);
-        self.engine1 = Some(engine1);
+    /// Start one engine, as a child process or, for the built-in engine, in-process
+    fn spawn_engine(path: &str, label: &str) -> Result<(Option<Child>, EngineInput, EngineOutput)> {
+        if crate::inprocess_engine::is_in_process(path) {
+            let (stdout, stdin) = tokio::io::split(crate::inprocess_engine::spawn_pipe(label)?);
+            log::info!("{} started in-process", label);
+            return Ok((None, Box::new(stdin), Box::new(stdout)));
+        }
 
-        // Spawn engine 2
-        let engine2_dir = std::path::Path::new(&self.config.engine2_path)
+        // Set working directory to the engine's directory so it can find its files
+        let engine_dir = std::path::Path::new(path)
             .parent()
-            .ok_or_else(|| anyhow!("Invalid engine 2 path"))?;
-            
-        let engine2 = Command::new(&self.config.engine2_path)
-            .current_dir(engine2_dir)
+            .ok_or_else(|| anyhow!("Invalid {} path", label))?;
+
+        let mut engine = Command::new(path)
+            .current_dir(engine_dir)
             .stdin(std::process::Stdio::piped())
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
             .kill_on_drop(true)
             .spawn()
-            .map_err(|e| anyhow!("Failed to spawn engine 2: {}", e))?;
+            .map_err(|e| anyhow!("Failed to spawn {}: {}", label, e))?;
 
-        log::info!("Engine 2 spawned successfully");
-        self.engine2 = Some(engine2);
+        log::info!("{} spawned successfully with working dir: {:?}", label, engine_dir);
 
-        Ok(())
+        let stdin = engine.stdin.take()
+            .ok_or_else(|| anyhow!("Failed to get {} stdin", label))?;
+        let stdout = engine.stdout.take()
+            .ok_or_else(|| anyhow!("Failed to get {} stdout", label))?;
+        Ok((Some(engine), Box::new(stdin), Box::new(stdout)))
     }
 
     /// Initialize an engine with USI protocol and send saved options
     async fn initialize_engine_with_options(
-        stdin: &mut tokio::process::ChildStdin,
-        stdout: &mut tokio::process::ChildStdout,
+        stdin: &mut EngineInput,
+        stdout: &mut EngineOutput,
+        protocol: EngineProtocol,
         engine_id: &str,
         engine_storage: &tokio::sync::RwLock<crate::engine_storage::EngineStorage>,
     ) -> Result<()> {
@@ -132,8 +258,7 @@ impl EngineVsEngineManager {
         
         // Send usi command
         log::info!("Sending 'usi' command");
-        stdin.write_all(b"usi\n").await?;
-        stdin.flush().await?;
+        Self::send_command(stdin, protocol, "usi").await?;
         log::info!("'usi' command sent, waiting for response...");
 
         // Wait for usiok
@@ -146,9 +271,11 @@ impl EngineVsEngineManager {
             match timeout(Duration::from_millis(100), reader.read_line(&mut line)).await {
                 Ok(Ok(0)) => return Err(anyhow!("Engine closed connection")),
                 Ok(Ok(_)) => {
-                    let trimmed = line.trim();
-                    log::debug!("Engine init response: {}", trimmed);
-                    if trimmed == "usiok" {
+                    let Some(translated) = protocol.translate_output(line.trim()) else {
+                        continue;
+                    };
+                    log::debug!("Engine init response: {}", translated);
+                    if translated == "usiok" {
                         found_usiok = true;
                         break;
                     }
@@ -165,28 +292,31 @@ impl EngineVsEngineManager {
 
         log::info!("Received usiok, sending saved options");
 
-        // Send saved options if any
+        // Send saved options if any, held to the engine's resource limits
         let storage = engine_storage.read().await;
-        if let Some(options) = storage.get_engine_options(engine_id) {
+        let options = storage.find_for_runtime_id(engine_id).map(|config| {
+            let saved = config.saved_options.clone().unwrap_or_default();
+            let declared = config.metadata.as_ref().map_or(&[][..], |m| &m.options[..]);
+            config.limits.limit_options(&saved, declared)
+        });
+        if let Some(options) = options {
             if !options.is_empty() {
                 log::info!("Sending {} saved options to engine: {}", options.len(), engine_id);
                 for (option_name, option_value) in options {
-                    let option_command = format!("setoption name {} value {}\n", option_name, option_value);
-                    log::debug!("Sending option command: {}", option_command.trim());
-                    if let Err(e) = stdin.write_all(option_command.as_bytes()).await {
+                    let option_command = format!("setoption name {} value {}", option_name, option_value);
+                    log::debug!("Sending option command: {}", option_command);
+                    if let Err(e) = Self::send_command(stdin, protocol, &option_command).await {
                         log::warn!("Failed to send option '{}' to engine {}: {}", option_name, engine_id, e);
                         // Continue with other options even if one fails
                     }
                 }
-                stdin.flush().await?;
             }
         }
         drop(storage);
 
         log::info!("Sending 'isready' command");
         // Send isready
-        stdin.write_all(b"isready\n").await?;
-        stdin.flush().await?;
+        Self::send_command(stdin, protocol, "isready").await?;
         log::info!("'isready' command sent, waiting for response...");
 
         // Wait for readyok
@@ -198,9 +328,11 @@ impl EngineVsEngineManager {
             match timeout(Duration::from_millis(100), reader.read_line(&mut line)).await {
                 Ok(Ok(0)) => return Err(anyhow!("Engine closed connection")),
                 Ok(Ok(_)) => {
-                    let trimmed = line.trim();
-                    log::debug!("Engine ready response: {}", trimmed);
-                    if trimmed == "readyok" {
+                    let Some(translated) = protocol.translate_output(line.trim()) else {
+                        continue;
+                    };
+                    log::debug!("Engine ready response: {}", translated);
+                    if translated == "readyok" {
                         found_readyok = true;
                         break;
                     }
@@ -219,39 +351,41 @@ impl EngineVsEngineManager {
         Ok(())
     }
 
-    /// Request a move from an engine
+    /// Request a move from an engine, passing every info line it sends to
+    /// `on_info` as it thinks
     async fn request_move(
-        stdin: &mut tokio::process::ChildStdin,
-        stdout: &mut tokio::process::ChildStdout,
+        stdin: &mut EngineInput,
+        stdout: &mut EngineOutput,
+        protocol: EngineProtocol,
         position_sfen: &str,
         moves: &[String],
         time_ms: u64,
-    ) -> Result<String> {
+        mut on_info: impl FnMut(&UsiInfo),
+    ) -> Result<MoveReport> {
         use tokio::io::AsyncBufReadExt;
         
         // Build position command
         let pos_cmd = if moves.is_empty() {
-            format!("position sfen {}\n", position_sfen)
+            format!("position sfen {}", position_sfen)
         } else {
-            format!("position sfen {} moves {}\n", 
+            format!("position sfen {} moves {}", 
                 position_sfen.split(" moves").next().unwrap_or(position_sfen),
                 moves.join(" ")
             )
         };
 
-        stdin.write_all(pos_cmd.as_bytes()).await?;
-        stdin.flush().await?;
+        Self::send_command(stdin, protocol, &pos_cmd).await?;
 
         // Send go command
-        let go_cmd = format!("go btime {} wtime {}\n", time_ms, time_ms);
-        stdin.write_all(go_cmd.as_bytes()).await?;
-        stdin.flush().await?;
+        let go_cmd = format!("go btime {} wtime {}", time_ms, time_ms);
+        Self::send_command(stdin, protocol, &go_cmd).await?;
 
         // Wait for bestmove
         let mut reader = BufReader::new(stdout);
         let mut line = String::new();
         let timeout_duration = Duration::from_secs(time_ms / 1000 + 10);
         let start = tokio::time::Instant::now();
+        let mut report = MoveReport::default();
         
         while start.elapsed() < timeout_duration {
             line.clear();
@@ -259,12 +393,20 @@ impl EngineVsEngineManager {
             match timeout(Duration::from_millis(100), reader.read_line(&mut line)).await {
                 Ok(Ok(0)) => return Err(anyhow!("Engine closed connection")),
                 Ok(Ok(_)) => {
-                    let trimmed = line.trim();
+                    let Some(translated) = protocol.translate_output(line.trim()) else {
+                        continue;
+                    };
+                    let trimmed = translated.as_str();
                     log::debug!("Engine move response: {}", trimmed);
-                    if trimmed.starts_with("bestmove ") {
+                    if let Some(info) = UsiInfo::parse(trimmed) {
+                        report.absorb(&info);
+                        on_info(&info);
+                    } else if trimmed.starts_with("bestmove ") {
                         let parts: Vec<&str> = trimmed.split_whitespace().collect();
                         if parts.len() >= 2 {
-                            return Ok(parts[1].to_string());
+                            report.best_move = parts[1].to_string();
+                            report.time_ms = start.elapsed().as_millis() as u64;
+                            return Ok(report);
                         }
                     }
                 }
@@ -276,51 +418,75 @@ impl EngineVsEngineManager {
         Err(anyhow!("Timeout waiting for bestmove"))
     }
 
-    /// Run the engine-vs-engine match
-    pub async fn run_match(mut self) -> Result<()> {
+    /// Run the engine-vs-engine match, returning its final state
+    pub async fn run_match(mut self) -> Result<EngineVsEngineState> {
         log::info!("Starting engine-vs-engine match");
 
         // Spawn engines
-        self.spawn_engines().await?;
-
-        // Get stdin/stdout handles
-        let engine1_stdin = self.engine1.as_mut()
-            .and_then(|e| e.stdin.take())
-            .ok_or_else(|| anyhow!("Failed to get engine 1 stdin"))?;
-        let engine1_stdout = self.engine1.as_mut()
-            .and_then(|e| e.stdout.take())
-            .ok_or_else(|| anyhow!("Failed to get engine 1 stdout"))?;
-
-        let engine2_stdin = self.engine2.as_mut()
-            .and_then(|e| e.stdin.take())
-            .ok_or_else(|| anyhow!("Failed to get engine 2 stdin"))?;
-        let engine2_stdout = self.engine2.as_mut()
-            .and_then(|e| e.stdout.take())
-            .ok_or_else(|| anyhow!("Failed to get engine 2 stdout"))?;
-
-        let mut engine1_stdin = engine1_stdin;
-        let mut engine1_stdout = engine1_stdout;
-        let mut engine2_stdin = engine2_stdin;
-        let mut engine2_stdout = engine2_stdout;
+        let ((mut engine1_stdin, mut engine1_stdout), (mut engine2_stdin, mut engine2_stdout)) =
+            self.spawn_engines().await?;
+
+        let engine1_protocol = self.engine_protocol(&self.config.engine1_id).await;
+        let engine2_protocol = self.engine_protocol(&self.config.engine2_id).await;
 
         // Initialize both engines with saved options
-        Self::initialize_engine_with_options(&mut engine1_stdin, &mut engine1_stdout, &self.config.engine1_id, &self.engine_storage).await?;
-        Self::initialize_engine_with_options(&mut engine2_stdin, &mut engine2_stdout, &self.config.engine2_id, &self.engine_storage).await?;
+        Self::initialize_engine_with_options(
+            &mut engine1_stdin,
+            &mut engine1_stdout,
+            engine1_protocol,
+            &self.config.engine1_id,
+            &self.engine_storage,
+        )
+        .await?;
+        Self::initialize_engine_with_options(
+            &mut engine2_stdin,
+            &mut engine2_stdout,
+            engine2_protocol,
+            &self.config.engine2_id,
+            &self.engine_storage,
+        )
+        .await?;
 
         // Send usinewgame to both
-        engine1_stdin.write_all(b"usinewgame\n").await?;
-        engine1_stdin.flush().await?;
-        engine2_stdin.write_all(b"usinewgame\n").await?;
-        engine2_stdin.flush().await?;
+        Self::send_command(&mut engine1_stdin, engine1_protocol, "usinewgame").await?;
+        Self::send_command(&mut engine2_stdin, engine2_protocol, "usinewgame").await?;
 
         // Emit initial state
         {
             let state = self.state.lock().await;
-            let _ = self.app_handle.emit("engine-vs-engine-update", state.clone());
+            self.emit_session_event("engine-vs-engine-update", state.clone());
         }
 
+        // Follows the game to end it on mate, sennichite or impasse
+        let mut tracker = {
+            let state = self.state.lock().await;
+            let initial_sfen = state.position_sfen.split(" moves").next().unwrap_or_default();
+            match GameTracker::from_sfen(initial_sfen) {
+                Ok(tracker) => Some(tracker),
+                Err(e) => {
+                    log::warn!("Cannot follow the game from '{}': {}; game end detection is off", initial_sfen, e);
+                    None
+                }
+            }
+        };
+
+        let mut adjudicator = Adjudicator::new(self.config.adjudication.clone());
+        let mut tablebase = adjudicator.config().tablebase.then(MicroTablebase::new);
+
         // Main game loop
         for move_num in 1..=self.config.max_moves {
+            if self.cancel_flag.load(Ordering::Relaxed) {
+                log::info!(
+                    "Engine-vs-engine match in session {} cancelled",
+                    self.config.session_id
+                );
+                let mut state = self.state.lock().await;
+                state.game_over = true;
+                state.game_result = Some("Match cancelled".to_string());
+                self.emit_session_event("engine-vs-engine-update", state.clone());
+                break;
+            }
+
             let state_guard = self.state.lock().await;
             if state_guard.game_over {
                 break;
@@ -331,23 +497,35 @@ impl EngineVsEngineManager {
             drop(state_guard);
 
             // Select engine based on turn
-            let (stdin, stdout, engine_name) = if is_black_turn {
-                (&mut engine1_stdin, &mut engine1_stdout, &self.config.engine1_name)
+            let (stdin, stdout, protocol, engine_name) = if is_black_turn {
+                let name = &self.config.engine1_name;
+                (&mut engine1_stdin, &mut engine1_stdout, engine1_protocol, name)
             } else {
-                (&mut engine2_stdin, &mut engine2_stdout, &self.config.engine2_name)
+                let name = &self.config.engine2_name;
+                (&mut engine2_stdin, &mut engine2_stdout, engine2_protocol, name)
             };
 
             log::info!("Move {}: {} to move", move_num, if is_black_turn { "Black" } else { "White" });
 
-            // Request move from engine
-            let best_move = match Self::request_move(
+            // Request move from engine, relaying its thinking to spectators
+            let player = if is_black_turn { "black" } else { "white" };
+            let report = match Self::request_move(
                 stdin,
                 stdout,
+                protocol,
                 &current_sfen,
                 &move_history,
                 self.config.time_per_move_ms,
+                |info| {
+                    self.emit_session_event("engine-vs-engine-thinking", serde_json::json!({
+                        "engine": engine_name,
+                        "player": player,
+                        "move_number": move_num,
+                        "info": info,
+                    }));
+                },
             ).await {
-                Ok(mv) => mv,
+                Ok(report) => report,
                 Err(e) => {
                     log::error!("Error getting move from {}: {}", engine_name, e);
                     // Engine error - opponent wins
@@ -355,18 +533,20 @@ impl EngineVsEngineManager {
                     state.game_over = true;
                     state.winner = Some(if is_black_turn { "white".to_string() } else { "black".to_string() });
                     state.game_result = Some(format!("{} failed to respond", engine_name));
-                    let _ = self.app_handle.emit("engine-vs-engine-update", state.clone());
+                    self.emit_session_event("engine-vs-engine-update", state.clone());
                     break;
                 }
             };
 
+            let best_move = report.best_move.clone();
+
             // Check for resignation
             if best_move == "resign" {
                 let mut state = self.state.lock().await;
                 state.game_over = true;
                 state.winner = Some(if is_black_turn { "white".to_string() } else { "black".to_string() });
                 state.game_result = Some(format!("{} resigned", engine_name));
-                let _ = self.app_handle.emit("engine-vs-engine-update", state.clone());
+                self.emit_session_event("engine-vs-engine-update", state.clone());
                 log::info!("Game over: {} resigned", engine_name);
                 break;
             }
@@ -387,12 +567,77 @@ impl EngineVsEngineManager {
                     state.position_sfen = format!("{} moves {}", initial_sfen, state.move_history.join(" "));
                 }
 
+                let before = tracker.as_ref().map(GameTracker::position_text);
+                let played = tracker.as_mut().map(|tracker| tracker.play_usi(&best_move));
+                // Spectators update their board from the diff instead of the SFEN
+                let diff = match (&before, &played, tracker.as_ref()) {
+                    (Some(before), Some(Ok(_)), Some(tracker)) => {
+                        Some(BoardDiff::between(before, &tracker.position_text()))
+                    }
+                    _ => None,
+                };
+                match &played {
+                    Some(Ok(PlayedMove { status, .. })) if status.is_over() => {
+                        state.game_over = true;
+                        state.winner = Some(match status.winner() {
+                            Some(Player::Black) => "black".to_string(),
+                            Some(Player::White) => "white".to_string(),
+                            None => "draw".to_string(),
+                        });
+                        state.game_result = Some(status.reason().to_string());
+                        log::info!("Game over: {}", status.reason());
+                    }
+                    Some(Err(e)) => {
+                        state.game_over = true;
+                        state.winner = Some(if is_black_turn { "white".to_string() } else { "black".to_string() });
+                        state.game_result = Some(format!("{} played an illegal move ({})", engine_name, e));
+                        log::info!("Game over: {} played an illegal move: {}", engine_name, best_move);
+                    }
+                    _ => {}
+                }
+
+                if !state.game_over {
+                    let mover = if is_black_turn { Player::Black } else { Player::White };
+                    let adjudication = adjudicator.record(mover, report.score.as_ref()).or_else(|| {
+                        let tracker = tracker.as_ref()?;
+                        let result = tracker.probe_tablebase(tablebase.as_mut()?)?;
+                        adjudicator.tablebase(tracker.side_to_move(), &result)
+                    });
+                    match adjudication {
+                        Some(Adjudication::Win { winner, reason }) => {
+                            state.game_over = true;
+                            state.winner = Some(match winner {
+                                Player::Black => "black".to_string(),
+                                Player::White => "white".to_string(),
+                            });
+                            log::info!("Game over: {}", reason);
+                            state.game_result = Some(reason);
+                        }
+                        Some(Adjudication::Draw { reason }) => {
+                            state.game_over = true;
+                            state.winner = Some("draw".to_string());
+                            log::info!("Game over: {}", reason);
+                            state.game_result = Some(reason);
+                        }
+                        None => {}
+                    }
+                }
+
                 // Emit update
-                let _ = self.app_handle.emit("engine-vs-engine-update", state.clone());
-                let _ = self.app_handle.emit("engine-vs-engine-move", serde_json::json!({
+                self.emit_session_event("engine-vs-engine-update", state.clone());
+                self.emit_session_event("engine-vs-engine-move", serde_json::json!({
                     "move": best_move,
                     "engine": engine_name,
+                    "player": player,
                     "move_number": move_num,
+                    "time_ms": report.time_ms,
+                    "depth": report.depth,
+                    "seldepth": report.seldepth,
+                    "nodes": report.nodes,
+                    "score": report.score,
+                    "pv": report.pv,
+                    "metadata": played.and_then(Result::ok).map(|played| played.metadata),
+                    "diff": diff,
                 }));
             }
 
@@ -409,25 +654,56 @@ impl EngineVsEngineManager {
                 state.game_over = true;
                 state.game_result = Some("Maximum moves reached".to_string());
                 state.winner = Some("draw".to_string());
-                let _ = self.app_handle.emit("engine-vs-engine-update", state.clone());
+                self.emit_session_event("engine-vs-engine-update", state.clone());
             }
+
+            // One final event however the game ended, for the result banner and the record
+            self.emit_session_event("engine-vs-engine-result", serde_json::json!({
+                "winner": state.winner,
+                "result": state.game_result,
+                "moves": state.move_history,
+                "position_sfen": state.position_sfen,
+            }));
         }
 
         // Cleanup engines
-        let _ = engine1_stdin.write_all(b"quit\n").await;
-        let _ = engine1_stdin.flush().await;
-        let _ = engine2_stdin.write_all(b"quit\n").await;
-        let _ = engine2_stdin.flush().await;
+        let _ = Self::send_command(&mut engine1_stdin, engine1_protocol, "quit").await;
+        let _ = Self::send_command(&mut engine2_stdin, engine2_protocol, "quit").await;
 
-        if let Some(mut proc) = self.engine1.take() {
-            let _ = proc.kill().await;
-        }
-        if let Some(mut proc) = self.engine2.take() {
-            let _ = proc.kill().await;
-        }
+        let (engine1, engine2) = (self.engine1.take(), self.engine2.take());
+        let wait_engine = |process: Option<Child>, label: &'static str| async move {
+            if let Some(mut process) = process {
+                wait_or_kill(&mut process, label, QUIT_GRACE_PERIOD).await;
+            }
+        };
+        tokio::join!(wait_engine(engine1, "engine 1"), wait_engine(engine2, "engine 2"));
 
         log::info!("Engine-vs-engine match completed");
-        Ok(())
+        let final_state = self.state.lock().await.clone();
+        Ok(final_state)
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_report_keeps_the_main_line() {
+        let mut report = MoveReport::default();
+        for line in [
+            "info depth 8 seldepth 12 score cp 40 nodes 5000 pv 7g7f 3c3d",
+            "info depth 9 multipv 2 score cp -80 pv 2g2f",
+            "info currmove 2g2f currmovenumber 3",
+            "info depth 9 score cp 55 nodes 9000 multipv 1 pv 2g2f 8c8d",
+        ] {
+            report.absorb(&UsiInfo::parse(line).unwrap());
+        }
+        assert_eq!(report.depth, Some(9));
+        assert_eq!(report.seldepth, Some(12));
+        assert_eq!(report.nodes, Some(9000));
+        assert_eq!(report.score, Some(UsiScore::Cp(55)));
+        assert_eq!(report.pv, vec!["2g2f", "8c8d"]);
+    }
+}
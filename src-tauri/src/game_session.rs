@@ -0,0 +1,172 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Session used by commands that don't pass an explicit session id
+pub const DEFAULT_SESSION_ID: &str = "default";
+
+/// What a game session is used for
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionKind {
+    HumanVsEngine,
+    EngineVsEngine,
+    Analysis,
+}
+
+/// A single board/tab in the UI with its own set of engine instances
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameSession {
+    pub id: String,
+    pub kind: SessionKind,
+    pub label: Option<String>,
+    /// Runtime ids of the engines owned by this session
+    pub engine_ids: Vec<String>,
+    pub created_at: String,
+    /// Set when the session is closed so background tasks (e.g. matches) can wind down
+    #[serde(skip)]
+    cancel_flag: Arc<AtomicBool>,
+}
+
+impl GameSession {
+    pub fn new(id: String, kind: SessionKind, label: Option<String>) -> Self {
+        Self {
+            id,
+            kind,
+            label,
+            engine_ids: Vec::new(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            cancel_flag: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Flag checked by long-running tasks belonging to this session
+    pub fn cancel_flag(&self) -> Arc<AtomicBool> {
+        self.cancel_flag.clone()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel_flag.load(Ordering::Relaxed)
+    }
+}
+
+/// Registry of all concurrent game sessions
+pub struct SessionManager {
+    sessions: RwLock<HashMap<String, GameSession>>,
+}
+
+impl SessionManager {
+    pub fn new() -> Self {
+        let mut sessions = HashMap::new();
+        sessions.insert(
+            DEFAULT_SESSION_ID.to_string(),
+            GameSession::new(DEFAULT_SESSION_ID.to_string(), SessionKind::HumanVsEngine, None),
+        );
+        Self {
+            sessions: RwLock::new(sessions),
+        }
+    }
+
+    /// Create a new session with a fresh id
+    pub async fn create_session(&self, kind: SessionKind, label: Option<String>) -> GameSession {
+        let session = GameSession::new(Uuid::new_v4().to_string(), kind, label);
+        self.sessions
+            .write()
+            .await
+            .insert(session.id.clone(), session.clone());
+        log::info!("Created game session: {}", session.id);
+        session
+    }
+
+    /// Bring back a session saved before the app last closed, under its old
+    /// id. Its engines did not survive the restart, so it starts with none.
+    pub async fn restore_session(&self, mut session: GameSession) {
+        session.engine_ids.clear();
+        session.cancel_flag = Arc::new(AtomicBool::new(false));
+        log::info!("Restored game session: {}", session.id);
+        self.sessions
+            .write()
+            .await
+            .insert(session.id.clone(), session);
+    }
+
+    /// Get a snapshot of a session
+    pub async fn get_session(&self, session_id: &str) -> Option<GameSession> {
+        self.sessions.read().await.get(session_id).cloned()
+    }
+
+    /// List snapshots of all sessions
+    pub async fn list_sessions(&self) -> Vec<GameSession> {
+        self.sessions.read().await.values().cloned().collect()
+    }
+
+    /// Resolve an optional session id from a command, falling back to the default session
+    pub async fn resolve(&self, session_id: Option<&str>) -> Result<String> {
+        let session_id = session_id.unwrap_or(DEFAULT_SESSION_ID);
+        if self.sessions.read().await.contains_key(session_id) {
+            Ok(session_id.to_string())
+        } else {
+            Err(anyhow!("Session not found: {}", session_id))
+        }
+    }
+
+    /// Record that an engine instance belongs to a session
+    pub async fn attach_engine(&self, session_id: &str, engine_id: &str) -> Result<()> {
+        let mut sessions = self.sessions.write().await;
+        let session = sessions
+            .get_mut(session_id)
+            .ok_or_else(|| anyhow!("Session not found: {}", session_id))?;
+        if !session.engine_ids.iter().any(|id| id == engine_id) {
+            session.engine_ids.push(engine_id.to_string());
+        }
+        Ok(())
+    }
+
+    /// Forget an engine instance, whichever session it belongs to
+    pub async fn detach_engine(&self, engine_id: &str) {
+        let mut sessions = self.sessions.write().await;
+        for session in sessions.values_mut() {
+            session.engine_ids.retain(|id| !id.starts_with(engine_id));
+        }
+    }
+
+    /// Check whether an engine (runtime id or config id prefix) belongs to a session
+    pub async fn owns_engine(&self, session_id: &str, engine_id: &str) -> bool {
+        self.sessions
+            .read()
+            .await
+            .get(session_id)
+            .map(|session| session.engine_ids.iter().any(|id| id.starts_with(engine_id)))
+            .unwrap_or(false)
+    }
+
+    /// Remove a session and signal its background tasks to stop.
+    /// The default session is reset rather than removed.
+    pub async fn close_session(&self, session_id: &str) -> Result<GameSession> {
+        let mut sessions = self.sessions.write().await;
+        let session = sessions
+            .remove(session_id)
+            .ok_or_else(|| anyhow!("Session not found: {}", session_id))?;
+        session.cancel_flag.store(true, Ordering::Relaxed);
+
+        if session_id == DEFAULT_SESSION_ID {
+            sessions.insert(
+                DEFAULT_SESSION_ID.to_string(),
+                GameSession::new(DEFAULT_SESSION_ID.to_string(), SessionKind::HumanVsEngine, None),
+            );
+        }
+
+        log::info!("Closed game session: {}", session_id);
+        Ok(session)
+    }
+}
+
+impl Default for SessionManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
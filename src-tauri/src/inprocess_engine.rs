@@ -0,0 +1,132 @@
+//! In-process built-in engine
+//!
+//! Runs the `shogi-engine` USI handler on a dedicated thread inside the app
+//! instead of spawning the `usi-engine` executable, so the built-in engine no
+//! longer depends on finding a binary next to the app. Commands go in over a
+//! channel and the handler's output comes back as lines, which the engine
+//! manager processes exactly like the stdout of an external engine.
+
+use anyhow::{anyhow, Result};
+use shogi_engine::search::info_sink;
+use shogi_engine::usi::UsiHandler;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self as std_mpsc, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream};
+use tokio::sync::mpsc;
+
+/// Engine path that selects the in-process built-in engine
+pub const IN_PROCESS_ENGINE_PATH: &str = "builtin:in-process";
+
+/// How often the engine thread checks whether a timed search has finished
+const SEARCH_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+pub fn is_in_process(path: &str) -> bool {
+    path == IN_PROCESS_ENGINE_PATH
+}
+
+/// Handle to an engine running on its own thread
+#[derive(Debug)]
+pub struct InProcessEngine {
+    command_tx: std_mpsc::Sender<String>,
+    stop_flag: Arc<AtomicBool>,
+}
+
+impl InProcessEngine {
+    /// Start the engine thread; its output lines are sent to `output_tx`
+    pub fn spawn(engine_id: &str, output_tx: mpsc::UnboundedSender<String>) -> Result<Self> {
+        let mut handler = UsiHandler::new();
+        // Searches run on worker threads so this thread keeps answering
+        // `isready`, `stats` and the health probes while the engine thinks
+        handler.set_background_timed_search(true);
+        let stop_flag = handler.stop_flag();
+        let (command_tx, command_rx) = std_mpsc::channel::<String>();
+
+        std::thread::Builder::new()
+            .name(format!("engine-{}", engine_id))
+            .spawn(move || {
+                // Search info lines of this engine, and of the background searches
+                // it starts, go to its own output
+                let info_tx = output_tx.clone();
+                info_sink::set_thread_info_sink(Some(Arc::new(move |line: &str| {
+                    let _ = info_tx.send(line.to_string());
+                })));
+
+                loop {
+                    let command = if handler.timed_search_pending() {
+                        match command_rx.recv_timeout(SEARCH_POLL_INTERVAL) {
+                            Ok(command) => Some(command),
+                            Err(RecvTimeoutError::Timeout) => None,
+                            Err(RecvTimeoutError::Disconnected) => break,
+                        }
+                    } else {
+                        match command_rx.recv() {
+                            Ok(command) => Some(command),
+                            Err(_) => break,
+                        }
+                    };
+
+                    let mut output = handler.poll_timed_search();
+                    if let Some(command) = command {
+                        let command = command.trim();
+                        if command == "quit" {
+                            break;
+                        }
+                        output.extend(handler.handle_command(command));
+                    }
+                    for line in output {
+                        if output_tx.send(line).is_err() {
+                            return;
+                        }
+                    }
+                }
+            })
+            .map_err(|e| anyhow!("Failed to start in-process engine thread: {}", e))?;
+
+        Ok(Self { command_tx, stop_flag })
+    }
+
+    /// Queue a USI command for the engine thread
+    pub fn send(&self, command: &str) -> Result<()> {
+        // Stop a running search right away rather than when the engine thread
+        // gets to the command
+        if matches!(command.trim(), "stop" | "quit") {
+            self.stop_flag.store(true, Ordering::Relaxed);
+        }
+        self.command_tx
+            .send(command.to_string())
+            .map_err(|_| anyhow!("In-process engine has exited"))
+    }
+}
+
+/// Start an engine thread behind an in-memory pipe, for callers that talk USI
+/// over a byte stream (the engine-vs-engine runner) rather than through the
+/// engine manager. Closing the pipe quits the engine.
+pub fn spawn_pipe(engine_id: &str) -> Result<DuplexStream> {
+    let (output_tx, mut output_rx) = mpsc::unbounded_channel::<String>();
+    let engine = InProcessEngine::spawn(engine_id, output_tx)?;
+    let (client, server) = tokio::io::duplex(64 * 1024);
+    let (server_read, mut server_write) = tokio::io::split(server);
+
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(server_read).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if engine.send(&line).is_err() {
+                return;
+            }
+        }
+        let _ = engine.send("quit");
+    });
+
+    tokio::spawn(async move {
+        while let Some(line) = output_rx.recv().await {
+            let written = server_write.write_all(line.as_bytes()).await;
+            if written.is_err() || server_write.write_all(b"\n").await.is_err() {
+                break;
+            }
+        }
+    });
+
+    Ok(client)
+}
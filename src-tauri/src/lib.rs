@@ -1,18 +1,139 @@
+mod adjudication;
+mod auto_analysis;
+mod blindfold;
+mod capabilities;
 mod commands;
+mod correlation;
+mod deep_link;
+mod engine_health;
+mod engine_limits;
+mod engine_log;
 mod engine_manager;
+mod engine_profiles;
+mod engine_protocol;
 mod engine_storage;
 mod engine_validator;
 mod engine_vs_engine;
+mod game_session;
+mod inprocess_engine;
+mod session_events;
+mod session_store;
 mod state;
+mod takeback;
+mod tournament;
+mod tsume_trainer;
+mod usi_info;
 
 use engine_manager::EngineManager;
 use engine_storage::EngineStorage;
 use state::AppState;
 use tauri::Manager;
+use tauri_plugin_deep_link::DeepLinkExt;
+
+/// Every command the frontend can invoke. `get_backend_capabilities` lists the
+/// same names, so they are written down once, here.
+macro_rules! command_handlers {
+  ($($command:ident),* $(,)?) => {
+    pub(crate) const COMMAND_NAMES: &[&str] = &[$(stringify!($command)),*];
+
+    fn invoke_handler() -> impl Fn(tauri::ipc::Invoke) -> bool + Send + Sync + 'static {
+      tauri::generate_handler![$(commands::$command),*]
+    }
+  };
+}
+
+command_handlers![
+  get_backend_capabilities,
+  set_locale,
+  get_locale,
+  spawn_engine,
+  send_usi_command,
+  stop_engine,
+  get_engine_status,
+  list_engines,
+  stop_all_engines,
+  get_builtin_engine_path,
+  add_engine,
+  remove_engine,
+  get_engines,
+  validate_engine_path,
+  register_builtin_engine,
+  health_check_engines,
+  get_engine_health,
+  get_engine_log,
+  export_engine_log,
+  clear_engine_log,
+  get_search_statistics,
+  get_statistics_hub,
+  get_move_hints,
+  get_hint,
+  get_candidate_moves,
+  get_mate_meter,
+  get_position_sharpness,
+  get_game_status,
+  format_moves,
+  convert_move_notation,
+  export_position,
+  get_board_diff,
+  import_position,
+  set_position_from_matrix,
+  open_deep_link,
+  take_deep_links,
+  get_opening,
+  get_tsume_problem,
+  submit_tsume_moves,
+  get_tsume_stats,
+  create_tournament,
+  start_tournament,
+  pause_tournament,
+  get_tournament,
+  list_tournaments,
+  delete_tournament,
+  record_move_score,
+  get_score_trend,
+  reset_score_trend,
+  record_move_time,
+  get_move_times,
+  reset_move_times,
+  export_kif,
+  start_auto_analysis,
+  stop_auto_analysis,
+  get_auto_analysis_progress,
+  start_engine_vs_engine,
+  create_session,
+  list_sessions,
+  close_session,
+  save_session_snapshot,
+  get_saved_sessions,
+  take_back_moves,
+  set_takeback_limit,
+  set_blindfold_mode,
+  set_session_repertoire,
+  get_session_position,
+  play_session_move,
+  save_engine_options,
+  set_engine_resource_limits,
+  get_engine_options,
+  list_engine_profiles,
+  save_engine_profile,
+  apply_engine_profile,
+  clone_engine,
+  update_engine_display_name,
+  set_favorite_engine,
+  revalidate_engine_metadata,
+];
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-  tauri::Builder::default()
+  let mut builder = tauri::Builder::default();
+  // A second launch hands its shogi:// link to the running app
+  #[cfg(desktop)]
+  {
+    builder = builder.plugin(tauri_plugin_single_instance::init(|_app, _argv, _cwd| {}));
+  }
+
+  builder
+    .plugin(tauri_plugin_deep_link::init())
     .plugin(tauri_plugin_dialog::init())
     .setup(|app| {
       if cfg!(debug_assertions) {
@@ -35,107 +156,156 @@ pub fn run() {
         }
       };
       
-      // Auto-register built-in engine if not present, or fix path if it's incorrect
-      // Get the correct path first
-      let correct_path = if cfg!(debug_assertions) {
-        commands::find_workspace_root()
-          .map(|workspace_root| workspace_root.join("target/release/usi-engine"))
-          .filter(|engine_path| engine_path.exists())
-          .map(|engine_path| engine_path.display().to_string())
-      } else {
-        None
-      };
-      
+      // Auto-register the built-in engine if not present. It runs in-process unless
+      // SHOGI_EXTERNAL_ENGINE asks for the usi-engine executable; registrations
+      // pointing elsewhere are moved over to the chosen path.
+      // Its metadata is filled in once the app is up, as validating runs the
+      // engine's NPS benchmark.
+      let builtin_path = commands::builtin_engine_path(app.handle());
+      let builtin_path = builtin_path.as_str();
+      let mut validate_builtin = false;
+
       if !engine_storage.has_builtin_engine() {
         log::info!("Built-in engine not registered, registering now...");
-        
-        if let Some(engine_path) = correct_path.as_ref() {
-          log::info!("Found built-in engine at: {}", engine_path);
-          
-          // Validate the engine
-          let metadata = tauri::async_runtime::block_on(
-            crate::engine_validator::validate_engine(&engine_path)
-          ).ok();
-          
-          // Create config
-          let config = crate::engine_storage::EngineConfig::new(
-            "Built-in Engine".to_string(),
-            engine_path.clone(),
-            metadata,
-            true,
-          );
-          
-          // Add to storage
-          if let Ok(_) = engine_storage.add_engine(config) {
-            // Save to disk
-            if let Err(e) = tauri::async_runtime::block_on(engine_storage.save()) {
-              log::error!("Failed to save engine storage: {}", e);
-            } else {
-              log::info!("Built-in engine registered successfully");
-            }
+
+        let config = crate::engine_storage::EngineConfig::new(
+          "Built-in Engine".to_string(),
+          builtin_path.to_string(),
+          None,
+          true,
+        );
+        validate_builtin = true;
+
+        // Add to storage
+        if let Ok(_) = engine_storage.add_engine(config) {
+          // Save to disk
+          if let Err(e) = tauri::async_runtime::block_on(engine_storage.save()) {
+            log::error!("Failed to save engine storage: {}", e);
+          } else {
+            log::info!("Built-in engine registered successfully");
           }
-        } else {
-          log::warn!("Could not find built-in engine executable");
         }
-      } else if let Some(correct_path) = correct_path.as_ref() {
-        // Builtin engine exists - check if path needs updating
-        if let Some(builtin_engine) = engine_storage.engines.iter_mut().find(|e| e.is_builtin) {
-          let path_exists = std::path::Path::new(&builtin_engine.path).exists();
-          let path_is_correct = builtin_engine.path == *correct_path;
-          
-          if !path_is_correct || !path_exists {
-            log::info!("Updating built-in engine path from '{}' to '{}'", builtin_engine.path, correct_path);
-            builtin_engine.path = correct_path.clone();
-            
-            // Validate the engine and update metadata
-            let metadata = tauri::async_runtime::block_on(
-              crate::engine_validator::validate_engine(correct_path)
-            ).ok();
-            builtin_engine.metadata = metadata;
-            
-            // Save to disk
-            if let Err(e) = tauri::async_runtime::block_on(engine_storage.save()) {
-              log::error!("Failed to save engine storage: {}", e);
-            } else {
-              log::info!("Built-in engine path updated successfully");
-            }
+      } else if let Some(builtin_engine) = engine_storage.engines.iter_mut().find(|e| e.is_builtin) {
+        if builtin_engine.path != builtin_path {
+          log::info!("Updating built-in engine path from '{}' to '{}'", builtin_engine.path, builtin_path);
+          builtin_engine.path = builtin_path.to_string();
+          builtin_engine.metadata = None;
+          validate_builtin = true;
+
+          // Save to disk
+          if let Err(e) = tauri::async_runtime::block_on(engine_storage.save()) {
+            log::error!("Failed to save engine storage: {}", e);
           } else {
-            log::info!("Built-in engine already has correct path: {}", correct_path);
+            log::info!("Built-in engine path updated successfully");
           }
+        } else {
+          log::info!("Built-in engine already has correct path: {}", builtin_path);
         }
       }
       
-      let app_state = AppState::new(engine_manager, engine_storage);
+      let tsume_stats = match tauri::async_runtime::block_on(tsume_trainer::TsumeStats::load()) {
+        Ok(stats) => stats,
+        Err(e) => {
+          log::error!("Failed to load tsume statistics: {}", e);
+          tsume_trainer::TsumeStats::default()
+        }
+      };
+
+      let tournaments = match tauri::async_runtime::block_on(tournament::TournamentStore::load()) {
+        Ok(tournaments) => tournaments,
+        Err(e) => {
+          log::error!("Failed to load tournaments: {}", e);
+          tournament::TournamentStore::default()
+        }
+      };
+
+      let session_store = match tauri::async_runtime::block_on(session_store::SessionStore::load()) {
+        Ok(store) => store,
+        Err(e) => {
+          log::error!("Failed to load saved sessions: {}", e);
+          session_store::SessionStore::default()
+        }
+      };
+
+      let app_state = AppState::new(engine_manager, engine_storage, tsume_stats, tournaments, session_store);
+
+      // Games left open when the app last closed, saved again as they change
+      tauri::async_runtime::block_on(async {
+        app_state
+          .session_store
+          .read()
+          .await
+          .restore(&app_state.sessions, &app_state.move_times)
+          .await
+      });
+      tauri::async_runtime::spawn(session_store::autosave(app_state.session_store.clone()));
+
+      // Spare-time analysis of enrolled games, for as long as the app runs
+      let auto_analysis = auto_analysis::AutoAnalysisRunner {
+        app_handle: app.handle().clone(),
+        queue: app_state.auto_analysis.clone(),
+        engine_manager: app_state.engine_manager.clone(),
+        background_games: app_state.background_games.clone(),
+        score_trends: app_state.score_trends.clone(),
+      };
+      tauri::async_runtime::spawn(auto_analysis.run());
+
+      if validate_builtin {
+        let engine_storage = app_state.engine_storage.clone();
+        let builtin_path = builtin_path.to_string();
+        tauri::async_runtime::spawn(async move {
+          let metadata = match crate::engine_validator::validate_engine(&builtin_path).await {
+            Ok(metadata) => metadata,
+            Err(e) => {
+              log::error!("Failed to validate built-in engine: {}", e);
+              return;
+            }
+          };
+          let mut engine_storage = engine_storage.write().await;
+          let builtin = engine_storage
+            .engines
+            .iter_mut()
+            .find(|e| e.is_builtin && e.path == builtin_path);
+          if let Some(builtin_engine) = builtin {
+            builtin_engine.metadata = Some(metadata);
+            if let Err(e) = engine_storage.save().await {
+              log::error!("Failed to save engine storage: {}", e);
+            }
+          }
+        });
+      }
 
       // Store state
       app.manage(app_state);
 
+      // shogi:// links the app is launched with and ones arriving later
+      #[cfg(any(windows, target_os = "linux"))]
+      if let Err(e) = app.deep_link().register_all() {
+        log::warn!("Failed to register {}:// links: {}", deep_link::DEEP_LINK_SCHEME, e);
+      }
+      let link_handle = app.handle().clone();
+      app.deep_link().on_open_url(move |event| {
+        let urls = event.urls().iter().map(|url| url.to_string()).collect();
+        tauri::async_runtime::spawn(deep_link::handle_urls(link_handle.clone(), urls));
+      });
+      if let Ok(Some(urls)) = app.deep_link().get_current() {
+        let urls = urls.iter().map(|url| url.to_string()).collect();
+        tauri::async_runtime::spawn(deep_link::handle_urls(app.handle().clone(), urls));
+      }
+
       log::info!("Shogi Game backend initialized");
 
       Ok(())
     })
-    .invoke_handler(tauri::generate_handler![
-      commands::spawn_engine,
-      commands::send_usi_command,
-      commands::stop_engine,
-      commands::get_engine_status,
-      commands::list_engines,
-      commands::stop_all_engines,
-      commands::get_builtin_engine_path,
-      commands::add_engine,
-      commands::remove_engine,
-      commands::get_engines,
-      commands::validate_engine_path,
-      commands::register_builtin_engine,
-      commands::health_check_engines,
-      commands::start_engine_vs_engine,
-      commands::save_engine_options,
-      commands::get_engine_options,
-      commands::clone_engine,
-      commands::update_engine_display_name,
-      commands::set_favorite_engine,
-      commands::revalidate_engine_metadata,
-    ])
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
+    .invoke_handler(invoke_handler())
+    .build(tauri::generate_context!())
+    .expect("error while building tauri application")
+    .run(|app_handle, event| {
+      // Let engines exit on their own and flush saves before the process ends
+      if let tauri::RunEvent::Exit = event {
+        if let Some(state) = app_handle.try_state::<AppState>() {
+          tauri::async_runtime::block_on(state.shutdown());
+        }
+      }
+    });
 }
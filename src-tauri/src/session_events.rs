@@ -0,0 +1,277 @@
+//! Semantic session events
+//!
+//! What happened in a game, named so the frontend can map it to sounds,
+//! vibration or notifications without diffing boards: a piece moved, a capture,
+//! a check, a promotion, a clock running low, the game leaving its opening
+//! repertoire, the game ending and why. Moves are described from their
+//! metadata and clocks from the recorded move times;
+//! both reach the frontend as `session-event::<session>` events, one per
+//! happening, in the order they occurred, each with the correlation ID of its
+//! move.
+
+use crate::blindfold::BlindfoldMode;
+use crate::correlation;
+use crate::session_store::ClockState;
+use serde::Serialize;
+use shogi_engine::game_status::GameStatus;
+use shogi_engine::i18n::{self, Locale};
+use shogi_engine::move_metadata::MoveMetadata;
+use shogi_engine::move_times::{MoveTime, TIME_PRESSURE_MS};
+use shogi_engine::opening_book::Deviation;
+use shogi_engine::types::{PieceType, Player};
+use tauri::{AppHandle, Emitter};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SessionEvent {
+    PieceMoved {
+        ply: u32,
+        player: Player,
+        usi: String,
+        /// `None` when the session's blindfold mode hides it
+        piece: Option<PieceType>,
+        is_drop: bool,
+    },
+    Capture {
+        ply: u32,
+        player: Player,
+        captured: Option<PieceType>,
+    },
+    Check {
+        ply: u32,
+        player: Player,
+    },
+    Promotion {
+        ply: u32,
+        player: Player,
+    },
+    /// The mover's clock dropped below the time-pressure threshold
+    LowTime {
+        ply: u32,
+        player: Option<Player>,
+        remaining_ms: u64,
+    },
+    /// The move was the first to leave the session's opening repertoire
+    OpeningDeviation {
+        ply: u32,
+        player: Player,
+        played: String,
+        recommended: String,
+        /// Every move the repertoire had for the position, recommended first
+        expected: Vec<String>,
+        source: RepertoireSource,
+    },
+    GameEnd {
+        /// English reason, stable for matching
+        reason: String,
+        /// The reason in the session's locale, for display
+        message: String,
+        winner: Option<Player>,
+    },
+}
+
+/// Which lines a session's moves are checked against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepertoireSource {
+    /// The player's own lines, chosen with `set_session_repertoire`
+    Repertoire,
+    /// The built-in opening book, for even games without a chosen repertoire
+    Book,
+}
+
+/// Events of the move played as ply `ply`, ending with the game's end if it
+/// ended the game
+pub fn move_events(
+    ply: u32,
+    metadata: &MoveMetadata,
+    status: &GameStatus,
+    mode: BlindfoldMode,
+    locale: Locale,
+) -> Vec<SessionEvent> {
+    let visible = mode.filter_move(metadata);
+    let player = metadata.player;
+    let piece_shown = (!mode.hides_identity(player)).then_some(metadata.piece);
+    let mut events = vec![SessionEvent::PieceMoved {
+        ply,
+        player,
+        usi: metadata.usi.clone(),
+        piece: piece_shown,
+        is_drop: metadata.is_drop,
+    }];
+    if visible.is_capture {
+        events.push(SessionEvent::Capture {
+            ply,
+            player,
+            captured: visible.captured,
+        });
+    }
+    if metadata.is_promotion {
+        events.push(SessionEvent::Promotion { ply, player });
+    }
+    if metadata.gives_check {
+        events.push(SessionEvent::Check { ply, player });
+    }
+    if status.is_over() {
+        events.push(SessionEvent::GameEnd {
+            reason: status.reason().to_string(),
+            message: status.message(locale),
+            winner: status.winner(),
+        });
+    }
+    events
+}
+
+/// The deviation event for the move played as ply `ply`, when `deviation` is
+/// that move's
+pub fn deviation_event(
+    ply: u32,
+    player: Player,
+    deviation: &Deviation,
+    source: RepertoireSource,
+) -> Option<SessionEvent> {
+    (deviation.ply == ply as usize).then(|| SessionEvent::OpeningDeviation {
+        ply,
+        player,
+        played: deviation.played.clone(),
+        recommended: deviation.recommended.clone(),
+        expected: deviation.expected.clone(),
+        source,
+    })
+}
+
+/// Events of the mover's clock after ply `time.ply`: `low_time` when the move
+/// took it below the time-pressure threshold (`previous` is the mover's clock
+/// after their move before), and a loss on time when it ran out in a game
+/// without byoyomi or increment
+pub fn clock_events(
+    time: &MoveTime,
+    previous: Option<u64>,
+    player: Option<Player>,
+    clocks: Option<&ClockState>,
+    locale: Locale,
+) -> Vec<SessionEvent> {
+    let mut events = Vec::new();
+    let Some(remaining_ms) = time.remaining_ms else {
+        return events;
+    };
+    if remaining_ms < TIME_PRESSURE_MS
+        && previous.map_or(true, |previous| previous >= TIME_PRESSURE_MS)
+    {
+        events.push(SessionEvent::LowTime {
+            ply: time.ply,
+            player,
+            remaining_ms,
+        });
+    }
+    let sudden_death =
+        clocks.map_or(false, |clocks| clocks.byoyomi_ms == 0 && clocks.increment_ms == 0);
+    if let (0, true, Some(player)) = (remaining_ms, sudden_death, player) {
+        events.push(SessionEvent::GameEnd {
+            reason: "Time forfeit".to_string(),
+            message: i18n::text(locale, "status.time_forfeit"),
+            winner: Some(player.opposite()),
+        });
+    }
+    events
+}
+
+impl SessionEvent {
+    /// Ply the event belongs to; `None` for the end of the game
+    pub fn ply(&self) -> Option<u32> {
+        match self {
+            SessionEvent::PieceMoved { ply, .. }
+            | SessionEvent::Capture { ply, .. }
+            | SessionEvent::Check { ply, .. }
+            | SessionEvent::Promotion { ply, .. }
+            | SessionEvent::LowTime { ply, .. }
+            | SessionEvent::OpeningDeviation { ply, .. } => Some(*ply),
+            SessionEvent::GameEnd { .. } => None,
+        }
+    }
+}
+
+/// A session event as the frontend receives it
+#[derive(Serialize)]
+struct EmittedEvent<'a> {
+    #[serde(flatten)]
+    event: &'a SessionEvent,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    correlation_id: Option<String>,
+}
+
+/// Emit `events` in order. The end of the game takes the ply of the event
+/// before it.
+pub fn emit(app_handle: &AppHandle, session_id: &str, events: &[SessionEvent]) {
+    let event_name = format!("session-event::{}", session_id);
+    let mut ply = None;
+    for event in events {
+        ply = event.ply().or(ply);
+        let correlation_id = ply.map(|ply| correlation::correlation_id(session_id, ply));
+        log::debug!(
+            "[{}] Session event: {:?}",
+            correlation_id.as_deref().unwrap_or("-"),
+            event
+        );
+        if let Err(e) = app_handle.emit(&event_name, EmittedEvent { event, correlation_id }) {
+            log::error!("Failed to emit session event: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_and_clock_events() {
+        let metadata = MoveMetadata {
+            usi: "8h2b+".to_string(),
+            player: Player::Black,
+            piece: PieceType::Bishop,
+            is_drop: false,
+            is_promotion: true,
+            captured: Some(PieceType::Bishop),
+            gives_check: false,
+            is_mate: false,
+            same_square: false,
+        };
+        let events = move_events(3, &metadata, &GameStatus::Ongoing, BlindfoldMode::Off, Locale::En);
+        let names: Vec<_> = events
+            .iter()
+            .map(|event| serde_json::to_value(event).unwrap()["event"].clone())
+            .collect();
+        assert_eq!(names, ["piece_moved", "capture", "promotion"]);
+
+        let hidden = move_events(3, &metadata, &GameStatus::Ongoing, BlindfoldMode::HideAll, Locale::En);
+        assert!(matches!(hidden[0], SessionEvent::PieceMoved { piece: None, .. }));
+        assert!(matches!(hidden[1], SessionEvent::Capture { captured: None, .. }));
+
+        let deviation = Deviation {
+            ply: 3,
+            played: "5g5f".to_string(),
+            recommended: "2g2f".to_string(),
+            expected: vec!["2g2f".to_string()],
+        };
+        let event = deviation_event(3, Player::Black, &deviation, RepertoireSource::Book).unwrap();
+        assert_eq!(serde_json::to_value(&event).unwrap()["source"], "book");
+        assert!(deviation_event(5, Player::Black, &deviation, RepertoireSource::Book).is_none());
+
+        let time = MoveTime { ply: 9, spent_ms: 5_000, remaining_ms: Some(58_000) };
+        assert_eq!(clock_events(&time, Some(63_000), Some(Player::Black), None, Locale::En).len(), 1);
+        assert!(clock_events(&time, Some(59_000), Some(Player::Black), None, Locale::En).is_empty());
+
+        let flagged = MoveTime { ply: 10, spent_ms: 9_000, remaining_ms: Some(0) };
+        let clocks = ClockState::default();
+        let events =
+            clock_events(&flagged, Some(9_000), Some(Player::White), Some(&clocks), Locale::Ja);
+        assert_eq!(
+            events,
+            vec![SessionEvent::GameEnd {
+                reason: "Time forfeit".to_string(),
+                message: "時間切れ".to_string(),
+                winner: Some(Player::Black),
+            }]
+        );
+    }
+}
@@ -0,0 +1,252 @@
+//! Session persistence
+//!
+//! Games in progress survive a crash or an accidental close. The frontend
+//! hands over a snapshot of each session as it changes (starting position,
+//! moves, clocks and which engine sits in which seat); the backend checks it,
+//! keeps it with the session's recorded move times, and writes the lot to
+//! `sessions.json` every few seconds while anything changed.
+//!
+//! On startup the sessions come back under their old ids without engines,
+//! which do not survive a restart: the frontend respawns them from the seat
+//! assignments. The file carries a schema version. Older files are migrated on
+//! load; a newer or unreadable file is moved aside rather than overwritten.
+
+use crate::game_session::{GameSession, SessionManager};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use shogi_engine::game_status::GameTracker;
+use shogi_engine::move_times::{MoveTime, MoveTimes};
+use shogi_engine::position_format::PositionText;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+/// Version of the `sessions.json` layout
+pub const SESSION_STORE_VERSION: u32 = 1;
+/// How often changed snapshots are written out
+pub const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Both players' clocks when the snapshot was taken
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClockState {
+    pub black_ms: u64,
+    pub white_ms: u64,
+    #[serde(default)]
+    pub byoyomi_ms: u64,
+    #[serde(default)]
+    pub increment_ms: u64,
+}
+
+/// What the frontend knows of a game in progress
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    /// Starting position
+    pub sfen: String,
+    /// Moves played from it in USI notation
+    #[serde(default)]
+    pub moves: Vec<String>,
+    #[serde(default)]
+    pub clocks: Option<ClockState>,
+    /// Engine config id by seat, e.g. `"white"` or `"analysis"`
+    #[serde(default)]
+    pub engines: HashMap<String, String>,
+}
+
+impl SessionSnapshot {
+    /// Canonicalize the starting position and check every move is legal
+    pub fn validate(&mut self) -> Result<(), String> {
+        let position = PositionText::parse(&self.sfen)?;
+        self.sfen = position.to_sfen();
+        let mut tracker = GameTracker::from_sfen(&self.sfen)?;
+        for (ply, usi) in self.moves.iter().enumerate() {
+            tracker
+                .play_usi(usi)
+                .map_err(|e| format!("Move {} ({}): {}", ply + 1, usi, e))?;
+        }
+        Ok(())
+    }
+}
+
+/// A session as saved to disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersistedSession {
+    pub session: GameSession,
+    pub snapshot: SessionSnapshot,
+    #[serde(default)]
+    pub move_times: Vec<MoveTime>,
+    pub saved_at: String,
+}
+
+/// Saved sessions by id, persisted next to the engine storage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionStore {
+    pub version: u32,
+    pub sessions: HashMap<String, PersistedSession>,
+    /// Whether anything changed since the last save
+    #[serde(skip)]
+    dirty: bool,
+}
+
+impl Default for SessionStore {
+    fn default() -> Self {
+        Self {
+            version: SESSION_STORE_VERSION,
+            sessions: HashMap::new(),
+            dirty: false,
+        }
+    }
+}
+
+impl SessionStore {
+    /// Get the platform-appropriate storage path, next to the engine storage
+    pub fn get_storage_path() -> Result<PathBuf> {
+        let engines_path = crate::engine_storage::EngineStorage::get_storage_path()?;
+        Ok(engines_path.with_file_name("sessions.json"))
+    }
+
+    /// Load the saved sessions. A file that cannot be read, or was written by
+    /// a newer version of the app, is renamed aside and an empty store used.
+    pub async fn load() -> Result<Self> {
+        let path = Self::get_storage_path()?;
+        if !path.exists() {
+            log::info!("Session file not found, starting fresh");
+            return Ok(Self::default());
+        }
+
+        let contents = tokio::fs::read_to_string(&path).await?;
+        match Self::from_json(&contents) {
+            Ok(store) => {
+                log::info!("Loaded {} saved sessions", store.sessions.len());
+                Ok(store)
+            }
+            Err(e) => {
+                let aside = path.with_extension("json.bak");
+                log::warn!("Ignoring saved sessions ({}), moved to {}", e, aside.display());
+                tokio::fs::rename(&path, &aside).await?;
+                Ok(Self::default())
+            }
+        }
+    }
+
+    /// Read a store, migrating older layouts
+    pub fn from_json(contents: &str) -> Result<Self, String> {
+        let value: serde_json::Value = serde_json::from_str(contents).map_err(|e| e.to_string())?;
+        let version = value
+            .get("version")
+            .and_then(|version| version.as_u64())
+            .unwrap_or(0) as u32;
+        if version > SESSION_STORE_VERSION {
+            return Err(format!("written by a newer version (format {})", version));
+        }
+        // Version 1 is the first layout; later versions migrate `value` here
+        let mut store: Self = serde_json::from_value(value).map_err(|e| e.to_string())?;
+        store.version = SESSION_STORE_VERSION;
+        Ok(store)
+    }
+
+    /// Save the sessions to disk, through a temporary file so a crash while
+    /// writing leaves the previous save intact
+    pub async fn save(&self) -> Result<()> {
+        let path = Self::get_storage_path()?;
+        let temp = path.with_extension("json.tmp");
+        let contents = serde_json::to_string_pretty(self)?;
+        tokio::fs::write(&temp, contents).await?;
+        tokio::fs::rename(&temp, &path).await?;
+        Ok(())
+    }
+
+    /// Keep the latest snapshot of a session
+    pub fn record(
+        &mut self,
+        session: GameSession,
+        snapshot: SessionSnapshot,
+        move_times: &MoveTimes,
+    ) {
+        let persisted = PersistedSession {
+            session,
+            snapshot,
+            move_times: move_times.times().to_vec(),
+            saved_at: chrono::Utc::now().to_rfc3339(),
+        };
+        self.sessions.insert(persisted.session.id.clone(), persisted);
+        self.dirty = true;
+    }
+
+    /// Forget a session that was closed on purpose
+    pub fn remove(&mut self, session_id: &str) {
+        if self.sessions.remove(session_id).is_some() {
+            self.dirty = true;
+        }
+    }
+
+    /// Bring the saved sessions back, with their move times
+    pub async fn restore(
+        &self,
+        sessions: &SessionManager,
+        move_times: &RwLock<HashMap<String, MoveTimes>>,
+    ) {
+        let mut move_times = move_times.write().await;
+        for persisted in self.sessions.values() {
+            sessions.restore_session(persisted.session.clone()).await;
+            let times = move_times.entry(persisted.session.id.clone()).or_default();
+            for time in &persisted.move_times {
+                times.record(*time);
+            }
+        }
+    }
+}
+
+/// Write the store whenever it changed, for as long as the app runs
+pub async fn autosave(store: Arc<RwLock<SessionStore>>) {
+    loop {
+        tokio::time::sleep(AUTOSAVE_INTERVAL).await;
+        let mut store = store.write().await;
+        if !store.dirty {
+            continue;
+        }
+        match store.save().await {
+            Ok(()) => store.dirty = false,
+            Err(e) => log::error!("Failed to save sessions: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_session::SessionKind;
+    use shogi_engine::handicap::Handicap;
+
+    #[test]
+    fn test_store_round_trip() {
+        let mut snapshot = SessionSnapshot {
+            sfen: Handicap::Even.sfen().to_string(),
+            moves: vec!["7g7f".to_string(), "3c3d".to_string()],
+            clocks: Some(ClockState {
+                black_ms: 290_000,
+                white_ms: 295_000,
+                ..ClockState::default()
+            }),
+            engines: HashMap::from([("white".to_string(), "engine-1".to_string())]),
+        };
+        snapshot.validate().unwrap();
+
+        let mut store = SessionStore::default();
+        let session = GameSession::new("s1".to_string(), SessionKind::HumanVsEngine, None);
+        store.record(session, snapshot.clone(), &MoveTimes::new());
+        let loaded = SessionStore::from_json(&serde_json::to_string(&store).unwrap()).unwrap();
+        assert_eq!(loaded.sessions["s1"].snapshot, snapshot);
+
+        snapshot.moves.push("5a5c".to_string());
+        assert!(snapshot.validate().is_err());
+    }
+
+    #[test]
+    fn test_newer_store_is_refused() {
+        let newer = format!(r#"{{"version": {}, "sessions": {{}}}}"#, SESSION_STORE_VERSION + 1);
+        assert!(SessionStore::from_json(&newer).is_err());
+        assert!(SessionStore::from_json("not json").is_err());
+    }
+}
@@ -1,20 +1,160 @@
-use crate::engine_manager::EngineManager;
+use crate::auto_analysis::AutoAnalysisQueue;
+use crate::blindfold::BlindfoldMode;
+use crate::deep_link::DeepLinkInbox;
+use crate::engine_manager::{EngineManager, QUIT_GRACE_PERIOD};
 use crate::engine_storage::EngineStorage;
+use crate::game_session::{SessionManager, DEFAULT_SESSION_ID};
+use crate::session_store::SessionStore;
+use crate::takeback::TakebackPolicy;
+use crate::tournament::{TournamentStatus, TournamentStore};
+use crate::tsume_trainer::TsumeStats;
+use shogi_engine::i18n::Locale;
+use shogi_engine::move_times::MoveTimes;
+use shogi_engine::opening_book::Repertoire;
+use shogi_engine::score_trend::ScoreTrend;
+use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock};
+use tokio::task::JoinHandle;
+use tokio::time::{timeout_at, Instant};
+
+/// Engine-vs-engine matches and tournament runners playing in the background
+#[derive(Default)]
+pub struct BackgroundGames {
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl BackgroundGames {
+    /// Play `game` in the background, tracked until it ends
+    pub async fn spawn<F>(&self, game: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let mut tasks = self.tasks.lock().await;
+        tasks.retain(|task| !task.is_finished());
+        tasks.push(tokio::spawn(game));
+    }
+
+    /// Whether a match or tournament is still being played
+    pub async fn any_running(&self) -> bool {
+        let mut tasks = self.tasks.lock().await;
+        tasks.retain(|task| !task.is_finished());
+        !tasks.is_empty()
+    }
+
+    /// Wait up to `limit` for every match and tournament to wind down
+    pub async fn join_all(&self, limit: Duration) {
+        let tasks = std::mem::take(&mut *self.tasks.lock().await);
+        let deadline = Instant::now() + limit;
+        for task in tasks {
+            if timeout_at(deadline, task).await.is_err() {
+                log::warn!("Background games still running after {:?}", limit);
+                return;
+            }
+        }
+    }
+}
 
 /// Application state that is shared across the Tauri app
 pub struct AppState {
     pub engine_manager: Arc<EngineManager>,
     pub engine_storage: Arc<RwLock<EngineStorage>>,
+    pub sessions: Arc<SessionManager>,
+    pub tsume_stats: Arc<RwLock<TsumeStats>>,
+    /// Evaluation after each played move, per game session
+    pub score_trends: Arc<RwLock<HashMap<String, ScoreTrend>>>,
+    /// Time spent on each played move, per game session
+    pub move_times: Arc<RwLock<HashMap<String, MoveTimes>>>,
+    pub tournaments: Arc<RwLock<TournamentStore>>,
+    /// Matches and tournaments being played, which keep auto-analysis waiting
+    pub background_games: Arc<BackgroundGames>,
+    /// Games analysed in the background while the engines are idle
+    pub auto_analysis: Arc<RwLock<AutoAnalysisQueue>>,
+    /// shogi:// links opened before the frontend listened for them
+    pub deep_links: Arc<RwLock<DeepLinkInbox>>,
+    /// Snapshots of games in progress, saved so they survive a restart
+    pub session_store: Arc<RwLock<SessionStore>>,
+    /// Takeback limits and use, per game session
+    pub takebacks: Arc<RwLock<HashMap<String, TakebackPolicy>>>,
+    /// What the frontend may see of the position, per blindfold session
+    pub blindfold: Arc<RwLock<HashMap<String, BlindfoldMode>>>,
+    /// Opening repertoire each session's moves are checked against; even games
+    /// without one are checked against the book
+    pub repertoires: Arc<RwLock<HashMap<String, Repertoire>>>,
+    /// Language of text the backend and built-in engine produce for players
+    pub locale: Arc<RwLock<Locale>>,
 }
 
 impl AppState {
-    pub fn new(engine_manager: EngineManager, engine_storage: EngineStorage) -> Self {
+    pub fn new(
+        engine_manager: EngineManager,
+        engine_storage: EngineStorage,
+        tsume_stats: TsumeStats,
+        tournaments: TournamentStore,
+        session_store: SessionStore,
+    ) -> Self {
         Self {
             engine_manager: Arc::new(engine_manager),
             engine_storage: Arc::new(RwLock::new(engine_storage)),
+            sessions: Arc::new(SessionManager::new()),
+            tsume_stats: Arc::new(RwLock::new(tsume_stats)),
+            score_trends: Arc::new(RwLock::new(HashMap::new())),
+            move_times: Arc::new(RwLock::new(HashMap::new())),
+            tournaments: Arc::new(RwLock::new(tournaments)),
+            background_games: Arc::new(BackgroundGames::default()),
+            auto_analysis: Arc::new(RwLock::new(AutoAnalysisQueue::new())),
+            deep_links: Arc::new(RwLock::new(DeepLinkInbox::default())),
+            session_store: Arc::new(RwLock::new(session_store)),
+            takebacks: Arc::new(RwLock::new(HashMap::new())),
+            blindfold: Arc::new(RwLock::new(HashMap::new())),
+            repertoires: Arc::new(RwLock::new(HashMap::new())),
+            locale: Arc::new(RwLock::new(Locale::default())),
         }
     }
-}
 
+    /// Wind the app down: pause running tournaments, stop background matches
+    /// and wait for them to quit their engines, give every engine its grace
+    /// period to exit after `quit` so it can write its own files, then save
+    /// everything the app keeps on disk
+    pub async fn shutdown(&self) {
+        log::info!("Shutting down");
+
+        {
+            let mut tournaments = self.tournaments.write().await;
+            for tournament in tournaments.tournaments.values_mut() {
+                if tournament.status == TournamentStatus::Running {
+                    tournament.status = TournamentStatus::Pending;
+                }
+            }
+            if let Err(e) = tournaments.save().await {
+                log::error!("Failed to save tournaments: {}", e);
+            }
+        }
+
+        // Saved before the sessions close, so open games come back next time
+        if let Err(e) = self.session_store.read().await.save().await {
+            log::error!("Failed to save sessions: {}", e);
+        }
+
+        for session in self.sessions.list_sessions().await {
+            if session.id != DEFAULT_SESSION_ID {
+                let _ = self.sessions.close_session(&session.id).await;
+            }
+        }
+        self.background_games.join_all(QUIT_GRACE_PERIOD).await;
+
+        if let Err(e) = self.engine_manager.stop_all_engines().await {
+            log::error!("Failed to stop engines: {}", e);
+        }
+
+        if let Err(e) = self.tsume_stats.read().await.save().await {
+            log::error!("Failed to save tsume statistics: {}", e);
+        }
+        if let Err(e) = self.engine_storage.read().await.save().await {
+            log::error!("Failed to save engine storage: {}", e);
+        }
+        log::info!("Shutdown complete");
+    }
+}
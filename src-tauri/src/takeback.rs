@@ -0,0 +1,140 @@
+//! Takebacks in human-vs-engine play
+//!
+//! A takeback rewinds the session's saved game, by default two plies so the
+//! human is to move again after the engine's reply is undone too. Each side's
+//! clock goes back to what it showed after its last remaining move, or gets the
+//! time of its undone moves back when that was not recorded. A game may cap how
+//! many takebacks it allows.
+
+use crate::session_store::SessionSnapshot;
+use serde::{Deserialize, Serialize};
+use shogi_engine::game_status::GameTracker;
+use shogi_engine::move_times::MoveTimes;
+use shogi_engine::types::Player;
+
+/// Plies undone by a takeback when none are given: the human's move and the
+/// engine's reply
+pub const DEFAULT_TAKEBACK_PLIES: usize = 2;
+
+/// How many takebacks a game allows and how many it has used
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TakebackPolicy {
+    /// `None` for no limit
+    pub limit: Option<u32>,
+    pub used: u32,
+}
+
+impl TakebackPolicy {
+    /// Takebacks left; `None` when unlimited
+    pub fn remaining(&self) -> Option<u32> {
+        self.limit.map(|limit| limit.saturating_sub(self.used))
+    }
+
+    /// Refuse a takeback once the limit is used up
+    pub fn check(&self) -> Result<(), String> {
+        match self.remaining() {
+            Some(0) => Err(format!(
+                "No takebacks left ({} allowed in this game)",
+                self.limit.unwrap_or(0)
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Undo the last `plies` moves of `snapshot`, truncating `move_times` to match
+/// and winding the clocks back. Returns the undone moves, oldest first.
+pub fn rewind(
+    snapshot: &mut SessionSnapshot,
+    plies: usize,
+    move_times: &mut MoveTimes,
+) -> Result<Vec<String>, String> {
+    if plies == 0 || plies > snapshot.moves.len() {
+        return Err(format!(
+            "Cannot take back {} plies of a {}-ply game",
+            plies,
+            snapshot.moves.len()
+        ));
+    }
+    let first_mover = GameTracker::from_sfen(&snapshot.sfen)?.side_to_move();
+    let mover = |ply: usize| {
+        if ply % 2 == 1 {
+            first_mover
+        } else {
+            first_mover.opposite()
+        }
+    };
+
+    let kept = snapshot.moves.len() - plies;
+    if let Some(clocks) = &mut snapshot.clocks {
+        for ply in kept + 1..=snapshot.moves.len() {
+            let spent = move_times.get(ply as u32).map_or(0, |time| time.spent_ms);
+            match mover(ply) {
+                Player::Black => clocks.black_ms += spent,
+                Player::White => clocks.white_ms += spent,
+            }
+        }
+        // The clock recorded after a side's last kept move is exact
+        for ply in (1..=kept).rev().take(2) {
+            let time = move_times.get(ply as u32);
+            if let Some(remaining) = time.and_then(|time| time.remaining_ms) {
+                match mover(ply) {
+                    Player::Black => clocks.black_ms = remaining,
+                    Player::White => clocks.white_ms = remaining,
+                }
+            }
+        }
+    }
+
+    move_times.truncate(kept as u32);
+    Ok(snapshot.moves.split_off(kept))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_store::ClockState;
+    use shogi_engine::handicap::Handicap;
+    use shogi_engine::move_times::MoveTime;
+
+    #[test]
+    fn test_rewind_restores_clocks() {
+        let mut snapshot = SessionSnapshot {
+            sfen: Handicap::Even.sfen().to_string(),
+            moves: ["7g7f", "3c3d", "2g2f", "8c8d"].map(str::to_string).to_vec(),
+            clocks: Some(ClockState {
+                black_ms: 50_000,
+                white_ms: 40_000,
+                ..ClockState::default()
+            }),
+            ..SessionSnapshot::default()
+        };
+        let mut times = MoveTimes::new();
+        times.record(MoveTime { ply: 1, spent_ms: 5_000, remaining_ms: Some(55_000) });
+        times.record(MoveTime { ply: 2, spent_ms: 8_000, remaining_ms: None });
+        times.record(MoveTime { ply: 3, spent_ms: 5_000, remaining_ms: Some(50_000) });
+        times.record(MoveTime { ply: 4, spent_ms: 7_000, remaining_ms: None });
+
+        let undone = rewind(&mut snapshot, 2, &mut times).unwrap();
+        assert_eq!(undone, vec!["2g2f", "8c8d"]);
+        assert_eq!(snapshot.moves, vec!["7g7f", "3c3d"]);
+        let clocks = snapshot.clocks.clone().unwrap();
+        assert_eq!(clocks.black_ms, 55_000);
+        assert_eq!(clocks.white_ms, 47_000);
+        assert!(times.get(3).is_none());
+
+        assert!(rewind(&mut snapshot, 3, &mut times).is_err());
+    }
+
+    #[test]
+    fn test_takeback_limit() {
+        let mut policy = TakebackPolicy { limit: Some(1), used: 0 };
+        assert!(policy.check().is_ok());
+        policy.used += 1;
+        assert_eq!(policy.remaining(), Some(0));
+        assert!(policy.check().is_err());
+
+        let unlimited = TakebackPolicy { limit: None, used: 40 };
+        assert!(unlimited.check().is_ok());
+    }
+}
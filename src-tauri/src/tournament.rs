@@ -0,0 +1,713 @@
+/**
+ * Engine tournaments
+ * Schedules engine-vs-engine games between registered engines, round robin or
+ * Swiss, plays them in their own sessions and keeps the standings on disk so a
+ * tournament survives an app restart and can be resumed.
+ */
+
+use crate::adjudication::AdjudicationConfig;
+use crate::engine_storage::EngineStorage;
+use crate::engine_vs_engine::{EngineVsEngineConfig, EngineVsEngineManager};
+use crate::game_session::{SessionKind, SessionManager};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::RwLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TournamentFormat {
+    /// Everyone plays everyone; `rounds` is the number of cycles, colors
+    /// alternating between cycles
+    RoundRobin,
+    /// Engines on equal points meet; `rounds` is the number of rounds
+    Swiss,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TournamentStatus {
+    /// Created or paused; `start_tournament` (re)starts it
+    Pending,
+    Running,
+    Finished,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TournamentEntrant {
+    pub engine_id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TournamentConfig {
+    pub name: String,
+    pub format: TournamentFormat,
+    pub entrants: Vec<TournamentEntrant>,
+    /// Cycles for round robin, rounds for Swiss; `None` for one cycle or
+    /// enough Swiss rounds to separate the field
+    #[serde(default)]
+    pub rounds: Option<usize>,
+    pub time_per_move_ms: u64,
+    pub max_moves: usize,
+    #[serde(default)]
+    pub initial_sfen: Option<String>,
+    #[serde(default)]
+    pub adjudication: AdjudicationConfig,
+    /// Games played at the same time, each in its own session
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+}
+
+fn default_concurrency() -> usize {
+    1
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameOutcome {
+    BlackWins,
+    WhiteWins,
+    Draw,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameResult {
+    pub outcome: GameOutcome,
+    pub reason: String,
+    pub moves: Vec<String>,
+}
+
+/// One scheduled game
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pairing {
+    /// Round number, from 1
+    pub round: usize,
+    /// Engine id of sente
+    pub black: String,
+    /// Engine id of gote; `None` for a bye, which scores as a win for `black`
+    pub white: Option<String>,
+    /// Session the game is played in, once started
+    pub session_id: Option<String>,
+    pub result: Option<GameResult>,
+}
+
+impl Pairing {
+    fn new(round: usize, black: &str, white: Option<&str>) -> Self {
+        let result = white.is_none().then(|| GameResult {
+            outcome: GameOutcome::BlackWins,
+            reason: "Bye".to_string(),
+            moves: Vec::new(),
+        });
+        Self {
+            round,
+            black: black.to_string(),
+            white: white.map(str::to_string),
+            session_id: None,
+            result,
+        }
+    }
+
+    fn involves(&self, engine_id: &str) -> bool {
+        self.black == engine_id || self.white.as_deref() == Some(engine_id)
+    }
+
+    /// Points scored by `engine_id` in this game, `None` if it did not play it
+    /// or it is not over
+    fn points_for(&self, engine_id: &str) -> Option<f32> {
+        let result = self.result.as_ref()?;
+        let as_black = if self.black == engine_id {
+            true
+        } else if self.white.as_deref() == Some(engine_id) {
+            false
+        } else {
+            return None;
+        };
+        Some(match (result.outcome, as_black) {
+            (GameOutcome::Draw, _) => 0.5,
+            (GameOutcome::BlackWins, true) | (GameOutcome::WhiteWins, false) => 1.0,
+            _ => 0.0,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Standing {
+    pub engine_id: String,
+    pub name: String,
+    pub points: f32,
+    pub games: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    /// Sum of the opponents' points, the Swiss tie-break
+    pub buchholz: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tournament {
+    pub id: String,
+    pub config: TournamentConfig,
+    pub status: TournamentStatus,
+    pub pairings: Vec<Pairing>,
+    pub created_at: String,
+    /// Bumped by every start, so a runner left over from before a pause
+    /// stops instead of playing alongside the new one
+    #[serde(skip)]
+    run_generation: u64,
+}
+
+impl Tournament {
+    pub fn new(id: String, config: TournamentConfig) -> Result<Self> {
+        if config.entrants.len() < 2 {
+            return Err(anyhow!("A tournament needs at least two engines"));
+        }
+        let mut tournament = Self {
+            id,
+            config,
+            status: TournamentStatus::Pending,
+            pairings: Vec::new(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            run_generation: 0,
+        };
+        match tournament.config.format {
+            TournamentFormat::RoundRobin => tournament.schedule_round_robin(),
+            TournamentFormat::Swiss => tournament.pair_swiss_round(),
+        }
+        Ok(tournament)
+    }
+
+    /// Rounds the tournament will have
+    pub fn total_rounds(&self) -> usize {
+        let entrants = self.config.entrants.len();
+        match self.config.format {
+            TournamentFormat::RoundRobin => {
+                let per_cycle = if entrants % 2 == 0 { entrants - 1 } else { entrants };
+                per_cycle * self.config.rounds.unwrap_or(1).max(1)
+            }
+            TournamentFormat::Swiss => self.config.rounds.unwrap_or_else(|| {
+                // Enough rounds for a single perfect score in a field of this size
+                (usize::BITS - (entrants - 1).leading_zeros()) as usize
+            }),
+        }
+    }
+
+    /// Every round of every cycle, by the circle method
+    fn schedule_round_robin(&mut self) {
+        let mut circle: Vec<Option<&str>> = self
+            .config
+            .entrants
+            .iter()
+            .map(|entrant| Some(entrant.engine_id.as_str()))
+            .collect();
+        if circle.len() % 2 == 1 {
+            circle.push(None);
+        }
+        let size = circle.len();
+        let mut pairings = Vec::new();
+        for cycle in 0..self.config.rounds.unwrap_or(1).max(1) {
+            for round in 0..size - 1 {
+                for board in 0..size / 2 {
+                    let (Some(first), Some(second)) = (circle[board], circle[size - 1 - board])
+                    else {
+                        continue;
+                    };
+                    let swap = (round + board + cycle) % 2 == 1;
+                    let (black, white) = if swap { (second, first) } else { (first, second) };
+                    pairings.push(Pairing::new(cycle * (size - 1) + round + 1, black, Some(white)));
+                }
+                // Keep the first seat, rotate the others
+                let last = circle.pop().flatten();
+                circle.insert(1, last);
+            }
+        }
+        self.pairings = pairings;
+    }
+
+    /// Pair the next Swiss round from the standings: the best-placed engines
+    /// meet first, avoiding rematches where possible, and the lowest-placed
+    /// engine without one gets the bye in an odd field
+    fn pair_swiss_round(&mut self) {
+        let round = self.pairings.iter().map(|pairing| pairing.round).max().unwrap_or(0) + 1;
+        let mut order: Vec<String> =
+            self.standings().into_iter().map(|standing| standing.engine_id).collect();
+
+        if order.len() % 2 == 1 {
+            let had_bye = |id: &String| {
+                self.pairings.iter().any(|pairing| pairing.black == *id && pairing.white.is_none())
+            };
+            let bye = order.iter().rposition(|id| !had_bye(id)).unwrap_or(order.len() - 1);
+            let engine_id = order.remove(bye);
+            self.pairings.push(Pairing::new(round, &engine_id, None));
+        }
+
+        let met = |a: &str, b: &str| {
+            self.pairings.iter().any(|pairing| pairing.involves(a) && pairing.involves(b))
+        };
+        let blacks = |id: &str| self.pairings.iter().filter(|pairing| pairing.black == id).count();
+        let mut new_pairings = Vec::new();
+        while !order.is_empty() {
+            let first = order.remove(0);
+            let opponent = order.iter().position(|id| !met(&first, id)).unwrap_or(0);
+            let second = order.remove(opponent);
+            let (black, white) = if blacks(&second) < blacks(&first) {
+                (second, first)
+            } else {
+                (first, second)
+            };
+            new_pairings.push(Pairing::new(round, &black, Some(white.as_str())));
+        }
+        self.pairings.extend(new_pairings);
+    }
+
+    /// Mark the tournament running; returns the generation its runner plays for
+    pub fn start_run(&mut self) -> u64 {
+        self.status = TournamentStatus::Running;
+        self.run_generation += 1;
+        self.run_generation
+    }
+
+    /// Whether the runner started for `generation` is the one to play on
+    fn is_current_run(&self, generation: u64) -> bool {
+        self.status == TournamentStatus::Running && self.run_generation == generation
+    }
+
+    /// Indexes of the games still to play
+    pub fn pending(&self) -> Vec<usize> {
+        (0..self.pairings.len()).filter(|&i| self.pairings[i].result.is_none()).collect()
+    }
+
+    /// Move on once every scheduled game has a result: pair the next Swiss
+    /// round, or finish. Returns whether there are games to play.
+    pub fn advance(&mut self) -> bool {
+        if !self.pending().is_empty() {
+            return true;
+        }
+        let played_rounds = self.pairings.iter().map(|pairing| pairing.round).max().unwrap_or(0);
+        if self.config.format == TournamentFormat::Swiss && played_rounds < self.total_rounds() {
+            self.pair_swiss_round();
+            return true;
+        }
+        self.status = TournamentStatus::Finished;
+        false
+    }
+
+    /// Standings, best first: by points, then Buchholz, then name
+    pub fn standings(&self) -> Vec<Standing> {
+        let points = |engine_id: &str| -> f32 {
+            self.pairings.iter().filter_map(|pairing| pairing.points_for(engine_id)).sum()
+        };
+        let mut standings: Vec<Standing> = self
+            .config
+            .entrants
+            .iter()
+            .map(|entrant| {
+                let mut standing = Standing {
+                    engine_id: entrant.engine_id.clone(),
+                    name: entrant.name.clone(),
+                    points: 0.0,
+                    games: 0,
+                    wins: 0,
+                    draws: 0,
+                    losses: 0,
+                    buchholz: 0.0,
+                };
+                for pairing in &self.pairings {
+                    let Some(scored) = pairing.points_for(&entrant.engine_id) else {
+                        continue;
+                    };
+                    standing.points += scored;
+                    if pairing.white.is_none() {
+                        continue;
+                    }
+                    standing.games += 1;
+                    match scored {
+                        s if s >= 1.0 => standing.wins += 1,
+                        s if s > 0.0 => standing.draws += 1,
+                        _ => standing.losses += 1,
+                    }
+                    let opponent = if pairing.black == entrant.engine_id {
+                        pairing.white.as_deref().unwrap_or_default()
+                    } else {
+                        pairing.black.as_str()
+                    };
+                    standing.buchholz += points(opponent);
+                }
+                standing
+            })
+            .collect();
+        standings.sort_by(|a, b| {
+            b.points
+                .total_cmp(&a.points)
+                .then(b.buchholz.total_cmp(&a.buchholz))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        standings
+    }
+
+    fn entrant_name(&self, engine_id: &str) -> &str {
+        self.config
+            .entrants
+            .iter()
+            .find(|entrant| entrant.engine_id == engine_id)
+            .map_or(engine_id, |entrant| entrant.name.as_str())
+    }
+
+    /// Payload of the `tournament-update` event
+    pub fn summary(&self) -> serde_json::Value {
+        serde_json::json!({
+            "tournament": self,
+            "standings": self.standings(),
+            "total_rounds": self.total_rounds(),
+        })
+    }
+}
+
+/// All tournaments, persisted next to the engine storage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TournamentStore {
+    pub version: String,
+    pub tournaments: HashMap<String, Tournament>,
+}
+
+impl Default for TournamentStore {
+    fn default() -> Self {
+        Self {
+            version: "1.0".to_string(),
+            tournaments: HashMap::new(),
+        }
+    }
+}
+
+impl TournamentStore {
+    /// Get the platform-appropriate storage path, next to the engine storage
+    pub fn get_storage_path() -> Result<PathBuf> {
+        let engines_path = EngineStorage::get_storage_path()?;
+        Ok(engines_path.with_file_name("tournaments.json"))
+    }
+
+    /// Load the tournaments from disk. Tournaments that were running when the
+    /// app closed come back paused, their unfinished games to be replayed.
+    pub async fn load() -> Result<Self> {
+        let path = Self::get_storage_path()?;
+        if !path.exists() {
+            log::info!("Tournament file not found, starting fresh");
+            return Ok(Self::default());
+        }
+
+        let contents = tokio::fs::read_to_string(&path).await?;
+        let mut store: Self = serde_json::from_str(&contents)?;
+        for tournament in store.tournaments.values_mut() {
+            if tournament.status == TournamentStatus::Running {
+                tournament.status = TournamentStatus::Pending;
+            }
+            for pairing in &mut tournament.pairings {
+                if pairing.result.is_none() {
+                    pairing.session_id = None;
+                }
+            }
+        }
+        log::info!("Loaded {} tournaments", store.tournaments.len());
+        Ok(store)
+    }
+
+    /// Save the tournaments to disk
+    pub async fn save(&self) -> Result<()> {
+        let path = Self::get_storage_path()?;
+        let contents = serde_json::to_string_pretty(self)?;
+        tokio::fs::write(&path, contents).await?;
+        Ok(())
+    }
+}
+
+/// Everything the tournament runner needs from the app
+#[derive(Clone)]
+pub struct TournamentRunner {
+    pub app_handle: AppHandle,
+    pub tournaments: Arc<RwLock<TournamentStore>>,
+    pub sessions: Arc<SessionManager>,
+    pub engine_storage: Arc<RwLock<EngineStorage>>,
+}
+
+impl TournamentRunner {
+    fn emit_update(&self, tournament: &Tournament) {
+        let event_name = format!("tournament-update::{}", tournament.id);
+        if let Err(e) = self.app_handle.emit(&event_name, tournament.summary()) {
+            log::error!("Failed to emit tournament update: {}", e);
+        }
+    }
+
+    async fn save(&self) {
+        if let Err(e) = self.tournaments.read().await.save().await {
+            log::error!("Failed to save tournaments: {}", e);
+        }
+    }
+
+    /// Play a running tournament until it finishes or is paused. `generation`
+    /// is what `Tournament::start_run` returned; once the tournament has been
+    /// started again the runner exits without touching it.
+    pub async fn run(self, tournament_id: String, generation: u64) {
+        log::info!("Running tournament {}", tournament_id);
+        loop {
+            // Start the next batch of games, each in a fresh session
+            let batch = {
+                let mut store = self.tournaments.write().await;
+                let Some(tournament) = store.tournaments.get_mut(&tournament_id) else {
+                    return;
+                };
+                if !tournament.is_current_run(generation) {
+                    break;
+                }
+                if !tournament.advance() {
+                    log::info!("Tournament {} finished", tournament_id);
+                    self.emit_update(tournament);
+                    break;
+                }
+                let concurrency = tournament.config.concurrency.max(1);
+                let mut batch = Vec::new();
+                for index in tournament.pending().into_iter().take(concurrency) {
+                    let pairing = &tournament.pairings[index];
+                    let label = format!(
+                        "{}: {} vs {}",
+                        tournament.config.name,
+                        tournament.entrant_name(&pairing.black),
+                        tournament.entrant_name(pairing.white.as_deref().unwrap_or_default())
+                    );
+                    let session = self
+                        .sessions
+                        .create_session(SessionKind::EngineVsEngine, Some(label))
+                        .await;
+                    tournament.pairings[index].session_id = Some(session.id.clone());
+                    batch.push((index, tournament.pairings[index].clone(), session));
+                }
+                self.emit_update(tournament);
+                (tournament.config.clone(), batch)
+            };
+            self.save().await;
+
+            let (config, batch) = batch;
+            let handles: Vec<_> = batch
+                .into_iter()
+                .map(|(index, pairing, session)| {
+                    let runner = self.clone();
+                    let config = config.clone();
+                    tokio::spawn(async move {
+                        let result = runner.play_game(&config, &pairing, &session.id).await;
+                        let cancelled = session.is_cancelled();
+                        let _ = runner.sessions.close_session(&session.id).await;
+                        (index, if cancelled { Ok(None) } else { result })
+                    })
+                })
+                .collect();
+
+            let mut failed = false;
+            for handle in handles {
+                let (index, result) = match handle.await {
+                    Ok(outcome) => outcome,
+                    Err(e) => {
+                        log::error!("Tournament game task failed: {}", e);
+                        failed = true;
+                        continue;
+                    }
+                };
+                let mut store = self.tournaments.write().await;
+                let Some(tournament) = store.tournaments.get_mut(&tournament_id) else {
+                    return;
+                };
+                if tournament.run_generation != generation {
+                    // Restarted meanwhile: the new runner replays this game
+                    return;
+                }
+                match result {
+                    Ok(Some(result)) => tournament.pairings[index].result = Some(result),
+                    Ok(None) => tournament.pairings[index].session_id = None,
+                    Err(e) => {
+                        log::error!("Tournament {} game failed to start: {}", tournament_id, e);
+                        tournament.pairings[index].session_id = None;
+                        failed = true;
+                    }
+                }
+                self.emit_update(tournament);
+            }
+
+            // A game that cannot be played stops the tournament rather than spinning on it
+            if failed {
+                let mut store = self.tournaments.write().await;
+                let tournament = store.tournaments.get_mut(&tournament_id);
+                if let Some(tournament) = tournament.filter(|t| t.is_current_run(generation)) {
+                    tournament.status = TournamentStatus::Pending;
+                    self.emit_update(tournament);
+                }
+            }
+            self.save().await;
+        }
+        self.save().await;
+    }
+
+    /// Play one game; `None` when the session was closed before it ended
+    async fn play_game(
+        &self,
+        config: &TournamentConfig,
+        pairing: &Pairing,
+        session_id: &str,
+    ) -> Result<Option<GameResult>> {
+        let white = pairing.white.as_deref().ok_or_else(|| anyhow!("A bye is not played"))?;
+        let storage = self.engine_storage.read().await;
+        let engine1 = storage
+            .get_engine(&pairing.black)
+            .ok_or_else(|| anyhow!("Engine not found: {}", pairing.black))?;
+        let engine2 = storage
+            .get_engine(white)
+            .ok_or_else(|| anyhow!("Engine not found: {}", white))?;
+        let match_config = EngineVsEngineConfig {
+            session_id: session_id.to_string(),
+            engine1_id: engine1.id.clone(),
+            engine1_path: engine1.path.clone(),
+            engine1_name: engine1.name.clone(),
+            engine2_id: engine2.id.clone(),
+            engine2_path: engine2.path.clone(),
+            engine2_name: engine2.name.clone(),
+            initial_sfen: config.initial_sfen.clone(),
+            time_per_move_ms: config.time_per_move_ms,
+            max_moves: config.max_moves,
+            adjudication: config.adjudication.clone(),
+        };
+        drop(storage);
+
+        let session = self
+            .sessions
+            .get_session(session_id)
+            .await
+            .ok_or_else(|| anyhow!("Session not found: {}", session_id))?;
+        let manager = EngineVsEngineManager::new(
+            self.app_handle.clone(),
+            match_config,
+            self.engine_storage.clone(),
+            session.cancel_flag(),
+        );
+        let state = manager.run_match().await?;
+        let outcome = match state.winner.as_deref() {
+            Some("black") => GameOutcome::BlackWins,
+            Some("white") => GameOutcome::WhiteWins,
+            Some(_) => GameOutcome::Draw,
+            None => return Ok(None),
+        };
+        Ok(Some(GameResult {
+            outcome,
+            reason: state.game_result.unwrap_or_default(),
+            moves: state.move_history,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(format: TournamentFormat, engines: &[&str]) -> TournamentConfig {
+        TournamentConfig {
+            name: "Test".to_string(),
+            format,
+            entrants: engines
+                .iter()
+                .map(|id| TournamentEntrant { engine_id: id.to_string(), name: id.to_string() })
+                .collect(),
+            rounds: None,
+            time_per_move_ms: 100,
+            max_moves: 50,
+            initial_sfen: None,
+            adjudication: AdjudicationConfig::default(),
+            concurrency: 1,
+        }
+    }
+
+    fn finish(tournament: &mut Tournament, index: usize, outcome: GameOutcome) {
+        tournament.pairings[index].result = Some(GameResult {
+            outcome,
+            reason: "Checkmate".to_string(),
+            moves: Vec::new(),
+        });
+    }
+
+    #[test]
+    fn test_restart_retires_the_previous_runner() {
+        let mut tournament =
+            Tournament::new("t".to_string(), config(TournamentFormat::RoundRobin, &["a", "b"]))
+                .unwrap();
+        let first = tournament.start_run();
+        assert!(tournament.is_current_run(first));
+
+        // Paused, then started again before the first runner noticed
+        tournament.status = TournamentStatus::Pending;
+        let second = tournament.start_run();
+        assert!(!tournament.is_current_run(first));
+        assert!(tournament.is_current_run(second));
+    }
+
+    #[test]
+    fn test_round_robin_meets_everyone_once() {
+        let engines = ["a", "b", "c", "d"];
+        let tournament =
+            Tournament::new("t".to_string(), config(TournamentFormat::RoundRobin, &engines))
+                .unwrap();
+        assert_eq!(tournament.total_rounds(), 3);
+        assert_eq!(tournament.pairings.len(), 6);
+        for (a, b) in [("a", "b"), ("a", "c"), ("a", "d"), ("b", "c"), ("b", "d"), ("c", "d")] {
+            let games = tournament
+                .pairings
+                .iter()
+                .filter(|pairing| pairing.involves(a) && pairing.involves(b))
+                .count();
+            assert_eq!(games, 1, "{} vs {}", a, b);
+        }
+
+        // Odd fields sit one engine out each round instead of giving byes
+        let tournament =
+            Tournament::new("t".to_string(), config(TournamentFormat::RoundRobin, &engines[..3]))
+                .unwrap();
+        assert_eq!(tournament.pairings.len(), 3);
+        assert!(tournament.pairings.iter().all(|pairing| pairing.white.is_some()));
+    }
+
+    #[test]
+    fn test_swiss_pairs_winners_and_scores_byes() {
+        let engines = ["a", "b", "c", "d", "e"];
+        let mut tournament =
+            Tournament::new("t".to_string(), config(TournamentFormat::Swiss, &engines)).unwrap();
+        assert_eq!(tournament.total_rounds(), 3);
+        // Two games and a bye, already scored
+        assert_eq!(tournament.pairings.len(), 3);
+        assert_eq!(tournament.pending().len(), 2);
+        for index in tournament.pending() {
+            finish(&mut tournament, index, GameOutcome::BlackWins);
+        }
+        assert!(tournament.advance());
+        let round_two: Vec<&Pairing> =
+            tournament.pairings.iter().filter(|pairing| pairing.round == 2).collect();
+        assert_eq!(round_two.len(), 3);
+        // The three engines on one point are paired among themselves, no rematches
+        let leaders: Vec<String> = tournament
+            .standings()
+            .iter()
+            .filter(|standing| standing.points >= 1.0)
+            .map(|standing| standing.engine_id.clone())
+            .collect();
+        assert_eq!(leaders.len(), 3);
+        let top_game = round_two.iter().find(|pairing| pairing.white.is_some()).unwrap();
+        assert!(leaders.contains(&top_game.black));
+        assert!(leaders.contains(top_game.white.as_ref().unwrap()));
+        for pairing in &round_two {
+            if let Some(white) = &pairing.white {
+                let rematches = tournament
+                    .pairings
+                    .iter()
+                    .filter(|other| other.involves(&pairing.black) && other.involves(white))
+                    .count();
+                assert_eq!(rematches, 1);
+            }
+        }
+    }
+}
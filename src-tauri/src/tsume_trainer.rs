@@ -0,0 +1,100 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use shogi_engine::tsume::{TsumeProblem, TsumeVerdict};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Solve record for one tsume problem
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TsumeProblemStats {
+    /// Finished attempts, solved or failed
+    pub attempts: u32,
+    pub solved: u32,
+    /// Wrong moves submitted across all attempts
+    pub mistakes: u32,
+    pub last_solved_at: Option<String>,
+}
+
+/// Persistent tsume trainer statistics, keyed by problem id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TsumeStats {
+    pub version: String,
+    pub problems: HashMap<String, TsumeProblemStats>,
+}
+
+impl Default for TsumeStats {
+    fn default() -> Self {
+        Self {
+            version: "1.0".to_string(),
+            problems: HashMap::new(),
+        }
+    }
+}
+
+impl TsumeStats {
+    /// Get the platform-appropriate storage path, next to the engine storage
+    pub fn get_storage_path() -> Result<PathBuf> {
+        let engines_path = crate::engine_storage::EngineStorage::get_storage_path()?;
+        Ok(engines_path.with_file_name("tsume_stats.json"))
+    }
+
+    /// Load the statistics from disk
+    pub async fn load() -> Result<Self> {
+        let path = Self::get_storage_path()?;
+        if !path.exists() {
+            log::info!("Tsume statistics file not found, starting fresh");
+            return Ok(Self::default());
+        }
+
+        let contents = tokio::fs::read_to_string(&path).await?;
+        let stats: Self = serde_json::from_str(&contents)?;
+        log::info!("Loaded tsume statistics for {} problems", stats.problems.len());
+        Ok(stats)
+    }
+
+    /// Save the statistics to disk
+    pub async fn save(&self) -> Result<()> {
+        let path = Self::get_storage_path()?;
+        let contents = serde_json::to_string_pretty(self)?;
+        tokio::fs::write(&path, contents).await?;
+        Ok(())
+    }
+
+    /// Record a verdict for a submitted move sequence. Returns whether the
+    /// statistics changed; a sequence that is correct so far changes nothing.
+    pub fn record(&mut self, problem_id: &str, verdict: &TsumeVerdict) -> bool {
+        let stats = self.problems.entry(problem_id.to_string()).or_default();
+        match verdict {
+            TsumeVerdict::Solved => {
+                stats.attempts += 1;
+                stats.solved += 1;
+                stats.last_solved_at = Some(chrono::Utc::now().to_rfc3339());
+                true
+            }
+            TsumeVerdict::Wrong { .. } => {
+                stats.attempts += 1;
+                stats.mistakes += 1;
+                true
+            }
+            TsumeVerdict::Continue { .. } => false,
+        }
+    }
+
+    pub fn get(&self, problem_id: &str) -> TsumeProblemStats {
+        self.problems.get(problem_id).cloned().unwrap_or_default()
+    }
+}
+
+/// The problem with `problem_id`, or the first built-in problem not solved yet
+/// (the first problem once all are solved) when no id is given
+pub fn select_problem(stats: &TsumeStats, problem_id: Option<&str>) -> Option<TsumeProblem> {
+    let problems = shogi_engine::tsume::builtin_problems();
+    match problem_id {
+        Some(id) => problems.into_iter().find(|problem| problem.id == id),
+        None => problems
+            .iter()
+            .find(|problem| stats.get(&problem.id).solved == 0)
+            .or_else(|| problems.first())
+            .cloned(),
+    }
+}
@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+use shogi_engine::error::ShogiError;
+use shogi_engine::search::perspective::EvaluationPerspective;
+
+/// Score reported in a USI `info` line, from the perspective in `UsiInfo::perspective`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+pub enum UsiScore {
+    /// Centipawn score
+    Cp(i32),
+    /// Mate distance in plies; negative when the side the score is for is being mated.
+    /// `None` when the engine only reported the sign (`score mate +`/`score mate -`).
+    Mate(Option<i32>),
+}
+
+/// Whether the reported score is exact or only a bound
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScoreBound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+/// Structured form of a USI `info` line, emitted to the frontend as a typed event
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsiInfo {
+    pub depth: Option<u32>,
+    pub seldepth: Option<u32>,
+    pub time_ms: Option<u64>,
+    pub nodes: Option<u64>,
+    pub nps: Option<u64>,
+    pub hashfull: Option<u32>,
+    pub multipv: Option<u32>,
+    pub score: Option<UsiScore>,
+    /// Whose point of view `score` takes; the engine manager fills this in from
+    /// the engine's `ScorePerspective` option, as the line itself does not say
+    #[serde(default)]
+    pub perspective: EvaluationPerspective,
+    pub bound: Option<ScoreBound>,
+    /// Win probability `score` stands for, in permille (`winrate`, sent by the
+    /// built-in engine with `ShowWinRate` on)
+    pub win_rate: Option<u16>,
+    pub currmove: Option<String>,
+    pub currmovenumber: Option<u32>,
+    pub pv: Vec<String>,
+    pub string: Option<String>,
+    /// Error the built-in engine reported as `info string error <category>: ...`,
+    /// so the frontend can tell bad input from a failure worth retrying
+    pub error: Option<ShogiError>,
+}
+
+impl UsiInfo {
+    /// Parse an `info ...` line. Returns `None` for lines that are not info lines.
+    /// Unknown tokens are skipped so engine-specific extensions don't break parsing.
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut tokens = line.split_whitespace();
+        if tokens.next() != Some("info") {
+            return None;
+        }
+
+        let parts: Vec<&str> = tokens.collect();
+        let mut info = UsiInfo::default();
+        let mut i = 0;
+
+        while i < parts.len() {
+            match parts[i] {
+                "depth" => {
+                    info.depth = parts.get(i + 1).and_then(|v| v.parse().ok());
+                    i += 2;
+                }
+                "seldepth" => {
+                    info.seldepth = parts.get(i + 1).and_then(|v| v.parse().ok());
+                    i += 2;
+                }
+                "time" => {
+                    info.time_ms = parts.get(i + 1).and_then(|v| v.parse().ok());
+                    i += 2;
+                }
+                "nodes" => {
+                    info.nodes = parts.get(i + 1).and_then(|v| v.parse().ok());
+                    i += 2;
+                }
+                "nps" => {
+                    info.nps = parts.get(i + 1).and_then(|v| v.parse().ok());
+                    i += 2;
+                }
+                "hashfull" => {
+                    info.hashfull = parts.get(i + 1).and_then(|v| v.parse().ok());
+                    i += 2;
+                }
+                "multipv" => {
+                    info.multipv = parts.get(i + 1).and_then(|v| v.parse().ok());
+                    i += 2;
+                }
+                "currmove" => {
+                    info.currmove = parts.get(i + 1).map(|v| v.to_string());
+                    i += 2;
+                }
+                "currmovenumber" => {
+                    info.currmovenumber = parts.get(i + 1).and_then(|v| v.parse().ok());
+                    i += 2;
+                }
+                "score" => {
+                    match (parts.get(i + 1), parts.get(i + 2)) {
+                        (Some(&"cp"), Some(value)) => {
+                            info.score = value.parse().ok().map(UsiScore::Cp);
+                        }
+                        (Some(&"mate"), Some(value)) => {
+                            info.score = Some(UsiScore::Mate(match *value {
+                                "+" | "-" => None,
+                                other => other.trim_start_matches('+').parse().ok(),
+                            }));
+                        }
+                        _ => {}
+                    }
+                    i += 3;
+                    info.bound = Some(ScoreBound::Exact);
+                }
+                "winrate" => {
+                    info.win_rate = parts.get(i + 1).and_then(|v| v.parse().ok());
+                    i += 2;
+                }
+                "lowerbound" => {
+                    info.bound = Some(ScoreBound::Lower);
+                    i += 1;
+                }
+                "upperbound" => {
+                    info.bound = Some(ScoreBound::Upper);
+                    i += 1;
+                }
+                "pv" => {
+                    // The principal variation runs to the end of the line
+                    info.pv = parts[i + 1..].iter().map(|m| m.to_string()).collect();
+                    break;
+                }
+                "string" => {
+                    info.string = Some(parts[i + 1..].join(" "));
+                    info.error = ShogiError::from_usi_info(line);
+                    break;
+                }
+                _ => i += 1,
+            }
+        }
+
+        Some(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_full_info_line() {
+        let line = "info depth 12 seldepth 18 score cp 145 lowerbound winrate 560 nodes 123456 \
+                    nps 987654 hashfull 321 time 125 multipv 1 pv 7g7f 3c3d 2g2f";
+        let info = UsiInfo::parse(line).unwrap();
+        assert_eq!(info.depth, Some(12));
+        assert_eq!(info.seldepth, Some(18));
+        assert_eq!(info.score, Some(UsiScore::Cp(145)));
+        assert_eq!(info.bound, Some(ScoreBound::Lower));
+        assert_eq!(info.win_rate, Some(560));
+        // Plain USI scores are for the side to move until the engine manager says otherwise
+        assert_eq!(info.perspective, EvaluationPerspective::SideToMove);
+        assert_eq!(info.nodes, Some(123456));
+        assert_eq!(info.nps, Some(987654));
+        assert_eq!(info.hashfull, Some(321));
+        assert_eq!(info.time_ms, Some(125));
+        assert_eq!(info.multipv, Some(1));
+        assert_eq!(info.pv, vec!["7g7f", "3c3d", "2g2f"]);
+    }
+
+    #[test]
+    fn test_parse_mate_and_currmove() {
+        let info = UsiInfo::parse("info depth 5 score mate -3 pv 5a4b").unwrap();
+        assert_eq!(info.score, Some(UsiScore::Mate(Some(-3))));
+
+        let info = UsiInfo::parse("info score mate + pv G*5b").unwrap();
+        assert_eq!(info.score, Some(UsiScore::Mate(None)));
+
+        let info = UsiInfo::parse("info currmove 7g7f currmovenumber 3").unwrap();
+        assert_eq!(info.currmove.as_deref(), Some("7g7f"));
+        assert_eq!(info.currmovenumber, Some(3));
+    }
+
+    #[test]
+    fn test_parse_string_and_non_info() {
+        let info = UsiInfo::parse("info string Board state updated.").unwrap();
+        assert_eq!(info.string.as_deref(), Some("Board state updated."));
+        assert_eq!(info.error, None);
+        assert!(UsiInfo::parse("bestmove 7g7f").is_none());
+
+        let info = UsiInfo::parse("info string error parse: Failed to parse FEN: bad").unwrap();
+        assert_eq!(info.error, Some(ShogiError::parse("Failed to parse FEN: bad")));
+    }
+}
@@ -0,0 +1,192 @@
+//! Large Asset Storage
+//!
+//! Opening books and tablebase files can run to hundreds of megabytes while a
+//! game reads a small part of them. `AssetStorage` maps such a file into memory
+//! so the operating system pages in only what lookups touch, and falls back to
+//! reading the whole file where mapping is unavailable (WASM) or fails. Every
+//! read marks the pages it covered, so the statistics hub can report how much
+//! of each asset has actually been paged in.
+
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Granularity of the page-touch statistics
+pub const PAGE_SIZE: usize = 4096;
+
+enum Backing {
+    #[cfg(not(target_arch = "wasm32"))]
+    Mapped(memmap2::Mmap),
+    InMemory(Box<[u8]>),
+}
+
+impl Backing {
+    fn bytes(&self) -> &[u8] {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            Backing::Mapped(mmap) => mmap,
+            Backing::InMemory(bytes) => bytes,
+        }
+    }
+}
+
+/// Read-only bytes of a large asset file with page-touch statistics
+pub struct AssetStorage {
+    name: String,
+    backing: Backing,
+    /// One bit per page, set on the first read covering it
+    touched: Vec<AtomicU64>,
+    pages_touched: AtomicU64,
+    reads: AtomicU64,
+}
+
+impl std::fmt::Debug for AssetStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AssetStorage").field("stats", &self.stats()).finish()
+    }
+}
+
+/// Memory use of one asset
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssetStats {
+    pub name: String,
+    /// Whether the file is memory-mapped rather than read into memory
+    pub mapped: bool,
+    pub size_bytes: u64,
+    pub pages: u64,
+    /// Pages covered by at least one read
+    pub pages_touched: u64,
+    pub reads: u64,
+    /// Bytes held in memory: the touched pages when mapped, the whole file otherwise
+    pub resident_bytes: u64,
+}
+
+impl AssetStorage {
+    /// Map the file at `path`, or read it into memory where mapping is unavailable
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let name = path
+            .file_name()
+            .map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned());
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let file = std::fs::File::open(path)?;
+            // The asset files are only ever replaced, not modified in place
+            if let Ok(mmap) = unsafe { memmap2::Mmap::map(&file) } {
+                return Ok(Self::with_backing(name, Backing::Mapped(mmap)));
+            }
+        }
+        Ok(Self::from_bytes(name, std::fs::read(path)?))
+    }
+
+    /// Storage over bytes already in memory, such as an embedded or fetched asset
+    pub fn from_bytes(name: impl Into<String>, bytes: Vec<u8>) -> Self {
+        Self::with_backing(name.into(), Backing::InMemory(bytes.into_boxed_slice()))
+    }
+
+    fn with_backing(name: String, backing: Backing) -> Self {
+        let pages = backing.bytes().len().div_ceil(PAGE_SIZE);
+        Self {
+            name,
+            backing,
+            touched: (0..pages.div_ceil(64)).map(|_| AtomicU64::new(0)).collect(),
+            pages_touched: AtomicU64::new(0),
+            reads: AtomicU64::new(0),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn len(&self) -> usize {
+        self.backing.bytes().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_mapped(&self) -> bool {
+        !matches!(self.backing, Backing::InMemory(_))
+    }
+
+    /// `len` bytes at `offset`, or `None` if they run past the end
+    pub fn read(&self, offset: usize, len: usize) -> Option<&[u8]> {
+        let bytes = self.backing.bytes().get(offset..offset.checked_add(len)?)?;
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        if len > 0 {
+            for page in offset / PAGE_SIZE..=(offset + len - 1) / PAGE_SIZE {
+                let bit = 1u64 << (page % 64);
+                if self.touched[page / 64].fetch_or(bit, Ordering::Relaxed) & bit == 0 {
+                    self.pages_touched.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+        Some(bytes)
+    }
+
+    /// Little-endian `u32` at `offset`
+    pub fn read_u32(&self, offset: usize) -> Option<u32> {
+        self.read(offset, 4).map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    /// Little-endian `u64` at `offset`
+    pub fn read_u64(&self, offset: usize) -> Option<u64> {
+        self.read(offset, 8).map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    pub fn stats(&self) -> AssetStats {
+        let size_bytes = self.len() as u64;
+        let pages_touched = self.pages_touched.load(Ordering::Relaxed);
+        let resident_bytes = if self.is_mapped() {
+            (pages_touched * PAGE_SIZE as u64).min(size_bytes)
+        } else {
+            size_bytes
+        };
+        AssetStats {
+            name: self.name.clone(),
+            mapped: self.is_mapped(),
+            size_bytes,
+            pages: self.len().div_ceil(PAGE_SIZE) as u64,
+            pages_touched,
+            reads: self.reads.load(Ordering::Relaxed),
+            resident_bytes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_mark_touched_pages() {
+        let storage = AssetStorage::from_bytes("test.bin", vec![7u8; PAGE_SIZE * 4 + 10]);
+        assert_eq!(storage.stats().pages, 5);
+
+        assert_eq!(storage.read(PAGE_SIZE - 2, 4), Some(&[7u8; 4][..]));
+        assert!(storage.read(PAGE_SIZE, 8).is_some());
+        assert!(storage.read(PAGE_SIZE * 4 + 8, 4).is_none());
+
+        let stats = storage.stats();
+        assert_eq!(stats.pages_touched, 2);
+        assert_eq!(stats.reads, 2);
+        assert!(!stats.mapped);
+        assert_eq!(stats.resident_bytes, stats.size_bytes);
+    }
+
+    #[test]
+    fn test_open_maps_file() {
+        let path = std::env::temp_dir().join("asset_storage_test.bin");
+        std::fs::write(&path, 42u64.to_le_bytes()).unwrap();
+        let storage = AssetStorage::open(&path).unwrap();
+        assert_eq!(storage.read_u64(0), Some(42));
+        assert_eq!(storage.name(), "asset_storage_test.bin");
+        assert_eq!(storage.stats().pages_touched, 1);
+        std::fs::remove_file(&path).ok();
+    }
+}
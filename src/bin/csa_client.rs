@@ -0,0 +1,95 @@
+//! CSA Server Client
+//!
+//! Play the engine on floodgate or any other server speaking the CSA protocol
+//! and report the results of the session.
+
+use clap::Parser;
+use shogi_engine::csa_client::{CsaClient, CsaClientConfig, DEFAULT_PORT};
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+#[command(name = "csa-client")]
+#[command(about = "Play games on a CSA protocol server such as floodgate")]
+struct Cli {
+    /// Server host name
+    #[arg(long, default_value = "wdoor.c.u-tokyo.ac.jp")]
+    host: String,
+
+    /// Server port
+    #[arg(long, default_value_t = DEFAULT_PORT)]
+    port: u16,
+
+    /// Login name
+    #[arg(short, long)]
+    user: String,
+
+    /// Password (floodgate expects `<game name>,<trip>`, e.g. `floodgate-300-10F,secret`)
+    #[arg(short, long)]
+    password: String,
+
+    /// Number of games to play; plays until disconnected if omitted
+    #[arg(short, long)]
+    games: Option<u32>,
+
+    /// Time kept in reserve per move for network latency, in milliseconds
+    #[arg(long, default_value_t = 1000)]
+    margin_ms: u64,
+
+    /// Seconds of silence after which a keep-alive line is sent
+    #[arg(long, default_value_t = 30)]
+    keep_alive: u64,
+
+    /// Engine option as NAME=VALUE (repeatable)
+    #[arg(short = 'o', long = "option", value_name = "NAME=VALUE")]
+    options: Vec<String>,
+
+    /// Write the session results as JSON
+    #[arg(long, value_name = "FILE")]
+    results: Option<PathBuf>,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    env_logger::init();
+    let cli = Cli::parse();
+
+    let mut config = CsaClientConfig::new(&cli.host, &cli.user, &cli.password);
+    config.port = cli.port;
+    config.games = cli.games;
+    config.margin_ms = cli.margin_ms;
+    config.keep_alive = Duration::from_secs(cli.keep_alive.max(1));
+    for option in &cli.options {
+        let (name, value) = option
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid option (expected NAME=VALUE): {}", option))?;
+        config.usi_options.push((name.to_string(), value.to_string()));
+    }
+
+    let mut client = CsaClient::connect(config)?;
+    let record = client.run()?;
+
+    println!("\n=== CSA Session Results ===");
+    for game in &record.games {
+        println!(
+            "{}  vs {:<20} {:?} ({})",
+            game.game_id,
+            game.opponent,
+            game.outcome,
+            game.reason.as_deref().unwrap_or("-")
+        );
+    }
+    println!(
+        "Wins: {}  Losses: {}  Draws: {}  Interrupted: {}",
+        record.wins, record.losses, record.draws, record.interrupted
+    );
+    if let Some(rate) = record.score_rate() {
+        println!("Score: {:.1}%", rate * 100.0);
+    }
+
+    if let Some(path) = &cli.results {
+        std::fs::write(path, serde_json::to_string_pretty(record)?)?;
+    }
+
+    Ok(())
+}
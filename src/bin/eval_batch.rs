@@ -0,0 +1,217 @@
+//! Static Evaluation Batch Tool
+//!
+//! Evaluates batches of SFEN positions without searching and writes every
+//! position's evaluation terms as CSV, for inspecting tuning datasets and for
+//! comparing weight versions: run it once per weights file and diff the
+//! output, or pass `--baseline-weights` to get each position's score change in
+//! a single file. Terms without a column of their own are summed into `other`.
+
+use clap::Parser;
+use shogi_engine::bitboards::BitboardBoard;
+use shogi_engine::evaluation::breakdown::{EvaluationBreakdown, TERM_ORDER};
+use shogi_engine::evaluation::PositionEvaluator;
+use shogi_engine::types::core::Player;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "eval-batch",
+    about = "Evaluate batches of SFEN positions statically and write per-term CSV"
+)]
+struct Args {
+    /// File with one SFEN per line, optionally prefixed with `sfen`; stdin if omitted
+    input: Option<PathBuf>,
+
+    /// CSV output file; stdout if omitted
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Tuned weights file to evaluate with
+    #[arg(long)]
+    weights: Option<PathBuf>,
+
+    /// Weights file to compare against; adds its score and the difference to each row
+    #[arg(long)]
+    baseline_weights: Option<PathBuf>,
+
+    /// Write each term's middlegame and endgame values instead of the interpolated one
+    #[arg(long)]
+    tapered: bool,
+
+    /// Report scores with Black positive instead of from the side to move
+    #[arg(long)]
+    black_positive: bool,
+}
+
+fn load_evaluator(weights: Option<&Path>) -> Result<PositionEvaluator, Box<dyn std::error::Error>> {
+    let mut evaluator = PositionEvaluator::new();
+    if let Some(path) = weights {
+        evaluator
+            .load_tuned_weights(path)
+            .map_err(|e| format!("failed to load weights from {}: {}", path.display(), e))?;
+    }
+    Ok(evaluator)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let mut evaluator = load_evaluator(args.weights.as_deref())?;
+    let mut baseline = args
+        .baseline_weights
+        .as_deref()
+        .map(|path| load_evaluator(Some(path)))
+        .transpose()?;
+
+    let input: Box<dyn BufRead> = match &args.input {
+        Some(path) => Box::new(BufReader::new(File::open(path)?)),
+        None => Box::new(BufReader::new(io::stdin())),
+    };
+    let mut output: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout())),
+    };
+
+    writeln!(output, "{}", header(args.tapered, baseline.is_some()))?;
+    let (mut evaluated, mut skipped) = (0usize, 0usize);
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        let Some(sfen) = parse_line(&line) else {
+            continue;
+        };
+        let (board, player, captured) = match BitboardBoard::from_fen(sfen) {
+            Ok(position) => position,
+            Err(e) => {
+                eprintln!("line {}: skipping invalid SFEN: {}", index + 1, e);
+                skipped += 1;
+                continue;
+            }
+        };
+        let breakdown = evaluator.explain_evaluation(&board, player, &captured);
+        let baseline_score = baseline
+            .as_mut()
+            .map(|baseline| baseline.explain_evaluation(&board, player, &captured).score);
+        let row = Row { line: index + 1, sfen, breakdown: &breakdown, baseline_score };
+        writeln!(output, "{}", row.to_csv(args.tapered, args.black_positive))?;
+        evaluated += 1;
+    }
+    output.flush()?;
+    eprintln!("evaluated {} positions, skipped {}", evaluated, skipped);
+    Ok(())
+}
+
+/// The SFEN on an input line, or `None` for blank and `#` comment lines
+fn parse_line(line: &str) -> Option<&str> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let line = line.strip_prefix("position").map_or(line, str::trim_start);
+    Some(line.strip_prefix("sfen").map_or(line, str::trim_start))
+}
+
+fn header(tapered: bool, baseline: bool) -> String {
+    let mut columns: Vec<String> = ["line", "sfen", "side_to_move", "phase", "score"]
+        .iter()
+        .map(|c| c.to_string())
+        .collect();
+    if baseline {
+        columns.push("baseline_score".to_string());
+        columns.push("score_delta".to_string());
+    }
+    for term in TERM_ORDER.iter().chain(&["other"]) {
+        if tapered {
+            columns.push(format!("{}_mg", term));
+            columns.push(format!("{}_eg", term));
+        } else {
+            columns.push(term.to_string());
+        }
+    }
+    columns.join(",")
+}
+
+/// One evaluated position
+struct Row<'a> {
+    line: usize,
+    sfen: &'a str,
+    breakdown: &'a EvaluationBreakdown,
+    baseline_score: Option<i32>,
+}
+
+impl Row<'_> {
+    fn to_csv(&self, tapered: bool, black_positive: bool) -> String {
+        let breakdown = self.breakdown;
+        let sign = if black_positive && breakdown.player == Player::White { -1 } else { 1 };
+        let mut fields = vec![
+            self.line.to_string(),
+            self.sfen.to_string(),
+            if breakdown.player == Player::Black { "b" } else { "w" }.to_string(),
+            breakdown.phase.to_string(),
+            (sign * breakdown.score).to_string(),
+        ];
+        if let Some(baseline_score) = self.baseline_score {
+            fields.push((sign * baseline_score).to_string());
+            fields.push((sign * (breakdown.score - baseline_score)).to_string());
+        }
+
+        // (mg, eg, interpolated) per column, unknown terms summed into the last
+        let mut values = vec![(0, 0, 0); TERM_ORDER.len() + 1];
+        for term in &breakdown.terms {
+            let column = TERM_ORDER
+                .iter()
+                .position(|known| *known == term.name)
+                .unwrap_or(TERM_ORDER.len());
+            let value = &mut values[column];
+            *value = (value.0 + term.mg, value.1 + term.eg, value.2 + term.score);
+        }
+        for (mg, eg, score) in values {
+            if tapered {
+                fields.push((sign * mg).to_string());
+                fields.push((sign * eg).to_string());
+            } else {
+                fields.push((sign * score).to_string());
+            }
+        }
+        fields.join(",")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shogi_engine::types::evaluation::TaperedScore;
+    use std::collections::HashMap;
+
+    #[test]
+    fn parse_line_strips_prefixes_and_skips_comments() {
+        let sfen = "4k4/9/9/9/9/9/9/9/4K4 b G 1";
+        assert_eq!(parse_line(sfen), Some(sfen));
+        assert_eq!(parse_line(&format!("  sfen {}", sfen)), Some(sfen));
+        assert_eq!(parse_line(&format!("position sfen {}", sfen)), Some(sfen));
+        assert_eq!(parse_line("# dataset v2"), None);
+        assert_eq!(parse_line("   "), None);
+    }
+
+    #[test]
+    fn row_columns_match_header() {
+        let components = HashMap::from([
+            ("material".to_string(), TaperedScore::new(100)),
+            ("custom".to_string(), TaperedScore::new_tapered(10, 30)),
+            ("spare".to_string(), TaperedScore::new(5)),
+        ]);
+        let breakdown = EvaluationBreakdown::from_components(Player::White, 0, 135, &components);
+        let row = Row { line: 3, sfen: "sfen", breakdown: &breakdown, baseline_score: Some(100) };
+
+        let csv = row.to_csv(false, true);
+        let fields: Vec<&str> = csv.split(',').collect();
+        assert_eq!(fields.len(), header(false, true).split(',').count());
+        assert_eq!(&fields[..7], &["3", "sfen", "w", "0", "-135", "-100", "-35"]);
+        assert_eq!(fields[7], "-100");
+        assert_eq!(fields.last(), Some(&"-35"));
+
+        let csv = row.to_csv(true, false);
+        assert_eq!(csv.split(',').count(), header(true, true).split(',').count());
+        assert!(csv.ends_with(",15,35"));
+    }
+}
@@ -0,0 +1,92 @@
+//! Win Rate Scale Fitting Tool
+//!
+//! Fits the scale of the centipawn to win probability curve to game results,
+//! for checking `WIN_RATE_SCALE` against self-play. Each input line is a
+//! searched score and the result of its game for the side the score is for
+//! (1 win, 0.5 draw, 0 loss), separated by a comma or whitespace, e.g. one line
+//! per move of every game with the score from the mover's point of view.
+
+use clap::Parser;
+use shogi_engine::search::win_probability::{fit_scale, FIT_SCORE_CAP, WIN_RATE_SCALE};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "fit-win-rate",
+    about = "Fit the win probability scale to searched scores and game results"
+)]
+struct Args {
+    /// File with one `score result` pair per line; stdin if omitted
+    input: Option<PathBuf>,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let input: Box<dyn BufRead> = match &args.input {
+        Some(path) => Box::new(BufReader::new(File::open(path)?)),
+        None => Box::new(BufReader::new(io::stdin())),
+    };
+
+    let mut samples = Vec::new();
+    let mut skipped = 0usize;
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        match parse_line(&line) {
+            Some(Ok(sample)) => samples.push(sample),
+            Some(Err(e)) => {
+                eprintln!("line {}: skipping: {}", index + 1, e);
+                skipped += 1;
+            }
+            None => {}
+        }
+    }
+    let used = samples.iter().filter(|(score, _)| score.abs() <= FIT_SCORE_CAP).count();
+    eprintln!("read {} samples, skipped {}, {} within the score cap", samples.len(), skipped, used);
+
+    match fit_scale(&samples) {
+        Some(scale) => {
+            println!("fitted scale: {:.1} centipawns per unit of logit", scale);
+            println!("current WIN_RATE_SCALE: {:.1}", WIN_RATE_SCALE);
+            Ok(())
+        }
+        None => Err("the samples do not determine a positive scale".into()),
+    }
+}
+
+/// The score and result on an input line, or `None` for blank and `#` comment lines
+fn parse_line(line: &str) -> Option<Result<(i32, f64), String>> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let mut fields = line.split(|c: char| c == ',' || c.is_whitespace()).filter(|f| !f.is_empty());
+    let (Some(score), Some(result), None) = (fields.next(), fields.next(), fields.next()) else {
+        return Some(Err(format!("expected `score result`: {}", line)));
+    };
+    let score = match score.parse::<i32>() {
+        Ok(score) => score,
+        Err(_) => return Some(Err(format!("invalid score: {}", score))),
+    };
+    match result.parse::<f64>() {
+        Ok(result) if (0.0..=1.0).contains(&result) => Some(Ok((score, result))),
+        _ => Some(Err(format!("invalid result: {}", result))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_line_reads_pairs_and_skips_comments() {
+        assert_eq!(parse_line("250 1"), Some(Ok((250, 1.0))));
+        assert_eq!(parse_line(" -80,0.5 "), Some(Ok((-80, 0.5))));
+        assert_eq!(parse_line("# self-play, 2000 games"), None);
+        assert_eq!(parse_line("   "), None);
+        assert!(matches!(parse_line("250"), Some(Err(_))));
+        assert!(matches!(parse_line("250 2"), Some(Err(_))));
+        assert!(matches!(parse_line("cp 1"), Some(Err(_))));
+    }
+}
@@ -5,13 +5,28 @@
 //!   cargo run --bin generate_magic_tables [--output <path>]
 //!
 //! If no output path is specified, defaults to `resources/magic_tables/magic_table.bin`
+//!
+//! With `--embedded`, it instead runs the deterministic magic search and writes the
+//! Rust source of the magic numbers compiled into the engine:
+//!   cargo run --release --bin generate_magic_tables -- --embedded [--output <path>]
+//!
+//! The default output path is then `src/bitboards/magic/embedded.rs`.
 
+use shogi_engine::bitboards::magic::magic_finder::{find_embeddable_magic, MagicFinder};
+use shogi_engine::types::core::PieceType;
 use std::env;
+use std::fmt::Write as _;
 use std::path::PathBuf;
 use std::time::Instant;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = env::args().collect();
+
+    if args.iter().any(|a| a == "--embedded") {
+        let output_path = output_arg(&args)
+            .unwrap_or_else(|| PathBuf::from("src/bitboards/magic/embedded.rs"));
+        return write_embedded_source(&output_path);
+    }
     
     // Parse output path from command line arguments
     let output_path = if let Some(output_idx) = args.iter().position(|a| a == "--output" || a == "-o") {
@@ -82,3 +97,66 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+fn output_arg(args: &[String]) -> Option<PathBuf> {
+    let output_idx = args.iter().position(|a| a == "--output" || a == "-o")?;
+    match args.get(output_idx + 1) {
+        Some(path) => Some(PathBuf::from(path)),
+        None => {
+            eprintln!("Error: --output requires a path argument");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Search magic numbers for every rook square, then every bishop square, and
+/// write them out as the `embedded` module
+fn write_embedded_source(output_path: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+    let start_time = Instant::now();
+    let finder = MagicFinder::new();
+    let mut entries = 0usize;
+    let mut magics = Vec::new();
+    for piece_type in [PieceType::Rook, PieceType::Bishop] {
+        let mut squares = Vec::with_capacity(81);
+        for square in 0..81u8 {
+            let magic = find_embeddable_magic(square, piece_type);
+            entries += 1 << finder.generate_relevant_mask(square, piece_type).count_ones();
+            eprintln!("{:?} square {}: {:#034x}", piece_type, square, magic);
+            squares.push(magic);
+        }
+        magics.push(squares);
+    }
+
+    let mut source = String::new();
+    writeln!(source, "//! Magic numbers embedded at build time")?;
+    writeln!(source, "//!")?;
+    writeln!(
+        source,
+        "//! Generated by `cargo run --release --bin generate_magic_tables -- --embedded`;"
+    )?;
+    writeln!(
+        source,
+        "//! do not edit by hand. `MagicTable::from_embedded` builds the attack tables"
+    )?;
+    writeln!(source, "//! from these numbers.")?;
+    writeln!(source)?;
+    writeln!(source, "#![allow(clippy::unreadable_literal)]")?;
+    writeln!(source)?;
+    writeln!(source, "/// Attack table entries over all rook and bishop squares")?;
+    writeln!(source, "pub const ATTACK_TABLE_ENTRIES: usize = {};", entries)?;
+    for (name, squares) in ["ROOK_MAGICS", "BISHOP_MAGICS"].iter().zip(&magics) {
+        writeln!(source)?;
+        writeln!(source, "pub const {}: [u128; 81] = [", name)?;
+        for magic in squares {
+            writeln!(source, "    {:#034x},", magic)?;
+        }
+        writeln!(source, "];")?;
+    }
+
+    std::fs::write(output_path, source)?;
+    println!(
+        "Wrote embedded magic numbers to {} in {:?}",
+        output_path.display(),
+        start_time.elapsed()
+    );
+    Ok(())
+}
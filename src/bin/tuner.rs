@@ -100,6 +100,19 @@ enum Commands {
         #[arg(short, long, default_value_t = 100)]
         iterations: u32,
     },
+    /// Tune the piece-square tables entry by entry, starting from the built-in
+    /// tables, and write them to the output path as a PST data file
+    Pst {
+        /// Semantic version recorded in the PST file
+        #[arg(long, default_value = "0.1.0")]
+        version: String,
+        /// Description recorded in the PST file
+        #[arg(long)]
+        description: Option<String>,
+        /// Step size; weights are centipawns, so steps are large
+        #[arg(long, default_value_t = 10_000.0)]
+        learning_rate: f64,
+    },
 }
 
 /// Main function for the tuning binary
@@ -127,6 +140,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             Commands::Benchmark { iterations } => {
                 run_benchmark(&cli, *iterations)?;
             }
+            Commands::Pst { version, description, learning_rate } => {
+                run_pst_tuning(&cli, version, description.clone(), *learning_rate)?;
+            }
         }
         return Ok(());
     }
@@ -228,6 +244,51 @@ fn run_tuning(cli: &Cli) -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Tune the piece-square tables and write them as a PST data file
+fn run_pst_tuning(
+    cli: &Cli,
+    version: &str,
+    description: Option<String>,
+    learning_rate: f64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use shogi_engine::evaluation::piece_square_tables::PieceSquareTables;
+    use shogi_engine::evaluation::pst_loader::PieceSquareTableLoader;
+    use shogi_engine::tuning::optimizer::TexelTuner;
+    use shogi_engine::tuning::pst_features::{
+        pst_training_positions, pst_weights, tables_from_weights, DEFAULT_PST_K_FACTOR,
+    };
+
+    let config = create_tuning_config(cli)?;
+    let data_processor = DataProcessor::new(config.position_filter.clone());
+    let positions = pst_training_positions(&load_dataset(&cli.dataset, &data_processor)?);
+    if positions.is_empty() {
+        return Err("No training positions with a FEN found in dataset".into());
+    }
+    if cli.verbose {
+        println!("Tuning piece-square tables on {} positions...", positions.len());
+    }
+
+    let mut tuner = TexelTuner::with_params(
+        positions,
+        Some(pst_weights(&PieceSquareTables::new())),
+        DEFAULT_PST_K_FACTOR,
+        learning_rate,
+        0.9,
+        0.0,
+        cli.regularization,
+        cli.iterations as usize,
+        1e-6,
+        50,
+    );
+    let result = tuner.optimize();
+    let tables = tables_from_weights(&result.optimized_weights)?;
+    PieceSquareTableLoader::save(&tables, &cli.output, Some(version.to_string()), description)?;
+
+    println!("Final error: {:.6} after {} iterations", result.final_error, result.iterations);
+    println!("Piece-square tables saved to {:?}", cli.output);
+    Ok(())
+}
+
 /// Run validation-only mode
 fn run_validation(cli: &Cli, folds: u32) -> Result<(), Box<dyn std::error::Error>> {
     if cli.verbose {
@@ -2,10 +2,13 @@ use crate::search::RepetitionState;
 use crate::types::board::{CapturedPieces, GamePhase};
 use crate::types::core::{Move, Piece, PieceType, Player, Position};
 use crate::types::{Bitboard, EMPTY_BITBOARD, ImpasseOutcome, ImpasseResult, MagicError, MagicTable, clear_bit, get_lsb, is_bit_set, set_bit};
+use std::borrow::Cow;
+use std::fmt;
 use std::sync::{Arc, OnceLock};
 
 // Include the magic bitboard module
 pub mod api;
+pub mod attack_map;
 pub mod attack_patterns;
 pub mod bit_iterator;
 pub mod bit_utils;
@@ -13,10 +16,12 @@ pub mod bitscan;
 pub mod branch_opt;
 pub mod cache_opt;
 pub mod debruijn;
+pub mod influence;
 pub mod integration;
 pub mod lookup_tables;
 pub mod magic;
 pub mod masks;
+pub mod pins;
 pub mod platform_detection;
 pub mod popcount;
 pub mod sliding_moves;
@@ -178,39 +183,31 @@ pub(crate) fn record_attack_table_init(time_nanos: u64, memory_bytes: u64) {
 
 /// Get or initialize the shared magic table singleton
 /// Returns None if magic table initialization fails
-/// 
-/// Attempts to load from precomputed file first, then generates if not found.
+///
+/// Built from the magic numbers embedded at build time, so no search runs here.
 fn get_shared_magic_table() -> Option<Arc<MagicTable>> {
     Some(
         SHARED_MAGIC_TABLE
-            .get_or_init(|| {
-                let default_path = magic::magic_table::get_default_magic_table_path();
-                
-                // Try to load or generate magic table
-                let table = MagicTable::try_load_or_generate(&default_path, true)
-                    .unwrap_or_else(|e| {
-                        crate::utils::telemetry::debug_log(&format!(
-                            "[MAGIC_TABLE] Failed to load or generate magic table: {}, using default",
-                            e
-                        ));
-                        MagicTable::default()
-                    });
-                
-                Arc::new(table)
-            })
+            .get_or_init(|| Arc::new(MagicTable::from_embedded()))
             .clone(),
     )
 }
 
+/// Magic table new boards start with: the shared table, or `None` when the
+/// `raycast-sliding` feature forces ray-cast sliding attacks for debugging
+fn default_magic_table() -> Option<Arc<MagicTable>> {
+    if cfg!(feature = "raycast-sliding") {
+        None
+    } else {
+        get_shared_magic_table()
+    }
+}
+
 /// Initialize the shared magic table singleton explicitly
 /// This should be called once at startup if magic support is desired
-/// 
-/// Attempts to load from precomputed file first, then generates if not found.
 pub fn init_shared_magic_table() -> Result<(), MagicError> {
-    let default_path = magic::magic_table::get_default_magic_table_path();
-    let table = MagicTable::try_load_or_generate(&default_path, true)?;
     SHARED_MAGIC_TABLE
-        .set(Arc::new(table))
+        .set(Arc::new(MagicTable::from_embedded()))
         .map_err(|_| MagicError::InitializationFailed {
             reason: "Magic table already initialized".to_string(),
         })?;
@@ -271,6 +268,8 @@ pub struct BitboardBoard {
     sliding_generator: Option<sliding_moves::SlidingMoveGenerator>,
     side_to_move: Player,
     repetition_state: RepetitionState,
+    /// Incrementally maintained attack maps, when enabled
+    attack_maps: Option<Box<attack_map::AttackMaps>>,
 }
 
 impl BitboardBoard {
@@ -335,10 +334,11 @@ impl BitboardBoard {
             squares: [None; 81],
             attack_patterns: AttackPatterns::new(),
             attack_tables,
-            magic_table: None,
+            magic_table: default_magic_table(),
             sliding_generator: None,
             side_to_move: Player::Black,
             repetition_state: RepetitionState::None,
+            attack_maps: None,
         }
     }
 
@@ -400,6 +400,7 @@ impl BitboardBoard {
         }
         set_bit(&mut self.occupied, position);
         self.set_square(position, Some(piece));
+        self.update_attack_maps(position);
     }
 
     pub fn remove_piece(&mut self, position: Position) -> Option<Piece> {
@@ -436,12 +437,45 @@ impl BitboardBoard {
             }
             clear_bit(&mut self.occupied, position);
             self.squares[idx] = None;
+            self.update_attack_maps(position);
             Some(piece)
         } else {
             None
         }
     }
 
+    /// Start maintaining attack maps on every `place_piece`/`remove_piece`
+    pub fn enable_attack_maps(&mut self) {
+        if self.attack_maps.is_none() {
+            self.attack_maps = Some(Box::new(attack_map::AttackMaps::compute(self)));
+        }
+    }
+
+    pub fn disable_attack_maps(&mut self) {
+        self.attack_maps = None;
+    }
+
+    /// The incrementally maintained attack maps, if enabled
+    pub fn tracked_attack_maps(&self) -> Option<&attack_map::AttackMaps> {
+        self.attack_maps.as_deref()
+    }
+
+    /// Attack maps of the current position: the tracked ones when enabled,
+    /// otherwise computed on the spot
+    pub fn attack_maps(&self) -> Cow<'_, attack_map::AttackMaps> {
+        match self.attack_maps.as_deref() {
+            Some(maps) => Cow::Borrowed(maps),
+            None => Cow::Owned(attack_map::AttackMaps::compute(self)),
+        }
+    }
+
+    fn update_attack_maps(&mut self, position: Position) {
+        if let Some(mut maps) = self.attack_maps.take() {
+            maps.update(self, position);
+            self.attack_maps = Some(maps);
+        }
+    }
+
     pub fn get_piece(&self, position: Position) -> Option<Piece> {
         if !position.is_valid() {
             return None;
@@ -624,11 +658,30 @@ impl BitboardBoard {
         }
     }
 
+    /// Pins against `player`'s king and `player`'s discovered-check candidates
+    pub fn pin_info(&self, player: Player) -> pins::PinInfo {
+        pins::PinInfo::compute(self, player)
+    }
+
+    /// `player`'s pieces pinned to their own king
+    pub fn pinned_pieces(&self, player: Player) -> Bitboard {
+        self.pin_info(player).pinned
+    }
+
+    /// `player`'s pieces that give a discovered check by moving off their line
+    pub fn discovered_check_candidates(&self, player: Player) -> Bitboard {
+        self.pin_info(player).discovered_check_candidates
+    }
+
     /// Check if a square is attacked by a player
     /// Task 3.0.3.1: Rewritten to iterate attackers by bitboard instead of nested 9×9 loops
     pub fn is_square_attacked_by(&self, target_pos: Position, attacking_player: Player) -> bool {
         use crate::bitboards::integration::GlobalOptimizer;
         
+        if let Some(maps) = self.attack_maps.as_deref() {
+            return maps.is_attacked(target_pos, attacking_player);
+        }
+
         let target_idx = target_pos.to_index();
         let player_idx = if attacking_player == Player::Black { 0 } else { 1 };
         let _target_bit = 1u128 << target_idx;
@@ -696,8 +749,12 @@ impl BitboardBoard {
         let from_idx = from_pos.to_index();
         
         match piece_type {
-            // Non-sliding pieces: use precomputed attack tables
-            PieceType::Pawn | PieceType::Lance | PieceType::Knight | PieceType::Silver
+            // Pawns and lances have no precomputed attack table
+            PieceType::Pawn | PieceType::Lance => {
+                (self.attacks_from(from_pos, piece_type, player) & (1u128 << target_idx)) != 0
+            }
+            // Other non-sliding pieces: use precomputed attack tables
+            PieceType::Knight | PieceType::Silver
             | PieceType::Gold | PieceType::King | PieceType::PromotedPawn
             | PieceType::PromotedLance | PieceType::PromotedKnight | PieceType::PromotedSilver => {
                 self.attack_tables.is_square_attacked(from_idx, target_idx, piece_type, player)
@@ -946,27 +1003,7 @@ impl BitboardBoard {
 
     pub fn to_fen(&self, player: Player, captured_pieces: &CapturedPieces) -> String {
         let mut fen = String::with_capacity(128);
-        for r in 0..9 {
-            let mut empty_squares = 0;
-            for c in 0..9 {
-                let pos = Position::new(r, c);
-                if let Some(piece) = self.get_piece(pos) {
-                    if empty_squares > 0 {
-                        fen.push_str(&empty_squares.to_string());
-                        empty_squares = 0;
-                    }
-                    fen.push_str(&piece.to_fen_char());
-                } else {
-                    empty_squares += 1;
-                }
-            }
-            if empty_squares > 0 {
-                fen.push_str(&empty_squares.to_string());
-            }
-            if r < 8 {
-                fen.push('/');
-            }
-        }
+        fen.push_str(&self.to_string());
         fen.push(' ');
         fen.push(if player == Player::Black { 'b' } else { 'w' });
         fen.push(' ');
@@ -1097,10 +1134,11 @@ impl BitboardBoard {
 
         // 3. Parse pieces in hand
         if parts[2] != "-" {
-            let mut count = 1;
+            // Counts may have two digits ("18p"); no count means one
+            let mut count = 0;
             for ch in parts[2].chars() {
                 if let Some(digit) = ch.to_digit(10) {
-                    count = digit;
+                    count = count * 10 + digit;
                 } else {
                     let hand_player = if ch.is_uppercase() {
                         Player::Black
@@ -1117,10 +1155,10 @@ impl BitboardBoard {
                         'r' => PieceType::Rook,
                         _ => return Err("Invalid FEN: unknown piece in hand"),
                     };
-                    for _ in 0..count {
+                    for _ in 0..count.max(1) {
                         captured_pieces.add_piece(piece_type, hand_player);
                     }
-                    count = 1;
+                    count = 0;
                 }
             }
         }
@@ -1177,6 +1215,7 @@ impl BitboardBoard {
             sliding_generator: None,
             side_to_move: Player::Black,
             repetition_state: RepetitionState::None,
+            attack_maps: None,
         })
     }
 
@@ -1207,6 +1246,42 @@ impl BitboardBoard {
         )
     }
 
+    /// Squares attacked by a piece of `piece_type` owned by `player` standing on
+    /// `square`, taking the current occupancy into account for sliding pieces
+    pub fn attacks_from(
+        &self,
+        square: Position,
+        piece_type: PieceType,
+        player: Player,
+    ) -> Bitboard {
+        match piece_type {
+            PieceType::Pawn | PieceType::Lance => {
+                let dir: i8 = if player == Player::Black { -1 } else { 1 };
+                let mut attacks = EMPTY_BITBOARD;
+                let mut row = square.row as i8 + dir;
+                while (0..9).contains(&row) {
+                    let target = Position::new(row as u8, square.col);
+                    set_bit(&mut attacks, target);
+                    if piece_type == PieceType::Pawn || self.is_square_occupied(target) {
+                        break;
+                    }
+                    row += dir;
+                }
+                attacks
+            }
+            PieceType::Rook | PieceType::Bishop => self.get_attack_pattern(square, piece_type),
+            PieceType::PromotedRook => {
+                self.get_attack_pattern(square, PieceType::Rook)
+                    | self.get_attack_pattern_precomputed(square, PieceType::King, player)
+            }
+            PieceType::PromotedBishop => {
+                self.get_attack_pattern(square, PieceType::Bishop)
+                    | self.get_attack_pattern_precomputed(square, PieceType::King, player)
+            }
+            _ => self.get_attack_pattern_precomputed(square, piece_type, player),
+        }
+    }
+
     /// Get attack table statistics and metadata
     pub fn get_attack_table_stats(&self) -> &attack_patterns::AttackTablesMetadata {
         self.attack_tables.memory_stats()
@@ -1582,6 +1657,33 @@ impl BoardTrait for BitboardBoard {
     }
 }
 
+/// The board field of SFEN, e.g. `lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL`
+impl fmt::Display for BitboardBoard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for r in 0..9 {
+            let mut empty_squares = 0;
+            for c in 0..9 {
+                if let Some(piece) = self.get_piece(Position::new(r, c)) {
+                    if empty_squares > 0 {
+                        write!(f, "{}", empty_squares)?;
+                        empty_squares = 0;
+                    }
+                    f.write_str(&piece.to_fen_char())?;
+                } else {
+                    empty_squares += 1;
+                }
+            }
+            if empty_squares > 0 {
+                write!(f, "{}", empty_squares)?;
+            }
+            if r < 8 {
+                f.write_str("/")?;
+            }
+        }
+        Ok(())
+    }
+}
+
 impl Clone for BitboardBoard {
     /// Task 5.0.5.2: Track board clone operations
     fn clone(&self) -> Self {
@@ -1601,7 +1703,33 @@ impl Clone for BitboardBoard {
             sliding_generator: self.sliding_generator.clone(),
             side_to_move: self.side_to_move,
             repetition_state: self.repetition_state,
+            attack_maps: self.attack_maps.clone(),
+        }
+    }
+
+    /// Copy `source` into an existing board, keeping the shared tables when both
+    /// boards already point at the same ones. Not counted as a clone.
+    fn clone_from(&mut self, source: &Self) {
+        self.pieces = source.pieces;
+        self.occupied = source.occupied;
+        self.black_occupied = source.black_occupied;
+        self.white_occupied = source.white_occupied;
+        self.squares = source.squares;
+        if !Arc::ptr_eq(&self.attack_tables, &source.attack_tables) {
+            self.attack_tables = Arc::clone(&source.attack_tables);
+        }
+        let same_magic_table = match (&self.magic_table, &source.magic_table) {
+            (Some(own), Some(theirs)) => Arc::ptr_eq(own, theirs),
+            (None, None) => true,
+            _ => false,
+        };
+        if !same_magic_table {
+            self.magic_table = source.magic_table.clone();
         }
+        self.sliding_generator.clone_from(&source.sliding_generator);
+        self.side_to_move = source.side_to_move;
+        self.repetition_state = source.repetition_state;
+        self.attack_maps.clone_from(&source.attack_maps);
     }
 }
 
@@ -1729,6 +1857,19 @@ mod tests {
         assert!(board.is_square_attacked_by(white_king_pos, Player::Black));
     }
 
+    #[test]
+    fn test_is_square_attacked_by_pawn_and_lance() {
+        let (board, _, _) = BitboardBoard::from_fen("4k4/9/4P4/9/9/l8/9/9/4K4 b - 1").unwrap();
+
+        // The pawn on 5c guards 5b only
+        assert!(board.is_square_attacked_by(Position::new(1, 4), Player::Black));
+        assert!(!board.is_square_attacked_by(Position::new(0, 4), Player::Black));
+
+        // The lance on 9f reaches down to 9i
+        assert!(board.is_square_attacked_by(Position::new(8, 0), Player::White));
+        assert!(!board.is_square_attacked_by(Position::new(4, 0), Player::White));
+    }
+
     #[test]
     fn test_is_square_attacked_by_drop_heavy() {
         // Position with many pieces in hand (drop-heavy scenario)
@@ -0,0 +1,188 @@
+//! Attack Maps
+//!
+//! Per-side attack maps that a board can keep up to date as pieces are placed
+//! and removed, so evaluation, exchange analysis and the influence heatmap can
+//! ask which squares a side attacks, and how often, without regenerating every
+//! piece's attacks. A change at one square only affects the piece standing
+//! there and the sliding pieces whose attacks reach it, so an update recomputes
+//! just those. Tracking is opt-in per board (`enable_attack_maps`): it adds a
+//! few attack lookups to every `place_piece`/`remove_piece` and pays off when
+//! the maps are queried more than once per move, so searches turn it on for
+//! their boards and evaluation, SEE and check detection read the maps there.
+
+use crate::bitboards::{BitIterator, BitboardBoard};
+use crate::types::core::{Piece, PieceType, Player, Position};
+use crate::types::{clear_bit, is_bit_set, set_bit, Bitboard, EMPTY_BITBOARD};
+
+/// Attacks of every piece on the board and the resulting per-side totals
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttackMaps {
+    /// Squares attacked by the piece on each square; empty for empty squares
+    from: [Bitboard; 81],
+    owner: [Option<Player>; 81],
+    /// Number of pieces of each side attacking each square
+    counts: [[u8; 81]; 2],
+    attacked: [Bitboard; 2],
+    /// Squares holding a lance, rook, bishop, dragon or horse
+    sliders: Bitboard,
+}
+
+#[inline]
+fn player_index(player: Player) -> usize {
+    if player == Player::Black {
+        0
+    } else {
+        1
+    }
+}
+
+fn is_slider(piece_type: PieceType) -> bool {
+    matches!(
+        piece_type,
+        PieceType::Lance
+            | PieceType::Rook
+            | PieceType::Bishop
+            | PieceType::PromotedRook
+            | PieceType::PromotedBishop
+    )
+}
+
+impl AttackMaps {
+    fn empty() -> Self {
+        Self {
+            from: [EMPTY_BITBOARD; 81],
+            owner: [None; 81],
+            counts: [[0; 81]; 2],
+            attacked: [EMPTY_BITBOARD; 2],
+            sliders: EMPTY_BITBOARD,
+        }
+    }
+
+    /// Maps of `board` computed from scratch
+    pub fn compute(board: &BitboardBoard) -> Self {
+        let mut maps = Self::empty();
+        for (pos, piece) in board.iter_pieces() {
+            maps.set(pos, Some(piece), board.attacks_from(pos, piece.piece_type, piece.player));
+        }
+        maps
+    }
+
+    /// Bring the maps up to date after the contents of `changed` changed on `board`
+    pub(crate) fn update(&mut self, board: &BitboardBoard, changed: Position) {
+        let mut reaching = self.sliders;
+        clear_bit(&mut reaching, changed);
+        self.refresh(board, changed);
+        for idx in BitIterator::new(reaching) {
+            if is_bit_set(self.from[idx as usize], changed) {
+                self.refresh(board, Position::from_index(idx));
+            }
+        }
+    }
+
+    fn refresh(&mut self, board: &BitboardBoard, pos: Position) {
+        let piece = board.get_piece(pos);
+        let attacks = piece
+            .map(|p| board.attacks_from(pos, p.piece_type, p.player))
+            .unwrap_or(EMPTY_BITBOARD);
+        self.set(pos, piece, attacks);
+    }
+
+    fn set(&mut self, pos: Position, piece: Option<Piece>, attacks: Bitboard) {
+        let idx = pos.to_index() as usize;
+        if let Some(old_owner) = self.owner[idx] {
+            let side = player_index(old_owner);
+            for target in BitIterator::new(self.from[idx]) {
+                let count = &mut self.counts[side][target as usize];
+                *count -= 1;
+                if *count == 0 {
+                    clear_bit(&mut self.attacked[side], Position::from_index(target));
+                }
+            }
+        }
+
+        self.from[idx] = attacks;
+        self.owner[idx] = piece.map(|p| p.player);
+        match piece {
+            Some(p) if is_slider(p.piece_type) => set_bit(&mut self.sliders, pos),
+            _ => clear_bit(&mut self.sliders, pos),
+        }
+
+        if let Some(p) = piece {
+            let side = player_index(p.player);
+            for target in BitIterator::new(attacks) {
+                self.counts[side][target as usize] += 1;
+            }
+            self.attacked[side] |= attacks;
+        }
+    }
+
+    /// Squares attacked by the piece on `pos`; empty if there is none
+    pub fn attacks_from(&self, pos: Position) -> Bitboard {
+        self.from[pos.to_index() as usize]
+    }
+
+    /// Every square `player` attacks
+    pub fn attacked_squares(&self, player: Player) -> Bitboard {
+        self.attacked[player_index(player)]
+    }
+
+    /// Number of `player`'s pieces attacking `pos`
+    pub fn attack_count(&self, player: Player, pos: Position) -> u8 {
+        self.counts[player_index(player)][pos.to_index() as usize]
+    }
+
+    pub fn is_attacked(&self, pos: Position, player: Player) -> bool {
+        is_bit_set(self.attacked[player_index(player)], pos)
+    }
+
+    /// Squares of `player`'s pieces attacking `pos`
+    pub fn attackers(&self, player: Player, pos: Position) -> Bitboard {
+        let mut attackers = EMPTY_BITBOARD;
+        for idx in 0..81u8 {
+            if self.owner[idx as usize] == Some(player) && is_bit_set(self.from[idx as usize], pos)
+            {
+                set_bit(&mut attackers, Position::from_index(idx));
+            }
+        }
+        attackers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moves::MoveGenerator;
+    use crate::types::CapturedPieces;
+
+    #[test]
+    fn test_tracked_maps_follow_make_and_unmake() {
+        let (mut board, player, captured) = BitboardBoard::from_fen(
+            "ln1g1g1nl/1r1sk2b1/p1ppppspp/1p4p2/9/2P4P1/PP1PPPP1P/1BS4R1/LN1GKGSNL b - 1",
+        )
+        .unwrap();
+        board.enable_attack_maps();
+        let moves = MoveGenerator::new().generate_legal_moves(&board, player, &captured);
+        assert!(!moves.is_empty());
+        for mv in &moves {
+            let info = board.make_move_with_info(mv);
+            assert_eq!(board.tracked_attack_maps(), Some(&AttackMaps::compute(&board)));
+            board.unmake_move(&info);
+            assert_eq!(board.tracked_attack_maps(), Some(&AttackMaps::compute(&board)));
+        }
+    }
+
+    #[test]
+    fn test_slider_attacks_stop_at_blocker() {
+        let (mut board, _, _) = BitboardBoard::from_fen("4k4/9/9/9/9/9/9/9/R3K4 b - 1").unwrap();
+        board.enable_attack_maps();
+        let top = Position::new(0, 0);
+        assert!(board.attack_maps().is_attacked(top, Player::Black));
+
+        board.place_piece(Piece::new(PieceType::Pawn, Player::White), Position::new(4, 0));
+        let maps = board.attack_maps();
+        assert!(!maps.is_attacked(top, Player::Black));
+        assert_eq!(maps.attack_count(Player::Black, Position::new(4, 0)), 1);
+        assert_eq!(maps.attackers(Player::Black, Position::new(4, 0)).count_ones(), 1);
+        assert!(board.is_square_attacked_by(Position::new(5, 0), Player::Black));
+    }
+}
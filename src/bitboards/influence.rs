@@ -0,0 +1,86 @@
+//! Influence Maps
+//!
+//! Per-square attack counts for both sides, used by the GUI to draw influence
+//! heatmaps. A square occupied by a player's own piece counts that player's
+//! attackers as defenders of the piece.
+
+use crate::bitboards::BitboardBoard;
+use crate::types::core::{Player, Position};
+use serde::{Deserialize, Serialize};
+
+/// Number of pieces of each side attacking every square, indexed `[row][col]`
+/// in board coordinates (row 0 is White's back rank)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InfluenceMap {
+    pub black: [[u8; 9]; 9],
+    pub white: [[u8; 9]; 9],
+}
+
+impl InfluenceMap {
+    /// Count the attackers of every square for both sides, from the board's
+    /// tracked attack maps when it keeps them
+    pub fn compute(board: &BitboardBoard) -> Self {
+        let maps = board.attack_maps();
+        let mut map = Self::default();
+        for row in 0..9 {
+            for col in 0..9 {
+                let pos = Position::new(row, col);
+                map.black[row as usize][col as usize] = maps.attack_count(Player::Black, pos);
+                map.white[row as usize][col as usize] = maps.attack_count(Player::White, pos);
+            }
+        }
+        map
+    }
+
+    /// Attack counts for one side
+    pub fn for_player(&self, player: Player) -> &[[u8; 9]; 9] {
+        match player {
+            Player::Black => &self.black,
+            Player::White => &self.white,
+        }
+    }
+
+    /// Attackers minus defenders of each square from `player`'s point of view
+    pub fn control(&self, player: Player) -> [[i8; 9]; 9] {
+        let own = self.for_player(player);
+        let other = self.for_player(player.opposite());
+        let mut control = [[0i8; 9]; 9];
+        for row in 0..9 {
+            for col in 0..9 {
+                control[row][col] = own[row][col] as i8 - other[row][col] as i8;
+            }
+        }
+        control
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_starting_position_is_symmetric() {
+        let map = InfluenceMap::compute(&BitboardBoard::new());
+        for row in 0..9 {
+            for col in 0..9 {
+                assert_eq!(map.black[row][col], map.white[8 - row][8 - col]);
+            }
+        }
+        // Black's pawns on row 6 each attack the square in front of them
+        assert!(map.black[5].iter().all(|&count| count >= 1));
+    }
+
+    #[test]
+    fn test_lance_ray_stops_at_blocker() {
+        let (board, _, _) = BitboardBoard::from_fen("4k4/9/9/4p4/9/9/9/9/L3K4 b - 1").unwrap();
+        let map = InfluenceMap::compute(&board);
+        // The lance on 9i sees up the 9th file; the white pawn on 5d is elsewhere
+        assert_eq!(map.black[0][0], 1);
+
+        let (board, _, _) = BitboardBoard::from_fen("4k4/9/9/p8/9/9/9/9/L3K4 b - 1").unwrap();
+        let map = InfluenceMap::compute(&board);
+        assert_eq!(map.black[3][0], 1);
+        assert_eq!(map.black[2][0], 0);
+        assert_eq!(map.control(Player::Black)[3][0], 1);
+    }
+}
@@ -34,6 +34,7 @@
 //! `CompressedMagicTable::from_table_with_config()`. When disabled, the table
 //! behaves identically to an uncompressed table but with compression metadata.
 
+use super::magic_table::magic_index;
 use crate::types::core::PieceType;
 use crate::types::{Bitboard, EMPTY_BITBOARD, MagicError, MagicTable};
 use std::collections::HashMap;
@@ -427,8 +428,8 @@ impl CompressedMagicTable {
         };
 
         let relevant_occupied = occupied & magic_entry.mask;
-        let hash = (relevant_occupied.wrapping_mul(magic_entry.magic_number as u128)) >> magic_entry.shift;
-        let attack_index = magic_entry.attack_base + hash as usize;
+        let attack_index = magic_entry.attack_base
+            + magic_index(relevant_occupied, magic_entry.magic_number, magic_entry.shift);
 
         if attack_index >= self.lookup_table.len() {
             return EMPTY_BITBOARD;
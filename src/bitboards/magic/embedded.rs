@@ -0,0 +1,178 @@
+//! Magic numbers embedded at build time
+//!
+//! Generated by `cargo run --release --bin generate_magic_tables -- --embedded`;
+//! do not edit by hand. `MagicTable::from_embedded` builds the attack tables
+//! from these numbers.
+
+#![allow(clippy::unreadable_literal)]
+
+/// Attack table entries over all rook and bishop squares
+pub const ATTACK_TABLE_ENTRIES: usize = 515840;
+
+pub const ROOK_MAGICS: [u128; 81] = [
+    0x0040000a098302098000200220009000,
+    0x42200400020008208002040100034000,
+    0x30800282020004008004000008003000,
+    0x02800084018080020004000080918000,
+    0x010002014010004040442116040000b2,
+    0x00400041800006000400308201000800,
+    0x01000011000808004c04000000004800,
+    0x4040044000040001810010040a81200d,
+    0x0100004220040a880102200400140400,
+    0x2000200018018900042010014a200020,
+    0x08201000010010801040004000100030,
+    0x021b4004000044006040000000010100,
+    0x104080010202080220c4001001000401,
+    0x00208000421000101010024a00100008,
+    0x30008000430040100200000000408096,
+    0x01024000400021040100080104204003,
+    0x00010001c08012100206844411000401,
+    0x800040000e049504010000c100201000,
+    0x0048204001080800802000405c001200,
+    0x22024040010702008a02024000000430,
+    0x18000230010002004004040000011000,
+    0x0014001000440a202001008800002002,
+    0x0234203000a840001800004480000005,
+    0x00002010001010000800041082040500,
+    0x4000a10008a080800208000100080000,
+    0x81c12280008034040842408001410000,
+    0x80000840004401080122800500055000,
+    0x28201450480001a480002000001c000a,
+    0x01000400040100008000000040400000,
+    0x00001020100100002108410480152001,
+    0x40001000a00088004028001002001008,
+    0x00080100080012007000411000404101,
+    0x40804000d00020000248001001080908,
+    0x00024010300050000e04002000260080,
+    0x80a904c0400004108488020004e8b046,
+    0xc0820514200001080100420800080404,
+    0x30000808000c00002080106003004010,
+    0x28302088001400800050002100000008,
+    0x00100006000200200040804008000010,
+    0x1400400200180080083800002101008c,
+    0x008402000804000858000080034000c0,
+    0x03808000803000081010000600240000,
+    0x20202000200400180040900100294003,
+    0x02000200802000040280900000062041,
+    0x08010010821000080102a20002802080,
+    0x80202000440002002020510420541200,
+    0x10824800840019002002001014000000,
+    0x21100400460002001080421480020044,
+    0x28000902000402001001002002084008,
+    0x0402800101010800040c0500100062a0,
+    0x0001802204800800082a0c0300800500,
+    0x00218000312408001004002000040008,
+    0x20002006100002000a00000880000004,
+    0x20d0418004080800012040400050000a,
+    0x01304810031200010000044000001200,
+    0x8004110a000100108001022000502800,
+    0x80010400920002010008000000000221,
+    0x04008801110004020015082820a10000,
+    0x00040001004002010022088000000801,
+    0x04808005004081040022002802400188,
+    0x00008009000a60040006001000090002,
+    0x0012010290810a880001000810200200,
+    0x002041b0024881040002002000109900,
+    0x504001c8000401404080082320909000,
+    0x84008202900403004200001140321000,
+    0x02212040010151014200000211200b00,
+    0x48c40110410000200080008010220001,
+    0x0050030000240820008000a102000800,
+    0x65008000404080214600040010008221,
+    0x02048000418420162a00050042000020,
+    0x02014040908020411c00041108040805,
+    0x0010015010821000c200044010020442,
+    0x08002670000805004090800020940821,
+    0x00108490020600020808800000080023,
+    0x83001522000500010008400001000200,
+    0x70402008530000a00004400006200000,
+    0x22120100001012001000400000100008,
+    0x82104000800008881090800008100000,
+    0x00002000200010000111400004001008,
+    0x00000011000220642102000050110282,
+    0xa8420010016001004301000000000090,
+];
+
+pub const BISHOP_MAGICS: [u128; 81] = [
+    0x0010104a8002420102000a0000040360,
+    0x00940125008808004800200001801800,
+    0x0801010680180b800000800000380004,
+    0x0082010202040080840800100a200084,
+    0x82010080682004a0040001509c001111,
+    0x0020204102004c0022c000000ac00311,
+    0x00444022009182085002040011000200,
+    0x24803080090046200821040900900900,
+    0x80215040800204210806004340020004,
+    0x0241044402100808002020901000d008,
+    0x04049481009404062200484204090000,
+    0x000026004002010400000080c8000080,
+    0x002002014042890444c810c000000110,
+    0x23304480410880290000048020000008,
+    0x0014801020810208020100000209ca00,
+    0x000802c01080218800000040004c2008,
+    0x08000410408020a40a04000000008252,
+    0x39200042044020089004004000200044,
+    0xc0010028050920020100800040000800,
+    0x08100010402808284d00604082041250,
+    0x4460802410c008010200010410640000,
+    0x30808144021018004000500228840001,
+    0xc0008042415008000020000ac2000280,
+    0x90025000400803201000000820000002,
+    0x81001005200268034000001114000200,
+    0x00206002046040829000000028400080,
+    0x00016010020104201002a28002000108,
+    0x000c1200004080c001a0000001080020,
+    0x400203c90100800420800a0000100000,
+    0x00188040090112080088018812002000,
+    0x40040200000c01808100020000000101,
+    0x00004008102001003280008010020000,
+    0x14105008002006400094000800181008,
+    0x01422024021008200080152001440130,
+    0xa0090024083408200a02200082002000,
+    0x70010248420481040821418003202020,
+    0x00004404900840616010000108500030,
+    0x80020081080490402080400804860400,
+    0x00080550200040201200004200000000,
+    0x000310008002010821000082a2000130,
+    0x00600050020008004000803828002212,
+    0x20210008010110002002000120000010,
+    0x59008100010468200c05006010082100,
+    0x0050c480891104029000001000430842,
+    0x08004120503188004182000020000060,
+    0x06102304440000202000001100101080,
+    0x0000204081040004200000000c904004,
+    0x00000204484000a01000608020004031,
+    0x10048000804000010202006000000848,
+    0x0200010a014000010080016800500180,
+    0x011000818300802800c0038428005010,
+    0x09c80400543020008108000385800018,
+    0x44240440808880008048280004100000,
+    0x01110080400ce4008040002000010219,
+    0x08010042001084004a04014200088000,
+    0x00022068410481054408012880020480,
+    0x04c0800210400c040810001002000000,
+    0x4004161005401600080000002a010000,
+    0x00010401010080400200000002202370,
+    0x30080041015010800200001021110240,
+    0x50048800144002008040205803088002,
+    0x00040202200630030041200010140210,
+    0x30840080882001288012042c22848442,
+    0x02488028a10210425040418028308004,
+    0x04302420400810809400400000002200,
+    0x0000c808046040c20088100060440280,
+    0x21090080201430080802250408c80000,
+    0x04138000010100880041c50000000012,
+    0x41010004141009080000020142010000,
+    0x000008040c4008012200000940004000,
+    0x2018850a000410048004000440080900,
+    0x01808600100810410004022610400000,
+    0x80138010080a2420c02000000a000009,
+    0x004040403002a0080502000002100000,
+    0x03048280410244106000180100000000,
+    0x24002a0000d00410880400102001a200,
+    0x0800020120008280e400190400202010,
+    0x812000b0008014820406240002100154,
+    0x508421020a0404020500000600006282,
+    0x60010205400920100900010102600000,
+    0x00100122002001008240240802001002,
+];
@@ -69,7 +69,13 @@ impl LazyMagicTable {
         if needs_init {
             // Initialize the square
             if let Err(e) = self.initialize_square(square, piece_type) {
-                eprintln!("Failed to initialize square {} for {:?}: {:?}", square, piece_type, e);
+                crate::utils::logging::error(
+                    "magic",
+                    &format!(
+                        "Failed to initialize square {} for {:?}: {:?}",
+                        square, piece_type, e
+                    ),
+                );
                 return EMPTY_BITBOARD;
             }
         }
@@ -3,6 +3,8 @@
 //! This module provides functionality to generate and validate magic numbers
 //! used in magic bitboard implementations for efficient sliding piece move generation.
 
+use super::attack_generator::AttackGenerator;
+use super::magic_table::magic_index;
 use crate::types::core::PieceType;
 use crate::types::{Bitboard, EMPTY_BITBOARD, MagicError, MagicGenerationResult};
 use rand::rngs::ThreadRng;
@@ -92,13 +94,13 @@ impl MagicFinder {
         let max_attempts = 1_000_000;
 
         for _ in 0..max_attempts {
-            let candidate = self.rng.gen::<u64>();
+            let candidate = self.rng.gen::<u128>();
             if self.validate_magic_fast(candidate, square, piece_type, &mask, shift) {
                 return Ok(MagicGenerationResult {
                     magic_number: candidate,
                     mask,
                     shift,
-                    table_size: 1 << (64 - shift),
+                    table_size: 1 << (128 - shift),
                     generation_time: std::time::Duration::from_secs(0),
                 });
             }
@@ -126,13 +128,13 @@ impl MagicFinder {
         let start_time = std::time::Instant::now();
 
         // Try magic numbers starting from 1
-        for magic in 1..=u64::MAX {
+        for magic in 1..=u128::MAX {
             if self.validate_magic_fast(magic, square, piece_type, &mask, shift) {
                 return Ok(MagicGenerationResult {
                     magic_number: magic,
                     mask,
                     shift,
-                    table_size: 1 << (64 - shift),
+                    table_size: 1 << (128 - shift),
                     generation_time: start_time.elapsed(),
                 });
             }
@@ -160,13 +162,13 @@ impl MagicFinder {
         // Heuristic: try magic numbers with specific patterns
         let heuristic_candidates = self.generate_heuristic_candidates(mask);
 
-        for candidate in heuristic_candidates {
+        for candidate in heuristic_candidates.into_iter().map(u128::from) {
             if self.validate_magic_fast(candidate, square, piece_type, &mask, shift) {
                 return Ok(MagicGenerationResult {
                     magic_number: candidate,
                     mask,
                     shift,
-                    table_size: 1 << (64 - shift),
+                    table_size: 1 << (128 - shift),
                     generation_time: start_time.elapsed(),
                 });
             }
@@ -175,13 +177,13 @@ impl MagicFinder {
 
         // If heuristics fail, try some random numbers with better distribution
         for _ in 0..100_000 {
-            let candidate = self.rng.gen::<u64>();
+            let candidate = self.rng.gen::<u128>();
             if self.validate_magic_fast(candidate, square, piece_type, &mask, shift) {
                 return Ok(MagicGenerationResult {
                     magic_number: candidate,
                     mask,
                     shift,
-                    table_size: 1 << (64 - shift),
+                    table_size: 1 << (128 - shift),
                     generation_time: start_time.elapsed(),
                 });
             }
@@ -293,47 +295,28 @@ impl MagicFinder {
     }
 
     /// Generate relevant mask for a square and piece type
-    fn generate_relevant_mask(&self, square: u8, piece_type: PieceType) -> Bitboard {
-        let (row, col) = (square / 9, square % 9);
-        let mut mask = EMPTY_BITBOARD;
-
-        match piece_type {
-            PieceType::Rook | PieceType::PromotedRook => {
-                // Rook moves horizontally and vertically
-                // Add all squares in the same row and column, excluding the square itself
-                for i in 0..9 {
-                    if i != col {
-                        mask |= 1u128 << (row * 9 + i);
-                    }
-                    if i != row {
-                        mask |= 1u128 << (i * 9 + col);
-                    }
-                }
-            }
+    ///
+    /// The mask holds every square along the piece's rays except the last one on
+    /// each ray: a blocker on the board edge never changes the attack set.
+    pub fn generate_relevant_mask(&self, square: u8, piece_type: PieceType) -> Bitboard {
+        let directions: [(i8, i8); 4] = match piece_type {
+            PieceType::Rook | PieceType::PromotedRook => [(1, 0), (-1, 0), (0, 1), (0, -1)],
             PieceType::Bishop | PieceType::PromotedBishop => {
-                // Bishop moves diagonally
-                // Add all squares on the diagonals, excluding the square itself
-                for i in 1..9 {
-                    // Diagonal 1: (row+i, col+i) and (row-i, col-i)
-                    if row + i < 9 && col + i < 9 {
-                        mask |= 1u128 << ((row + i) * 9 + (col + i));
-                    }
-                    if row >= i && col >= i {
-                        mask |= 1u128 << ((row - i) * 9 + (col - i));
-                    }
-
-                    // Diagonal 2: (row+i, col-i) and (row-i, col+i)
-                    if row + i < 9 && col >= i {
-                        mask |= 1u128 << ((row + i) * 9 + (col - i));
-                    }
-                    if row >= i && col + i < 9 {
-                        mask |= 1u128 << ((row - i) * 9 + (col + i));
-                    }
-                }
+                [(1, 1), (1, -1), (-1, 1), (-1, -1)]
             }
-            _ => {
-                // Invalid piece type for magic bitboards
-                return EMPTY_BITBOARD;
+            // Invalid piece type for magic bitboards
+            _ => return EMPTY_BITBOARD,
+        };
+        let on_board = |row: i8, col: i8| (0..9).contains(&row) && (0..9).contains(&col);
+        let (row, col) = ((square / 9) as i8, (square % 9) as i8);
+        let mut mask = EMPTY_BITBOARD;
+
+        for (dr, dc) in directions {
+            let (mut r, mut c) = (row + dr, col + dc);
+            while on_board(r, c) && on_board(r + dr, c + dc) {
+                mask |= 1u128 << (r * 9 + c);
+                r += dr;
+                c += dc;
             }
         }
 
@@ -345,15 +328,15 @@ impl MagicFinder {
         // Count the number of set bits in the mask
         let bit_count = mask.count_ones() as u8;
 
-        // The shift is 64 - number of relevant bits
+        // The shift is 128 - number of relevant bits
         // This ensures we use the minimum table size
-        64 - bit_count
+        128 - bit_count
     }
 
     /// Fast magic number validation
     fn validate_magic_fast(
         &self,
-        magic: u64,
+        magic: u128,
         _square: u8,
         _piece_type: PieceType,
         mask: &Bitboard,
@@ -365,8 +348,7 @@ impl MagicFinder {
 
         for blockers in &blocker_configs {
             // Calculate the hash index
-            let hash = (blockers.wrapping_mul(magic as u128)) >> shift;
-            let index = hash as usize;
+            let index = magic_index(*blockers, magic, shift);
 
             // Check for collision
             if used_indices.contains(&index) {
@@ -438,9 +420,12 @@ impl MagicFinder {
         for piece_type in piece_types {
             for square in 0..81 {
                 if let Err(e) = self.find_magic_number(square, piece_type) {
-                    eprintln!(
-                        "Failed to generate magic number for square {} piece {:?}: {}",
-                        square, piece_type, e
+                    crate::utils::logging::error(
+                        "magic",
+                        &format!(
+                            "Failed to generate magic number for square {} piece {:?}: {}",
+                            square, piece_type, e
+                        ),
                     );
                     return Err(e);
                 }
@@ -476,6 +461,71 @@ impl Default for MagicFinder {
     }
 }
 
+/// Seed of the deterministic search that produced the embedded magic numbers
+pub const EMBEDDED_MAGIC_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Deterministic magic search used to produce `embedded.rs`
+///
+/// Tries sparse 128-bit candidates (each half the AND of three xorshift64
+/// outputs, high half first) until every blocker configuration of the mask
+/// lands on a slot holding its own attack set.
+/// Unlike `find_magic_number`, configurations with the same attacks may share a
+/// slot, which is what lets the tables stay at `1 << mask bits` entries.
+/// Each square and piece type gets its own stream derived from
+/// `EMBEDDED_MAGIC_SEED`, so any single magic can be reproduced on its own.
+pub fn find_embeddable_magic(square: u8, piece_type: PieceType) -> u128 {
+    let mask = MagicFinder::new().generate_relevant_mask(square, piece_type);
+    let bits = mask.count_ones();
+    let shift = (128 - bits) as u8;
+    let mut generator = AttackGenerator::new();
+    let configs: Vec<(Bitboard, Bitboard)> = generator
+        .generate_all_blocker_combinations(mask)
+        .into_iter()
+        .map(|blockers| (blockers, generator.generate_attack_pattern(square, piece_type, blockers)))
+        .collect();
+
+    let stream = match piece_type {
+        PieceType::Bishop => 81 + u64::from(square),
+        _ => u64::from(square),
+    };
+    let mut rng_state =
+        EMBEDDED_MAGIC_SEED.wrapping_add((stream + 1).wrapping_mul(0x2545_F491_4F6C_DD1D));
+
+    // Slots are stamped with the attempt that filled them, so they never need clearing
+    let mut slots: Vec<(u64, Bitboard)> = vec![(0, EMPTY_BITBOARD); 1 << bits];
+    let mut attempt = 0u64;
+    loop {
+        let high = sparse_random(&mut rng_state);
+        let magic = (u128::from(high) << 64) | u128::from(sparse_random(&mut rng_state));
+        attempt += 1;
+        let fits = configs.iter().all(|&(blockers, attack)| {
+            let slot = &mut slots[magic_index(blockers, magic, shift)];
+            if slot.0 != attempt {
+                *slot = (attempt, attack);
+                true
+            } else {
+                slot.1 == attack
+            }
+        });
+        if fits {
+            return magic;
+        }
+    }
+}
+
+fn sparse_random(state: &mut u64) -> u64 {
+    xorshift64(state) & xorshift64(state) & xorshift64(state)
+}
+
+fn xorshift64(state: &mut u64) -> u64 {
+    let mut x = *state;
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    *state = x;
+    x
+}
+
 #[cfg(all(test, feature = "legacy-tests"))]
 mod tests {
     use super::*;
@@ -537,17 +587,17 @@ mod tests {
         // Test with empty mask
         let empty_mask = EMPTY_BITBOARD;
         let shift = finder.calculate_shift(empty_mask);
-        assert_eq!(shift, 64);
+        assert_eq!(shift, 128);
 
         // Test with single bit mask
         let single_bit_mask = 1u128 << 40;
         let shift = finder.calculate_shift(single_bit_mask);
-        assert_eq!(shift, 63);
+        assert_eq!(shift, 127);
 
         // Test with multiple bits
         let multi_bit_mask = 0xFFu128;
         let shift = finder.calculate_shift(multi_bit_mask);
-        assert_eq!(shift, 64 - 8);
+        assert_eq!(shift, 128 - 8);
     }
 
     #[test]
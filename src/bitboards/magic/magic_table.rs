@@ -15,7 +15,22 @@ use std::path::Path;
 pub const MAGIC_TABLE_FILE_MAGIC: &[u8] = b"SHOGI_MAGIC_V1";
 
 /// Current version of the magic table file format
-pub const MAGIC_TABLE_FILE_VERSION: u8 = 1;
+///
+/// Version 2 stores 128-bit magic numbers, multiplied with the full occupancy
+/// (`magic_index`), and masks without ray ends; version 1 files are rejected.
+pub const MAGIC_TABLE_FILE_VERSION: u8 = 2;
+
+/// Attack table index of a masked occupancy for `magic` and `shift`
+///
+/// The multiply is done in 128 bits: 81-square masks do not fold into 64 bits
+/// without leaving some squares without a usable magic number.
+#[inline(always)]
+pub fn magic_index(relevant_occupied: Bitboard, magic: u128, shift: u8) -> usize {
+    relevant_occupied
+        .wrapping_mul(magic)
+        .checked_shr(u32::from(shift))
+        .unwrap_or(0) as usize
+}
 
 /// Get the default path for the magic table file
 /// 
@@ -122,10 +137,11 @@ impl MagicTable {
             }
         }
 
-        println!(
+        // Not stdout: the USI engine builds these tables and stdout is its protocol channel
+        crate::utils::telemetry::debug_log(&format!(
             "Magic table initialization completed in {:?}",
             start_time.elapsed()
-        );
+        ));
         Ok(())
     }
 
@@ -141,32 +157,7 @@ impl MagicTable {
     pub fn initialize_rook_square(&mut self, square: u8) -> Result<(), MagicError> {
         let mut finder = MagicFinder::new();
         let magic_result = finder.find_magic_number(square, PieceType::Rook)?;
-        let attack_base = self.memory_pool.allocate(magic_result.table_size)?;
-
-        // Generate all attack patterns for this square
-        let mut generator = AttackGenerator::new();
-        let mask = magic_result.mask;
-        for blockers in generator.generate_all_blocker_combinations(mask) {
-            let attack = generator.generate_attack_pattern(square, PieceType::Rook, blockers);
-            let hash =
-                (blockers.wrapping_mul(magic_result.magic_number as u128)) >> magic_result.shift;
-            let index = attack_base + hash as usize;
-
-            if index >= self.attack_storage.len() {
-                self.attack_storage.resize(index + 1, EMPTY_BITBOARD);
-            }
-
-            self.attack_storage[index] = attack;
-        }
-
-        self.rook_magics[square as usize] = MagicBitboard {
-            magic_number: magic_result.magic_number,
-            mask: magic_result.mask,
-            shift: magic_result.shift,
-            attack_base,
-            table_size: magic_result.table_size,
-        };
-
+        self.install_magic(square, PieceType::Rook, magic_result.magic_number, magic_result.mask);
         Ok(())
     }
 
@@ -174,33 +165,57 @@ impl MagicTable {
     pub fn initialize_bishop_square(&mut self, square: u8) -> Result<(), MagicError> {
         let mut finder = MagicFinder::new();
         let magic_result = finder.find_magic_number(square, PieceType::Bishop)?;
-        let attack_base = self.memory_pool.allocate(magic_result.table_size)?;
+        self.install_magic(square, PieceType::Bishop, magic_result.magic_number, magic_result.mask);
+        Ok(())
+    }
+
+    /// Build the table from the magic numbers embedded at build time
+    ///
+    /// No search is needed, so this only costs filling the attack tables.
+    pub fn from_embedded() -> Self {
+        let finder = MagicFinder::new();
+        let mut table = Self::default();
+        table.attack_storage.reserve(super::embedded::ATTACK_TABLE_ENTRIES);
+        for square in 0..81u8 {
+            let mask = finder.generate_relevant_mask(square, PieceType::Rook);
+            let magic = super::embedded::ROOK_MAGICS[square as usize];
+            table.install_magic(square, PieceType::Rook, magic, mask);
+        }
+        for square in 0..81u8 {
+            let mask = finder.generate_relevant_mask(square, PieceType::Bishop);
+            let magic = super::embedded::BISHOP_MAGICS[square as usize];
+            table.install_magic(square, PieceType::Bishop, magic, mask);
+        }
+        table
+    }
+
+    /// Fill the attack table of one square for a known magic number, appending it
+    /// to the attack storage
+    fn install_magic(&mut self, square: u8, piece_type: PieceType, magic: u128, mask: Bitboard) {
+        let bits = mask.count_ones() as u8;
+        let shift = 128 - bits;
+        let table_size = 1usize << bits;
+        let attack_base = self.attack_storage.len();
+        self.attack_storage.resize(attack_base + table_size, EMPTY_BITBOARD);
 
         // Generate all attack patterns for this square
         let mut generator = AttackGenerator::new();
-        let mask = magic_result.mask;
         for blockers in generator.generate_all_blocker_combinations(mask) {
-            let attack = generator.generate_attack_pattern(square, PieceType::Bishop, blockers);
-            let hash =
-                (blockers.wrapping_mul(magic_result.magic_number as u128)) >> magic_result.shift;
-            let index = attack_base + hash as usize;
-
-            if index >= self.attack_storage.len() {
-                self.attack_storage.resize(index + 1, EMPTY_BITBOARD);
-            }
-
-            self.attack_storage[index] = attack;
+            let attack = generator.generate_attack_pattern(square, piece_type, blockers);
+            self.attack_storage[attack_base + magic_index(blockers, magic, shift)] = attack;
         }
 
-        self.bishop_magics[square as usize] = MagicBitboard {
-            magic_number: magic_result.magic_number,
-            mask: magic_result.mask,
-            shift: magic_result.shift,
+        let entry = MagicBitboard {
+            magic_number: magic,
+            mask,
+            shift,
             attack_base,
-            table_size: magic_result.table_size,
+            table_size,
         };
-
-        Ok(())
+        match piece_type {
+            PieceType::Rook | PieceType::PromotedRook => self.rook_magics[square as usize] = entry,
+            _ => self.bishop_magics[square as usize] = entry,
+        }
     }
 
     /// Get attack pattern for a square using magic bitboards
@@ -234,12 +249,9 @@ impl MagicTable {
         // Apply mask to get relevant occupied squares
         let relevant_occupied = occupied & magic_entry.mask;
 
-        // Calculate hash index
-        let hash =
-            (relevant_occupied.wrapping_mul(magic_entry.magic_number as u128)) >> magic_entry.shift;
-
         // Lookup attack pattern with bounds checking
-        let attack_index = magic_entry.attack_base + hash as usize;
+        let attack_index = magic_entry.attack_base
+            + magic_index(relevant_occupied, magic_entry.magic_number, magic_entry.shift);
         if attack_index < self.attack_storage.len() {
             self.attack_storage[attack_index]
         } else {
@@ -543,7 +555,7 @@ impl MagicTable {
 
         // Read rook magics
         for i in 0..81 {
-            let mut magic_number = [0u8; 8];
+            let mut magic_number = [0u8; 16];
             cursor
                 .read_exact(&mut magic_number)
                 .map_err(|e| MagicError::IoError(e.to_string()))?;
@@ -565,7 +577,7 @@ impl MagicTable {
                 .map_err(|e| MagicError::IoError(e.to_string()))?;
 
             table.rook_magics[i] = MagicBitboard {
-                magic_number: u64::from_le_bytes(magic_number),
+                magic_number: u128::from_le_bytes(magic_number),
                 mask: u128::from_le_bytes(mask),
                 shift: shift[0],
                 attack_base: u64::from_le_bytes(attack_base) as usize,
@@ -575,7 +587,7 @@ impl MagicTable {
 
         // Read bishop magics
         for i in 0..81 {
-            let mut magic_number = [0u8; 8];
+            let mut magic_number = [0u8; 16];
             cursor
                 .read_exact(&mut magic_number)
                 .map_err(|e| MagicError::IoError(e.to_string()))?;
@@ -597,7 +609,7 @@ impl MagicTable {
                 .map_err(|e| MagicError::IoError(e.to_string()))?;
 
             table.bishop_magics[i] = MagicBitboard {
-                magic_number: u64::from_le_bytes(magic_number),
+                magic_number: u128::from_le_bytes(magic_number),
                 mask: u128::from_le_bytes(mask),
                 shift: shift[0],
                 attack_base: u64::from_le_bytes(attack_base) as usize,
@@ -695,15 +707,21 @@ impl MagicTable {
             Err(e) => {
                 // If file doesn't exist or is invalid, generate new table
                 if !path.exists() {
-                    eprintln!(
-                        "Magic table file not found at {}, generating new table...",
-                        path.display()
+                    crate::utils::logging::info(
+                        "magic",
+                        &format!(
+                            "Magic table file not found at {}, generating new table",
+                            path.display()
+                        ),
                     );
                 } else {
-                    eprintln!(
-                        "Failed to load magic table from {}: {}, generating new table...",
-                        path.display(),
-                        e
+                    crate::utils::logging::warn(
+                        "magic",
+                        &format!(
+                            "Failed to load magic table from {}: {}, generating new table",
+                            path.display(),
+                            e
+                        ),
                     );
                 }
             }
@@ -718,15 +736,18 @@ impl MagicTable {
         // Save if requested
         if save_if_generated {
             if let Err(e) = table.save_to_file(path) {
-                eprintln!(
-                    "Warning: Failed to save generated magic table to {}: {}",
-                    path.display(),
-                    e
+                crate::utils::logging::warn(
+                    "magic",
+                    &format!(
+                        "Failed to save generated magic table to {}: {}",
+                        path.display(),
+                        e
+                    ),
                 );
             } else {
-                eprintln!(
-                    "Generated magic table saved to {}",
-                    path.display()
+                crate::utils::logging::info(
+                    "magic",
+                    &format!("Generated magic table saved to {}", path.display()),
                 );
             }
         }
@@ -1168,4 +1189,34 @@ mod tests {
 
         assert_eq!(table.attack_storage, deserialized.attack_storage);
     }
+
+    #[test]
+    fn test_embedded_table_matches_attack_generator() {
+        use rand::Rng;
+
+        let table = MagicTable::from_embedded();
+        assert!(table.is_fully_initialized());
+        assert_eq!(table.attack_storage.len(), super::super::embedded::ATTACK_TABLE_ENTRIES);
+        // Every blocker configuration of every mask
+        assert!(table.validate().is_ok());
+
+        // Full-board occupancies, edge squares and the slider's own square included
+        let mut generator = AttackGenerator::new();
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let occupied = rng.gen::<u128>() & ((1u128 << 81) - 1);
+            for square in 0..81u8 {
+                for piece_type in [PieceType::Rook, PieceType::Bishop] {
+                    assert_eq!(
+                        table.get_attacks(square, piece_type, occupied),
+                        generator.generate_attack_pattern(square, piece_type, occupied),
+                        "{:?} on square {} with occupancy {:#x}",
+                        piece_type,
+                        square,
+                        occupied
+                    );
+                }
+            }
+        }
+    }
 }
@@ -15,6 +15,7 @@
 //! # Components
 //!
 //! - `magic_finder`: Magic number generation and validation
+//! - `embedded`: Magic numbers found offline and compiled into the binary
 //! - `attack_generator`: Attack pattern generation using ray-casting
 //! - `magic_table`: Magic table construction and management
 //! - `lookup_engine`: Fast lookup implementation with caching
@@ -35,6 +36,7 @@
 //! ```
 
 pub mod attack_generator;
+pub mod embedded;
 pub mod magic_finder;
 pub mod magic_table;
 pub mod lookup_engine;
@@ -66,22 +68,35 @@ pub use crate::types::{
 
 /// Initialize the magic bitboard system
 ///
-/// This function should be called once during application startup
-/// to initialize the magic bitboard system.
+/// Builds the shared magic table that every `BitboardBoard` uses for sliding
+/// pieces from the embedded magic numbers. Call it once at startup to pay the
+/// cost up front; otherwise the first board built does it.
+/// Fails when no valid table could be built, in which case boards fall back to
+/// ray-casting. With the `raycast-sliding` feature there is nothing to build.
 pub fn initialize() -> Result<(), MagicError> {
-    // Placeholder for system initialization
-    // In a real implementation, this might precompute magic tables
-    // or perform other initialization tasks
-    Ok(())
+    if cfg!(feature = "raycast-sliding") {
+        return Ok(());
+    }
+    let table = super::get_shared_magic_table().ok_or_else(|| MagicError::InitializationFailed {
+        reason: "Failed to get shared magic table".to_string(),
+    })?;
+    if table.is_fully_initialized() {
+        Ok(())
+    } else {
+        Err(MagicError::InitializationFailed {
+            reason: "Magic table is incomplete; sliding attacks use ray-casting".to_string(),
+        })
+    }
 }
 
 /// Get system information about magic bitboards
 pub fn system_info() -> SystemInfo {
+    let table = super::SHARED_MAGIC_TABLE.get();
     SystemInfo {
         version: env!("CARGO_PKG_VERSION"),
         magic_table_size: 81 * 2, // 81 squares * 2 piece types
-        memory_usage: 0,          // Will be updated when tables are created
-        initialized: false,       // Will be updated when system is initialized
+        memory_usage: table.map_or(0, |table| table.memory_stats().memory_usage_bytes),
+        initialized: table.is_some_and(|table| table.is_fully_initialized()),
     }
 }
 
@@ -108,6 +123,9 @@ mod tests {
     fn test_system_info() {
         let info = system_info();
         assert_eq!(info.magic_table_size, 162); // 81 * 2
-        assert!(!info.initialized);
+
+        initialize().unwrap();
+        let info = system_info();
+        assert_eq!(info.initialized, !cfg!(feature = "raycast-sliding"));
     }
 }
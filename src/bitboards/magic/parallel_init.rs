@@ -7,6 +7,7 @@
 
 use crate::bitboards::magic::attack_generator::AttackGenerator;
 use crate::bitboards::magic::magic_finder::MagicFinder;
+use crate::bitboards::magic::magic_table::magic_index;
 use crate::types::core::PieceType;
 use crate::types::{Bitboard, EMPTY_BITBOARD, MagicError, MagicTable};
 use std::sync::{Arc, Mutex};
@@ -130,8 +131,8 @@ impl ParallelInitializer {
                     .iter()
                     .map(|&blockers| {
                         let attack = generator.generate_attack_pattern(square, PieceType::Rook, blockers);
-                        let hash = (blockers.wrapping_mul(magic_result.magic_number as u128)) >> magic_result.shift;
-                        (hash as usize, attack)
+                        let hash = magic_index(blockers, magic_result.magic_number, magic_result.shift);
+                        (hash, attack)
                     })
                     .collect();
 
@@ -142,7 +143,10 @@ impl ParallelInitializer {
         // Process rook results and update table sequentially (to avoid mutability issues)
         for result in rook_results? {
             let (square, magic_result, patterns) = result;
-            let attack_base = table.memory_pool.allocate(magic_result.table_size)?;
+            let attack_base = table.attack_storage.len();
+            table
+                .attack_storage
+                .resize(attack_base + magic_result.table_size, EMPTY_BITBOARD);
             
             // Store patterns
             for (hash, attack) in patterns {
@@ -185,8 +189,8 @@ impl ParallelInitializer {
                     .iter()
                     .map(|&blockers| {
                         let attack = generator.generate_attack_pattern(square, PieceType::Bishop, blockers);
-                        let hash = (blockers.wrapping_mul(magic_result.magic_number as u128)) >> magic_result.shift;
-                        (hash as usize, attack)
+                        let hash = magic_index(blockers, magic_result.magic_number, magic_result.shift);
+                        (hash, attack)
                     })
                     .collect();
 
@@ -197,7 +201,10 @@ impl ParallelInitializer {
         // Process bishop results and update table sequentially
         for result in bishop_results? {
             let (square, magic_result, patterns) = result;
-            let attack_base = table.memory_pool.allocate(magic_result.table_size)?;
+            let attack_base = table.attack_storage.len();
+            table
+                .attack_storage
+                .resize(attack_base + magic_result.table_size, EMPTY_BITBOARD);
             
             // Store patterns
             for (hash, attack) in patterns {
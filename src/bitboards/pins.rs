@@ -0,0 +1,181 @@
+//! Pins and Discovered Checks
+//!
+//! A piece standing alone between a king and an enemy slider that would
+//! otherwise attack it is a blocker. When the blocker belongs to the king's
+//! side it is pinned: it may only move along the line between king and
+//! pinner, capturing the pinner included. When it belongs to the slider's side,
+//! moving it off the line gives a discovered check. Both come from the same
+//! walk outward from each king, eight rays of at most eight squares.
+
+use crate::bitboards::BitboardBoard;
+use crate::types::core::{Move, PieceType, Player, Position};
+use crate::types::{is_bit_set, set_bit, Bitboard, EMPTY_BITBOARD};
+
+const DIRECTIONS: [(i8, i8); 8] =
+    [(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+/// Pins against `player`'s king and `player`'s discovered-check candidates
+/// against the opposing king
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PinInfo {
+    pub player: Player,
+    /// `player`'s pieces pinned to their king
+    pub pinned: Bitboard,
+    /// Opposing sliders doing the pinning
+    pub pinners: Bitboard,
+    /// `player`'s pieces whose move off the line uncovers a check from one of
+    /// `player`'s sliders
+    pub discovered_check_candidates: Bitboard,
+    /// For each pinned square, the squares it may still move to
+    pin_lines: [Bitboard; 81],
+}
+
+/// Whether a `piece_type` of `owner` attacks along the step `(dr, dc)` beyond
+/// its neighbouring square
+pub(crate) fn slides_along(piece_type: PieceType, owner: Player, (dr, dc): (i8, i8)) -> bool {
+    match piece_type {
+        PieceType::Rook | PieceType::PromotedRook => dr == 0 || dc == 0,
+        PieceType::Bishop | PieceType::PromotedBishop => dr != 0 && dc != 0,
+        PieceType::Lance => dc == 0 && dr == if owner == Player::Black { -1 } else { 1 },
+        _ => false,
+    }
+}
+
+/// Blockers between the king on `king` and sliders of `slider_owner`, each with
+/// the slider's square and the line from the king up to and including it
+fn blockers(
+    board: &BitboardBoard,
+    king: Position,
+    slider_owner: Player,
+) -> impl Iterator<Item = (Position, Position, Bitboard)> + '_ {
+    DIRECTIONS.into_iter().filter_map(move |(dr, dc)| {
+        let mut line = EMPTY_BITBOARD;
+        let mut blocker = None;
+        let (mut row, mut col) = (king.row as i8 + dr, king.col as i8 + dc);
+        while (0..9).contains(&row) && (0..9).contains(&col) {
+            let pos = Position::new(row as u8, col as u8);
+            set_bit(&mut line, pos);
+            if let Some(piece) = board.get_piece(pos) {
+                match blocker {
+                    None => blocker = Some(pos),
+                    Some(blocker) => {
+                        let pins = piece.player == slider_owner
+                            && slides_along(piece.piece_type, piece.player, (-dr, -dc));
+                        return pins.then_some((blocker, pos, line));
+                    }
+                }
+            }
+            row += dr;
+            col += dc;
+        }
+        None
+    })
+}
+
+impl PinInfo {
+    pub fn compute(board: &BitboardBoard, player: Player) -> Self {
+        let opponent = player.opposite();
+        let mut info = Self {
+            player,
+            pinned: EMPTY_BITBOARD,
+            pinners: EMPTY_BITBOARD,
+            discovered_check_candidates: EMPTY_BITBOARD,
+            pin_lines: [EMPTY_BITBOARD; 81],
+        };
+
+        if let Some(king) = board.find_king_position(player) {
+            for (blocker, pinner, line) in blockers(board, king, opponent) {
+                if board.get_piece(blocker).map(|p| p.player) == Some(player) {
+                    set_bit(&mut info.pinned, blocker);
+                    set_bit(&mut info.pinners, pinner);
+                    info.pin_lines[blocker.to_index() as usize] = line;
+                }
+            }
+        }
+        if let Some(king) = board.find_king_position(opponent) {
+            for (blocker, _, _) in blockers(board, king, player) {
+                if board.get_piece(blocker).map(|p| p.player) == Some(player) {
+                    set_bit(&mut info.discovered_check_candidates, blocker);
+                }
+            }
+        }
+        info
+    }
+
+    pub fn is_pinned(&self, pos: Position) -> bool {
+        is_bit_set(self.pinned, pos)
+    }
+
+    /// Squares the piece on `pos` may move to without exposing its king; every
+    /// square when it is not pinned
+    pub fn allowed_squares(&self, pos: Position) -> Bitboard {
+        if self.is_pinned(pos) {
+            self.pin_lines[pos.to_index() as usize]
+        } else {
+            !EMPTY_BITBOARD
+        }
+    }
+
+    /// Whether `mv` keeps `player`'s pins intact. Drops always do; king
+    /// moves and evasions from check still need their own test.
+    pub fn respects_pins(&self, mv: &Move) -> bool {
+        match mv.from {
+            Some(from) => is_bit_set(self.allowed_squares(from), mv.to),
+            None => true,
+        }
+    }
+
+    /// Whether `mv` moves a discovered-check candidate off its line
+    pub fn uncovers_check(&self, board: &BitboardBoard, mv: &Move) -> bool {
+        let Some(from) = mv.from else {
+            return false;
+        };
+        if !is_bit_set(self.discovered_check_candidates, from) {
+            return false;
+        }
+        let Some(king) = board.find_king_position(self.player.opposite()) else {
+            return false;
+        };
+        blockers(board, king, self.player)
+            .find(|&(blocker, _, _)| blocker == from)
+            .map_or(false, |(_, _, line)| !is_bit_set(line, mv.to))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pins_and_discovered_candidates() {
+        // White's rook on 5a pins the silver on 5g; Black's lance on 1i sits
+        // behind the gold on 1c, facing White's king on 1a
+        let (board, _, _) = BitboardBoard::from_fen("4r3k/9/8G/9/9/9/4S4/9/4K3L b - 1").unwrap();
+        let info = PinInfo::compute(&board, Player::Black);
+        let silver = Position::new(6, 4);
+        let gold = Position::new(2, 8);
+        assert_eq!(info.pinned.count_ones(), 1);
+        assert!(info.is_pinned(silver));
+        assert!(is_bit_set(info.pinners, Position::new(0, 4)));
+        assert!(is_bit_set(info.allowed_squares(silver), Position::new(5, 4)));
+        assert!(!is_bit_set(info.allowed_squares(silver), Position::new(5, 3)));
+        assert!(is_bit_set(info.discovered_check_candidates, gold));
+
+        let sideways =
+            Move::new_move(gold, Position::new(2, 7), PieceType::Gold, Player::Black, false);
+        assert!(info.uncovers_check(&board, &sideways));
+        let forward =
+            Move::new_move(gold, Position::new(1, 8), PieceType::Gold, Player::Black, false);
+        assert!(!info.uncovers_check(&board, &forward));
+    }
+
+    #[test]
+    fn test_lance_only_pins_forward() {
+        // A white lance only attacks towards rank i, so one below the king pins nothing
+        let (board, _, _) = BitboardBoard::from_fen("k8/9/9/9/4K4/4G4/9/9/4l4 b - 1").unwrap();
+        assert_eq!(PinInfo::compute(&board, Player::Black).pinned, EMPTY_BITBOARD);
+
+        let (board, _, _) = BitboardBoard::from_fen("k3l4/9/9/9/4G4/4K4/9/9/9 b - 1").unwrap();
+        assert!(PinInfo::compute(&board, Player::Black).is_pinned(Position::new(4, 4)));
+    }
+}
@@ -0,0 +1,174 @@
+//! Board Diffs
+//!
+//! What changed between two positions, small enough to send after every move:
+//! the squares whose contents differ and how each hand count moved. The board
+//! animates a move from its diff instead of redrawing every square, and the
+//! engine-vs-engine spectator stream sends the diff with each move rather than
+//! leaving spectators to replay the SFEN. Squares are written as in USI (`7f`)
+//! and pieces as SFEN letters (`+R` for sente's dragon, `p` for gote's pawn).
+
+use crate::board_matrix::parse_cell;
+use crate::position_format::{PositionText, HAND_ORDER};
+use crate::types::{Piece, Player, Position};
+use serde::{Deserialize, Serialize};
+
+/// One square whose contents differ
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SquareChange {
+    pub square: String,
+    /// The piece before, `None` for an empty square
+    pub before: Option<String>,
+    /// The piece after, `None` for an empty square
+    pub after: Option<String>,
+}
+
+/// Change in how many pieces of one kind a player holds in hand
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HandDelta {
+    pub player: Player,
+    /// Upper-case SFEN letter of the piece
+    pub piece: String,
+    /// Pieces gained, negative for pieces dropped
+    pub delta: i32,
+}
+
+/// Minimal difference from one position to another
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoardDiff {
+    /// Changed squares, from file 9 rank a in board order
+    pub squares: Vec<SquareChange>,
+    pub hands: Vec<HandDelta>,
+    /// Side to move in the later position
+    pub side_to_move: Player,
+}
+
+fn piece_code(piece: Option<Piece>) -> Option<String> {
+    piece.map(|piece| piece.to_fen_char())
+}
+
+impl BoardDiff {
+    /// The changes that turn `before` into `after`
+    pub fn between(before: &PositionText, after: &PositionText) -> Self {
+        let mut squares = Vec::new();
+        for row in 0..9 {
+            for col in 0..9 {
+                let position = Position::new(row, col);
+                let (old, new) =
+                    (before.board.get_piece(position), after.board.get_piece(position));
+                if old != new {
+                    squares.push(SquareChange {
+                        square: position.to_string(),
+                        before: piece_code(old),
+                        after: piece_code(new),
+                    });
+                }
+            }
+        }
+
+        let mut hands = Vec::new();
+        for player in [Player::Black, Player::White] {
+            for piece in HAND_ORDER {
+                let delta = after.captured_pieces.count(piece, player) as i32
+                    - before.captured_pieces.count(piece, player) as i32;
+                if delta != 0 {
+                    hands.push(HandDelta {
+                        player,
+                        piece: Piece::new(piece, Player::Black).to_fen_char(),
+                        delta,
+                    });
+                }
+            }
+        }
+        Self { squares, hands, side_to_move: after.side_to_move }
+    }
+
+    /// Whether the two positions had the same pieces in the same places
+    pub fn is_empty(&self) -> bool {
+        self.squares.is_empty() && self.hands.is_empty()
+    }
+
+    /// Apply the diff to `position`, which must be the earlier of the two
+    /// positions: every changed square and hand count is checked before it is
+    /// touched, so a mismatched diff leaves `position` as it was
+    pub fn apply(&self, position: &mut PositionText) -> Result<(), String> {
+        let mut changes = Vec::with_capacity(self.squares.len());
+        for change in &self.squares {
+            let square = Position::from_usi_string(&change.square)
+                .map_err(|_| format!("Invalid square '{}'", change.square))?;
+            let found = piece_code(position.board.get_piece(square));
+            if found != change.before {
+                return Err(format!(
+                    "Square {} holds {:?}, the diff expects {:?}",
+                    change.square, found, change.before
+                ));
+            }
+            let after = match change.after.as_deref() {
+                Some(code) => parse_cell(code)?,
+                None => None,
+            };
+            changes.push((square, after));
+        }
+        let mut hands = position.captured_pieces.clone();
+        for hand in &self.hands {
+            let piece = parse_cell(&hand.piece)?
+                .ok_or_else(|| format!("Unknown hand piece '{}'", hand.piece))?
+                .piece_type;
+            for _ in 0..hand.delta.max(0) {
+                hands.add_piece(piece, hand.player);
+            }
+            for _ in 0..(-hand.delta).max(0) {
+                if !hands.remove_piece(piece, hand.player) {
+                    return Err(format!("{:?} has no {:?} in hand to give up", hand.player, piece));
+                }
+            }
+        }
+
+        for (square, piece) in changes {
+            position.board.remove_piece(square);
+            if let Some(piece) = piece {
+                position.board.place_piece(piece, square);
+            }
+        }
+        position.captured_pieces = hands;
+        position.side_to_move = self.side_to_move;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_diff_round_trips() {
+        let before = PositionText::from_sfen("4k4/9/4p4/9/4R4/9/9/9/4K4 b - 1").unwrap();
+        let after = PositionText::from_sfen("4k4/9/4+R4/9/9/9/9/9/4K4 w P 2").unwrap();
+
+        let diff = BoardDiff::between(&before, &after);
+        assert_eq!(
+            diff.squares,
+            vec![
+                SquareChange {
+                    square: "5c".to_string(),
+                    before: Some("p".to_string()),
+                    after: Some("+R".to_string()),
+                },
+                SquareChange {
+                    square: "5e".to_string(),
+                    before: Some("R".to_string()),
+                    after: None
+                },
+            ]
+        );
+        assert_eq!(
+            diff.hands,
+            vec![HandDelta { player: Player::Black, piece: "P".to_string(), delta: 1 }]
+        );
+        assert_eq!(diff.side_to_move, Player::White);
+
+        let mut position = before.clone();
+        diff.apply(&mut position).unwrap();
+        assert!(BoardDiff::between(&position, &after).is_empty());
+        assert!(diff.apply(&mut position).is_err());
+    }
+}
@@ -0,0 +1,262 @@
+//! Board Matrix
+//!
+//! A position handed over as a 9x9 grid of piece codes and two hands, the form
+//! a board-photo recognizer or a third-party tool produces more easily than
+//! SFEN. Rows run from rank a (gote's back rank) down to rank i, and each row
+//! from file 9 to file 1, as the board is drawn.
+//!
+//! Cells are read leniently so recognizers need not agree on one spelling: SFEN
+//! letters (`P`, `+r`, case giving the owner), BOD kanji with `v` for gote's
+//! pieces (`歩`, `v龍`), and `""`, `.`, `-`, `*` or `・` for an empty square.
+//! Hand pieces are counted by code whatever their case, and promoted codes
+//! count as the piece they demote to. The result is a `PositionText`, so it
+//! comes out as canonical SFEN and is validated like a pasted position.
+
+use crate::bitboards::BitboardBoard;
+use crate::bod;
+use crate::position_format::{PositionText, HAND_ORDER};
+use crate::types::{CapturedPieces, Piece, PieceType, Player, Position};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A position as a grid of piece codes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoardMatrix {
+    /// Nine rows of nine cells, rank a first, file 9 first within a rank
+    pub squares: Vec<Vec<String>>,
+    /// Sente's hand: piece code to count
+    #[serde(default)]
+    pub black_hand: BTreeMap<String, u32>,
+    /// Gote's hand: piece code to count
+    #[serde(default)]
+    pub white_hand: BTreeMap<String, u32>,
+    #[serde(default = "default_side_to_move")]
+    pub side_to_move: Player,
+    /// SFEN move number; 1 when omitted
+    #[serde(default)]
+    pub move_number: Option<u32>,
+}
+
+fn default_side_to_move() -> Player {
+    Player::Black
+}
+
+impl Default for BoardMatrix {
+    fn default() -> Self {
+        Self {
+            squares: Vec::new(),
+            black_hand: BTreeMap::new(),
+            white_hand: BTreeMap::new(),
+            side_to_move: Player::Black,
+            move_number: None,
+        }
+    }
+}
+
+impl BoardMatrix {
+    /// Read the grid into a position; malformed grids and unknown codes are
+    /// errors naming the square. The position is not validated here.
+    pub fn to_position(&self) -> Result<PositionText, String> {
+        if self.squares.len() != 9 {
+            return Err(format!("Expected 9 rows, found {}", self.squares.len()));
+        }
+        let mut board = BitboardBoard::empty();
+        for (row, cells) in self.squares.iter().enumerate() {
+            if cells.len() != 9 {
+                return Err(format!(
+                    "Row {} has {} squares, expected 9",
+                    row + 1,
+                    cells.len()
+                ));
+            }
+            for (col, code) in cells.iter().enumerate() {
+                let square = Position::new(row as u8, col as u8);
+                let piece = parse_cell(code).map_err(|e| format!("{} on {}", e, square))?;
+                if let Some(piece) = piece {
+                    board.place_piece(piece, square);
+                }
+            }
+        }
+
+        let mut captured_pieces = CapturedPieces::new();
+        let hands = [(Player::Black, &self.black_hand), (Player::White, &self.white_hand)];
+        for (player, hand) in hands {
+            for (code, &count) in hand {
+                let piece = parse_hand_piece(code)?;
+                for _ in 0..count {
+                    captured_pieces.add_piece(piece, player);
+                }
+            }
+        }
+
+        board.set_side_to_move(self.side_to_move);
+        Ok(PositionText::new(
+            board,
+            self.side_to_move,
+            captured_pieces,
+            self.move_number.unwrap_or(1).max(1),
+        ))
+    }
+
+    /// The grid of a position, in SFEN letters with `""` for empty squares
+    pub fn from_position(position: &PositionText) -> Self {
+        let squares = (0..9)
+            .map(|row| {
+                (0..9)
+                    .map(|col| {
+                        position
+                            .board
+                            .get_piece(Position::new(row, col))
+                            .map(|piece| piece.to_fen_char())
+                            .unwrap_or_default()
+                    })
+                    .collect()
+            })
+            .collect();
+        let hand = |player: Player| {
+            HAND_ORDER
+                .iter()
+                .filter_map(|&piece| {
+                    let count = position.captured_pieces.count(piece, player) as u32;
+                    (count > 0).then(|| (Piece::new(piece, Player::Black).to_fen_char(), count))
+                })
+                .collect()
+        };
+        Self {
+            squares,
+            black_hand: hand(Player::Black),
+            white_hand: hand(Player::White),
+            side_to_move: position.side_to_move,
+            move_number: Some(position.move_number),
+        }
+    }
+}
+
+/// Piece type of a SFEN letter, either case
+fn sfen_piece(letter: char) -> Option<PieceType> {
+    Some(match letter.to_ascii_uppercase() {
+        'P' => PieceType::Pawn,
+        'L' => PieceType::Lance,
+        'N' => PieceType::Knight,
+        'S' => PieceType::Silver,
+        'G' => PieceType::Gold,
+        'B' => PieceType::Bishop,
+        'R' => PieceType::Rook,
+        'K' => PieceType::King,
+        _ => return None,
+    })
+}
+
+/// Read one cell; `None` for an empty square
+pub fn parse_cell(code: &str) -> Result<Option<Piece>, String> {
+    let code = code.trim();
+    if matches!(code, "" | "." | "-" | "*" | "・") {
+        return Ok(None);
+    }
+
+    let unknown = || format!("Unknown piece code '{}'", code);
+    let mut chars = code.chars();
+    let first = chars.next().ok_or_else(unknown)?;
+    let rest = chars.as_str();
+
+    // SFEN: an optional '+' and one letter, uppercase for sente
+    let (promoted, letter) = match (first, rest) {
+        ('+', rest) if rest.chars().count() == 1 => (true, rest.chars().next().unwrap()),
+        (letter, "") if letter.is_ascii_alphabetic() => (false, letter),
+        _ => {
+            // BOD: an optional 'v' for gote and one kanji
+            let (player, kanji) = match (first, rest) {
+                ('v' | 'V', rest) if rest.chars().count() == 1 => {
+                    (Player::White, rest.chars().next().unwrap())
+                }
+                (kanji, "") => (Player::Black, kanji),
+                _ => return Err(unknown()),
+            };
+            let piece = bod::piece_from_char(kanji).ok_or_else(unknown)?;
+            return Ok(Some(Piece::new(piece, player)));
+        }
+    };
+    let piece = sfen_piece(letter).ok_or_else(unknown)?;
+    let piece = if promoted {
+        piece.promoted_version().ok_or_else(unknown)?
+    } else {
+        piece
+    };
+    let player = if letter.is_ascii_uppercase() {
+        Player::Black
+    } else {
+        Player::White
+    };
+    Ok(Some(Piece::new(piece, player)))
+}
+
+/// Read a hand piece code; promoted codes demote, kings cannot be in hand
+fn parse_hand_piece(code: &str) -> Result<PieceType, String> {
+    let piece = parse_cell(code)?
+        .ok_or_else(|| format!("Empty hand piece code '{}'", code))?
+        .piece_type;
+    let piece = piece.unpromoted_version().unwrap_or(piece);
+    if piece == PieceType::King {
+        return Err("A king cannot be in hand".to_string());
+    }
+    Ok(piece)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(rows: [&str; 9]) -> Vec<Vec<String>> {
+        rows.iter()
+            .map(|row| row.split(',').map(str::to_string).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_matrix_normalizes_to_sfen() {
+        let matrix = BoardMatrix {
+            squares: rows([
+                ".,.,.,.,k,.,.,.,.",
+                ".,.,.,.,.,.,.,.,.",
+                ".,.,.,.,P,.,.,.,.",
+                ".,.,.,.,.,.,.,.,.",
+                ".,.,.,.,.,.,.,.,.",
+                ".,.,.,.,.,.,.,.,.",
+                ".,.,.,.,.,.,.,.,.",
+                ".,.,.,.,.,.,.,.,.",
+                "-,*,・, ,玉,, ,+r,",
+            ]),
+            black_hand: BTreeMap::from([("g".to_string(), 1), ("+P".to_string(), 2)]),
+            white_hand: BTreeMap::new(),
+            side_to_move: Player::Black,
+            move_number: None,
+        };
+        let position = matrix.to_position().unwrap();
+        assert_eq!(position.to_sfen(), "4k4/9/4P4/9/9/9/9/9/4K2+r1 b G2P 1");
+        let round_trip = BoardMatrix::from_position(&position).to_position().unwrap();
+        assert_eq!(round_trip.to_sfen(), position.to_sfen());
+    }
+
+    #[test]
+    fn test_matrix_errors() {
+        assert!(parse_cell("Q").is_err());
+        assert!(parse_cell("+k").is_err());
+        assert_eq!(
+            parse_cell("v馬").unwrap(),
+            Some(Piece::new(PieceType::PromotedBishop, Player::White))
+        );
+
+        let short = BoardMatrix {
+            squares: vec![vec![String::new(); 9]; 8],
+            ..BoardMatrix::default()
+        };
+        assert!(short.to_position().is_err());
+
+        let king_in_hand = BoardMatrix {
+            squares: vec![vec![String::new(); 9]; 9],
+            white_hand: BTreeMap::from([("K".to_string(), 1)]),
+            ..BoardMatrix::default()
+        };
+        assert!(king_in_hand.to_position().is_err());
+    }
+}
@@ -0,0 +1,315 @@
+//! BOD Board Diagrams
+//!
+//! The Japanese text board diagram (盤面図) found in books, mailing lists and
+//! KIF files that start from a set position:
+//!
+//! ```text
+//! 後手の持駒：飛　歩二
+//!   ９ ８ ７ ６ ５ ４ ３ ２ １
+//! +---------------------------+
+//! |v香v桂 ・ ・ ・ ・ ・v桂v香|一
+//! | ・ ・ ・ ・ ・ ・ ・ ・ ・|二
+//! ...
+//! +---------------------------+
+//! 先手の持駒：角　金　歩十八
+//! 手数＝52
+//! 後手番
+//! ```
+//!
+//! `v` marks gote's pieces. Hand counts are kanji; `手数` counts the moves
+//! already played, so it is one less than the SFEN move number. Handicap
+//! diagrams label the sides 上手 and 下手 instead of 後手 and 先手.
+
+use crate::bitboards::BitboardBoard;
+use crate::position_format::{PositionText, HAND_ORDER};
+use crate::types::{CapturedPieces, Piece, PieceType, Player, Position};
+
+const RANKS: [char; 9] = ['一', '二', '三', '四', '五', '六', '七', '八', '九'];
+const FILE_HEADER: &str = "  ９ ８ ７ ６ ５ ４ ３ ２ １";
+const BORDER: &str = "+---------------------------+";
+
+/// Read a BOD diagram; lines that are not part of one are skipped
+pub fn parse(text: &str) -> Result<PositionText, String> {
+    let mut board = BitboardBoard::empty();
+    let mut captured_pieces = CapturedPieces::new();
+    let mut side_to_move = Player::Black;
+    let mut move_number = 1;
+    let mut row = 0u8;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Some(cells) = trimmed.strip_prefix('|') {
+            if row >= 9 {
+                return Err("BOD: more than nine ranks".to_string());
+            }
+            let cells = cells.split('|').next().unwrap_or_default();
+            for (col, cell) in parse_row(cells)?.into_iter().enumerate() {
+                if let Some(piece) = cell {
+                    board.place_piece(piece, Position::new(row, col as u8));
+                }
+            }
+            row += 1;
+        } else if let Some((player, hand)) = hand_line(trimmed) {
+            for (piece, count) in parse_hand(hand)? {
+                for _ in 0..count {
+                    captured_pieces.add_piece(piece, player);
+                }
+            }
+        } else if let Some(played) = move_count(trimmed) {
+            move_number = played + 1;
+        } else if let Some(player) = turn_line(trimmed) {
+            side_to_move = player;
+        }
+    }
+    if row != 9 {
+        return Err(format!("BOD: expected nine ranks, found {}", row));
+    }
+    board.set_side_to_move(side_to_move);
+    Ok(PositionText::new(board, side_to_move, captured_pieces, move_number))
+}
+
+/// Write `position` as a BOD diagram, with `手数` when past the first move
+pub fn render(position: &PositionText) -> String {
+    let hand = |player| hand_text(&position.captured_pieces, player);
+    let mut lines = vec![format!("後手の持駒：{}", hand(Player::White))];
+    lines.push(FILE_HEADER.to_string());
+    lines.push(BORDER.to_string());
+    for row in 0..9u8 {
+        let mut line = String::from("|");
+        for col in 0..9 {
+            match position.board.get_piece(Position::new(row, col)) {
+                Some(piece) => {
+                    line.push(if piece.player == Player::White { 'v' } else { ' ' });
+                    line.push(piece_char(piece.piece_type));
+                }
+                None => line.push_str(" ・"),
+            }
+        }
+        line.push('|');
+        line.push(RANKS[row as usize]);
+        lines.push(line);
+    }
+    lines.push(BORDER.to_string());
+    lines.push(format!("先手の持駒：{}", hand(Player::Black)));
+    if position.move_number > 1 {
+        lines.push(format!("手数＝{}", position.move_number - 1));
+    }
+    let turn = if position.side_to_move == Player::Black { "先手番" } else { "後手番" };
+    lines.push(turn.to_string());
+    lines.join("\n") + "\n"
+}
+
+/// Whether `line` belongs to a BOD diagram, for picking one out of a KIF file
+pub fn is_bod_line(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.starts_with(['|', '+', '９'])
+        || hand_line(trimmed).is_some()
+        || move_count(trimmed).is_some()
+        || turn_line(trimmed).is_some()
+}
+
+fn hand_line(line: &str) -> Option<(Player, &str)> {
+    let (label, hand) = line.split_once(['：', ':'])?;
+    match label {
+        "先手の持駒" | "下手の持駒" => Some((Player::Black, hand)),
+        "後手の持駒" | "上手の持駒" => Some((Player::White, hand)),
+        _ => None,
+    }
+}
+
+/// Moves played from `手数＝52`; the KIF move header `手数----` is not one
+fn move_count(line: &str) -> Option<u32> {
+    let rest = line.strip_prefix("手数")?.strip_prefix(['＝', '='])?;
+    let digits: String = rest.chars().take_while(|&c| number_value(c).is_some()).collect();
+    digits.chars().try_fold(0, |value, c| Some(value * 10 + number_value(c)?))
+}
+
+fn turn_line(line: &str) -> Option<Player> {
+    if line.starts_with("先手番") || line.starts_with("下手番") {
+        Some(Player::Black)
+    } else if line.starts_with("後手番") || line.starts_with("上手番") {
+        Some(Player::White)
+    } else {
+        None
+    }
+}
+
+/// ASCII or full-width digit
+fn number_value(c: char) -> Option<u32> {
+    match c {
+        '０'..='９' => Some(c as u32 - '０' as u32),
+        c => c.to_digit(10),
+    }
+}
+
+fn piece_char(piece: PieceType) -> char {
+    match piece {
+        PieceType::Pawn => '歩',
+        PieceType::Lance => '香',
+        PieceType::Knight => '桂',
+        PieceType::Silver => '銀',
+        PieceType::Gold => '金',
+        PieceType::Bishop => '角',
+        PieceType::Rook => '飛',
+        PieceType::King => '玉',
+        PieceType::PromotedPawn => 'と',
+        PieceType::PromotedLance => '杏',
+        PieceType::PromotedKnight => '圭',
+        PieceType::PromotedSilver => '全',
+        PieceType::PromotedBishop => '馬',
+        PieceType::PromotedRook => '龍',
+    }
+}
+
+pub(crate) fn piece_from_char(c: char) -> Option<PieceType> {
+    Some(match c {
+        '歩' => PieceType::Pawn,
+        '香' => PieceType::Lance,
+        '桂' => PieceType::Knight,
+        '銀' => PieceType::Silver,
+        '金' => PieceType::Gold,
+        '角' => PieceType::Bishop,
+        '飛' => PieceType::Rook,
+        '玉' | '王' => PieceType::King,
+        'と' => PieceType::PromotedPawn,
+        '杏' => PieceType::PromotedLance,
+        '圭' => PieceType::PromotedKnight,
+        '全' => PieceType::PromotedSilver,
+        '馬' => PieceType::PromotedBishop,
+        '龍' | '竜' => PieceType::PromotedRook,
+        _ => return None,
+    })
+}
+
+/// The nine cells between the borders of a rank: ` 歩` sente, `v歩` gote,
+/// ` ・` empty
+fn parse_row(cells: &str) -> Result<Vec<Option<Piece>>, String> {
+    let mut row = Vec::with_capacity(9);
+    let mut player = Player::Black;
+    for c in cells.chars() {
+        match c {
+            'v' | 'V' => player = Player::White,
+            ' ' | '^' => player = Player::Black,
+            '・' => row.push(None),
+            c => {
+                let piece = piece_from_char(c).ok_or_else(|| format!("BOD: unknown piece {}", c))?;
+                row.push(Some(Piece::new(piece, player)));
+                player = Player::Black;
+            }
+        }
+    }
+    if row.len() != 9 {
+        return Err(format!("BOD: rank with {} squares: |{}|", row.len(), cells));
+    }
+    Ok(row)
+}
+
+/// A hand such as `飛　角　歩十八`, or `なし`
+fn parse_hand(hand: &str) -> Result<Vec<(PieceType, u32)>, String> {
+    let hand = hand.trim();
+    if hand.is_empty() || hand == "なし" {
+        return Ok(Vec::new());
+    }
+    hand.split(char::is_whitespace)
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            let mut chars = item.chars();
+            let piece = chars
+                .next()
+                .and_then(piece_from_char)
+                .ok_or_else(|| format!("BOD: unknown hand piece {}", item))?;
+            let count = kanji_number(chars.as_str())
+                .ok_or_else(|| format!("BOD: bad count in {}", item))?;
+            Ok((piece, count))
+        })
+        .collect()
+}
+
+/// `""` as 1, `二` to `十八`, and Arabic digits, ASCII or full-width
+fn kanji_number(text: &str) -> Option<u32> {
+    if text.is_empty() {
+        return Some(1);
+    }
+    let digit = |c: char| match c {
+        '一' => Some(1),
+        '二' => Some(2),
+        '三' => Some(3),
+        '四' => Some(4),
+        '五' => Some(5),
+        '六' => Some(6),
+        '七' => Some(7),
+        '八' => Some(8),
+        '九' => Some(9),
+        c => number_value(c),
+    };
+    if let Some((tens, ones)) = text.split_once('十') {
+        let tens = if tens.is_empty() { 1 } else { digit(tens.chars().next()?)? };
+        let ones = if ones.is_empty() { 0 } else { digit(ones.chars().next()?)? };
+        return Some(tens * 10 + ones);
+    }
+    text.chars().try_fold(0, |value, c| Some(value * 10 + digit(c)?))
+}
+
+fn hand_text(captured_pieces: &CapturedPieces, player: Player) -> String {
+    const NUMBERS: [&str; 10] = ["", "", "二", "三", "四", "五", "六", "七", "八", "九"];
+    let items: Vec<String> = HAND_ORDER
+        .iter()
+        .filter_map(|&piece| {
+            let count = captured_pieces.count(piece, player);
+            let number = match count {
+                0 => return None,
+                1..=9 => NUMBERS[count].to_string(),
+                10 => "十".to_string(),
+                _ => format!("十{}", NUMBERS[count - 10]),
+            };
+            Some(format!("{}{}", piece_char(piece), number))
+        })
+        .collect();
+    if items.is_empty() {
+        "なし".to_string()
+    } else {
+        items.join("　")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// As printed in a book: 竜 and 王, full-width move count, no trailing rank
+    /// labels on two rows, and a header line before the diagram
+    const BOOK_DIAGRAM: &str = "\
+第1図は△５二金まで
+後手の持駒：角　歩十八
+  ９ ８ ７ ６ ５ ４ ３ ２ １
++---------------------------+
+|v香v桂 ・ ・ ・ ・ ・v桂v香|一
+| ・ ・ ・ ・ ・ ・v王 ・ ・|二
+| ・ ・ ・ ・v金 ・ ・ ・ ・|三
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|五
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|六
+| ・ ・ ・ ・ ・ ・ 竜 ・ ・|七
+| ・ 玉 ・ ・ ・ ・ ・ ・ ・|
+| 香 桂 ・ ・ ・ ・ ・ 桂 香|九
++---------------------------+
+先手の持駒：飛　金三　銀四
+手数＝５２
+先手番
+";
+
+    #[test]
+    fn test_book_diagram() {
+        let position = parse(BOOK_DIAGRAM).unwrap();
+        assert_eq!(
+            position.to_sfen(),
+            "ln5nl/6k2/4g4/9/9/9/6+R2/1K7/LN5NL b R3G4Sb18p 53"
+        );
+        assert_eq!(parse(&render(&position)).unwrap().to_sfen(), position.to_sfen());
+
+        assert!(is_bod_line("手数＝５２"));
+        assert!(!is_bod_line("手数----指手---------消費時間--"));
+        assert!(!is_bod_line("第1図は△５二金まで"));
+        assert!(parse("|v玉 ・ ・|一").is_err());
+    }
+}
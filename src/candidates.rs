@@ -0,0 +1,126 @@
+//! Candidate Moves
+//!
+//! The top few moves of a position with scores and short continuations, for the
+//! GUI's ranked analysis arrows. Candidates come from a MultiPV-style search:
+//! each rank is a short `searchmoves` search over the root moves not ranked yet,
+//! so every candidate gets an exact score rather than a fail-low bound. The time
+//! budget is split evenly over the ranks. Scores are reported in the engine's
+//! `ScorePerspective`, and each candidate says which one that is, along with
+//! the win probability the score stands for.
+
+use crate::search::mate_score::mate_distance;
+use crate::search::perspective::EvaluationPerspective;
+use crate::search::win_probability::win_rate_permille;
+use crate::types::core::{Move, Player};
+use serde::{Deserialize, Serialize};
+
+/// Most candidates one request may ask for
+pub const MAX_CANDIDATES: usize = 8;
+
+/// Candidates when the caller does not say
+pub const DEFAULT_CANDIDATES: usize = 3;
+
+/// Time for the whole request when the caller does not say
+pub const DEFAULT_CANDIDATE_TIME_MS: u32 = 600;
+
+/// Depth cap for each rank's search; the time budget normally ends it first
+pub const CANDIDATE_MAX_DEPTH: u8 = 64;
+
+/// Continuation moves kept per candidate, the candidate itself included
+pub const CANDIDATE_PV_LENGTH: usize = 6;
+
+/// Shortest search a single rank is given, however many ranks share the budget
+const MIN_RANK_TIME_MS: u32 = 50;
+
+/// One ranked candidate move
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CandidateMove {
+    /// 1 for the best move
+    pub rank: usize,
+    /// Move in USI notation
+    pub usi: String,
+    /// Origin square of the arrow; `None` for a drop
+    pub from: Option<String>,
+    /// Destination square of the arrow
+    pub to: String,
+    /// Score in centipawns from `perspective`
+    pub score: i32,
+    /// Signed plies to mate from `perspective`, if the score is a mate
+    pub mate: Option<i32>,
+    #[serde(default)]
+    pub perspective: EvaluationPerspective,
+    /// Win probability from `perspective` in permille
+    #[serde(default)]
+    pub win_rate: u16,
+    /// Deepest completed iteration of this rank's search
+    pub depth: u8,
+    /// Continuation in USI notation, starting with the candidate itself
+    pub pv: Vec<String>,
+}
+
+impl CandidateMove {
+    pub fn new(rank: usize, mv: &Move, score: i32, depth: u8, pv: &[Move]) -> Self {
+        Self {
+            rank,
+            usi: mv.to_usi_string(),
+            from: mv.from.map(|from| from.to_string()),
+            to: mv.to.to_string(),
+            score,
+            mate: mate_distance(score),
+            perspective: EvaluationPerspective::SideToMove,
+            win_rate: win_rate_permille(score),
+            depth,
+            pv: pv
+                .iter()
+                .take(CANDIDATE_PV_LENGTH)
+                .map(|mv| mv.to_usi_string())
+                .collect(),
+        }
+    }
+
+    /// The same candidate with its score reported from `perspective`
+    pub fn in_perspective(self, perspective: EvaluationPerspective, side_to_move: Player) -> Self {
+        let score = perspective.orient(self.score, side_to_move);
+        Self {
+            score,
+            mate: mate_distance(score),
+            perspective,
+            win_rate: win_rate_permille(score),
+            ..self
+        }
+    }
+}
+
+/// Time each of `count` ranks gets out of `time_limit_ms`
+pub fn rank_time_ms(time_limit_ms: u32, count: usize) -> u32 {
+    let count = u32::try_from(count.clamp(1, MAX_CANDIDATES)).unwrap_or(1);
+    (time_limit_ms / count).max(MIN_RANK_TIME_MS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::mate_score::mate_in;
+    use crate::types::core::{PieceType, Position};
+
+    #[test]
+    fn test_candidate_move_fields() {
+        let drop = Move::new_drop(PieceType::Gold, Position::new(1, 4), Player::Black);
+        let candidate = CandidateMove::new(1, &drop, mate_in(1), 3, &[drop.clone()]);
+        assert_eq!(candidate.usi, "G*5b");
+        assert_eq!(candidate.from, None);
+        assert_eq!(candidate.to, "5b");
+        assert_eq!(candidate.mate, Some(1));
+        assert_eq!(candidate.pv, vec!["G*5b"]);
+
+        // With gote to move, a mate for the side to move is a mate against sente
+        let candidate =
+            candidate.in_perspective(EvaluationPerspective::BlackPositive, Player::White);
+        assert_eq!(candidate.mate, Some(-1));
+        assert_eq!(candidate.win_rate, 0);
+        assert_eq!(candidate.perspective, EvaluationPerspective::BlackPositive);
+
+        assert_eq!(rank_time_ms(600, 3), 200);
+        assert_eq!(rank_time_ms(100, 8), MIN_RANK_TIME_MS);
+    }
+}
@@ -22,6 +22,7 @@ interface SearchInfo {
   seldepth?: number;
   nodes: number;
   score: number;
+  winRate?: number; // permille, when the engine reports it
   pv: string; // space-separated move list
 }
 
@@ -171,6 +172,7 @@ export const TauriUsiMonitor: React.FC<TauriUsiMonitorProps> = ({
           seldepth: info.seldepth,
           nodes: info.nodes,
           score: info.score,
+          winRate: info.winRate,
           pv: info.pv ? info.pv.join(' ') : '',
         };
 
@@ -421,6 +423,7 @@ export const TauriUsiMonitor: React.FC<TauriUsiMonitorProps> = ({
                               <td>{formatNumber(info.nodes)}</td>
                               <td className={info.score > 0 ? 'score-positive' : info.score < 0 ? 'score-negative' : ''}>
                                 {info.score > 0 ? '+' : ''}{info.score}
+                                {info.winRate !== undefined && ` (${(info.winRate / 10).toFixed(1)}%)`}
                               </td>
                               <td className="pv-cell">{formatPV(info.pv, engineId)}</td>
                             </tr>
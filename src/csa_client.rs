@@ -0,0 +1,654 @@
+//! CSA Server Client
+//!
+//! Client for the CSA server protocol (version 1.2), spoken by floodgate and
+//! most computer shogi servers. The client logs in, accepts the games the
+//! server offers, plays them with the engine through its USI handler, relays
+//! moves in both directions and keeps a tally of the results.
+
+use crate::bitboards::BitboardBoard;
+use crate::csa_parser::{csa_code, parse_square, usi_letter, usi_square, CsaGame};
+use crate::types::core::{Move, Player, Position};
+use crate::usi::UsiHandler;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+/// Port used by floodgate and the reference CSA server
+pub const DEFAULT_PORT: u16 = 4081;
+
+/// Connection and play settings
+#[derive(Debug, Clone)]
+pub struct CsaClientConfig {
+    pub host: String,
+    pub port: u16,
+    pub user: String,
+    pub password: String,
+    /// Number of games to play before logging out; `None` plays until the
+    /// server closes the connection
+    pub games: Option<u32>,
+    /// Time kept in reserve for network latency on every move
+    pub margin_ms: u64,
+    /// Idle time after which an empty keep-alive line is sent
+    pub keep_alive: Duration,
+    /// USI options applied to the engine before the first game
+    pub usi_options: Vec<(String, String)>,
+}
+
+impl CsaClientConfig {
+    pub fn new(host: &str, user: &str, password: &str) -> Self {
+        Self {
+            host: host.to_string(),
+            port: DEFAULT_PORT,
+            user: user.to_string(),
+            password: password.to_string(),
+            games: None,
+            margin_ms: 1000,
+            keep_alive: Duration::from_secs(30),
+            usi_options: Vec::new(),
+        }
+    }
+}
+
+/// A game offered by the server (`BEGIN Game_Summary` block)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameSummary {
+    pub game_id: String,
+    pub black_name: String,
+    pub white_name: String,
+    /// Side this client plays
+    pub my_side: Player,
+    /// Starting position in SFEN
+    pub initial_sfen: String,
+    /// Moves already played from the starting position, in USI notation, with
+    /// the time each took in time units
+    pub moves: Vec<(String, Option<u32>)>,
+    /// Length of one time unit in milliseconds
+    pub time_unit_ms: u64,
+    pub total_time: u64,
+    pub byoyomi: u64,
+    pub increment: u64,
+    pub max_moves: Option<u32>,
+}
+
+impl GameSummary {
+    /// Parse the lines of a summary, with or without the `BEGIN`/`END` markers
+    pub fn parse(lines: &[String]) -> Result<Self, String> {
+        let mut values = Vec::new();
+        let mut position = Vec::new();
+        let mut in_position = false;
+        for line in lines.iter().map(|line| line.trim()) {
+            match line {
+                "BEGIN Position" => in_position = true,
+                "END Position" => in_position = false,
+                _ if in_position => position.push(line),
+                _ => {
+                    if let Some((key, value)) = line.split_once(':') {
+                        values.push((key, value.trim()));
+                    }
+                }
+            }
+        }
+        let value = |key: &str| values.iter().find(|(name, _)| *name == key).map(|(_, v)| *v);
+        let number = |key: &str| value(key).and_then(|v| v.parse::<u64>().ok());
+
+        let game_id = value("Game_ID").ok_or("Game summary without Game_ID")?;
+        let my_side = match value("Your_Turn") {
+            Some("+") => Player::Black,
+            Some("-") => Player::White,
+            other => return Err(format!("Invalid Your_Turn: {:?}", other)),
+        };
+        let game = CsaGame::from_string(&position.join("\n"))?;
+
+        Ok(Self {
+            game_id: game_id.to_string(),
+            black_name: value("Name+").unwrap_or_default().to_string(),
+            white_name: value("Name-").unwrap_or_default().to_string(),
+            my_side,
+            initial_sfen: game.initial_sfen,
+            moves: game.moves.into_iter().map(|mv| (mv.usi_move, mv.time_secs)).collect(),
+            time_unit_ms: value("Time_Unit").map_or(Ok(1000), parse_time_unit)?,
+            total_time: number("Total_Time").unwrap_or(0),
+            byoyomi: number("Byoyomi").unwrap_or(0),
+            increment: number("Increment").unwrap_or(0),
+            max_moves: value("Max_Moves").and_then(|v| v.parse().ok()),
+        })
+    }
+}
+
+/// `Time_Unit` value such as `1sec`, `1min` or `100msec`, in milliseconds
+fn parse_time_unit(value: &str) -> Result<u64, String> {
+    let digits = value.chars().take_while(char::is_ascii_digit).count();
+    let count = if digits == 0 { Ok(1) } else { value[..digits].parse::<u64>() }
+        .map_err(|_| format!("Invalid Time_Unit: {}", value))?;
+    let unit_ms = match &value[digits..] {
+        "msec" => 1,
+        "sec" => 1000,
+        "min" => 60_000,
+        _ => return Err(format!("Invalid Time_Unit: {}", value)),
+    };
+    Ok(count * unit_ms)
+}
+
+/// How a game ended for this client
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameOutcome {
+    Win,
+    Lose,
+    Draw,
+    /// Ended by the server without a result (`#CENSORED`)
+    Censored,
+    /// Interrupted by the server (`#CHUDAN`)
+    Interrupted,
+}
+
+/// Record of one finished game
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameReport {
+    pub game_id: String,
+    pub my_side: Player,
+    pub opponent: String,
+    pub outcome: GameOutcome,
+    /// Special move that ended the game, e.g. `RESIGN` or `TIME_UP`
+    pub reason: Option<String>,
+    /// Moves of the game in USI notation
+    pub moves: Vec<String>,
+}
+
+/// Results of a session
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MatchRecord {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    pub interrupted: u32,
+    pub games: Vec<GameReport>,
+}
+
+impl MatchRecord {
+    pub fn record(&mut self, report: GameReport) {
+        match report.outcome {
+            GameOutcome::Win => self.wins += 1,
+            GameOutcome::Lose => self.losses += 1,
+            GameOutcome::Draw => self.draws += 1,
+            GameOutcome::Censored | GameOutcome::Interrupted => self.interrupted += 1,
+        }
+        self.games.push(report);
+    }
+
+    /// Points per decided game, counting draws as half, or `None` before any
+    pub fn score_rate(&self) -> Option<f64> {
+        let played = self.wins + self.losses + self.draws;
+        (played > 0)
+            .then(|| (f64::from(self.wins) + f64::from(self.draws) / 2.0) / f64::from(played))
+    }
+}
+
+/// Both players' clocks during a game
+#[derive(Debug, Clone)]
+struct Clock {
+    unit_ms: u64,
+    remaining_ms: [u64; 2],
+    byoyomi_ms: u64,
+    increment_ms: u64,
+}
+
+impl Clock {
+    fn new(summary: &GameSummary) -> Self {
+        let unit_ms = summary.time_unit_ms;
+        Self {
+            unit_ms,
+            remaining_ms: [summary.total_time * unit_ms; 2],
+            byoyomi_ms: summary.byoyomi * unit_ms,
+            increment_ms: summary.increment * unit_ms,
+        }
+    }
+
+    fn index(player: Player) -> usize {
+        if player == Player::Black {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// Charge `player` for a move that took `units` time units
+    fn spend(&mut self, player: Player, units: Option<u32>) {
+        let remaining = &mut self.remaining_ms[Self::index(player)];
+        let spent = u64::from(units.unwrap_or(0)) * self.unit_ms;
+        *remaining = remaining.saturating_sub(spent) + self.increment_ms;
+    }
+
+    /// USI `go` command for `me`, keeping `margin_ms` in reserve
+    fn go_command(&self, me: Player, margin_ms: u64) -> String {
+        let time = |player: Player| {
+            let remaining = self.remaining_ms[Self::index(player)];
+            if player == me {
+                remaining.saturating_sub(margin_ms)
+            } else {
+                remaining
+            }
+        };
+        format!(
+            "go btime {} wtime {} byoyomi {} binc {} winc {}",
+            time(Player::Black),
+            time(Player::White),
+            self.byoyomi_ms.saturating_sub(margin_ms),
+            self.increment_ms,
+            self.increment_ms
+        )
+    }
+}
+
+/// Convert a USI move to CSA notation, e.g. `7g7f` to `+7776FU`
+pub fn usi_to_csa(board: &BitboardBoard, player: Player, usi_move: &str) -> Result<String, String> {
+    let sign = if player == Player::Black { '+' } else { '-' };
+    let mv = Move::from_usi_string(usi_move, player, board)
+        .map_err(|e| format!("{}: {}", usi_move, e))?;
+    let square = |position: Position| format!("{}{}", 9 - position.col, position.row + 1);
+    let piece_type = if mv.is_promotion {
+        mv.piece_type
+            .promoted_version()
+            .ok_or_else(|| format!("Piece cannot promote: {}", usi_move))?
+    } else {
+        mv.piece_type
+    };
+    let from = mv.from.map_or_else(|| "00".to_string(), square);
+    Ok(format!("{}{}{}{}", sign, from, square(mv.to), csa_code(piece_type)))
+}
+
+/// Convert a CSA move body (without time or comments) to USI notation, e.g.
+/// `+8822UM` to `8h2b+`
+pub fn csa_to_usi(board: &BitboardBoard, csa_move: &str) -> Result<String, String> {
+    let body = csa_move
+        .get(1..)
+        .filter(|body| body.len() == 6)
+        .ok_or_else(|| format!("Invalid CSA move: {}", csa_move))?;
+    let (from, rest) = body.split_at(2);
+    let (to, code) = rest.split_at(2);
+    let (to_file, to_rank) = parse_square(to)?;
+    let to_usi = usi_square(to_file, to_rank);
+
+    if from == "00" {
+        return Ok(format!("{}*{}", usi_letter(code)?, to_usi));
+    }
+
+    let (from_file, from_rank) = parse_square(from)?;
+    let from_usi = usi_square(from_file, from_rank);
+    let position =
+        Position::from_usi_string(&from_usi).map_err(|e| format!("{}: {}", csa_move, e))?;
+    let piece = board
+        .get_piece(position)
+        .ok_or_else(|| format!("No piece on the source square: {}", csa_move))?;
+    let promotes =
+        usi_letter(code)?.starts_with('+') && piece.piece_type.unpromoted_version().is_none();
+    Ok(format!("{}{}{}", from_usi, to_usi, if promotes { "+" } else { "" }))
+}
+
+fn apply_usi(board: &mut BitboardBoard, player: Player, usi_move: &str) -> Result<(), String> {
+    let mv = Move::from_usi_string(usi_move, player, board)
+        .map_err(|e| format!("{}: {}", usi_move, e))?;
+    board.make_move(&mv);
+    Ok(())
+}
+
+/// Connection to a CSA server
+pub struct CsaClient {
+    config: CsaClientConfig,
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    /// Bytes of a line whose end has not arrived yet
+    partial_line: String,
+    handler: UsiHandler,
+    record: MatchRecord,
+}
+
+impl CsaClient {
+    pub fn connect(config: CsaClientConfig) -> Result<Self, String> {
+        let stream = TcpStream::connect((config.host.as_str(), config.port))
+            .map_err(|e| format!("Failed to connect to {}:{}: {}", config.host, config.port, e))?;
+        stream
+            .set_read_timeout(Some(config.keep_alive))
+            .map_err(|e| format!("Failed to configure connection: {}", e))?;
+        let writer = stream
+            .try_clone()
+            .map_err(|e| format!("Failed to configure connection: {}", e))?;
+
+        let mut handler = UsiHandler::new();
+        for (name, value) in &config.usi_options {
+            handler.handle_command(&format!("setoption name {} value {}", name, value));
+        }
+
+        Ok(Self {
+            config,
+            reader: BufReader::new(stream),
+            writer,
+            partial_line: String::new(),
+            handler,
+            record: MatchRecord::default(),
+        })
+    }
+
+    /// Results of the games played so far
+    pub fn record(&self) -> &MatchRecord {
+        &self.record
+    }
+
+    /// Log in, play the configured number of games and log out
+    pub fn run(&mut self) -> Result<&MatchRecord, String> {
+        self.login()?;
+        while self.config.games.map_or(true, |games| self.record.games.len() < games as usize) {
+            let Some(summary) = self.next_game()? else {
+                break;
+            };
+            let report = self.play_game(&summary)?;
+            log::info!(
+                "Game {} finished: {:?} ({:?})",
+                report.game_id,
+                report.outcome,
+                report.reason
+            );
+            self.record.record(report);
+        }
+        self.logout();
+        Ok(&self.record)
+    }
+
+    fn login(&mut self) -> Result<(), String> {
+        self.send(&format!("LOGIN {} {}", self.config.user, self.config.password))?;
+        let reply = self.read_line()?.ok_or("Connection closed during login")?;
+        if reply.starts_with("LOGIN:") && reply.ends_with(" OK") {
+            Ok(())
+        } else {
+            Err(format!("Login rejected: {}", reply))
+        }
+    }
+
+    fn logout(&mut self) {
+        if self.send("LOGOUT").is_ok() {
+            // The server confirms with LOGOUT:completed and closes the connection
+            let _ = self.read_line();
+        }
+    }
+
+    /// Wait for a game summary and accept it. Returns `None` if the server
+    /// closes the connection, and skips games the opponent rejects.
+    fn next_game(&mut self) -> Result<Option<GameSummary>, String> {
+        loop {
+            let mut lines = Vec::new();
+            loop {
+                let Some(line) = self.read_line()? else {
+                    return Ok(None);
+                };
+                if line == "END Game_Summary" {
+                    break;
+                }
+                if line == "BEGIN Game_Summary" {
+                    lines.clear();
+                } else {
+                    lines.push(line);
+                }
+            }
+
+            let summary = GameSummary::parse(&lines)?;
+            self.send(&format!("AGREE {}", summary.game_id))?;
+            loop {
+                let Some(line) = self.read_line()? else {
+                    return Ok(None);
+                };
+                if line.starts_with("START:") {
+                    return Ok(Some(summary));
+                }
+                if line.starts_with("REJECT:") {
+                    log::info!("Game {} was rejected: {}", summary.game_id, line);
+                    break;
+                }
+            }
+        }
+    }
+
+    fn play_game(&mut self, summary: &GameSummary) -> Result<GameReport, String> {
+        let (mut board, mut to_move, _) = BitboardBoard::from_fen(&summary.initial_sfen)?;
+        let mut clock = Clock::new(summary);
+        let mut moves = Vec::new();
+        for (usi_move, time) in &summary.moves {
+            apply_usi(&mut board, to_move, usi_move)?;
+            clock.spend(to_move, *time);
+            moves.push(usi_move.clone());
+            to_move = to_move.opposite();
+        }
+
+        self.handler.handle_command("usinewgame");
+        let mut awaiting_echo = false;
+        let mut reason = None;
+        let outcome = loop {
+            if to_move == summary.my_side && !awaiting_echo {
+                let reply = self.choose_move(summary, &board, &moves, &clock)?;
+                self.send(&reply)?;
+                awaiting_echo = true;
+            }
+
+            let Some(line) = self.read_line()? else {
+                return Err(format!("Connection closed during game {}", summary.game_id));
+            };
+            if let Some(result) = line.strip_prefix('#') {
+                match result {
+                    "WIN" => break GameOutcome::Win,
+                    "LOSE" => break GameOutcome::Lose,
+                    "DRAW" => break GameOutcome::Draw,
+                    "CENSORED" => break GameOutcome::Censored,
+                    "CHUDAN" => break GameOutcome::Interrupted,
+                    special => reason = Some(special.to_string()),
+                }
+            } else if line.starts_with('+') || line.starts_with('-') {
+                let mut fields = line.split(',');
+                let csa_move = fields.next().unwrap_or_default();
+                let time = fields.find_map(|field| field.strip_prefix('T')?.parse().ok());
+                let usi_move = csa_to_usi(&board, csa_move)?;
+                apply_usi(&mut board, to_move, &usi_move)?;
+                clock.spend(to_move, time);
+                moves.push(usi_move);
+                to_move = to_move.opposite();
+                awaiting_echo = false;
+            }
+            // `%TORYO`/`%KACHI` echoes are followed by the result lines
+        };
+
+        self.handler.handle_command(&format!(
+            "gameover {}",
+            match outcome {
+                GameOutcome::Win => "win",
+                GameOutcome::Lose => "lose",
+                _ => "draw",
+            }
+        ));
+
+        let opponent = if summary.my_side == Player::Black {
+            &summary.white_name
+        } else {
+            &summary.black_name
+        };
+        Ok(GameReport {
+            game_id: summary.game_id.clone(),
+            my_side: summary.my_side,
+            opponent: opponent.clone(),
+            outcome,
+            reason,
+            moves,
+        })
+    }
+
+    /// Search the current position and return the CSA message to send
+    fn choose_move(
+        &mut self,
+        summary: &GameSummary,
+        board: &BitboardBoard,
+        moves: &[String],
+        clock: &Clock,
+    ) -> Result<String, String> {
+        let position = if moves.is_empty() {
+            format!("position sfen {}", summary.initial_sfen)
+        } else {
+            format!("position sfen {} moves {}", summary.initial_sfen, moves.join(" "))
+        };
+        self.handler.handle_command(&position);
+
+        let output = self
+            .handler
+            .handle_command(&clock.go_command(summary.my_side, self.config.margin_ms));
+        let best_move = output
+            .iter()
+            .find_map(|line| line.strip_prefix("bestmove "))
+            .and_then(|rest| rest.split_whitespace().next());
+        match best_move {
+            None | Some("resign") => Ok("%TORYO".to_string()),
+            Some("win") => Ok("%KACHI".to_string()),
+            Some(usi_move) => usi_to_csa(board, summary.my_side, usi_move),
+        }
+    }
+
+    fn send(&mut self, message: &str) -> Result<(), String> {
+        log::debug!("CSA > {}", message);
+        writeln!(self.writer, "{}", message)
+            .and_then(|()| self.writer.flush())
+            .map_err(|e| format!("Failed to send to server: {}", e))
+    }
+
+    /// Next non-empty line from the server, or `None` once it closes the
+    /// connection. Sends keep-alive lines while waiting.
+    fn read_line(&mut self) -> Result<Option<String>, String> {
+        loop {
+            match self.reader.read_line(&mut self.partial_line) {
+                Ok(0) => return Ok(None),
+                Ok(_) if !self.partial_line.ends_with('\n') => continue,
+                Ok(_) => {
+                    let line = self.partial_line.trim().to_string();
+                    self.partial_line.clear();
+                    if line.is_empty() {
+                        continue;
+                    }
+                    log::debug!("CSA < {}", line);
+                    return Ok(Some(line));
+                }
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    self.send("")?;
+                }
+                Err(e) => return Err(format!("Failed to read from server: {}", e)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    const SUMMARY: &str = "BEGIN Game_Summary
+Protocol_Version:1.2
+Protocol_Mode:Server
+Format:Shogi 1.0
+Game_ID:wdoor+floodgate-300-10F+alpha+beta+20240501120000
+Name+:alpha
+Name-:beta
+Your_Turn:+
+Rematch_On_Draw:NO
+To_Move:+
+Max_Moves:256
+BEGIN Time
+Time_Unit:1sec
+Total_Time:300
+Byoyomi:0
+Increment:10
+END Time
+BEGIN Position
+PI
++
++7776FU,T3
+END Position
+END Game_Summary";
+
+    fn summary_lines() -> Vec<String> {
+        SUMMARY.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse_game_summary() {
+        let summary = GameSummary::parse(&summary_lines()).unwrap();
+        assert_eq!(summary.game_id, "wdoor+floodgate-300-10F+alpha+beta+20240501120000");
+        assert_eq!(summary.my_side, Player::Black);
+        assert_eq!(summary.white_name, "beta");
+        assert_eq!(summary.moves, vec![("7g7f".to_string(), Some(3))]);
+        assert!(summary.initial_sfen.starts_with("lnsgkgsnl/1r5b1/ppppppppp/"));
+        assert_eq!((summary.time_unit_ms, summary.total_time, summary.increment), (1000, 300, 10));
+        assert_eq!(summary.max_moves, Some(256));
+    }
+
+    #[test]
+    fn test_move_conversion() {
+        let (mut board, _, _) =
+            BitboardBoard::from_fen(crate::handicap::Handicap::Even.sfen()).unwrap();
+        assert_eq!(usi_to_csa(&board, Player::Black, "7g7f").unwrap(), "+7776FU");
+        apply_usi(&mut board, Player::Black, "7g7f").unwrap();
+        assert_eq!(csa_to_usi(&board, "-3334FU").unwrap(), "3c3d");
+        apply_usi(&mut board, Player::White, "3c3d").unwrap();
+
+        assert_eq!(usi_to_csa(&board, Player::Black, "8h2b+").unwrap(), "+8822UM");
+        assert_eq!(csa_to_usi(&board, "+8822UM").unwrap(), "8h2b+");
+        apply_usi(&mut board, Player::Black, "8h2b+").unwrap();
+        assert_eq!(csa_to_usi(&board, "-3122GI").unwrap(), "3a2b");
+        assert_eq!(csa_to_usi(&board, "+0045KA").unwrap(), "B*4e");
+        assert_eq!(usi_to_csa(&board, Player::Black, "B*4e").unwrap(), "+0045KA");
+    }
+
+    #[test]
+    fn test_clock_tracks_increment_and_margin() {
+        let summary = GameSummary::parse(&summary_lines()).unwrap();
+        let mut clock = Clock::new(&summary);
+        clock.spend(Player::Black, Some(3));
+        assert_eq!(clock.remaining_ms, [307_000, 300_000]);
+        assert_eq!(
+            clock.go_command(Player::White, 1000),
+            "go btime 307000 wtime 299000 byoyomi 0 binc 10000 winc 10000"
+        );
+        assert_eq!(parse_time_unit("100msec"), Ok(100));
+        assert!(parse_time_unit("1hour").is_err());
+    }
+
+    #[test]
+    fn test_session_with_scripted_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut writer = stream;
+            let mut expect = |prefix: &str| {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                assert!(line.starts_with(prefix), "expected {}, got {:?}", prefix, line);
+            };
+
+            expect("LOGIN alpha secret");
+            writeln!(writer, "LOGIN:alpha OK\n{}", SUMMARY).unwrap();
+            expect("AGREE wdoor+floodgate");
+            // The opponent resigns before this client has to move
+            writeln!(writer, "START:wdoor+floodgate\n%TORYO,T1\n#RESIGN\n#WIN").unwrap();
+            expect("LOGOUT");
+            writeln!(writer, "LOGOUT:completed").unwrap();
+        });
+
+        let mut config = CsaClientConfig::new("127.0.0.1", "alpha", "secret");
+        config.port = port;
+        config.games = Some(1);
+        let mut client = CsaClient::connect(config).unwrap();
+        let record = client.run().unwrap().clone();
+        server.join().unwrap();
+
+        assert_eq!(record.wins, 1);
+        assert_eq!(record.games[0].reason.as_deref(), Some("RESIGN"));
+        assert_eq!(record.games[0].moves, vec!["7g7f".to_string()]);
+        assert_eq!(record.score_rate(), Some(1.0));
+    }
+}
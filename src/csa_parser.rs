@@ -0,0 +1,611 @@
+//! CSA Format Parser
+//!
+//! Parser for CSA (Computer Shogi Association) game records, the format used by
+//! floodgate and most computer shogi servers. Supports player names, `$` headers,
+//! the `PI` / `P1`..`P9` / `P+` / `P-` position descriptions (including handicap
+//! setups), move time stamps (`T<seconds>`), comments and termination codes.
+//! Moves are converted to USI so records can feed the same `GameRecord`
+//! structures as KIF files.
+
+use crate::bitboards::BitboardBoard;
+use crate::tuning::types::{GameRecord, GameResult, TimeControl};
+use crate::types::core::{Move, PieceType, Player};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// CSA piece codes and USI piece letters, indexed by `PieceType::as_index`
+const CSA_PIECES: [(&str, &str); 14] = [
+    ("FU", "P"),
+    ("KY", "L"),
+    ("KE", "N"),
+    ("GI", "S"),
+    ("KI", "G"),
+    ("KA", "B"),
+    ("HI", "R"),
+    ("OU", "K"),
+    ("TO", "+P"),
+    ("NY", "+L"),
+    ("NK", "+N"),
+    ("NG", "+S"),
+    ("UM", "+B"),
+    ("RY", "+R"),
+];
+
+/// Number of each unpromoted piece in a full set, for `00AL`
+const PIECE_SET: [(&str, u8); 8] =
+    [("FU", 18), ("KY", 4), ("KE", 4), ("GI", 4), ("KI", 4), ("KA", 2), ("HI", 2), ("OU", 2)];
+
+/// Hand pieces in SFEN order
+const HAND_ORDER: [&str; 7] = ["HI", "KA", "KI", "GI", "KE", "KY", "FU"];
+
+/// Hirate starting position as CSA rows (rank 1 to 9, file 9 to 1)
+const HIRATE_ROWS: [&str; 9] = [
+    "-KY-KE-GI-KI-OU-KI-GI-KE-KY",
+    " * -HI *  *  *  *  * -KA * ",
+    "-FU-FU-FU-FU-FU-FU-FU-FU-FU",
+    " *  *  *  *  *  *  *  *  * ",
+    " *  *  *  *  *  *  *  *  * ",
+    " *  *  *  *  *  *  *  *  * ",
+    "+FU+FU+FU+FU+FU+FU+FU+FU+FU",
+    " * +KA *  *  *  *  * +HI * ",
+    "+KY+KE+GI+KI+OU+KI+GI+KE+KY",
+];
+
+/// A move from a CSA record
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CsaMove {
+    pub move_number: usize,
+    pub player: Player,
+    /// Move as written in the record, e.g. `+7776FU`
+    pub csa_move: String,
+    pub usi_move: String,
+    /// Time spent on the move in seconds (`T` line)
+    pub time_secs: Option<u32>,
+    /// Comment lines (`'...`) following the move
+    pub comments: Vec<String>,
+}
+
+/// Complete parsed CSA game
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CsaGame {
+    pub version: Option<String>,
+    pub black_name: Option<String>,
+    pub white_name: Option<String>,
+    /// `$KEY:value` headers in file order
+    pub headers: Vec<(String, String)>,
+    /// Starting position in SFEN, reflecting any handicap setup
+    pub initial_sfen: String,
+    pub moves: Vec<CsaMove>,
+    /// Termination code without the `%`, e.g. `TORYO` or `SENNICHITE`
+    pub termination: Option<String>,
+    /// Winner implied by the termination code, if any
+    pub winner: Option<Player>,
+}
+
+/// Mutable position used while reading a record
+struct CsaPosition {
+    /// `[rank - 1][9 - file]`, holding the owner and CSA piece code
+    squares: [[Option<(Player, String)>; 9]; 9],
+    hands: [Vec<String>; 2],
+    side_to_move: Player,
+}
+
+impl CsaPosition {
+    fn empty() -> Self {
+        Self {
+            squares: Default::default(),
+            hands: [Vec::new(), Vec::new()],
+            side_to_move: Player::Black,
+        }
+    }
+
+    fn hand_index(player: Player) -> usize {
+        if player == Player::Black {
+            0
+        } else {
+            1
+        }
+    }
+
+    fn square(&mut self, file: u8, rank: u8) -> Result<&mut Option<(Player, String)>, String> {
+        if !(1..=9).contains(&file) || !(1..=9).contains(&rank) {
+            return Err(format!("Square out of range: {}{}", file, rank));
+        }
+        Ok(&mut self.squares[(rank - 1) as usize][(9 - file) as usize])
+    }
+
+    /// Parse a `P1`..`P9` row
+    fn set_row(&mut self, rank: u8, row: &str) -> Result<(), String> {
+        // Trailing blanks of an empty last square are often trimmed
+        let cells: Vec<char> = row.chars().collect();
+        if cells.len() > 27 {
+            return Err(format!("Invalid board row: P{}{}", rank, row));
+        }
+        for i in 0..9 {
+            let cell: String = cells.iter().skip(i * 3).take(3).collect();
+            let piece = match cell.chars().next() {
+                Some('+') if cell.len() == 3 => Some((Player::Black, cell[1..].to_string())),
+                Some('-') if cell.len() == 3 => Some((Player::White, cell[1..].to_string())),
+                Some('+' | '-') => return Err(format!("Invalid board row: P{}{}", rank, row)),
+                _ => None,
+            };
+            *self.square(9 - i as u8, rank)? = piece;
+        }
+        Ok(())
+    }
+
+    /// Parse `P+`/`P-` piece placements; square `00` is the hand and `00AL`
+    /// gives that side every piece not yet placed
+    fn add_pieces(&mut self, player: Player, spec: &str) -> Result<(), String> {
+        let chars: Vec<char> = spec.chars().collect();
+        for chunk in chars.chunks(4) {
+            let chunk: String = chunk.iter().collect();
+            if chunk.len() != 4 {
+                return Err(format!("Invalid piece placement: {}", spec));
+            }
+            let (square, piece) = chunk.split_at(2);
+            if square == "00" && piece == "AL" {
+                for (code, total) in PIECE_SET {
+                    if code == "OU" {
+                        continue;
+                    }
+                    for _ in self.count(code)..total {
+                        self.hands[Self::hand_index(player)].push(code.to_string());
+                    }
+                }
+            } else if square == "00" {
+                self.hands[Self::hand_index(player)].push(piece.to_string());
+            } else {
+                let (file, rank) = parse_square(square)?;
+                *self.square(file, rank)? = Some((player, piece.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Pieces of a kind (counting promoted forms) already on the board or in hand
+    fn count(&self, code: &str) -> u8 {
+        let on_board = self
+            .squares
+            .iter()
+            .flatten()
+            .flatten()
+            .filter(|(_, piece)| unpromote(piece) == code)
+            .count();
+        let in_hand = self.hands.iter().flatten().filter(|piece| *piece == code).count();
+        (on_board + in_hand) as u8
+    }
+
+    /// Apply a move such as `+7776FU` or `-0055KA`, returning it in USI notation
+    fn apply(&mut self, player: Player, body: &str) -> Result<String, String> {
+        if body.len() != 6 {
+            return Err(format!("Invalid CSA move: {}", body));
+        }
+        let (from, rest) = body.split_at(2);
+        let (to, piece) = rest.split_at(2);
+        let (to_file, to_rank) = parse_square(to)?;
+        let to_usi = usi_square(to_file, to_rank);
+
+        if from == "00" {
+            let hand = &mut self.hands[Self::hand_index(player)];
+            let index = hand
+                .iter()
+                .position(|held| held == piece)
+                .ok_or_else(|| format!("Drop of a piece not in hand: {}", body))?;
+            hand.remove(index);
+            *self.square(to_file, to_rank)? = Some((player, piece.to_string()));
+            return Ok(format!("{}*{}", usi_letter(piece)?, to_usi));
+        }
+
+        let (from_file, from_rank) = parse_square(from)?;
+        let (owner, moved) = self
+            .square(from_file, from_rank)?
+            .take()
+            .ok_or_else(|| format!("No piece on the source square: {}", body))?;
+        if owner != player {
+            return Err(format!("Moving an opponent's piece: {}", body));
+        }
+        if let Some((_, captured)) = self.square(to_file, to_rank)?.take() {
+            self.hands[Self::hand_index(player)].push(unpromote(&captured).to_string());
+        }
+        let promotes = moved != piece;
+        *self.square(to_file, to_rank)? = Some((player, piece.to_string()));
+
+        Ok(format!(
+            "{}{}{}",
+            usi_square(from_file, from_rank),
+            to_usi,
+            if promotes { "+" } else { "" }
+        ))
+    }
+
+    fn to_sfen(&self, move_number: usize) -> Result<String, String> {
+        let mut rows = Vec::with_capacity(9);
+        for rank in &self.squares {
+            let mut row = String::new();
+            let mut empty = 0;
+            for square in rank {
+                match square {
+                    Some((player, piece)) => {
+                        if empty > 0 {
+                            row.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        let letter = usi_letter(piece)?;
+                        row.push_str(&if *player == Player::Black {
+                            letter.to_string()
+                        } else {
+                            letter.to_lowercase()
+                        });
+                    }
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                row.push_str(&empty.to_string());
+            }
+            rows.push(row);
+        }
+
+        let mut hands = String::new();
+        for (player, hand) in [(Player::Black, &self.hands[0]), (Player::White, &self.hands[1])] {
+            for code in HAND_ORDER {
+                let count = hand.iter().filter(|piece| *piece == code).count();
+                if count == 0 {
+                    continue;
+                }
+                if count > 1 {
+                    hands.push_str(&count.to_string());
+                }
+                let letter = usi_letter(code)?;
+                hands.push_str(&if player == Player::Black {
+                    letter.to_string()
+                } else {
+                    letter.to_lowercase()
+                });
+            }
+        }
+        if hands.is_empty() {
+            hands.push('-');
+        }
+
+        let side = if self.side_to_move == Player::Black { "b" } else { "w" };
+        Ok(format!("{} {} {} {}", rows.join("/"), side, hands, move_number))
+    }
+}
+
+pub(crate) fn parse_square(text: &str) -> Result<(u8, u8), String> {
+    let mut digits = text.chars().map(|c| c.to_digit(10));
+    match (digits.next(), digits.next()) {
+        (Some(Some(file)), Some(Some(rank))) => Ok((file as u8, rank as u8)),
+        _ => Err(format!("Invalid square: {}", text)),
+    }
+}
+
+pub(crate) fn usi_square(file: u8, rank: u8) -> String {
+    format!("{}{}", file, (b'a' + rank - 1) as char)
+}
+
+pub(crate) fn usi_letter(code: &str) -> Result<&'static str, String> {
+    CSA_PIECES
+        .iter()
+        .find(|(csa, _)| *csa == code)
+        .map(|(_, usi)| *usi)
+        .ok_or_else(|| format!("Unknown CSA piece: {}", code))
+}
+
+/// CSA code of a piece type, e.g. `FU` or `UM`
+pub(crate) fn csa_code(piece_type: PieceType) -> &'static str {
+    CSA_PIECES[piece_type.as_index()].0
+}
+
+fn unpromote(code: &str) -> &str {
+    match code {
+        "TO" => "FU",
+        "NY" => "KY",
+        "NK" => "KE",
+        "NG" => "GI",
+        "UM" => "KA",
+        "RY" => "HI",
+        other => other,
+    }
+}
+
+/// Parse a `$TIME_LIMIT:hh:mm+ss` header into a time control
+fn parse_time_limit(value: &str) -> Option<TimeControl> {
+    let (clock, byoyomi) = value.split_once('+')?;
+    let (hours, minutes) = clock.split_once(':')?;
+    let initial =
+        hours.trim().parse::<u32>().ok()? * 3600 + minutes.trim().parse::<u32>().ok()? * 60;
+    Some(TimeControl::new(initial, byoyomi.trim().parse().ok()?))
+}
+
+impl CsaGame {
+    /// Load all games from a CSA file (games are separated by `/` lines)
+    pub fn from_file(path: &str) -> Result<Vec<Self>, String> {
+        let content =
+            fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+        Self::parse_all(&content)
+    }
+
+    /// Parse every game in a CSA document
+    pub fn parse_all(content: &str) -> Result<Vec<Self>, String> {
+        let mut games = Vec::new();
+        let mut current = Vec::new();
+        for line in content.lines() {
+            if line.trim() == "/" {
+                games.push(Self::from_string(&current.join("\n"))?);
+                current.clear();
+            } else {
+                current.push(line);
+            }
+        }
+        if current.iter().any(|line| !line.trim().is_empty()) {
+            games.push(Self::from_string(&current.join("\n"))?);
+        }
+        Ok(games)
+    }
+
+    /// Parse a single CSA game
+    pub fn from_string(content: &str) -> Result<Self, String> {
+        let mut game = CsaGame {
+            version: None,
+            black_name: None,
+            white_name: None,
+            headers: Vec::new(),
+            initial_sfen: String::new(),
+            moves: Vec::new(),
+            termination: None,
+            winner: None,
+        };
+        let mut position = CsaPosition::empty();
+        let mut in_moves = false;
+
+        // Several statements may share a line, separated by commas
+        let statements =
+            content
+                .lines()
+                .flat_map(|line| {
+                    if line.starts_with('\'') {
+                        vec![line]
+                    } else {
+                        line.split(',').collect()
+                    }
+                })
+                .map(str::trim)
+                .filter(|statement| !statement.is_empty());
+
+        for statement in statements {
+            if let Some(comment) = statement.strip_prefix('\'') {
+                if let Some(last) = game.moves.last_mut() {
+                    last.comments.push(comment.to_string());
+                }
+            } else if statement.starts_with('V') {
+                game.version = Some(statement.to_string());
+            } else if let Some(name) = statement.strip_prefix("N+") {
+                game.black_name = Some(name.to_string());
+            } else if let Some(name) = statement.strip_prefix("N-") {
+                game.white_name = Some(name.to_string());
+            } else if let Some(header) = statement.strip_prefix('$') {
+                let (key, value) = header.split_once(':').unwrap_or((header, ""));
+                game.headers.push((key.to_string(), value.to_string()));
+            } else if let Some(removed) = statement.strip_prefix("PI") {
+                for (rank, row) in HIRATE_ROWS.iter().enumerate() {
+                    position.set_row(rank as u8 + 1, row)?;
+                }
+                // Handicaps list the squares to clear, e.g. "PI82HI22KA"
+                let chars: Vec<char> = removed.chars().collect();
+                for chunk in chars.chunks(4) {
+                    let square: String = chunk.iter().take(2).collect();
+                    let (file, rank) = parse_square(&square)?;
+                    *position.square(file, rank)? = None;
+                }
+            } else if let Some(spec) = statement.strip_prefix("P+") {
+                position.add_pieces(Player::Black, spec)?;
+            } else if let Some(spec) = statement.strip_prefix("P-") {
+                position.add_pieces(Player::White, spec)?;
+            } else if let Some(row) = statement.strip_prefix('P') {
+                let rank = row
+                    .chars()
+                    .next()
+                    .and_then(|c| c.to_digit(10))
+                    .ok_or_else(|| format!("Invalid position line: {}", statement))?;
+                position.set_row(rank as u8, &row[1..])?;
+            } else if (statement == "+" || statement == "-") && !in_moves {
+                position.side_to_move =
+                    if statement == "+" { Player::Black } else { Player::White };
+                game.initial_sfen = position.to_sfen(1)?;
+                in_moves = true;
+            } else if let Some(body) =
+                statement.strip_prefix('+').or_else(|| statement.strip_prefix('-'))
+            {
+                if !in_moves {
+                    game.initial_sfen = position.to_sfen(1)?;
+                    in_moves = true;
+                }
+                let player = if statement.starts_with('+') { Player::Black } else { Player::White };
+                let usi_move = position.apply(player, body)?;
+                game.moves.push(CsaMove {
+                    move_number: game.moves.len() + 1,
+                    player,
+                    csa_move: statement.to_string(),
+                    usi_move,
+                    time_secs: None,
+                    comments: Vec::new(),
+                });
+            } else if let Some(seconds) = statement.strip_prefix('T') {
+                if let Some(last) = game.moves.last_mut() {
+                    last.time_secs = seconds.parse().ok();
+                }
+            } else if let Some(code) = statement.strip_prefix('%') {
+                game.termination = Some(code.to_string());
+                game.winner = Self::winner_for(code, game.next_player(position.side_to_move));
+            }
+        }
+
+        if game.initial_sfen.is_empty() {
+            game.initial_sfen = position.to_sfen(1)?;
+        }
+        Ok(game)
+    }
+
+    /// Side to move after the recorded moves
+    fn next_player(&self, first_player: Player) -> Player {
+        match self.moves.last() {
+            Some(last) => last.player.opposite(),
+            None => first_player,
+        }
+    }
+
+    /// Winner implied by a termination code, given the side to move when it was recorded
+    fn winner_for(code: &str, to_move: Player) -> Option<Player> {
+        match code {
+            // The side to move resigns, runs out of time or is mated
+            "TORYO" | "TIME_UP" | "TSUMI" => Some(to_move.opposite()),
+            // The side to move declares a win
+            "KACHI" => Some(to_move),
+            "+ILLEGAL_ACTION" => Some(Player::White),
+            "-ILLEGAL_ACTION" => Some(Player::Black),
+            // An illegal move loses for the player who made it
+            "ILLEGAL_MOVE" => Some(to_move),
+            _ => None,
+        }
+    }
+
+    /// Convert to a tuning `GameRecord`, replaying the moves on a board
+    pub fn to_game_record(&self) -> Result<GameRecord, String> {
+        let (mut board, mut player, _) = BitboardBoard::from_fen(&self.initial_sfen)?;
+        let mut moves = Vec::with_capacity(self.moves.len());
+        for csa_move in &self.moves {
+            let mv = Move::from_usi_string(&csa_move.usi_move, player, &board)
+                .map_err(|e| format!("{}: {}", csa_move.csa_move, e))?;
+            board.make_move(&mv);
+            moves.push(mv);
+            player = player.opposite();
+        }
+
+        let result = match self.winner {
+            Some(Player::Black) => GameResult::BlackWin,
+            Some(Player::White) => GameResult::WhiteWin,
+            None => GameResult::Draw,
+        };
+        let time_control = self
+            .header("TIME_LIMIT")
+            .and_then(parse_time_limit)
+            .unwrap_or_else(|| TimeControl::new(600, 10));
+
+        let mut record = GameRecord::new(moves, result, time_control);
+        record.date = self.header("START_TIME").map(str::to_string);
+        record.opening = self.header("OPENING").map(str::to_string);
+        for (key, value) in &self.headers {
+            record.metadata.insert(key.clone(), value.clone());
+        }
+        if let Some(name) = &self.black_name {
+            record.metadata.insert("black".to_string(), name.clone());
+        }
+        if let Some(name) = &self.white_name {
+            record.metadata.insert("white".to_string(), name.clone());
+        }
+        record.metadata.insert("initial_sfen".to_string(), self.initial_sfen.clone());
+        Ok(record)
+    }
+
+    /// Look up a `$` header value
+    pub fn header(&self, key: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FLOODGATE_GAME: &str = "V2.2
+N+alpha
+N-beta
+$EVENT:wdoor+floodgate-300-10F
+$START_TIME:2024/05/01 12:00:00
+$TIME_LIMIT:00:05+10
+P1-KY-KE-GI-KI-OU-KI-GI-KE-KY
+P2 * -HI *  *  *  *  * -KA *
+P3-FU-FU-FU-FU-FU-FU-FU-FU-FU
+P4 *  *  *  *  *  *  *  *  *
+P5 *  *  *  *  *  *  *  *  *
+P6 *  *  *  *  *  *  *  *  *
+P7+FU+FU+FU+FU+FU+FU+FU+FU+FU
+P8 * +KA *  *  *  *  * +HI *
+P9+KY+KE+GI+KI+OU+KI+GI+KE+KY
++
++7776FU
+T3
+-3334FU
+T5
+'bishop exchange
++8822UM,T2
+-3122GI,T1
++0045KA
+T4
+%TORYO
+";
+
+    #[test]
+    fn test_parses_floodgate_record() {
+        let game = CsaGame::from_string(FLOODGATE_GAME).unwrap();
+        assert_eq!(game.black_name.as_deref(), Some("alpha"));
+        assert_eq!(game.header("EVENT"), Some("wdoor+floodgate-300-10F"));
+        assert_eq!(
+            game.initial_sfen,
+            "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1"
+        );
+
+        let usi: Vec<&str> = game.moves.iter().map(|m| m.usi_move.as_str()).collect();
+        assert_eq!(usi, vec!["7g7f", "3c3d", "8h2b+", "3a2b", "B*4e"]);
+        assert_eq!(game.moves[1].time_secs, Some(5));
+        assert_eq!(game.moves[1].comments, vec!["bishop exchange"]);
+        assert_eq!(game.moves[2].time_secs, Some(2));
+
+        // White is to move after Black's drop and resigns
+        assert_eq!(game.termination.as_deref(), Some("TORYO"));
+        assert_eq!(game.winner, Some(Player::Black));
+    }
+
+    #[test]
+    fn test_handicap_setup() {
+        // Two-piece handicap: White (the stronger side) gives up rook and bishop and moves first
+        let game = CsaGame::from_string("PI82HI22KA\n-\n-3334FU\n").unwrap();
+        assert_eq!(
+            game.initial_sfen,
+            "lnsgkgsnl/9/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL w - 1"
+        );
+        assert_eq!(game.moves[0].player, Player::White);
+        assert_eq!(game.moves[0].usi_move, "3c3d");
+    }
+
+    #[test]
+    fn test_hand_placement_and_all_remaining() {
+        let game =
+            CsaGame::from_string("P1 *  *  *  *  *  *  *  * -OU\nP+00KI\nP-00AL\n+\n").unwrap();
+        assert!(game.initial_sfen.starts_with("8k/9/9/9/9/9/9/9/9 b G2r2b3g4s4n4l18p"));
+    }
+
+    #[test]
+    fn test_game_record_conversion() {
+        let record = CsaGame::from_string(FLOODGATE_GAME).unwrap().to_game_record().unwrap();
+        assert_eq!(record.moves.len(), 5);
+        assert!(matches!(record.result, GameResult::BlackWin));
+        assert_eq!(record.time_control.initial_time, 300);
+        assert_eq!(record.time_control.increment, 10);
+        assert_eq!(record.date.as_deref(), Some("2024/05/01 12:00:00"));
+    }
+
+    #[test]
+    fn test_parse_all_splits_games() {
+        let content = format!("{}/\n{}", FLOODGATE_GAME, "PI\n+\n+2726FU\n%CHUDAN\n");
+        let games = CsaGame::parse_all(&content).unwrap();
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[1].winner, None);
+    }
+}
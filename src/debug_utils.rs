@@ -128,8 +128,16 @@ fn get_current_time_ms() -> f64 {
 }
 
 /// Enable or disable debug logging
+///
+/// Also moves the structured log between recording trace records and only
+/// info and above.
 pub fn set_debug_enabled(enabled: bool) {
     DEBUG_ENABLED.store(enabled, std::sync::atomic::Ordering::Relaxed);
+    crate::utils::logging::set_level(if enabled {
+        crate::utils::logging::LogLevel::Trace
+    } else {
+        crate::utils::logging::LogLevel::Info
+    });
 }
 
 /// Check if debug logging is enabled
@@ -187,7 +195,7 @@ pub fn end_timing(key: &str, feature: &str) {
     }
 }
 
-/// Log with feature context and timing information
+/// Trace logging into the structured log, with the feature as category
 /// Optimized: checks debug flag first to avoid unnecessary string formatting
 #[cfg(feature = "verbose-debug")]
 #[inline]
@@ -196,17 +204,14 @@ pub fn trace_log(feature: &str, message: &str) {
         return;
     }
 
-    let search_elapsed = get_search_elapsed_ms();
-    let formatted_message = format!("[{}] [{}ms] {}", feature, search_elapsed, message);
-
-    debug_log(&formatted_message);
+    crate::utils::logging::log(crate::utils::logging::LogLevel::Trace, feature, message);
 }
 
 #[cfg(not(feature = "verbose-debug"))]
 #[inline]
 pub fn trace_log(_: &str, _: &str) {}
 
-/// Debug logging for standalone environments
+/// Debug logging into the structured log
 /// Optimized: checks debug flag first to avoid unnecessary string formatting
 #[cfg(feature = "verbose-debug")]
 #[inline]
@@ -215,10 +220,7 @@ pub fn debug_log(message: &str) {
         return;
     }
 
-    let search_elapsed = get_search_elapsed_ms();
-    let formatted_message = format!("[{}ms] {}", search_elapsed, message);
-
-    eprintln!("DEBUG: {}", formatted_message);
+    crate::utils::logging::log(crate::utils::logging::LogLevel::Debug, "DEBUG", message);
 }
 
 #[cfg(not(feature = "verbose-debug"))]
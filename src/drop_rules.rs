@@ -0,0 +1,174 @@
+//! Drop rules
+//!
+//! Where a piece from hand may be dropped:
+//! - only on an empty square;
+//! - a pawn not on a file that already holds an unpromoted pawn of the same
+//!   player (nifu / 二歩); tokins do not count;
+//! - not where the piece could never move: a pawn or lance on the last rank, a
+//!   knight on the last two;
+//! - a pawn not so that it delivers immediate checkmate (uchifuzume / 打ち歩詰め).
+//!
+//! Move generation uses `is_legal_drop`; `check_drop` says which rule a drop breaks.
+
+use crate::bitboards::BitboardBoard;
+use crate::moves::MoveGenerator;
+use crate::types::board::CapturedPieces;
+use crate::types::core::{Piece, PieceType, Player, Position};
+use std::fmt;
+
+/// The rule an illegal drop breaks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DropRuleViolation {
+    /// The target square is occupied
+    Occupied,
+    /// A pawn onto a file with an unpromoted pawn of the same player
+    Nifu,
+    /// The piece could never move from the target square
+    NoLegalMoves,
+    /// A pawn drop delivering immediate checkmate
+    PawnDropMate,
+}
+
+impl fmt::Display for DropRuleViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DropRuleViolation::Occupied => write!(f, "target square is occupied"),
+            DropRuleViolation::Nifu => write!(f, "two unpromoted pawns on one file (nifu)"),
+            DropRuleViolation::NoLegalMoves => write!(f, "piece could never move"),
+            DropRuleViolation::PawnDropMate => write!(f, "pawn drop checkmate (uchifuzume)"),
+        }
+    }
+}
+
+/// Check a drop of `piece_type` by `player` on `pos` against the drop rules
+pub fn check_drop(
+    board: &BitboardBoard,
+    piece_type: PieceType,
+    pos: Position,
+    player: Player,
+) -> Result<(), DropRuleViolation> {
+    if board.is_square_occupied(pos) {
+        return Err(DropRuleViolation::Occupied);
+    }
+    if is_dead_square(piece_type, pos, player) {
+        return Err(DropRuleViolation::NoLegalMoves);
+    }
+    if piece_type == PieceType::Pawn {
+        if has_unpromoted_pawn_on_file(board, pos.col, player) {
+            return Err(DropRuleViolation::Nifu);
+        }
+        if is_pawn_drop_mate(board, pos, player) {
+            return Err(DropRuleViolation::PawnDropMate);
+        }
+    }
+    Ok(())
+}
+
+/// Whether the drop breaks none of the drop rules
+pub fn is_legal_drop(
+    board: &BitboardBoard,
+    piece_type: PieceType,
+    pos: Position,
+    player: Player,
+) -> bool {
+    match check_drop(board, piece_type, pos, player) {
+        Ok(()) => true,
+        Err(violation) => {
+            crate::utils::telemetry::debug_log(&format!(
+                "[DROP_RULES] Illegal {:?} drop at {}{}: {}",
+                piece_type,
+                9 - pos.col,
+                (b'a' + pos.row) as char,
+                violation
+            ));
+            false
+        }
+    }
+}
+
+/// Whether `player` has an unpromoted pawn on file `col`
+pub fn has_unpromoted_pawn_on_file(board: &BitboardBoard, col: u8, player: Player) -> bool {
+    (0..9).any(|row| {
+        board.get_piece(Position::new(row, col)).map_or(false, |piece| {
+            piece.piece_type == PieceType::Pawn && piece.player == player
+        })
+    })
+}
+
+/// Whether an unpromoted piece on `pos` would have no legal moves (pawn or lance on
+/// the last rank, knight on the last two). Such drops are illegal and moves onto
+/// these squares must promote.
+pub fn is_dead_square(piece_type: PieceType, pos: Position, player: Player) -> bool {
+    let last_rank = if player == Player::Black { 0 } else { 8 };
+    let second_last_rank = if player == Player::Black { 1 } else { 7 };
+    match piece_type {
+        PieceType::Pawn | PieceType::Lance => pos.row == last_rank,
+        PieceType::Knight => pos.row == last_rank || pos.row == second_last_rank,
+        _ => false,
+    }
+}
+
+/// Check if dropping a pawn at the given position gives immediate checkmate (Uchifuzume)
+///
+/// A pawn check is adjacent to the king, so it cannot be blocked and the defender's
+/// hand is irrelevant: it is mate exactly when no king move or capture of the pawn
+/// (including by pinned pieces, which legal move generation rules out) is legal.
+pub fn is_pawn_drop_mate(board: &BitboardBoard, drop_pos: Position, player: Player) -> bool {
+    // Find opponent's king
+    let opponent = player.opposite();
+    let Some(king_pos) = board.find_king_position(opponent) else {
+        return false; // No king, can't be checkmate
+    };
+
+    // The pawn only gives check from directly in front of the king
+    let pawn_target_row = match player {
+        Player::Black => drop_pos.row.checked_sub(1),
+        Player::White => Some(drop_pos.row + 1),
+    };
+    if pawn_target_row != Some(king_pos.row) || king_pos.col != drop_pos.col {
+        return false; // Not even giving check, so not checkmate
+    }
+
+    let mut temp_board = board.clone();
+    temp_board.place_piece(Piece::new(PieceType::Pawn, player), drop_pos);
+
+    // With an empty hand the defender generates no drops, so this cannot recurse
+    let no_hand = CapturedPieces::new();
+    MoveGenerator::new()
+        .generate_legal_moves(&temp_board, opponent, &no_hand)
+        .is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_drop_reports_the_broken_rule() {
+        let (board, _, _) = BitboardBoard::from_fen("4k4/9/9/9/5+P3/9/9/4P4/4K4 b P 1").unwrap();
+
+        // 5h holds a sente pawn: nifu on file 5
+        assert_eq!(
+            check_drop(&board, PieceType::Pawn, Position::new(3, 4), Player::Black),
+            Err(DropRuleViolation::Nifu)
+        );
+        // A tokin is not a pawn for nifu
+        assert_eq!(
+            check_drop(&board, PieceType::Pawn, Position::new(3, 5), Player::Black),
+            Ok(())
+        );
+        assert_eq!(
+            check_drop(&board, PieceType::Pawn, Position::new(4, 5), Player::Black),
+            Err(DropRuleViolation::Occupied)
+        );
+        assert_eq!(
+            check_drop(&board, PieceType::Knight, Position::new(1, 0), Player::Black),
+            Err(DropRuleViolation::NoLegalMoves)
+        );
+        // Gote has no pawn on file 5
+        assert_eq!(
+            check_drop(&board, PieceType::Pawn, Position::new(3, 4), Player::White),
+            Ok(())
+        );
+    }
+}
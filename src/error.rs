@@ -45,7 +45,25 @@
 //! - [`TranspositionTableError`]: Transposition table errors (invalid size, probe failure, etc.)
 //! - [`MoveGenerationError`]: Move generation errors
 //! - [`ConfigurationError`]: Configuration validation and loading errors
+//!
+//! # Public API errors and recovery
+//!
+//! [`ShogiError`] is what the engine's public APIs (`ShogiEngine`, the USI
+//! handler) return. It has four categories, each with its own recovery policy:
+//! - `Parse`: malformed input (SFEN, move text, book data). The command is
+//!   rejected and the engine state is left as it was.
+//! - `Rule`: well-formed input the rules do not allow (moving a piece that
+//!   is not there, an illegal move). Rejected like `Parse`.
+//! - `Search`: the search could not produce a result (busy, no move found).
+//!   Nothing is changed; the caller may retry or fall back.
+//! - `Resource`: a file, lock or memory could not be had. The previous
+//!   resource (opening book, weights, hash table) stays in use; retrying may help.
+//!
+//! In USI output an error is one `info string error <category>: <message>`
+//! line, which [`ShogiError::from_usi_info`] reads back for GUIs.
 
+use crate::opening_book::OpeningBookError;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// Root error type for all engine operations
@@ -341,3 +359,192 @@ impl ConfigurationError {
 /// Convenience type alias for Result with ShogiEngineError
 pub type Result<T, E = ShogiEngineError> = std::result::Result<T, E>;
 
+/// Category of a [`ShogiError`], deciding how a caller recovers from it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    Parse,
+    Rule,
+    Search,
+    Resource,
+}
+
+impl ErrorCategory {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Parse => "parse",
+            Self::Rule => "rule",
+            Self::Search => "search",
+            Self::Resource => "resource",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "parse" => Some(Self::Parse),
+            "rule" => Some(Self::Rule),
+            "search" => Some(Self::Search),
+            "resource" => Some(Self::Resource),
+            _ => None,
+        }
+    }
+}
+
+/// Error returned by the engine's public APIs; see the module docs for the
+/// recovery policy of each category
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "category", rename_all = "snake_case")]
+pub enum ShogiError {
+    /// Malformed input
+    #[error("{message}")]
+    Parse { message: String },
+
+    /// Input the rules of shogi do not allow
+    #[error("{message}")]
+    Rule { message: String },
+
+    /// The search could not produce a result
+    #[error("{message}")]
+    Search { message: String },
+
+    /// A file, lock or memory could not be had
+    #[error("{message}")]
+    Resource { message: String },
+}
+
+impl ShogiError {
+    pub fn parse<S: Into<String>>(message: S) -> Self {
+        Self::Parse { message: message.into() }
+    }
+
+    pub fn rule<S: Into<String>>(message: S) -> Self {
+        Self::Rule { message: message.into() }
+    }
+
+    pub fn search<S: Into<String>>(message: S) -> Self {
+        Self::Search { message: message.into() }
+    }
+
+    pub fn resource<S: Into<String>>(message: S) -> Self {
+        Self::Resource { message: message.into() }
+    }
+
+    pub fn new<S: Into<String>>(category: ErrorCategory, message: S) -> Self {
+        match category {
+            ErrorCategory::Parse => Self::parse(message),
+            ErrorCategory::Rule => Self::rule(message),
+            ErrorCategory::Search => Self::search(message),
+            ErrorCategory::Resource => Self::resource(message),
+        }
+    }
+
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::Parse { .. } => ErrorCategory::Parse,
+            Self::Rule { .. } => ErrorCategory::Rule,
+            Self::Search { .. } => ErrorCategory::Search,
+            Self::Resource { .. } => ErrorCategory::Resource,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Parse { message }
+            | Self::Rule { message }
+            | Self::Search { message }
+            | Self::Resource { message } => message,
+        }
+    }
+
+    /// Whether the same call may succeed when tried again; bad input never will
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Search { .. } | Self::Resource { .. })
+    }
+
+    /// The error as a USI `info string error <category>: <message>` line
+    pub fn to_usi_info(&self) -> String {
+        format!("info string error {}: {}", self.category().as_str(), self.message())
+    }
+
+    /// Read back a line written by `to_usi_info`; `None` for other lines
+    pub fn from_usi_info(line: &str) -> Option<Self> {
+        let rest = line.trim().strip_prefix("info string error ")?;
+        let (category, message) = rest.split_once(": ")?;
+        Some(Self::new(ErrorCategory::parse(category)?, message))
+    }
+}
+
+impl From<ShogiEngineError> for ShogiError {
+    fn from(error: ShogiEngineError) -> Self {
+        let message = error.to_string();
+        match error {
+            ShogiEngineError::Search(_) | ShogiEngineError::MoveGeneration(_) => {
+                Self::search(message)
+            }
+            ShogiEngineError::Evaluation(EvaluationError::InvalidPosition { .. }) => {
+                Self::rule(message)
+            }
+            ShogiEngineError::Evaluation(_) | ShogiEngineError::TranspositionTable(_) => {
+                Self::resource(message)
+            }
+            ShogiEngineError::Configuration(ConfigurationError::FileNotFound { .. }) => {
+                Self::resource(message)
+            }
+            ShogiEngineError::Configuration(_) => Self::parse(message),
+        }
+    }
+}
+
+/// For callers that still report errors as text
+impl From<ShogiError> for String {
+    fn from(error: ShogiError) -> Self {
+        error.to_string()
+    }
+}
+
+impl From<std::io::Error> for ShogiError {
+    fn from(error: std::io::Error) -> Self {
+        Self::resource(error.to_string())
+    }
+}
+
+impl From<OpeningBookError> for ShogiError {
+    fn from(error: OpeningBookError) -> Self {
+        match error {
+            OpeningBookError::IoError(message) | OpeningBookError::HashCollision(message) => {
+                Self::resource(format!("Failed to load opening book: {}", message))
+            }
+            OpeningBookError::InvalidFen(message)
+            | OpeningBookError::InvalidMove(message)
+            | OpeningBookError::BinaryFormatError(message)
+            | OpeningBookError::JsonParseError(message) => {
+                Self::parse(format!("Failed to load opening book: {}", message))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shogi_error_round_trips_usi_info() {
+        let error = ShogiError::rule("No piece at source square in move '5e5d'");
+        let line = error.to_usi_info();
+        assert_eq!(line, "info string error rule: No piece at source square in move '5e5d'");
+        assert_eq!(ShogiError::from_usi_info(&line), Some(error.clone()));
+        assert!(!error.is_retryable());
+        assert!(ShogiError::from_usi_info("info string error Invalid value").is_none());
+
+        let json = serde_json::to_value(ShogiError::resource("engine is busy")).unwrap();
+        assert_eq!(json, serde_json::json!({"category": "resource", "message": "engine is busy"}));
+
+        let error: ShogiError = OpeningBookError::JsonParseError("eof".to_string()).into();
+        assert_eq!(error.category(), ErrorCategory::Parse);
+        let error = ShogiError::from(ShogiEngineError::from(ConfigurationError::file_not_found(
+            "book.bin",
+        )));
+        assert_eq!(error.category(), ErrorCategory::Resource);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::bitboards::*;
 use crate::evaluation::piece_square_tables::PieceSquareTables;
+use crate::handicap::Handicap;
 use crate::moves::MoveGenerator;
 use crate::types::board::CapturedPieces;
 use crate::types::core::{PieceType, Player, Position};
@@ -29,12 +30,15 @@ use crate::weights::{WeightError, WeightManager};
 // Advanced evaluation modules
 pub mod advanced_interpolation;
 pub mod attacks;
+pub mod breakdown;
 pub mod castle_fixtures;
 pub mod castle_geometry;
 pub mod castles;
+pub mod complexity;
 pub mod config;
 pub mod endgame_patterns;
 pub mod integration;
+pub mod king_danger;
 pub mod king_safety;
 pub mod material;
 pub mod material_value_loader;
@@ -60,6 +64,7 @@ pub mod pattern_search_integration;
 pub mod positional_fixtures;
 pub mod positional_patterns;
 pub mod tactical_patterns;
+pub mod threats;
 
 // Newly extracted modules (Task 1.0: File Modularization)
 pub mod component_coordinator;
@@ -77,6 +82,7 @@ pub mod aggregators {
     pub use crate::evaluation::aggregators::*;
 }
 use advanced_integration::AdvancedIntegration;
+use breakdown::EvaluationBreakdown;
 use eval_cache::{EvaluationCache, MultiLevelCache};
 use integration::IntegratedEvaluator;
 use king_safety::KingSafetyEvaluator;
@@ -106,6 +112,8 @@ pub struct PositionEvaluator {
     multi_level_cache: Option<MultiLevelCache>,
     // Whether to use cache
     use_cache: bool,
+    // Handicap setup; White's missing material is offset in the score
+    handicap: Handicap,
 }
 
 impl PositionEvaluator {
@@ -122,6 +130,7 @@ impl PositionEvaluator {
             eval_cache: None,
             multi_level_cache: None,
             use_cache: false,
+            handicap: Handicap::Even,
         }
     }
 
@@ -139,6 +148,7 @@ impl PositionEvaluator {
             eval_cache: None,
             multi_level_cache: None,
             use_cache: false,
+            handicap: Handicap::Even,
         }
     }
 
@@ -171,6 +181,27 @@ impl PositionEvaluator {
         self.king_safety_evaluator = KingSafetyEvaluator::with_config(config);
     }
 
+    /// Set the handicap setup of the current game
+    pub fn set_handicap(&mut self, handicap: Handicap) {
+        self.handicap = handicap;
+    }
+
+    /// Get the handicap setup of the current game
+    pub fn get_handicap(&self) -> Handicap {
+        self.handicap
+    }
+
+    /// Score adjustment that offsets the material White gave up in a handicap game,
+    /// so the handicap itself isn't judged as a lost position
+    fn handicap_bias(&self, player: Player) -> i32 {
+        let deficit = self.handicap.material_deficit();
+        if player == Player::White {
+            deficit
+        } else {
+            -deficit
+        }
+    }
+
     /// Load tuned weights from a file
     pub fn load_tuned_weights<P: AsRef<std::path::Path>>(
         &mut self,
@@ -181,6 +212,15 @@ impl PositionEvaluator {
         Ok(())
     }
 
+    /// Re-read the tuned weights file after it was edited; cached scores from
+    /// the old weights are dropped
+    pub fn reload_tuned_weights(&mut self) -> Result<(), WeightError> {
+        self.weight_manager.reload_weights()?;
+        self.use_tuned_weights = self.weight_manager.is_enabled();
+        self.clear_eval_cache();
+        Ok(())
+    }
+
     /// Enable or disable tuned weights
     pub fn set_use_tuned_weights(&mut self, enabled: bool) {
         if enabled && self.weight_manager.has_weights() {
@@ -466,11 +506,11 @@ impl PositionEvaluator {
         if self.use_cache {
             if let Some(ref cache) = self.eval_cache {
                 if let Some(score) = cache.probe(board, player, captured_pieces) {
-                    return score;
+                    return score + self.handicap_bias(player);
                 }
             } else if let Some(ref ml_cache) = self.multi_level_cache {
                 if let Some(score) = ml_cache.probe(board, player, captured_pieces) {
-                    return score;
+                    return score + self.handicap_bias(player);
                 }
             }
         }
@@ -514,7 +554,82 @@ impl PositionEvaluator {
             }
         }
 
-        score
+        score + self.handicap_bias(player)
+    }
+
+    /// Break the static evaluation of a position down into its terms
+    pub fn explain_evaluation(
+        &mut self,
+        board: &BitboardBoard,
+        player: Player,
+        captured_pieces: &CapturedPieces,
+    ) -> EvaluationBreakdown {
+        if self.use_integrated_eval {
+            if let Some(ref mut integrated) = self.integrated_evaluator {
+                let mut result = integrated.explain(board, player, captured_pieces);
+                let bias = self.handicap_bias(player);
+                if bias != 0 {
+                    result
+                        .component_scores
+                        .insert("handicap".to_string(), TaperedScore::new(bias));
+                }
+                return EvaluationBreakdown::from_components(
+                    player,
+                    result.phase,
+                    result.score + bias,
+                    &result.component_scores,
+                );
+            }
+        }
+
+        // Legacy evaluation: material and piece-square values are a single term
+        let mut components = std::collections::HashMap::new();
+        components.insert("tempo".to_string(), TaperedScore::new(10));
+        components.insert(
+            "material".to_string(),
+            self.evaluate_material_and_position(board, player),
+        );
+        components.insert(
+            "pawn_structure".to_string(),
+            self.evaluate_pawn_structure(board, player),
+        );
+        components.insert(
+            "king_safety".to_string(),
+            self.evaluate_king_safety(board, player),
+        );
+        components.insert(
+            "mobility".to_string(),
+            self.evaluate_mobility(board, player, captured_pieces),
+        );
+        components.insert(
+            "coordination".to_string(),
+            self.evaluate_piece_coordination(board, player),
+        );
+        components.insert(
+            "center_control".to_string(),
+            self.evaluate_center_control(board, player),
+        );
+        components.insert(
+            "development".to_string(),
+            self.evaluate_development(board, player),
+        );
+
+        let phase = self.calculate_game_phase(board, captured_pieces);
+        let score = self.evaluate_with_context_internal(
+            board,
+            player,
+            captured_pieces,
+            0,
+            false,
+            false,
+            false,
+            false,
+        );
+        let bias = self.handicap_bias(player);
+        if bias != 0 {
+            components.insert("handicap".to_string(), TaperedScore::new(bias));
+        }
+        EvaluationBreakdown::from_components(player, phase, score + bias, &components)
     }
 
     /// Evaluate using tuned weights if available, otherwise use traditional evaluation
@@ -559,11 +674,11 @@ impl PositionEvaluator {
         if self.use_cache && depth > 0 {
             if let Some(ref cache) = self.eval_cache {
                 if let Some(score) = cache.probe(board, player, captured_pieces) {
-                    return score;
+                    return score + self.handicap_bias(player);
                 }
             } else if let Some(ref ml_cache) = self.multi_level_cache {
                 if let Some(score) = ml_cache.probe(board, player, captured_pieces) {
-                    return score;
+                    return score + self.handicap_bias(player);
                 }
             }
         }
@@ -589,7 +704,7 @@ impl PositionEvaluator {
             }
         }
 
-        score
+        score + self.handicap_bias(player)
     }
 
     /// Internal evaluation with context (extracted for cache integration)
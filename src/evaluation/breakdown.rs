@@ -0,0 +1,137 @@
+//! Evaluation Breakdown Module
+//!
+//! Per-term breakdown of a static evaluation, used by the GUI's "why this score"
+//! panel and as a sanity check while tuning. Each term is reported both as its
+//! middlegame/endgame pair and interpolated at the position's game phase.
+
+use crate::types::core::Player;
+use crate::types::evaluation::TaperedScore;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Display order of the known evaluation terms; unknown terms follow alphabetically
+pub const TERM_ORDER: &[&str] = &[
+    "material",
+    "hand",
+    "handicap",
+    "piece_square_tables",
+    "king_safety",
+    "castle_patterns",
+    "mobility",
+    "pawn_structure",
+    "center_control",
+    "development",
+    "coordination",
+    "opening_principles",
+    "endgame_patterns",
+    "tactical_patterns",
+    "positional_patterns",
+    "tempo",
+];
+
+/// A single evaluation term
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvaluationTerm {
+    pub name: String,
+    pub mg: i32,
+    pub eg: i32,
+    /// Term interpolated at the position's game phase
+    pub score: i32,
+}
+
+/// Static evaluation split into its terms, from `player`'s perspective
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvaluationBreakdown {
+    pub player: Player,
+    /// Game phase used for interpolation (0 = endgame)
+    pub phase: i32,
+    /// Final evaluation. Terms may not sum to it exactly because of rounding and
+    /// phase-transition smoothing.
+    pub score: i32,
+    pub terms: Vec<EvaluationTerm>,
+}
+
+impl EvaluationBreakdown {
+    /// Build a breakdown from named tapered component scores
+    pub fn from_components(
+        player: Player,
+        phase: i32,
+        score: i32,
+        components: &HashMap<String, TaperedScore>,
+    ) -> Self {
+        let mut names: Vec<&String> = components.keys().collect();
+        names.sort_by_key(|name| {
+            let rank = TERM_ORDER
+                .iter()
+                .position(|known| *known == name.as_str())
+                .unwrap_or(TERM_ORDER.len());
+            (rank, name.as_str())
+        });
+
+        let terms = names
+            .into_iter()
+            .map(|name| {
+                let value = components[name];
+                EvaluationTerm {
+                    name: name.clone(),
+                    mg: value.mg,
+                    eg: value.eg,
+                    score: value.interpolate(phase),
+                }
+            })
+            .collect();
+
+        Self { player, phase, score, terms }
+    }
+
+    /// Look up a term by name
+    pub fn term(&self, name: &str) -> Option<&EvaluationTerm> {
+        self.terms.iter().find(|term| term.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitboards::BitboardBoard;
+    use crate::evaluation::PositionEvaluator;
+    use crate::types::board::CapturedPieces;
+
+    #[test]
+    fn test_terms_follow_display_order() {
+        let mut components = HashMap::new();
+        components.insert("mobility".to_string(), TaperedScore::new_tapered(10, 30));
+        components.insert("custom".to_string(), TaperedScore::new(5));
+        components.insert("material".to_string(), TaperedScore::new(100));
+
+        let breakdown = EvaluationBreakdown::from_components(Player::Black, 0, 135, &components);
+        let names: Vec<&str> = breakdown.terms.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["material", "mobility", "custom"]);
+        // Phase 0 is the endgame, so the endgame value applies
+        assert_eq!(breakdown.term("mobility").unwrap().score, 30);
+    }
+
+    #[test]
+    fn test_explain_starting_position() {
+        let mut evaluator = PositionEvaluator::new();
+        let board = BitboardBoard::new();
+        let captured = CapturedPieces::new();
+
+        let breakdown = evaluator.explain_evaluation(&board, Player::Black, &captured);
+        assert_eq!(breakdown.player, Player::Black);
+        assert_eq!(breakdown.term("material").unwrap().score, 0);
+        assert_eq!(breakdown.term("hand").unwrap().score, 0);
+        assert!(breakdown.term("king_safety").is_some());
+    }
+
+    #[test]
+    fn test_explain_separates_hand_material() {
+        let (board, player, captured) =
+            BitboardBoard::from_fen("4k4/9/9/9/9/9/9/9/4K4 b G 1").unwrap();
+        let mut evaluator = PositionEvaluator::new();
+
+        let breakdown = evaluator.explain_evaluation(&board, player, &captured);
+        assert!(breakdown.term("hand").unwrap().score > 0);
+        assert_eq!(breakdown.term("material").unwrap().score, 0);
+    }
+}
@@ -0,0 +1,121 @@
+//! Position Complexity
+//!
+//! How much is going on in a position, from 0 (quiet) to 1 (sharp), read off
+//! the attack maps: pieces standing under attack, pieces attacked and not
+//! defended, pieces in hand that can be dropped almost anywhere, and attacks on
+//! the squares around each king. Positions like these are where a player short
+//! of time goes wrong, so clock-aware play steers towards them when the
+//! opponent is short and away from them when the engine is.
+
+use crate::bitboards::BitboardBoard;
+use crate::types::board::CapturedPieces;
+use crate::types::core::{Move, PieceType, Player, Position};
+use serde::{Deserialize, Serialize};
+
+/// Weighted tactical count at which the complexity reaches one half
+const COMPLEXITY_SCALE: f64 = 12.0;
+
+/// What makes a position tactically dense, counted for both sides together
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct TacticalDensity {
+    /// Pieces attacked by the opponent, kings excluded
+    pub attacked_pieces: u32,
+    /// Attacked pieces with no defender
+    pub hanging_pieces: u32,
+    pub hand_pieces: u32,
+    /// Opponent attacks on the squares next to each king
+    pub king_zone_attacks: u32,
+}
+
+impl TacticalDensity {
+    pub fn of(board: &BitboardBoard, captured_pieces: &CapturedPieces) -> Self {
+        let maps = board.attack_maps();
+        let mut density = Self {
+            hand_pieces: (captured_pieces.black.len() + captured_pieces.white.len()) as u32,
+            ..Self::default()
+        };
+        for (pos, piece) in board.iter_pieces() {
+            let opponent = piece.player.opposite();
+            if piece.piece_type == PieceType::King {
+                for row in pos.row.saturating_sub(1)..=(pos.row + 1).min(8) {
+                    for col in pos.col.saturating_sub(1)..=(pos.col + 1).min(8) {
+                        let square = Position::new(row, col);
+                        if square != pos {
+                            density.king_zone_attacks +=
+                                u32::from(maps.attack_count(opponent, square));
+                        }
+                    }
+                }
+            } else if maps.is_attacked(pos, opponent) {
+                density.attacked_pieces += 1;
+                if !maps.is_attacked(pos, piece.player) {
+                    density.hanging_pieces += 1;
+                }
+            }
+        }
+        density
+    }
+
+    /// The counts folded into a complexity from 0 to 1
+    pub fn complexity(&self) -> f64 {
+        let weighted = f64::from(self.attacked_pieces)
+            + 2.0 * f64::from(self.hanging_pieces)
+            + 0.5 * f64::from(self.hand_pieces)
+            + 0.5 * f64::from(self.king_zone_attacks);
+        weighted / (weighted + COMPLEXITY_SCALE)
+    }
+}
+
+/// Complexity of a position from 0 (quiet) to 1 (sharp); the same for either
+/// side to move
+pub fn position_complexity(board: &BitboardBoard, captured_pieces: &CapturedPieces) -> f64 {
+    TacticalDensity::of(board, captured_pieces).complexity()
+}
+
+/// Complexity after `player` plays `mv`
+pub fn complexity_after(
+    board: &BitboardBoard,
+    captured_pieces: &CapturedPieces,
+    player: Player,
+    mv: &Move,
+) -> f64 {
+    let mut next_board = board.clone();
+    let mut next_captured = captured_pieces.clone();
+    if mv.from.is_none() {
+        next_captured.remove_piece(mv.piece_type, player);
+    }
+    if let Some(captured) = next_board.make_move(mv) {
+        next_captured.add_piece(captured.piece_type, player);
+    }
+    position_complexity(&next_board, &next_captured)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contact_raises_complexity() {
+        let (quiet, _, captured) = BitboardBoard::from_fen(
+            "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+        )
+        .unwrap();
+        assert_eq!(TacticalDensity::of(&quiet, &captured), TacticalDensity::default());
+        assert_eq!(position_complexity(&quiet, &captured), 0.0);
+
+        // Facing pawns, each attacked and undefended, and a pawn in each hand
+        let (sharp, _, captured) =
+            BitboardBoard::from_fen("4k4/9/4p4/4P4/9/9/9/9/4K4 b Pp 1").unwrap();
+        let density = TacticalDensity::of(&sharp, &captured);
+        assert_eq!(
+            density,
+            TacticalDensity {
+                attacked_pieces: 2,
+                hanging_pieces: 2,
+                hand_pieces: 2,
+                king_zone_attacks: 0
+            }
+        );
+        assert!((density.complexity() - 7.0 / 19.0).abs() < 1e-9);
+    }
+}
@@ -205,6 +205,7 @@ use crate::evaluation::{
     statistics::{EvaluationStatistics, EvaluationTelemetry, PieceSquareTelemetry},
     tactical_patterns::{TacticalConfig, TacticalPatternRecognizer},
     tapered_eval::TaperedEvaluation,
+    threats::{evaluate_threats, ThreatConfig},
 };
 use crate::tuning::OptimizationMethod;
 use crate::types::board::CapturedPieces;
@@ -299,6 +300,8 @@ pub struct IntegratedEvaluator {
     eval_cache: HashMap<u64, CachedEvaluation>,
     /// Phase history for phase-aware validation (Task 20.0 - Task 5.14)
     phase_history: Vec<i32>,
+    /// Record every weighted term in `component_scores` (set by `explain()`)
+    record_components: bool,
 }
 
 impl IntegratedEvaluator {
@@ -370,6 +373,7 @@ impl IntegratedEvaluator {
             phase_cache: HashMap::new(),
             eval_cache: HashMap::new(),
             phase_history: Vec::new(), // Task 20.0 - Task 5.14
+            record_components: false,
         };
 
         evaluator
@@ -460,6 +464,28 @@ impl IntegratedEvaluator {
         result
     }
 
+    /// Evaluate with every weighted term recorded in `component_scores`, bypassing the
+    /// evaluation cache. Hand material is reported separately as `"hand"`.
+    pub fn explain(
+        &mut self,
+        board: &BitboardBoard,
+        player: Player,
+        captured_pieces: &CapturedPieces,
+    ) -> EvaluationResult {
+        self.record_components = true;
+        let mut result = self.evaluate_standard(board, player, captured_pieces, None);
+        self.record_components = false;
+
+        if self.config.components.material {
+            let hand_score = self.material_eval.evaluate_hand(captured_pieces, player);
+            if let Some(material_score) = result.component_scores.get_mut("material") {
+                *material_score -= hand_score;
+            }
+            result.component_scores.insert("hand".to_string(), hand_score);
+        }
+        result
+    }
+
     /// Update statistics from an evaluation result
     ///
     /// This method should be called after `evaluate()` if statistics tracking is enabled.
@@ -592,6 +618,9 @@ impl IntegratedEvaluator {
 
             total += pst_score;
             pst_telemetry = Some(telemetry);
+            if self.record_components {
+                component_scores.insert("piece_square_tables".to_string(), pst_score);
+            }
             // Track contribution for telemetry
             if stats_enabled {
                 let pst_interp = pst_score.interpolate(phase);
@@ -646,6 +675,9 @@ impl IntegratedEvaluator {
             let king_safety_weighted = king_safety_score * weights.king_safety_weight;
             total += king_safety_weighted;
             pf_total += king_safety_weighted;
+            if self.record_components {
+                component_scores.insert("king_safety".to_string(), king_safety_weighted);
+            }
 
             // Pawn structure
             let pawn_score = self.position_features.evaluate_pawn_structure(
@@ -667,6 +699,9 @@ impl IntegratedEvaluator {
             let pawn_weighted = pawn_score * weights.pawn_structure_weight;
             total += pawn_weighted;
             pf_total += pawn_weighted;
+            if self.record_components {
+                component_scores.insert("pawn_structure".to_string(), pawn_weighted);
+            }
 
             // Mobility
             let mobility_score =
@@ -683,6 +718,9 @@ impl IntegratedEvaluator {
             let mobility_weighted = mobility_score * weights.mobility_weight;
             total += mobility_weighted;
             pf_total += mobility_weighted;
+            if self.record_components {
+                component_scores.insert("mobility".to_string(), mobility_weighted);
+            }
 
             // Center control (Task 20.0 - Task 1.0)
             // Skip center control in position_features if positional_patterns takes precedence
@@ -704,6 +742,9 @@ impl IntegratedEvaluator {
             let center_weighted = center_score * weights.center_control_weight;
             total += center_weighted;
             pf_total += center_weighted;
+            if self.record_components {
+                component_scores.insert("center_control".to_string(), center_weighted);
+            }
 
             // Development (Task 20.0 - Task 1.0)
             // Skip development in position_features if opening_principles is enabled in opening phase
@@ -721,6 +762,9 @@ impl IntegratedEvaluator {
             let dev_weighted = dev_score * weights.development_weight;
             total += dev_weighted;
             pf_total += dev_weighted;
+            if self.record_components {
+                component_scores.insert("development".to_string(), dev_weighted);
+            }
 
             if stats_enabled && self.config.collect_position_feature_stats {
                 position_feature_stats_snapshot = Some(self.position_features.stats().clone());
@@ -763,6 +807,9 @@ impl IntegratedEvaluator {
             opening_score = opening_score * coordination.opening_fade_factor;
 
             total += opening_score;
+            if self.record_components {
+                component_scores.insert("opening_principles".to_string(), opening_score);
+            }
         }
 
         // Endgame patterns (if in endgame)
@@ -797,6 +844,9 @@ impl IntegratedEvaluator {
                 }
 
                 total += endgame_score;
+                if self.record_components {
+                    component_scores.insert("endgame_patterns".to_string(), endgame_score);
+                }
             }
         }
 
@@ -828,6 +878,19 @@ impl IntegratedEvaluator {
             }
 
             total += tactical_score * weights.tactical_weight;
+            if self.record_components {
+                component_scores.insert(
+                    "tactical_patterns".to_string(),
+                    tactical_score * weights.tactical_weight,
+                );
+            }
+
+            let threat_score = evaluate_threats(board, player, &self.config.threats);
+            total += threat_score * weights.tactical_weight;
+            if self.record_components {
+                component_scores
+                    .insert("threats".to_string(), threat_score * weights.tactical_weight);
+            }
             // Track contribution for telemetry
             if stats_enabled {
                 let tactical_interp =
@@ -889,6 +952,12 @@ impl IntegratedEvaluator {
             }
 
             total += positional_score * weights.positional_weight;
+            if self.record_components {
+                component_scores.insert(
+                    "positional_patterns".to_string(),
+                    positional_score * weights.positional_weight,
+                );
+            }
             // Track contribution for telemetry
             if stats_enabled {
                 let positional_interp =
@@ -936,6 +1005,10 @@ impl IntegratedEvaluator {
             }
 
             total += castle_score * weights.castle_weight;
+            if self.record_components {
+                component_scores
+                    .insert("castle_patterns".to_string(), castle_score * weights.castle_weight);
+            }
             // Track contribution for telemetry
             if stats_enabled {
                 let castle_interp =
@@ -1333,6 +1406,8 @@ pub struct IntegratedEvaluationConfig {
     pub position_features: PositionFeatureConfig,
     /// Tactical pattern configuration
     pub tactical: TacticalConfig,
+    /// Hanging piece, fork and skewer scoring; part of the tactical component
+    pub threats: ThreatConfig,
     /// Evaluation weights for combining features
     pub weights: EvaluationWeights,
     /// Enable phase-dependent weight scaling (default: false for backward compatibility)
@@ -1374,6 +1449,7 @@ impl Default for IntegratedEvaluationConfig {
             pst: PieceSquareTableConfig::default(),
             position_features: PositionFeatureConfig::default(),
             tactical: TacticalConfig::default(),
+            threats: ThreatConfig::default(),
             weights: EvaluationWeights::default(),
             enable_phase_dependent_weights: false,
             weight_contribution_threshold: 1000.0,
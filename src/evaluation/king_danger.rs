@@ -0,0 +1,304 @@
+//! King Danger Module
+//!
+//! The endgame "mate meter": how close each king is to being mated, as a 0-100
+//! level for the GUI. It is built from an attack summary of the king's
+//! neighbourhood (enemy pieces bearing on it, escape squares, defenders, pieces
+//! in the attacker's hand) and a short checks-only mate search. A mate found
+//! within `MATE_THREAT_PLY` plies pins the level near the top of the scale;
+//! otherwise the summary decides and the level stays below `MAX_HEURISTIC_LEVEL`.
+//!
+//! Cheap enough to recompute whenever the analysed position changes.
+
+use crate::bitboards::BitboardBoard;
+use crate::search::mate_search::{MateSearchResult, MateSearcher};
+use crate::types::board::CapturedPieces;
+use crate::types::core::{PieceType, Player, Position};
+use serde::{Deserialize, Serialize};
+
+/// Longest mate the meter looks for: mate in two attacker moves
+pub const MATE_THREAT_PLY: u8 = 3;
+
+/// Time for each side's mate search when the caller does not say
+pub const DEFAULT_MATE_THREAT_TIME_MS: u32 = 100;
+
+/// Level when the attacker is to move and has a forced mate
+pub const MATE_AVAILABLE_LEVEL: u8 = 100;
+
+/// Level when the attacker would have a forced mate if it were their move
+pub const MATE_THREAT_LEVEL: u8 = 90;
+
+/// Highest level the attack summary alone can give
+pub const MAX_HEURISTIC_LEVEL: u8 = 80;
+
+const ATTACKER_WEIGHT: i32 = 12;
+const ATTACKED_SQUARE_WEIGHT: i32 = 4;
+const HAND_PIECE_WEIGHT: i32 = 3;
+const MAX_HAND_BONUS: i32 = 15;
+const IN_CHECK_BONUS: i32 = 15;
+const ESCAPE_SQUARE_WEIGHT: i32 = 6;
+const DEFENDER_WEIGHT: i32 = 5;
+
+/// What bears on one king's neighbourhood (the king square and the squares next to it)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KingAttackSummary {
+    /// King square; `None` when the player has no king on the board
+    pub king: Option<String>,
+    pub in_check: bool,
+    /// Enemy pieces attacking at least one square of the neighbourhood
+    pub attackers: u8,
+    /// Neighbourhood squares attacked by the enemy
+    pub attacked_squares: u8,
+    /// Squares next to the king it could step to: not own-occupied, not attacked
+    pub escape_squares: u8,
+    /// Own pieces other than the king covering a square next to it
+    pub defenders: u8,
+    /// Pieces in the enemy's hand
+    pub attacker_hand_pieces: u8,
+}
+
+impl KingAttackSummary {
+    /// Summarize the attack on `player`'s king
+    pub fn compute(
+        board: &BitboardBoard,
+        captured_pieces: &CapturedPieces,
+        player: Player,
+    ) -> Self {
+        let enemy = player.opposite();
+        let enemy_hand = match enemy {
+            Player::Black => captured_pieces.black.len(),
+            Player::White => captured_pieces.white.len(),
+        };
+        let Some(king) = board.find_king_position(player) else {
+            return Self {
+                attacker_hand_pieces: saturate(enemy_hand),
+                ..Self::default()
+            };
+        };
+        let in_zone = |pos: Position| {
+            pos.row.abs_diff(king.row) <= 1 && pos.col.abs_diff(king.col) <= 1
+        };
+
+        let mut attacked = [[false; 9]; 9];
+        let mut attackers = 0usize;
+        let mut defenders = 0usize;
+        for row in 0..9 {
+            for col in 0..9 {
+                let pos = Position::new(row, col);
+                let Some(piece) = board.get_piece(pos) else {
+                    continue;
+                };
+                if piece.player == player && piece.piece_type == PieceType::King {
+                    continue;
+                }
+                let attacks = board.attacks_from(pos, piece.piece_type, piece.player);
+                let mut bears_on_zone = false;
+                for target in board.iter_attack_targets(attacks).filter(|target| in_zone(*target)) {
+                    if piece.player == enemy {
+                        attacked[target.row as usize][target.col as usize] = true;
+                        bears_on_zone = true;
+                    } else if target != king {
+                        bears_on_zone = true;
+                    }
+                }
+                if bears_on_zone {
+                    if piece.player == enemy {
+                        attackers += 1;
+                    } else {
+                        defenders += 1;
+                    }
+                }
+            }
+        }
+
+        let mut attacked_squares = 0usize;
+        let mut escape_squares = 0usize;
+        for row in king.row.saturating_sub(1)..=(king.row + 1).min(8) {
+            for col in king.col.saturating_sub(1)..=(king.col + 1).min(8) {
+                let pos = Position::new(row, col);
+                let is_attacked = attacked[row as usize][col as usize];
+                if is_attacked {
+                    attacked_squares += 1;
+                }
+                let own_piece = board
+                    .get_piece(pos)
+                    .map_or(false, |piece| piece.player == player);
+                if pos != king && !own_piece && !is_attacked {
+                    escape_squares += 1;
+                }
+            }
+        }
+
+        Self {
+            king: Some(king.to_string()),
+            in_check: board.is_king_in_check(player, captured_pieces),
+            attackers: saturate(attackers),
+            attacked_squares: saturate(attacked_squares),
+            escape_squares: saturate(escape_squares),
+            defenders: saturate(defenders),
+            attacker_hand_pieces: saturate(enemy_hand),
+        }
+    }
+
+    /// Danger from the summary alone, `0..=MAX_HEURISTIC_LEVEL`
+    pub fn heuristic_level(&self) -> u8 {
+        if self.king.is_none() {
+            return 0;
+        }
+        let score = i32::from(self.attackers) * ATTACKER_WEIGHT
+            + i32::from(self.attacked_squares) * ATTACKED_SQUARE_WEIGHT
+            + (i32::from(self.attacker_hand_pieces) * HAND_PIECE_WEIGHT).min(MAX_HAND_BONUS)
+            + if self.in_check { IN_CHECK_BONUS } else { 0 }
+            - i32::from(self.escape_squares) * ESCAPE_SQUARE_WEIGHT
+            - i32::from(self.defenders) * DEFENDER_WEIGHT;
+        score.clamp(0, i32::from(MAX_HEURISTIC_LEVEL)) as u8
+    }
+}
+
+fn saturate(count: usize) -> u8 {
+    u8::try_from(count).unwrap_or(u8::MAX)
+}
+
+/// How close one king is to being mated
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KingDanger {
+    pub player: Player,
+    /// 0 (safe) to 100 (mate on the board for the side to move)
+    pub level: u8,
+    /// Plies of the shortest mate found against this king, if any
+    pub mate_in: Option<u8>,
+    /// That mate in USI notation, attacker first
+    pub mate_line: Vec<String>,
+    pub summary: KingAttackSummary,
+}
+
+impl KingDanger {
+    /// Danger to `player`'s king with `side_to_move` to play, searching for
+    /// mates for up to `time_limit_ms`
+    pub fn compute(
+        board: &BitboardBoard,
+        captured_pieces: &CapturedPieces,
+        player: Player,
+        side_to_move: Player,
+        time_limit_ms: u32,
+    ) -> Self {
+        let summary = KingAttackSummary::compute(board, captured_pieces, player);
+        let attacker = player.opposite();
+        // With the defender to move this asks what the attacker threatens; a king
+        // already in check has to answer it first, so there is no threat to ask about
+        let attacker_to_move = side_to_move == attacker;
+        let mate = if summary.king.is_some() && (attacker_to_move || !summary.in_check) {
+            match MateSearcher::new(MATE_THREAT_PLY, Some(time_limit_ms), None).search(
+                board,
+                captured_pieces,
+                attacker,
+            ) {
+                MateSearchResult::Mate(line) => Some(line),
+                MateSearchResult::NoMate | MateSearchResult::Timeout => None,
+            }
+        } else {
+            None
+        };
+
+        let level = match &mate {
+            Some(_) if attacker_to_move => MATE_AVAILABLE_LEVEL,
+            Some(_) => MATE_THREAT_LEVEL,
+            None => summary.heuristic_level(),
+        };
+        Self {
+            player,
+            level,
+            mate_in: mate.as_ref().map(|line| saturate(line.len())),
+            mate_line: mate
+                .unwrap_or_default()
+                .iter()
+                .map(|mv| mv.to_usi_string())
+                .collect(),
+            summary,
+        }
+    }
+}
+
+/// Danger to both kings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MateMeter {
+    pub side_to_move: Player,
+    pub black: KingDanger,
+    pub white: KingDanger,
+}
+
+impl MateMeter {
+    pub fn compute(
+        board: &BitboardBoard,
+        captured_pieces: &CapturedPieces,
+        side_to_move: Player,
+        time_limit_ms: u32,
+    ) -> Self {
+        Self {
+            side_to_move,
+            black: KingDanger::compute(
+                board,
+                captured_pieces,
+                Player::Black,
+                side_to_move,
+                time_limit_ms,
+            ),
+            white: KingDanger::compute(
+                board,
+                captured_pieces,
+                Player::White,
+                side_to_move,
+                time_limit_ms,
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meter(sfen: &str) -> MateMeter {
+        let (board, player, captured) = BitboardBoard::from_fen(sfen).unwrap();
+        MateMeter::compute(&board, &captured, player, 1_000)
+    }
+
+    #[test]
+    fn test_start_position_is_calm() {
+        let meter = meter("lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1");
+        assert_eq!(meter.black.level, 0);
+        assert_eq!(meter.white.level, 0);
+        assert_eq!(meter.white.mate_in, None);
+        // Both golds, both silvers and the rook cover squares next to the king
+        assert_eq!(meter.white.summary.defenders, 5);
+        assert_eq!(meter.white.summary.escape_squares, 3);
+        assert_eq!(meter.white.summary.king.as_deref(), Some("5a"));
+    }
+
+    #[test]
+    fn test_mate_available_and_threatened() {
+        // Sente mates with G*5b
+        let meter_sente_to_move = meter("4k4/9/4P4/9/9/9/9/9/4K4 b G 1");
+        assert_eq!(meter_sente_to_move.white.level, MATE_AVAILABLE_LEVEL);
+        assert_eq!(meter_sente_to_move.white.mate_in, Some(1));
+        assert_eq!(meter_sente_to_move.white.mate_line, vec!["G*5b"]);
+        assert!(meter_sente_to_move.black.level < MATE_THREAT_LEVEL);
+
+        // Same position with gote to move: the mate is only a threat
+        let meter_gote_to_move = meter("4k4/9/4P4/9/9/9/9/9/4K4 w G 1");
+        assert_eq!(meter_gote_to_move.white.level, MATE_THREAT_LEVEL);
+    }
+
+    #[test]
+    fn test_summary_counts_escapes_and_attackers() {
+        // Bare gote king on 5a against a sente rook on 5i: 5b is attacked
+        let (board, _, captured) =
+            BitboardBoard::from_fen("4k4/9/9/9/9/9/9/9/K3R4 b - 1").unwrap();
+        let summary = KingAttackSummary::compute(&board, &captured, Player::White);
+        assert!(summary.in_check);
+        assert_eq!(summary.attackers, 1);
+        // 5a and 5b are attacked; 4a, 6a, 4b and 6b are free
+        assert_eq!(summary.attacked_squares, 2);
+        assert_eq!(summary.escape_squares, 4);
+        assert_eq!(summary.defenders, 0);
+    }
+}
@@ -494,6 +494,15 @@ impl MaterialEvaluator {
         score
     }
 
+    /// Evaluate only the pieces in hand (zero when hand pieces are excluded)
+    pub fn evaluate_hand(&self, captured_pieces: &CapturedPieces, player: Player) -> TaperedScore {
+        if !self.config.include_hand_pieces {
+            return TaperedScore::default();
+        }
+        let mut contribution = MaterialContribution::default();
+        self.evaluate_hand_material(captured_pieces, player, &mut contribution)
+    }
+
     /// Compute a tapered score delta for incremental updates.
     pub fn evaluate_delta(&self, delta: &MaterialDelta) -> TaperedScore {
         let mut score = TaperedScore::default();
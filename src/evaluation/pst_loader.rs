@@ -1,23 +1,23 @@
 use crate::evaluation::piece_square_tables::{PieceSquareTableRaw, PieceSquareTables};
 use crate::types::core::PieceType;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
-use std::io::Read;
+use std::io::{Read, Write};
 use std::path::Path;
 use thiserror::Error;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct SerializedPieceTable {
     mg: [[i32; 9]; 9],
     eg: [[i32; 9]; 9],
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct SerializedPieceSquareTables {
     version: Option<String>,
     description: Option<String>,
-    tables: HashMap<String, SerializedPieceTable>,
+    tables: BTreeMap<String, SerializedPieceTable>,
 }
 
 #[derive(Debug)]
@@ -67,6 +67,42 @@ impl PieceSquareTableLoader {
         Self::from_serialized(serialized)
     }
 
+    /// Write `tables` in the format `from_path` reads, e.g. after tuning
+    pub fn save(
+        tables: &PieceSquareTables,
+        path: impl AsRef<Path>,
+        version: Option<String>,
+        description: Option<String>,
+    ) -> Result<(), PieceSquareTableLoadError> {
+        let mut file = File::create(path)?;
+        Self::to_writer(tables, &mut file, version, description)
+    }
+
+    pub fn to_writer<W>(
+        tables: &PieceSquareTables,
+        writer: &mut W,
+        version: Option<String>,
+        description: Option<String>,
+    ) -> Result<(), PieceSquareTableLoadError>
+    where
+        W: Write,
+    {
+        let tables = tables
+            .to_phase_tables()
+            .into_iter()
+            .map(|(piece, phase)| {
+                (
+                    piece_type_name(piece).to_string(),
+                    SerializedPieceTable { mg: phase.mg, eg: phase.eg },
+                )
+            })
+            .collect();
+        let serialized = SerializedPieceSquareTables { version, description, tables };
+        serde_json::to_writer_pretty(&mut *writer, &serialized)?;
+        writer.write_all(b"\n")?;
+        Ok(())
+    }
+
     fn from_serialized(
         serialized: SerializedPieceSquareTables,
     ) -> Result<PieceSquareTableLoadResult, PieceSquareTableLoadError> {
@@ -204,6 +240,25 @@ fn parse_piece_type(name: &str) -> Option<PieceType> {
     }
 }
 
+fn piece_type_name(piece: PieceType) -> &'static str {
+    match piece {
+        PieceType::Pawn => "pawn",
+        PieceType::Lance => "lance",
+        PieceType::Knight => "knight",
+        PieceType::Silver => "silver",
+        PieceType::Gold => "gold",
+        PieceType::Bishop => "bishop",
+        PieceType::Rook => "rook",
+        PieceType::King => "king",
+        PieceType::PromotedPawn => "promoted_pawn",
+        PieceType::PromotedLance => "promoted_lance",
+        PieceType::PromotedKnight => "promoted_knight",
+        PieceType::PromotedSilver => "promoted_silver",
+        PieceType::PromotedBishop => "promoted_bishop",
+        PieceType::PromotedRook => "promoted_rook",
+    }
+}
+
 const DEFAULT_PRESET_PATH: &str = "config/pst/default.json";
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -323,6 +378,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn saved_tables_load_back() {
+        let tables = PieceSquareTables::new();
+        let mut buffer = Vec::new();
+        PieceSquareTableLoader::to_writer(&tables, &mut buffer, Some("0.2".to_string()), None)
+            .expect("write tables");
+
+        let result = PieceSquareTableLoader::from_reader(&mut Cursor::new(buffer)).unwrap();
+        assert_eq!(result.version.as_deref(), Some("0.2"));
+        for piece in [PieceType::Silver, PieceType::PromotedRook] {
+            assert_eq!(result.tables.get_tables(piece), tables.get_tables(piece));
+        }
+    }
+
     #[test]
     fn load_custom_tables_from_path() {
         let mut file = NamedTempFile::new().expect("temp file");
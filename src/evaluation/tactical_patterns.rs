@@ -690,20 +690,16 @@ impl TacticalPatternRecognizer {
     fn detect_pins(&mut self, ctx: &TacticalDetectionContext) -> TaperedScore {
         self.stats.pin_checks += 1;
 
+        let pinned = ctx.board.pinned_pieces(ctx.player);
         let mut total_score = 0;
-        if let Some(king_pos) = self.find_king_position(ctx.board, ctx.player) {
-            total_score += self.check_pins_in_directions(
-                ctx,
-                king_pos,
-                ctx.player,
-                &[(1, 0), (-1, 0), (0, 1), (0, -1)],
-            );
-            total_score += self.check_pins_in_directions(
-                ctx,
-                king_pos,
-                ctx.player,
-                &[(1, 1), (-1, 1), (1, -1), (-1, -1)],
-            );
+        for pos in ctx.board.iter_attack_targets(pinned) {
+            if let Some(piece) = ctx.board.get_piece(pos) {
+                let pinned_value = piece.piece_type.base_value();
+                let penalty =
+                    (pinned_value as f32 * self.config.pin_penalty_ratio).round() as i32;
+                total_score -= penalty.max(1);
+                self.stats.pins_found.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            }
         }
 
         let drop_bonus = self.detect_drop_pin_threats(ctx);
@@ -716,75 +712,6 @@ impl TacticalPatternRecognizer {
         }
     }
 
-    /// Check for pins in given directions
-    fn check_pins_in_directions(
-        &self,
-        ctx: &TacticalDetectionContext,
-        king_pos: Position,
-        player: Player,
-        directions: &[(i8, i8)],
-    ) -> i32 {
-        let mut pin_penalty = 0;
-        let opponent = player.opposite();
-
-        for &(dr, dc) in directions {
-            let mut first_friendly: Option<Piece> = None;
-            let mut row = king_pos.row as i8 + dr;
-            let mut col = king_pos.col as i8 + dc;
-
-            // Scan outward from king
-            while row >= 0 && row < 9 && col >= 0 && col < 9 {
-                let pos = Position::new(row as u8, col as u8);
-
-                if let Some(piece) = ctx.board.get_piece(pos) {
-                    if piece.player == player {
-                        if first_friendly.is_some() {
-                            // Two friendly pieces before encountering attacker: no pin
-                            break;
-                        }
-                        first_friendly = Some(piece);
-                    } else {
-                        if let Some(friendly) = first_friendly {
-                            if piece.player == opponent
-                                && self.can_pin_along_line(piece.piece_type, dr, dc)
-                            {
-                                let pinned_value = friendly.piece_type.base_value();
-                                let penalty = (pinned_value as f32 * self.config.pin_penalty_ratio)
-                                    .round() as i32;
-                                let penalty = penalty.max(1);
-                                pin_penalty -= penalty;
-                                self.stats
-                                    .pins_found
-                                    .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-                            }
-                        }
-                        break;
-                    }
-                }
-
-                row += dr;
-                col += dc;
-            }
-        }
-
-        pin_penalty
-    }
-
-    /// Check if piece type can create pins along given direction
-    fn can_pin_along_line(&self, piece_type: PieceType, dr: i8, dc: i8) -> bool {
-        match piece_type {
-            PieceType::Rook | PieceType::PromotedRook | PieceType::Lance => {
-                // Can pin along ranks and files
-                dr == 0 || dc == 0
-            }
-            PieceType::Bishop | PieceType::PromotedBishop => {
-                // Can pin along diagonals
-                dr.abs() == dc.abs()
-            }
-            _ => false,
-        }
-    }
-
     // ===================================================================
     // SKEWER DETECTION
     // ===================================================================
@@ -879,86 +806,19 @@ impl TacticalPatternRecognizer {
         self.stats.discovered_checks += 1;
 
         let mut total_bonus = 0;
-        let opponent = ctx.opponent;
-
-        // Find opponent king
-        let opp_king_pos = match self.find_king_position(ctx.board, opponent) {
-            Some(pos) => pos,
-            None => return TaperedScore::default(),
-        };
 
-        // Check if any of our pieces can create discovered attacks by moving
-        for &(pos, _) in &ctx.player_pieces {
-            if self.can_create_discovered_attack(ctx, pos, opp_king_pos) {
-                total_bonus += self.config.discovered_attack_bonus_cp;
-                self.stats
-                    .discovered_attacks_found
-                    .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-            }
+        // Our pieces standing between a friendly slider and the opponent king
+        let candidates = ctx.board.discovered_check_candidates(ctx.player);
+        for _ in ctx.board.iter_attack_targets(candidates) {
+            total_bonus += self.config.discovered_attack_bonus_cp;
+            self.stats
+                .discovered_attacks_found
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
         }
 
         self.apply_phase_weights(total_bonus, &self.config.phase_weights.discovered)
     }
 
-    /// Check if moving a piece can create a discovered attack
-    fn can_create_discovered_attack(
-        &self,
-        ctx: &TacticalDetectionContext,
-        piece_pos: Position,
-        target_pos: Position,
-    ) -> bool {
-        // Check if there's a friendly sliding piece behind this piece that would attack target
-        let direction = match TacticalDetectionContext::direction_towards(piece_pos, target_pos) {
-            Some(dir) => dir,
-            None => return false,
-        };
-
-        // Path between piece and target must be clear
-        let mut row = piece_pos.row as i8 + direction.0;
-        let mut col = piece_pos.col as i8 + direction.1;
-        let mut reached_target = false;
-
-        while row >= 0 && row < 9 && col >= 0 && col < 9 {
-            let check_pos = Position::new(row as u8, col as u8);
-            if check_pos == target_pos {
-                reached_target = true;
-                break;
-            }
-
-            if ctx.board.get_piece(check_pos).is_some() {
-                return false;
-            }
-
-            row += direction.0;
-            col += direction.1;
-        }
-
-        if !reached_target {
-            return false;
-        }
-
-        // Look behind for sliding piece that would attack along this line
-        let behind_direction = (-direction.0, -direction.1);
-        let mut row = piece_pos.row as i8 + behind_direction.0;
-        let mut col = piece_pos.col as i8 + behind_direction.1;
-
-        while row >= 0 && row < 9 && col >= 0 && col < 9 {
-            let check_pos = Position::new(row as u8, col as u8);
-            match ctx.board.get_piece(check_pos) {
-                Some(piece) if piece.player == ctx.player => {
-                    return self.can_pin_along_line(piece.piece_type, direction.0, direction.1);
-                }
-                Some(_) => return false,
-                None => {
-                    row += behind_direction.0;
-                    col += behind_direction.1;
-                }
-            }
-        }
-
-        false
-    }
-
     // ===================================================================
     // KNIGHT FORK DETECTION
     // ===================================================================
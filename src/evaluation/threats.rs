@@ -0,0 +1,246 @@
+//! Threat Detection
+//!
+//! Immediate threats one side poses against the other's pieces, read off the
+//! board's attack maps:
+//! - Hanging pieces: attacked and not defended at all
+//! - Forks: one piece attacking two targets worth taking, such as a knight
+//!   hitting a rook and a gold or a bishop hitting both flanks
+//! - Skewers: a slider attacking a piece with a lesser one behind it on the
+//!   same line, so the front piece has to move and leave the other
+//!
+//! The integrated evaluator scores them as the `threats` term, and hints name
+//! the motif a move creates. On search boards the attack maps are tracked, so
+//! scoring reads them instead of regenerating every piece's attacks.
+
+use crate::bitboards::attack_map::AttackMaps;
+use crate::bitboards::pins::slides_along;
+use crate::bitboards::BitboardBoard;
+use crate::types::core::{PieceType, Player, Position};
+use crate::types::evaluation::TaperedScore;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThreatKind {
+    HangingPiece,
+    Fork,
+    Skewer,
+}
+
+/// A threat against the opponent's pieces
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Threat {
+    pub kind: ThreatKind,
+    /// Square of the threatening piece
+    pub attacker: Position,
+    /// Threatened pieces; the most valuable first for a fork, the front piece
+    /// first for a skewer
+    pub targets: Vec<(Position, PieceType)>,
+    /// Material at stake in centipawns: the hanging piece, the lesser fork
+    /// target or the piece behind a skewer
+    pub value: i32,
+}
+
+/// Scaling of each motif into evaluation centipawns, as a share of the
+/// material at stake
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThreatConfig {
+    pub enabled: bool,
+    pub hanging_piece_ratio: f32,
+    /// Forks and skewers are already scored by `TacticalPatternRecognizer`, so
+    /// these stay at zero unless its fork and skewer detection is turned off
+    pub fork_ratio: f32,
+    pub skewer_ratio: f32,
+}
+
+impl Default for ThreatConfig {
+    fn default() -> Self {
+        Self { enabled: true, hanging_piece_ratio: 0.25, fork_ratio: 0.0, skewer_ratio: 0.0 }
+    }
+}
+
+/// Threats `player` poses against the opponent's pieces
+pub fn detect_threats(board: &BitboardBoard, player: Player) -> Vec<Threat> {
+    detect_with_maps(board, &board.attack_maps(), player)
+}
+
+/// Threats `player` poses after a move that were not there before it
+pub fn new_threats(before: &BitboardBoard, after: &BitboardBoard, player: Player) -> Vec<Threat> {
+    let existing = detect_threats(before, player);
+    detect_threats(after, player)
+        .into_iter()
+        .filter(|threat| {
+            !existing
+                .iter()
+                .any(|old| old.kind == threat.kind && old.targets == threat.targets)
+        })
+        .collect()
+}
+
+/// Threat score for `player`, the side to move: their threats minus the
+/// opponent's. The mover can still save one of its own hanging pieces, so the
+/// most valuable of them is left out of the opponent's score.
+pub fn evaluate_threats(
+    board: &BitboardBoard,
+    player: Player,
+    config: &ThreatConfig,
+) -> TaperedScore {
+    if !config.enabled {
+        return TaperedScore::default();
+    }
+    let maps = board.attack_maps();
+    let score = |side: Player| -> f32 {
+        let threats = detect_with_maps(board, &maps, side);
+        let saved = if side == player {
+            0
+        } else {
+            threats
+                .iter()
+                .filter(|threat| threat.kind == ThreatKind::HangingPiece)
+                .map(|threat| threat.value)
+                .max()
+                .unwrap_or(0)
+        };
+        let total: f32 = threats
+            .iter()
+            .map(|threat| {
+                let ratio = match threat.kind {
+                    ThreatKind::HangingPiece => config.hanging_piece_ratio,
+                    ThreatKind::Fork => config.fork_ratio,
+                    ThreatKind::Skewer => config.skewer_ratio,
+                };
+                threat.value as f32 * ratio
+            })
+            .sum();
+        total - saved as f32 * config.hanging_piece_ratio
+    };
+    TaperedScore::new((score(player) - score(player.opposite())).round() as i32)
+}
+
+fn detect_with_maps(board: &BitboardBoard, maps: &AttackMaps, player: Player) -> Vec<Threat> {
+    let opponent = player.opposite();
+    let mut threats = Vec::new();
+
+    for (pos, piece) in board.iter_pieces() {
+        if piece.player == opponent
+            && piece.piece_type != PieceType::King
+            && maps.attack_count(player, pos) > 0
+            && maps.attack_count(opponent, pos) == 0
+        {
+            let attacker =
+                board.iter_attack_targets(maps.attackers(player, pos)).min_by_key(|&square| {
+                    board.get_piece(square).map_or(0, |p| p.piece_type.base_value())
+                });
+            if let Some(attacker) = attacker {
+                threats.push(Threat {
+                    kind: ThreatKind::HangingPiece,
+                    attacker,
+                    targets: vec![(pos, piece.piece_type)],
+                    value: piece.piece_type.base_value(),
+                });
+            }
+        }
+    }
+
+    for (pos, piece) in board.iter_pieces().filter(|(_, piece)| piece.player == player) {
+        let own_value = piece.piece_type.base_value();
+        let mut targets: Vec<(Position, PieceType)> = board
+            .iter_attack_targets(maps.attacks_from(pos))
+            .filter_map(|square| {
+                let target = board.get_piece(square).filter(|p| p.player == opponent)?;
+                let worth_taking = target.piece_type == PieceType::King
+                    || target.piece_type.base_value() > own_value
+                    || maps.attack_count(opponent, square) == 0;
+                worth_taking.then_some((square, target.piece_type))
+            })
+            .collect();
+
+        if let Some(skewer) = skewer_from(board, pos, piece.piece_type, player, &targets) {
+            threats.push(skewer);
+        }
+        if targets.len() >= 2 {
+            targets.sort_by_key(|(_, piece_type)| std::cmp::Reverse(piece_type.base_value()));
+            threats.push(Threat {
+                kind: ThreatKind::Fork,
+                attacker: pos,
+                value: targets[1].1.base_value(),
+                targets,
+            });
+        }
+    }
+    threats
+}
+
+/// A skewer by the slider on `from` through one of its `targets`, if the
+/// first piece behind it on the line is a lesser opposing piece
+fn skewer_from(
+    board: &BitboardBoard,
+    from: Position,
+    piece_type: PieceType,
+    player: Player,
+    targets: &[(Position, PieceType)],
+) -> Option<Threat> {
+    targets.iter().find_map(|&(front, front_type)| {
+        let step = (
+            (front.row as i8 - from.row as i8).signum(),
+            (front.col as i8 - from.col as i8).signum(),
+        );
+        if !slides_along(piece_type, player, step) {
+            return None;
+        }
+        let (mut row, mut col) = (front.row as i8 + step.0, front.col as i8 + step.1);
+        while (0..9).contains(&row) && (0..9).contains(&col) {
+            let behind = Position::new(row as u8, col as u8);
+            if let Some(piece) = board.get_piece(behind) {
+                let lesser = piece.player != player
+                    && piece.piece_type != PieceType::King
+                    && piece.piece_type.base_value() < front_type.base_value();
+                return lesser.then(|| Threat {
+                    kind: ThreatKind::Skewer,
+                    attacker: from,
+                    targets: vec![(front, front_type), (behind, piece.piece_type)],
+                    value: piece.piece_type.base_value(),
+                });
+            }
+            row += step.0;
+            col += step.1;
+        }
+        None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hanging_piece_and_fork() {
+        // Black's knight on 5e hits the rook on 6c and the gold on 4c; the gold
+        // is defended by the rook, the rook by nothing
+        let (board, _, _) = BitboardBoard::from_fen("k8/9/3r1g3/9/4N4/9/9/9/8K b - 1").unwrap();
+        let threats = detect_threats(&board, Player::Black);
+        let fork = threats.iter().find(|t| t.kind == ThreatKind::Fork).unwrap();
+        assert_eq!(fork.targets[0].1, PieceType::Rook);
+        assert_eq!(fork.value, PieceType::Gold.base_value());
+        let hanging: Vec<_> =
+            threats.iter().filter(|t| t.kind == ThreatKind::HangingPiece).collect();
+        assert_eq!(hanging.len(), 1);
+        assert_eq!(hanging[0].targets[0].1, PieceType::Rook);
+        assert!(evaluate_threats(&board, Player::Black, &ThreatConfig::default()).mg > 0);
+        // With White to move the rook can step away, so nothing is scored
+        assert_eq!(evaluate_threats(&board, Player::White, &ThreatConfig::default()).mg, 0);
+    }
+
+    #[test]
+    fn test_skewer_along_file() {
+        // Black's rook on 5i skewers White's bishop on 5e to the silver on 5b
+        let (board, _, _) = BitboardBoard::from_fen("k8/4s4/9/9/4b4/9/9/K8/4R4 b - 1").unwrap();
+        let threats = detect_threats(&board, Player::Black);
+        let skewer = threats.iter().find(|t| t.kind == ThreatKind::Skewer).unwrap();
+        assert_eq!(skewer.targets[0].1, PieceType::Bishop);
+        assert_eq!(skewer.targets[1].1, PieceType::Silver);
+        assert!(detect_threats(&board, Player::White)
+            .iter()
+            .all(|t| t.kind != ThreatKind::Skewer));
+    }
+}
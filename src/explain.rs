@@ -0,0 +1,301 @@
+//! Move Explanations
+//!
+//! Answers "why did (or didn't) the engine play this move?" for the `why`
+//! debug command. Two searches of the current position are compared: a normal
+//! one, which picks the engine's move and records how every root move was
+//! ordered, deepened and scored, and one restricted to the move in question,
+//! which gives its exact score, the line that refutes it and the pruning done
+//! in its subtree. Scores are for the side to move.
+
+use crate::search::statistics::{RootMoveIteration, SearchStatisticsReport};
+use crate::types::core::Move;
+use serde::{Deserialize, Serialize};
+
+/// Time for the whole command when the caller does not say
+pub const DEFAULT_EXPLAIN_TIME_MS: u32 = 2_000;
+
+/// Depth cap for both searches; the time budget normally ends them first
+pub const EXPLAIN_MAX_DEPTH: u8 = 64;
+
+/// Moves kept of the best line and of the refutation
+pub const EXPLAIN_LINE_LENGTH: usize = 8;
+
+/// Shortest time either search is given
+const MIN_SEARCH_TIME_MS: u32 = 100;
+
+/// Time each of the two searches gets out of `time_limit_ms`
+pub fn search_time_ms(time_limit_ms: u32) -> u32 {
+    (time_limit_ms / 2).max(MIN_SEARCH_TIME_MS)
+}
+
+/// One of the two searches an explanation compares
+#[derive(Debug, Clone)]
+pub struct ExplainSearch {
+    pub best_move: Move,
+    pub score: i32,
+    /// Deepest completed iteration
+    pub depth: u8,
+    /// Continuation after `best_move`
+    pub continuation: Vec<Move>,
+    pub statistics: SearchStatisticsReport,
+}
+
+/// Pruning and reductions in the subtree of the explained move
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubtreePruning {
+    pub nodes: u64,
+    pub beta_cutoffs: u64,
+    pub null_move_attempts: u64,
+    pub null_move_cutoffs: u64,
+    pub lmr_reductions: u64,
+    pub lmr_researches: u64,
+    pub drops_pruned: u64,
+}
+
+impl From<&SearchStatisticsReport> for SubtreePruning {
+    fn from(report: &SearchStatisticsReport) -> Self {
+        Self {
+            nodes: report.nodes,
+            beta_cutoffs: report.beta_cutoffs,
+            null_move_attempts: report.null_move_attempts,
+            null_move_cutoffs: report.null_move_cutoffs,
+            lmr_reductions: report.lmr_reductions,
+            lmr_researches: report.lmr_researches,
+            drops_pruned: report.drops_pruned,
+        }
+    }
+}
+
+/// Why a move was or was not chosen
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MoveExplanation {
+    /// The explained move in USI notation
+    pub usi: String,
+    /// Whether the normal search chose it
+    pub chosen: bool,
+    pub best_move: String,
+    pub best_score: i32,
+    /// Exact score of the explained move from its own search
+    pub score: i32,
+    /// `best_score - score`; negative when the move did better on its own
+    pub score_loss: i32,
+    /// Depth of the move's own search
+    pub depth: u8,
+    /// The chosen move and its continuation
+    pub best_line: Vec<String>,
+    /// Continuation after the explained move, starting with the reply
+    pub refutation: Vec<String>,
+    /// 1-based place in the normal search's initial root order, if it was searched
+    pub ordering_rank: Option<usize>,
+    pub root_moves: usize,
+    /// Share of the normal search's root nodes spent on the move, in percent
+    pub node_share: f64,
+    /// The move's score in each iteration of the normal search
+    pub root_history: Vec<RootMoveIteration>,
+    /// Consecutive iterations the move was refuted at the end of the normal search
+    pub refuted_iterations: u8,
+    pub pruning: SubtreePruning,
+    /// The above in words, most important first
+    pub reasons: Vec<String>,
+}
+
+fn usi_line(moves: &[Move]) -> Vec<String> {
+    moves.iter().take(EXPLAIN_LINE_LENGTH).map(Move::to_usi_string).collect()
+}
+
+/// Explain `mv` from the normal search `full` and the search restricted to it
+pub fn explain(mv: &Move, full: &ExplainSearch, restricted: &ExplainSearch) -> MoveExplanation {
+    let usi = mv.to_usi_string();
+    let root_moves = &full.statistics.root_moves;
+    let ordering_rank = root_moves.iter().position(|report| report.move_usi == usi);
+    let report = ordering_rank.map(|index| &root_moves[index]);
+    let total_nodes: u64 = root_moves.iter().map(|report| report.nodes).sum();
+    let node_share = match (report, total_nodes) {
+        (Some(report), total) if total > 0 => report.nodes as f64 * 100.0 / total as f64,
+        _ => 0.0,
+    };
+    let root_history = report.map(|report| report.score_history.clone()).unwrap_or_default();
+
+    let mut best_line = vec![full.best_move.clone()];
+    best_line.extend(full.continuation.iter().cloned());
+    let explanation = MoveExplanation {
+        chosen: full.best_move.to_usi_string() == usi,
+        best_move: full.best_move.to_usi_string(),
+        best_score: full.score,
+        score: restricted.score,
+        score_loss: full.score.saturating_sub(restricted.score),
+        depth: restricted.depth,
+        best_line: usi_line(&best_line),
+        refutation: usi_line(&restricted.continuation),
+        ordering_rank: ordering_rank.map(|index| index + 1),
+        root_moves: root_moves.len(),
+        node_share,
+        root_history,
+        refuted_iterations: report.map_or(0, |report| report.refuted_iterations),
+        pruning: SubtreePruning::from(&restricted.statistics),
+        reasons: Vec::new(),
+        usi,
+    };
+    MoveExplanation { reasons: reasons(&explanation), ..explanation }
+}
+
+fn reasons(explanation: &MoveExplanation) -> Vec<String> {
+    let mut reasons = Vec::new();
+    if explanation.chosen {
+        reasons.push(format!(
+            "chosen: best at depth {} with score {}",
+            explanation.depth, explanation.best_score
+        ));
+    } else if explanation.score_loss > 0 {
+        reasons.push(format!(
+            "scores {} ({} below {}, which scores {})",
+            explanation.score,
+            explanation.score_loss,
+            explanation.best_move,
+            explanation.best_score
+        ));
+    } else {
+        reasons.push(format!(
+            "scores {} on its own, not below {} ({}): the normal search did not see this \
+             within its depth",
+            explanation.score, explanation.best_move, explanation.best_score
+        ));
+    }
+    if !explanation.chosen && !explanation.refutation.is_empty() {
+        reasons.push(format!(
+            "refuted by {} (line: {})",
+            explanation.refutation[0],
+            explanation.refutation.join(" ")
+        ));
+    }
+
+    match explanation.ordering_rank {
+        Some(rank) => reasons.push(format!(
+            "ordered {} of {} at the root and given {:.1}% of the root nodes",
+            rank, explanation.root_moves, explanation.node_share
+        )),
+        None => reasons.push("not among the normal search's root moves".to_string()),
+    }
+    let reduced: Vec<String> = explanation
+        .root_history
+        .iter()
+        .filter(|iteration| iteration.searched_depth < iteration.depth)
+        .map(|iteration| format!("{} (to {})", iteration.depth, iteration.searched_depth))
+        .collect();
+    if !reduced.is_empty() {
+        reasons.push(format!(
+            "searched at reduced depth as a refuted move in iterations {}",
+            reduced.join(", ")
+        ));
+    }
+    if explanation.refuted_iterations > 0 {
+        reasons.push(format!(
+            "scored more than {} below the best in the last {} iteration(s)",
+            crate::search::root_moves::REFUTATION_MARGIN_CP,
+            explanation.refuted_iterations
+        ));
+    }
+
+    let pruning = &explanation.pruning;
+    reasons.push(format!(
+        "in its subtree: {} nodes, {} of {} null moves cut off, {} late moves reduced \
+         ({} re-searched), {} quiet drops pruned",
+        pruning.nodes,
+        pruning.null_move_cutoffs,
+        pruning.null_move_attempts,
+        pruning.lmr_reductions,
+        pruning.lmr_researches,
+        pruning.drops_pruned
+    ));
+    reasons
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::statistics::RootMoveReport;
+    use crate::types::core::{PieceType, Player, Position};
+
+    fn pawn_push(col: u8, player: Player) -> Move {
+        let (from, to) = match player {
+            Player::Black => (6, 5),
+            Player::White => (2, 3),
+        };
+        Move::new_move(
+            Position::new(from, col),
+            Position::new(to, col),
+            PieceType::Pawn,
+            player,
+            false,
+        )
+    }
+
+    fn history(scores: &[(u8, u8, i32)]) -> Vec<RootMoveIteration> {
+        scores
+            .iter()
+            .map(|&(depth, searched_depth, score)| RootMoveIteration {
+                depth,
+                searched_depth,
+                score,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_refuted_move_explanation() {
+        let best = pawn_push(2, Player::Black);
+        let weak = pawn_push(0, Player::Black);
+        let full = ExplainSearch {
+            best_move: best.clone(),
+            score: 80,
+            depth: 6,
+            continuation: vec![pawn_push(6, Player::White)],
+            statistics: SearchStatisticsReport {
+                root_moves: vec![
+                    RootMoveReport {
+                        move_usi: best.to_usi_string(),
+                        nodes: 900,
+                        ..RootMoveReport::default()
+                    },
+                    RootMoveReport {
+                        move_usi: weak.to_usi_string(),
+                        nodes: 100,
+                        score_history: history(&[(4, 4, -250), (5, 3, -400), (6, 4, -420)]),
+                        refuted_iterations: 2,
+                    },
+                ],
+                ..SearchStatisticsReport::default()
+            },
+        };
+        let restricted = ExplainSearch {
+            best_move: weak.clone(),
+            score: -380,
+            depth: 5,
+            continuation: vec![pawn_push(0, Player::White), pawn_push(1, Player::Black)],
+            statistics: SearchStatisticsReport {
+                nodes: 5_000,
+                lmr_reductions: 40,
+                ..SearchStatisticsReport::default()
+            },
+        };
+
+        let explanation = explain(&weak, &full, &restricted);
+        assert!(!explanation.chosen);
+        assert_eq!(explanation.best_move, "7g7f");
+        assert_eq!(explanation.score_loss, 460);
+        assert_eq!(explanation.ordering_rank, Some(2));
+        assert_eq!(explanation.node_share, 10.0);
+        assert_eq!(explanation.refutation, vec!["9c9d", "8g8f"]);
+        assert_eq!(explanation.pruning.lmr_reductions, 40);
+        assert!(explanation.reasons[1].starts_with("refuted by 9c9d"));
+        assert!(explanation
+            .reasons
+            .iter()
+            .any(|reason| reason.contains("iterations 5 (to 3), 6 (to 4)")));
+
+        let explanation = explain(&best, &full, &restricted);
+        assert!(explanation.chosen);
+        assert!(explanation.reasons[0].starts_with("chosen"));
+        assert_eq!(explanation.best_line, vec!["7g7f", "3c3d"]);
+    }
+}
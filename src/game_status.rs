@@ -0,0 +1,291 @@
+//! Game Status
+//!
+//! Whether a game has ended, and how, from the current position and the
+//! positions played before it. The engine, the Tauri session and the
+//! engine-vs-engine harness all ask this module instead of each detecting the
+//! end of the game on its own.
+//!
+//! Rules applied, in order:
+//! - no legal move: the side to move loses, by checkmate when in check;
+//! - the same position for the fourth time (sennichite): a draw, unless one
+//!   side checked with every move of the cycle, in which case that side loses;
+//! - both kings in the enemy camp (jishogi): decided by the 24-point count.
+
+use crate::bitboards::BitboardBoard;
+use crate::error::ShogiError;
+use crate::i18n::{self, Locale};
+use crate::move_metadata::MoveMetadata;
+use crate::moves::MoveGenerator;
+use crate::notation::NotationContext;
+use crate::position_format::PositionText;
+use crate::search::repetition::{RepetitionEntry, RepetitionOutcome, RepetitionPath};
+use crate::search::ShogiHashHandler;
+use crate::tablebase::{MicroTablebase, TablebaseResult};
+use crate::types::{CapturedPieces, ImpasseOutcome, ImpasseResult, Move, Player, Position};
+use serde::{Deserialize, Serialize};
+
+/// Occurrences of a position that make sennichite
+pub const SENNICHITE_OCCURRENCES: usize = 4;
+
+/// How the game stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameStatus {
+    Ongoing,
+    /// The side to move is checkmated; holds the winner
+    Checkmate(Player),
+    /// The side to move has no legal move without being in check. Shogi has no
+    /// stalemate draw, so this is a loss like checkmate; holds the winner.
+    NoLegalMoves(Player),
+    /// Sennichite without perpetual check: a draw
+    Repetition,
+    /// Sennichite where one side checked with every move; holds the loser
+    PerpetualCheck(Player),
+    /// Both kings have entered the enemy camp; the points decide
+    Impasse(ImpasseResult),
+}
+
+impl GameStatus {
+    /// Status of the position with `side_to_move` to play, given the positions
+    /// before it (`history`, oldest first, the current one excluded)
+    pub fn compute(
+        board: &BitboardBoard,
+        captured_pieces: &CapturedPieces,
+        side_to_move: Player,
+        history: &[RepetitionEntry],
+    ) -> Self {
+        let legal_moves =
+            MoveGenerator::new().generate_legal_moves(board, side_to_move, captured_pieces);
+        let current = RepetitionEntry::new(
+            &ShogiHashHandler::new_default(),
+            board,
+            captured_pieces,
+            side_to_move,
+        );
+        if legal_moves.is_empty() {
+            return if current.in_check {
+                GameStatus::Checkmate(side_to_move.opposite())
+            } else {
+                GameStatus::NoLegalMoves(side_to_move.opposite())
+            };
+        }
+
+        let earlier = history.iter().filter(|entry| entry.hash == current.hash).count();
+        if earlier + 1 >= SENNICHITE_OCCURRENCES {
+            let mut path = RepetitionPath::new();
+            path.reset(history);
+            match path.classify(current) {
+                Some(RepetitionOutcome::PerpetualCheckByMover) => {
+                    return GameStatus::PerpetualCheck(side_to_move)
+                }
+                Some(RepetitionOutcome::PerpetualCheckByOpponent) => {
+                    return GameStatus::PerpetualCheck(side_to_move.opposite())
+                }
+                Some(RepetitionOutcome::Draw) | None => return GameStatus::Repetition,
+            }
+        }
+
+        match board.check_impasse_result(captured_pieces) {
+            Some(result) => GameStatus::Impasse(result),
+            None => GameStatus::Ongoing,
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        !matches!(self, GameStatus::Ongoing)
+    }
+
+    /// Short description of how the game ended, for logs and result lines
+    pub fn reason(&self) -> &'static str {
+        match self {
+            GameStatus::Ongoing => "Ongoing",
+            GameStatus::Checkmate(_) => "Checkmate",
+            GameStatus::NoLegalMoves(_) => "No legal moves",
+            GameStatus::Repetition => "Repetition (sennichite)",
+            GameStatus::PerpetualCheck(_) => "Perpetual check",
+            GameStatus::Impasse(_) => "Impasse (jishogi)",
+        }
+    }
+
+    /// `reason` in `locale`, for players
+    pub fn message(&self, locale: Locale) -> String {
+        let key = match self {
+            GameStatus::Ongoing => "status.ongoing",
+            GameStatus::Checkmate(_) => "status.checkmate",
+            GameStatus::NoLegalMoves(_) => "status.no_legal_moves",
+            GameStatus::Repetition => "status.repetition",
+            GameStatus::PerpetualCheck(_) => "status.perpetual_check",
+            GameStatus::Impasse(_) => "status.impasse",
+        };
+        i18n::text(locale, key)
+    }
+
+    /// Winner of a finished game; `None` while it goes on and for draws
+    pub fn winner(&self) -> Option<Player> {
+        match *self {
+            GameStatus::Checkmate(winner) | GameStatus::NoLegalMoves(winner) => Some(winner),
+            GameStatus::PerpetualCheck(loser) => Some(loser.opposite()),
+            GameStatus::Impasse(result) => match result.outcome {
+                ImpasseOutcome::BlackWins => Some(Player::Black),
+                ImpasseOutcome::WhiteWins => Some(Player::White),
+                ImpasseOutcome::Draw => None,
+            },
+            GameStatus::Ongoing | GameStatus::Repetition => None,
+        }
+    }
+}
+
+/// A move applied by `GameTracker`: what it did and where it left the game
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayedMove {
+    pub metadata: MoveMetadata,
+    pub status: GameStatus,
+}
+
+/// A game followed move by move, for callers that only see USI moves
+#[derive(Clone)]
+pub struct GameTracker {
+    board: BitboardBoard,
+    captured_pieces: CapturedPieces,
+    side_to_move: Player,
+    history: Vec<RepetitionEntry>,
+    /// Destination of the last move played
+    last_to: Option<Position>,
+    /// SFEN move number of the current position
+    move_number: u32,
+}
+
+impl GameTracker {
+    /// Start from an SFEN position
+    pub fn from_sfen(sfen: &str) -> Result<Self, ShogiError> {
+        let position = PositionText::from_sfen(sfen)
+            .map_err(|e| ShogiError::parse(format!("Invalid SFEN: {}", e)))?;
+        Ok(Self {
+            board: position.board,
+            captured_pieces: position.captured_pieces,
+            side_to_move: position.side_to_move,
+            history: Vec::new(),
+            last_to: None,
+            move_number: position.move_number,
+        })
+    }
+
+    pub fn side_to_move(&self) -> Player {
+        self.side_to_move
+    }
+
+    /// Play a USI move, refusing illegal ones
+    pub fn play_usi(&mut self, usi: &str) -> Result<PlayedMove, ShogiError> {
+        let mv: Move = MoveGenerator::new()
+            .generate_legal_moves(&self.board, self.side_to_move, &self.captured_pieces)
+            .into_iter()
+            .find(|mv| mv.to_usi_string() == usi)
+            .ok_or_else(|| ShogiError::rule(format!("Illegal move: {}", usi)))?;
+        let metadata =
+            MoveMetadata::compute(&self.board, &self.captured_pieces, &mv, self.last_to);
+
+        self.history.push(RepetitionEntry::new(
+            &ShogiHashHandler::new_default(),
+            &self.board,
+            &self.captured_pieces,
+            self.side_to_move,
+        ));
+        if mv.from.is_none() {
+            self.captured_pieces.remove_piece(mv.piece_type, self.side_to_move);
+        }
+        if let Some(captured) = self.board.make_move(&mv) {
+            self.captured_pieces.add_piece(captured.piece_type, self.side_to_move);
+        }
+        self.side_to_move = self.side_to_move.opposite();
+        self.last_to = Some(mv.to);
+        self.move_number += 1;
+        Ok(PlayedMove {
+            metadata,
+            status: self.status(),
+        })
+    }
+
+    /// Legal moves of the side to move in USI notation
+    pub fn legal_moves_usi(&self) -> Vec<String> {
+        MoveGenerator::new()
+            .generate_legal_moves(&self.board, self.side_to_move, &self.captured_pieces)
+            .iter()
+            .map(|mv| mv.to_usi_string())
+            .collect()
+    }
+
+    /// The current position, for writing or reading the next move
+    pub fn notation_context(&self) -> NotationContext<'_> {
+        NotationContext::new(&self.board, &self.captured_pieces, self.side_to_move, self.last_to)
+    }
+
+    /// The current position, for writing as SFEN or BOD
+    pub fn position_text(&self) -> PositionText {
+        PositionText::new(
+            self.board.clone(),
+            self.side_to_move,
+            self.captured_pieces.clone(),
+            self.move_number,
+        )
+    }
+
+    /// Probe `tablebase` with the current position, side to move included
+    pub fn probe_tablebase(&self, tablebase: &mut MicroTablebase) -> Option<TablebaseResult> {
+        tablebase.probe(&self.board, self.side_to_move, &self.captured_pieces)
+    }
+
+    pub fn status(&self) -> GameStatus {
+        GameStatus::compute(&self.board, &self.captured_pieces, self.side_to_move, &self.history)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCategory;
+
+    const START_SFEN: &str = "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1";
+
+    #[test]
+    fn test_checkmate_and_ongoing() {
+        let tracker = GameTracker::from_sfen(START_SFEN).unwrap();
+        assert_eq!(tracker.status(), GameStatus::Ongoing);
+
+        // Sente drops the gold on 5b, protected by the pawn on 5c
+        let mut tracker = GameTracker::from_sfen("4k4/9/4P4/9/9/9/9/9/4K4 b G 1").unwrap();
+        let played = tracker.play_usi("G*5b").unwrap();
+        assert!(played.metadata.is_mate);
+        let status = played.status;
+        assert_eq!(status, GameStatus::Checkmate(Player::Black));
+        assert_eq!(status.winner(), Some(Player::Black));
+        assert_eq!(tracker.play_usi("5a4a").unwrap_err().category(), ErrorCategory::Rule);
+        let error = GameTracker::from_sfen("4k4/9 b - 1").err().unwrap();
+        assert_eq!(error.category(), ErrorCategory::Parse);
+    }
+
+    #[test]
+    fn test_repetition_and_perpetual_check() {
+        // Both rooks shuffle: the start position comes back every four plies
+        let mut tracker = GameTracker::from_sfen(START_SFEN).unwrap();
+        let shuffle = ["2h3h", "8b7b", "3h2h", "7b8b"];
+        let mut status = GameStatus::Ongoing;
+        for (ply, usi) in shuffle.iter().cycle().take(12).enumerate() {
+            status = tracker.play_usi(usi).unwrap().status;
+            if ply < 11 {
+                assert_eq!(status, GameStatus::Ongoing, "ended early at ply {}", ply + 1);
+            }
+        }
+        assert_eq!(status, GameStatus::Repetition);
+        assert_eq!(status.winner(), None);
+
+        // Gote's king steps between 5a and 4a; sente's rook follows it, checking every move
+        let mut tracker = GameTracker::from_sfen("4k4/9/9/9/9/9/9/4R4/K8 w - 1").unwrap();
+        let cycle = ["5a4a", "5h4h", "4a5a", "4h5h"];
+        let mut status = GameStatus::Ongoing;
+        for usi in cycle.iter().cycle().take(12) {
+            status = tracker.play_usi(usi).unwrap().status;
+        }
+        assert_eq!(status, GameStatus::PerpetualCheck(Player::Black));
+        assert_eq!(status.winner(), Some(Player::White));
+    }
+}
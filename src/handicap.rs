@@ -0,0 +1,228 @@
+//! Handicap Games
+//!
+//! Standard handicap (駒落ち) setups. In a handicap game the stronger player
+//! (上手, White in SFEN terms) removes pieces from their side and moves first.
+//! Besides the starting positions this module can recognise a handicap from the
+//! material left in a position, which the opening book uses to group its lines
+//! and the evaluator uses to offset the missing material.
+
+use crate::bitboards::BitboardBoard;
+use crate::types::board::CapturedPieces;
+use crate::types::core::{PieceType, Position};
+use serde::{Deserialize, Serialize};
+
+/// Number of each piece type in a full set for both sides
+const FULL_SET: [(PieceType, usize); 7] = [
+    (PieceType::Pawn, 18),
+    (PieceType::Lance, 4),
+    (PieceType::Knight, 4),
+    (PieceType::Silver, 4),
+    (PieceType::Gold, 4),
+    (PieceType::Bishop, 2),
+    (PieceType::Rook, 2),
+];
+
+/// Standard handicap setups, all given by White
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Handicap {
+    /// Even game (平手)
+    #[default]
+    Even,
+    /// Left lance (香落ち)
+    Lance,
+    /// Bishop (角落ち)
+    Bishop,
+    /// Rook (飛車落ち)
+    Rook,
+    /// Rook and bishop (二枚落ち)
+    TwoPiece,
+    /// Two-piece plus both lances (四枚落ち)
+    FourPiece,
+    /// Four-piece plus both knights (六枚落ち)
+    SixPiece,
+}
+
+impl Handicap {
+    pub const ALL: [Handicap; 7] = [
+        Handicap::Even,
+        Handicap::Lance,
+        Handicap::Bishop,
+        Handicap::Rook,
+        Handicap::TwoPiece,
+        Handicap::FourPiece,
+        Handicap::SixPiece,
+    ];
+
+    /// Name used for the `Handicap` USI option
+    pub fn name(self) -> &'static str {
+        match self {
+            Handicap::Even => "Even",
+            Handicap::Lance => "Lance",
+            Handicap::Bishop => "Bishop",
+            Handicap::Rook => "Rook",
+            Handicap::TwoPiece => "TwoPiece",
+            Handicap::FourPiece => "FourPiece",
+            Handicap::SixPiece => "SixPiece",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|handicap| handicap.name() == name)
+    }
+
+    /// Starting position in SFEN
+    pub fn sfen(self) -> &'static str {
+        match self {
+            Handicap::Even => "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+            Handicap::Lance => "lnsgkgsn1/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL w - 1",
+            Handicap::Bishop => "lnsgkgsnl/1r7/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL w - 1",
+            Handicap::Rook => "lnsgkgsnl/7b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL w - 1",
+            Handicap::TwoPiece => "lnsgkgsnl/9/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL w - 1",
+            Handicap::FourPiece => "1nsgkgsn1/9/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL w - 1",
+            Handicap::SixPiece => "2sgkgs2/9/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL w - 1",
+        }
+    }
+
+    /// Pieces White removes from the even setup
+    pub fn removed_pieces(self) -> &'static [PieceType] {
+        match self {
+            Handicap::Even => &[],
+            Handicap::Lance => &[PieceType::Lance],
+            Handicap::Bishop => &[PieceType::Bishop],
+            Handicap::Rook => &[PieceType::Rook],
+            Handicap::TwoPiece => &[PieceType::Rook, PieceType::Bishop],
+            Handicap::FourPiece => {
+                &[PieceType::Rook, PieceType::Bishop, PieceType::Lance, PieceType::Lance]
+            }
+            Handicap::SixPiece => &[
+                PieceType::Rook,
+                PieceType::Bishop,
+                PieceType::Lance,
+                PieceType::Lance,
+                PieceType::Knight,
+                PieceType::Knight,
+            ],
+        }
+    }
+
+    /// Material White gives up, in centipawns
+    pub fn material_deficit(self) -> i32 {
+        self.removed_pieces().iter().map(|piece_type| piece_type.base_value()).sum()
+    }
+
+    /// Recognise the handicap from the pieces still in play (on the board or in
+    /// hand). Returns `None` for material that matches no standard setup, such as
+    /// tsume problems.
+    pub fn classify(board: &BitboardBoard, captured_pieces: &CapturedPieces) -> Option<Self> {
+        let mut counts = [0usize; 7];
+        let mut count = |piece_type: PieceType| {
+            let base = piece_type.unpromoted_version().unwrap_or(piece_type);
+            if let Some(index) = FULL_SET.iter().position(|(full, _)| *full == base) {
+                counts[index] += 1;
+            }
+        };
+        for row in 0..9 {
+            for col in 0..9 {
+                if let Some(piece) = board.get_piece(Position::new(row, col)) {
+                    count(piece.piece_type);
+                }
+            }
+        }
+        for piece_type in captured_pieces.black.iter().chain(&captured_pieces.white) {
+            count(*piece_type);
+        }
+
+        Self::ALL.into_iter().find(|handicap| {
+            FULL_SET.iter().zip(counts).all(|((piece_type, full), present)| {
+                let removed = handicap
+                    .removed_pieces()
+                    .iter()
+                    .filter(|removed| *removed == piece_type)
+                    .count();
+                present == full - removed
+            })
+        })
+    }
+
+    /// Recognise the handicap of a position given in SFEN
+    pub fn classify_sfen(sfen: &str) -> Option<Self> {
+        let (board, _, captured_pieces) = BitboardBoard::from_fen(sfen).ok()?;
+        Self::classify(&board, &captured_pieces)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::core::Player;
+
+    #[test]
+    fn test_presets_parse_with_white_to_move() {
+        for handicap in Handicap::ALL {
+            let (_, player, _) = BitboardBoard::from_fen(handicap.sfen()).unwrap();
+            let expected = if handicap == Handicap::Even { Player::Black } else { Player::White };
+            assert_eq!(player, expected, "{:?}", handicap);
+            assert_eq!(Handicap::classify_sfen(handicap.sfen()), Some(handicap));
+        }
+    }
+
+    #[test]
+    fn test_names_round_trip() {
+        for handicap in Handicap::ALL {
+            assert_eq!(Handicap::from_name(handicap.name()), Some(handicap));
+        }
+        assert_eq!(Handicap::from_name("Queen"), None);
+    }
+
+    #[test]
+    fn test_classify_counts_hand_and_promoted_pieces() {
+        // Two-piece handicap game with a captured bishop in hand and a promoted pawn
+        let sfen = "lnsgkgsnl/9/ppppp+Pppp/9/9/9/PPPP1PPPP/7R1/LNSGKGSNL w Bp 1";
+        assert_eq!(Handicap::classify_sfen(sfen), Some(Handicap::TwoPiece));
+        assert_eq!(Handicap::classify_sfen("4k4/9/9/9/9/9/9/9/4K4 b G 1"), None);
+    }
+
+    #[test]
+    fn test_opening_book_sections() {
+        use crate::opening_book::{BookMove, OpeningBook};
+
+        let mut book = OpeningBook::new();
+        let pawn_push = BookMove::new(
+            Some(Position::new(6, 2)),
+            Position::new(5, 2),
+            PieceType::Pawn,
+            false,
+            false,
+            500,
+            0,
+        );
+        book.add_position(Handicap::Even.sfen().to_string(), vec![pawn_push.clone()]);
+        book.add_position(Handicap::Rook.sfen().to_string(), vec![pawn_push]);
+
+        let sections = book.handicap_sections();
+        assert_eq!(sections[&Handicap::Even], vec![Handicap::Even.sfen()]);
+        assert_eq!(sections[&Handicap::Rook], vec![Handicap::Rook.sfen()]);
+        assert!(!sections.contains_key(&Handicap::Bishop));
+    }
+
+    #[test]
+    fn test_evaluation_offsets_missing_material() {
+        use crate::evaluation::PositionEvaluator;
+
+        let (board, _, captured) = BitboardBoard::from_fen(Handicap::TwoPiece.sfen()).unwrap();
+        let mut evaluator = PositionEvaluator::new();
+        let raw = evaluator.evaluate(&board, Player::White, &captured);
+        evaluator.set_handicap(Handicap::TwoPiece);
+        let offset = evaluator.evaluate(&board, Player::White, &captured);
+        assert_eq!(offset - raw, Handicap::TwoPiece.material_deficit());
+    }
+
+    #[test]
+    fn test_material_deficit() {
+        assert_eq!(Handicap::Even.material_deficit(), 0);
+        assert_eq!(
+            Handicap::TwoPiece.material_deficit(),
+            PieceType::Rook.base_value() + PieceType::Bishop.base_value()
+        );
+    }
+}
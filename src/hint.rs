@@ -0,0 +1,287 @@
+//! Best-Move Hints
+//!
+//! Difficulty-adjusted hints for the GUI's hint button and best-move arrow. Low
+//! levels search shallowly, so the suggested move is one a learner can follow;
+//! the top level searches at full strength. Each hint carries a short reason
+//! built from the move itself (capture, check, promotion, drop), the threats it
+//! creates (fork, skewer, attack on a loose piece) and the evaluation terms it
+//! improves the most.
+
+use crate::evaluation::breakdown::EvaluationBreakdown;
+use crate::evaluation::threats::{Threat, ThreatKind};
+use crate::i18n::{self, Locale};
+use crate::search::mate_score::mate_distance;
+use crate::types::core::{Move, Position};
+use serde::{Deserialize, Serialize};
+
+/// Highest hint level; it returns the full-strength best move
+pub const MAX_HINT_LEVEL: u8 = 5;
+
+/// Search depth and time per hint level, from level 1 up; depth 0 is adaptive
+const HINT_SEARCH_LIMITS: [(u8, u32); MAX_HINT_LEVEL as usize] =
+    [(1, 200), (2, 300), (3, 500), (5, 1_000), (0, 3_000)];
+
+/// Smallest term improvement, in centipawns, worth mentioning in a reason
+const MIN_TERM_GAIN_CP: i32 = 10;
+
+/// Search depth and time limit for a hint level, clamped to `1..=MAX_HINT_LEVEL`
+pub fn hint_search_limits(level: u8) -> (u8, u32) {
+    HINT_SEARCH_LIMITS[usize::from(level.clamp(1, MAX_HINT_LEVEL) - 1)]
+}
+
+/// A suggested move for the side to move
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hint {
+    pub level: u8,
+    /// Suggested move in USI notation
+    pub usi: String,
+    /// Origin square of the arrow; `None` for a drop
+    pub from: Option<String>,
+    /// Destination square of the arrow
+    pub to: String,
+    /// Search score in centipawns from the side to move's perspective
+    pub score: i32,
+    /// Human-readable reason in the engine's language, e.g. "develops the
+    /// silver toward the attack"
+    pub reason: String,
+}
+
+impl Hint {
+    /// Build a hint for `mv`. `before` and `after` are breakdowns of the position
+    /// before and after the move, both from the mover's perspective,
+    /// `enemy_king` is the opponent's king square if it is on the board, and
+    /// `threats` are the threats the move creates.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        locale: Locale,
+        level: u8,
+        mv: &Move,
+        score: i32,
+        gives_check: bool,
+        enemy_king: Option<Position>,
+        before: &EvaluationBreakdown,
+        after: &EvaluationBreakdown,
+        threats: &[Threat],
+    ) -> Self {
+        Self {
+            level: level.clamp(1, MAX_HINT_LEVEL),
+            usi: mv.to_usi_string(),
+            from: mv.from.map(|from| from.to_string()),
+            to: mv.to.to_string(),
+            score,
+            reason: describe_move(
+                locale,
+                mv,
+                score,
+                gives_check,
+                enemy_king,
+                before,
+                after,
+                threats,
+            ),
+        }
+    }
+}
+
+/// Reason for playing `mv` in `locale`: forced mates first, then what the move
+/// does on the board and the most valuable threat it creates, then the
+/// evaluation term it improves the most
+#[allow(clippy::too_many_arguments)]
+pub fn describe_move(
+    locale: Locale,
+    mv: &Move,
+    score: i32,
+    gives_check: bool,
+    enemy_king: Option<Position>,
+    before: &EvaluationBreakdown,
+    after: &EvaluationBreakdown,
+    threats: &[Threat],
+) -> String {
+    let piece = i18n::piece_name(locale, mv.piece_type);
+    let with_piece = |key: &str| i18n::text_with(locale, key, &[("piece", &piece)]);
+    if let Some(plies) = mate_distance(score).filter(|plies| *plies > 0) {
+        let moves = (plies + 1) / 2;
+        return if moves == 1 {
+            i18n::text(locale, "hint.mates_immediately")
+        } else {
+            i18n::text_with(locale, "hint.forced_mate", &[("moves", &moves)])
+        };
+    }
+
+    let mut actions = Vec::new();
+    if let Some(captured) = &mv.captured_piece {
+        let captured = i18n::piece_name(locale, captured.piece_type);
+        actions.push(i18n::text_with(locale, "hint.captures", &[("piece", &captured)]));
+    } else if mv.is_capture {
+        actions.push(i18n::text(locale, "hint.wins_material"));
+    }
+    if gives_check {
+        actions.push(i18n::text(locale, "hint.gives_check"));
+    }
+    if mv.is_promotion {
+        actions.push(with_piece("hint.promotes"));
+    }
+    if let Some(threat) = threats.iter().max_by_key(|threat| threat.value) {
+        actions.push(describe_threat(locale, threat));
+    }
+    if !actions.is_empty() {
+        return actions.join(&i18n::text(locale, "hint.and"));
+    }
+
+    let toward_attack = match (mv.from, enemy_king) {
+        (Some(from), Some(king)) => mv.to.distance_to(king) < from.distance_to(king),
+        _ => false,
+    };
+    match best_term_gain(before, after) {
+        Some("development") if toward_attack => with_piece("hint.develops_toward_attack"),
+        Some("development") => with_piece("hint.develops"),
+        Some("mobility") if toward_attack => with_piece("hint.activates_toward_attack"),
+        Some("mobility") => with_piece("hint.activates"),
+        Some("king_safety" | "castle_patterns") => i18n::text(locale, "hint.king_safety"),
+        Some("center_control") => with_piece("hint.centre"),
+        Some("pawn_structure") => i18n::text(locale, "hint.pawn_structure"),
+        Some("coordination") => i18n::text(locale, "hint.coordination"),
+        Some("tactical_patterns" | "threats") => i18n::text(locale, "hint.tactical_threat"),
+        Some("opening_principles") => i18n::text(locale, "hint.opening_principles"),
+        Some("endgame_patterns") => i18n::text(locale, "hint.endgame"),
+        _ if mv.from.is_none() => with_piece("hint.drop"),
+        _ if toward_attack => with_piece("hint.toward_attack"),
+        _ => with_piece("hint.improves"),
+    }
+}
+
+fn describe_threat(locale: Locale, threat: &Threat) -> String {
+    let target = |index: usize| {
+        threat
+            .targets
+            .get(index)
+            .map_or_else(String::new, |&(_, piece)| i18n::piece_name(locale, piece))
+    };
+    match threat.kind {
+        ThreatKind::HangingPiece => {
+            i18n::text_with(locale, "hint.attacks_loose", &[("piece", &target(0))])
+        }
+        ThreatKind::Fork => i18n::text_with(
+            locale,
+            "hint.fork",
+            &[("first", &target(0)), ("second", &target(1))],
+        ),
+        ThreatKind::Skewer => i18n::text_with(
+            locale,
+            "hint.skewer",
+            &[("first", &target(0)), ("second", &target(1))],
+        ),
+    }
+}
+
+/// Name of the evaluation term the move improves the most, if any by a noticeable margin
+fn best_term_gain<'a>(
+    before: &EvaluationBreakdown,
+    after: &'a EvaluationBreakdown,
+) -> Option<&'a str> {
+    after
+        .terms
+        .iter()
+        .filter(|term| term.name != "tempo")
+        .map(|term| {
+            let previous = before.term(&term.name).map_or(0, |t| t.score);
+            (term.name.as_str(), term.score - previous)
+        })
+        .filter(|(_, gain)| *gain >= MIN_TERM_GAIN_CP)
+        .max_by_key(|(_, gain)| *gain)
+        .map(|(name, _)| name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::mate_score::mate_in;
+    use crate::types::core::{Piece, PieceType, Player};
+    use crate::types::evaluation::TaperedScore;
+    use std::collections::HashMap;
+
+    fn breakdown(terms: &[(&str, i32)]) -> EvaluationBreakdown {
+        let components: HashMap<String, TaperedScore> = terms
+            .iter()
+            .map(|(name, score)| (name.to_string(), TaperedScore::new(*score)))
+            .collect();
+        EvaluationBreakdown::from_components(Player::Black, 0, 0, &components)
+    }
+
+    fn silver_up() -> Move {
+        Move::new_move(
+            Position::new(8, 3),
+            Position::new(7, 3),
+            PieceType::Silver,
+            Player::Black,
+            false,
+        )
+    }
+
+    #[test]
+    fn test_limits_grow_with_level() {
+        assert_eq!(hint_search_limits(0), hint_search_limits(1));
+        assert_eq!(hint_search_limits(MAX_HINT_LEVEL).0, 0);
+        assert!(hint_search_limits(2).0 > hint_search_limits(1).0);
+    }
+
+    #[test]
+    fn test_reason_from_evaluation_terms() {
+        let before = breakdown(&[("development", 0), ("mobility", 5)]);
+        let after = breakdown(&[("development", 40), ("mobility", 10)]);
+        let enemy_king = Some(Position::new(0, 4));
+        let hint =
+            Hint::new(Locale::En, 1, &silver_up(), 20, false, enemy_king, &before, &after, &[]);
+        assert_eq!(hint.reason, "develops the silver toward the attack");
+        assert_eq!(hint.from.as_deref(), Some("6i"));
+        assert_eq!(hint.to, "6h");
+
+        // Nothing improved noticeably: fall back to the move itself
+        let reason =
+            describe_move(Locale::En, &silver_up(), 0, false, None, &before, &before, &[]);
+        assert_eq!(reason, "improves the position of the silver");
+    }
+
+    #[test]
+    fn test_reason_from_move_metadata() {
+        let neutral = breakdown(&[]);
+        let mut capture = silver_up();
+        capture.is_capture = true;
+        capture.captured_piece = Some(Piece::new(PieceType::Bishop, Player::White));
+        assert_eq!(
+            describe_move(Locale::En, &capture, 300, true, None, &neutral, &neutral, &[]),
+            "captures the bishop and gives check"
+        );
+        assert_eq!(
+            describe_move(Locale::Ja, &capture, 300, true, None, &neutral, &neutral, &[]),
+            "角を取る、王手をかける"
+        );
+        assert_eq!(
+            describe_move(
+                Locale::En,
+                &silver_up(),
+                mate_in(3),
+                true,
+                None,
+                &neutral,
+                &neutral,
+                &[]
+            ),
+            "starts a forced mate in 2 moves"
+        );
+
+        let fork = Threat {
+            kind: ThreatKind::Fork,
+            attacker: Position::new(4, 4),
+            targets: vec![
+                (Position::new(2, 3), PieceType::Rook),
+                (Position::new(2, 5), PieceType::Gold),
+            ],
+            value: PieceType::Gold.base_value(),
+        };
+        assert_eq!(
+            describe_move(Locale::En, &silver_up(), 0, true, None, &neutral, &neutral, &[fork]),
+            "gives check and forks the rook and the gold"
+        );
+    }
+}
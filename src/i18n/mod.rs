@@ -0,0 +1,164 @@
+//! Message Catalog
+//!
+//! User-facing text the engine produces (hint reasons, game-end reasons,
+//! position issues, opening names) is looked up by message key in a per-locale
+//! catalog instead of being written inline, so a GUI can show it in the
+//! player's language. The catalogs are TOML files compiled into the binary,
+//! with `{name}` placeholders filled in by the caller. A key missing from a
+//! catalog falls back to English, and a key missing from English to the key
+//! itself, so a gap shows up as text rather than an error.
+
+use crate::types::core::{PieceType, Player};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+const EN_CATALOG: &str = include_str!("en.toml");
+const JA_CATALOG: &str = include_str!("ja.toml");
+
+static CATALOGS: OnceLock<[HashMap<String, String>; 2]> = OnceLock::new();
+
+/// Language of engine-produced text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Ja,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::Ja];
+
+    /// Read a locale code such as `ja` or `ja-JP`; `None` for one without a catalog
+    pub fn parse(code: &str) -> Option<Self> {
+        let language = code.split(['-', '_']).next().unwrap_or_default();
+        match language.to_ascii_lowercase().as_str() {
+            "en" => Some(Locale::En),
+            "ja" => Some(Locale::Ja),
+            _ => None,
+        }
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Ja => "ja",
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+fn catalogs() -> &'static [HashMap<String, String>; 2] {
+    CATALOGS.get_or_init(|| [load_catalog(EN_CATALOG), load_catalog(JA_CATALOG)])
+}
+
+/// Flatten a catalog's tables into dotted keys, e.g. `hint.gives_check`
+fn load_catalog(source: &str) -> HashMap<String, String> {
+    fn flatten(prefix: &str, table: &toml::Table, messages: &mut HashMap<String, String>) {
+        for (name, value) in table {
+            let key = if prefix.is_empty() { name.clone() } else { format!("{}.{}", prefix, name) };
+            match value {
+                toml::Value::String(text) => {
+                    messages.insert(key, text.clone());
+                }
+                toml::Value::Table(table) => flatten(&key, table, messages),
+                _ => {}
+            }
+        }
+    }
+
+    // The catalogs are compiled in and checked by the tests below
+    let mut messages = HashMap::new();
+    flatten("", &source.parse().unwrap_or_default(), &mut messages);
+    messages
+}
+
+/// Message `key` in `locale`, without placeholders filled in
+pub fn text(locale: Locale, key: &str) -> String {
+    let [en, ja] = catalogs();
+    let catalog = match locale {
+        Locale::En => en,
+        Locale::Ja => ja,
+    };
+    catalog
+        .get(key)
+        .or_else(|| en.get(key))
+        .cloned()
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// Message `key` in `locale` with each `{name}` placeholder replaced by its argument
+pub fn text_with(locale: Locale, key: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    args.iter().fold(text(locale, key), |message, (name, value)| {
+        message.replace(&format!("{{{}}}", name), &value.to_string())
+    })
+}
+
+pub fn player_name(locale: Locale, player: Player) -> String {
+    match player {
+        Player::Black => text(locale, "player.black"),
+        Player::White => text(locale, "player.white"),
+    }
+}
+
+pub fn piece_name(locale: Locale, piece_type: PieceType) -> String {
+    let key = match piece_type {
+        PieceType::Pawn => "piece.pawn",
+        PieceType::Lance => "piece.lance",
+        PieceType::Knight => "piece.knight",
+        PieceType::Silver => "piece.silver",
+        PieceType::Gold => "piece.gold",
+        PieceType::Bishop => "piece.bishop",
+        PieceType::Rook => "piece.rook",
+        PieceType::King => "piece.king",
+        PieceType::PromotedPawn => "piece.promoted_pawn",
+        PieceType::PromotedLance => "piece.promoted_lance",
+        PieceType::PromotedKnight => "piece.promoted_knight",
+        PieceType::PromotedSilver => "piece.promoted_silver",
+        PieceType::PromotedBishop => "piece.promoted_bishop",
+        PieceType::PromotedRook => "piece.promoted_rook",
+    };
+    text(locale, key)
+}
+
+/// Display name of a book opening, e.g. `Yagura`; unknown names are kept as they are
+pub fn opening_name(locale: Locale, name: &str) -> String {
+    let key = format!("opening.{}", name.to_ascii_lowercase().replace(' ', "_"));
+    match text(locale, &key) {
+        missing if missing == key => name.to_string(),
+        translated => translated,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalogs_cover_english_keys() {
+        let [en, ja] = catalogs();
+        assert!(!en.is_empty());
+        let missing: Vec<_> = en.keys().filter(|key| !ja.contains_key(*key)).collect();
+        assert!(missing.is_empty(), "keys missing from ja.toml: {:?}", missing);
+        let unknown: Vec<_> = ja.keys().filter(|key| !en.contains_key(*key)).collect();
+        assert!(unknown.is_empty(), "keys only in ja.toml: {:?}", unknown);
+    }
+
+    #[test]
+    fn test_lookup_and_fallback() {
+        let piece = piece_name(Locale::Ja, PieceType::Silver);
+        assert_eq!(text_with(Locale::Ja, "hint.captures", &[("piece", &piece)]), "銀を取る");
+        assert_eq!(text(Locale::En, "no.such.key"), "no.such.key");
+        assert_eq!(opening_name(Locale::Ja, "Yagura"), "矢倉");
+        assert_eq!(opening_name(Locale::Ja, "Unlisted Line"), "Unlisted Line");
+        assert_eq!(Locale::parse("ja-JP"), Some(Locale::Ja));
+        assert_eq!(Locale::parse("fr"), None);
+    }
+}
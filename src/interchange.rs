@@ -0,0 +1,392 @@
+//! Interchange Formats
+//!
+//! Serde support for `BitboardBoard`, `CapturedPieces` and `Move`, so sessions,
+//! analysis caches and the Tauri layer can store and send them directly.
+//!
+//! Human-readable formats such as JSON get a stable schema:
+//!
+//! - a board is `{"board": <SFEN board field>, "side_to_move": "Black"}`
+//! - a hand is `{"black": ["Rook", "Pawn"], "white": []}`
+//! - a move is an object with `from` (`null` for drops), `to`, `piece_type`,
+//!   `player` and `is_promotion`. The search hints `is_capture`,
+//!   `captured_piece`, `gives_check` and `is_recapture` may be left out.
+//!
+//! Binary formats get the compact encodings of `to_bytes`, which can also be
+//! used on their own. Those encodings are fixed-size:
+//!
+//! - a board is 82 bytes, one per square from 9a in board order, then the side to move
+//! - a hand is 14 bytes, the counts of R B G S N L P for black and then white
+//! - a move is 5 bytes
+//!
+//! A piece is one byte: 0 for none, otherwise `PieceType::to_u8` plus one, with
+//! the high bit set for white.
+
+use crate::bitboards::BitboardBoard;
+use crate::error::ShogiError;
+use crate::position_format::HAND_ORDER;
+use crate::types::{CapturedPieces, Move, Piece, PieceType, Player, Position};
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use std::fmt;
+
+/// Length of `board_to_bytes`
+pub const BOARD_BYTES: usize = 82;
+/// Length of `hand_to_bytes`
+pub const HAND_BYTES: usize = 14;
+/// Length of `move_to_bytes`
+pub const MOVE_BYTES: usize = 5;
+
+const WHITE_BIT: u8 = 0x80;
+const NO_SQUARE: u8 = 0xFF;
+
+fn piece_to_byte(piece: Option<Piece>) -> u8 {
+    match piece {
+        None => 0,
+        Some(piece) => {
+            let code = piece.piece_type.to_u8() + 1;
+            if piece.player == Player::White {
+                code | WHITE_BIT
+            } else {
+                code
+            }
+        }
+    }
+}
+
+fn piece_from_byte(byte: u8) -> Result<Option<Piece>, ShogiError> {
+    let code = byte & !WHITE_BIT;
+    if code == 0 {
+        return if byte == 0 { Ok(None) } else { Err(invalid("piece", byte)) };
+    }
+    if code > 14 {
+        return Err(invalid("piece", byte));
+    }
+    let player = if byte & WHITE_BIT != 0 { Player::White } else { Player::Black };
+    Ok(Some(Piece::new(PieceType::from_u8(code - 1), player)))
+}
+
+fn square_from_byte(byte: u8) -> Result<Position, ShogiError> {
+    if byte >= 81 {
+        return Err(invalid("square", byte));
+    }
+    Ok(Position::from_index(byte))
+}
+
+fn invalid(what: &str, byte: u8) -> ShogiError {
+    ShogiError::parse(format!("Invalid {} byte {:#04x}", what, byte))
+}
+
+fn expect_len(what: &str, bytes: &[u8], len: usize) -> Result<(), ShogiError> {
+    if bytes.len() != len {
+        return Err(ShogiError::parse(format!(
+            "{} takes {} bytes, got {}",
+            what,
+            len,
+            bytes.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Compact encoding of `board`, including the side to move
+pub fn board_to_bytes(board: &BitboardBoard) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(BOARD_BYTES);
+    for index in 0..81 {
+        bytes.push(piece_to_byte(board.get_piece(Position::from_index(index))));
+    }
+    bytes.push(u8::from(board.side_to_move() == Player::White));
+    bytes
+}
+
+pub fn board_from_bytes(bytes: &[u8]) -> Result<BitboardBoard, ShogiError> {
+    expect_len("A board", bytes, BOARD_BYTES)?;
+    let mut board = BitboardBoard::empty();
+    for (index, &byte) in (0..81).zip(bytes) {
+        if let Some(piece) = piece_from_byte(byte)? {
+            board.place_piece(piece, Position::from_index(index));
+        }
+    }
+    board.set_side_to_move(match bytes[81] {
+        0 => Player::Black,
+        1 => Player::White,
+        byte => return Err(invalid("side to move", byte)),
+    });
+    Ok(board)
+}
+
+/// Compact encoding of both hands; the order pieces were captured in is not kept
+pub fn hand_to_bytes(captured_pieces: &CapturedPieces) -> Vec<u8> {
+    [Player::Black, Player::White]
+        .into_iter()
+        .flat_map(|player| {
+            HAND_ORDER
+                .into_iter()
+                .map(move |piece| captured_pieces.count(piece, player) as u8)
+        })
+        .collect()
+}
+
+pub fn hand_from_bytes(bytes: &[u8]) -> Result<CapturedPieces, ShogiError> {
+    expect_len("A hand", bytes, HAND_BYTES)?;
+    let mut captured_pieces = CapturedPieces::new();
+    for (i, &count) in bytes.iter().enumerate() {
+        let player = if i < HAND_ORDER.len() { Player::Black } else { Player::White };
+        let piece = HAND_ORDER[i % HAND_ORDER.len()];
+        if count > 18 {
+            return Err(ShogiError::parse(format!("{} {:?} in one hand", count, piece)));
+        }
+        for _ in 0..count {
+            captured_pieces.add_piece(piece, player);
+        }
+    }
+    Ok(captured_pieces)
+}
+
+/// Compact encoding of `mv`: from square (0xFF for drops), to square, piece
+/// type, flags and captured piece
+pub fn move_to_bytes(mv: &Move) -> [u8; MOVE_BYTES] {
+    let flags = [mv.is_promotion, mv.is_capture, mv.gives_check, mv.is_recapture]
+        .into_iter()
+        .enumerate()
+        .fold(0, |flags, (bit, set)| flags | (u8::from(set) << bit));
+    [
+        mv.from.map_or(NO_SQUARE, Position::to_u8),
+        mv.to.to_u8(),
+        mv.piece_type.to_u8(),
+        flags | if mv.player == Player::White { WHITE_BIT } else { 0 },
+        piece_to_byte(mv.captured_piece),
+    ]
+}
+
+pub fn move_from_bytes(bytes: &[u8]) -> Result<Move, ShogiError> {
+    expect_len("A move", bytes, MOVE_BYTES)?;
+    let from = match bytes[0] {
+        NO_SQUARE => None,
+        byte => Some(square_from_byte(byte)?),
+    };
+    if bytes[2] >= 14 {
+        return Err(invalid("piece type", bytes[2]));
+    }
+    let flags = bytes[3];
+    if flags & !(WHITE_BIT | 0x0F) != 0 {
+        return Err(invalid("move flags", flags));
+    }
+    Ok(Move {
+        from,
+        to: square_from_byte(bytes[1])?,
+        piece_type: PieceType::from_u8(bytes[2]),
+        player: if flags & WHITE_BIT != 0 { Player::White } else { Player::Black },
+        is_promotion: flags & 1 != 0,
+        is_capture: flags & 2 != 0,
+        captured_piece: piece_from_byte(bytes[4])?,
+        gives_check: flags & 4 != 0,
+        is_recapture: flags & 8 != 0,
+    })
+}
+
+/// Reads a byte string, or a sequence of bytes from formats without one
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a byte string")
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+        Ok(bytes.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, bytes: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(bytes)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+fn deserialize_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    deserializer.deserialize_bytes(BytesVisitor)
+}
+
+#[derive(Serialize, Deserialize)]
+struct BoardSchema {
+    board: String,
+    side_to_move: Player,
+}
+
+impl Serialize for BitboardBoard {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !serializer.is_human_readable() {
+            return serializer.serialize_bytes(&board_to_bytes(self));
+        }
+        BoardSchema { board: self.to_string(), side_to_move: self.side_to_move() }
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for BitboardBoard {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if !deserializer.is_human_readable() {
+            let bytes = deserialize_bytes(deserializer)?;
+            return board_from_bytes(&bytes).map_err(de::Error::custom);
+        }
+        let schema = BoardSchema::deserialize(deserializer)?;
+        let side = if schema.side_to_move == Player::White { 'w' } else { 'b' };
+        let (board, _, _) = BitboardBoard::from_fen(&format!("{} {} - 1", schema.board, side))
+            .map_err(de::Error::custom)?;
+        Ok(board)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct HandSchema {
+    black: Vec<PieceType>,
+    white: Vec<PieceType>,
+}
+
+impl Serialize for CapturedPieces {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !serializer.is_human_readable() {
+            return serializer.serialize_bytes(&hand_to_bytes(self));
+        }
+        HandSchema { black: self.black.clone(), white: self.white.clone() }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CapturedPieces {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if !deserializer.is_human_readable() {
+            let bytes = deserialize_bytes(deserializer)?;
+            return hand_from_bytes(&bytes).map_err(de::Error::custom);
+        }
+        let schema = HandSchema::deserialize(deserializer)?;
+        Ok(CapturedPieces { black: schema.black, white: schema.white })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct MoveSchema {
+    from: Option<Position>,
+    to: Position,
+    piece_type: PieceType,
+    player: Player,
+    is_promotion: bool,
+    #[serde(default)]
+    is_capture: bool,
+    #[serde(default)]
+    captured_piece: Option<Piece>,
+    #[serde(default)]
+    gives_check: bool,
+    #[serde(default)]
+    is_recapture: bool,
+}
+
+impl Serialize for Move {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !serializer.is_human_readable() {
+            return serializer.serialize_bytes(&move_to_bytes(self));
+        }
+        MoveSchema {
+            from: self.from,
+            to: self.to,
+            piece_type: self.piece_type,
+            player: self.player,
+            is_promotion: self.is_promotion,
+            is_capture: self.is_capture,
+            captured_piece: self.captured_piece,
+            gives_check: self.gives_check,
+            is_recapture: self.is_recapture,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Move {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if !deserializer.is_human_readable() {
+            let bytes = deserialize_bytes(deserializer)?;
+            return move_from_bytes(&bytes).map_err(de::Error::custom);
+        }
+        let schema = MoveSchema::deserialize(deserializer)?;
+        Ok(Move {
+            from: schema.from,
+            to: schema.to,
+            piece_type: schema.piece_type,
+            player: schema.player,
+            is_promotion: schema.is_promotion,
+            is_capture: schema.is_capture,
+            captured_piece: schema.captured_piece,
+            gives_check: schema.gives_check,
+            is_recapture: schema.is_recapture,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SFEN: &str =
+        "ln1g1g1nl/1ks2r3/1pppp1bpp/p3spp2/9/P1P1P3P/1PSPSPPP1/1BK1GR3/LN1G3NL w Pp 30";
+
+    #[test]
+    fn test_json_round_trips() {
+        let (board, _, captured) = BitboardBoard::from_fen(SFEN).unwrap();
+
+        let json = serde_json::to_value(&board).unwrap();
+        assert_eq!(json["board"], SFEN.split(' ').next().unwrap());
+        assert_eq!(json["side_to_move"], "White");
+        let read: BitboardBoard = serde_json::from_value(json).unwrap();
+        assert_eq!(board_to_bytes(&read), board_to_bytes(&board));
+
+        let read: CapturedPieces =
+            serde_json::from_str(&serde_json::to_string(&captured).unwrap()).unwrap();
+        assert_eq!(read.black, vec![PieceType::Pawn]);
+        assert_eq!(read.white, vec![PieceType::Pawn]);
+
+        let mv: Move = serde_json::from_value(serde_json::json!({
+            "from": null,
+            "to": {"row": 4, "col": 4},
+            "piece_type": "Pawn",
+            "player": "White",
+            "is_promotion": false,
+        }))
+        .unwrap();
+        assert_eq!(mv, Move::new_drop(PieceType::Pawn, Position::new(4, 4), Player::White));
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let (board, _, captured) = BitboardBoard::from_fen(SFEN).unwrap();
+        let bytes = board_to_bytes(&board);
+        assert_eq!(bytes.len(), BOARD_BYTES);
+        let read = board_from_bytes(&bytes).unwrap();
+        assert_eq!(read.to_string(), board.to_string());
+        assert_eq!(read.side_to_move(), Player::White);
+
+        let read = hand_from_bytes(&hand_to_bytes(&captured)).unwrap();
+        assert_eq!(read.count(PieceType::Pawn, Player::Black), 1);
+        assert_eq!(read.count(PieceType::Pawn, Player::White), 1);
+
+        let mut mv = Move::new_move(
+            Position::new(6, 5),
+            Position::new(2, 5),
+            PieceType::Rook,
+            Player::White,
+            true,
+        );
+        mv.is_capture = true;
+        mv.captured_piece = Some(Piece::new(PieceType::Pawn, Player::Black));
+        assert_eq!(move_from_bytes(&move_to_bytes(&mv)).unwrap(), mv);
+
+        assert!(board_from_bytes(&bytes[..81]).is_err());
+        assert!(move_from_bytes(&[0, 81, 0, 0, 0]).is_err());
+    }
+}
@@ -0,0 +1,228 @@
+//! Piece Count Invariants
+//!
+//! A move never creates or destroys a piece: a capture takes one of the
+//! opponent's pieces into the mover's hand and a drop puts one from the hand
+//! back on the board. Counting each player's pieces by unpromoted kind, board
+//! and hand together, therefore gives numbers that only change by the piece a
+//! move captures. State corruption (a dropped piece left in the hand, a capture
+//! credited to the wrong player as in the player-swap report) shows up as a
+//! count that moved when it should not have.
+//!
+//! The checks run on every position load and on every make and unmake in the
+//! search. They are on in debug builds, where a violation in the search panics,
+//! and can be turned on in release builds with `debug invariants on`, where
+//! violations are reported as `info string` lines and counted.
+
+use crate::bitboards::BitboardBoard;
+use crate::types::board::CapturedPieces;
+use crate::types::core::{PieceType, Player};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use thiserror::Error;
+
+/// Unpromoted piece kinds, in `PieceType::as_index` order
+pub const KINDS: [PieceType; 8] = [
+    PieceType::Pawn,
+    PieceType::Lance,
+    PieceType::Knight,
+    PieceType::Silver,
+    PieceType::Gold,
+    PieceType::Bishop,
+    PieceType::Rook,
+    PieceType::King,
+];
+
+/// Pieces of each kind in a full set, both players together
+pub const FULL_SET: [u8; 8] = [18, 4, 4, 4, 4, 2, 2, 2];
+
+static CHECKS_ENABLED: AtomicBool = AtomicBool::new(cfg!(debug_assertions));
+static VIOLATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Whether positions and moves are checked
+pub fn checks_enabled() -> bool {
+    CHECKS_ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn set_checks_enabled(enabled: bool) {
+    CHECKS_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Violations reported since the process started
+pub fn violation_count() -> u64 {
+    VIOLATIONS.load(Ordering::Relaxed)
+}
+
+/// A broken invariant
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum InvariantViolation {
+    #[error("{count} {kind:?} on board and in hand, a full set has {max}")]
+    TooMany { kind: PieceType, count: u8, max: u8 },
+
+    #[error("{player:?} has {count} kings")]
+    ExtraKing { player: Player, count: u8 },
+
+    #[error("{kind:?} in {player:?}'s hand")]
+    NotDroppable { kind: PieceType, player: Player },
+
+    #[error("{player:?} has {found} {kind:?} after the move, expected {expected}")]
+    CountChanged { kind: PieceType, player: Player, expected: u8, found: u8 },
+}
+
+/// Each player's pieces by unpromoted kind, board and hand together
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PieceCounts([[u8; 8]; 2]);
+
+fn player_index(player: Player) -> usize {
+    match player {
+        Player::Black => 0,
+        Player::White => 1,
+    }
+}
+
+fn kind_index(piece_type: PieceType) -> usize {
+    piece_type.unpromoted_version().unwrap_or(piece_type).as_index()
+}
+
+impl PieceCounts {
+    pub fn of(board: &BitboardBoard, captured_pieces: &CapturedPieces) -> Self {
+        let mut counts = Self::default();
+        for (_, piece) in board.iter_pieces() {
+            counts.0[player_index(piece.player)][kind_index(piece.piece_type)] += 1;
+        }
+        for (player, hand) in
+            [(Player::Black, &captured_pieces.black), (Player::White, &captured_pieces.white)]
+        {
+            for &piece_type in hand {
+                counts.0[player_index(player)][kind_index(piece_type)] += 1;
+            }
+        }
+        counts
+    }
+
+    pub fn count(&self, kind: PieceType, player: Player) -> u8 {
+        self.0[player_index(player)][kind_index(kind)]
+    }
+
+    /// Both players' pieces of `kind`
+    pub fn total(&self, kind: PieceType) -> u8 {
+        self.count(kind, Player::Black) + self.count(kind, Player::White)
+    }
+
+    /// The counts after `player` moves, capturing `captured` if any
+    pub fn after_move(&self, player: Player, captured: Option<PieceType>) -> Self {
+        let mut counts = *self;
+        if let Some(kind) = captured {
+            counts.0[player_index(player)][kind_index(kind)] += 1;
+            let opponent = &mut counts.0[player_index(player.opposite())][kind_index(kind)];
+            *opponent = opponent.saturating_sub(1);
+        }
+        counts
+    }
+
+    /// `Ok` when `found` has the same counts as `self`
+    pub fn expect(&self, found: &PieceCounts) -> Result<(), InvariantViolation> {
+        for player in [Player::Black, Player::White] {
+            for kind in KINDS {
+                let (expected, found) = (self.count(kind, player), found.count(kind, player));
+                if expected != found {
+                    return Err(InvariantViolation::CountChanged { kind, player, expected, found });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Check a position on its own: no more pieces of a kind than a full set, at
+/// most one king each and only droppable pieces in hand. Handicap and tsume
+/// positions have fewer pieces, so missing pieces are not an error.
+pub fn check_position(
+    board: &BitboardBoard,
+    captured_pieces: &CapturedPieces,
+) -> Result<PieceCounts, InvariantViolation> {
+    for (player, hand) in
+        [(Player::Black, &captured_pieces.black), (Player::White, &captured_pieces.white)]
+    {
+        if let Some(&kind) = hand
+            .iter()
+            .find(|kind| **kind == PieceType::King || kind.unpromoted_version().is_some())
+        {
+            return Err(InvariantViolation::NotDroppable { kind, player });
+        }
+    }
+    let counts = PieceCounts::of(board, captured_pieces);
+    for (kind, max) in KINDS.into_iter().zip(FULL_SET) {
+        let count = counts.total(kind);
+        if count > max {
+            return Err(InvariantViolation::TooMany { kind, count, max });
+        }
+    }
+    for player in [Player::Black, Player::White] {
+        let count = counts.count(PieceType::King, player);
+        if count > 1 {
+            return Err(InvariantViolation::ExtraKing { player, count });
+        }
+    }
+    Ok(counts)
+}
+
+/// Report a violation found at `context`: a panic in debug builds, an
+/// `info string` line otherwise
+pub fn report(context: &str, violation: &InvariantViolation) {
+    VIOLATIONS.fetch_add(1, Ordering::Relaxed);
+    if cfg!(debug_assertions) {
+        panic!("invariant violated at {}: {}", context, violation);
+    }
+    crate::search::info_sink::emit_info(&format!(
+        "info string error invariant violated at {}: {}",
+        context, violation
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_follow_captures_and_drops() {
+        let (board, _, captured) = BitboardBoard::from_fen(
+            "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+        )
+        .unwrap();
+        let counts = check_position(&board, &captured).unwrap();
+        for (kind, full) in KINDS.into_iter().zip(FULL_SET) {
+            assert_eq!(counts.total(kind), full);
+        }
+
+        let after = counts.after_move(Player::Black, Some(PieceType::Pawn));
+        assert_eq!(after.count(PieceType::Pawn, Player::Black), 10);
+        assert_eq!(counts.expect(&counts.after_move(Player::White, None)), Ok(()));
+        assert_eq!(
+            counts.expect(&after),
+            Err(InvariantViolation::CountChanged {
+                kind: PieceType::Pawn,
+                player: Player::Black,
+                expected: 9,
+                found: 10,
+            })
+        );
+    }
+
+    #[test]
+    fn test_check_position_rejects_impossible_hands() {
+        let (board, _, mut captured) =
+            BitboardBoard::from_fen("4k4/9/9/9/9/9/9/9/4K4 b 2R 1").unwrap();
+        assert!(check_position(&board, &captured).is_ok());
+
+        captured.add_piece(PieceType::Rook, Player::White);
+        assert_eq!(
+            check_position(&board, &captured),
+            Err(InvariantViolation::TooMany { kind: PieceType::Rook, count: 3, max: 2 })
+        );
+        captured.white.clear();
+        captured.add_piece(PieceType::PromotedSilver, Player::White);
+        assert!(matches!(
+            check_position(&board, &captured),
+            Err(InvariantViolation::NotDroppable { player: Player::White, .. })
+        ));
+    }
+}
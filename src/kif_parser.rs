@@ -1,23 +1,85 @@
 //! KIF Format Parser
 //!
 //! Parser for Japanese Shogi KIF (棋譜) format game files
-//! Supports parsing game metadata, moves, and positions
-
+//! Supports parsing game metadata, moves, and positions. Per-move comments,
+//! time consumption and variations (変化) are preserved so that imported
+//! commentary survives a round trip through `to_kif_string()`. Games that
+//! start from a set position carry it as a BOD diagram in the header.
+
+use crate::bod;
+use crate::game_status::GameTracker;
+use crate::handicap::Handicap;
+use crate::notation::NotationStyle;
+use crate::position_format::PositionText;
+use serde::{Deserialize, Serialize};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 // Note: Move and Player types are available but not directly imported here
 
+/// Header line that starts the move section
+const MOVE_SECTION_HEADER: &str = "手数----指手---------消費時間--";
+
+/// Time consumption recorded for a move, e.g. `( 0:12/00:01:34)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KifMoveTime {
+    /// Time spent on this move in seconds
+    pub move_secs: u32,
+    /// Cumulative time used by the mover in seconds
+    pub total_secs: u32,
+}
+
+impl KifMoveTime {
+    /// Parse the contents of a time field (`0:12/00:01:34`)
+    fn parse(text: &str) -> Option<Self> {
+        let (move_part, total_part) = text.split_once('/')?;
+        Some(Self {
+            move_secs: parse_clock(move_part.trim())?,
+            total_secs: parse_clock(total_part.trim())?,
+        })
+    }
+
+    fn to_kif_string(self) -> String {
+        format!(
+            "({:>2}:{:02}/{:02}:{:02}:{:02})",
+            self.move_secs / 60,
+            self.move_secs % 60,
+            self.total_secs / 3600,
+            self.total_secs / 60 % 60,
+            self.total_secs % 60
+        )
+    }
+}
+
+/// Parse `m:ss` or `h:mm:ss` into seconds
+fn parse_clock(text: &str) -> Option<u32> {
+    text.split(':')
+        .try_fold(0u32, |acc, part| Some(acc * 60 + part.trim().parse::<u32>().ok()?))
+}
+
 /// Parsed move from KIF file
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct KifMove {
     pub move_number: usize,
     pub move_text: String,
     pub usi_move: Option<String>,
-    pub comment: Option<String>,
+    /// Comment lines (`*...`) following the move, without the leading `*`
+    pub comments: Vec<String>,
+    pub time: Option<KifMoveTime>,
+}
+
+/// A variation (変化) branching off another line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KifVariation {
+    /// Move number of the first move of the variation; it replaces the move with
+    /// the same number in the parent line
+    pub start_move: usize,
+    /// Index of the parent variation in `KifGame::variations`, `None` for the main line
+    pub parent: Option<usize>,
+    pub moves: Vec<KifMove>,
 }
 
 /// Game metadata from KIF header
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct KifMetadata {
     pub date: Option<String>,
     pub time_control: Option<String>,
@@ -27,10 +89,22 @@ pub struct KifMetadata {
 }
 
 /// Complete parsed KIF game
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct KifGame {
     pub metadata: KifMetadata,
+    /// All `key：value` header lines in file order, including ones without a
+    /// dedicated metadata field
+    pub headers: Vec<(String, String)>,
+    /// Starting position as SFEN when the header has a BOD diagram; `None`
+    /// for games from the position `手合割` names
+    #[serde(default)]
+    pub initial_sfen: Option<String>,
+    /// Comments on the initial position
+    pub comments: Vec<String>,
+    /// Main line
     pub moves: Vec<KifMove>,
+    /// Variations in file order
+    pub variations: Vec<KifVariation>,
 }
 
 impl KifGame {
@@ -50,15 +124,14 @@ impl KifGame {
     pub fn from_string(content: &str) -> Result<Self, String> {
         let lines: Vec<&str> = content.lines().collect();
 
-        let mut metadata = KifMetadata {
-            date: None,
-            time_control: None,
-            player1_name: None,
-            player2_name: None,
-            game_type: None,
-        };
-
-        let mut moves = Vec::new();
+        let mut metadata = KifMetadata::default();
+        let mut headers = Vec::new();
+        let mut bod_lines = Vec::new();
+        let mut comments = Vec::new();
+        let mut moves: Vec<KifMove> = Vec::new();
+        let mut variations: Vec<KifVariation> = Vec::new();
+        // Open lines while reading variations: None is the main line
+        let mut line_stack: Vec<Option<usize>> = vec![None];
         let mut in_move_section = false;
 
         for line in lines {
@@ -68,67 +141,175 @@ impl KifGame {
                 continue;
             }
 
+            if !in_move_section && bod::is_bod_line(trimmed) {
+                bod_lines.push(line);
+                continue;
+            }
+
             // Parse metadata using substring to avoid UTF-8 boundary issues
-            if trimmed.starts_with("開始日時：") {
-                metadata.date = Some(
-                    trimmed
-                        .split_once("開始日時：")
-                        .map(|(_, v)| v)
-                        .unwrap_or("")
-                        .to_string(),
-                );
-            } else if trimmed.starts_with("終了日時：") {
-                // End date - could be used for game duration
-            } else if trimmed.starts_with("持ち時間：") {
-                metadata.time_control = Some(
-                    trimmed
-                        .split_once("持ち時間：")
-                        .map(|(_, v)| v)
-                        .unwrap_or("")
-                        .to_string(),
-                );
-            } else if trimmed.starts_with("先手：") {
-                metadata.player1_name = Some(
-                    trimmed
-                        .split_once("先手：")
-                        .map(|(_, v)| v)
-                        .unwrap_or("")
-                        .to_string(),
-                );
-            } else if trimmed.starts_with("後手：") {
-                metadata.player2_name = Some(
-                    trimmed
-                        .split_once("後手：")
-                        .map(|(_, v)| v)
-                        .unwrap_or("")
-                        .to_string(),
-                );
-            } else if trimmed.starts_with("手合割：") {
-                metadata.game_type = Some(
-                    trimmed
-                        .split_once("手合割：")
-                        .map(|(_, v)| v)
-                        .unwrap_or("")
-                        .to_string(),
-                );
-            } else if trimmed.starts_with("手数") || trimmed.starts_with("手-----") {
+            if !in_move_section && !trimmed.starts_with('*') {
+                if let Some((key, value)) = trimmed.split_once('：') {
+                    let value = value.to_string();
+                    match key {
+                        "開始日時" => metadata.date = Some(value.clone()),
+                        "持ち時間" => metadata.time_control = Some(value.clone()),
+                        "先手" => metadata.player1_name = Some(value.clone()),
+                        "後手" => metadata.player2_name = Some(value.clone()),
+                        "手合割" => metadata.game_type = Some(value.clone()),
+                        _ => {}
+                    }
+                    headers.push((key.to_string(), value));
+                    continue;
+                }
+            }
+
+            if trimmed.starts_with("手数") || trimmed.starts_with("手-----") {
                 // Move header - start of move section
                 in_move_section = true;
-                continue;
+            } else if let Some(rest) = trimmed.strip_prefix("変化：") {
+                // Variation header: "変化：3手"
+                let start_move: usize = rest
+                    .trim_end_matches('手')
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("Invalid variation header: {}", trimmed))?;
+                // A variation branches from the most recent open line that starts
+                // before it; lines starting at or after it are finished
+                while line_stack.len() > 1 {
+                    let top = line_stack[line_stack.len() - 1].expect("only the root is None");
+                    if variations[top].start_move >= start_move {
+                        line_stack.pop();
+                    } else {
+                        break;
+                    }
+                }
+                variations.push(KifVariation {
+                    start_move,
+                    parent: *line_stack.last().expect("main line is never popped"),
+                    moves: Vec::new(),
+                });
+                line_stack.push(Some(variations.len() - 1));
+                in_move_section = true;
+            } else if let Some(comment) = trimmed.strip_prefix('*') {
+                let current = match line_stack.last() {
+                    Some(Some(index)) => &mut variations[*index].moves,
+                    _ => &mut moves,
+                };
+                match current.last_mut() {
+                    Some(last) => last.comments.push(comment.to_string()),
+                    None => comments.push(comment.to_string()),
+                }
             } else if in_move_section && trimmed.starts_with(char::is_numeric) {
                 // Parse move line
                 if let Some(kif_move) = Self::parse_move_line(trimmed) {
-                    moves.push(kif_move);
+                    match line_stack.last() {
+                        Some(Some(index)) => variations[*index].moves.push(kif_move),
+                        _ => moves.push(kif_move),
+                    }
                 }
             }
         }
 
-        Ok(KifGame { metadata, moves })
+        let initial_sfen = if bod_lines.is_empty() {
+            None
+        } else {
+            let position = bod::parse(&bod_lines.join("\n"))
+                .map_err(|e| format!("Invalid starting position: {}", e))?;
+            Some(position.to_sfen())
+        };
+
+        Ok(KifGame {
+            metadata,
+            headers,
+            initial_sfen,
+            comments,
+            moves,
+            variations,
+        })
+    }
+
+    /// A record of the USI moves played from `sfen` (the even starting position
+    /// when `None`), for exporting a game; other starting positions are kept
+    /// as a BOD diagram
+    pub fn from_usi_moves(sfen: Option<&str>, moves: &[String]) -> Result<Self, String> {
+        let even = PositionText::from_sfen(Handicap::Even.sfen())?.to_sfen();
+        let mut tracker = GameTracker::from_sfen(sfen.unwrap_or(&even))?;
+        let start = tracker.position_text().to_sfen();
+
+        let mut kif_moves = Vec::with_capacity(moves.len());
+        for (index, usi) in moves.iter().enumerate() {
+            let move_text =
+                tracker.notation_context().convert(usi, NotationStyle::Usi, NotationStyle::Kif)?;
+            tracker.play_usi(usi)?;
+            kif_moves.push(KifMove {
+                move_number: index + 1,
+                move_text,
+                usi_move: Some(usi.clone()),
+                comments: Vec::new(),
+                time: None,
+            });
+        }
+
+        let mut metadata = KifMetadata::default();
+        let mut headers = Vec::new();
+        let mut initial_sfen = None;
+        if start == even {
+            metadata.game_type = Some("平手".to_string());
+            headers.push(("手合割".to_string(), "平手".to_string()));
+        } else {
+            initial_sfen = Some(start);
+        }
+        Ok(KifGame {
+            metadata,
+            headers,
+            initial_sfen,
+            comments: Vec::new(),
+            moves: kif_moves,
+            variations: Vec::new(),
+        })
+    }
+
+    /// Serialize the game back to KIF, including comments, times and variations
+    pub fn to_kif_string(&self) -> String {
+        let mut out = String::new();
+        for (key, value) in &self.headers {
+            out.push_str(&format!("{}：{}\n", key, value));
+        }
+        if let Some(position) =
+            self.initial_sfen.as_deref().and_then(|sfen| PositionText::from_sfen(sfen).ok())
+        {
+            out.push_str(&bod::render(&position));
+        }
+        out.push_str(MOVE_SECTION_HEADER);
+        out.push('\n');
+        for comment in &self.comments {
+            out.push_str(&format!("*{}\n", comment));
+        }
+        Self::write_moves(&mut out, &self.moves);
+
+        for variation in &self.variations {
+            out.push_str(&format!("\n変化：{}手\n", variation.start_move));
+            Self::write_moves(&mut out, &variation.moves);
+        }
+        out
+    }
+
+    fn write_moves(out: &mut String, moves: &[KifMove]) {
+        for kif_move in moves {
+            out.push_str(&format!("{:>4} {}", kif_move.move_number, kif_move.move_text));
+            if let Some(time) = kif_move.time {
+                out.push_str(&format!("   {}", time.to_kif_string()));
+            }
+            out.push('\n');
+            for comment in &kif_move.comments {
+                out.push_str(&format!("*{}\n", comment));
+            }
+        }
     }
 
     /// Parse a single move line from KIF format
     fn parse_move_line(line: &str) -> Option<KifMove> {
-        // Parse format: "   1 ７六歩(77)"
+        // Parse format: "   1 ７六歩(77)   ( 0:01/00:00:01)"
         let parts: Vec<&str> = line.split_whitespace().collect();
 
         if parts.len() < 2 {
@@ -138,14 +319,14 @@ impl KifGame {
         let move_number: usize = parts[0].parse().ok()?;
         let move_text = parts[1].to_string();
 
-        // Try to extract comment if present
-        let comment = if line.contains('(') {
-            let start = line.find('(')?;
-            let end = line.find(')')?;
-            Some(line[start + 1..end].to_string())
-        } else {
-            None
-        };
+        // Time consumption is the last parenthesised field containing a '/'
+        let time = line
+            .rfind('(')
+            .filter(|&start| line[start..].contains('/'))
+            .and_then(|start| {
+                let field = &line[start + 1..];
+                KifMoveTime::parse(field.split(')').next()?)
+            });
 
         // Convert to USI format (simplified for now)
         let usi_move = Self::kif_to_usi(&move_text);
@@ -154,7 +335,8 @@ impl KifGame {
             move_number,
             move_text,
             usi_move,
-            comment,
+            comments: Vec::new(),
+            time,
         })
     }
 
@@ -290,6 +472,118 @@ mod tests {
         assert_eq!(kif_move.move_text, "７六歩(77)");
     }
 
+    const ANNOTATED_KIF: &str = "開始日時：2024/01/02 10:00:00
+先手：Alice
+後手：Bob
+戦型：相掛かり
+手数----指手---------消費時間--
+*Opening comment
+   1 ７六歩(77)   ( 0:05/00:00:05)
+*Most popular first move
+   2 ３四歩(33)   ( 0:10/00:00:10)
+   3 ２六歩(27)   ( 1:02/00:01:07)
+   4 投了   ( 0:01/00:00:11)
+
+変化：3手
+   3 ６六歩(67)   ( 0:03/00:00:08)
+*Quieter
+   4 ８四歩(83)   ( 0:01/00:00:11)
+
+変化：4手
+   4 ４四歩(43)   ( 0:02/00:00:12)
+
+変化：3手
+   3 ２二角成(88)   ( 0:01/00:00:06)
+";
+
+    #[test]
+    fn test_comments_and_times_are_preserved() {
+        let game = KifGame::from_string(ANNOTATED_KIF).unwrap();
+        assert_eq!(game.metadata.player1_name.as_deref(), Some("Alice"));
+        assert!(game.headers.contains(&("戦型".to_string(), "相掛かり".to_string())));
+        assert_eq!(game.comments, vec!["Opening comment"]);
+        assert_eq!(game.moves.len(), 4);
+        assert_eq!(game.moves[0].comments, vec!["Most popular first move"]);
+        assert_eq!(
+            game.moves[2].time,
+            Some(KifMoveTime {
+                move_secs: 62,
+                total_secs: 67
+            })
+        );
+        assert_eq!(game.moves[2].usi_move.as_deref(), Some("2g2f"));
+    }
+
+    #[test]
+    fn test_variation_tree() {
+        let game = KifGame::from_string(ANNOTATED_KIF).unwrap();
+        assert_eq!(game.variations.len(), 3);
+
+        assert_eq!(game.variations[0].start_move, 3);
+        assert_eq!(game.variations[0].parent, None);
+        assert_eq!(game.variations[0].moves[0].comments, vec!["Quieter"]);
+
+        // Branches off the first variation at move 4
+        assert_eq!(game.variations[1].start_move, 4);
+        assert_eq!(game.variations[1].parent, Some(0));
+
+        // Sibling of the first variation, branching off the main line again
+        assert_eq!(game.variations[2].start_move, 3);
+        assert_eq!(game.variations[2].parent, None);
+        assert_eq!(game.variations[2].moves[0].usi_move.as_deref(), Some("8h2b+"));
+    }
+
+    #[test]
+    fn test_round_trip_through_export() {
+        let game = KifGame::from_string(ANNOTATED_KIF).unwrap();
+        let exported = game.to_kif_string();
+        assert_eq!(KifGame::from_string(&exported).unwrap(), game);
+    }
+
+    #[test]
+    fn test_bod_starting_position() {
+        let kif = "手合割：平手
+後手の持駒：なし
+  ９ ８ ７ ６ ５ ４ ３ ２ １
++---------------------------+
+| ・ ・ ・ ・v玉 ・ ・ ・ ・|一
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|二
+| ・ ・ ・ ・ 歩 ・ ・ ・ ・|三
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|四
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|五
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|六
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|七
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|八
+| ・ ・ ・ ・ 玉 ・ ・ ・ ・|九
++---------------------------+
+先手の持駒：金
+先手番
+手数----指手---------消費時間--
+   1 ５二金打
+   2 詰み
+";
+        let game = KifGame::from_string(kif).unwrap();
+        assert_eq!(game.initial_sfen.as_deref(), Some("4k4/9/4P4/9/9/9/9/9/4K4 b G 1"));
+        assert_eq!(game.headers, vec![("手合割".to_string(), "平手".to_string())]);
+        assert_eq!(game.moves.len(), 2);
+        assert_eq!(KifGame::from_string(&game.to_kif_string()).unwrap(), game);
+    }
+
+    #[test]
+    fn test_record_from_usi_moves() {
+        let moves = vec!["7g7f".to_string(), "3c3d".to_string()];
+        let mut game = KifGame::from_usi_moves(None, &moves).unwrap();
+        game.moves[1].time = Some(KifMoveTime { move_secs: 75, total_secs: 3_725 });
+        let kif = game.to_kif_string();
+        assert!(kif.starts_with("手合割：平手\n手数----"));
+        assert!(kif.contains("   1 ７六歩(77)\n"));
+        assert!(kif.contains("   2 ３四歩(33)   ( 1:15/01:02:05)\n"));
+
+        let tsume = KifGame::from_usi_moves(Some("4k4/9/4P4/9/9/9/9/9/4K4 b G 1"), &[]).unwrap();
+        assert!(tsume.headers.is_empty());
+        assert!(tsume.to_kif_string().contains("先手の持駒：金"));
+    }
+
     #[test]
     fn test_kif_to_usi() {
         // Test basic pawn move conversion
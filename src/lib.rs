@@ -1,24 +1,47 @@
 use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 use rand::SeedableRng;
-use serde::{Deserialize, Serialize};
 use std::sync::{
     atomic::{AtomicBool, Ordering},
     Arc, Mutex,
 };
 
+pub mod asset_storage;
 pub mod bitboards;
+pub mod board_diff;
+pub mod board_matrix;
+pub mod bod;
+pub mod candidates;
 pub mod config;
+pub mod csa_client;
+pub mod csa_parser;
 pub mod debug_utils;
+pub mod drop_rules;
 pub mod error;
 pub mod evaluation;
+pub mod explain;
+pub mod game_status;
+pub mod handicap;
+pub mod hint;
+pub mod i18n;
+pub mod interchange;
+pub mod invariants;
 pub mod kif_parser;
+pub mod move_hints;
+pub mod move_metadata;
+pub mod move_times;
 pub mod moves;
+pub mod notation;
 pub mod opening_book;
 pub mod opening_book_converter;
+pub mod position_format;
+pub mod score_trend;
 pub mod search;
+pub mod sharpness;
+pub mod statistics_hub;
 pub mod tablebase;
 pub mod time_utils;
+pub mod tsume;
 pub mod tuning;
 pub mod types;
 pub mod weights;
@@ -42,37 +65,33 @@ pub mod patterns {
 }
 
 pub mod usi;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
+use error::ShogiError;
 use evaluation::pst_loader::{PieceSquareTableConfig, PieceSquareTablePreset};
+use handicap::Handicap;
 use moves::*;
 use opening_book::OpeningBook;
+use search::adaptive_configuration::{
+    AdaptiveConfigurationManager, CalibrationSample, MachineProfile,
+};
+use search::analysis_cache::{AnalysisCache, CachedAnalysis};
+use search::clock_contempt::{ClockMode, Clocks};
+use search::info_verbosity::{InfoFields, InfoVerbosity};
+use search::perspective::EvaluationPerspective;
+use search::repetition::RepetitionEntry;
 use search::search_engine::SearchEngine;
+use search::search_watchdog::SearchPanic;
+use search::transposition_config::TranspositionConfig;
 use search::ParallelSearchConfig;
+use statistics_hub::{StatisticsHub, StatisticsSnapshot, TablebaseCounters};
 use tablebase::MicroTablebase;
 use types::*;
 
 // Re-export BitboardBoard for external use
 pub use bitboards::BitboardBoard;
 
-#[derive(Serialize, Deserialize)]
-struct PieceJson {
-    position: PositionJson,
-    piece_type: String,
-    player: String,
-}
-
-#[derive(Serialize, Deserialize)]
-struct PositionJson {
-    row: u8,
-    col: u8,
-}
-
-#[derive(Serialize, Deserialize)]
-struct CapturedPieceJson {
-    piece_type: String,
-    player: String,
-}
-
 #[derive(Clone)]
 pub struct ShogiEngine {
     board: BitboardBoard,
@@ -80,15 +99,71 @@ pub struct ShogiEngine {
     current_player: Player,
     opening_book: OpeningBook,
     opening_book_prefilled: bool,
+    /// Opening names by move sequence, built from the book on first use
+    opening_trie: Option<opening_book::OpeningTrie>,
+    /// Castle plans that bias root move ordering once the book runs out
+    shape_book: opening_book::ShapeBook,
+    /// Moves played since an even-game `position startpos`; `None` for other setups
+    game_moves: Option<Vec<String>>,
     tablebase: MicroTablebase,
     stop_flag: Arc<AtomicBool>,
     search_engine: Arc<Mutex<SearchEngine>>,
+    /// Statistics the search engine publishes after every iteration, for
+    /// `get_search_statistics` while a search holds the engine
+    search_statistics: Arc<Mutex<search::statistics::SearchStatisticsReport>>,
     debug_mode: bool,
     pondering: bool,
     depth: u8,
     thread_count: usize,
     parallel_options: ParallelOptions,
     pst_config: PieceSquareTableConfig,
+    /// Tuned evaluation weights file (`WeightsFile`, or `SHOGI_WEIGHTS_FILE` at startup)
+    weights_path: Option<String>,
+    /// Reproducible searches for debugging and regression bisecting (`Determinism` option)
+    deterministic: bool,
+    /// Second-key TT verification for measuring collisions (`TTKeyVerification`)
+    tt_key_verification: bool,
+    /// Spread candidate analysis over `thread_count` workers that each search
+    /// their own root moves (`AnalysisRootSplit`)
+    analysis_root_split: bool,
+    /// Recursion limit of the main search (`MaxSearchPly`)
+    max_search_ply: u16,
+    /// Handicap setup (`Handicap` option): `position startpos` uses its starting
+    /// position and the evaluator offsets the material White gave up
+    handicap: Handicap,
+    /// Baseline for `statshub delta`
+    statistics_hub: StatisticsHub,
+    /// Transposition table size (`USI_Hash`)
+    hash_size_mb: usize,
+    /// Move-ordering cache size picked by `auto_configure`, kept across `USI_Hash` changes
+    move_ordering_cache_size: Option<usize>,
+    /// Play and prefill from the opening book (`USI_OwnBook`)
+    own_book: bool,
+    /// Report an expected reply with `bestmove` and accept `go ponder` (`USI_Ponder`)
+    ponder_enabled: bool,
+    /// Search briefly on the first `isready` before the first real move (`WarmUp`)
+    warm_up: bool,
+    /// Whether the warm-up search has run
+    warmed_up: bool,
+    /// Positions before the current one since the `position` command's start,
+    /// for perpetual check detection
+    position_history: Vec<RepetitionEntry>,
+    /// Perspective of reported scores (`ScorePerspective`)
+    score_perspective: EvaluationPerspective,
+    /// Append the win probability to `info` scores (`ShowWinRate`)
+    show_win_rate: bool,
+    /// How often `info` lines are sent and which fields they carry (`Info*`)
+    info_verbosity: InfoVerbosity,
+    /// Language of hint reasons and other text meant for players (`Language`)
+    locale: i18n::Locale,
+    /// Destination of the last move of the `position` command, for `same_square`
+    /// in move metadata
+    last_move_to: Option<Position>,
+    /// Deepest `go infinite` analysis of each position, shared with the clones
+    /// that run background searches
+    analysis_cache: Arc<Mutex<AnalysisCache>>,
+    /// Both clocks of the current `go`, for clock-aware play; `None` without a clock
+    clocks: Option<Clocks>,
 }
 
 impl ShogiEngine {
@@ -101,21 +176,47 @@ impl ShogiEngine {
             current_player: Player::Black,
             opening_book: OpeningBook::new(),
             opening_book_prefilled: false,
+            opening_trie: None,
+            shape_book: opening_book::ShapeBook::new(),
+            game_moves: Some(Vec::new()),
             tablebase: MicroTablebase::new(),
             stop_flag: stop_flag.clone(),
             search_engine: Arc::new(Mutex::new(SearchEngine::new(Some(stop_flag), 16))),
+            search_statistics: Arc::default(),
             debug_mode: true,
             pondering: false,
             depth: 0, // Default to 0 (unlimited/adaptive), like YaneuraOu
             thread_count,
             parallel_options: ParallelOptions::default(),
             pst_config: PieceSquareTableConfig::default(),
+            weights_path: None,
+            deterministic: false,
+            tt_key_verification: false,
+            analysis_root_split: false,
+            max_search_ply: search::ply_guard::DEFAULT_MAX_PLY,
+            handicap: Handicap::Even,
+            statistics_hub: StatisticsHub::new(),
+            hash_size_mb: 16,
+            move_ordering_cache_size: None,
+            own_book: true,
+            ponder_enabled: false,
+            warm_up: true,
+            warmed_up: false,
+            position_history: Vec::new(),
+            score_perspective: EvaluationPerspective::default(),
+            show_win_rate: false,
+            info_verbosity: InfoVerbosity::default(),
+            locale: i18n::Locale::default(),
+            last_move_to: None,
+            analysis_cache: Arc::new(Mutex::new(AnalysisCache::default())),
+            clocks: None,
         };
         engine.parallel_options.enable_parallel = thread_count > 1;
         engine.parallel_options.hash_size_mb = 16;
 
         if let Ok(mut search_engine_guard) = engine.search_engine.lock() {
             search_engine_guard.set_parallel_options(engine.parallel_options.clone());
+            search_engine_guard.set_statistics_publisher(Some(engine.search_statistics.clone()));
         }
 
         // Try to load persisted preferences (thread count)
@@ -130,6 +231,13 @@ impl ShogiEngine {
             ));
         }
 
+        if let Ok(path) = std::env::var("SHOGI_WEIGHTS_FILE") {
+            engine.weights_path = Some(path);
+            if let Err(err) = engine.apply_weights_file() {
+                crate::utils::telemetry::debug_log(&format!("[Weights] {}", err));
+            }
+        }
+
         engine
     }
 
@@ -158,12 +266,210 @@ impl ShogiEngine {
         self.sync_parallel_options();
     }
 
+    fn read_prefs() -> serde_json::Map<String, serde_json::Value> {
+        std::fs::read(Self::prefs_path())
+            .ok()
+            .and_then(|data| serde_json::from_slice::<serde_json::Value>(&data).ok())
+            .and_then(|json| match json {
+                serde_json::Value::Object(map) => Some(map),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Set `key` in the prefs file, keeping the other entries
+    fn write_pref(key: &str, value: serde_json::Value) {
+        let mut prefs = Self::read_prefs();
+        prefs.insert(key.to_string(), value);
+        let obj = serde_json::Value::Object(prefs);
+        let _ = std::fs::write(
+            Self::prefs_path(),
+            serde_json::to_vec_pretty(&obj).unwrap_or_default(),
+        );
+    }
+
     fn save_prefs(&self) {
-        let path = Self::prefs_path();
-        let obj = serde_json::json!({
-            "thread_count": self.thread_count
+        Self::write_pref("thread_count", serde_json::json!(self.thread_count));
+    }
+
+    /// Apply this machine's saved profile, or calibrate and save one on first run
+    ///
+    /// A `thread_count` the user saved with `USI_Threads` wins over the profile's.
+    pub fn auto_configure(&mut self) -> Option<MachineProfile> {
+        let prefs = Self::read_prefs();
+        let machine_id = search::adaptive_configuration::machine_id();
+        let saved = prefs
+            .get("auto_profile")
+            .and_then(|value| serde_json::from_value::<MachineProfile>(value.clone()).ok())
+            .filter(|profile| profile.machine_id == machine_id);
+
+        let profile = match saved {
+            Some(profile) => profile,
+            None => {
+                let sample = Self::calibrate();
+                let mut manager = AdaptiveConfigurationManager::new(TranspositionConfig::default());
+                let profile = match manager.recommend_profile(&machine_id, sample) {
+                    Ok(profile) => profile,
+                    Err(err) => {
+                        crate::utils::telemetry::debug_log(&format!(
+                            "[AutoConfig] No profile recommended: {}",
+                            err
+                        ));
+                        return None;
+                    }
+                };
+                Self::write_pref(
+                    "auto_profile",
+                    serde_json::to_value(&profile).unwrap_or_default(),
+                );
+                profile
+            }
+        };
+
+        if !prefs.contains_key("thread_count") {
+            self.thread_count = profile.thread_count.clamp(1, 32);
+            self.parallel_options.enable_parallel = self.thread_count > 1;
+        }
+        self.move_ordering_cache_size = Some(profile.move_ordering_cache_size);
+        self.set_hash_size(profile.hash_size_mb);
+        crate::utils::telemetry::debug_log(&format!(
+            "[AutoConfig] {}: hash {} MB, {} threads, ordering cache {}",
+            profile.machine_id,
+            self.hash_size_mb,
+            self.thread_count,
+            profile.move_ordering_cache_size
+        ));
+        Some(profile)
+    }
+
+    /// Short single-threaded search from the starting position, measuring speed
+    /// and cache behaviour for `auto_configure`
+    pub fn calibrate() -> CalibrationSample {
+        const CALIBRATION_TIME_MS: u32 = 1000;
+        let mut search_engine = SearchEngine::new(None, 16);
+        // Progress lines come from a thread of their own, which the suppression misses
+        let verbosity = search_engine.info_verbosity();
+        search_engine.set_info_verbosity(InfoVerbosity {
+            interval_ms: 0,
+            currmove: false,
+            ..verbosity
+        });
+        let board = BitboardBoard::new();
+        let captured_pieces = CapturedPieces::new();
+        let mut searcher =
+            search::search_engine::IterativeDeepening::new(64, CALIBRATION_TIME_MS, None);
+        let start = std::time::Instant::now();
+        let _ = search::info_sink::with_info_suppressed(|| {
+            searcher.search(&mut search_engine, &board, &captured_pieces, Player::Black)
         });
-        let _ = std::fs::write(path, serde_json::to_vec_pretty(&obj).unwrap_or_default());
+        let elapsed_ms = start.elapsed().as_millis().max(1) as u64;
+
+        let snapshot = search_engine.statistics_snapshot();
+        let ordering_lookups =
+            snapshot.move_ordering.cache_hits + snapshot.move_ordering.cache_misses;
+        let (available_memory_bytes, logical_cores) =
+            search::adaptive_configuration::system_resources();
+        CalibrationSample {
+            nodes_per_second: snapshot.search.nodes * 1000 / elapsed_ms,
+            tt_hit_rate: snapshot.search.tt_hit_rate / 100.0,
+            ordering_cache_hit_rate: if ordering_lookups == 0 {
+                0.0
+            } else {
+                snapshot.move_ordering.cache_hits as f64 / ordering_lookups as f64
+            },
+            logical_cores,
+            available_memory_bytes,
+        }
+    }
+
+    /// Search the starting position briefly so the first real move is not slowed
+    /// by cold caches, lazily built tables and memory not yet faulted in. Runs
+    /// once per engine, on the first `isready` with the `WarmUp` option on, and
+    /// leaves what it learned in the transposition table. Returns the `info
+    /// string` to report, or `None` when no warm-up ran.
+    pub fn warm_up(&mut self) -> Option<String> {
+        const WARM_UP_TIME_MS: u32 = 200;
+        if !self.warm_up || self.warmed_up {
+            return None;
+        }
+        self.warmed_up = true;
+        let mut search_engine_guard = self.search_engine.try_lock().ok()?;
+        let verbosity = search_engine_guard.info_verbosity();
+        // Progress lines come from a thread of their own, which the suppression misses
+        search_engine_guard.set_info_verbosity(InfoVerbosity {
+            interval_ms: 0,
+            currmove: false,
+            ..verbosity
+        });
+        let board = BitboardBoard::new();
+        let captured_pieces = CapturedPieces::new();
+        let mut searcher =
+            search::search_engine::IterativeDeepening::new(64, WARM_UP_TIME_MS, None);
+        let start = std::time::Instant::now();
+        let result = search::info_sink::with_info_suppressed(|| {
+            searcher.search(&mut search_engine_guard, &board, &captured_pieces, Player::Black)
+        });
+        search_engine_guard.set_info_verbosity(verbosity);
+
+        result?;
+        let statistics = search_engine_guard.get_search_statistics();
+        Some(format!(
+            "info string Warmed up in {} ms: depth {}, {} nodes",
+            start.elapsed().as_millis(),
+            statistics.iteration_times_ms.len(),
+            statistics.nodes
+        ))
+    }
+
+    /// Transposition table size in MB (`USI_Hash`)
+    pub fn hash_size_mb(&self) -> usize {
+        self.hash_size_mb
+    }
+
+    /// Recreate the search engine with a `size_mb` transposition table
+    fn set_hash_size(&mut self, size_mb: usize) -> bool {
+        let size = size_mb.clamp(1, 1024);
+        self.parallel_options.hash_size_mb = size.min(512);
+        let mut search_engine = self.new_search_engine(size, Some(self.stop_flag.clone()));
+        search_engine.set_statistics_publisher(Some(self.search_statistics.clone()));
+        let resized = match self.search_engine.lock() {
+            Ok(mut search_engine_guard) => {
+                *search_engine_guard = search_engine;
+                true
+            }
+            Err(_) => false,
+        };
+        if resized {
+            self.hash_size_mb = size;
+        }
+        self.opening_book_prefilled = false;
+        self.maybe_prefill_opening_book();
+        resized
+    }
+
+    /// A search engine with a `size_mb` transposition table and this engine's
+    /// evaluation and search settings
+    fn new_search_engine(
+        &self,
+        size_mb: usize,
+        stop_flag: Option<Arc<AtomicBool>>,
+    ) -> SearchEngine {
+        let mut search_engine = SearchEngine::new(stop_flag, size_mb);
+        search_engine.set_parallel_options(self.parallel_options.clone());
+        search_engine.get_evaluator_mut().set_handicap(self.handicap);
+        if let Some(cache_size) = self.move_ordering_cache_size {
+            search_engine.set_move_ordering_cache_size(cache_size);
+        }
+        search_engine.set_game_history(self.position_history.clone());
+        search_engine.set_score_perspective(self.score_perspective);
+        search_engine.set_show_win_rate(self.show_win_rate);
+        search_engine.set_info_verbosity(self.info_verbosity);
+        search_engine.set_tt_key_verification(self.tt_key_verification);
+        search_engine.set_max_search_ply(self.max_search_ply);
+        if let Some(ref path) = self.weights_path {
+            let _ = search_engine.get_evaluator_mut().load_tuned_weights(path);
+        }
+        search_engine
     }
 
     fn sync_parallel_options(&mut self) {
@@ -179,10 +485,55 @@ impl ShogiEngine {
         }
     }
 
+    /// Load the weights file into the evaluator, or return to the built-in
+    /// weights when none is set
+    fn apply_weights_file(&self) -> Result<(), String> {
+        let mut guard = self
+            .search_engine
+            .lock()
+            .map_err(|_| "Failed to acquire search engine lock".to_string())?;
+        let evaluator = guard.get_evaluator_mut();
+        match self.weights_path {
+            Some(ref path) => evaluator
+                .load_tuned_weights(path)
+                .map_err(|e| format!("Failed to load weights from '{}': {}", path, e)),
+            None => {
+                evaluator.set_use_tuned_weights(false);
+                Ok(())
+            }
+        }
+    }
+
+    /// `reloadweights`: re-read the weights file after editing it, without a restart
+    pub fn handle_reloadweights(&mut self) -> Vec<String> {
+        let Some(path) = self.weights_path.clone() else {
+            return vec!["info string error No WeightsFile set".to_string()];
+        };
+        let result = match self.search_engine.lock() {
+            Ok(mut guard) => {
+                guard.get_evaluator_mut().reload_tuned_weights().map_err(|e| e.to_string())
+            }
+            Err(_) => Err("Failed to acquire search engine lock".to_string()),
+        };
+        match result {
+            Ok(()) => {
+                // Scores from the old weights would be shown for revisited positions
+                if let Ok(mut cache) = self.analysis_cache.lock() {
+                    cache.clear();
+                }
+                vec![format!("info string Reloaded weights from '{}'", path)]
+            }
+            Err(err) => vec![format!(
+                "info string error Failed to reload weights from '{}': {}",
+                path, err
+            )],
+        }
+    }
+
     /// Load default opening book from embedded data
     fn load_default_opening_book(&mut self) {
         // Try to load from embedded JSON data first
-        let json_data = include_str!("ai/openingBook.json");
+        let json_data = opening_book::EMBEDDED_BOOK_JSON;
         if self.load_opening_book_from_json(json_data).is_ok() {
             crate::utils::telemetry::debug_log("Loaded default opening book from JSON");
             self.opening_book_prefilled = false;
@@ -196,7 +547,7 @@ impl ShogiEngine {
     }
 
     fn maybe_prefill_opening_book(&mut self) {
-        if self.opening_book_prefilled || !self.opening_book.is_loaded() {
+        if self.opening_book_prefilled || !self.own_book || !self.opening_book.is_loaded() {
             return;
         }
 
@@ -219,21 +570,28 @@ impl ShogiEngine {
     }
 
     /// Load opening book from binary data
-    pub fn load_opening_book_from_binary(&mut self, data: &[u8]) -> Result<(), String> {
-        self.opening_book
-            .load_from_binary(data)
-            .map_err(|e| format!("Failed to load opening book: {:?}", e))?;
+    pub fn load_opening_book_from_binary(&mut self, data: &[u8]) -> Result<(), ShogiError> {
+        self.opening_book.load_from_binary(data)?;
+        self.opening_book_prefilled = false;
+        self.opening_trie = None;
+        self.maybe_prefill_opening_book();
+        Ok(())
+    }
+
+    /// Open a binary opening book file, paging its entries in as lookups reach them
+    pub fn load_opening_book_from_file(&mut self, path: &str) -> Result<(), ShogiError> {
+        self.opening_book = OpeningBook::open_mapped(path)?;
         self.opening_book_prefilled = false;
+        self.opening_trie = None;
         self.maybe_prefill_opening_book();
         Ok(())
     }
 
     /// Load opening book from JSON data
-    pub fn load_opening_book_from_json(&mut self, json_data: &str) -> Result<(), String> {
-        self.opening_book
-            .load_from_json(json_data)
-            .map_err(|e| format!("Failed to load opening book: {:?}", e))?;
+    pub fn load_opening_book_from_json(&mut self, json_data: &str) -> Result<(), ShogiError> {
+        self.opening_book.load_from_json(json_data)?;
         self.opening_book_prefilled = false;
+        self.opening_trie = None;
         self.maybe_prefill_opening_book();
         Ok(())
     }
@@ -332,6 +690,20 @@ impl ShogiEngine {
         }
     }
 
+    /// Named opening of the current game, from its moves since `position startpos`.
+    /// `None` for games set up from an SFEN or a handicap, or before any book line.
+    pub fn current_opening(&mut self) -> Option<opening_book::OpeningMatch> {
+        let game_moves = self.game_moves.as_ref()?;
+        if self.opening_trie.is_none() {
+            self.opening_trie = Some(opening_book::OpeningTrie::from_book(
+                &mut self.opening_book,
+                Handicap::Even.sfen(),
+                opening_book::classifier::DEFAULT_CLASSIFIER_PLIES,
+            ));
+        }
+        self.opening_trie.as_ref()?.classify(game_moves)
+    }
+
     /// Get a random opening book move for variety
     pub fn get_random_opening_book_move(&mut self) -> Option<Move> {
         if !self.opening_book.is_loaded() {
@@ -384,21 +756,16 @@ impl ShogiEngine {
     }
 
     // Methods needed for WebAssembly integration
-    pub fn set_position(&mut self, board_json: &str) {
-        self.board = BitboardBoard::empty(); // Clear the board
-        if let Ok(pieces) = serde_json::from_str::<Vec<PieceJson>>(board_json) {
-            for piece_json in pieces {
-                let player = if piece_json.player == "Black" {
-                    Player::Black
-                } else {
-                    Player::White
-                };
-                if let Some(piece_type) = PieceType::from_str(&piece_json.piece_type) {
-                    let pos = Position::new(piece_json.position.row, piece_json.position.col);
-                    self.board.place_piece(Piece::new(piece_type, player), pos);
-                }
-            }
-        }
+
+    /// Replace the board with one in the `interchange` JSON schema; the side to
+    /// move comes with it. A board that does not parse leaves the engine as it was.
+    pub fn set_position(&mut self, board_json: &str) -> Result<(), ShogiError> {
+        let board: BitboardBoard = serde_json::from_str(board_json)
+            .map_err(|e| ShogiError::parse(format!("Invalid board JSON: {}", e)))?;
+        self.current_player = board.side_to_move();
+        self.board = board;
+        self.game_moves = None;
+        Ok(())
     }
 
     pub fn set_current_player(&mut self, player: &str) {
@@ -413,7 +780,6 @@ impl ShogiEngine {
         // Allow depth 0 (unlimited/adaptive) - engine will decide based on time
         self.depth = depth;
         crate::utils::telemetry::debug_log(&format!("Set depth to: {} (0 = unlimited)", depth));
-        eprintln!("DEBUG: Set depth to: {} (0 = unlimited)", depth);
     }
 
     /// Set max depth (allows 0 for unlimited)
@@ -453,6 +819,169 @@ impl ShogiEngine {
     }
 }
 
+/// How `prepare_search` left a `get_best_move` call
+enum SearchStart {
+    /// Settled without searching: a tablebase or book move, or no legal move
+    Settled(Option<Move>),
+    Search(RootSearch),
+}
+
+/// The part of `get_best_move` after the search is configured. It holds only the
+/// search engine, a copy of the position and the stop flag, so it can be moved
+/// to a search thread without the rest of the engine.
+struct RootSearch {
+    search_engine: Arc<Mutex<SearchEngine>>,
+    searcher: search::search_engine::IterativeDeepening,
+    board: BitboardBoard,
+    captured_pieces: CapturedPieces,
+    player: Player,
+    time_limit_ms: u32,
+    stop_flag: Option<Arc<AtomicBool>>,
+    deterministic: bool,
+    info_fields: InfoFields,
+    score_perspective: EvaluationPerspective,
+}
+
+impl RootSearch {
+    fn run(mut self) -> Option<Move> {
+        // A short forced mate is played at once rather than left to a shallow main search
+        if let Some(mate_move) = self.find_root_mate() {
+            return Some(mate_move);
+        }
+
+        // Try to get the search engine lock, but don't panic if it fails
+        // Note: This engine runs as a separate process communicating via USI protocol.
+        // The search runs in this process, so periodic yielding helps keep the process responsive.
+        crate::utils::telemetry::debug_log("About to lock search engine");
+        let search_engine = self.search_engine.clone();
+        let search_result = search_engine.lock().map(|mut search_engine_guard| {
+            crate::utils::telemetry::debug_log("Got search engine lock, starting search");
+            self.searcher.search(
+                &mut search_engine_guard,
+                &self.board,
+                &self.captured_pieces,
+                self.player,
+            )
+        });
+
+        crate::utils::telemetry::debug_log("Search completed, checking result");
+
+        if let Ok(Some((move_, _score))) = search_result {
+            Some(self.apply_root_variety(move_))
+        } else {
+            // Fallback to random move if search fails
+            let move_generator = MoveGenerator::new();
+            let legal_moves = move_generator.generate_legal_moves(
+                &self.board,
+                self.player,
+                &self.captured_pieces,
+            );
+            if legal_moves.is_empty() {
+                return None;
+            }
+            // Use a seeded RNG that's platform-compatible
+            let mut rng = StdRng::seed_from_u64(42); // Fixed seed for deterministic behavior
+            legal_moves.choose(&mut rng).cloned()
+        }
+    }
+
+    /// Checks-only mate probe run before the main search. A proven mate is reported
+    /// as `score mate N` and its first move returned; a probe that runs out of time or
+    /// finds nothing leaves the position to the main search.
+    fn find_root_mate(&self) -> Option<Move> {
+        let budget_ms = (self.time_limit_ms / search::mate_search::ROOT_MATE_TIME_DIVISOR)
+            .min(search::mate_search::ROOT_MATE_MAX_TIME_MS)
+            .max(1);
+        let start = std::time::Instant::now();
+        let mut searcher = search::mate_search::MateSearcher::new(
+            search::mate_search::ROOT_MATE_PLY_LIMIT,
+            Some(budget_ms),
+            self.stop_flag.clone(),
+        );
+        let search::mate_search::MateSearchResult::Mate(line) =
+            searcher.search(&self.board, &self.captured_pieces, self.player)
+        else {
+            return None;
+        };
+        let mate_move = line.first()?.clone();
+        let allowed = self
+            .search_engine
+            .lock()
+            .map(|search_engine_guard| search_engine_guard.is_root_move_allowed(&mate_move))
+            .unwrap_or(true);
+        if !allowed {
+            return None;
+        }
+
+        crate::utils::telemetry::debug_log(&format!(
+            "[GET_BEST_MOVE] Root mate probe found mate in {} plies ({} nodes)",
+            line.len(),
+            searcher.nodes()
+        ));
+        if std::env::var("SHOGI_SILENT_BENCH").is_err() {
+            let pv: Vec<String> = line.iter().map(|mv| mv.to_usi_string()).collect();
+            let score = search::mate_score::mate_in(u8::try_from(line.len()).unwrap_or(u8::MAX));
+            search::info_sink::emit_info(&self.info_fields.filter(&format!(
+                "info depth {} seldepth {} score {} time {} nodes {} pv {}",
+                line.len(),
+                line.len(),
+                search::mate_score::format_usi_score(
+                    self.score_perspective.orient(score, self.player)
+                ),
+                start.elapsed().as_millis(),
+                searcher.nodes(),
+                pv.join(" ")
+            )));
+        }
+        Some(mate_move)
+    }
+
+    /// With coach mode enabled, replace the best move by a move a human at the coach
+    /// level would plausibly find; when the clocks bias the search, by the close
+    /// move leaving the most (or least) complex position; with root variety enabled,
+    /// by a softmax pick among the root moves close to it. Deterministic mode always
+    /// plays the best move.
+    fn apply_root_variety(&self, best_move: Move) -> Move {
+        if self.deterministic {
+            return best_move;
+        }
+        let Ok(search_engine_guard) = self.search_engine.lock() else {
+            return best_move;
+        };
+        let coach = search_engine_guard.coach();
+        if coach.is_enabled() {
+            return coach
+                .select(
+                    &self.board,
+                    &self.captured_pieces,
+                    search_engine_guard.root_move_scores(),
+                    &mut rand::thread_rng(),
+                )
+                .unwrap_or(best_move);
+        }
+        let clock_mode = search_engine_guard.clock_mode();
+        if clock_mode != ClockMode::Neutral {
+            return search_engine_guard
+                .clock_contempt()
+                .select(
+                    clock_mode,
+                    &self.board,
+                    &self.captured_pieces,
+                    self.player,
+                    search_engine_guard.root_move_scores(),
+                )
+                .unwrap_or(best_move);
+        }
+        let variety = search_engine_guard.root_variety();
+        if !variety.is_enabled() {
+            return best_move;
+        }
+        variety
+            .select(search_engine_guard.root_move_scores(), &mut rand::thread_rng())
+            .unwrap_or(best_move)
+    }
+}
+
 impl ShogiEngine {
     /// Enable or disable debug logging
     pub fn set_debug_enabled(&self, enabled: bool) {
@@ -464,16 +993,220 @@ impl ShogiEngine {
         crate::utils::telemetry::is_debug_enabled()
     }
 
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    pub fn set_deterministic(&mut self, enabled: bool) {
+        self.deterministic = enabled;
+    }
+
     pub fn parallel_search_options(&self) -> ParallelOptions {
         self.parallel_options.clone()
     }
 
+    /// Start searching the current position on a background thread. `on_progress`
+    /// runs on the search thread after every completed iteration; the returned
+    /// handle can be polled, awaited or cancelled.
+    pub fn start_search<F>(
+        &mut self,
+        depth: u8,
+        time_limit_ms: u32,
+        on_progress: F,
+    ) -> search::search_handle::SearchHandle
+    where
+        F: Fn(&search::search_handle::SearchProgress) + Send + Sync + 'static,
+    {
+        let stop_flag = self.stop_flag.clone();
+        stop_flag.store(false, Ordering::Relaxed);
+        if let Ok(mut search_engine_guard) = self.search_engine.lock() {
+            search_engine_guard.set_progress_callback(Some(Arc::new(on_progress)));
+        }
+        let search_engine = self.search_engine.clone();
+        let clear_progress_callback = move || {
+            if let Ok(mut search_engine_guard) = search_engine.lock() {
+                search_engine_guard.set_progress_callback(None);
+            }
+        };
+
+        match self.prepare_search(depth, time_limit_ms, Some(stop_flag.clone())) {
+            SearchStart::Settled(best_move) => {
+                clear_progress_callback();
+                search::search_handle::SearchHandle::settled(stop_flag, Ok(best_move))
+            }
+            SearchStart::Search(root_search) => {
+                search::search_handle::SearchHandle::spawn(stop_flag, move || {
+                    let best_move = root_search.run();
+                    clear_progress_callback();
+                    best_move
+                })
+            }
+        }
+    }
+
+    /// Start a search on a worker thread that holds only the search engine and a
+    /// copy of the position. Tablebase and book moves are found on the calling
+    /// thread, as is a panic before the search starts; a panic on the worker is
+    /// carried by the handle for `search_panic`.
+    pub fn spawn_search(
+        &mut self,
+        depth: u8,
+        time_limit_ms: u32,
+        stop_flag: Arc<AtomicBool>,
+    ) -> search::search_handle::SearchHandle {
+        let start = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            self.prepare_search(depth, time_limit_ms, Some(stop_flag.clone()))
+        }));
+        match start {
+            Ok(SearchStart::Search(root_search)) => {
+                search::search_handle::SearchHandle::spawn(stop_flag, move || root_search.run())
+            }
+            Ok(SearchStart::Settled(best_move)) => {
+                search::search_handle::SearchHandle::settled(stop_flag, Ok(best_move))
+            }
+            Err(payload) => search::search_handle::SearchHandle::settled(stop_flag, Err(payload)),
+        }
+    }
+
+    /// `go mate` without a time limit on a worker thread, so `stop` can end it
+    pub fn spawn_mate_search(
+        &self,
+        stop_flag: Arc<AtomicBool>,
+    ) -> search::search_handle::SearchHandle<search::mate_search::MateSearchResult> {
+        let board = self.board.clone();
+        let captured_pieces = self.captured_pieces.clone();
+        let player = self.current_player;
+        let search_stop_flag = stop_flag.clone();
+        search::search_handle::SearchHandle::spawn(stop_flag, move || {
+            search::mate_search::MateSearcher::new(
+                search::mate_search::DEFAULT_MATE_PLY_LIMIT,
+                None,
+                Some(search_stop_flag),
+            )
+            .search(&board, &captured_pieces, player)
+        })
+    }
+
+    /// Start analysing the current position. Every completed iteration of the
+    /// coming search is cached; an analysis cached on an earlier visit is
+    /// returned for immediate display and its best move seeded into the
+    /// transposition table, so the search picks up where that one left off.
+    pub fn begin_cached_analysis(&mut self) -> Option<CachedAnalysis> {
+        let mut search_engine_guard = self.search_engine.lock().ok()?;
+        let key = search_engine_guard.position_hash(
+            &self.board,
+            &self.captured_pieces,
+            self.current_player,
+        );
+        let cache = self.analysis_cache.clone();
+        search_engine_guard.set_progress_callback(Some(Arc::new(
+            move |progress: &search::search_handle::SearchProgress| {
+                if let Ok(mut cache) = cache.lock() {
+                    cache.record(key, CachedAnalysis::from_progress(progress));
+                }
+            },
+        )));
+
+        let cached = self.analysis_cache.lock().ok()?.get(key).cloned()?;
+        let best_move = cached.pv.first().and_then(|usi| {
+            MoveGenerator::new()
+                .generate_legal_moves(&self.board, self.current_player, &self.captured_pieces)
+                .into_iter()
+                .find(|mv| mv.to_usi_string() == *usi)
+        });
+        if let Some(best_move) = best_move {
+            search_engine_guard.seed_tt_entry(
+                &self.board,
+                &self.captured_pieces,
+                self.current_player,
+                best_move,
+                cached.score,
+                cached.depth,
+            );
+        }
+        Some(cached)
+    }
+
+    /// Stop caching the iterations of the search started after `begin_cached_analysis`
+    pub fn end_cached_analysis(&mut self) {
+        if let Ok(mut search_engine_guard) = self.search_engine.lock() {
+            search_engine_guard.set_progress_callback(None);
+        }
+    }
+
+    /// `get_best_move` that survives a panic inside the search
+    ///
+    /// The panic is logged with the position, the search engine is rebuilt (its
+    /// lock is poisoned and its state half-updated) and the error carries a legal
+    /// fallback move for the caller to play.
+    pub fn get_best_move_guarded(
+        &mut self,
+        depth: u8,
+        time_limit_ms: u32,
+        stop_flag: Option<Arc<AtomicBool>>,
+    ) -> Result<Option<Move>, SearchPanic> {
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            self.get_best_move(depth, time_limit_ms, stop_flag)
+        }));
+        result.map_err(|payload| self.search_panic(payload))
+    }
+
+    /// Log a search panic with the position, rebuild the search engine and pick
+    /// a fallback move
+    pub fn search_panic(&mut self, payload: Box<dyn std::any::Any + Send>) -> SearchPanic {
+        let message = search::search_watchdog::panic_message(payload.as_ref());
+        let sfen = self.board.to_fen(self.current_player, &self.captured_pieces);
+        crate::utils::logging::error(
+            "search",
+            &format!("Search panicked in position {}: {}", sfen, message),
+        );
+        self.recover_search_engine();
+        let fallback = search::search_watchdog::fallback_move(
+            &self.board,
+            &self.captured_pieces,
+            self.current_player,
+        );
+        SearchPanic {
+            message,
+            sfen,
+            fallback,
+        }
+    }
+
+    /// Replace the search engine after a panic poisoned its lock, keeping the
+    /// hash size, parallel options, handicap and PST configuration
+    fn recover_search_engine(&mut self) {
+        self.search_engine.clear_poison();
+        self.set_hash_size(self.hash_size_mb);
+        if let Err(err) = self.apply_pst_config() {
+            crate::utils::logging::warn(
+                "search",
+                &format!("PST configuration not restored after a search panic: {}", err),
+            );
+        }
+    }
+
     pub fn get_best_move(
         &mut self,
         depth: u8,
         time_limit_ms: u32,
         stop_flag: Option<Arc<AtomicBool>>,
     ) -> Option<Move> {
+        match self.prepare_search(depth, time_limit_ms, stop_flag) {
+            SearchStart::Settled(best_move) => best_move,
+            SearchStart::Search(root_search) => root_search.run(),
+        }
+    }
+
+    /// Everything of `get_best_move` that needs the whole engine: tablebase and
+    /// book moves, the legal-move check and the search configuration. What is
+    /// left is a `RootSearch` that can run on any thread.
+    fn prepare_search(
+        &mut self,
+        depth: u8,
+        time_limit_ms: u32,
+        stop_flag: Option<Arc<AtomicBool>>,
+    ) -> SearchStart {
         // CRITICAL DEBUG: Log the engine's internal state at the very beginning
         let fen = self
             .board
@@ -509,7 +1242,10 @@ impl ShogiEngine {
                 .probe(&self.board, self.current_player, &self.captured_pieces)
         {
             crate::debug_utils::end_timing("tablebase_check", "GET_BEST_MOVE");
-            if let Some(best_move) = tablebase_result.best_move {
+            if let Some(best_move) = tablebase_result
+                .best_move
+                .filter(|mv| self.is_root_move_allowed(mv))
+            {
                 crate::debug_utils::log_decision(
                     "GET_BEST_MOVE",
                     "Tablebase hit",
@@ -522,7 +1258,7 @@ impl ShogiEngine {
                     None,
                 );
 
-                return Some(best_move);
+                return SearchStart::Settled(Some(best_move));
             }
         } else {
             crate::debug_utils::end_timing("tablebase_check", "GET_BEST_MOVE");
@@ -530,90 +1266,647 @@ impl ShogiEngine {
 
         // Check opening book second
         crate::debug_utils::start_timing("opening_book_check");
-        if self.opening_book.is_loaded() {
-            if let Some(book_move) = self.opening_book.get_best_move(&fen) {
+        if self.own_book && self.opening_book.is_loaded() {
+            if let Some(book_move) = self
+                .opening_book
+                .get_best_move(&fen)
+                .filter(|mv| self.is_root_move_allowed(mv))
+            {
                 crate::utils::telemetry::debug_log(&format!(
                     "Found opening book move: {}",
                     book_move.to_usi_string()
                 ));
 
-                return Some(book_move);
-            }
-        }
+                return SearchStart::Settled(Some(book_move));
+            }
+        }
+
+        // Check for legal moves BEFORE starting search to avoid panics
+        crate::utils::telemetry::debug_log("Checking for legal moves before search");
+        let move_generator = MoveGenerator::new();
+        let legal_moves = move_generator.generate_legal_moves(
+            &self.board,
+            self.current_player,
+            &self.captured_pieces,
+        );
+
+        if legal_moves.is_empty() {
+            crate::utils::telemetry::debug_log(
+                "No legal moves available - position is checkmate or stalemate",
+            );
+            return SearchStart::Settled(None);
+        }
+
+        crate::utils::telemetry::debug_log(&format!(
+            "Found {} legal moves, proceeding with search",
+            legal_moves.len()
+        ));
+
+        // Out of book, a recognised castle shape puts its plan moves first at the root
+        let shape_plan = if self.own_book {
+            self.shape_book.suggest(&self.board, self.current_player, &legal_moves)
+        } else {
+            None
+        };
+        if let Some(plan) = &shape_plan {
+            crate::utils::telemetry::debug_log(&format!(
+                "Shape book: {} ({}/{}) - {}",
+                plan.name, plan.progress.0, plan.progress.1, plan.plan
+            ));
+        }
+        if let Ok(mut search_engine_guard) = self.search_engine.lock() {
+            search_engine_guard.set_preferred_root_moves(
+                shape_plan
+                    .map(|plan| plan.moves.iter().map(Move::to_usi_string).collect())
+                    .unwrap_or_default(),
+            );
+            let clock_mode = self.clocks.map_or(ClockMode::Neutral, |clocks| {
+                search_engine_guard.clock_contempt().mode(&clocks)
+            });
+            if clock_mode != ClockMode::Neutral {
+                crate::search::info_sink::emit_info(&format!(
+                    "info string clock {}",
+                    clock_mode.as_str()
+                ));
+            }
+            search_engine_guard.set_clock_mode(clock_mode);
+        }
+
+        // Handle depth 0 (unlimited/adaptive) - use high limit, engine will adapt based on time
+        // Using 100 as practical maximum (deep searches rarely exceed this)
+        let actual_depth = if depth == 0 { 100 } else { depth };
+
+        // Deterministic mode: one thread, no state carried over from earlier searches,
+        // and no wall-clock cutoffs when the depth is fixed
+        let (thread_count, time_limit_ms) = if self.deterministic {
+            if let Ok(mut search_engine_guard) = self.search_engine.lock() {
+                search_engine_guard.reset_search_state();
+            }
+            let time_limit_ms = if depth > 0 {
+                usi::UNBOUNDED_TIME_MS
+            } else {
+                time_limit_ms
+            };
+            (1, time_limit_ms)
+        } else {
+            (self.thread_count, time_limit_ms)
+        };
+
+        crate::utils::telemetry::debug_log(&format!(
+            "Creating searcher with depth: {} (requested: {}, 0 = unlimited), time_limit: {}ms",
+            actual_depth, depth, time_limit_ms
+        ));
+        let parallel_config =
+            ParallelSearchConfig::from_parallel_options(&self.parallel_options, thread_count);
+        let mut searcher = search::search_engine::IterativeDeepening::new_with_threads(
+            actual_depth,
+            time_limit_ms,
+            stop_flag.clone(),
+            thread_count,
+            parallel_config,
+        );
+        searcher.set_deterministic(self.deterministic);
+
+        SearchStart::Search(RootSearch {
+            search_engine: self.search_engine.clone(),
+            searcher,
+            board: self.board.clone(),
+            captured_pieces: self.captured_pieces.clone(),
+            player: self.current_player,
+            time_limit_ms,
+            stop_flag,
+            deterministic: self.deterministic,
+            info_fields: self.info_verbosity.fields,
+            score_perspective: self.score_perspective,
+        })
+    }
+
+    /// Limit subsequent searches to a fixed node count (`go nodes`); `None` clears the limit
+    pub fn set_node_limit(&mut self, node_limit: Option<u64>) {
+        if let Ok(mut search_engine_guard) = self.search_engine.lock() {
+            search_engine_guard.set_node_limit(node_limit);
+        }
+    }
+
+    /// Clocks of the next search (`go btime ... wtime ...`), for clock-aware play
+    pub fn set_clocks(&mut self, clocks: Option<Clocks>) {
+        self.clocks = clocks;
+    }
+
+    /// Restrict subsequent searches to the given root moves in USI notation
+    /// (`go searchmoves`); `None` searches every legal move
+    pub fn set_search_moves(&mut self, moves: Option<Vec<String>>) {
+        if let Ok(mut search_engine_guard) = self.search_engine.lock() {
+            search_engine_guard.set_search_moves(moves);
+        }
+    }
+
+    fn is_root_move_allowed(&self, mv: &Move) -> bool {
+        self.search_engine
+            .lock()
+            .map(|search_engine_guard| search_engine_guard.is_root_move_allowed(mv))
+            .unwrap_or(true)
+    }
+
+    /// Replace the pruning margins and thresholds used by subsequent searches
+    pub fn set_pruning_parameters(&mut self, params: crate::types::all::PruningParameters) {
+        if let Ok(mut search_engine_guard) = self.search_engine.lock() {
+            search_engine_guard.update_pruning_parameters(params);
+        }
+    }
+
+    /// Legal destinations with UI metadata for a selected square or hand piece of
+    /// the side to move
+    pub fn legal_move_hints(&self, source: move_hints::HintSource) -> Vec<move_hints::MoveHint> {
+        move_hints::legal_move_hints(
+            &self.board,
+            &self.captured_pieces,
+            self.current_player,
+            source,
+        )
+    }
+
+    /// Suggested move for the side to move with a short reason. Level 1 searches
+    /// shallowly for a move a learner can follow; `hint::MAX_HINT_LEVEL` returns the
+    /// full-strength best move. `None` without legal moves or while a search holds
+    /// the search engine.
+    pub fn get_hint(&self, level: u8) -> Option<hint::Hint> {
+        let (depth, time_limit_ms) = hint::hint_search_limits(level);
+        let mut search_engine_guard = self.search_engine.try_lock().ok()?;
+        let mut searcher = search::search_engine::IterativeDeepening::new(
+            if depth == 0 { 100 } else { depth },
+            time_limit_ms,
+            None,
+        );
+        let (best_move, score) = search::info_sink::with_info_suppressed(|| {
+            searcher.search(
+                &mut search_engine_guard,
+                &self.board,
+                &self.captured_pieces,
+                self.current_player,
+            )
+        })?;
+
+        let mut next_board = self.board.clone();
+        let mut next_captured = self.captured_pieces.clone();
+        if best_move.from.is_none() {
+            next_captured.remove_piece(best_move.piece_type, self.current_player);
+        }
+        if let Some(captured) = next_board.make_move(&best_move) {
+            next_captured.add_piece(captured.piece_type, self.current_player);
+        }
+        let opponent = self.current_player.opposite();
+        let evaluator = search_engine_guard.get_evaluator_mut();
+        let before =
+            evaluator.explain_evaluation(&self.board, self.current_player, &self.captured_pieces);
+        let after = evaluator.explain_evaluation(&next_board, self.current_player, &next_captured);
+        let threats =
+            evaluation::threats::new_threats(&self.board, &next_board, self.current_player);
+
+        Some(hint::Hint::new(
+            self.locale,
+            level,
+            &best_move,
+            score,
+            next_board.is_king_in_check(opponent, &next_captured),
+            self.board.find_king_position(opponent),
+            &before,
+            &after,
+            &threats,
+        ))
+    }
+
+    /// The `count` best moves of the current position, best first, from a short
+    /// MultiPV-style search sharing `time_limit_ms`. Fewer are returned when the
+    /// position has fewer legal moves or time runs out. `None` while a search holds
+    /// the search engine.
+    pub fn get_candidate_moves(
+        &self,
+        count: usize,
+        time_limit_ms: u32,
+    ) -> Option<Vec<candidates::CandidateMove>> {
+        let count = count.clamp(1, candidates::MAX_CANDIDATES);
+        if self.analysis_root_split && self.thread_count > 1 {
+            return self.get_candidate_moves_split(count, time_limit_ms);
+        }
+        let rank_time_ms = candidates::rank_time_ms(time_limit_ms, count);
+        let mut search_engine_guard = self.search_engine.try_lock().ok()?;
+        let saved_search_moves = search_engine_guard.search_moves().map(<[String]>::to_vec);
+        let mut remaining: Vec<String> = search_engine_guard
+            .filter_root_moves(MoveGenerator::new().generate_legal_moves(
+                &self.board,
+                self.current_player,
+                &self.captured_pieces,
+            ))
+            .iter()
+            .map(|mv| mv.to_usi_string())
+            .collect();
+
+        let mut result = Vec::new();
+        while result.len() < count && !remaining.is_empty() {
+            // Each rank searches only the moves not ranked yet
+            search_engine_guard.set_search_moves(Some(remaining.clone()));
+            let mut searcher = search::search_engine::IterativeDeepening::new(
+                candidates::CANDIDATE_MAX_DEPTH,
+                rank_time_ms,
+                None,
+            );
+            let Some((best_move, score)) = search::info_sink::with_info_suppressed(|| {
+                searcher.search(
+                    &mut search_engine_guard,
+                    &self.board,
+                    &self.captured_pieces,
+                    self.current_player,
+                )
+            }) else {
+                break;
+            };
+            let depth = search_engine_guard.get_search_statistics().iteration_times_ms.len();
+
+            // Continuation from the position after the candidate, so a transposition
+            // table entry left by an earlier rank cannot replace the candidate
+            let mut next_board = self.board.clone();
+            let mut next_captured = self.captured_pieces.clone();
+            if best_move.from.is_none() {
+                next_captured.remove_piece(best_move.piece_type, self.current_player);
+            }
+            if let Some(captured) = next_board.make_move(&best_move) {
+                next_captured.add_piece(captured.piece_type, self.current_player);
+            }
+            let mut pv = vec![best_move.clone()];
+            pv.extend(search_engine_guard.get_pv_for_reporting(
+                &next_board,
+                &next_captured,
+                self.current_player.opposite(),
+                candidates::CANDIDATE_PV_LENGTH as u8 - 1,
+            ));
+
+            let usi = best_move.to_usi_string();
+            remaining.retain(|mv| *mv != usi);
+            result.push(candidates::CandidateMove::new(
+                result.len() + 1,
+                &best_move,
+                score,
+                u8::try_from(depth).unwrap_or(u8::MAX),
+                &pv,
+            ));
+        }
+
+        search_engine_guard.set_search_moves(saved_search_moves);
+        let (perspective, side_to_move) = (self.score_perspective, self.current_player);
+        Some(
+            result
+                .into_iter()
+                .map(|candidate| candidate.in_perspective(perspective, side_to_move))
+                .collect(),
+        )
+    }
+
+    /// `get_candidate_moves` with the root moves split over `thread_count` workers,
+    /// each with its own search engine; every root move is scored and the best
+    /// `count` are kept
+    fn get_candidate_moves_split(
+        &self,
+        count: usize,
+        time_limit_ms: u32,
+    ) -> Option<Vec<candidates::CandidateMove>> {
+        let root_moves = self.search_engine.try_lock().ok()?.filter_root_moves(
+            MoveGenerator::new().generate_legal_moves(
+                &self.board,
+                self.current_player,
+                &self.captured_pieces,
+            ),
+        );
+        let workers = search::root_split::worker_count(root_moves.len(), self.thread_count);
+        let worker_hash_mb =
+            (self.hash_size_mb / workers).clamp(1, search::root_split::MAX_WORKER_HASH_MB);
+        let engines = (0..workers)
+            .map(|_| self.new_search_engine(worker_hash_mb, None))
+            .collect();
+        let results = search::root_split::analyze(
+            engines,
+            &self.board,
+            &self.captured_pieces,
+            self.current_player,
+            &root_moves,
+            candidates::CANDIDATE_MAX_DEPTH,
+            time_limit_ms,
+            Some(self.stop_flag.clone()),
+        );
+
+        let (perspective, side_to_move) = (self.score_perspective, self.current_player);
+        Some(
+            results
+                .iter()
+                .take(count)
+                .enumerate()
+                .map(|(index, result)| {
+                    let pv = &result.pv[..result.pv.len().min(candidates::CANDIDATE_PV_LENGTH)];
+                    candidates::CandidateMove::new(
+                        index + 1,
+                        &result.mv,
+                        result.score,
+                        result.depth,
+                        pv,
+                    )
+                    .in_perspective(perspective, side_to_move)
+                })
+                .collect(),
+        )
+    }
+
+    /// Search the current position and explain why `usi` was or was not chosen,
+    /// within `time_limit_ms` for the two searches together (`why` command)
+    pub fn explain_move(
+        &self,
+        usi: &str,
+        time_limit_ms: u32,
+    ) -> Result<explain::MoveExplanation, ShogiError> {
+        let mv = MoveGenerator::new()
+            .generate_legal_moves(&self.board, self.current_player, &self.captured_pieces)
+            .into_iter()
+            .find(|mv| mv.to_usi_string() == usi)
+            .ok_or_else(|| ShogiError::rule(format!("illegal move {}", usi)))?;
+        let mut search_engine_guard = self
+            .search_engine
+            .try_lock()
+            .map_err(|_| ShogiError::resource("engine is busy"))?;
+        let saved_search_moves = search_engine_guard.search_moves().map(<[String]>::to_vec);
+        let search_time_ms = explain::search_time_ms(time_limit_ms);
+
+        let full = self.explain_search(&mut search_engine_guard, None, search_time_ms);
+        let restricted = self.explain_search(
+            &mut search_engine_guard,
+            Some(vec![usi.to_string()]),
+            search_time_ms,
+        );
+        search_engine_guard.set_search_moves(saved_search_moves);
+        match (full, restricted) {
+            (Some(full), Some(restricted)) => Ok(explain::explain(&mv, &full, &restricted)),
+            _ => Err(ShogiError::search("search found no move")),
+        }
+    }
+
+    /// One of the searches behind `explain_move`, over `search_moves` or every move
+    fn explain_search(
+        &self,
+        engine: &mut SearchEngine,
+        search_moves: Option<Vec<String>>,
+        time_limit_ms: u32,
+    ) -> Option<explain::ExplainSearch> {
+        engine.set_search_moves(search_moves);
+        let mut searcher = search::search_engine::IterativeDeepening::new(
+            explain::EXPLAIN_MAX_DEPTH,
+            time_limit_ms,
+            None,
+        );
+        let (best_move, score) = search::info_sink::with_info_suppressed(|| {
+            searcher.search(engine, &self.board, &self.captured_pieces, self.current_player)
+        })?;
+        let statistics = engine.get_search_statistics();
+
+        let mut next_board = self.board.clone();
+        let mut next_captured = self.captured_pieces.clone();
+        if best_move.from.is_none() {
+            next_captured.remove_piece(best_move.piece_type, self.current_player);
+        }
+        if let Some(captured) = next_board.make_move(&best_move) {
+            next_captured.add_piece(captured.piece_type, self.current_player);
+        }
+        let continuation = engine.get_pv_for_reporting(
+            &next_board,
+            &next_captured,
+            self.current_player.opposite(),
+            explain::EXPLAIN_LINE_LENGTH as u8 - 1,
+        );
+        Some(explain::ExplainSearch {
+            best_move,
+            score,
+            depth: u8::try_from(statistics.iteration_times_ms.len()).unwrap_or(u8::MAX),
+            continuation,
+            statistics,
+        })
+    }
+
+    /// Handicap setup selected with the `Handicap` option
+    pub fn handicap(&self) -> Handicap {
+        self.handicap
+    }
+
+    /// Perspective of the scores in `info` lines and analysis results (`ScorePerspective`)
+    pub fn score_perspective(&self) -> EvaluationPerspective {
+        self.score_perspective
+    }
+
+    /// Whether `info` scores carry the win probability (`ShowWinRate`)
+    pub fn show_win_rate(&self) -> bool {
+        self.show_win_rate
+    }
+
+    /// `info` output throttling and fields (`Info*` options)
+    pub fn info_verbosity(&self) -> InfoVerbosity {
+        self.info_verbosity
+    }
+
+    fn set_info_verbosity(&mut self, verbosity: InfoVerbosity) {
+        self.info_verbosity = verbosity;
+        if let Ok(mut search_engine_guard) = self.search_engine.lock() {
+            search_engine_guard.set_info_verbosity(verbosity);
+        }
+    }
+
+    /// Language of hint reasons (`Language`)
+    pub fn locale(&self) -> i18n::Locale {
+        self.locale
+    }
+
+    /// Whether the opening book is used (`USI_OwnBook`)
+    pub fn own_book(&self) -> bool {
+        self.own_book
+    }
+
+    /// Whether pondering is enabled (`USI_Ponder`)
+    pub fn ponder_enabled(&self) -> bool {
+        self.ponder_enabled
+    }
 
-        // Check for legal moves BEFORE starting search to avoid panics
-        crate::utils::telemetry::debug_log("Checking for legal moves before search");
-        let move_generator = MoveGenerator::new();
-        let legal_moves = move_generator.generate_legal_moves(
+    /// The opponent's expected reply to `best_move`, taken from the principal
+    /// variation of the last search, for `bestmove <move> ponder <reply>`
+    pub fn ponder_move(&self, best_move: &Move) -> Option<Move> {
+        let pv = self.search_engine.lock().ok()?.get_pv_for_reporting(
             &self.board,
-            self.current_player,
             &self.captured_pieces,
+            self.current_player,
+            2,
         );
-
-        if legal_moves.is_empty() {
-            crate::utils::telemetry::debug_log(
-                "No legal moves available - position is checkmate or stalemate",
-            );
+        let [first, reply, ..] = pv.as_slice() else {
+            return None;
+        };
+        if first.to_usi_string() != best_move.to_usi_string() {
             return None;
         }
 
-        crate::utils::telemetry::debug_log(&format!(
-            "Found {} legal moves, proceeding with search",
-            legal_moves.len()
-        ));
+        // The line comes from the transposition table, so check the reply is legal
+        let mut next_board = self.board.clone();
+        let mut next_captured = self.captured_pieces.clone();
+        if best_move.from.is_none() {
+            next_captured.remove_piece(best_move.piece_type, self.current_player);
+        }
+        if let Some(captured) = next_board.make_move(best_move) {
+            next_captured.add_piece(captured.piece_type, self.current_player);
+        }
+        let reply_usi = reply.to_usi_string();
+        MoveGenerator::new()
+            .generate_legal_moves(&next_board, self.current_player.opposite(), &next_captured)
+            .into_iter()
+            .find(|mv| mv.to_usi_string() == reply_usi)
+    }
 
-        // Handle depth 0 (unlimited/adaptive) - use high limit, engine will adapt based on time
-        // Using 100 as practical maximum (deep searches rarely exceed this)
-        let actual_depth = if depth == 0 { 100 } else { depth };
-        crate::utils::telemetry::debug_log(&format!(
-            "Creating searcher with depth: {} (requested: {}, 0 = unlimited), time_limit: {}ms",
-            actual_depth, depth, time_limit_ms
-        ));
-        let parallel_config =
-            ParallelSearchConfig::from_parallel_options(&self.parallel_options, self.thread_count);
-        let mut searcher = search::search_engine::IterativeDeepening::new_with_threads(
-            actual_depth,
+    /// Per-square attack counts for both sides in the current position, for
+    /// influence heatmaps
+    pub fn get_influence_map(&self) -> bitboards::influence::InfluenceMap {
+        bitboards::influence::InfluenceMap::compute(&self.board)
+    }
+
+    /// Danger to both kings in the current position for the endgame mate meter,
+    /// searching each side's mates for up to `time_limit_ms`
+    pub fn mate_meter(&self, time_limit_ms: u32) -> evaluation::king_danger::MateMeter {
+        evaluation::king_danger::MateMeter::compute(
+            &self.board,
+            &self.captured_pieces,
+            self.current_player,
             time_limit_ms,
-            stop_flag,
-            self.thread_count,
-            parallel_config,
-        );
+        )
+    }
 
-        crate::utils::telemetry::debug_log("Trying to get search engine lock");
+    /// Sharpness of the current position from the win rates of its `count` best
+    /// moves, searched within `time_limit_ms`, and its tactical density. `None`
+    /// while a search holds the search engine.
+    pub fn sharpness(&self, count: usize, time_limit_ms: u32) -> Option<sharpness::Sharpness> {
+        let win_rates: Vec<u16> = self
+            .get_candidate_moves(count, time_limit_ms)?
+            .iter()
+            .map(|candidate| candidate.win_rate)
+            .collect();
+        let density =
+            evaluation::complexity::TacticalDensity::of(&self.board, &self.captured_pieces);
+        Some(sharpness::Sharpness::estimate(&win_rates, density))
+    }
 
-        // Try to get the search engine lock, but don't panic if it fails
-        // Note: This engine runs as a separate process communicating via USI protocol.
-        // The search runs in this process, so periodic yielding helps keep the process responsive.
-        crate::utils::telemetry::debug_log("About to lock search engine");
-        let search_result = self.search_engine.lock().map(|mut search_engine_guard| {
-            crate::utils::telemetry::debug_log("Got search engine lock, starting search");
-            searcher.search(
-                &mut search_engine_guard,
-                &self.board,
-                &self.captured_pieces,
-                self.current_player,
-            )
-        });
+    /// Whether the game has ended in the current position and how, taking the
+    /// positions since the `position` command's start into account for sennichite
+    pub fn game_status(&self) -> game_status::GameStatus {
+        game_status::GameStatus::compute(
+            &self.board,
+            &self.captured_pieces,
+            self.current_player,
+            &self.position_history,
+        )
+    }
 
-        crate::utils::telemetry::debug_log("Search completed, checking result");
+    /// What `usi` would do if played in the current position: check, mate,
+    /// promotion, capture. `None` when it is not a legal move.
+    pub fn move_metadata(&self, usi: &str) -> Option<move_metadata::MoveMetadata> {
+        let mv = MoveGenerator::new()
+            .generate_legal_moves(&self.board, self.current_player, &self.captured_pieces)
+            .into_iter()
+            .find(|mv| mv.to_usi_string() == usi)?;
+        Some(move_metadata::MoveMetadata::compute(
+            &self.board,
+            &self.captured_pieces,
+            &mv,
+            self.last_move_to,
+        ))
+    }
 
-        if let Ok(Some((move_, _score))) = search_result {
-            Some(move_)
-        } else {
-            // Fallback to random move if search fails
-            let move_generator = MoveGenerator::new();
-            let legal_moves = move_generator.generate_legal_moves(
-                &self.board,
-                self.current_player,
-                &self.captured_pieces,
-            );
-            if legal_moves.is_empty() {
-                return None;
-            }
-            // Use a seeded RNG that's platform-compatible
-            let mut rng = StdRng::seed_from_u64(42); // Fixed seed for deterministic behavior
-            legal_moves.choose(&mut rng).cloned()
+    /// Rewrite a move in the current position from one notation to another,
+    /// e.g. `7g7f` to `７六歩`; the previous move's square supplies 同
+    pub fn convert_notation(
+        &self,
+        text: &str,
+        from: notation::NotationStyle,
+        to: notation::NotationStyle,
+    ) -> Result<String, ShogiError> {
+        notation::NotationContext::new(
+            &self.board,
+            &self.captured_pieces,
+            self.current_player,
+            self.last_move_to,
+        )
+        .convert(text, from, to)
+    }
+
+    /// Per-term breakdown of the static evaluation of the current position, from the
+    /// side to move's perspective. Returns `None` while a search holds the search engine.
+    pub fn explain_evaluation(&self) -> Option<evaluation::breakdown::EvaluationBreakdown> {
+        let mut search_engine_guard = self.search_engine.try_lock().ok()?;
+        Some(search_engine_guard.get_evaluator_mut().explain_evaluation(
+            &self.board,
+            self.current_player,
+            &self.captured_pieces,
+        ))
+    }
+
+    /// Statistics of the most recent search for the GUI's engine dashboard; while
+    /// a search runs, as of its last completed iteration
+    pub fn get_search_statistics(&self) -> search::statistics::SearchStatisticsReport {
+        if let Ok(search_engine_guard) = self.search_engine.try_lock() {
+            return search_engine_guard.get_search_statistics();
         }
+        self.search_statistics
+            .lock()
+            .map(|report| report.clone())
+            .unwrap_or_default()
+    }
+
+    /// Search, move ordering, TT, tablebase and magic bitboard counters in one
+    /// snapshot; `None` while a search holds the engine
+    pub fn statistics_snapshot(&self) -> Option<StatisticsSnapshot> {
+        let mut snapshot = self.search_engine.try_lock().ok()?.statistics_snapshot();
+        snapshot
+            .tablebase
+            .merge(&TablebaseCounters::from(self.tablebase.get_stats()));
+        snapshot.assets = self
+            .opening_book
+            .asset_stats()
+            .into_iter()
+            .chain(self.tablebase.asset_stats())
+            .collect();
+        Some(snapshot)
+    }
+
+    /// Counters accumulated since the last `reset_statistics`
+    pub fn statistics_delta(&self) -> Option<StatisticsSnapshot> {
+        self.statistics_snapshot()
+            .map(|snapshot| self.statistics_hub.delta(snapshot))
+    }
+
+    /// Start measuring deltas from now; returns the snapshot taken as the baseline
+    pub fn reset_statistics(&mut self) -> Option<StatisticsSnapshot> {
+        let snapshot = self.statistics_snapshot()?;
+        self.statistics_hub.reset(snapshot.clone());
+        Some(snapshot)
+    }
+
+    /// Search the current position for a forced mate by the side to move (`go mate`).
+    /// `time_limit_ms` of `None` runs until the ply limit is exhausted or `stop_flag` is set.
+    pub fn solve_mate(
+        &self,
+        time_limit_ms: Option<u32>,
+        stop_flag: Option<Arc<AtomicBool>>,
+    ) -> search::mate_search::MateSearchResult {
+        let mut searcher = search::mate_search::MateSearcher::new(
+            search::mate_search::DEFAULT_MATE_PLY_LIMIT,
+            time_limit_ms,
+            stop_flag,
+        );
+        let result = searcher.search(&self.board, &self.captured_pieces, self.current_player);
+        crate::utils::telemetry::debug_log(&format!(
+            "[SOLVE_MATE] {} nodes, result: {:?}",
+            searcher.nodes(),
+            result
+        ));
+        result
     }
 
     /// Apply a move to the engine's board
@@ -645,6 +1938,9 @@ impl ShogiEngine {
 
         // Switch turns
         self.current_player = self.current_player.opposite();
+        if let Some(game_moves) = self.game_moves.as_mut() {
+            game_moves.push(move_.to_usi_string());
+        }
 
         crate::utils::telemetry::debug_log(&format!("Applied move: {}", move_.to_usi_string()));
         true
@@ -683,110 +1979,112 @@ impl ShogiEngine {
     }
 
     pub fn handle_position(&mut self, parts: &[&str]) -> Vec<String> {
-        let mut output = Vec::new();
-        let sfen_str: String;
-        let mut moves_start_index: Option<usize> = None;
-
-        crate::utils::telemetry::debug_log(&format!(
-            "handle_position called with {} parts",
-            parts.len()
-        ));
-        crate::utils::telemetry::debug_log(&format!("Parts: {:?}", parts));
-
-        if parts.is_empty() {
-            output.push("info string error Invalid position command".to_string());
-            return output;
+        match self.apply_position_command(parts) {
+            Ok(()) => vec!["info string Board state updated.".to_string()],
+            Err(e) => {
+                crate::utils::telemetry::debug_log(&format!("position rejected: {}", e));
+                vec![e.to_usi_info()]
+            }
         }
+    }
 
-        if parts[0] == "startpos" {
-            sfen_str =
-                "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1".to_string();
-            crate::utils::telemetry::debug_log("Using startpos");
-            if parts.len() > 1 && parts[1] == "moves" {
-                moves_start_index = Some(2);
+    /// Set up the position of a USI `position` command (`startpos` or `sfen ...`,
+    /// optionally followed by `moves ...`). The position is built aside and only
+    /// replaces the current one once every move has been played, so a bad SFEN
+    /// or move leaves the engine where it was.
+    pub fn apply_position_command(&mut self, parts: &[&str]) -> Result<(), ShogiError> {
+        crate::utils::telemetry::debug_log(&format!("apply_position_command: {:?}", parts));
+
+        let (sfen_str, mut game_moves, moves) = match parts.first() {
+            Some(&"startpos") => {
+                let moves = match parts.get(1) {
+                    Some(&"moves") => &parts[2..],
+                    _ => &[],
+                };
+                (
+                    self.handicap.sfen().to_string(),
+                    (self.handicap == Handicap::Even).then(Vec::new),
+                    moves,
+                )
             }
-        } else if parts[0] == "sfen" {
-            // sfen can be up to 4 parts, plus "moves"
-            let mut sfen_parts = Vec::new();
-            let mut current_index = 1;
-            while current_index < parts.len() && parts[current_index] != "moves" {
-                sfen_parts.push(parts[current_index]);
-                current_index += 1;
+            Some(&"sfen") => {
+                // sfen can be up to 4 parts, plus "moves"
+                let moves_index =
+                    parts.iter().position(|part| *part == "moves").unwrap_or(parts.len());
+                let moves = parts.get(moves_index + 1..).unwrap_or(&[]);
+                (parts[1..moves_index].join(" "), None, moves)
             }
-            sfen_str = sfen_parts.join(" ");
-            crate::utils::telemetry::debug_log(&format!("Parsed SFEN: '{}'", sfen_str));
-            if current_index < parts.len() && parts[current_index] == "moves" {
-                moves_start_index = Some(current_index + 1);
+            _ => {
+                return Err(ShogiError::parse(
+                    "Invalid position command: expected 'startpos' or 'sfen'",
+                ))
             }
+        };
+
+        let (mut board, mut player, mut captured_pieces) = BitboardBoard::from_fen(&sfen_str)
+            .map_err(|e| ShogiError::parse(format!("Failed to parse FEN: {}", e)))?;
+        let check_invariants = invariants::checks_enabled();
+        let mut counts = if check_invariants {
+            invariants::check_position(&board, &captured_pieces)
+                .map_err(|e| ShogiError::rule(format!("Impossible position: {}", e)))?
         } else {
-            output.push(
-                "info string error Invalid position command: expected 'startpos' or 'sfen'"
-                    .to_string(),
-            );
-            return output;
-        }
+            invariants::PieceCounts::default()
+        };
 
-        crate::utils::telemetry::debug_log(&format!("About to parse SFEN: '{}'", sfen_str));
-        match BitboardBoard::from_fen(&sfen_str) {
-            Ok((board, player, captured_pieces)) => {
-                crate::utils::telemetry::debug_log(&format!(
-                    "SFEN parsed successfully, player: {:?}",
-                    player
-                ));
-                self.board = board;
-                self.current_player = player;
-                self.captured_pieces = captured_pieces;
-
-                // CRITICAL DEBUG: Verify the state was actually set
-                let verify_fen = self
-                    .board
-                    .to_fen(self.current_player, &self.captured_pieces);
-                crate::utils::telemetry::debug_log("========================================");
-                crate::utils::telemetry::debug_log("[HANDLE_POSITION] STATE SET - VERIFICATION:");
-                crate::utils::telemetry::debug_log(&format!(
-                    "[HANDLE_POSITION]   self.current_player = {:?}",
-                    self.current_player
-                ));
-                crate::utils::telemetry::debug_log(&format!(
-                    "[HANDLE_POSITION]   Verification FEN: {}",
-                    verify_fen
-                ));
-                crate::utils::telemetry::debug_log(&format!(
-                    "[HANDLE_POSITION]   self.captured_pieces: black={:?}, white={:?}",
-                    self.captured_pieces.black, self.captured_pieces.white
-                ));
-                crate::utils::telemetry::debug_log("========================================");
+        let mut position_history = Vec::with_capacity(moves.len());
+        let mut last_move_to = None;
+        let hash_handler = search::ShogiHashHandler::new_default();
+        for move_str in moves {
+            let mv = Move::from_usi_string(move_str, player, &board).map_err(|e| {
+                ShogiError::new(e.category(), format!("Failed to parse move '{}': {}", move_str, e))
+            })?;
+            position_history.push(RepetitionEntry::new(
+                &hash_handler,
+                &board,
+                &captured_pieces,
+                player,
+            ));
+            if mv.from.is_none() && !captured_pieces.remove_piece(mv.piece_type, player) {
+                return Err(ShogiError::rule(format!(
+                    "Failed to parse move '{}': no {:?} in hand",
+                    move_str, mv.piece_type
+                )));
             }
-            Err(e) => {
-                crate::utils::telemetry::debug_log(&format!("SFEN parse FAILED: {}", e));
-                output.push(format!("info string error Failed to parse FEN: {}", e));
-                return output;
+            let captured = board.make_move(&mv);
+            if let Some(captured) = &captured {
+                captured_pieces.add_piece(captured.piece_type, player);
             }
-        }
-
-        if let Some(start_index) = moves_start_index {
-            for move_str in &parts[start_index..] {
-                match Move::from_usi_string(move_str, self.current_player, &self.board) {
-                    Ok(mv) => {
-                        if let Some(captured) = self.board.make_move(&mv) {
-                            self.captured_pieces
-                                .add_piece(captured.piece_type, self.current_player);
-                        }
-                        self.current_player = self.current_player.opposite();
-                    }
-                    Err(e) => {
-                        output.push(format!(
-                            "info string error Failed to parse move '{}': {}",
-                            move_str, e
-                        ));
-                        return output;
-                    }
-                }
+            if check_invariants {
+                let expected =
+                    counts.after_move(player, captured.as_ref().map(|piece| piece.piece_type));
+                let found = invariants::PieceCounts::of(&board, &captured_pieces);
+                expected.expect(&found).map_err(|e| {
+                    ShogiError::rule(format!("Move '{}' broke the piece counts: {}", move_str, e))
+                })?;
+                counts = found;
+            }
+            player = player.opposite();
+            last_move_to = Some(mv.to);
+            if let Some(game_moves) = game_moves.as_mut() {
+                game_moves.push(move_str.to_string());
             }
         }
 
-        output.push("info string Board state updated.".to_string());
-        output
+        self.board = board;
+        self.current_player = player;
+        self.captured_pieces = captured_pieces;
+        self.position_history = position_history;
+        self.last_move_to = last_move_to;
+        self.game_moves = game_moves;
+        crate::utils::telemetry::debug_log(&format!(
+            "apply_position_command: now {}",
+            self.board.to_fen(self.current_player, &self.captured_pieces)
+        ));
+
+        if let Ok(mut search_engine_guard) = self.search_engine.lock() {
+            search_engine_guard.set_game_history(self.position_history.clone());
+        }
+        Ok(())
     }
 
     pub fn handle_stop(&mut self) -> Vec<String> {
@@ -800,16 +2098,12 @@ impl ShogiEngine {
             match parts[1] {
                 "USI_Hash" => {
                     if let Ok(size) = parts[3].parse::<usize>() {
-                        let size = size.clamp(1, 1024);
-                        if let Ok(mut search_engine_guard) = self.search_engine.lock() {
-                            *search_engine_guard =
-                                SearchEngine::new(Some(self.stop_flag.clone()), size);
-                            self.parallel_options.hash_size_mb = size.min(512);
-                            search_engine_guard.set_parallel_options(self.parallel_options.clone());
-                            output.push(format!("info string Set USI_Hash to {} MB", size));
+                        if self.set_hash_size(size) {
+                            output.push(format!(
+                                "info string Set USI_Hash to {} MB",
+                                self.hash_size_mb
+                            ));
                         }
-                        self.opening_book_prefilled = false;
-                        self.maybe_prefill_opening_book();
                     }
                 }
                 "PSTPreset" => {
@@ -849,6 +2143,64 @@ impl ShogiEngine {
                         ));
                     }
                 }
+                "WeightsFile" => {
+                    let value = parts[3..].join(" ");
+                    let trimmed = value.trim();
+                    let previous_path = self.weights_path.clone();
+                    self.weights_path = (!trimmed.is_empty()).then(|| trimmed.to_string());
+                    match self.apply_weights_file() {
+                        Ok(()) if trimmed.is_empty() => output.push(
+                            "info string Cleared WeightsFile; using built-in weights".to_string(),
+                        ),
+                        Ok(()) => {
+                            output.push(format!("info string Loaded weights from '{}'", trimmed))
+                        }
+                        Err(err) => {
+                            // The failed load dropped the old weights; bring them back
+                            self.weights_path = previous_path;
+                            let _ = self.apply_weights_file();
+                            output.push(format!("info string error {}", err));
+                        }
+                    }
+                }
+                "BookFile" => {
+                    let value = parts[3..].join(" ");
+                    let trimmed = value.trim();
+                    if trimmed.is_empty() {
+                        self.load_default_opening_book();
+                        output.push(
+                            "info string Cleared BookFile; using built-in opening book".to_string(),
+                        );
+                    } else {
+                        match self.load_opening_book_from_file(trimmed) {
+                            Ok(()) => output.push(format!(
+                                "info string Opened opening book '{}' ({})",
+                                trimmed,
+                                match self.opening_book.asset_stats() {
+                                    Some(stats) if stats.mapped => "memory-mapped",
+                                    _ => "in memory",
+                                }
+                            )),
+                            Err(err) => output.push(err.to_usi_info()),
+                        }
+                    }
+                }
+                "TablebaseFile" => {
+                    let value = parts[3..].join(" ");
+                    let trimmed = value.trim();
+                    if !trimmed.is_empty() {
+                        match self.tablebase.load_table_file(trimmed) {
+                            Ok(records) => output.push(format!(
+                                "info string Loaded {} tablebase positions from '{}'",
+                                records, trimmed
+                            )),
+                            Err(err) => output.push(format!(
+                                "info string error Failed to open tablebase file '{}': {}",
+                                trimmed, err
+                            )),
+                        }
+                    }
+                }
                 "PSTPath" => {
                     let value = parts[3..].join(" ");
                     let trimmed = value.trim();
@@ -1202,6 +2554,253 @@ impl ShogiEngine {
                         output.push("info string Disabled tablebase".to_string());
                     }
                 }
+                "Handicap" => match Handicap::from_name(parts[3]) {
+                    Some(handicap) => {
+                        self.handicap = handicap;
+                        if let Ok(mut search_engine_guard) = self.search_engine.lock() {
+                            search_engine_guard.get_evaluator_mut().set_handicap(handicap);
+                        }
+                        output.push(format!("info string Set Handicap to {}", handicap.name()));
+                    }
+                    None => output.push(format!(
+                        "info string error Unknown Handicap value '{}'",
+                        parts[3]
+                    )),
+                },
+                "ScorePerspective" => match EvaluationPerspective::parse(parts[3]) {
+                    Some(perspective) => {
+                        self.score_perspective = perspective;
+                        if let Ok(mut search_engine_guard) = self.search_engine.lock() {
+                            search_engine_guard.set_score_perspective(perspective);
+                        }
+                        output.push(format!(
+                            "info string Set ScorePerspective to {}",
+                            perspective.as_str()
+                        ));
+                    }
+                    None => output.push(format!(
+                        "info string error Unknown ScorePerspective value '{}'",
+                        parts[3]
+                    )),
+                },
+                "Language" => match i18n::Locale::parse(parts[3]) {
+                    Some(locale) => {
+                        self.locale = locale;
+                        output.push(format!("info string Set Language to {}", locale));
+                    }
+                    None => output.push(format!(
+                        "info string error Unknown Language value '{}'",
+                        parts[3]
+                    )),
+                },
+                "RootVarietyMargin" | "RootVarietyTemperature" => {
+                    match parts[3].parse::<i32>() {
+                        Ok(value) if (0..=500).contains(&value) => {
+                            if let Ok(mut search_engine_guard) = self.search_engine.lock() {
+                                let mut variety = search_engine_guard.root_variety();
+                                if parts[1] == "RootVarietyMargin" {
+                                    variety.margin_cp = value;
+                                } else {
+                                    variety.temperature_cp = value.max(1);
+                                }
+                                search_engine_guard.set_root_variety(variety);
+                                output.push(format!("info string Set {} to {}", parts[1], value));
+                            }
+                        }
+                        _ => output.push(format!(
+                            "info string error {} must be between 0 and 500",
+                            parts[1]
+                        )),
+                    }
+                }
+                "ClockContemptMargin" | "ClockTroubleTime" => {
+                    let max = if parts[1] == "ClockTroubleTime" { 60_000 } else { 500 };
+                    match parts[3].parse::<u32>() {
+                        Ok(value) if value <= max => {
+                            if let Ok(mut search_engine_guard) = self.search_engine.lock() {
+                                let mut contempt = search_engine_guard.clock_contempt();
+                                if parts[1] == "ClockContemptMargin" {
+                                    contempt.margin_cp = value as i32;
+                                } else {
+                                    contempt.trouble_ms = value;
+                                }
+                                search_engine_guard.set_clock_contempt(contempt);
+                                output.push(format!("info string Set {} to {}", parts[1], value));
+                            }
+                        }
+                        _ => output.push(format!(
+                            "info string error {} must be between 0 and {}",
+                            parts[1], max
+                        )),
+                    }
+                }
+                "CoachLevel" => match parts[3].parse::<u8>() {
+                    Ok(level) if level <= search::coach::MAX_COACH_LEVEL => {
+                        if let Ok(mut search_engine_guard) = self.search_engine.lock() {
+                            search_engine_guard
+                                .set_coach(search::coach::CoachConfig::for_level(level));
+                            output.push(format!("info string Set CoachLevel to {}", level));
+                        }
+                    }
+                    _ => output.push(format!(
+                        "info string error CoachLevel must be between 0 and {}",
+                        search::coach::MAX_COACH_LEVEL
+                    )),
+                },
+                "Determinism" => {
+                    if let Ok(enabled) = parts[3].parse::<bool>() {
+                        self.deterministic = enabled;
+                        output.push(format!(
+                            "info string {} deterministic search",
+                            if enabled { "Enabled" } else { "Disabled" }
+                        ));
+                    } else {
+                        output.push("info string error Invalid Determinism value".to_string());
+                    }
+                }
+                "MaxSearchPly" => match parts[3].parse::<u16>() {
+                    Ok(plies) if (search::ply_guard::MIN_MAX_PLY..=1024).contains(&plies) => {
+                        self.max_search_ply = plies;
+                        if let Ok(mut search_engine_guard) = self.search_engine.lock() {
+                            search_engine_guard.set_max_search_ply(plies);
+                        }
+                        output.push(format!("info string Set MaxSearchPly to {}", plies));
+                    }
+                    _ => output.push(format!(
+                        "info string error MaxSearchPly must be between {} and 1024",
+                        search::ply_guard::MIN_MAX_PLY
+                    )),
+                },
+                "AnalysisRootSplit" => match parts[3].parse::<bool>() {
+                    Ok(enabled) => {
+                        self.analysis_root_split = enabled;
+                        output.push(format!(
+                            "info string {} root-move splitting for analysis",
+                            if enabled { "Enabled" } else { "Disabled" }
+                        ));
+                    }
+                    Err(_) => output
+                        .push("info string error Invalid AnalysisRootSplit value".to_string()),
+                },
+                "TTKeyVerification" => match parts[3].parse::<bool>() {
+                    Ok(enabled) => {
+                        self.tt_key_verification = enabled;
+                        if let Ok(mut search_engine_guard) = self.search_engine.lock() {
+                            search_engine_guard.set_tt_key_verification(enabled);
+                        }
+                        output.push(format!(
+                            "info string {} TT key verification",
+                            if enabled { "Enabled" } else { "Disabled" }
+                        ));
+                    }
+                    Err(_) => output
+                        .push("info string error Invalid TTKeyVerification value".to_string()),
+                },
+                "WarmUp" => match parts[3].parse::<bool>() {
+                    Ok(enabled) => {
+                        self.warm_up = enabled;
+                        output.push(format!(
+                            "info string {} warm-up search on isready",
+                            if enabled { "Enabled" } else { "Disabled" }
+                        ));
+                    }
+                    Err(_) => output.push("info string error Invalid WarmUp value".to_string()),
+                },
+                "ShowWinRate" => match parts[3].parse::<bool>() {
+                    Ok(enabled) => {
+                        self.show_win_rate = enabled;
+                        if let Ok(mut search_engine_guard) = self.search_engine.lock() {
+                            search_engine_guard.set_show_win_rate(enabled);
+                        }
+                        output.push(format!(
+                            "info string {} win rate in info scores",
+                            if enabled { "Enabled" } else { "Disabled" }
+                        ));
+                    }
+                    Err(_) => output
+                        .push("info string error Invalid ShowWinRate value".to_string()),
+                },
+                "InfoDepthInterval" => match parts[3].parse::<u8>() {
+                    Ok(interval) if (1..=64).contains(&interval) => {
+                        self.set_info_verbosity(InfoVerbosity {
+                            depth_interval: interval,
+                            ..self.info_verbosity
+                        });
+                        output.push(format!("info string Set InfoDepthInterval to {}", interval));
+                    }
+                    _ => output.push(
+                        "info string error InfoDepthInterval must be between 1 and 64".to_string(),
+                    ),
+                },
+                "InfoInterval" => match parts[3].parse::<u64>() {
+                    Ok(interval_ms) => {
+                        self.set_info_verbosity(InfoVerbosity {
+                            interval_ms,
+                            ..self.info_verbosity
+                        });
+                        output.push(format!("info string Set InfoInterval to {} ms", interval_ms));
+                    }
+                    Err(_) => output
+                        .push("info string error Invalid InfoInterval value".to_string()),
+                },
+                "InfoNodeInterval" => match parts[3].parse::<u64>() {
+                    Ok(node_interval) => {
+                        self.set_info_verbosity(InfoVerbosity {
+                            node_interval,
+                            ..self.info_verbosity
+                        });
+                        output.push(format!(
+                            "info string Set InfoNodeInterval to {} nodes",
+                            node_interval
+                        ));
+                    }
+                    Err(_) => output
+                        .push("info string error Invalid InfoNodeInterval value".to_string()),
+                },
+                "InfoCurrMove" => match parts[3].parse::<bool>() {
+                    Ok(currmove) => {
+                        self.set_info_verbosity(InfoVerbosity { currmove, ..self.info_verbosity });
+                        output.push(format!(
+                            "info string {} currmove info lines",
+                            if currmove { "Enabled" } else { "Disabled" }
+                        ));
+                    }
+                    Err(_) => output
+                        .push("info string error Invalid InfoCurrMove value".to_string()),
+                },
+                "InfoFields" => match InfoFields::parse(&parts[3..].join(" ")) {
+                    Ok(fields) => {
+                        self.set_info_verbosity(InfoVerbosity { fields, ..self.info_verbosity });
+                        output.push(format!(
+                            "info string Set InfoFields to {}",
+                            fields.as_string()
+                        ));
+                    }
+                    Err(e) => output.push(format!("info string error InfoFields: {}", e)),
+                },
+                "USI_OwnBook" => {
+                    if let Ok(enabled) = parts[3].parse::<bool>() {
+                        self.own_book = enabled;
+                        self.maybe_prefill_opening_book();
+                        output.push(format!(
+                            "info string {} opening book",
+                            if enabled { "Enabled" } else { "Disabled" }
+                        ));
+                    } else {
+                        output.push("info string error Invalid USI_OwnBook value".to_string());
+                    }
+                }
+                "USI_Ponder" => {
+                    if let Ok(enabled) = parts[3].parse::<bool>() {
+                        self.ponder_enabled = enabled;
+                        output.push(format!(
+                            "info string {} pondering",
+                            if enabled { "Enabled" } else { "Disabled" }
+                        ));
+                    } else {
+                        output.push("info string error Invalid USI_Ponder value".to_string());
+                    }
+                }
                 "USI_Threads" => {
                     if let Ok(threads) = parts[3].parse::<usize>() {
                         self.thread_count = threads.clamp(1, 32);
@@ -1359,14 +2958,32 @@ impl ShogiEngine {
                     self.set_debug_enabled(false);
                     output.push("info string trace logging disabled".to_string());
                 }
+                "invariants" => match parts.get(1) {
+                    Some(&("on" | "off")) => {
+                        invariants::set_checks_enabled(parts[1] == "on");
+                        output.push(format!("info string invariant checks {}", parts[1]));
+                    }
+                    _ => output.push(format!(
+                        "info string invariant checks {}, {} violations reported",
+                        if invariants::checks_enabled() { "on" } else { "off" },
+                        invariants::violation_count()
+                    )),
+                },
+                "check" => match invariants::check_position(&self.board, &self.captured_pieces) {
+                    Ok(_) => output.push("info string invariants hold".to_string()),
+                    Err(e) => output.push(format!("info string error invariant violated: {}", e)),
+                },
                 _ => output.push(format!(
-                    "info string unknown debug command {} (use: on/off/trace/notrace)",
+                    "info string unknown debug command {} \
+                     (use: on/off/trace/notrace/invariants/check)",
                     part
                 )),
             }
         } else {
             output.push(
-                "info string debug command needs an argument (on/off/trace/notrace)".to_string(),
+                "info string debug command needs an argument \
+                 (on/off/trace/notrace/invariants/check)"
+                    .to_string(),
             );
         }
         output
@@ -1403,8 +3020,11 @@ impl ShogiEngine {
     pub fn get_tablebase_stats(&self) -> String {
         let stats = self.tablebase.get_stats();
         format!(
-            "Tablebase Stats: Probes={}, Cache Hits={}, Solver Hits={}, Misses={}, Cache Hit Rate={:.2}%, Solver Hit Rate={:.2}%, Overall Hit Rate={:.2}%, Avg Probe Time={:.2}ms",
+            "Tablebase Stats: Probes={}, Filtered={}, Cache Hits={}, Solver Hits={}, Misses={}, \
+             Cache Hit Rate={:.2}%, Solver Hit Rate={:.2}%, Overall Hit Rate={:.2}%, \
+             Avg Probe Time={:.2}ms",
             stats.total_probes,
+            stats.filtered_probes,
             stats.cache_hits,
             stats.solver_hits,
             stats.misses,
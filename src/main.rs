@@ -61,5 +61,9 @@ fn install_signal_handlers() {
 fn main() {
     install_panic_hook();
     install_signal_handlers();
+    // Build the sliding-piece magic tables before the GUI starts timing us
+    if let Err(e) = shogi_engine::bitboards::magic::initialize() {
+        eprintln!("[engine] {}", e);
+    }
     run_with_panic_logging(|| run_usi_loop());
 }
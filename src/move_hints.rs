@@ -0,0 +1,209 @@
+//! Legal-Move Hints
+//!
+//! Legal destinations for a selected board square or hand piece, with the metadata
+//! the GUI needs to highlight them (captures, promotion choice, checks and a rough
+//! material estimate). Built from the engine's own legal move generator so the UI
+//! and the engine always agree on what is playable.
+
+use crate::bitboards::influence::InfluenceMap;
+use crate::bitboards::BitboardBoard;
+use crate::moves::MoveGenerator;
+use crate::search::move_ordering::calculate_see_internal_helper;
+use crate::types::board::CapturedPieces;
+use crate::types::core::{Move, PieceType, Player, Position};
+use serde::{Deserialize, Serialize};
+
+/// What the user selected: a piece on the board or a piece type in hand
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintSource {
+    Square(Position),
+    Hand(PieceType),
+}
+
+impl HintSource {
+    /// Parse a USI square (`7g`) or a hand piece (`P*` or `P`)
+    pub fn parse(text: &str) -> Option<Self> {
+        let piece = match text.trim_end_matches('*') {
+            "P" => Some(PieceType::Pawn),
+            "L" => Some(PieceType::Lance),
+            "N" => Some(PieceType::Knight),
+            "S" => Some(PieceType::Silver),
+            "G" => Some(PieceType::Gold),
+            "B" => Some(PieceType::Bishop),
+            "R" => Some(PieceType::Rook),
+            _ => None,
+        };
+        match piece {
+            Some(piece_type) => Some(HintSource::Hand(piece_type)),
+            None => Position::from_usi_string(text).ok().map(HintSource::Square),
+        }
+    }
+
+    fn matches(&self, move_: &Move) -> bool {
+        match *self {
+            HintSource::Square(pos) => move_.from == Some(pos),
+            HintSource::Hand(piece_type) => move_.from.is_none() && move_.piece_type == piece_type,
+        }
+    }
+}
+
+/// Whether moving to a destination can or must promote
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromotionChoice {
+    None,
+    Optional,
+    Forced,
+}
+
+/// A legal destination for the selected piece
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MoveHint {
+    /// Destination square in USI notation
+    pub to: String,
+    /// USI move played by default: the promoting move when promotion is forced
+    pub usi: String,
+    pub is_capture: bool,
+    pub promotion: PromotionChoice,
+    /// Whether the default move gives check
+    pub gives_check: bool,
+    /// Whether the promoting move gives check (false when it can't promote)
+    pub promotion_gives_check: bool,
+    /// Static exchange estimate in centipawns: the exchange result for captures,
+    /// minus the piece's value for a quiet move onto an undefended attacked square
+    pub see: i32,
+}
+
+/// Legal destinations for `source` with `player` to move
+pub fn legal_move_hints(
+    board: &BitboardBoard,
+    captured_pieces: &CapturedPieces,
+    player: Player,
+    source: HintSource,
+) -> Vec<MoveHint> {
+    let moves: Vec<Move> = MoveGenerator::new()
+        .generate_legal_moves(board, player, captured_pieces)
+        .into_iter()
+        .filter(|move_| source.matches(move_))
+        .collect();
+
+    let mut hints: Vec<MoveHint> = Vec::new();
+    for move_ in moves.iter().filter(|m| !m.is_promotion) {
+        let promoting = moves.iter().find(|m| m.is_promotion && m.to == move_.to);
+        hints.push(MoveHint {
+            to: move_.to.to_string(),
+            usi: move_.to_usi_string(),
+            is_capture: move_.is_capture,
+            promotion: if promoting.is_some() {
+                PromotionChoice::Optional
+            } else {
+                PromotionChoice::None
+            },
+            gives_check: gives_check(board, move_),
+            promotion_gives_check: promoting.map_or(false, |m| gives_check(board, m)),
+            see: see_estimate(board, move_),
+        });
+    }
+
+    // Promotions without a non-promoting counterpart are forced
+    for move_ in moves.iter().filter(|m| m.is_promotion) {
+        if hints.iter().any(|hint| hint.to == move_.to.to_string()) {
+            continue;
+        }
+        let checks = gives_check(board, move_);
+        hints.push(MoveHint {
+            to: move_.to.to_string(),
+            usi: move_.to_usi_string(),
+            is_capture: move_.is_capture,
+            promotion: PromotionChoice::Forced,
+            gives_check: checks,
+            promotion_gives_check: checks,
+            see: see_estimate(board, move_),
+        });
+    }
+
+    hints
+}
+
+fn gives_check(board: &BitboardBoard, move_: &Move) -> bool {
+    let mut next_board = board.clone();
+    next_board.make_move(move_);
+    next_board.is_king_in_check(move_.player.opposite(), &CapturedPieces::new())
+}
+
+fn see_estimate(board: &BitboardBoard, move_: &Move) -> i32 {
+    if move_.is_capture {
+        return calculate_see_internal_helper(move_, board);
+    }
+
+    let mut next_board = board.clone();
+    next_board.make_move(move_);
+    let influence = InfluenceMap::compute(&next_board);
+    let (row, col) = (move_.to.row as usize, move_.to.col as usize);
+    let attackers = influence.for_player(move_.player.opposite())[row][col];
+    let defenders = influence.for_player(move_.player)[row][col];
+    if attackers > 0 && defenders == 0 {
+        -move_.piece_type.base_value()
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hint_source() {
+        assert_eq!(HintSource::parse("P*"), Some(HintSource::Hand(PieceType::Pawn)));
+        assert_eq!(HintSource::parse("G"), Some(HintSource::Hand(PieceType::Gold)));
+        assert_eq!(
+            HintSource::parse("7g"),
+            Some(HintSource::Square(Position::from_usi_string("7g").unwrap()))
+        );
+        assert_eq!(HintSource::parse("x9"), None);
+    }
+
+    #[test]
+    fn test_opening_pawn_push() {
+        let board = BitboardBoard::new();
+        let hints = legal_move_hints(
+            &board,
+            &CapturedPieces::new(),
+            Player::Black,
+            HintSource::parse("7g").unwrap(),
+        );
+        assert_eq!(hints.len(), 1);
+        assert_eq!(hints[0].usi, "7g7f");
+        assert_eq!(hints[0].promotion, PromotionChoice::None);
+        assert!(!hints[0].is_capture);
+    }
+
+    #[test]
+    fn test_forced_and_optional_promotion() {
+        // Pawn on 5b must promote on 5a; silver on 3d may promote on 3c
+        let (board, player, captured) =
+            BitboardBoard::from_fen("k8/4P4/9/6S2/9/9/9/9/4K4 b - 1").unwrap();
+
+        let pawn = legal_move_hints(&board, &captured, player, HintSource::parse("5b").unwrap());
+        assert_eq!(pawn.len(), 1);
+        assert_eq!(pawn[0].promotion, PromotionChoice::Forced);
+        assert_eq!(pawn[0].usi, "5b5a+");
+
+        let silver = legal_move_hints(&board, &captured, player, HintSource::parse("3d").unwrap());
+        let to_3c = silver.iter().find(|hint| hint.to == "3c").unwrap();
+        assert_eq!(to_3c.promotion, PromotionChoice::Optional);
+        assert_eq!(to_3c.usi, "3d3c");
+    }
+
+    #[test]
+    fn test_drop_giving_check() {
+        let (board, player, captured) =
+            BitboardBoard::from_fen("4k4/9/9/9/9/9/9/9/4K4 b G 1").unwrap();
+        let hints = legal_move_hints(&board, &captured, player, HintSource::Hand(PieceType::Gold));
+        let check = hints.iter().find(|hint| hint.usi == "G*5b").unwrap();
+        assert!(check.gives_check);
+        // The gold on 5b is attacked by the king and undefended
+        assert!(check.see < 0);
+    }
+}
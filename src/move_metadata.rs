@@ -0,0 +1,101 @@
+//! Move Metadata
+//!
+//! What a move does, reported alongside it wherever a move is applied: whether
+//! it checks or mates, promotes, drops, captures, and whether it lands on the
+//! square of the previous move (同 in KIF). The GUI picks sounds and animations
+//! from it and the KIF exporter adds its notation marks without replaying the
+//! position.
+
+use crate::bitboards::BitboardBoard;
+use crate::types::{CapturedPieces, Move, PieceType, Player, Position};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveMetadata {
+    /// Move in USI notation
+    pub usi: String,
+    pub player: Player,
+    /// Piece that moved, as it stood before the move
+    pub piece: PieceType,
+    pub is_drop: bool,
+    pub is_promotion: bool,
+    /// Piece taken, as it stood on the board (a promoted piece stays promoted)
+    pub captured: Option<PieceType>,
+    pub gives_check: bool,
+    pub is_mate: bool,
+    /// The move lands where the previous move did
+    pub same_square: bool,
+}
+
+impl MoveMetadata {
+    /// Metadata of `mv`, a legal move in the position before it; `previous_to`
+    /// is the destination of the move before, if known
+    pub fn compute(
+        board: &BitboardBoard,
+        captured_pieces: &CapturedPieces,
+        mv: &Move,
+        previous_to: Option<Position>,
+    ) -> Self {
+        let captured = board
+            .get_piece(mv.to)
+            .filter(|piece| piece.player != mv.player)
+            .map(|piece| piece.piece_type);
+
+        let mut next_board = board.clone();
+        let mut next_captured = captured_pieces.clone();
+        if mv.from.is_none() {
+            next_captured.remove_piece(mv.piece_type, mv.player);
+        }
+        if let Some(taken) = next_board.make_move(mv) {
+            next_captured.add_piece(taken.piece_type, mv.player);
+        }
+        let opponent = mv.player.opposite();
+        let gives_check = next_board.is_king_in_check(opponent, &next_captured);
+
+        Self {
+            usi: mv.to_usi_string(),
+            player: mv.player,
+            piece: mv.piece_type,
+            is_drop: mv.from.is_none(),
+            is_promotion: mv.is_promotion,
+            captured,
+            gives_check,
+            is_mate: gives_check && next_board.is_checkmate(opponent, &next_captured),
+            same_square: previous_to == Some(mv.to),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moves::MoveGenerator;
+
+    fn metadata(sfen: &str, usi: &str, previous_to: Option<Position>) -> MoveMetadata {
+        let (board, player, captured) = BitboardBoard::from_fen(sfen).unwrap();
+        let mv = MoveGenerator::new()
+            .generate_legal_moves(&board, player, &captured)
+            .into_iter()
+            .find(|mv| mv.to_usi_string() == usi)
+            .expect("legal move");
+        MoveMetadata::compute(&board, &captured, &mv, previous_to)
+    }
+
+    #[test]
+    fn test_drop_mate_and_promoting_capture() {
+        let mate = metadata("4k4/9/4P4/9/9/9/9/9/4K4 b G 1", "G*5b", None);
+        assert!(mate.is_drop && mate.gives_check && mate.is_mate);
+        assert_eq!(mate.captured, None);
+
+        // The bishop takes the silver on 2b, promotes and lands where gote just moved
+        let capture = metadata(
+            "lnsgkg1nl/1r5s1/pppppp1pp/6p2/9/2P6/PP1PPPPPP/1B5R1/LNSGKGSNL b - 5",
+            "8h2b+",
+            Some(Position::new(1, 7)),
+        );
+        assert_eq!(capture.piece, PieceType::Bishop);
+        assert!(capture.is_promotion && capture.same_square && !capture.is_drop);
+        assert_eq!(capture.captured, Some(PieceType::Silver));
+        assert!(!capture.is_mate);
+    }
+}
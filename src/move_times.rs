@@ -0,0 +1,204 @@
+//! Move Times
+//!
+//! How long each move of a game took: engine moves from `go` to `bestmove`,
+//! human moves from the session clock. The times go into KIF exports as the
+//! per-move time field, and the review sets them against its markers to tell
+//! mistakes made with the clock running out from those made with time to spare.
+
+use crate::kif_parser::{KifMove, KifMoveTime};
+use crate::score_trend::{MoveMarker, ScoreSeries};
+use serde::{Deserialize, Serialize};
+
+/// Less than this left on the mover's clock is time pressure
+pub const TIME_PRESSURE_MS: u64 = 60_000;
+
+/// Time spent on one move
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveTime {
+    /// Plies played after the move; 1 is the first move
+    pub ply: u32,
+    pub spent_ms: u64,
+    /// The mover's clock after the move, in games with a clock
+    pub remaining_ms: Option<u64>,
+}
+
+impl MoveTime {
+    pub fn under_pressure(&self) -> bool {
+        self.remaining_ms.map_or(false, |remaining| remaining < TIME_PRESSURE_MS)
+    }
+}
+
+/// A move the review marked, with the time behind it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarkedMoveTime {
+    pub time: MoveTime,
+    pub marker: MoveMarker,
+    pub under_pressure: bool,
+}
+
+/// Reviewed moves split by time pressure; "marked" counts mistakes and blunders
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimePressureReport {
+    pub pressured_moves: usize,
+    pub pressured_marked: usize,
+    pub calm_moves: usize,
+    pub calm_marked: usize,
+    /// Every move with a marker, inaccuracies included, by ply
+    pub marked: Vec<MarkedMoveTime>,
+}
+
+/// Move times recorded for one game, ordered by ply
+#[derive(Debug, Clone, Default)]
+pub struct MoveTimes {
+    times: Vec<MoveTime>,
+}
+
+impl MoveTimes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store `time`, replacing an earlier time for the same ply
+    pub fn record(&mut self, time: MoveTime) {
+        match self.times.binary_search_by_key(&time.ply, |existing| existing.ply) {
+            Ok(index) => self.times[index] = time,
+            Err(index) => self.times.insert(index, time),
+        }
+    }
+
+    /// Forget the times after `ply`, e.g. after a takeback
+    pub fn truncate(&mut self, ply: u32) {
+        self.times.retain(|time| time.ply <= ply);
+    }
+
+    pub fn clear(&mut self) {
+        self.times.clear();
+    }
+
+    pub fn get(&self, ply: u32) -> Option<&MoveTime> {
+        self.times
+            .binary_search_by_key(&ply, |time| time.ply)
+            .ok()
+            .map(|index| &self.times[index])
+    }
+
+    pub fn times(&self) -> &[MoveTime] {
+        &self.times
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.times.is_empty()
+    }
+
+    /// Fill in the KIF time fields of `moves`, whose move numbers are plies: the
+    /// move's seconds and the mover's running total. Moves without a recorded
+    /// time are left alone and count as no time in the totals.
+    pub fn apply_to_kif(&self, moves: &mut [KifMove]) {
+        let mut totals_ms = [0u64; 2];
+        let mut times = self.times.iter().peekable();
+        for kif_move in moves.iter_mut() {
+            let ply = kif_move.move_number as u32;
+            while times.next_if(|time| time.ply < ply).is_some() {}
+            let Some(time) = times.next_if(|time| time.ply == ply) else {
+                continue;
+            };
+            let total = &mut totals_ms[(ply % 2) as usize];
+            *total += time.spent_ms;
+            kif_move.time = Some(KifMoveTime {
+                move_secs: (time.spent_ms / 1000) as u32,
+                total_secs: (*total / 1000) as u32,
+            });
+        }
+    }
+
+    /// How the review's judgement of each timed move compares with and without
+    /// time pressure
+    pub fn pressure_report(&self, series: &ScoreSeries) -> TimePressureReport {
+        let mut report = TimePressureReport::default();
+        for point in &series.points {
+            let Some(time) = self.get(point.point.ply) else {
+                continue;
+            };
+            if point.loss_cp.is_none() {
+                continue;
+            }
+            let under_pressure = time.under_pressure();
+            let serious = point.marker.map_or(false, |marker| marker >= MoveMarker::Mistake);
+            if under_pressure {
+                report.pressured_moves += 1;
+                report.pressured_marked += serious as usize;
+            } else {
+                report.calm_moves += 1;
+                report.calm_marked += serious as usize;
+            }
+            if let Some(marker) = point.marker {
+                report.marked.push(MarkedMoveTime {
+                    time: *time,
+                    marker,
+                    under_pressure,
+                });
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::score_trend::{ScorePoint, ScoreSource, ScoreTrend};
+
+    fn time(ply: u32, spent_ms: u64, remaining_ms: u64) -> MoveTime {
+        MoveTime {
+            ply,
+            spent_ms,
+            remaining_ms: Some(remaining_ms),
+        }
+    }
+
+    #[test]
+    fn test_kif_times_and_time_pressure() {
+        let mut times = MoveTimes::new();
+        times.record(time(1, 12_400, 588_000));
+        times.record(time(2, 3_000, 597_000));
+        times.record(time(3, 95_000, 45_000));
+        times.record(time(4, 5_000, 592_000));
+        times.record(time(5, 1_000, 40_000));
+        times.truncate(4);
+        assert_eq!(times.get(5), None);
+
+        let mut moves: Vec<KifMove> = (1..=4)
+            .map(|ply| KifMove {
+                move_number: ply,
+                move_text: String::new(),
+                usi_move: None,
+                comments: Vec::new(),
+                time: None,
+            })
+            .collect();
+        times.apply_to_kif(&mut moves);
+        // Sente's running total covers plies 1 and 3 only
+        let sente = moves[2].time.unwrap();
+        assert_eq!((sente.move_secs, sente.total_secs), (95, 107));
+        assert_eq!(moves[3].time.unwrap().total_secs, 8);
+
+        let mut trend = ScoreTrend::new();
+        for (ply, score) in [(0, 0), (1, 10), (2, 0), (3, -300), (4, -280)] {
+            trend.record(ScorePoint {
+                ply,
+                move_usi: None,
+                score,
+                mate: None,
+                source: ScoreSource::Review,
+                depth: None,
+                second_best: None,
+            });
+        }
+        let report = times.pressure_report(&trend.series());
+        assert_eq!((report.pressured_moves, report.pressured_marked), (1, 1));
+        assert_eq!((report.calm_moves, report.calm_marked), (3, 0));
+        assert_eq!(report.marked.len(), 1);
+        assert!(report.marked[0].under_pressure);
+        assert_eq!(report.marked[0].marker, MoveMarker::Blunder);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::bitboards::*;
+use crate::drop_rules::{self, is_dead_square};
 use crate::types::board::CapturedPieces;
 use crate::types::core::{Move, Piece, PieceType, Player, Position};
 use std::collections::HashSet;
@@ -107,9 +108,15 @@ impl MoveGenerator {
             pseudo_legal_moves.len()
         ));
 
+        // Out of check, only a king move or a pinned piece leaving its line can
+        // expose the king, so everything else skips the make-and-test below
+        let pins = board.pin_info(player);
         let legal_moves: Vec<Move> = pseudo_legal_moves
             .into_iter()
             .filter(|m| {
+                if !is_in_check && m.piece_type != PieceType::King {
+                    return pins.respects_pins(m);
+                }
                 let mut temp_board = board.clone();
                 let mut temp_captured = captured_pieces.clone();
 
@@ -198,11 +205,14 @@ impl MoveGenerator {
                     let from_in_opponent_promo = pos.is_in_promotion_zone(player.opposite());
                     let to_in_opponent_promo = to_pos.is_in_promotion_zone(player.opposite());
 
-                    // Non-promoted move
-                    let mut move_ = Move::new_move(pos, to_pos, piece.piece_type, player, false);
-                    move_.is_capture = true;
-                    move_.captured_piece = board.get_piece(to_pos);
-                    moves.push(move_);
+                    // Non-promoted move (not allowed where the piece would be stuck)
+                    if !is_dead_square(piece.piece_type, to_pos, player) {
+                        let mut move_ =
+                            Move::new_move(pos, to_pos, piece.piece_type, player, false);
+                        move_.is_capture = true;
+                        move_.captured_piece = board.get_piece(to_pos);
+                        moves.push(move_);
+                    }
 
                     // Promoted move
                     if piece.piece_type.can_promote()
@@ -240,36 +250,16 @@ impl MoveGenerator {
                     }
                 }
             }
-            PieceType::Lance | PieceType::Rook | PieceType::Bishop => {
-                let directions = match piece.piece_type {
-                    PieceType::Lance => {
-                        if player == Player::Black {
-                            vec![(-1, 0)]
-                        } else {
-                            vec![(1, 0)]
-                        }
-                    }
-                    PieceType::Rook => vec![(1, 0), (-1, 0), (0, 1), (0, -1)],
-                    PieceType::Bishop => vec![(1, 1), (1, -1), (-1, 1), (-1, -1)],
-                    _ => vec![],
-                };
-
-                for (dr, dc) in directions {
-                    let mut current_pos = pos;
-                    loop {
-                        let new_row = current_pos.row as i8 + dr;
-                        let new_col = current_pos.col as i8 + dc;
-                        if new_row < 0 || new_row >= 9 || new_col < 0 || new_col >= 9 {
-                            break;
-                        }
-
-                        current_pos = Position::new(new_row as u8, new_col as u8);
-                        handle_capture_move(&mut moves, current_pos);
-
-                        if board.is_square_occupied(current_pos) {
-                            break;
-                        }
-                    }
+            PieceType::Lance
+            | PieceType::Rook
+            | PieceType::Bishop
+            | PieceType::PromotedBishop
+            | PieceType::PromotedRook => {
+                // Sliding attacks respect blockers; rook and bishop rays come from
+                // the magic tables (ray-cast with the `raycast-sliding` feature)
+                let attacks = board.attacks_from(pos, piece.piece_type, player);
+                for target_pos in board.iter_attack_targets(attacks) {
+                    handle_capture_move(&mut moves, target_pos);
                 }
             }
             PieceType::Silver
@@ -278,9 +268,7 @@ impl MoveGenerator {
             | PieceType::PromotedPawn
             | PieceType::PromotedLance
             | PieceType::PromotedKnight
-            | PieceType::PromotedSilver
-            | PieceType::PromotedBishop
-            | PieceType::PromotedRook => {
+            | PieceType::PromotedSilver => {
                 let dir: i8 = if player == Player::Black { -1 } else { 1 };
                 let offsets = piece.piece_type.get_move_offsets(dir);
                 for (dr, dc) in offsets {
@@ -340,13 +328,15 @@ impl MoveGenerator {
                 let from_in_opponent_promo = pos.is_in_promotion_zone(player.opposite());
                 let to_in_opponent_promo = to_pos.is_in_promotion_zone(player.opposite());
 
-                // Non-promoted move
-                let mut move_ = Move::new_move(pos, to_pos, piece.piece_type, player, false);
-                if is_capture {
-                    move_.is_capture = true;
-                    move_.captured_piece = board.get_piece(to_pos);
+                // Non-promoted move (not allowed where the piece would be stuck)
+                if !is_dead_square(piece.piece_type, to_pos, player) {
+                    let mut move_ = Move::new_move(pos, to_pos, piece.piece_type, player, false);
+                    if is_capture {
+                        move_.is_capture = true;
+                        move_.captured_piece = board.get_piece(to_pos);
+                    }
+                    moves.push(move_);
                 }
-                moves.push(move_);
 
                 // Promoted move
                 if piece.piece_type.can_promote()
@@ -388,36 +378,16 @@ impl MoveGenerator {
                     }
                 }
             }
-            PieceType::Lance | PieceType::Rook | PieceType::Bishop => {
-                let directions = match piece.piece_type {
-                    PieceType::Lance => {
-                        if player == Player::Black {
-                            vec![(-1, 0)]
-                        } else {
-                            vec![(1, 0)]
-                        }
-                    }
-                    PieceType::Rook => vec![(1, 0), (-1, 0), (0, 1), (0, -1)],
-                    PieceType::Bishop => vec![(1, 1), (1, -1), (-1, 1), (-1, -1)],
-                    _ => vec![],
-                };
-
-                for (dr, dc) in directions {
-                    let mut current_pos = pos;
-                    loop {
-                        let new_row = current_pos.row as i8 + dr;
-                        let new_col = current_pos.col as i8 + dc;
-                        if new_row < 0 || new_row >= 9 || new_col < 0 || new_col >= 9 {
-                            break;
-                        }
-
-                        current_pos = Position::new(new_row as u8, new_col as u8);
-                        handle_move(&mut moves, current_pos);
-
-                        if board.is_square_occupied(current_pos) {
-                            break;
-                        }
-                    }
+            PieceType::Lance
+            | PieceType::Rook
+            | PieceType::Bishop
+            | PieceType::PromotedBishop
+            | PieceType::PromotedRook => {
+                // Sliding attacks respect blockers; rook and bishop rays come from
+                // the magic tables (ray-cast with the `raycast-sliding` feature)
+                let attacks = board.attacks_from(pos, piece.piece_type, player);
+                for target_pos in board.iter_attack_targets(attacks) {
+                    handle_move(&mut moves, target_pos);
                 }
             }
             PieceType::Silver
@@ -426,9 +396,7 @@ impl MoveGenerator {
             | PieceType::PromotedPawn
             | PieceType::PromotedLance
             | PieceType::PromotedKnight
-            | PieceType::PromotedSilver
-            | PieceType::PromotedBishop
-            | PieceType::PromotedRook => {
+            | PieceType::PromotedSilver => {
                 // Use precomputed attack patterns for better performance
                 let attacks = board.get_attack_pattern_precomputed(pos, piece.piece_type, player);
 
@@ -473,7 +441,7 @@ impl MoveGenerator {
                     let pos = Position::new(r, c);
                     if !board.is_square_occupied(pos) {
                         // Basic legality check for drops (e.g., pawn drops)
-                        if is_legal_drop_location(board, piece_type, pos, player) {
+                        if drop_rules::is_legal_drop(board, piece_type, pos, player) {
                             moves.push(Move::new_drop(piece_type, pos, player));
                         }
                     }
@@ -902,6 +870,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_promoted_sliders_stop_at_blockers() {
+        // Dragon on 5e, own pawn on 5c, enemy silver on 2e
+        let fen = "4k4/9/4P4/9/4+R2s1/9/9/9/4K4 b - 1";
+        let (board, player, captured_pieces) = BitboardBoard::from_fen(fen).unwrap();
+        let move_generator = MoveGenerator::new();
+        let moves: Vec<String> = move_generator
+            .generate_legal_moves(&board, player, &captured_pieces)
+            .iter()
+            .map(|m| m.to_usi_string())
+            .collect();
+        assert!(moves.contains(&"5e5d".to_string()));
+        assert!(moves.contains(&"5e2e".to_string()));
+        assert!(!moves.contains(&"5e5b".to_string()));
+        assert!(!moves.contains(&"5e1e".to_string()));
+
+        let captures: Vec<String> = move_generator
+            .generate_legal_captures(&board, player, &captured_pieces)
+            .iter()
+            .map(|m| m.to_usi_string())
+            .collect();
+        assert_eq!(captures, vec!["5e2e".to_string()]);
+    }
+
     #[test]
     fn test_quiescence_move_sorting_total_order() {
         let move_generator = MoveGenerator::new();
@@ -980,125 +972,6 @@ mod tests {
         }
     }
 }
-fn is_legal_drop_location(
-    board: &BitboardBoard,
-    piece_type: PieceType,
-    pos: Position,
-    player: Player,
-) -> bool {
-    if piece_type == PieceType::Pawn {
-        // Rule 1: Cannot drop on a file that already contains an unpromoted pawn of the same color (Nifu / 二歩)
-        for r in 0..9 {
-            if let Some(p) = board.get_piece(Position::new(r, pos.col)) {
-                if p.piece_type == PieceType::Pawn && p.player == player {
-                    crate::utils::telemetry::debug_log(&format!(
-                        "[NIFU] Illegal pawn drop at {}{}. Already have pawn on file {}",
-                        (b'a' + pos.col) as char,
-                        9 - pos.row,
-                        (b'a' + pos.col) as char
-                    ));
-                    return false;
-                }
-            }
-        }
-
-        // Rule 2: Cannot drop pawn to give immediate checkmate (Uchifuzume / 打ち歩詰め)
-        // This rule only applies to drops that give checkmate, not just check
-        if is_pawn_drop_mate(board, pos, player) {
-            crate::utils::telemetry::debug_log(&format!(
-                "[UCHIFUZUME] Illegal pawn drop mate at {}{}",
-                (b'a' + pos.col) as char,
-                9 - pos.row
-            ));
-            return false;
-        }
-    }
-
-    // Cannot drop a piece where it has no legal moves
-    let last_rank = if player == Player::Black { 0 } else { 8 };
-    let second_last_rank = if player == Player::Black { 1 } else { 7 };
-    match piece_type {
-        PieceType::Pawn | PieceType::Lance if pos.row == last_rank => return false,
-        PieceType::Knight if pos.row == last_rank || pos.row == second_last_rank => return false,
-        _ => true,
-    }
-}
-
-/// Check if dropping a pawn at the given position gives immediate checkmate (Uchifuzume)
-/// This is illegal in Shogi - you cannot drop a pawn to deliver checkmate
-fn is_pawn_drop_mate(board: &BitboardBoard, drop_pos: Position, player: Player) -> bool {
-    // Find opponent's king
-    let opponent = player.opposite();
-    let Some(king_pos) = board.find_king_position(opponent) else {
-        return false; // No king, can't be checkmate
-    };
-
-    // Check if the pawn would give check
-    let pawn_gives_check = match player {
-        Player::Black => {
-            // Black pawn attacks one square forward (decreasing row)
-            king_pos.row == drop_pos.row.wrapping_sub(1) && king_pos.col == drop_pos.col
-        }
-        Player::White => {
-            // White pawn attacks one square forward (increasing row)
-            king_pos.row == drop_pos.row + 1 && king_pos.col == drop_pos.col
-        }
-    };
-
-    if !pawn_gives_check {
-        return false; // Not even giving check, so not checkmate
-    }
-
-    // Now check if it's actually checkmate (king has no escape)
-    // This requires simulating the pawn drop and checking if the king has any legal moves
-    let mut temp_board = board.clone();
-    temp_board.place_piece(Piece::new(PieceType::Pawn, player), drop_pos);
-
-    // Check if opponent king has any legal moves or if check can be blocked
-    // For simplicity, check if king can escape to any adjacent square
-    for dr in -1..=1 {
-        for dc in -1..=1 {
-            if dr == 0 && dc == 0 {
-                continue;
-            }
-
-            let new_row = king_pos.row as i8 + dr;
-            let new_col = king_pos.col as i8 + dc;
-
-            if new_row >= 0 && new_row < 9 && new_col >= 0 && new_col < 9 {
-                let escape_pos = Position::new(new_row as u8, new_col as u8);
-
-                // Check if this square is a valid escape
-                if let Some(piece_at_escape) = temp_board.get_piece(escape_pos) {
-                    // Can only escape by capturing opponent's piece
-                    if piece_at_escape.player == player {
-                        continue; // Can't capture own piece
-                    }
-                }
-
-                // Temporarily move king to escape square and see if it's still in check
-                temp_board.remove_piece(king_pos);
-                temp_board.place_piece(Piece::new(PieceType::King, opponent), escape_pos);
-
-                // Create a temporary CapturedPieces (empty for this check)
-                let temp_captured = CapturedPieces::new();
-                if !temp_board.is_king_in_check(opponent, &temp_captured) {
-                    return false; // King can escape, so not checkmate
-                }
-
-                // Restore the king position for next check
-                temp_board.remove_piece(escape_pos);
-                temp_board.place_piece(Piece::new(PieceType::King, opponent), king_pos);
-            }
-        }
-    }
-
-    // TODO: Also check if the pawn can be captured or if check can be blocked
-    // For now, this is a reasonable approximation
-
-    true // Looks like checkmate
-}
-
 /// Performance metrics for move generation
 #[derive(Debug, Clone)]
 pub struct MoveGenerationMetrics {
@@ -0,0 +1,535 @@
+//! Move Notation
+//!
+//! Converts moves between USI coordinates (`7g7f`), Japanese notation
+//! (`７六歩`) and Western notation (`P-7f`). Japanese and Western notation only
+//! name the destination and the piece, so both need the position to write or
+//! read a move.
+//!
+//! Japanese notation comes in two forms:
+//! - KIF file form names the origin square: `７六歩(77)`, `同　歩(76)`, `５五角打`;
+//! - the display form (KI2) resolves ambiguity the way the JSA rules do, first
+//!   by the direction of the move (上 up, 引 back, 寄 sideways), then by which
+//!   piece moves (右 right, 左 left, 直 straight up), from the mover's side of
+//!   the board: `５八金右`, `同銀直`.
+//!
+//! Western notation follows Hodges: `P-7f`, `Sx3c+`, `B*4e`, `N-2c=` for a
+//! declined promotion, with the origin written only when needed (`G6i-5h`).
+//!
+//! Reading works by writing every legal move in the requested style and
+//! comparing, so anything written here reads back, and so do the common
+//! variants (ASCII digits, 竜/王, the KI2 abbreviations 全/圭/杏, a written
+//! square instead of 同, ▲/△ prefixes).
+
+use crate::bitboards::BitboardBoard;
+use crate::error::ShogiError;
+use crate::moves::MoveGenerator;
+use crate::types::{CapturedPieces, Move, PieceType, Player, Position};
+use serde::{Deserialize, Serialize};
+
+const KIF_FILES: [&str; 9] = ["１", "２", "３", "４", "５", "６", "７", "８", "９"];
+const KIF_RANKS: [&str; 9] = ["一", "二", "三", "四", "五", "六", "七", "八", "九"];
+
+/// A way of writing moves
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotationStyle {
+    /// USI coordinates: `7g7f`, `8h2b+`, `P*5e`
+    Usi,
+    /// KIF file form with the origin square: `７六歩(77)`
+    Kif,
+    /// Japanese display form with 右/左/直 and 上/引/寄: `５八金右`
+    Ki2,
+    /// Hodges-style Western notation: `P-7f`
+    Western,
+}
+
+impl NotationStyle {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "usi" => Some(Self::Usi),
+            "kif" => Some(Self::Kif),
+            "ki2" | "japanese" => Some(Self::Ki2),
+            "western" | "hodges" => Some(Self::Western),
+            _ => None,
+        }
+    }
+}
+
+/// The position a move is written or read in
+#[derive(Clone, Copy)]
+pub struct NotationContext<'a> {
+    pub board: &'a BitboardBoard,
+    pub captured_pieces: &'a CapturedPieces,
+    pub player: Player,
+    /// Destination of the previous move, for 同
+    pub previous_to: Option<Position>,
+}
+
+impl<'a> NotationContext<'a> {
+    pub fn new(
+        board: &'a BitboardBoard,
+        captured_pieces: &'a CapturedPieces,
+        player: Player,
+        previous_to: Option<Position>,
+    ) -> Self {
+        Self {
+            board,
+            captured_pieces,
+            player,
+            previous_to,
+        }
+    }
+
+    fn legal_moves(&self) -> Vec<Move> {
+        MoveGenerator::new().generate_legal_moves(self.board, self.player, self.captured_pieces)
+    }
+
+    /// Write `mv`, a legal move in this position, in `style`
+    pub fn format(&self, mv: &Move, style: NotationStyle) -> String {
+        let legal = self.legal_moves();
+        self.format_with(mv, style, &legal, &WriteOptions::default())
+    }
+
+    /// Read `text` written in `style` as a legal move in this position
+    pub fn parse(&self, text: &str, style: NotationStyle) -> Result<Move, ShogiError> {
+        let legal = self.legal_moves();
+        if style == NotationStyle::Usi {
+            return legal
+                .into_iter()
+                .find(|mv| mv.to_usi_string() == text.trim())
+                .ok_or_else(|| ShogiError::rule(format!("Illegal or unknown move: {}", text)));
+        }
+
+        let wanted = normalize(text);
+        let variants = WriteOptions::variants();
+        let mut matches = legal.iter().filter(|mv| {
+            variants
+                .iter()
+                .any(|options| normalize(&self.format_with(mv, style, &legal, options)) == wanted)
+        });
+        match (matches.next(), matches.next()) {
+            (Some(mv), None) => Ok(mv.clone()),
+            (Some(_), Some(_)) => Err(ShogiError::parse(format!("Ambiguous move: {}", text))),
+            (None, _) => Err(ShogiError::rule(format!("Illegal or unknown move: {}", text))),
+        }
+    }
+
+    /// Convert `text` from one style to another
+    pub fn convert(
+        &self,
+        text: &str,
+        from: NotationStyle,
+        to: NotationStyle,
+    ) -> Result<String, ShogiError> {
+        let mv = self.parse(text, from)?;
+        Ok(self.format(&mv, to))
+    }
+
+    fn format_with(
+        &self,
+        mv: &Move,
+        style: NotationStyle,
+        legal: &[Move],
+        options: &WriteOptions,
+    ) -> String {
+        match style {
+            NotationStyle::Usi => mv.to_usi_string(),
+            NotationStyle::Kif => self.kif(mv, legal, options),
+            NotationStyle::Ki2 => self.ki2(mv, legal, options),
+            NotationStyle::Western => self.western(mv, legal, options),
+        }
+    }
+
+    /// Type of the piece making `mv`, as it stands before the move
+    fn moving_piece(&self, mv: &Move) -> PieceType {
+        mv.from
+            .and_then(|from| self.board.get_piece(from))
+            .map_or(mv.piece_type, |piece| piece.piece_type)
+    }
+
+    /// Whether `mv` could have promoted but did not
+    fn declined_promotion(&self, mv: &Move, legal: &[Move]) -> bool {
+        !mv.is_promotion
+            && mv.from.is_some()
+            && legal
+                .iter()
+                .any(|other| other.is_promotion && other.from == mv.from && other.to == mv.to)
+    }
+
+    /// Other board moves of the same piece type to the same square
+    fn rivals(&self, mv: &Move, legal: &[Move]) -> Vec<Position> {
+        let piece = self.moving_piece(mv);
+        let mut origins: Vec<Position> = legal
+            .iter()
+            .filter(|other| other.to == mv.to && other.from.is_some() && other.from != mv.from)
+            .filter(|other| self.moving_piece(other) == piece)
+            .filter_map(|other| other.from)
+            .collect();
+        origins.sort_by_key(|pos| (pos.row, pos.col));
+        origins.dedup();
+        origins
+    }
+
+    fn destination(&self, mv: &Move, options: &WriteOptions, same: &str) -> String {
+        if options.same_square && self.previous_to == Some(mv.to) {
+            same.to_string()
+        } else {
+            kif_square(mv.to)
+        }
+    }
+
+    fn promotion_suffix(&self, mv: &Move, legal: &[Move]) -> &'static str {
+        if mv.is_promotion {
+            "成"
+        } else if self.declined_promotion(mv, legal) {
+            "不成"
+        } else {
+            ""
+        }
+    }
+
+    fn kif(&self, mv: &Move, legal: &[Move], options: &WriteOptions) -> String {
+        let destination = self.destination(mv, options, "同　");
+        let piece = kif_piece(self.moving_piece(mv));
+        match mv.from {
+            Some(from) => format!(
+                "{}{}{}({}{})",
+                destination,
+                piece,
+                self.promotion_suffix(mv, legal),
+                9 - from.col,
+                from.row + 1
+            ),
+            None => format!("{}{}打", destination, piece),
+        }
+    }
+
+    fn ki2(&self, mv: &Move, legal: &[Move], options: &WriteOptions) -> String {
+        let destination = self.destination(mv, options, "同");
+        let piece = kif_piece(self.moving_piece(mv));
+        let rivals = self.rivals(mv, legal);
+        let Some(from) = mv.from else {
+            // 打 only when a piece on the board could also go there
+            let board_piece_could_move = legal.iter().any(|other| {
+                other.to == mv.to
+                    && other.from.is_some()
+                    && self.moving_piece(other) == mv.piece_type
+            });
+            let drop_mark = if board_piece_could_move || options.always_mark_drops {
+                "打"
+            } else {
+                ""
+            };
+            return format!("{}{}{}", destination, piece, drop_mark);
+        };
+        format!(
+            "{}{}{}{}",
+            destination,
+            piece,
+            self.disambiguation(mv.to, from, &rivals),
+            self.promotion_suffix(mv, legal)
+        )
+    }
+
+    /// 右/左/直 and 上/引/寄 for a move from `from` to `to` with `rivals`, the
+    /// origins of the other pieces of the same type that can reach `to`
+    fn disambiguation(&self, to: Position, from: Position, rivals: &[Position]) -> String {
+        if rivals.is_empty() {
+            return String::new();
+        }
+        let direction = |origin: Position| self.direction(origin, to);
+        let own_direction = direction(from);
+        let same_direction: Vec<Position> = rivals
+            .iter()
+            .copied()
+            .filter(|rival| direction(*rival) == own_direction)
+            .collect();
+        if same_direction.is_empty() {
+            return own_direction.mark().to_string();
+        }
+
+        let piece = self.board.get_piece(from).map(|piece| piece.piece_type);
+        let ranging = matches!(
+            piece,
+            Some(
+                PieceType::Rook
+                    | PieceType::Bishop
+                    | PieceType::PromotedRook
+                    | PieceType::PromotedBishop
+            )
+        );
+        if !ranging && from.col == to.col && own_direction == Direction::Up {
+            return "直".to_string();
+        }
+
+        let right = |pos: Position| match self.player {
+            Player::Black => i16::from(pos.col),
+            Player::White => 8 - i16::from(pos.col),
+        };
+        let side = |others: &[Position]| {
+            if others.iter().all(|other| right(*other) < right(from)) {
+                Some("右")
+            } else if others.iter().all(|other| right(*other) > right(from)) {
+                Some("左")
+            } else {
+                None
+            }
+        };
+        if let Some(mark) = side(rivals) {
+            return mark.to_string();
+        }
+        match side(&same_direction) {
+            Some(mark) => format!("{}{}", mark, own_direction.mark()),
+            None => own_direction.mark().to_string(),
+        }
+    }
+
+    fn direction(&self, from: Position, to: Position) -> Direction {
+        let forward = match self.player {
+            Player::Black => i16::from(from.row) - i16::from(to.row),
+            Player::White => i16::from(to.row) - i16::from(from.row),
+        };
+        match forward {
+            f if f > 0 => Direction::Up,
+            f if f < 0 => Direction::Back,
+            _ => Direction::Sideways,
+        }
+    }
+
+    fn western(&self, mv: &Move, legal: &[Move], options: &WriteOptions) -> String {
+        let piece = western_piece(self.moving_piece(mv));
+        let Some(from) = mv.from else {
+            return format!("{}*{}", piece, mv.to);
+        };
+        let origin = if options.always_write_origin || !self.rivals(mv, legal).is_empty() {
+            from.to_string()
+        } else {
+            String::new()
+        };
+        let action = if self.board.get_piece(mv.to).is_some() { "x" } else { "-" };
+        let promotion = if mv.is_promotion {
+            "+"
+        } else if self.declined_promotion(mv, legal) {
+            "="
+        } else {
+            ""
+        };
+        format!("{}{}{}{}{}", piece, origin, action, mv.to, promotion)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Up,
+    Back,
+    Sideways,
+}
+
+impl Direction {
+    fn mark(self) -> &'static str {
+        match self {
+            Direction::Up => "上",
+            Direction::Back => "引",
+            Direction::Sideways => "寄",
+        }
+    }
+}
+
+/// Choices that change how a move is written but not which move it is
+#[derive(Debug, Clone, Copy)]
+struct WriteOptions {
+    /// Write 同 for a move to the previous move's square
+    same_square: bool,
+    /// Write 打 on every drop (KI2 omits it when no board piece could move there)
+    always_mark_drops: bool,
+    /// Write the origin of every Western board move
+    always_write_origin: bool,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            same_square: true,
+            always_mark_drops: false,
+            always_write_origin: false,
+        }
+    }
+}
+
+impl WriteOptions {
+    /// Every way of writing a move that reading accepts
+    fn variants() -> Vec<Self> {
+        let mut variants = Vec::with_capacity(8);
+        for same_square in [true, false] {
+            for always_mark_drops in [false, true] {
+                for always_write_origin in [false, true] {
+                    variants.push(Self {
+                        same_square,
+                        always_mark_drops,
+                        always_write_origin,
+                    });
+                }
+            }
+        }
+        variants
+    }
+}
+
+fn kif_square(pos: Position) -> String {
+    format!("{}{}", KIF_FILES[usize::from(8 - pos.col)], KIF_RANKS[usize::from(pos.row)])
+}
+
+fn kif_piece(piece: PieceType) -> &'static str {
+    match piece {
+        PieceType::Pawn => "歩",
+        PieceType::Lance => "香",
+        PieceType::Knight => "桂",
+        PieceType::Silver => "銀",
+        PieceType::Gold => "金",
+        PieceType::Bishop => "角",
+        PieceType::Rook => "飛",
+        PieceType::King => "玉",
+        PieceType::PromotedPawn => "と",
+        PieceType::PromotedLance => "成香",
+        PieceType::PromotedKnight => "成桂",
+        PieceType::PromotedSilver => "成銀",
+        PieceType::PromotedBishop => "馬",
+        PieceType::PromotedRook => "龍",
+    }
+}
+
+fn western_piece(piece: PieceType) -> &'static str {
+    match piece {
+        PieceType::Pawn => "P",
+        PieceType::Lance => "L",
+        PieceType::Knight => "N",
+        PieceType::Silver => "S",
+        PieceType::Gold => "G",
+        PieceType::Bishop => "B",
+        PieceType::Rook => "R",
+        PieceType::King => "K",
+        PieceType::PromotedPawn => "+P",
+        PieceType::PromotedLance => "+L",
+        PieceType::PromotedKnight => "+N",
+        PieceType::PromotedSilver => "+S",
+        PieceType::PromotedBishop => "+B",
+        PieceType::PromotedRook => "+R",
+    }
+}
+
+/// Fold the spellings that mean the same move onto one
+fn normalize(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '▲' | '△' | '☗' | '☖' | '　' => {}
+            c if c.is_whitespace() => {}
+            '１'..='９' => out.push(char::from(b'1' + (c as u32 - '１' as u32) as u8)),
+            '竜' => out.push('龍'),
+            '王' => out.push('玉'),
+            '全' => out.push_str("成銀"),
+            '圭' => out.push_str("成桂"),
+            '杏' => out.push_str("成香"),
+            c => {
+                if let Some(rank) = KIF_RANKS.iter().position(|kanji| kanji.starts_with(c)) {
+                    out.push(char::from(b'1' + rank as u8));
+                } else {
+                    out.push(c);
+                }
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCategory;
+
+    fn convert(
+        sfen: &str,
+        previous_to: Option<Position>,
+        usi: &str,
+        style: NotationStyle,
+    ) -> String {
+        let (board, player, captured) = BitboardBoard::from_fen(sfen).unwrap();
+        NotationContext::new(&board, &captured, player, previous_to)
+            .convert(usi, NotationStyle::Usi, style)
+            .unwrap()
+    }
+
+    const START: &str = "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1";
+
+    #[test]
+    fn test_plain_moves_in_every_style() {
+        assert_eq!(convert(START, None, "7g7f", NotationStyle::Kif), "７六歩(77)");
+        assert_eq!(convert(START, None, "7g7f", NotationStyle::Ki2), "７六歩");
+        assert_eq!(convert(START, None, "7g7f", NotationStyle::Western), "P-7f");
+
+        // After 7g7f 3c3d the bishops face each other
+        let sfen = "lnsgkgsnl/1r5b1/pppppp1pp/6p2/9/2P6/PP1PPPPPP/1B5R1/LNSGKGSNL b - 3";
+        assert_eq!(convert(sfen, None, "8h2b+", NotationStyle::Kif), "２二角成(88)");
+        assert_eq!(convert(sfen, None, "8h2b", NotationStyle::Western), "Bx2b=");
+        assert_eq!(convert(sfen, None, "8h2b+", NotationStyle::Western), "Bx2b+");
+
+        // Gote recaptures the horse on the square sente just moved to
+        let sfen = "lnsgkgsnl/1r5+B1/pppppp1pp/6p2/9/2P6/PP1PPPPPP/7R1/LNSGKGSNL w B 4";
+        let previous_to = Some(Position::new(1, 7));
+        assert_eq!(convert(sfen, previous_to, "3a2b", NotationStyle::Kif), "同　銀(31)");
+        assert_eq!(convert(sfen, previous_to, "3a2b", NotationStyle::Ki2), "同銀");
+        assert_eq!(convert(sfen, previous_to, "3a2b", NotationStyle::Western), "Sx2b");
+    }
+
+    #[test]
+    fn test_japanese_disambiguation() {
+        // Golds on 6i and 4i can both reach 5h
+        let sfen = "4k4/9/9/9/9/9/9/9/3GKG3 b - 1";
+        assert_eq!(convert(sfen, None, "4i5h", NotationStyle::Ki2), "５八金右");
+        assert_eq!(convert(sfen, None, "6i5h", NotationStyle::Ki2), "５八金左");
+        assert_eq!(convert(sfen, None, "6i5h", NotationStyle::Western), "G6i-5h");
+
+        // Golds on 5i and 4i reaching 5h: one straight up, one from the side of it
+        let sfen = "4k4/9/9/9/9/9/9/9/3KGG3 b - 1";
+        assert_eq!(convert(sfen, None, "5i5h", NotationStyle::Ki2), "５八金直");
+        assert_eq!(convert(sfen, None, "4i5h", NotationStyle::Ki2), "５八金右");
+
+        // A silver on 6g also reaches 5h, but only another gold would need a mark
+        let sfen = "4k4/9/9/9/9/9/3S5/5G3/4K4 b - 1";
+        assert_eq!(convert(sfen, None, "4h5h", NotationStyle::Ki2), "５八金");
+
+        // Gote: its right is the board's left; golds on 4a and 6a reaching 5b
+        let sfen = "3gkg3/9/9/9/9/9/9/9/4K4 w - 1";
+        assert_eq!(convert(sfen, None, "6a5b", NotationStyle::Ki2), "５二金右");
+        assert_eq!(convert(sfen, None, "4a5b", NotationStyle::Ki2), "５二金左");
+    }
+
+    #[test]
+    fn test_reading_accepts_common_spellings() {
+        let (board, player, captured) = BitboardBoard::from_fen(START).unwrap();
+        let context = NotationContext::new(&board, &captured, player, None);
+        for text in ["▲７六歩(77)", "７六歩", "76歩", "7六歩"] {
+            let style = if text.contains('(') {
+                NotationStyle::Kif
+            } else {
+                NotationStyle::Ki2
+            };
+            assert_eq!(context.parse(text, style).unwrap().to_usi_string(), "7g7f", "{}", text);
+        }
+        for text in ["P-7f", "P7g-7f"] {
+            let usi = context.convert(text, NotationStyle::Western, NotationStyle::Usi);
+            assert_eq!(usi.unwrap(), "7g7f");
+        }
+        let error = context.parse("５五歩", NotationStyle::Ki2).unwrap_err();
+        assert_eq!(error.category(), ErrorCategory::Rule);
+
+        // Two golds reach 5h: the bare move is ambiguous, the marked one is not
+        let (board, player, captured) =
+            BitboardBoard::from_fen("4k4/9/9/9/9/9/9/9/3GKG3 b - 1").unwrap();
+        let context = NotationContext::new(&board, &captured, player, None);
+        let error = context.parse("５八金", NotationStyle::Ki2).unwrap_err();
+        assert_eq!(error.category(), ErrorCategory::Parse);
+        let mv = context.parse("５八金左", NotationStyle::Ki2).unwrap();
+        assert_eq!(mv.to_usi_string(), "6i5h");
+    }
+}
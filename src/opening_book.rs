@@ -1,8 +1,13 @@
+use crate::asset_storage::{AssetStats, AssetStorage};
+use crate::handicap::Handicap;
 use crate::types::core::{Move, PieceType, Player, Position};
 use lru::LruCache;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// The book built into the engine, in the JSON format `from_json` reads
+pub const EMBEDDED_BOOK_JSON: &str = include_str!("ai/openingBook.json");
+
 /// Enhanced book move with comprehensive metadata
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct BookMove {
@@ -340,6 +345,9 @@ pub struct OpeningBook {
     /// Chunk manager for streaming mode (None if streaming not enabled)
     #[serde(skip)]
     chunk_manager: Option<ChunkManager>,
+    /// Book file read entry by entry on lookup (None unless opened with `open_mapped`)
+    #[serde(skip)]
+    paged: Option<std::sync::Arc<PagedBook>>,
 }
 
 impl Default for OpeningBook {
@@ -374,6 +382,7 @@ impl<'de> Deserialize<'de> for OpeningBook {
             metadata: data.metadata,
             hash_collision_stats: HashCollisionStats::new(),
             chunk_manager: None,
+            paged: None,
         })
     }
 }
@@ -602,6 +611,7 @@ impl OpeningBook {
             },
             hash_collision_stats: HashCollisionStats::new(),
             chunk_manager: None,
+            paged: None,
         }
     }
 
@@ -617,9 +627,39 @@ impl OpeningBook {
         reader.read_opening_book()
     }
 
+    /// Open a binary book file without decoding it: the file is memory-mapped
+    /// where the platform allows and entries are paged in as lookups reach them
+    pub fn open_mapped(path: impl AsRef<std::path::Path>) -> Result<Self, OpeningBookError> {
+        let storage = AssetStorage::open(path)
+            .map_err(|e| OpeningBookError::IoError(format!("Failed to open book: {}", e)))?;
+        Self::from_storage(storage)
+    }
+
+    /// Opening book read entry by entry from `storage`
+    pub fn from_storage(storage: AssetStorage) -> Result<Self, OpeningBookError> {
+        let paged = PagedBook::new(storage)?;
+        let header = paged.header();
+        let mut book = Self::new();
+        book.total_moves = header.total_moves as usize;
+        book.loaded = header.entry_count > 0;
+        book.metadata.version = header.version;
+        book.metadata.position_count = header.entry_count as usize;
+        book.metadata.move_count = header.total_moves as usize;
+        book.metadata.created_at = Some(header.created_at.to_string());
+        book.metadata.updated_at = Some(header.updated_at.to_string());
+        book.paged = Some(std::sync::Arc::new(paged));
+        Ok(book)
+    }
+
+    /// Memory use of the book file, if the book is read from one
+    pub fn asset_stats(&self) -> Option<AssetStats> {
+        self.paged.as_ref().map(|paged| paged.stats())
+    }
+
     /// Load opening book from binary data
     pub fn load_from_binary(&mut self, data: &[u8]) -> Result<(), OpeningBookError> {
         let book = Self::from_binary(data)?;
+        self.paged = None;
         self.positions = book.positions;
         self.total_moves = book.total_moves;
         self.loaded = book.loaded;
@@ -627,6 +667,11 @@ impl OpeningBook {
         Ok(())
     }
 
+    /// The book built into the engine
+    pub fn embedded() -> Result<Self, OpeningBookError> {
+        Self::from_json(EMBEDDED_BOOK_JSON)
+    }
+
     /// Create opening book from JSON data (for migration)
     pub fn from_json(json_data: &str) -> Result<Self, OpeningBookError> {
         use crate::opening_book_converter::OpeningBookConverter;
@@ -638,6 +683,7 @@ impl OpeningBook {
     /// Load opening book from JSON data (for backward compatibility)
     pub fn load_from_json(&mut self, json_data: &str) -> Result<(), OpeningBookError> {
         let book = Self::from_json(json_data)?;
+        self.paged = None;
         self.positions = book.positions;
         self.total_moves = book.total_moves;
         self.loaded = book.loaded;
@@ -653,6 +699,7 @@ impl OpeningBook {
     /// Get all moves for a position
     pub fn get_moves(&mut self, fen: &str) -> Option<Vec<BookMove>> {
         let hash = self.hash_fen(fen);
+        self.page_in(hash);
 
         // First check cache
         if let Some(entry) = self.position_cache.get(&hash) {
@@ -683,6 +730,7 @@ impl OpeningBook {
     /// Get the best move for a position with weight-based selection
     pub fn get_best_move(&mut self, fen: &str) -> Option<Move> {
         let hash = self.hash_fen(fen);
+        self.page_in(hash);
         let player = Self::determine_player_from_fen(fen);
 
         // First check cache
@@ -744,6 +792,7 @@ impl OpeningBook {
         >,
     ) -> Option<Move> {
         let hash = self.hash_fen(fen);
+        self.page_in(hash);
         let player = Self::determine_player_from_fen(fen);
 
         // Get position entry
@@ -831,6 +880,7 @@ impl OpeningBook {
     /// Get a random move for a position with weighted random selection
     pub fn get_random_move(&mut self, fen: &str) -> Option<Move> {
         let hash = self.hash_fen(fen);
+        self.page_in(hash);
         let player = Self::determine_player_from_fen(fen);
 
         // First check cache
@@ -867,16 +917,36 @@ impl OpeningBook {
 
     /// Get all moves for a position with enhanced metadata
     pub fn get_moves_with_metadata(&self, fen: &str) -> Option<Vec<(BookMove, Move)>> {
-        if let Some(entry) = self.positions.get(&self.hash_fen(fen)) {
-            let player = Self::determine_player_from_fen(fen);
-            let moves: Vec<(BookMove, Move)> = entry
-                .moves
-                .iter()
-                .map(|book_move| (book_move.clone(), book_move.to_engine_move(player)))
-                .collect();
-            return Some(moves);
+        let hash = self.hash_fen(fen);
+        let paged_entry;
+        let entry = match self.positions.get(&hash) {
+            Some(entry) => entry,
+            None => {
+                paged_entry = self.paged.as_ref()?.lookup(hash).ok()??;
+                &paged_entry
+            }
+        };
+        let player = Self::determine_player_from_fen(fen);
+        let moves: Vec<(BookMove, Move)> = entry
+            .moves
+            .iter()
+            .map(|book_move| (book_move.clone(), book_move.to_engine_move(player)))
+            .collect();
+        Some(moves)
+    }
+
+    /// Read the entry for `hash` from the book file into `positions`, if the book
+    /// is paged and the entry is not in memory yet
+    fn page_in(&mut self, hash: u64) {
+        let Some(paged) = &self.paged else {
+            return;
+        };
+        if self.positions.contains_key(&hash) || self.lazy_positions.contains_key(&hash) {
+            return;
+        }
+        if let Ok(Some(entry)) = paged.lookup(hash) {
+            self.positions.insert(hash, entry);
         }
-        None
     }
 
     /// Load a lazy position into memory
@@ -1019,6 +1089,27 @@ impl OpeningBook {
         &self.metadata
     }
 
+    /// Book positions grouped by the handicap setup they belong to
+    ///
+    /// Positions are keyed by FEN, so each handicap's lines already live apart
+    /// from the even-game lines; this recovers the section of each position from
+    /// its material. Positions matching no standard setup are left out.
+    pub fn handicap_sections(&self) -> HashMap<Handicap, Vec<&str>> {
+        let fens = self
+            .positions
+            .values()
+            .map(|entry| entry.fen.as_str())
+            .chain(self.lazy_positions.values().map(|entry| entry.fen.as_str()));
+
+        let mut sections: HashMap<Handicap, Vec<&str>> = HashMap::new();
+        for fen in fens {
+            if let Some(handicap) = Handicap::classify_sfen(fen) {
+                sections.entry(handicap).or_default().push(fen);
+            }
+        }
+        sections
+    }
+
     /// Get cache statistics
     pub fn get_cache_stats(&self) -> (usize, usize) {
         (self.position_cache.len(), self.position_cache.cap().get())
@@ -1513,6 +1604,15 @@ impl OpeningBook {
         }
     }
 
+    /// All positions in the book, loading lazy positions first
+    pub fn position_entries(&mut self) -> Vec<PositionEntry> {
+        let lazy_hashes: Vec<u64> = self.lazy_positions.keys().cloned().collect();
+        for hash in lazy_hashes {
+            let _ = self.load_lazy_position(hash);
+        }
+        self.positions.values().cloned().collect()
+    }
+
     /// Collect all entries suitable for transposition table prefill
     pub fn collect_prefill_entries(&mut self) -> Vec<OpeningBookPrefillEntry> {
         // Materialize all lazy positions to ensure comprehensive coverage
@@ -1770,7 +1870,27 @@ pub mod coverage;
 #[path = "opening_book/validation.rs"]
 pub mod validation;
 
+/// Named opening detection from move sequences
+#[path = "opening_book/classifier.rs"]
+pub mod classifier;
+
+/// Opening repertoires and deviations from them
+#[path = "opening_book/repertoire.rs"]
+pub mod repertoire;
+
+/// Castle and rook-file plans for positions out of the exact book
+#[path = "opening_book/shape_book.rs"]
+pub mod shape_book;
+
+/// Entry-by-entry reading of large book files
+#[path = "opening_book/paged_book.rs"]
+pub mod paged_book;
+
+pub use classifier::{OpeningMatch, OpeningTrie};
+pub use paged_book::PagedBook;
+pub use repertoire::{Deviation, Repertoire};
 pub use coverage::{CoverageAnalyzer, CoverageReport};
+pub use shape_book::{ShapeBook, ShapePlan};
 pub use statistics::BookStatistics;
 pub use validation::{BookValidator, ValidationReport};
 
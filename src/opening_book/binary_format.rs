@@ -346,6 +346,7 @@ impl BinaryReader {
                 },
                 hash_collision_stats: crate::opening_book::HashCollisionStats::new(),
                 chunk_manager: None,
+                paged: None,
             });
         }
 
@@ -393,6 +394,7 @@ impl BinaryReader {
             },
             hash_collision_stats: crate::opening_book::HashCollisionStats::new(),
             chunk_manager: None,
+            paged: None,
         })
     }
 
@@ -0,0 +1,208 @@
+/// Named opening detection from the game's move sequence
+///
+/// A prefix trie over USI move sequences, built by walking the opening book from
+/// the starting position and labelling each book move with its `opening_name`.
+/// Classifying a game follows its moves down the trie and reports the deepest
+/// labelled node, so the name refines as the game leaves the common trunk
+/// (e.g. "Ibisha" becoming "Yagura") and sticks once the game leaves the book.
+use super::OpeningBook;
+use crate::bitboards::BitboardBoard;
+use crate::types::board::CapturedPieces;
+use crate::types::core::{Move, Player};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Book depth walked when building the trie from an opening book
+pub const DEFAULT_CLASSIFIER_PLIES: usize = 24;
+
+/// The opening a game is in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpeningMatch {
+    pub name: String,
+    /// Number of the game's moves that follow the named book line
+    pub plies: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+struct TrieNode {
+    name: Option<String>,
+    children: HashMap<String, TrieNode>,
+}
+
+/// Prefix trie from USI move sequences to opening names
+#[derive(Debug, Clone, Default)]
+pub struct OpeningTrie {
+    root: TrieNode,
+    lines: usize,
+}
+
+impl OpeningTrie {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build the trie by walking `book` from `start_sfen` for up to `max_plies` plies
+    pub fn from_book(book: &mut OpeningBook, start_sfen: &str, max_plies: usize) -> Self {
+        let mut trie = Self::new();
+        let Ok((board, player, captured)) = BitboardBoard::from_fen(start_sfen) else {
+            return trie;
+        };
+        let mut visited = HashSet::new();
+        let mut stack = vec![(board, player, captured, Vec::<String>::new())];
+
+        while let Some((board, player, captured, line)) = stack.pop() {
+            let Some(book_moves) = book_moves_at(book, &board, player, &captured, line.len())
+            else {
+                continue;
+            };
+            for book_move in book_moves {
+                let Some(usi) = book_move.move_notation.as_deref() else {
+                    continue;
+                };
+                let Ok(move_) = Move::from_usi_string(usi, player, &board) else {
+                    continue;
+                };
+                let mut next_line = line.clone();
+                next_line.push(usi.to_string());
+                if let Some(name) = book_move.opening_name.as_deref() {
+                    trie.insert(&next_line, name);
+                }
+
+                let mut next_board = board.clone();
+                let mut next_captured = captured.clone();
+                if move_.from.is_none() {
+                    next_captured.remove_piece(move_.piece_type, player);
+                }
+                if let Some(taken) = next_board.make_move(&move_) {
+                    next_captured.add_piece(taken.piece_type, player);
+                }
+                let key = next_board.to_fen(player.opposite(), &next_captured);
+                if next_line.len() < max_plies && visited.insert(key) {
+                    stack.push((next_board, player.opposite(), next_captured, next_line));
+                }
+            }
+        }
+        trie
+    }
+
+    /// Label the line `moves` with `name`; a later label for the same line replaces it
+    pub fn insert(&mut self, moves: &[String], name: &str) {
+        let mut node = &mut self.root;
+        for usi in moves {
+            node = node.children.entry(usi.clone()).or_default();
+        }
+        if node.name.is_none() {
+            self.lines += 1;
+        }
+        node.name = Some(name.to_string());
+    }
+
+    /// The deepest named opening along `moves`
+    pub fn classify(&self, moves: &[String]) -> Option<OpeningMatch> {
+        let mut node = &self.root;
+        let mut found = None;
+        for (ply, usi) in moves.iter().enumerate() {
+            let Some(child) = node.children.get(usi) else {
+                break;
+            };
+            node = child;
+            if let Some(name) = &node.name {
+                found = Some(OpeningMatch {
+                    name: name.clone(),
+                    plies: ply + 1,
+                });
+            }
+        }
+        found
+    }
+
+    /// Number of labelled lines
+    pub fn len(&self) -> usize {
+        self.lines
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines == 0
+    }
+}
+
+/// Book moves for a position reached after `ply` plies. Book FENs may or may not
+/// carry a move number, so both forms are tried.
+pub(super) fn book_moves_at(
+    book: &mut OpeningBook,
+    board: &BitboardBoard,
+    player: Player,
+    captured: &CapturedPieces,
+    ply: usize,
+) -> Option<Vec<super::BookMove>> {
+    let fen = board.to_fen(player, captured);
+    book.get_moves(&format!("{} {}", fen, ply + 1))
+        .or_else(|| book.get_moves(&fen))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::opening_book::BookMove;
+    use crate::types::core::{PieceType, Position};
+
+    const START: &str = "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1";
+
+    fn line(moves: &[&str]) -> Vec<String> {
+        moves.iter().map(|m| m.to_string()).collect()
+    }
+
+    fn book_move(from: (u8, u8), to: (u8, u8), usi: &str, name: &str) -> BookMove {
+        let mut book_move = BookMove::new(
+            Some(Position::new(from.0, from.1)),
+            Position::new(to.0, to.1),
+            PieceType::Pawn,
+            false,
+            false,
+            500,
+            0,
+        );
+        book_move.move_notation = Some(usi.to_string());
+        book_move.opening_name = Some(name.to_string());
+        book_move
+    }
+
+    #[test]
+    fn test_classify_reports_deepest_named_line() {
+        let mut trie = OpeningTrie::new();
+        trie.insert(&line(&["7g7f"]), "Ibisha");
+        trie.insert(&line(&["7g7f", "8c8d", "6g6f"]), "Yagura");
+        assert_eq!(trie.len(), 2);
+
+        let game = line(&["7g7f", "8c8d", "6g6f", "3c3d", "5i6h"]);
+        assert_eq!(
+            trie.classify(&game),
+            Some(OpeningMatch {
+                name: "Yagura".to_string(),
+                plies: 3
+            })
+        );
+        assert_eq!(trie.classify(&line(&["7g7f", "3c3d"])).unwrap().name, "Ibisha");
+        assert_eq!(trie.classify(&line(&["2g2f"])), None);
+    }
+
+    #[test]
+    fn test_from_book_follows_book_moves() {
+        let mut book = OpeningBook::new();
+        book.add_position(
+            START.to_string(),
+            vec![book_move((6, 2), (5, 2), "7g7f", "Ibisha")],
+        );
+        let (mut board, _, captured) = BitboardBoard::from_fen(START).unwrap();
+        let first = Move::from_usi_string("7g7f", Player::Black, &board).unwrap();
+        board.make_move(&first);
+        book.add_position(
+            format!("{} 2", board.to_fen(Player::White, &captured)),
+            vec![book_move((2, 1), (3, 1), "8c8d", "Aigakari")],
+        );
+
+        let trie = OpeningTrie::from_book(&mut book, START, DEFAULT_CLASSIFIER_PLIES);
+        assert_eq!(trie.len(), 2);
+        assert_eq!(trie.classify(&line(&["7g7f", "8c8d"])).unwrap().name, "Aigakari");
+    }
+}
@@ -0,0 +1,122 @@
+/// Paged SBOB reader for books too large to decode up front
+///
+/// `BinaryReader` turns a whole book into `PositionEntry`s at load time.
+/// `PagedBook` instead keeps the file in `AssetStorage` and binary-searches the
+/// hash table, which the writer sorts by hash, on each lookup, decoding only
+/// the entry found. Entries are written back to back in hash order, so an entry
+/// ends where the next one starts. Only the pages the game's positions live on
+/// are ever read.
+use super::binary_format::{BinaryHeader, BinaryReader};
+use super::{OpeningBookError, PositionEntry};
+use crate::asset_storage::{AssetStats, AssetStorage};
+
+const HEADER_SIZE: usize = 48;
+const SLOT_SIZE: usize = 16;
+
+fn truncated() -> OpeningBookError {
+    OpeningBookError::BinaryFormatError("Unexpected end of data".to_string())
+}
+
+/// An SBOB file read entry by entry
+#[derive(Debug)]
+pub struct PagedBook {
+    storage: AssetStorage,
+    header: BinaryHeader,
+}
+
+impl PagedBook {
+    /// Check the header and hash table bounds of the book in `storage`
+    pub fn new(storage: AssetStorage) -> Result<Self, OpeningBookError> {
+        let header = BinaryHeader::from_bytes(storage.read(0, HEADER_SIZE).ok_or_else(truncated)?)?;
+        let table_end = usize::try_from(header.hash_table_size)
+            .ok()
+            .and_then(|size| size.checked_mul(SLOT_SIZE))
+            .and_then(|size| size.checked_add(HEADER_SIZE));
+        if header.entry_count > header.hash_table_size
+            || table_end.map_or(true, |end| end > storage.len())
+        {
+            return Err(truncated());
+        }
+        Ok(Self { storage, header })
+    }
+
+    pub fn header(&self) -> &BinaryHeader {
+        &self.header
+    }
+
+    pub fn stats(&self) -> AssetStats {
+        self.storage.stats()
+    }
+
+    /// The entry stored under `hash`, if the book has one
+    pub fn lookup(&self, hash: u64) -> Result<Option<PositionEntry>, OpeningBookError> {
+        let (mut low, mut high) = (0, self.header.entry_count as usize);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match self.slot(mid)?.0.cmp(&hash) {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+                std::cmp::Ordering::Equal => return self.entry_at(mid).map(Some),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Hash and entry offset in slot `index` of the hash table
+    fn slot(&self, index: usize) -> Result<(u64, u64), OpeningBookError> {
+        let offset = HEADER_SIZE + index * SLOT_SIZE;
+        let hash = self.storage.read_u64(offset).ok_or_else(truncated)?;
+        let entry_offset = self.storage.read_u64(offset + 8).ok_or_else(truncated)?;
+        Ok((hash, entry_offset))
+    }
+
+    fn entry_at(&self, index: usize) -> Result<PositionEntry, OpeningBookError> {
+        let start = self.slot(index)?.1 as usize;
+        let end = if index + 1 < self.header.entry_count as usize {
+            self.slot(index + 1)?.1 as usize
+        } else {
+            self.storage.len()
+        };
+        let bytes = self.storage.read(start, end.saturating_sub(start)).ok_or_else(truncated)?;
+        let (fen, moves) = BinaryReader::new(bytes.to_vec()).read_position_entry()?;
+        Ok(PositionEntry { fen, moves })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::binary_format::BinaryWriter;
+    use super::super::{BookMove, OpeningBook};
+    use super::*;
+    use crate::types::core::{PieceType, Position};
+
+    const START: &str = "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b -";
+
+    #[test]
+    fn test_lookup_reads_only_the_entry_found() {
+        let mut book = OpeningBook::new();
+        for file in 1..=9u8 {
+            let fen = format!("{} {}", START, file);
+            let mv = BookMove::new(
+                Some(Position::new(6, 9 - file)),
+                Position::new(5, 9 - file),
+                PieceType::Pawn,
+                false,
+                false,
+                100,
+                0,
+            );
+            book.add_position(fen, vec![mv]);
+        }
+        let bytes = BinaryWriter::new().write_opening_book(&book).unwrap();
+        let paged = PagedBook::new(AssetStorage::from_bytes("book.sbob", bytes)).unwrap();
+        assert_eq!(paged.header().entry_count, 9);
+
+        let fen = format!("{} 7", START);
+        let entry = paged.lookup(book.hash_fen(&fen)).unwrap().unwrap();
+        assert_eq!(entry.fen, fen);
+        assert_eq!(entry.moves[0].to, Position::new(5, 2));
+        assert!(paged.lookup(12345).unwrap().is_none());
+        assert!(paged.stats().reads > 0);
+    }
+}
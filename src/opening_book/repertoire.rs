@@ -0,0 +1,199 @@
+/// Opening repertoires and where a game left them
+///
+/// A repertoire is a tree of USI move sequences from one starting position:
+/// either the player's own lines, where the first move recorded at a branch is
+/// the one recommended there, or the opening book walked from the start, where
+/// the book's heaviest move is. Following a game's moves down the tree finds
+/// the first move the repertoire did not expect; running off the end of a line
+/// is not a deviation, since the repertoire has nothing more to say there.
+use super::classifier::book_moves_at;
+use super::OpeningBook;
+use crate::bitboards::BitboardBoard;
+use crate::types::core::Move;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Book depth walked when building a repertoire from an opening book
+pub const DEFAULT_REPERTOIRE_PLIES: usize = 24;
+
+/// The first move of a game that left its repertoire
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Deviation {
+    /// Ply of the deviating move, 1 for the first move of the game
+    pub ply: usize,
+    /// The move played, in USI notation
+    pub played: String,
+    /// The move the repertoire recommends instead
+    pub recommended: String,
+    /// Every move the repertoire has for the position, recommended first
+    pub expected: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct RepertoireNode {
+    /// Continuations in recommendation order
+    moves: Vec<(String, RepertoireNode)>,
+}
+
+impl RepertoireNode {
+    fn child(&self, usi: &str) -> Option<&RepertoireNode> {
+        self.moves.iter().find(|(mv, _)| mv == usi).map(|(_, node)| node)
+    }
+
+    fn child_mut(&mut self, usi: &str) -> &mut RepertoireNode {
+        let index = match self.moves.iter().position(|(mv, _)| mv == usi) {
+            Some(index) => index,
+            None => {
+                self.moves.push((usi.to_string(), RepertoireNode::default()));
+                self.moves.len() - 1
+            }
+        };
+        &mut self.moves[index].1
+    }
+}
+
+/// Tree of the lines a player means to follow
+#[derive(Debug, Clone, Default)]
+pub struct Repertoire {
+    root: RepertoireNode,
+    lines: usize,
+}
+
+impl Repertoire {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Repertoire of `lines`, each a space-separated USI move sequence
+    pub fn from_lines<S: AsRef<str>>(lines: &[S]) -> Self {
+        let mut repertoire = Self::new();
+        for line in lines {
+            let moves: Vec<String> = line.as_ref().split_whitespace().map(str::to_string).collect();
+            repertoire.add_line(&moves);
+        }
+        repertoire
+    }
+
+    /// Repertoire of the book's lines from `start_sfen`, up to `max_plies` deep,
+    /// recommending the heaviest book move at each position
+    pub fn from_book(book: &mut OpeningBook, start_sfen: &str, max_plies: usize) -> Self {
+        let mut repertoire = Self::new();
+        let Ok((board, player, captured)) = BitboardBoard::from_fen(start_sfen) else {
+            return repertoire;
+        };
+        let mut visited = HashSet::new();
+        let mut stack = vec![(board, player, captured, Vec::<String>::new())];
+
+        while let Some((board, player, captured, line)) = stack.pop() {
+            let Some(mut book_moves) = book_moves_at(book, &board, player, &captured, line.len())
+            else {
+                continue;
+            };
+            book_moves.sort_by(|a, b| b.weight.cmp(&a.weight));
+            for book_move in book_moves {
+                let Some(usi) = book_move.move_notation.as_deref() else {
+                    continue;
+                };
+                let Ok(move_) = Move::from_usi_string(usi, player, &board) else {
+                    continue;
+                };
+                let mut next_line = line.clone();
+                next_line.push(usi.to_string());
+                repertoire.add_line(&next_line);
+
+                let mut next_board = board.clone();
+                let mut next_captured = captured.clone();
+                if move_.from.is_none() {
+                    next_captured.remove_piece(move_.piece_type, player);
+                }
+                if let Some(taken) = next_board.make_move(&move_) {
+                    next_captured.add_piece(taken.piece_type, player);
+                }
+                let key = next_board.to_fen(player.opposite(), &next_captured);
+                if next_line.len() < max_plies && visited.insert(key) {
+                    stack.push((next_board, player.opposite(), next_captured, next_line));
+                }
+            }
+        }
+        repertoire
+    }
+
+    /// Add the line `moves`; moves already in the repertoire keep their place
+    pub fn add_line(&mut self, moves: &[String]) {
+        if moves.is_empty() {
+            return;
+        }
+        let mut node = &mut self.root;
+        let mut added = false;
+        for usi in moves {
+            added |= node.child(usi).is_none();
+            node = node.child_mut(usi);
+        }
+        if added {
+            self.lines += 1;
+        }
+    }
+
+    /// The first of `moves` the repertoire did not expect, if the game left it
+    /// before running off the end of a line
+    pub fn deviation(&self, moves: &[String]) -> Option<Deviation> {
+        let mut node = &self.root;
+        for (index, usi) in moves.iter().enumerate() {
+            if node.moves.is_empty() {
+                return None;
+            }
+            match node.child(usi) {
+                Some(child) => node = child,
+                None => {
+                    let expected: Vec<String> =
+                        node.moves.iter().map(|(mv, _)| mv.clone()).collect();
+                    return Some(Deviation {
+                        ply: index + 1,
+                        played: usi.clone(),
+                        recommended: expected[0].clone(),
+                        expected,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Number of lines added that were not already part of another
+    pub fn len(&self) -> usize {
+        self.lines
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.moves.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(moves: &[&str]) -> Vec<String> {
+        moves.iter().map(|m| m.to_string()).collect()
+    }
+
+    #[test]
+    fn test_deviation_finds_first_unexpected_move() {
+        let repertoire =
+            Repertoire::from_lines(&["7g7f 3c3d 2g2f", "7g7f 8c8d 6g6f", "7g7f 3c3d 6g6f"]);
+        assert_eq!(repertoire.len(), 3);
+
+        assert_eq!(repertoire.deviation(&line(&["7g7f", "3c3d", "2g2f", "4c4d"])), None);
+        assert_eq!(
+            repertoire.deviation(&line(&["7g7f", "3c3d", "5g5f"])),
+            Some(Deviation {
+                ply: 3,
+                played: "5g5f".to_string(),
+                recommended: "2g2f".to_string(),
+                expected: line(&["2g2f", "6g6f"]),
+            })
+        );
+        assert_eq!(repertoire.deviation(&line(&["2g2f"])).unwrap().recommended, "7g7f");
+        assert_eq!(Repertoire::new().deviation(&line(&["2g2f"])), None);
+    }
+}
@@ -0,0 +1,235 @@
+/// Shape book: standard plans for positions the opening book does not know
+///
+/// Once the game leaves the exact book there is usually still a recognisable
+/// shape: a static or ranging rook and a castle being built around the king.
+/// Each shape names a rook-file range, the files the king is castling on and
+/// the castle's target squares. A position matches when its rook and king sit
+/// in those ranges, and the shape with the most castle pieces already in place
+/// wins. The plan is the set of legal moves that bring a missing castle piece
+/// to its square; the engine searches those first rather than playing them
+/// outright. Squares are written from Black's side and mirrored for White.
+use crate::bitboards::BitboardBoard;
+use crate::types::core::{Move, PieceType, Player, Position};
+
+/// A standard rook and castle formation
+#[derive(Debug, Clone)]
+pub struct Shape {
+    pub name: &'static str,
+    pub plan: &'static str,
+    /// Files (1-9, from the mover's side) the unpromoted rook must stand on
+    pub rook_files: (u8, u8),
+    /// Files the king must stand on
+    pub king_files: (u8, u8),
+    /// Castle pieces and their target squares in USI notation for Black
+    pub formation: &'static [(PieceType, &'static str)],
+}
+
+/// The shape a position matches and the moves that continue its plan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShapePlan {
+    pub name: &'static str,
+    pub plan: &'static str,
+    /// Castle pieces already on their squares, out of the formation's total
+    pub progress: (usize, usize),
+    pub moves: Vec<Move>,
+}
+
+const STATIC_ROOK: (u8, u8) = (1, 3);
+const RANGING_ROOK: (u8, u8) = (5, 8);
+
+const BUILTIN_SHAPES: &[Shape] = &[
+    Shape {
+        name: "Yagura",
+        plan: "Static rook: build the Yagura with the silver on 7g and golds on 7h and 6g",
+        rook_files: STATIC_ROOK,
+        king_files: (5, 8),
+        formation: &[
+            (PieceType::King, "8h"),
+            (PieceType::Silver, "7g"),
+            (PieceType::Gold, "7h"),
+            (PieceType::Gold, "6g"),
+            (PieceType::Pawn, "7f"),
+            (PieceType::Pawn, "6f"),
+        ],
+    },
+    Shape {
+        name: "Ibisha Anaguma",
+        plan: "Static rook: bury the king on 9i behind the lance, silver and golds",
+        rook_files: STATIC_ROOK,
+        king_files: (7, 9),
+        formation: &[
+            (PieceType::King, "9i"),
+            (PieceType::Lance, "9h"),
+            (PieceType::Silver, "8h"),
+            (PieceType::Gold, "7i"),
+            (PieceType::Gold, "7h"),
+        ],
+    },
+    Shape {
+        name: "Mino",
+        plan: "Ranging rook: castle on the right with the king on 2h and the silver on 3h",
+        rook_files: RANGING_ROOK,
+        king_files: (1, 5),
+        formation: &[
+            (PieceType::King, "2h"),
+            (PieceType::Silver, "3h"),
+            (PieceType::Gold, "4i"),
+            (PieceType::Gold, "5h"),
+        ],
+    },
+    Shape {
+        name: "Furibisha Anaguma",
+        plan: "Ranging rook: bury the king on 1i behind the lance, silver and golds",
+        rook_files: RANGING_ROOK,
+        king_files: (1, 3),
+        formation: &[
+            (PieceType::King, "1i"),
+            (PieceType::Lance, "1h"),
+            (PieceType::Silver, "2h"),
+            (PieceType::Gold, "3i"),
+            (PieceType::Gold, "3h"),
+        ],
+    },
+];
+
+/// `pos` seen from `player`'s side: Black's squares as they are, White's rotated
+fn oriented(pos: Position, player: Player) -> Position {
+    match player {
+        Player::Black => pos,
+        Player::White => Position::new(8 - pos.row, 8 - pos.col),
+    }
+}
+
+fn file_of(pos: Position, player: Player) -> u8 {
+    9 - oriented(pos, player).col
+}
+
+fn in_range(file: u8, (low, high): (u8, u8)) -> bool {
+    (low..=high).contains(&file)
+}
+
+/// Shapes consulted once the exact opening book has no move
+#[derive(Debug, Clone)]
+pub struct ShapeBook {
+    shapes: Vec<Shape>,
+}
+
+impl Default for ShapeBook {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShapeBook {
+    /// The built-in Yagura, Anaguma and Mino shapes
+    pub fn new() -> Self {
+        Self { shapes: BUILTIN_SHAPES.to_vec() }
+    }
+
+    pub fn shapes(&self) -> &[Shape] {
+        &self.shapes
+    }
+
+    /// The best matching shape for `player` and its plan moves among `legal_moves`.
+    /// `None` when no shape matches or the matching one has nothing left to play.
+    pub fn suggest(
+        &self,
+        board: &BitboardBoard,
+        player: Player,
+        legal_moves: &[Move],
+    ) -> Option<ShapePlan> {
+        let own = |piece_type: PieceType| {
+            board
+                .iter_pieces()
+                .find(|(_, piece)| piece.player == player && piece.piece_type == piece_type)
+                .map(|(pos, _)| file_of(pos, player))
+        };
+        let rook_file = own(PieceType::Rook)?;
+        let king_file = own(PieceType::King)?;
+
+        let (shape, targets, in_place) = self
+            .shapes
+            .iter()
+            .filter(|shape| {
+                in_range(rook_file, shape.rook_files) && in_range(king_file, shape.king_files)
+            })
+            .map(|shape| {
+                let targets: Vec<(PieceType, Position)> = shape
+                    .formation
+                    .iter()
+                    .filter_map(|&(piece_type, square)| {
+                        let pos = Position::from_usi_string(square).ok()?;
+                        Some((piece_type, oriented(pos, player)))
+                    })
+                    .collect();
+                let in_place = targets
+                    .iter()
+                    .filter(|&&(piece_type, pos)| {
+                        board.get_piece(pos).map_or(false, |piece| {
+                            piece.player == player && piece.piece_type == piece_type
+                        })
+                    })
+                    .count();
+                (shape, targets, in_place)
+            })
+            // First listed shape wins ties
+            .rev()
+            .max_by_key(|(_, _, in_place)| *in_place)?;
+
+        let moves: Vec<Move> = legal_moves
+            .iter()
+            .filter(|mv| {
+                mv.from.is_some()
+                    && !mv.is_promotion
+                    && targets.iter().any(|&(piece_type, pos)| {
+                        mv.piece_type == piece_type
+                            && mv.to == pos
+                            && board.get_piece(pos).map_or(true, |p| p.piece_type != piece_type)
+                    })
+            })
+            .cloned()
+            .collect();
+        if moves.is_empty() {
+            return None;
+        }
+        Some(ShapePlan {
+            name: shape.name,
+            plan: shape.plan,
+            progress: (in_place, targets.len()),
+            moves,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moves::MoveGenerator;
+
+    fn plan_for(fen: &str) -> Option<ShapePlan> {
+        let (board, player, captured) = BitboardBoard::from_fen(fen).unwrap();
+        let moves = MoveGenerator::new().generate_legal_moves(&board, player, &captured);
+        ShapeBook::new().suggest(&board, player, &moves)
+    }
+
+    #[test]
+    fn test_ranging_rook_gets_mino_plan() {
+        // Rook swung to 6h, king on its way to 2h, gold already on 4i
+        let plan = plan_for("4k4/9/9/9/9/9/5S3/3R5/5GK2 b - 1").unwrap();
+        assert_eq!(plan.name, "Mino");
+        assert_eq!(plan.progress, (1, 4));
+        let usi: Vec<String> = plan.moves.iter().map(Move::to_usi_string).collect();
+        assert!(usi.contains(&"3i2h".to_string()));
+        assert!(usi.contains(&"4g3h".to_string()));
+
+        // The same position turned round, with White to move
+        let plan = plan_for("2kg5/5r3/3s5/9/9/9/9/9/4K4 w - 1").unwrap();
+        assert_eq!(plan.name, "Mino");
+        assert!(plan.moves.iter().any(|mv| mv.to_usi_string() == "7a8b"));
+    }
+
+    #[test]
+    fn test_no_shape_without_rook() {
+        assert_eq!(plan_for("4k4/9/9/9/9/9/9/9/4K4 b - 1"), None);
+    }
+}
@@ -1,11 +1,17 @@
+use crate::bitboards::BitboardBoard;
 use crate::opening_book::*;
-use crate::types::core::PieceType;
+use crate::search::book_hash::{
+    apery_piece_type, apery_square, piece_type_from_apery, position_from_apery_square,
+    PositionHashScheme,
+};
+use crate::types::board::CapturedPieces;
+use crate::types::core::{Move, PieceType, Player};
 /// Opening Book JSON to Binary Converter
 ///
 /// This module provides functionality to convert the existing JSON opening book
 /// format to the new binary format, with enhanced move analysis and weight assignment.
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 
 /// JSON format structures for parsing the existing opening book
@@ -608,9 +614,206 @@ impl Default for OpeningBookConverterBuilder {
     }
 }
 
+/// Size of one entry of an Apery-format `book.bin`, in bytes
+pub const APERY_BOOK_ENTRY_SIZE: usize = 16;
+
+/// Book depth walked when importing an Apery book from the starting position
+pub const DEFAULT_APERY_IMPORT_PLIES: usize = 40;
+
+/// One entry of an Apery-format `book.bin`: a book move for the position whose
+/// Apery key is `key`. Entries are 16 bytes, little-endian, sorted by key:
+/// key (u64), move (u16), count (u16), score (i32).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AperyBookEntry {
+    pub key: u64,
+    /// Destination square in bits 0-6, origin square in bits 7-13 (81 + piece
+    /// type - 1 for drops), promotion in bit 14
+    pub from_to_pro: u16,
+    /// How often the move was played
+    pub count: u16,
+    pub score: i32,
+}
+
+impl AperyBookEntry {
+    /// The entry's move in USI notation, `None` if the encoding is invalid
+    pub fn usi_move(&self) -> Option<String> {
+        let to = position_from_apery_square(usize::from(self.from_to_pro & 0x7f))?;
+        let from = usize::from((self.from_to_pro >> 7) & 0x7f);
+        let promote = self.from_to_pro & (1 << 14) != 0;
+        if from >= 81 {
+            let piece_type = piece_type_from_apery(from - 80)?;
+            let piece = match piece_type {
+                PieceType::Pawn => "P",
+                PieceType::Lance => "L",
+                PieceType::Knight => "N",
+                PieceType::Silver => "S",
+                PieceType::Gold => "G",
+                PieceType::Bishop => "B",
+                PieceType::Rook => "R",
+                _ => return None,
+            };
+            return Some(format!("{}*{}", piece, to));
+        }
+        let from = position_from_apery_square(from)?;
+        Some(format!("{}{}{}", from, to, if promote { "+" } else { "" }))
+    }
+}
+
+/// Apery move encoding of a book move
+pub fn encode_apery_move(book_move: &BookMove) -> u16 {
+    let to = apery_square(book_move.to) as u16;
+    let from = match book_move.from {
+        Some(from) if !book_move.is_drop => apery_square(from) as u16,
+        _ => (80 + apery_piece_type(book_move.piece_type)) as u16,
+    };
+    let promote = if book_move.is_promotion { 1 << 14 } else { 0 };
+    to | (from << 7) | promote
+}
+
+/// Write `book` as an Apery-format `book.bin`. Move weights become counts and
+/// evaluations become scores; positions whose FEN doesn't parse are skipped.
+pub fn export_apery_book(book: &mut OpeningBook) -> Vec<u8> {
+    let mut entries = Vec::new();
+    for position in book.position_entries() {
+        let Ok((board, player, captured)) = BitboardBoard::from_fen(&position.fen) else {
+            continue;
+        };
+        let key = PositionHashScheme::Apery.hash(&board, player, &captured);
+        for book_move in &position.moves {
+            entries.push(AperyBookEntry {
+                key,
+                from_to_pro: encode_apery_move(book_move),
+                count: book_move.weight.min(u32::from(u16::MAX)) as u16,
+                score: book_move.evaluation,
+            });
+        }
+    }
+    entries.sort_by(|a, b| a.key.cmp(&b.key).then(b.count.cmp(&a.count)));
+
+    let mut data = Vec::with_capacity(entries.len() * APERY_BOOK_ENTRY_SIZE);
+    for entry in entries {
+        data.extend_from_slice(&entry.key.to_le_bytes());
+        data.extend_from_slice(&entry.from_to_pro.to_le_bytes());
+        data.extend_from_slice(&entry.count.to_le_bytes());
+        data.extend_from_slice(&entry.score.to_le_bytes());
+    }
+    data
+}
+
+/// An Apery-format `book.bin` loaded for probing by position
+#[derive(Debug, Clone, Default)]
+pub struct AperyBook {
+    entries: Vec<AperyBookEntry>,
+}
+
+impl AperyBook {
+    /// Parse a `book.bin`; entries are sorted by key if the file isn't
+    pub fn from_bytes(data: &[u8]) -> Result<Self, OpeningBookError> {
+        if data.len() % APERY_BOOK_ENTRY_SIZE != 0 {
+            return Err(OpeningBookError::BinaryFormatError(format!(
+                "Apery book size {} is not a multiple of {}",
+                data.len(),
+                APERY_BOOK_ENTRY_SIZE
+            )));
+        }
+        let mut entries: Vec<AperyBookEntry> = data
+            .chunks_exact(APERY_BOOK_ENTRY_SIZE)
+            .map(|chunk| AperyBookEntry {
+                key: u64::from_le_bytes(chunk[0..8].try_into().unwrap()),
+                from_to_pro: u16::from_le_bytes([chunk[8], chunk[9]]),
+                count: u16::from_le_bytes([chunk[10], chunk[11]]),
+                score: i32::from_le_bytes(chunk[12..16].try_into().unwrap()),
+            })
+            .collect();
+        entries.sort_by_key(|entry| entry.key);
+        Ok(Self { entries })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Entries for the position with `player` to move
+    pub fn probe(
+        &self,
+        board: &BitboardBoard,
+        player: Player,
+        captured_pieces: &CapturedPieces,
+    ) -> &[AperyBookEntry] {
+        let key = PositionHashScheme::Apery.hash(board, player, captured_pieces);
+        let start = self.entries.partition_point(|entry| entry.key < key);
+        let end = self.entries.partition_point(|entry| entry.key <= key);
+        &self.entries[start..end]
+    }
+
+    /// Import the book lines reachable from `start_sfen` within `max_plies` plies.
+    /// Apery keys can't be turned back into positions, so the book is walked
+    /// move by move; counts are scaled to weights relative to the most played
+    /// move of each position.
+    pub fn to_opening_book(&self, start_sfen: &str, max_plies: usize) -> OpeningBook {
+        let mut book = OpeningBook::new();
+        let Ok((board, player, captured)) = BitboardBoard::from_fen(start_sfen) else {
+            return book.mark_loaded();
+        };
+        let mut visited = HashSet::new();
+        let mut stack = vec![(board, player, captured, 0usize)];
+
+        while let Some((board, player, captured, ply)) = stack.pop() {
+            let fen = board.to_fen(player, &captured);
+            if !visited.insert(fen.clone()) {
+                continue;
+            }
+            let entries = self.probe(&board, player, &captured);
+            let max_count = entries.iter().map(|entry| entry.count).max().unwrap_or(0).max(1);
+            let mut book_moves = Vec::new();
+            for entry in entries {
+                let Some(usi) = entry.usi_move() else {
+                    continue;
+                };
+                let Ok(move_) = Move::from_usi_string(&usi, player, &board) else {
+                    continue;
+                };
+                let weight = u32::from(entry.count) * 1000 / u32::from(max_count);
+                book_moves.push(BookMove::new_with_metadata(
+                    move_.from,
+                    move_.to,
+                    move_.piece_type,
+                    move_.from.is_none(),
+                    move_.is_promotion,
+                    weight,
+                    entry.score,
+                    None,
+                    Some(usi),
+                ));
+
+                if ply + 1 < max_plies {
+                    let mut next_board = board.clone();
+                    let mut next_captured = captured.clone();
+                    if move_.from.is_none() {
+                        next_captured.remove_piece(move_.piece_type, player);
+                    }
+                    if let Some(taken) = next_board.make_move(&move_) {
+                        next_captured.add_piece(taken.piece_type, player);
+                    }
+                    stack.push((next_board, player.opposite(), next_captured, ply + 1));
+                }
+            }
+            if !book_moves.is_empty() {
+                book.add_position(fen, book_moves);
+            }
+        }
+        book.mark_loaded()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::core::Position;
 
     #[test]
     fn test_coordinate_conversion() {
@@ -836,4 +1039,79 @@ evaluation_scores:
         // The weight should be based on our custom config (950 base)
         assert!(moves[0].weight >= 950);
     }
+
+    #[test]
+    fn test_apery_move_encoding() {
+        let pawn_push = BookMove::new(
+            Some(Position::new(6, 2)),
+            Position::new(5, 2),
+            PieceType::Pawn,
+            false,
+            false,
+            500,
+            0,
+        );
+        let encoded = encode_apery_move(&pawn_push);
+        assert_eq!(encoded, 59 | (60 << 7));
+        let entry = AperyBookEntry {
+            key: 0,
+            from_to_pro: encoded,
+            count: 1,
+            score: 0,
+        };
+        assert_eq!(entry.usi_move().as_deref(), Some("7g7f"));
+
+        let drop = BookMove::new(None, Position::new(1, 4), PieceType::Gold, true, false, 1, 0);
+        let entry = AperyBookEntry {
+            from_to_pro: encode_apery_move(&drop),
+            ..entry
+        };
+        assert_eq!(entry.usi_move().as_deref(), Some("G*5b"));
+    }
+
+    #[test]
+    fn test_apery_book_round_trip() {
+        let start = "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1";
+        let mut book = OpeningBook::new();
+        let mut pawn_push = BookMove::new(
+            Some(Position::new(6, 2)),
+            Position::new(5, 2),
+            PieceType::Pawn,
+            false,
+            false,
+            800,
+            30,
+        );
+        pawn_push.move_notation = Some("7g7f".to_string());
+        let rook_pawn = BookMove::new(
+            Some(Position::new(6, 7)),
+            Position::new(5, 7),
+            PieceType::Pawn,
+            false,
+            false,
+            400,
+            10,
+        );
+        book.add_position(start.to_string(), vec![pawn_push, rook_pawn]);
+
+        let data = export_apery_book(&mut book);
+        assert_eq!(data.len(), 2 * APERY_BOOK_ENTRY_SIZE);
+        let apery = AperyBook::from_bytes(&data).unwrap();
+        let (board, player, captured) = BitboardBoard::from_fen(start).unwrap();
+        let entries = apery.probe(&board, player, &captured);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].usi_move().as_deref(), Some("7g7f"));
+        assert_eq!((entries[0].count, entries[0].score), (800, 30));
+        assert!(apery.probe(&board, player.opposite(), &captured).is_empty());
+
+        let mut imported = apery.to_opening_book(start, DEFAULT_APERY_IMPORT_PLIES);
+        let fen = board.to_fen(player, &captured);
+        let moves = imported.get_moves(&fen).unwrap();
+        assert_eq!(moves.len(), 2);
+        let best = moves.iter().max_by_key(|m| m.weight).unwrap();
+        assert_eq!(best.move_notation.as_deref(), Some("7g7f"));
+        assert_eq!(best.weight, 1000);
+
+        assert!(AperyBook::from_bytes(&data[..15]).is_err());
+    }
 }
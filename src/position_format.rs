@@ -0,0 +1,298 @@
+//! Position Formats
+//!
+//! Reading and writing whole positions for copy and paste: SFEN, written in
+//! canonical form (hand pieces in the usual R B G S N L P order with counts,
+//! move number always present), and BOD board diagrams through `bod`.
+//!
+//! `validate` checks what the parsers cannot: king counts, nifu, pieces that
+//! could never move, more pieces than a set has, and the side not to move
+//! standing in check. A missing king is only a warning, since tsume problems
+//! leave out the attacker's king.
+
+use crate::bitboards::BitboardBoard;
+use crate::bod;
+use crate::drop_rules::is_dead_square;
+use crate::i18n::{self, Locale};
+use crate::types::{CapturedPieces, Piece, PieceType, Player, Position};
+use std::fmt;
+
+/// Hand pieces in the order SFEN and BOD list them
+pub const HAND_ORDER: [PieceType; 7] = [
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Gold,
+    PieceType::Silver,
+    PieceType::Knight,
+    PieceType::Lance,
+    PieceType::Pawn,
+];
+
+/// Something wrong with a position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionIssue {
+    TooManyKings(Player),
+    /// Allowed, with a warning: tsume problems omit the attacker's king
+    MissingKing(Player),
+    /// Two unpromoted pawns of `player` on `file` (1-9)
+    Nifu { player: Player, file: u8 },
+    /// A pawn or lance on the last rank, or a knight on the last two
+    DeadPiece(Position),
+    /// More pieces of a kind (promoted or not, board and hands) than a set has
+    TooManyPieces { piece: PieceType, count: usize },
+    /// The side not to move is in check, so the side to move could take a king
+    OpponentInCheck,
+}
+
+impl PositionIssue {
+    /// Whether the position cannot be played; warnings can
+    pub fn is_error(&self) -> bool {
+        !matches!(self, PositionIssue::MissingKing(_))
+    }
+
+    /// Description of the issue in `locale`
+    pub fn message(&self, locale: Locale) -> String {
+        match self {
+            PositionIssue::TooManyKings(player) => i18n::text_with(
+                locale,
+                "issue.too_many_kings",
+                &[("player", &i18n::player_name(locale, *player))],
+            ),
+            PositionIssue::MissingKing(player) => i18n::text_with(
+                locale,
+                "issue.missing_king",
+                &[("player", &i18n::player_name(locale, *player))],
+            ),
+            PositionIssue::Nifu { player, file } => i18n::text_with(
+                locale,
+                "issue.nifu",
+                &[("player", &i18n::player_name(locale, *player)), ("file", file)],
+            ),
+            PositionIssue::DeadPiece(square) => {
+                i18n::text_with(locale, "issue.dead_piece", &[("square", square)])
+            }
+            PositionIssue::TooManyPieces { piece, count } => i18n::text_with(
+                locale,
+                "issue.too_many_pieces",
+                &[("count", count), ("piece", &i18n::piece_name(locale, *piece))],
+            ),
+            PositionIssue::OpponentInCheck => i18n::text(locale, "issue.opponent_in_check"),
+        }
+    }
+}
+
+impl fmt::Display for PositionIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message(Locale::En))
+    }
+}
+
+/// Pieces of each kind in a set, by unpromoted type
+fn set_count(piece: PieceType) -> usize {
+    match piece {
+        PieceType::Pawn => 18,
+        PieceType::Bishop | PieceType::Rook | PieceType::King => 2,
+        _ => 4,
+    }
+}
+
+/// Problems with a position, errors and warnings alike; empty when it is fine
+pub fn validate(
+    board: &BitboardBoard,
+    captured_pieces: &CapturedPieces,
+    side_to_move: Player,
+) -> Vec<PositionIssue> {
+    let mut issues = Vec::new();
+    let mut counts = [0usize; 14];
+    let mut kings = [0usize; 2];
+    let mut pawn_files = [[false; 9]; 2];
+
+    for row in 0..9 {
+        for col in 0..9 {
+            let square = Position::new(row, col);
+            let Some(piece) = board.get_piece(square) else {
+                continue;
+            };
+            counts[piece.unpromoted().piece_type as usize] += 1;
+            let side = piece.player as usize;
+            match piece.piece_type {
+                PieceType::King => kings[side] += 1,
+                PieceType::Pawn => {
+                    if pawn_files[side][col as usize] {
+                        issues.push(PositionIssue::Nifu { player: piece.player, file: 9 - col });
+                    }
+                    pawn_files[side][col as usize] = true;
+                }
+                _ => {}
+            }
+            if is_dead_square(piece.piece_type, square, piece.player) {
+                issues.push(PositionIssue::DeadPiece(square));
+            }
+        }
+    }
+    for piece in captured_pieces.black.iter().chain(&captured_pieces.white) {
+        counts[piece.unpromoted_version().unwrap_or(*piece) as usize] += 1;
+    }
+
+    for player in [Player::Black, Player::White] {
+        match kings[player as usize] {
+            0 => issues.push(PositionIssue::MissingKing(player)),
+            1 => {}
+            _ => issues.push(PositionIssue::TooManyKings(player)),
+        }
+    }
+    for piece in HAND_ORDER.iter().chain([PieceType::King].iter()) {
+        let count = counts[*piece as usize];
+        if count > set_count(*piece) {
+            issues.push(PositionIssue::TooManyPieces { piece: *piece, count });
+        }
+    }
+    let opponent = side_to_move.opposite();
+    if kings[opponent as usize] == 1 && board.is_king_in_check(opponent, captured_pieces) {
+        issues.push(PositionIssue::OpponentInCheck);
+    }
+    issues
+}
+
+/// A position read from text, with the move number SFEN carries
+#[derive(Clone)]
+pub struct PositionText {
+    pub board: BitboardBoard,
+    pub side_to_move: Player,
+    pub captured_pieces: CapturedPieces,
+    pub move_number: u32,
+}
+
+impl PositionText {
+    pub fn new(
+        board: BitboardBoard,
+        side_to_move: Player,
+        captured_pieces: CapturedPieces,
+        move_number: u32,
+    ) -> Self {
+        Self {
+            board,
+            side_to_move,
+            captured_pieces,
+            move_number,
+        }
+    }
+
+    /// Read SFEN or BOD, whichever `text` is
+    pub fn parse(text: &str) -> Result<Self, String> {
+        if text.contains('|') {
+            Self::from_bod(text)
+        } else {
+            Self::from_sfen(text)
+        }
+    }
+
+    /// Read an SFEN, also as pasted from a USI log (`position sfen ...`,
+    /// `startpos`); any moves after it are ignored
+    pub fn from_sfen(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let text = text.strip_prefix("position").map_or(text, str::trim_start);
+        let text = text.split(" moves").next().unwrap_or(text).trim();
+        let sfen = match text {
+            "startpos" => crate::handicap::Handicap::Even.sfen(),
+            _ => text.strip_prefix("sfen").map_or(text, str::trim_start),
+        };
+        let (board, side_to_move, captured_pieces) =
+            BitboardBoard::from_fen(sfen).map_err(|e| e.to_string())?;
+        let move_number = match sfen.split_whitespace().nth(3) {
+            Some(number) => number.parse().map_err(|_| format!("Invalid move number: {}", number))?,
+            None => 1,
+        };
+        Ok(Self::new(board, side_to_move, captured_pieces, move_number))
+    }
+
+    /// Read a BOD diagram
+    pub fn from_bod(text: &str) -> Result<Self, String> {
+        bod::parse(text)
+    }
+
+    /// Canonical SFEN
+    pub fn to_sfen(&self) -> String {
+        let placement = self.board.to_fen(self.side_to_move, &CapturedPieces::new());
+        let placement = placement.split_whitespace().next().unwrap_or_default();
+        let mut hand = String::new();
+        for player in [Player::Black, Player::White] {
+            for piece in HAND_ORDER {
+                let count = self.captured_pieces.count(piece, player);
+                if count > 1 {
+                    hand.push_str(&count.to_string());
+                }
+                if count > 0 {
+                    hand.push_str(&Piece::new(piece, player).to_fen_char());
+                }
+            }
+        }
+        format!(
+            "{} {} {} {}",
+            placement,
+            if self.side_to_move == Player::Black { 'b' } else { 'w' },
+            if hand.is_empty() { "-" } else { &hand },
+            self.move_number
+        )
+    }
+
+    /// BOD diagram
+    pub fn to_bod(&self) -> String {
+        bod::render(self)
+    }
+
+    pub fn validate(&self) -> Vec<PositionIssue> {
+        validate(&self.board, &self.captured_pieces, self.side_to_move)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sfen_and_bod_round_trip() {
+        // Unordered, uncounted hands come back in canonical order with counts
+        let position =
+            PositionText::from_sfen("position sfen 4k4/9/9/9/9/9/9/9/4K4 b PGPpr 7 moves 5i5h")
+                .unwrap();
+        let sfen = position.to_sfen();
+        assert_eq!(sfen, "4k4/9/9/9/9/9/9/9/4K4 b G2Prp 7");
+
+        let bod = position.to_bod();
+        assert!(bod.starts_with("後手の持駒：飛　歩\n"));
+        assert!(bod.contains("| ・ ・ ・ ・ 玉 ・ ・ ・ ・|九\n"));
+        assert!(bod.contains("先手の持駒：金　歩二\n手数＝6\n先手番\n"));
+        assert_eq!(PositionText::parse(&bod).unwrap().to_sfen(), sfen);
+
+        let start = PositionText::from_sfen("startpos").unwrap();
+        let bod = start.to_bod();
+        assert!(bod.contains("|v香v桂v銀v金v玉v金v銀v桂v香|一\n"));
+        assert_eq!(PositionText::parse(&bod).unwrap().to_sfen(), start.to_sfen());
+        assert!(start.validate().is_empty());
+
+        // Eighteen pawns in hand survive both formats
+        let pawns = PositionText::from_sfen("4k4/9/9/9/9/9/9/9/4K4 w 18p 1").unwrap();
+        assert!(pawns.to_bod().starts_with("後手の持駒：歩十八\n"));
+        assert_eq!(PositionText::parse(&pawns.to_bod()).unwrap().to_sfen(), pawns.to_sfen());
+    }
+
+    #[test]
+    fn test_validation_issues() {
+        let issues = |sfen: &str| PositionText::from_sfen(sfen).unwrap().validate();
+
+        // Tsume problems have no attacking king: a warning, not an error
+        let tsume = issues("4k4/9/4P4/9/9/9/9/9/9 b G 1");
+        assert_eq!(tsume, vec![PositionIssue::MissingKing(Player::Black)]);
+        assert!(!tsume[0].is_error());
+
+        let broken = issues("P3k4/9/9/4P4/4P4/9/9/9/4K4 b 3R 1");
+        assert!(broken.contains(&PositionIssue::Nifu { player: Player::Black, file: 5 }));
+        assert!(broken.contains(&PositionIssue::DeadPiece(Position::new(0, 0))));
+        let rooks = PositionIssue::TooManyPieces { piece: PieceType::Rook, count: 3 };
+        assert!(broken.contains(&rooks));
+
+        // Sente to move with gote's king already in check from the rook
+        assert_eq!(issues("4k4/9/9/9/4R4/9/9/9/4K4 b - 1"), vec![PositionIssue::OpponentInCheck]);
+        assert!(PositionText::from_bod("|v玉|一").is_err());
+    }
+}
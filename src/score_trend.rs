@@ -0,0 +1,459 @@
+//! Score Trend
+//!
+//! The evaluation after each played move, for the GUI's advantage graph. Scores
+//! arrive from live analysis while the game is played and from the post-game
+//! review; a review score replaces a live one for the same ply but not the other
+//! way round, since the review searches deeper. All scores are stored from
+//! sente's perspective so the graph needs no per-ply sign flips.
+//!
+//! Markers flag the moves the review judged to lose too much: the evaluation
+//! dropped, from the mover's point of view, by at least the inaccuracy, mistake
+//! or blunder threshold. Live scores are too shallow to judge moves by, so a
+//! marker needs review scores on both sides of the move.
+//!
+//! Critical moments flag the turning points of the game for the review
+//! timeline: positions where only one move held, because the review's best
+//! move scored at least `CRITICAL_SWING_CP` more than its second best, and
+//! moves that took the game from one outcome class (sente winning, unclear,
+//! gote winning) to another.
+
+use crate::search::mate_score::mate_distance;
+use crate::search::win_probability::win_rate_permille;
+use crate::types::core::Player;
+use serde::{Deserialize, Serialize};
+
+/// Centipawn loss that makes a move an inaccuracy
+pub const INACCURACY_CP: i32 = 50;
+
+/// Centipawn loss that makes a move a mistake
+pub const MISTAKE_CP: i32 = 100;
+
+/// Centipawn loss that makes a move a blunder
+pub const BLUNDER_CP: i32 = 200;
+
+/// Plotted scores are clamped to this many centipawns either way, so a found
+/// mate does not flatten the rest of the graph
+pub const GRAPH_SCORE_CAP: i32 = 3_000;
+
+/// Centipawns by which the best move must beat the second best for a position
+/// to be a critical moment
+pub const CRITICAL_SWING_CP: i32 = 150;
+
+/// Win rate in permille from which a side counts as winning
+pub const WINNING_WIN_RATE: u16 = 800;
+
+/// Where a score came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoreSource {
+    Live,
+    Review,
+}
+
+/// How badly the review judged a move
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MoveMarker {
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+impl MoveMarker {
+    /// Marker for a move losing `loss_cp` centipawns, if it loses enough to mark
+    pub fn from_loss(loss_cp: i32) -> Option<Self> {
+        if loss_cp >= BLUNDER_CP {
+            Some(MoveMarker::Blunder)
+        } else if loss_cp >= MISTAKE_CP {
+            Some(MoveMarker::Mistake)
+        } else if loss_cp >= INACCURACY_CP {
+            Some(MoveMarker::Inaccuracy)
+        } else {
+            None
+        }
+    }
+}
+
+/// Who the evaluation says is winning, for spotting moves that changed it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutcomeClass {
+    SenteWinning,
+    Unclear,
+    GoteWinning,
+}
+
+impl OutcomeClass {
+    /// Class of a position where sente wins with probability `win_rate` permille
+    pub fn from_win_rate(win_rate: u16) -> Self {
+        if win_rate >= WINNING_WIN_RATE {
+            OutcomeClass::SenteWinning
+        } else if win_rate <= 1000 - WINNING_WIN_RATE {
+            OutcomeClass::GoteWinning
+        } else {
+            OutcomeClass::Unclear
+        }
+    }
+}
+
+/// Why the review flagged a position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CriticalKind {
+    /// Only one move held: the best move scored `swing_cp` more than the second
+    /// best, from the point of view of the player to move
+    OnlyMove { swing_cp: i32 },
+    /// The move leading here changed who is winning
+    OutcomeChanged { before: OutcomeClass, after: OutcomeClass },
+}
+
+/// A turning point on the review timeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CriticalMoment {
+    /// Ply of the flagged position, the index of its point on the timeline
+    pub ply: u32,
+    #[serde(flatten)]
+    pub kind: CriticalKind,
+}
+
+/// The evaluation after one ply
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScorePoint {
+    /// Plies played; 0 is the starting position
+    pub ply: u32,
+    /// Move that led here in USI notation; `None` for the starting position
+    pub move_usi: Option<String>,
+    /// Centipawns from sente's perspective
+    pub score: i32,
+    /// Signed plies to mate from sente's perspective (positive when sente mates)
+    pub mate: Option<i32>,
+    pub source: ScoreSource,
+    /// Search depth behind the score, when known
+    pub depth: Option<u8>,
+    /// Centipawns from sente's perspective of the best move in this position
+    /// other than the one `score` stands for, when the review searched it
+    #[serde(default)]
+    pub second_best: Option<i32>,
+}
+
+impl ScorePoint {
+    /// Point for a search score given from the perspective of `side_to_move`,
+    /// the player to move after `move_usi`
+    pub fn from_side_to_move(
+        ply: u32,
+        move_usi: Option<String>,
+        score: i32,
+        side_to_move: Player,
+        source: ScoreSource,
+        depth: Option<u8>,
+    ) -> Self {
+        let sign = if side_to_move == Player::Black { 1 } else { -1 };
+        Self {
+            ply,
+            move_usi,
+            score: score * sign,
+            mate: mate_distance(score).map(|plies| plies * sign),
+            source,
+            depth,
+            second_best: None,
+        }
+    }
+
+    /// The same point with the second-best move's score, given like `score`
+    /// from the perspective of `side_to_move`
+    pub fn with_second_best(self, score: i32, side_to_move: Player) -> Self {
+        let sign = if side_to_move == Player::Black { 1 } else { -1 };
+        Self { second_best: Some(score * sign), ..self }
+    }
+}
+
+/// A point as plotted, with the review's judgement of the move that led to it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreSeriesPoint {
+    #[serde(flatten)]
+    pub point: ScorePoint,
+    /// `score` clamped to `GRAPH_SCORE_CAP`
+    pub plotted: i32,
+    /// Sente's win probability in permille, for plotting winning chances
+    #[serde(default)]
+    pub win_rate: u16,
+    /// Centipawns the move lost for the player who made it, by review scores
+    pub loss_cp: Option<i32>,
+    pub marker: Option<MoveMarker>,
+}
+
+/// The advantage graph of a game
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreSeries {
+    pub points: Vec<ScoreSeriesPoint>,
+    /// Plies of the moves marked as blunders
+    pub blunders: Vec<u32>,
+    /// Turning points flagged by the review, in ply order
+    #[serde(default)]
+    pub critical: Vec<CriticalMoment>,
+}
+
+/// Scores recorded for one game, ordered by ply
+#[derive(Debug, Clone)]
+pub struct ScoreTrend {
+    points: Vec<ScorePoint>,
+    /// Player making the first move: sente in even games, gote in handicap games
+    first_mover: Player,
+}
+
+impl Default for ScoreTrend {
+    fn default() -> Self {
+        Self::with_first_mover(Player::Black)
+    }
+}
+
+impl ScoreTrend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_first_mover(first_mover: Player) -> Self {
+        Self {
+            points: Vec::new(),
+            first_mover,
+        }
+    }
+
+    /// Player who made the move leading to `ply`
+    fn mover(&self, ply: u32) -> Player {
+        if ply % 2 == 1 {
+            self.first_mover
+        } else {
+            self.first_mover.opposite()
+        }
+    }
+
+    /// Store `point`, replacing a score for the same ply unless that one came
+    /// from the review and `point` did not. Returns whether it was stored.
+    pub fn record(&mut self, point: ScorePoint) -> bool {
+        match self.points.binary_search_by_key(&point.ply, |existing| existing.ply) {
+            Ok(index) => {
+                let existing = &mut self.points[index];
+                if existing.source == ScoreSource::Review && point.source == ScoreSource::Live {
+                    return false;
+                }
+                *existing = point;
+            }
+            Err(index) => self.points.insert(index, point),
+        }
+        true
+    }
+
+    /// Forget the scores after `ply`, e.g. after a takeback
+    pub fn truncate(&mut self, ply: u32) {
+        self.points.retain(|point| point.ply <= ply);
+    }
+
+    pub fn clear(&mut self) {
+        self.points.clear();
+    }
+
+    pub fn get(&self, ply: u32) -> Option<&ScorePoint> {
+        self.points
+            .binary_search_by_key(&ply, |point| point.ply)
+            .ok()
+            .map(|index| &self.points[index])
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// The series to plot, with markers on moves the review found too costly
+    /// and on the game's critical moments
+    pub fn series(&self) -> ScoreSeries {
+        let mut series = ScoreSeries::default();
+        let mut previous: Option<&ScorePoint> = None;
+        for point in &self.points {
+            let reviewed = previous.filter(|previous| {
+                previous.ply + 1 == point.ply
+                    && previous.source == ScoreSource::Review
+                    && point.source == ScoreSource::Review
+            });
+            if let Some(previous) = reviewed {
+                let before = OutcomeClass::from_win_rate(win_rate_permille(previous.score));
+                let after = OutcomeClass::from_win_rate(win_rate_permille(point.score));
+                if before != after {
+                    series.critical.push(CriticalMoment {
+                        ply: point.ply,
+                        kind: CriticalKind::OutcomeChanged { before, after },
+                    });
+                }
+            }
+            if let Some(swing_cp) = self.only_move_swing(point) {
+                series.critical.push(CriticalMoment {
+                    ply: point.ply,
+                    kind: CriticalKind::OnlyMove { swing_cp },
+                });
+            }
+
+            let loss_cp = reviewed.map(|previous| {
+                let before = previous.score.clamp(-GRAPH_SCORE_CAP, GRAPH_SCORE_CAP);
+                let after = point.score.clamp(-GRAPH_SCORE_CAP, GRAPH_SCORE_CAP);
+                if self.mover(point.ply) == Player::Black {
+                    before - after
+                } else {
+                    after - before
+                }
+            });
+            let marker = loss_cp.and_then(MoveMarker::from_loss);
+            if marker == Some(MoveMarker::Blunder) {
+                series.blunders.push(point.ply);
+            }
+            series.points.push(ScoreSeriesPoint {
+                point: point.clone(),
+                plotted: point.score.clamp(-GRAPH_SCORE_CAP, GRAPH_SCORE_CAP),
+                win_rate: win_rate_permille(point.score),
+                loss_cp,
+                marker,
+            });
+            previous = Some(point);
+        }
+        series
+    }
+
+    /// How much better the review's best move in `point`'s position was than its
+    /// second best, if by enough to make the position critical
+    fn only_move_swing(&self, point: &ScorePoint) -> Option<i32> {
+        if point.source != ScoreSource::Review {
+            return None;
+        }
+        let best = point.score.clamp(-GRAPH_SCORE_CAP, GRAPH_SCORE_CAP);
+        let second = point.second_best?.clamp(-GRAPH_SCORE_CAP, GRAPH_SCORE_CAP);
+        // The player to move here makes the move of the next ply
+        let swing = if self.mover(point.ply + 1) == Player::Black {
+            best - second
+        } else {
+            second - best
+        };
+        (swing >= CRITICAL_SWING_CP).then_some(swing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::mate_score::mate_in;
+
+    fn review(ply: u32, score: i32) -> ScorePoint {
+        ScorePoint {
+            ply,
+            move_usi: None,
+            score,
+            mate: None,
+            source: ScoreSource::Review,
+            depth: Some(12),
+            second_best: None,
+        }
+    }
+
+    #[test]
+    fn test_review_scores_replace_live_ones() {
+        let mut trend = ScoreTrend::new();
+        // Gote to move after sente's first move and 80 cp worse off: +80 for sente
+        let live = ScorePoint::from_side_to_move(
+            1,
+            Some("7g7f".to_string()),
+            -80,
+            Player::White,
+            ScoreSource::Live,
+            Some(4),
+        );
+        assert_eq!(live.score, 80);
+        assert!(trend.record(live.clone()));
+        assert!(trend.record(review(1, 40)));
+        assert!(!trend.record(live));
+        assert_eq!(trend.series().points[0].point.score, 40);
+
+        // A mate for the side to move is a mate for gote here
+        let mate = ScorePoint::from_side_to_move(
+            2,
+            None,
+            mate_in(3),
+            Player::White,
+            ScoreSource::Live,
+            None,
+        );
+        assert_eq!(mate.mate, Some(-3));
+        trend.record(mate);
+        assert_eq!(trend.series().points[1].plotted, -GRAPH_SCORE_CAP);
+        assert_eq!(trend.series().points[1].win_rate, 0);
+
+        trend.truncate(1);
+        assert_eq!(trend.len(), 1);
+    }
+
+    #[test]
+    fn test_series_marks_costly_moves_by_review_scores() {
+        let mut trend = ScoreTrend::new();
+        for (ply, score) in [(0, 30), (1, 20), (2, 260), (3, 120), (4, 180)] {
+            trend.record(review(ply, score));
+        }
+        // A live score is never judged, even against a review score
+        trend.record(ScorePoint { source: ScoreSource::Live, ..review(5, -200) });
+
+        let series = trend.series();
+        let markers: Vec<Option<MoveMarker>> =
+            series.points.iter().map(|point| point.marker).collect();
+        assert_eq!(
+            markers,
+            vec![
+                None,
+                None,
+                // Gote's move gave sente 240 cp
+                Some(MoveMarker::Blunder),
+                // Sente's move dropped 140 cp
+                Some(MoveMarker::Mistake),
+                // Gote's move gave away 60 cp
+                Some(MoveMarker::Inaccuracy),
+                None,
+            ]
+        );
+        assert_eq!(series.points[3].loss_cp, Some(140));
+        assert_eq!(series.blunders, vec![2]);
+
+        // In a handicap game the same swings belong to the other side
+        let mut handicap = ScoreTrend::with_first_mover(Player::White);
+        for (ply, score) in [(0, -600), (1, -300)] {
+            handicap.record(review(ply, score));
+        }
+        assert_eq!(handicap.series().points[1].loss_cp, Some(300));
+    }
+
+    #[test]
+    fn test_critical_moments_on_only_moves_and_outcome_changes() {
+        let mut trend = ScoreTrend::new();
+        // Sente to move at ply 0 with one good move; gote to move at ply 1 where
+        // any of several moves will do
+        trend.record(review(0, 100).with_second_best(-150, Player::Black));
+        trend.record(ScorePoint { second_best: Some(80), ..review(1, 60) });
+        // Gote's move at ply 2 throws the game away
+        trend.record(review(2, 1_500));
+
+        let series = trend.series();
+        assert_eq!(
+            series.critical,
+            vec![
+                CriticalMoment { ply: 0, kind: CriticalKind::OnlyMove { swing_cp: 250 } },
+                CriticalMoment {
+                    ply: 2,
+                    kind: CriticalKind::OutcomeChanged {
+                        before: OutcomeClass::Unclear,
+                        after: OutcomeClass::SenteWinning,
+                    },
+                },
+            ]
+        );
+        let json = serde_json::to_value(series.critical[0]).unwrap();
+        assert_eq!(json, serde_json::json!({ "ply": 0, "kind": "only_move", "swing_cp": 250 }));
+    }
+}
@@ -6,11 +6,84 @@
 
 #![allow(dead_code)]
 
+use crate::search::move_ordering::MoveOrdering;
 use crate::search::runtime_configuration::{PerformanceMetrics as RuntimePerformanceMetrics, *};
 use crate::search::transposition_config::*;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
+use sysinfo::{System, SystemExt};
+
+/// Approximate size of a transposition table entry, as `SearchEngine::new` assumes
+const TT_BYTES_PER_ENTRY: usize = 100;
+/// Seconds of search on all threads a startup profile sizes the table for
+const PROFILE_SEARCH_SECONDS: usize = 10;
+/// `USI_Hash` range a startup profile picks from
+const PROFILE_MIN_HASH_MB: usize = 16;
+const PROFILE_MAX_HASH_MB: usize = 1024;
+/// A startup profile's hash never takes more than 1/N of available memory
+const PROFILE_MEMORY_DIVISOR: u64 = 4;
+/// Smallest move-ordering cache a startup profile picks
+const PROFILE_MIN_ORDERING_CACHE: usize = 1000;
+
+/// Measurements of the startup calibration search
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationSample {
+    /// Single-threaded search speed
+    pub nodes_per_second: u64,
+    /// Share of transposition table probes that hit (0.0 to 1.0)
+    pub tt_hit_rate: f64,
+    /// Share of move-ordering cache lookups that hit (0.0 to 1.0)
+    pub ordering_cache_hit_rate: f64,
+    /// Logical CPU cores
+    pub logical_cores: usize,
+    /// Memory available when calibrating, in bytes
+    pub available_memory_bytes: u64,
+}
+
+/// Engine settings picked for one machine, persisted so calibration runs once
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MachineProfile {
+    /// `machine_id` of the machine the profile was calibrated on
+    pub machine_id: String,
+    /// `USI_Hash` in MB
+    pub hash_size_mb: usize,
+    /// `USI_Threads`
+    pub thread_count: usize,
+    /// Move-ordering score cache entries
+    pub move_ordering_cache_size: usize,
+    /// What the choice was based on
+    pub calibration: CalibrationSample,
+}
+
+/// Identifier of this machine for persisted profiles: architecture, OS, cores
+/// and installed memory, so a copied prefs file does not carry over
+pub fn machine_id() -> String {
+    let mut system = System::new();
+    system.refresh_memory();
+    format!(
+        "{}-{}-{}c-{}g",
+        std::env::consts::ARCH,
+        std::env::consts::OS,
+        num_cpus::get(),
+        system.total_memory() >> 30
+    )
+}
+
+/// Available memory and logical cores of this machine
+pub fn system_resources() -> (u64, usize) {
+    let mut system = System::new();
+    system.refresh_memory();
+    (system.available_memory(), num_cpus::get())
+}
+
+fn floor_power_of_two(value: usize) -> usize {
+    if value == 0 {
+        0
+    } else {
+        1 << (usize::BITS - 1 - value.leading_zeros())
+    }
+}
 
 /// Adaptive configuration manager
 pub struct AdaptiveConfigurationManager {
@@ -426,7 +499,10 @@ impl AdaptiveConfigurationManager {
             .iter()
             .find(|rule| rule.enabled && self.evaluate_condition(&rule.condition))
         {
-            println!("Adaptation triggered by rule: {}", rule.name);
+            crate::utils::telemetry::debug_log(&format!(
+                "Adaptation triggered by rule: {}",
+                rule.name
+            ));
 
             // Execute the action
             self.execute_action(&rule.action)?;
@@ -436,7 +512,7 @@ impl AdaptiveConfigurationManager {
             self.adaptation_state.last_adaptation_timestamp = std::time::SystemTime::now();
             self.last_adaptation_time = std::time::Instant::now();
 
-            println!("Adaptation completed successfully");
+            crate::utils::telemetry::debug_log("Adaptation completed successfully");
         }
 
         Ok(())
@@ -545,6 +621,75 @@ impl AdaptiveConfigurationManager {
         variance
     }
 
+    /// Pick startup settings from a calibration search
+    ///
+    /// The table starts sized for `PROFILE_SEARCH_SECONDS` of search on every
+    /// thread, within a share of available memory. The adaptation rules then get
+    /// one pass over the measured metrics, regardless of the adaptation interval,
+    /// and the active configuration is turned back into `USI_Hash` megabytes.
+    pub fn recommend_profile(
+        &mut self,
+        machine_id: &str,
+        sample: CalibrationSample,
+    ) -> Result<MachineProfile, String> {
+        // Leave a core to the GUI once there are enough of them
+        let thread_count = match sample.logical_cores {
+            0 | 1 => 1,
+            2 => 2,
+            cores => cores - 1,
+        }
+        .min(32);
+
+        let memory_budget_mb =
+            (sample.available_memory_bytes / PROFILE_MEMORY_DIVISOR / (1024 * 1024)) as usize;
+        let max_hash_mb = memory_budget_mb.clamp(1, PROFILE_MAX_HASH_MB);
+        let wanted_entries =
+            sample.nodes_per_second as usize * thread_count * PROFILE_SEARCH_SECONDS;
+        let wanted_mb = (wanted_entries * TT_BYTES_PER_ENTRY / (1024 * 1024)).max(1);
+        let table_size =
+            floor_power_of_two(wanted_mb.min(max_hash_mb) * 1024 * 1024 / TT_BYTES_PER_ENTRY);
+
+        {
+            let mut runtime_manager = self.runtime_manager.lock().unwrap();
+            let config = TranspositionConfig {
+                table_size,
+                ..runtime_manager.get_active_config()
+            };
+            runtime_manager.update_config(config, ConfigurationUpdateStrategy::Immediate)?;
+        }
+        let metrics = RuntimePerformanceMetrics {
+            hit_rate: sample.tt_hit_rate,
+            avg_operation_time_us: 1_000_000.0 / sample.nodes_per_second.max(1) as f64,
+            memory_usage_bytes: (table_size * TT_BYTES_PER_ENTRY) as u64,
+            collision_rate: 0.0,
+            replacements_per_second: 0.0,
+            system_load: 0.0,
+            available_memory_bytes: sample.available_memory_bytes,
+        };
+        self.performance_history.clear();
+        self.performance_history.push_back(metrics);
+        self.perform_adaptation()?;
+
+        let adapted_entries = self.runtime_manager.lock().unwrap().get_active_config().table_size;
+        let adapted_mb = adapted_entries * TT_BYTES_PER_ENTRY / (1024 * 1024);
+        let hash_size_mb =
+            floor_power_of_two(adapted_mb.clamp(PROFILE_MIN_HASH_MB.min(max_hash_mb), max_hash_mb));
+
+        // Cache about a tenth of a second of one thread's nodes
+        let move_ordering_cache_size = (sample.nodes_per_second as usize / 10).clamp(
+            PROFILE_MIN_ORDERING_CACHE,
+            MoveOrdering::get_platform_memory_limits().recommended_cache_size,
+        );
+
+        Ok(MachineProfile {
+            machine_id: machine_id.to_string(),
+            hash_size_mb,
+            thread_count,
+            move_ordering_cache_size,
+            calibration: sample,
+        })
+    }
+
     /// Get current adaptation state
     pub fn get_adaptation_state(&self) -> AdaptationState {
         self.adaptation_state.clone()
@@ -720,4 +865,33 @@ mod tests {
             AdaptationMode::Aggressive
         );
     }
+
+    #[test]
+    fn test_recommend_profile_respects_memory_and_cores() {
+        let sample = CalibrationSample {
+            nodes_per_second: 500_000,
+            tt_hit_rate: 0.5,
+            ordering_cache_hit_rate: 0.5,
+            logical_cores: 8,
+            available_memory_bytes: 256 * 1024 * 1024,
+        };
+        let mut manager = AdaptiveConfigurationManager::new(TranspositionConfig::default());
+        let profile = manager.recommend_profile("test-machine", sample).unwrap();
+
+        assert_eq!(profile.machine_id, "test-machine");
+        assert_eq!(profile.thread_count, 7);
+        assert!(profile.hash_size_mb.is_power_of_two());
+        assert!(profile.hash_size_mb <= 64, "hash {} MB", profile.hash_size_mb);
+        assert_eq!(profile.move_ordering_cache_size, 50_000);
+
+        let single_core = CalibrationSample {
+            logical_cores: 1,
+            nodes_per_second: 1_000,
+            ..sample
+        };
+        let profile = manager.recommend_profile("test-machine", single_core).unwrap();
+        assert_eq!(profile.thread_count, 1);
+        assert_eq!(profile.move_ordering_cache_size, PROFILE_MIN_ORDERING_CACHE);
+        assert!(profile.hash_size_mb >= 1);
+    }
 }
@@ -3,6 +3,11 @@
 //! This module provides detailed cache statistics, hit rate tracking by depth,
 //! collision monitoring, statistics export, visualization, and performance
 //! trend analysis for comprehensive transposition table performance monitoring.
+//!
+//! Key verification counts the probes that matched an entry on its 64-bit key
+//! but not on the second, independent key stored beside it when verification
+//! mode is on: real collisions, as opposed to the slot contention the
+//! collision monitor sees.
 
 use std::collections::{HashMap, VecDeque};
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -248,6 +253,58 @@ pub struct CollisionStats {
     pub most_collided_hashes: Vec<(u64, u64)>,
 }
 
+/// Probes checked against the second key of their entry
+#[derive(Debug, Default)]
+pub struct KeyVerificationMonitor {
+    verified_probes: AtomicU64,
+    collisions: AtomicU64,
+}
+
+impl KeyVerificationMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a probe that matched on the primary key; `collided` when the
+    /// second key showed the entry belongs to another position
+    pub fn record(&self, collided: bool) {
+        self.verified_probes.fetch_add(1, Ordering::Relaxed);
+        if collided {
+            self.collisions.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn get_stats(&self) -> KeyVerificationStats {
+        KeyVerificationStats {
+            verified_probes: self.verified_probes.load(Ordering::Acquire),
+            collisions: self.collisions.load(Ordering::Acquire),
+        }
+    }
+
+    pub fn clear(&self) {
+        self.verified_probes.store(0, Ordering::Release);
+        self.collisions.store(0, Ordering::Release);
+    }
+}
+
+/// Key verification statistics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyVerificationStats {
+    pub verified_probes: u64,
+    pub collisions: u64,
+}
+
+impl KeyVerificationStats {
+    /// Collisions per million verified probes
+    pub fn collisions_per_million(&self) -> f64 {
+        if self.verified_probes == 0 {
+            0.0
+        } else {
+            self.collisions as f64 * 1_000_000.0 / self.verified_probes as f64
+        }
+    }
+}
+
 /// Statistics export format
 #[derive(Debug, Clone, PartialEq)]
 pub enum ExportFormat {
@@ -713,6 +770,8 @@ pub struct AdvancedStatisticsManager {
     hit_rate_tracker: Arc<Mutex<HitRateByDepth>>,
     /// Collision monitor
     collision_monitor: Arc<CollisionMonitor>,
+    /// Second-key verification of TT hits
+    key_verification: Arc<KeyVerificationMonitor>,
     /// Performance trend analyzer
     trend_analyzer: Arc<PerformanceTrendAnalyzer>,
     /// Statistics exporter
@@ -726,6 +785,7 @@ impl AdvancedStatisticsManager {
             cache_stats: Arc::new(Mutex::new(DetailedCacheStats::default())),
             hit_rate_tracker: Arc::new(Mutex::new(HitRateByDepth::new(max_depth))),
             collision_monitor: Arc::new(CollisionMonitor::new(table_size, 1000)),
+            key_verification: Arc::new(KeyVerificationMonitor::new()),
             trend_analyzer: Arc::new(PerformanceTrendAnalyzer::new(1000, 3600)), // 1 hour window
             exporter: StatisticsExporter::new(ExportFormat::Text, true),
         }
@@ -773,6 +833,15 @@ impl AdvancedStatisticsManager {
         self.collision_monitor.record_collision(hash, index);
     }
 
+    /// Record a probe verified against its entry's second key; a failed check
+    /// is also recorded as a collision
+    pub fn record_key_verification(&self, hash: u64, index: usize, collided: bool) {
+        self.key_verification.record(collided);
+        if collided {
+            self.record_collision(hash, index);
+        }
+    }
+
     /// Update occupancy rate
     pub fn update_occupancy(&self, occupied_entries: usize, total_entries: usize) {
         let mut stats = self.cache_stats.lock().unwrap();
@@ -822,12 +891,14 @@ impl AdvancedStatisticsManager {
         hit_rate_tracker.update_hit_rates();
         let hit_rates = hit_rate_tracker.get_all_hit_rates();
         let collision_stats = self.collision_monitor.get_collision_stats();
+        let key_verification = self.key_verification.get_stats();
         let trends = self.trend_analyzer.analyze_trends();
 
         ComprehensiveStatisticsReport {
             cache_stats,
             hit_rates,
             collision_stats,
+            key_verification,
             trends,
             timestamp: SystemTime::now()
                 .duration_since(UNIX_EPOCH)
@@ -883,6 +954,7 @@ impl AdvancedStatisticsManager {
             *hit_rate_tracker = HitRateByDepth::new(20);
         }
         self.collision_monitor.clear();
+        self.key_verification.clear();
         self.trend_analyzer.clear();
     }
 }
@@ -893,6 +965,7 @@ pub struct ComprehensiveStatisticsReport {
     pub cache_stats: DetailedCacheStats,
     pub hit_rates: Vec<(u8, f64)>,
     pub collision_stats: CollisionStats,
+    pub key_verification: KeyVerificationStats,
     pub trends: PerformanceTrends,
     pub timestamp: u64,
 }
@@ -992,4 +1065,19 @@ mod tests {
         assert_eq!(report.cache_stats.total_stores, 1);
         assert_eq!(report.cache_stats.total_collisions, 1);
     }
+
+    #[test]
+    fn test_key_verification_counts_real_collisions() {
+        let manager = AdvancedStatisticsManager::new(1000, 20);
+        for _ in 0..3 {
+            manager.record_key_verification(0x123, 100, false);
+        }
+        manager.record_key_verification(0x456, 200, true);
+
+        let report = manager.get_comprehensive_report();
+        assert_eq!(report.key_verification.verified_probes, 4);
+        assert_eq!(report.key_verification.collisions, 1);
+        assert_eq!(report.key_verification.collisions_per_million(), 250_000.0);
+        assert_eq!(report.collision_stats.total_collisions, 1);
+    }
 }
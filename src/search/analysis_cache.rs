@@ -0,0 +1,158 @@
+//! Analysis Cache
+//!
+//! The deepest analysis found so far for each position, kept for as long as the
+//! engine runs. Stepping back and forth through a game in analysis mode would
+//! otherwise restart every position from nothing: with the cache, a position
+//! analysed before shows its last line at once, and its best move goes back
+//! into the transposition table so the new search starts from it.
+//!
+//! Entries are keyed by the search's position hash and only replaced by an
+//! analysis at least as deep. Searches restricted with `searchmoves` are not
+//! recorded, as their result does not hold for the whole position.
+
+use crate::search::perspective::EvaluationPerspective;
+use crate::search::search_handle::SearchProgress;
+use crate::search::win_probability::format_info_score;
+use crate::types::core::Player;
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::num::NonZeroUsize;
+
+/// Positions remembered before the least recently used one is dropped
+pub const DEFAULT_ANALYSIS_CACHE_POSITIONS: usize = 4096;
+
+/// The last completed iteration of an analysis
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedAnalysis {
+    pub depth: u8,
+    pub seldepth: u8,
+    /// Centipawns from the side to move's perspective
+    pub score: i32,
+    pub nodes: u64,
+    /// Principal variation in USI notation
+    pub pv: Vec<String>,
+}
+
+impl CachedAnalysis {
+    pub fn from_progress(progress: &SearchProgress) -> Self {
+        Self {
+            depth: progress.depth,
+            seldepth: progress.seldepth,
+            score: progress.score,
+            nodes: progress.nodes,
+            pv: progress.pv.clone(),
+        }
+    }
+
+    /// The analysis as a USI `info` line, scored like the engine's live output
+    pub fn info_line(
+        &self,
+        perspective: EvaluationPerspective,
+        side_to_move: Player,
+        show_win_rate: bool,
+    ) -> String {
+        let mut line = format!(
+            "info depth {} seldepth {} score {} nodes {}",
+            self.depth,
+            self.seldepth.max(self.depth),
+            format_info_score(perspective.orient(self.score, side_to_move), show_win_rate),
+            self.nodes
+        );
+        if !self.pv.is_empty() {
+            line.push_str(" pv ");
+            line.push_str(&self.pv.join(" "));
+        }
+        line
+    }
+}
+
+/// Deepest known analysis by position hash
+#[derive(Debug)]
+pub struct AnalysisCache {
+    entries: LruCache<u64, CachedAnalysis>,
+}
+
+impl Default for AnalysisCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_ANALYSIS_CACHE_POSITIONS)
+    }
+}
+
+impl AnalysisCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: LruCache::new(NonZeroUsize::new(capacity.max(1)).unwrap()),
+        }
+    }
+
+    /// The cached analysis of a position, marking it as recently used
+    pub fn get(&mut self, key: u64) -> Option<&CachedAnalysis> {
+        self.entries.get(&key)
+    }
+
+    /// Store `analysis` unless the position already has a deeper one. Returns
+    /// whether it was stored.
+    pub fn record(&mut self, key: u64, analysis: CachedAnalysis) -> bool {
+        if let Some(existing) = self.entries.peek(&key) {
+            if existing.depth > analysis.depth {
+                return false;
+            }
+        }
+        self.entries.put(key, analysis);
+        true
+    }
+
+    /// Forget everything, e.g. after the evaluation changed
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analysis(depth: u8, score: i32) -> CachedAnalysis {
+        CachedAnalysis {
+            depth,
+            seldepth: depth + 2,
+            score,
+            nodes: 1_000,
+            pv: vec!["7g7f".to_string(), "3c3d".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_keeps_deepest_analysis() {
+        let mut cache = AnalysisCache::new(2);
+        assert!(cache.record(1, analysis(8, 40)));
+        assert!(!cache.record(1, analysis(5, -20)));
+        assert!(cache.record(1, analysis(8, 35)));
+        assert_eq!(cache.get(1).unwrap().score, 35);
+
+        // Position 1 was used last, so position 2 is the one evicted
+        cache.record(2, analysis(3, 0));
+        cache.get(1);
+        cache.record(3, analysis(4, 0));
+        assert!(cache.get(2).is_none());
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_info_line_orients_score() {
+        let line = analysis(6, 120)
+            .info_line(EvaluationPerspective::BlackPositive, Player::White, false);
+        assert_eq!(line, "info depth 6 seldepth 8 score cp -120 nodes 1000 pv 7g7f 3c3d");
+        let line =
+            analysis(6, 120).info_line(EvaluationPerspective::SideToMove, Player::White, true);
+        assert!(line.contains("score cp 120 winrate 550 nodes"));
+    }
+}
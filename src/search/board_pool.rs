@@ -0,0 +1,133 @@
+//! Board Pool
+//!
+//! The search is make/unmake along its main line, but some places still copy
+//! the position: parallel YBWC siblings, IID and PV extraction. Cloning there
+//! allocates both hand vectors every time. A `BoardPool` keeps released
+//! positions and copies into them with `clone_from`, so the hands keep their
+//! capacity and the boards keep their shared tables.
+//!
+//! Each `SearchEngine` owns one pool, and every search thread runs its own
+//! engine, so the pool is per-thread and needs no locking.
+
+use crate::bitboards::BitboardBoard;
+use crate::types::board::CapturedPieces;
+
+/// Positions kept for reuse unless configured otherwise
+pub const DEFAULT_BOARD_POOL_SIZE: usize = 32;
+
+/// Reuse counters of a `BoardPool`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BoardPoolStats {
+    /// Positions handed out
+    pub acquired: u64,
+    /// Positions handed out by copying into a pooled one
+    pub reused: u64,
+    /// Positions dropped on release because the pool was full
+    pub discarded: u64,
+}
+
+/// Per-thread pool of board and hand buffers for copy-make search
+pub struct BoardPool {
+    positions: Vec<(BitboardBoard, CapturedPieces)>,
+    max_size: usize,
+    stats: BoardPoolStats,
+}
+
+impl Default for BoardPool {
+    fn default() -> Self {
+        Self::new(DEFAULT_BOARD_POOL_SIZE)
+    }
+}
+
+impl BoardPool {
+    /// Create a pool keeping at most `max_size` released positions
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            positions: Vec::with_capacity(max_size),
+            max_size,
+            stats: BoardPoolStats::default(),
+        }
+    }
+
+    /// Copy of `board` and `captured_pieces`, reusing a pooled position when available
+    pub fn acquire(
+        &mut self,
+        board: &BitboardBoard,
+        captured_pieces: &CapturedPieces,
+    ) -> (BitboardBoard, CapturedPieces) {
+        self.stats.acquired += 1;
+        match self.positions.pop() {
+            Some((mut pooled_board, mut pooled_captured)) => {
+                self.stats.reused += 1;
+                pooled_board.clone_from(board);
+                pooled_captured.black.clone_from(&captured_pieces.black);
+                pooled_captured.white.clone_from(&captured_pieces.white);
+                (pooled_board, pooled_captured)
+            }
+            None => (board.clone(), captured_pieces.clone()),
+        }
+    }
+
+    /// Hand a position back for reuse
+    pub fn release(&mut self, board: BitboardBoard, captured_pieces: CapturedPieces) {
+        if self.positions.len() < self.max_size {
+            self.positions.push((board, captured_pieces));
+        } else {
+            self.stats.discarded += 1;
+        }
+    }
+
+    /// Number of positions waiting for reuse
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Whether no position is waiting for reuse
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Reuse counters since the pool was created
+    pub fn stats(&self) -> BoardPoolStats {
+        self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::core::{PieceType, Player};
+
+    #[test]
+    fn test_acquire_reuses_released_positions() {
+        let mut pool = BoardPool::new(1);
+        let board = BitboardBoard::new();
+        let mut captured = CapturedPieces::new();
+        captured.add_piece(PieceType::Pawn, Player::Black);
+
+        let (first_board, first_captured) = pool.acquire(&board, &captured);
+        assert_eq!(first_captured.count(PieceType::Pawn, Player::Black), 1);
+        pool.release(first_board, first_captured);
+        assert_eq!(pool.len(), 1);
+
+        let empty_hands = CapturedPieces::new();
+        let (second_board, second_captured) = pool.acquire(&board, &empty_hands);
+        assert_eq!(second_captured.count(PieceType::Pawn, Player::Black), 0);
+        assert_eq!(
+            second_board.to_fen(Player::Black, &second_captured),
+            board.to_fen(Player::Black, &empty_hands)
+        );
+        assert!(pool.is_empty());
+
+        pool.release(second_board, second_captured);
+        pool.release(board.clone(), empty_hands);
+        assert_eq!(
+            pool.stats(),
+            BoardPoolStats {
+                acquired: 2,
+                reused: 1,
+                discarded: 1,
+            }
+        );
+    }
+}
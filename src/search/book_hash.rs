@@ -0,0 +1,300 @@
+//! External Position Hash Schemes
+//!
+//! Our Zobrist keys are seeded privately, so they mean nothing to other shogi
+//! tools. This module also computes the book key of Apery's `book.bin` format,
+//! which YaneuraOu can load as well, so books and position tables can be
+//! exchanged with existing tooling.
+//!
+//! The Apery key tables come from a 64-bit Mersenne Twister (`mt19937_64`) with
+//! its default seed. They are drawn in Apery's order:
+//! - one key per piece code (0..31, unused codes included) and square;
+//! - one key per hand piece (P, L, N, S, G, B, R) and count (0..=18);
+//! - the side-to-move key.
+//!
+//! A position's key XORs:
+//! - the key of every piece on the board;
+//! - the hand-count keys of the side to move only;
+//! - the side-to-move key when White (gote) is to move.
+
+use crate::bitboards::BitboardBoard;
+use crate::search::zobrist::{RepetitionState, ZobristHasher};
+use crate::types::board::CapturedPieces;
+use crate::types::core::{Piece, PieceType, Player, Position};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Piece codes in Apery's `Piece` enum; white pieces add `APERY_WHITE_OFFSET`
+const APERY_PIECE_CODES: usize = 31;
+const APERY_WHITE_OFFSET: usize = 16;
+
+/// Hand counts keyed per hand piece, 0 through 18
+const APERY_HAND_COUNTS: usize = 19;
+
+/// Hand pieces in Apery's `HandPiece` order
+pub const APERY_HAND_ORDER: [PieceType; 7] = [
+    PieceType::Pawn,
+    PieceType::Lance,
+    PieceType::Knight,
+    PieceType::Silver,
+    PieceType::Gold,
+    PieceType::Bishop,
+    PieceType::Rook,
+];
+
+const MT_STATE_SIZE: usize = 312;
+const MT_SHIFT: usize = 156;
+const MT_MATRIX_A: u64 = 0xB502_6F5A_A966_19E9;
+const MT_UPPER_MASK: u64 = 0xFFFF_FFFF_8000_0000;
+const MT_LOWER_MASK: u64 = 0x7FFF_FFFF;
+const MT_DEFAULT_SEED: u64 = 5489;
+
+/// Which key a position hash uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PositionHashScheme {
+    /// The engine's own Zobrist key, as used by the transposition table
+    #[default]
+    Native,
+    /// The key of Apery-format `book.bin` files
+    Apery,
+}
+
+impl PositionHashScheme {
+    /// Hash the position with `player` to move
+    pub fn hash(
+        self,
+        board: &BitboardBoard,
+        player: Player,
+        captured_pieces: &CapturedPieces,
+    ) -> u64 {
+        match self {
+            PositionHashScheme::Native => ZobristHasher::new().hash_position(
+                board,
+                player,
+                captured_pieces,
+                RepetitionState::None,
+            ),
+            PositionHashScheme::Apery => apery_key(board, player, captured_pieces),
+        }
+    }
+}
+
+impl FromStr for PositionHashScheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "native" => Ok(PositionHashScheme::Native),
+            "apery" => Ok(PositionHashScheme::Apery),
+            _ => Err(format!("Unknown position hash scheme: {}", s)),
+        }
+    }
+}
+
+/// Key tables of the Apery book hash
+struct AperyKeys {
+    piece: [[u64; 81]; APERY_PIECE_CODES],
+    hand: [[u64; APERY_HAND_COUNTS]; 7],
+    turn: u64,
+}
+
+impl AperyKeys {
+    fn generate() -> Self {
+        let mut rng = Mt19937_64::new(MT_DEFAULT_SEED);
+        let mut piece = [[0u64; 81]; APERY_PIECE_CODES];
+        for squares in piece.iter_mut() {
+            for key in squares.iter_mut() {
+                *key = rng.next_u64();
+            }
+        }
+        let mut hand = [[0u64; APERY_HAND_COUNTS]; 7];
+        for counts in hand.iter_mut() {
+            for key in counts.iter_mut() {
+                *key = rng.next_u64();
+            }
+        }
+        let turn = rng.next_u64();
+        Self { piece, hand, turn }
+    }
+}
+
+lazy_static! {
+    static ref APERY_KEYS: AperyKeys = AperyKeys::generate();
+}
+
+/// Apery book key of the position with `player` to move
+pub fn apery_key(board: &BitboardBoard, player: Player, captured_pieces: &CapturedPieces) -> u64 {
+    let keys = &*APERY_KEYS;
+    let mut key = 0u64;
+    for row in 0..9 {
+        for col in 0..9 {
+            let pos = Position::new(row, col);
+            if let Some(piece) = board.get_piece(pos) {
+                key ^= keys.piece[apery_piece(piece)][apery_square(pos)];
+            }
+        }
+    }
+    for (index, piece_type) in APERY_HAND_ORDER.iter().enumerate() {
+        let count = captured_pieces.count(*piece_type, player).min(APERY_HAND_COUNTS - 1);
+        key ^= keys.hand[index][count];
+    }
+    if player == Player::White {
+        key ^= keys.turn;
+    }
+    key
+}
+
+/// Apery square index: file-major from 1a (0) to 9i (80)
+pub fn apery_square(pos: Position) -> usize {
+    usize::from(8 - pos.col) * 9 + usize::from(pos.row)
+}
+
+/// Board position of an Apery square index, if it is on the board
+pub fn position_from_apery_square(square: usize) -> Option<Position> {
+    (square < 81).then(|| Position::new((square % 9) as u8, 8 - (square / 9) as u8))
+}
+
+/// Apery piece type code (`PieceType` enum), 1 (pawn) to 14 (dragon)
+pub fn apery_piece_type(piece_type: PieceType) -> usize {
+    match piece_type {
+        PieceType::Pawn => 1,
+        PieceType::Lance => 2,
+        PieceType::Knight => 3,
+        PieceType::Silver => 4,
+        PieceType::Bishop => 5,
+        PieceType::Rook => 6,
+        PieceType::Gold => 7,
+        PieceType::King => 8,
+        PieceType::PromotedPawn => 9,
+        PieceType::PromotedLance => 10,
+        PieceType::PromotedKnight => 11,
+        PieceType::PromotedSilver => 12,
+        PieceType::PromotedBishop => 13,
+        PieceType::PromotedRook => 14,
+    }
+}
+
+/// Piece type of an Apery piece type code
+pub fn piece_type_from_apery(code: usize) -> Option<PieceType> {
+    Some(match code {
+        1 => PieceType::Pawn,
+        2 => PieceType::Lance,
+        3 => PieceType::Knight,
+        4 => PieceType::Silver,
+        5 => PieceType::Bishop,
+        6 => PieceType::Rook,
+        7 => PieceType::Gold,
+        8 => PieceType::King,
+        9 => PieceType::PromotedPawn,
+        10 => PieceType::PromotedLance,
+        11 => PieceType::PromotedKnight,
+        12 => PieceType::PromotedSilver,
+        13 => PieceType::PromotedBishop,
+        14 => PieceType::PromotedRook,
+        _ => return None,
+    })
+}
+
+fn apery_piece(piece: Piece) -> usize {
+    let code = apery_piece_type(piece.piece_type);
+    match piece.player {
+        Player::Black => code,
+        Player::White => code + APERY_WHITE_OFFSET,
+    }
+}
+
+/// 64-bit Mersenne Twister, bit-compatible with C++ `std::mt19937_64`
+struct Mt19937_64 {
+    state: [u64; MT_STATE_SIZE],
+    index: usize,
+}
+
+impl Mt19937_64 {
+    fn new(seed: u64) -> Self {
+        let mut state = [0u64; MT_STATE_SIZE];
+        state[0] = seed;
+        for i in 1..MT_STATE_SIZE {
+            let previous = state[i - 1];
+            state[i] = 6_364_136_223_846_793_005u64
+                .wrapping_mul(previous ^ (previous >> 62))
+                .wrapping_add(i as u64);
+        }
+        Self {
+            state,
+            index: MT_STATE_SIZE,
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        if self.index >= MT_STATE_SIZE {
+            self.twist();
+        }
+        let mut x = self.state[self.index];
+        self.index += 1;
+        x ^= (x >> 29) & 0x5555_5555_5555_5555;
+        x ^= (x << 17) & 0x71D6_7FFF_EDA6_0000;
+        x ^= (x << 37) & 0xFFF7_EEE0_0000_0000;
+        x ^ (x >> 43)
+    }
+
+    fn twist(&mut self) {
+        for i in 0..MT_STATE_SIZE {
+            let x = (self.state[i] & MT_UPPER_MASK)
+                | (self.state[(i + 1) % MT_STATE_SIZE] & MT_LOWER_MASK);
+            let mut next = x >> 1;
+            if x & 1 != 0 {
+                next ^= MT_MATRIX_A;
+            }
+            self.state[i] = self.state[(i + MT_SHIFT) % MT_STATE_SIZE] ^ next;
+        }
+        self.index = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mersenne_twister_matches_std_mt19937_64() {
+        // The C++ standard requires the 10000th output of a default-seeded
+        // mt19937_64 to be 9981545732273789042
+        let mut rng = Mt19937_64::new(MT_DEFAULT_SEED);
+        let value = (0..10_000).map(|_| rng.next_u64()).last().unwrap();
+        assert_eq!(value, 9_981_545_732_273_789_042);
+    }
+
+    #[test]
+    fn test_square_and_piece_codes_round_trip() {
+        assert_eq!(apery_square(Position::new(0, 8)), 0); // 1a
+        assert_eq!(apery_square(Position::new(8, 0)), 80); // 9i
+        assert_eq!(apery_square(Position::new(6, 2)), 60); // 7g
+        for square in 0..81 {
+            let pos = position_from_apery_square(square).unwrap();
+            assert_eq!(apery_square(pos), square);
+        }
+        assert_eq!(position_from_apery_square(81), None);
+        for code in 1..=14 {
+            assert_eq!(apery_piece_type(piece_type_from_apery(code).unwrap()), code);
+        }
+    }
+
+    #[test]
+    fn test_apery_key_hashes_side_to_move_and_its_hand_only() {
+        let board = BitboardBoard::new();
+        let mut captured = CapturedPieces::new();
+        let black = apery_key(&board, Player::Black, &captured);
+        assert_ne!(black, apery_key(&board, Player::White, &captured));
+
+        captured.add_piece(PieceType::Pawn, Player::White);
+        assert_eq!(apery_key(&board, Player::Black, &captured), black);
+        captured.add_piece(PieceType::Pawn, Player::Black);
+        assert_ne!(apery_key(&board, Player::Black, &captured), black);
+        assert_eq!(
+            PositionHashScheme::Apery.hash(&board, Player::Black, &captured),
+            apery_key(&board, Player::Black, &captured)
+        );
+        assert_eq!("apery".parse(), Ok(PositionHashScheme::Apery));
+    }
+}
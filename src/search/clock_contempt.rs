@@ -0,0 +1,188 @@
+//! Clock-Aware Play
+//!
+//! Move selection that looks at both clocks as well as the board. A player in
+//! time trouble misplays complicated positions far more often than quiet ones,
+//! so when the opponent is short of time the engine prefers, among the root
+//! moves scoring within `margin_cp` of the best, the one leaving the most
+//! complex position (`evaluation::complexity`); when the engine itself is short
+//! it prefers the simplest. Each move's score is adjusted by up to `margin_cp`
+//! in proportion to the complexity, so a move is never chosen over one more
+//! than `margin_cp` better.
+//!
+//! A side is in time trouble when its time per move, estimated from the main
+//! time left, the increment and byoyomi, is below `trouble_ms`. Like root
+//! variety, the root is searched with alpha lowered by the margin whenever the
+//! clocks call for a bias, so every move within it gets an exact score.
+
+use crate::bitboards::BitboardBoard;
+use crate::evaluation::complexity::complexity_after;
+use crate::search::root_variety::RootVariety;
+use crate::types::board::CapturedPieces;
+use crate::types::core::{Move, Player};
+use crate::usi::MOVES_TO_GO;
+
+/// Default time per move below which a side is in time trouble
+pub const DEFAULT_TROUBLE_MS: u32 = 3_000;
+
+/// Both players' clocks at the start of a search, from the engine's side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Clocks {
+    pub own_ms: u32,
+    pub own_increment_ms: u32,
+    pub opponent_ms: u32,
+    pub opponent_increment_ms: u32,
+    pub byoyomi_ms: u32,
+}
+
+impl Clocks {
+    /// Estimated time per move for the engine
+    pub fn own_per_move_ms(&self) -> u32 {
+        per_move_ms(self.own_ms, self.own_increment_ms, self.byoyomi_ms)
+    }
+
+    /// Estimated time per move for the opponent
+    pub fn opponent_per_move_ms(&self) -> u32 {
+        per_move_ms(self.opponent_ms, self.opponent_increment_ms, self.byoyomi_ms)
+    }
+}
+
+fn per_move_ms(remaining_ms: u32, increment_ms: u32, byoyomi_ms: u32) -> u32 {
+    (remaining_ms / MOVES_TO_GO)
+        .saturating_add(increment_ms)
+        .saturating_add(byoyomi_ms)
+}
+
+/// Which way the clocks bias move selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClockMode {
+    #[default]
+    Neutral,
+    /// The opponent is in time trouble: keep the position complicated
+    Complicate,
+    /// The engine is in time trouble: simplify
+    Simplify,
+}
+
+impl ClockMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ClockMode::Neutral => "neutral",
+            ClockMode::Complicate => "complicate",
+            ClockMode::Simplify => "simplify",
+        }
+    }
+}
+
+/// Clock-aware play settings (`ClockContemptMargin` / `ClockTroubleTime` options)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockContempt {
+    /// Most centipawns given up for a more or less complex position; 0 disables it
+    pub margin_cp: i32,
+    /// Time per move below which a side is in time trouble
+    pub trouble_ms: u32,
+}
+
+impl Default for ClockContempt {
+    fn default() -> Self {
+        Self { margin_cp: 0, trouble_ms: DEFAULT_TROUBLE_MS }
+    }
+}
+
+impl ClockContempt {
+    pub fn is_enabled(&self) -> bool {
+        self.margin_cp > 0
+    }
+
+    /// Bias for a search started with `clocks`. The engine's own time trouble
+    /// wins when both sides are short.
+    pub fn mode(&self, clocks: &Clocks) -> ClockMode {
+        if !self.is_enabled() {
+            ClockMode::Neutral
+        } else if clocks.own_per_move_ms() < self.trouble_ms {
+            ClockMode::Simplify
+        } else if clocks.opponent_per_move_ms() < self.trouble_ms {
+            ClockMode::Complicate
+        } else {
+            ClockMode::Neutral
+        }
+    }
+
+    /// The root move to play under `mode`: the best score after adding (or,
+    /// when simplifying, subtracting) `margin_cp` times the complexity of the
+    /// position it leaves. `None` without scores or in neutral mode.
+    pub fn select(
+        &self,
+        mode: ClockMode,
+        board: &BitboardBoard,
+        captured_pieces: &CapturedPieces,
+        player: Player,
+        root_scores: &[(Move, i32)],
+    ) -> Option<Move> {
+        let sign = match mode {
+            ClockMode::Neutral => return None,
+            ClockMode::Complicate => 1.0,
+            ClockMode::Simplify => -1.0,
+        };
+        let margin = f64::from(self.margin_cp);
+        RootVariety { margin_cp: self.margin_cp, ..RootVariety::default() }
+            .candidates(root_scores)
+            .into_iter()
+            .map(|(mv, score)| {
+                let complexity = complexity_after(board, captured_pieces, player, mv);
+                (mv, f64::from(*score) + sign * margin * complexity)
+            })
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(mv, _)| mv.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::core::{PieceType, Position};
+
+    #[test]
+    fn test_mode_follows_the_clocks() {
+        let contempt = ClockContempt { margin_cp: 50, ..ClockContempt::default() };
+        let calm = Clocks { own_ms: 600_000, opponent_ms: 600_000, ..Clocks::default() };
+        assert_eq!(contempt.mode(&calm), ClockMode::Neutral);
+        assert_eq!(contempt.mode(&Clocks { opponent_ms: 40_000, ..calm }), ClockMode::Complicate);
+        assert_eq!(contempt.mode(&Clocks { own_ms: 40_000, ..calm }), ClockMode::Simplify);
+        assert_eq!(
+            contempt.mode(&Clocks { opponent_ms: 0, byoyomi_ms: 10_000, ..calm }),
+            ClockMode::Neutral
+        );
+        assert_eq!(
+            ClockContempt::default().mode(&Clocks { own_ms: 0, ..calm }),
+            ClockMode::Neutral
+        );
+    }
+
+    #[test]
+    fn test_select_trades_score_for_complexity() {
+        let (board, player, captured) =
+            BitboardBoard::from_fen("4k4/9/4p4/9/4P4/9/9/9/4K4 b - 1").unwrap();
+        // 5e-5d puts the pawns in contact; the king move keeps things quiet
+        let push = Move::new_move(
+            Position::new(4, 4),
+            Position::new(3, 4),
+            PieceType::Pawn,
+            player,
+            false,
+        );
+        let king = Move::new_move(
+            Position::new(8, 4),
+            Position::new(7, 4),
+            PieceType::King,
+            player,
+            false,
+        );
+        let scores = vec![(king.clone(), 20), (push.clone(), 0)];
+        let contempt = ClockContempt { margin_cp: 100, ..ClockContempt::default() };
+
+        let select = |mode| contempt.select(mode, &board, &captured, player, &scores);
+        assert_eq!(select(ClockMode::Complicate), Some(push));
+        assert_eq!(select(ClockMode::Simplify), Some(king));
+        assert_eq!(select(ClockMode::Neutral), None);
+    }
+}
@@ -0,0 +1,281 @@
+//! Coach Mode Module
+//!
+//! Training-mode move selection layered on top of the root move scores. Instead of
+//! playing the best move, the engine estimates how likely a human at the chosen
+//! level would be to find each candidate and picks among the candidates with
+//! weights `find_probability * exp(-loss / temperature)`. Weak levels tolerate
+//! larger losses and favour obvious moves (captures, checks, short moves) over
+//! hidden ones (quiet drops, long slider moves), so the engine plays moves a
+//! student can learn from rather than the most punishing line.
+//!
+//! Like root variety, coach mode needs exact scores for every move near the best,
+//! so the root is searched with alpha lowered by `max_loss_cp` while it is enabled.
+
+use crate::bitboards::BitboardBoard;
+use crate::search::mate_score::{is_mate_score, mate_distance};
+use crate::search::search_engine::make_move_with_hand;
+use crate::types::board::CapturedPieces;
+use crate::types::core::{Move, PieceType, Position};
+use rand::Rng;
+
+/// Strongest coach level; it plays close to full strength
+pub const MAX_COACH_LEVEL: u8 = 10;
+
+/// Find probability of the most obvious moves (a capture that also checks)
+const MAX_VISIBILITY: f64 = 0.95;
+
+/// Find probability of the least obvious moves, before the level is applied
+const MIN_VISIBILITY: f64 = 0.15;
+
+/// Coach mode settings (`CoachLevel` option)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CoachConfig {
+    /// Simulated human level from 1 (beginner) to `MAX_COACH_LEVEL`; 0 disables coach mode
+    pub level: u8,
+    /// Candidate moves must score within this many centipawns of the best
+    pub max_loss_cp: i32,
+    /// How strongly score loss is penalised; higher values accept worse moves more often
+    pub temperature_cp: i32,
+}
+
+impl Default for CoachConfig {
+    fn default() -> Self {
+        Self::for_level(0)
+    }
+}
+
+impl CoachConfig {
+    /// Settings for a level: level 1 accepts losses up to 500cp, level 10 up to 50cp
+    pub fn for_level(level: u8) -> Self {
+        let level = level.min(MAX_COACH_LEVEL);
+        let weakness = i32::from(MAX_COACH_LEVEL.saturating_sub(level.max(1)));
+        Self {
+            level,
+            max_loss_cp: 50 + weakness * 50,
+            temperature_cp: 15 + weakness * 25,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.level > 0
+    }
+
+    /// How much a human at this level closes the gap to perfect vision, from 0 to 1
+    fn skill(&self) -> f64 {
+        f64::from(self.level.min(MAX_COACH_LEVEL)) / f64::from(MAX_COACH_LEVEL)
+    }
+
+    /// Probability that a human at this level considers `mv` at all
+    pub fn find_probability(
+        &self,
+        board: &BitboardBoard,
+        captured_pieces: &CapturedPieces,
+        mv: &Move,
+    ) -> f64 {
+        let visibility = move_visibility(board, captured_pieces, mv);
+        visibility + (1.0 - visibility) * self.skill()
+    }
+
+    /// Root moves eligible for selection, with their scores. Moves that walk into a
+    /// forced mate are dropped unless every move does; a forced mate for the engine
+    /// is kept only if it is short enough for a human at this level to see.
+    pub fn candidates<'a>(&self, root_scores: &'a [(Move, i32)]) -> Vec<&'a (Move, i32)> {
+        let Some(best) = root_scores.iter().map(|(_, score)| *score).max() else {
+            return Vec::new();
+        };
+        if is_mate_score(best) && (best < 0 || self.sees_mate(best)) {
+            return root_scores.iter().filter(|(_, score)| *score == best).take(1).collect();
+        }
+        let reference = if is_mate_score(best) {
+            root_scores
+                .iter()
+                .map(|(_, score)| *score)
+                .filter(|score| !is_mate_score(*score))
+                .max()
+                .unwrap_or(best)
+        } else {
+            best
+        };
+        let floor = reference.saturating_sub(self.max_loss_cp);
+        root_scores
+            .iter()
+            .filter(|(_, score)| *score >= floor && (*score > 0 || !is_mate_score(*score)))
+            .collect()
+    }
+
+    /// Whether a mate this many plies away is within reach of this level
+    fn sees_mate(&self, score: i32) -> bool {
+        let plies = mate_distance(score).unwrap_or(i32::MAX);
+        plies <= 1 + 2 * i32::from(self.level / 2)
+    }
+
+    /// Pick a root move for the current position; `None` if there are no scores
+    pub fn select<R: Rng + ?Sized>(
+        &self,
+        board: &BitboardBoard,
+        captured_pieces: &CapturedPieces,
+        root_scores: &[(Move, i32)],
+        rng: &mut R,
+    ) -> Option<Move> {
+        let candidates = self.candidates(root_scores);
+        let best = candidates
+            .iter()
+            .map(|(_, score)| *score)
+            .filter(|score| !is_mate_score(*score))
+            .max();
+        let temperature = f64::from(self.temperature_cp.max(1));
+        let weights: Vec<f64> = candidates
+            .iter()
+            .map(|(mv, score)| {
+                let loss = match best {
+                    Some(best) if !is_mate_score(*score) => f64::from(best - score),
+                    _ => 0.0,
+                };
+                self.find_probability(board, captured_pieces, mv) * (-loss / temperature).exp()
+            })
+            .collect();
+
+        let mut pick = rng.gen::<f64>() * weights.iter().sum::<f64>();
+        for ((mv, _), weight) in candidates.iter().zip(&weights) {
+            if pick < *weight {
+                return Some(mv.clone());
+            }
+            pick -= weight;
+        }
+        candidates.last().map(|(mv, _)| mv.clone())
+    }
+}
+
+/// How obvious a move is to a human, from `MIN_VISIBILITY` to `MAX_VISIBILITY`.
+/// Captures and checks stand out; drops and long slider moves are easy to overlook.
+fn move_visibility(board: &BitboardBoard, captured_pieces: &CapturedPieces, mv: &Move) -> f64 {
+    let mut visibility: f64 = 0.45;
+    let is_capture = mv.is_capture || board.get_piece(mv.to).is_some();
+    if is_capture {
+        visibility += 0.3;
+    }
+    if gives_check(board, captured_pieces, mv) {
+        visibility += 0.2;
+    }
+    if mv.is_promotion {
+        visibility += 0.1;
+    }
+    match mv.from {
+        None if mv.piece_type != PieceType::Pawn => visibility -= 0.15,
+        None => visibility -= 0.05,
+        Some(from) if !is_capture && distance(from, mv.to) >= 4 => visibility -= 0.2,
+        Some(_) => {}
+    }
+    visibility.clamp(MIN_VISIBILITY, MAX_VISIBILITY)
+}
+
+fn gives_check(board: &BitboardBoard, captured_pieces: &CapturedPieces, mv: &Move) -> bool {
+    if mv.gives_check {
+        return true;
+    }
+    let mut next_board = board.clone();
+    let (_, next_captured) = make_move_with_hand(&mut next_board, captured_pieces, mv, mv.player);
+    next_board.is_king_in_check(mv.player.opposite(), &next_captured)
+}
+
+fn distance(a: Position, b: Position) -> u8 {
+    let dr = (a.row as i8 - b.row as i8).unsigned_abs();
+    let dc = (a.col as i8 - b.col as i8).unsigned_abs();
+    dr.max(dc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::mate_score::{mate_in, mated_in};
+    use crate::types::core::{Piece, Player};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn pawn_push(col: u8) -> Move {
+        Move::new_move(
+            Position::new(6, col),
+            Position::new(5, col),
+            PieceType::Pawn,
+            Player::Black,
+            false,
+        )
+    }
+
+    #[test]
+    fn test_levels_scale_loss_tolerance() {
+        let beginner = CoachConfig::for_level(1);
+        let expert = CoachConfig::for_level(MAX_COACH_LEVEL);
+        assert!(beginner.is_enabled() && expert.is_enabled());
+        assert!(!CoachConfig::default().is_enabled());
+        assert!(beginner.max_loss_cp > expert.max_loss_cp);
+        assert!(beginner.temperature_cp > expert.temperature_cp);
+
+        let scores = vec![(pawn_push(0), 100), (pawn_push(1), 0), (pawn_push(2), -300)];
+        assert_eq!(expert.candidates(&scores).len(), 1);
+        assert_eq!(beginner.candidates(&scores).len(), 3);
+    }
+
+    #[test]
+    fn test_captures_are_easier_to_find_than_long_quiet_moves() {
+        let mut board = BitboardBoard::empty();
+        board.place_piece(Piece::new(PieceType::King, Player::Black), Position::new(8, 8));
+        board.place_piece(Piece::new(PieceType::Rook, Player::Black), Position::new(7, 0));
+        board.place_piece(Piece::new(PieceType::Silver, Player::White), Position::new(6, 0));
+        board.place_piece(Piece::new(PieceType::King, Player::White), Position::new(0, 8));
+        let captured = CapturedPieces::new();
+        let capture = Move::new_move(
+            Position::new(7, 0),
+            Position::new(6, 0),
+            PieceType::Rook,
+            Player::Black,
+            false,
+        );
+        let long_move = Move::new_move(
+            Position::new(7, 0),
+            Position::new(7, 6),
+            PieceType::Rook,
+            Player::Black,
+            false,
+        );
+
+        let beginner = CoachConfig::for_level(1);
+        let capture_probability = beginner.find_probability(&board, &captured, &capture);
+        let quiet_probability = beginner.find_probability(&board, &captured, &long_move);
+        assert!(capture_probability > quiet_probability);
+
+        let expert = CoachConfig::for_level(MAX_COACH_LEVEL);
+        assert!((expert.find_probability(&board, &captured, &long_move) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_mates_and_mate_threats() {
+        let scores = vec![(pawn_push(0), 50), (pawn_push(1), mated_in(2))];
+        let beginner = CoachConfig::for_level(1);
+        let candidates = beginner.candidates(&scores);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].0, pawn_push(0));
+
+        // A long mate is beyond a beginner, so the coach plays on normally
+        let scores = vec![(pawn_push(0), 50), (pawn_push(1), mate_in(9))];
+        assert_eq!(beginner.candidates(&scores).len(), 2);
+        assert_eq!(CoachConfig::for_level(MAX_COACH_LEVEL).candidates(&scores).len(), 1);
+    }
+
+    #[test]
+    fn test_select_only_plays_candidates() {
+        let board = BitboardBoard::new();
+        let captured = CapturedPieces::new();
+        let coach = CoachConfig::for_level(5);
+        let scores = vec![(pawn_push(0), 100), (pawn_push(1), 60), (pawn_push(2), -900)];
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut picked = std::collections::HashSet::new();
+        for _ in 0..200 {
+            let mv = coach.select(&board, &captured, &scores, &mut rng).unwrap();
+            assert_ne!(mv, pawn_push(2));
+            picked.insert(mv.to_usi_string());
+        }
+        assert_eq!(picked.len(), 2);
+        assert_eq!(coach.select(&board, &captured, &[], &mut rng), None);
+    }
+}
@@ -0,0 +1,209 @@
+//! Drop Limiter
+//!
+//! With ten or more pieces in hand a node can have several hundred drops, most
+//! of them quiet drops far from any action. When the clock is short the search
+//! cannot afford them all, so under medium or high time pressure this limiter
+//! keeps only the drops that matter tactically:
+//! - drops giving check;
+//! - drops near either king (within two squares, one under high pressure);
+//! - drops attacking an opponent piece.
+//!
+//! Board moves are never removed. The limiter stays out of the way when the
+//! side to move is in check, at the root, and whenever the filtered list would
+//! be too short to trust; those nodes search every legal move.
+
+use crate::bitboards::BitboardBoard;
+use crate::types::{CapturedPieces, Move, Player, Position, TimePressure};
+
+/// When the limiter engages and how much it keeps
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DropLimiterConfig {
+    pub enabled: bool,
+    /// Pieces in the mover's hand needed before drops are limited
+    pub min_hand_pieces: usize,
+    /// King zone radius under medium time pressure
+    pub king_radius: u8,
+    /// King zone radius under high time pressure
+    pub king_radius_high_pressure: u8,
+    /// Below this many kept moves the full move list is searched instead
+    pub min_kept_moves: usize,
+}
+
+impl Default for DropLimiterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_hand_pieces: 10,
+            king_radius: 2,
+            king_radius_high_pressure: 1,
+            min_kept_moves: 8,
+        }
+    }
+}
+
+/// Counters since the engine started
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DropLimiterStats {
+    /// Nodes whose drops were limited
+    pub nodes_limited: u64,
+    /// Quiet drops left out of those nodes
+    pub drops_pruned: u64,
+    /// Nodes where limiting would have kept too few moves
+    pub fallbacks: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DropLimiter {
+    config: DropLimiterConfig,
+    stats: DropLimiterStats,
+}
+
+impl DropLimiter {
+    pub fn new(config: DropLimiterConfig) -> Self {
+        Self {
+            config,
+            stats: DropLimiterStats::default(),
+        }
+    }
+
+    pub fn config(&self) -> &DropLimiterConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, config: DropLimiterConfig) {
+        self.config = config;
+    }
+
+    pub fn stats(&self) -> DropLimiterStats {
+        self.stats
+    }
+
+    /// King zone radius to use at `time_pressure`; `None` when the limiter stays off
+    fn king_radius(&self, time_pressure: TimePressure) -> Option<u8> {
+        if !self.config.enabled {
+            return None;
+        }
+        match time_pressure {
+            TimePressure::Medium => Some(self.config.king_radius),
+            TimePressure::High => Some(self.config.king_radius_high_pressure),
+            TimePressure::None | TimePressure::Low => None,
+        }
+    }
+
+    /// The moves to search at a node. Returns `legal_moves` unchanged unless the
+    /// node qualifies (see the module docs); the caller rules out the root and
+    /// positions in check.
+    pub fn limit(
+        &mut self,
+        board: &BitboardBoard,
+        captured_pieces: &CapturedPieces,
+        player: Player,
+        legal_moves: Vec<Move>,
+        time_pressure: TimePressure,
+    ) -> Vec<Move> {
+        let Some(radius) = self.king_radius(time_pressure) else {
+            return legal_moves;
+        };
+        let hand = match player {
+            Player::Black => captured_pieces.black.len(),
+            Player::White => captured_pieces.white.len(),
+        };
+        if hand < self.config.min_hand_pieces {
+            return legal_moves;
+        }
+
+        let kings = [
+            board.find_king_position(player),
+            board.find_king_position(player.opposite()),
+        ];
+        let total = legal_moves.len();
+        let kept: Vec<Move> = legal_moves
+            .iter()
+            .filter(|mv| mv.from.is_some() || is_active_drop(board, mv, player, &kings, radius))
+            .cloned()
+            .collect();
+
+        if kept.len() == total {
+            return legal_moves;
+        }
+        if kept.len() < self.config.min_kept_moves {
+            self.stats.fallbacks += 1;
+            return legal_moves;
+        }
+        self.stats.nodes_limited += 1;
+        self.stats.drops_pruned += (total - kept.len()) as u64;
+        kept
+    }
+}
+
+/// Whether a drop checks, lands near a king or attacks an opponent piece
+fn is_active_drop(
+    board: &BitboardBoard,
+    drop: &Move,
+    player: Player,
+    kings: &[Option<Position>; 2],
+    radius: u8,
+) -> bool {
+    let near_king = kings.iter().flatten().any(|king| {
+        king.row.abs_diff(drop.to.row) <= radius && king.col.abs_diff(drop.to.col) <= radius
+    });
+    if near_king || drop.gives_check {
+        return true;
+    }
+    // A drop cannot uncover an attack, so its own attacks are all that changes
+    let attacks = board.attacks_from(drop.to, drop.piece_type, player);
+    board
+        .iter_attack_targets(attacks)
+        .any(|target| board.get_piece(target).map_or(false, |piece| piece.player != player))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moves::MoveGenerator;
+
+    #[test]
+    fn test_limits_quiet_drops_only_under_time_pressure() {
+        // Sente holds twelve pieces; the kings sit in opposite corners
+        let (board, player, captured) =
+            BitboardBoard::from_fen("8k/9/9/9/9/9/9/9/K8 b 2R2B2G2S2N2L 1").unwrap();
+        let legal = MoveGenerator::new().generate_legal_moves(&board, player, &captured);
+        let mut limiter = DropLimiter::default();
+
+        let unchanged = limiter.limit(&board, &captured, player, legal.clone(), TimePressure::Low);
+        assert_eq!(unchanged.len(), legal.len());
+        assert_eq!(limiter.stats(), DropLimiterStats::default());
+
+        let limited = limiter.limit(&board, &captured, player, legal.clone(), TimePressure::Medium);
+        assert!(limited.len() < legal.len());
+        assert!(limited.len() >= limiter.config().min_kept_moves);
+        // Every board move survives, and so does a checking drop far from sente's king
+        assert!(legal
+            .iter()
+            .filter(|mv| mv.from.is_some())
+            .all(|mv| limited.contains(mv)));
+        assert!(limited.iter().any(|mv| mv.to_usi_string() == "R*1e"));
+        // A quiet drop in the middle of the board does not
+        assert!(!limited.iter().any(|mv| mv.to_usi_string() == "G*5e"));
+
+        let stats = limiter.stats();
+        assert_eq!(stats.nodes_limited, 1);
+        assert_eq!(stats.drops_pruned, (legal.len() - limited.len()) as u64);
+    }
+
+    #[test]
+    fn test_falls_back_to_all_moves_when_too_few_remain() {
+        let (board, player, captured) =
+            BitboardBoard::from_fen("8k/9/9/9/9/9/9/9/K8 b 2G2S2N2L4P 1").unwrap();
+        let legal = MoveGenerator::new().generate_legal_moves(&board, player, &captured);
+        let mut limiter = DropLimiter::new(DropLimiterConfig {
+            min_kept_moves: legal.len(),
+            ..DropLimiterConfig::default()
+        });
+
+        let moves = limiter.limit(&board, &captured, player, legal.clone(), TimePressure::High);
+        assert_eq!(moves.len(), legal.len());
+        assert_eq!(limiter.stats().fallbacks, 1);
+        assert_eq!(limiter.stats().nodes_limited, 0);
+    }
+}
@@ -0,0 +1,119 @@
+//! Info Line Output
+//!
+//! Destination for the USI `info` lines produced while searching. Lines go to
+//! stdout unless a sink is installed; embedders without a stdout (the wasm
+//! bindings) install one to receive them as callbacks. Embedders running
+//! several engines in one process install a sink per engine thread instead,
+//! which background searches started from that thread inherit. Searches the
+//! GUI did not ask for, such as the warm-up after `isready`, run with output
+//! suppressed.
+
+use std::cell::{Cell, RefCell};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+/// Callback receiving one `info` line at a time
+pub type InfoSink = Box<dyn Fn(&str) + Send>;
+
+/// Sink of one engine thread, shared with the searches it starts
+pub type ThreadInfoSink = Arc<dyn Fn(&str) + Send + Sync>;
+
+static INFO_SINK: Mutex<Option<InfoSink>> = Mutex::new(None);
+
+thread_local! {
+    static INFO_SUPPRESSED: Cell<bool> = const { Cell::new(false) };
+    static THREAD_INFO_SINK: RefCell<Option<ThreadInfoSink>> = const { RefCell::new(None) };
+}
+
+/// Install a sink for info lines, or restore stdout output with `None`
+pub fn set_info_sink(sink: Option<InfoSink>) {
+    if let Ok(mut guard) = INFO_SINK.lock() {
+        *guard = sink;
+    }
+}
+
+/// Install a sink for the info lines this thread emits, ahead of the
+/// process-wide one, or remove it with `None`
+pub fn set_thread_info_sink(sink: Option<ThreadInfoSink>) {
+    THREAD_INFO_SINK.with(|thread_sink| *thread_sink.borrow_mut() = sink);
+}
+
+/// This thread's sink, for handing on to a search thread it starts
+pub fn thread_info_sink() -> Option<ThreadInfoSink> {
+    THREAD_INFO_SINK.with(|thread_sink| thread_sink.borrow().clone())
+}
+
+/// Run `f` with the info lines this thread emits discarded. A search's progress
+/// thread follows suit; lines from other threads `f` spawns are not affected.
+pub fn with_info_suppressed<T>(f: impl FnOnce() -> T) -> T {
+    let previous = INFO_SUPPRESSED.with(|suppressed| suppressed.replace(true));
+    let result = f();
+    INFO_SUPPRESSED.with(|suppressed| suppressed.set(previous));
+    result
+}
+
+/// Whether this thread's info lines are being discarded, for handing on to a
+/// thread reporting on its behalf
+pub fn info_suppressed() -> bool {
+    INFO_SUPPRESSED.with(Cell::get)
+}
+
+/// Discard (or stop discarding) the info lines this thread emits
+pub fn set_info_suppressed(suppressed: bool) {
+    INFO_SUPPRESSED.with(|cell| cell.set(suppressed));
+}
+
+/// Emit an info line to the installed sink, falling back to stdout
+pub fn emit_info(line: &str) {
+    if info_suppressed() {
+        return;
+    }
+    if let Some(sink) = thread_info_sink() {
+        sink(line);
+        return;
+    }
+    if let Ok(guard) = INFO_SINK.lock() {
+        if let Some(sink) = guard.as_ref() {
+            sink(line);
+            return;
+        }
+    }
+    println!("{}", line);
+    let _ = std::io::stdout().flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_sink_receives_lines() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink_received = received.clone();
+        set_info_sink(Some(Box::new(move |line| {
+            sink_received.lock().unwrap().push(line.to_string());
+        })));
+        emit_info("info depth 1 score cp 0");
+        set_info_sink(None);
+
+        let lines = received.lock().unwrap();
+        assert!(lines.iter().any(|line| line == "info depth 1 score cp 0"));
+    }
+
+    #[test]
+    fn test_thread_sink_is_per_thread() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink_received = received.clone();
+        set_thread_info_sink(Some(Arc::new(move |line: &str| {
+            sink_received.lock().unwrap().push(line.to_string());
+        })));
+        emit_info("info depth 2 score cp 10");
+        std::thread::spawn(|| assert!(thread_info_sink().is_none()))
+            .join()
+            .unwrap();
+        set_thread_info_sink(None);
+
+        assert_eq!(*received.lock().unwrap(), vec!["info depth 2 score cp 10".to_string()]);
+    }
+}
@@ -0,0 +1,203 @@
+//! Info Verbosity
+//!
+//! How much `info` output a search produces. Every line crosses the Tauri event
+//! channel, and long analyses at high node rates sent enough of them to slow
+//! the UI. The `Info*` options thin the output: completed iterations reported
+//! only every few depths, progress lines within an iteration at a longer
+//! interval or only after enough new nodes, no `currmove` lines, and fewer
+//! fields per line. `depth` and `score` are always sent, and a completed
+//! iteration held back by the depth interval is sent when the search ends, so
+//! the GUI always sees the final result.
+
+use super::statistics::InfoRateLimiter;
+use std::time::Duration;
+
+/// Default least time between progress lines within an iteration
+pub const DEFAULT_INFO_INTERVAL_MS: u64 = 1_000;
+
+/// Optional fields in the order they are written; `pv` runs to the end of the line
+const FIELD_NAMES: [&str; 8] =
+    ["seldepth", "multipv", "time", "nodes", "nps", "hashfull", "winrate", "pv"];
+
+/// Set of optional fields kept in `info` lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InfoFields(u16);
+
+impl InfoFields {
+    pub const ALL: Self = Self((1 << FIELD_NAMES.len()) - 1);
+    pub const NONE: Self = Self(0);
+
+    /// Parse an `InfoFields` option value: `all`, `none` or a comma-separated
+    /// list of field names; case-insensitive
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "all" => return Ok(Self::ALL),
+            "none" => return Ok(Self::NONE),
+            _ => {}
+        }
+        let mut fields = Self::NONE;
+        for name in value.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            let index = FIELD_NAMES
+                .iter()
+                .position(|field| field.eq_ignore_ascii_case(name))
+                .ok_or_else(|| format!("unknown info field '{}'", name))?;
+            fields.0 |= 1 << index;
+        }
+        Ok(fields)
+    }
+
+    pub fn contains(self, name: &str) -> bool {
+        FIELD_NAMES
+            .iter()
+            .position(|field| *field == name)
+            .is_some_and(|i| self.0 & (1 << i) != 0)
+    }
+
+    /// Field names as an option value
+    pub fn as_string(self) -> String {
+        match self {
+            Self::ALL => "all".to_string(),
+            Self::NONE => "none".to_string(),
+            _ => FIELD_NAMES
+                .iter()
+                .filter(|name| self.contains(name))
+                .copied()
+                .collect::<Vec<_>>()
+                .join(","),
+        }
+    }
+
+    /// `line` without the fields not in the set; `info string` lines and
+    /// unknown tokens pass through unchanged
+    pub fn filter(self, line: &str) -> String {
+        if self == Self::ALL {
+            return line.to_string();
+        }
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let mut kept = Vec::with_capacity(tokens.len());
+        let mut i = 0;
+        while i < tokens.len() {
+            let token = tokens[i];
+            if token == "string" {
+                kept.extend_from_slice(&tokens[i..]);
+                break;
+            }
+            if FIELD_NAMES.contains(&token) && !self.contains(token) {
+                if token == "pv" {
+                    break;
+                }
+                i += 2;
+                continue;
+            }
+            kept.push(token);
+            i += 1;
+        }
+        kept.join(" ")
+    }
+}
+
+impl Default for InfoFields {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+/// What a search reports in `info` lines and how often
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InfoVerbosity {
+    /// Completed iterations are reported at every `depth_interval`-th depth
+    pub depth_interval: u8,
+    /// Least time between progress lines within an iteration; 0 turns them off
+    pub interval_ms: u64,
+    /// Least nodes searched between progress lines; 0 for no node limit
+    pub node_interval: u64,
+    /// Report the root move being searched in long iterations
+    pub currmove: bool,
+    pub fields: InfoFields,
+}
+
+impl Default for InfoVerbosity {
+    fn default() -> Self {
+        Self {
+            depth_interval: 1,
+            interval_ms: DEFAULT_INFO_INTERVAL_MS,
+            node_interval: 0,
+            currmove: true,
+            fields: InfoFields::ALL,
+        }
+    }
+}
+
+impl InfoVerbosity {
+    /// Whether completed iteration `depth` is reported as soon as it completes
+    pub fn reports_depth(&self, depth: u8) -> bool {
+        self.depth_interval <= 1 || depth % self.depth_interval == 0
+    }
+
+    /// Throttle for the progress lines of one iteration; `None` when they are off
+    pub fn progress_throttle(&self) -> Option<ProgressThrottle> {
+        (self.interval_ms > 0).then(|| ProgressThrottle {
+            limiter: InfoRateLimiter::starting_now(Duration::from_millis(self.interval_ms)),
+            node_interval: self.node_interval,
+            last_nodes: 0,
+        })
+    }
+}
+
+/// Limits progress lines by both time and nodes searched
+#[derive(Debug, Clone)]
+pub struct ProgressThrottle {
+    limiter: InfoRateLimiter,
+    node_interval: u64,
+    last_nodes: u64,
+}
+
+impl ProgressThrottle {
+    /// Whether a progress line at `nodes` may be written now; records it if so
+    pub fn try_emit(&mut self, nodes: u64) -> bool {
+        if nodes.saturating_sub(self.last_nodes) < self.node_interval || !self.limiter.try_emit() {
+            return false;
+        }
+        self.last_nodes = nodes;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE: &str = "info depth 9 seldepth 14 multipv 1 score cp 85 winrate 535 time 820 \
+                        nodes 120000 nps 146000 hashfull 12 pv 7g7f 3c3d 2g2f";
+
+    #[test]
+    fn test_fields_filter_lines() {
+        assert_eq!(InfoFields::ALL.filter(LINE), LINE);
+        assert_eq!(InfoFields::NONE.filter(LINE), "info depth 9 score cp 85");
+
+        let fields = InfoFields::parse("nodes, PV").unwrap();
+        assert_eq!(fields.as_string(), "nodes,pv");
+        assert_eq!(fields.filter(LINE), "info depth 9 score cp 85 nodes 120000 pv 7g7f 3c3d 2g2f");
+        assert_eq!(fields.filter("info string time 5"), "info string time 5");
+        assert!(InfoFields::parse("nodes,eval").is_err());
+        assert_eq!(InfoFields::parse("All"), Ok(InfoFields::ALL));
+    }
+
+    #[test]
+    fn test_throttle_by_depth_and_nodes() {
+        let verbosity = InfoVerbosity { depth_interval: 3, ..InfoVerbosity::default() };
+        let reported: Vec<u8> = (1..=9).filter(|&depth| verbosity.reports_depth(depth)).collect();
+        assert_eq!(reported, vec![3, 6, 9]);
+        assert!(InfoVerbosity { interval_ms: 0, ..verbosity }.progress_throttle().is_none());
+
+        let mut throttle = InfoVerbosity { interval_ms: 1, node_interval: 1_000, ..verbosity }
+            .progress_throttle()
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(!throttle.try_emit(999));
+        assert!(throttle.try_emit(1_000));
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(!throttle.try_emit(1_500));
+        assert!(throttle.try_emit(2_000));
+    }
+}
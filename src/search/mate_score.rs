@@ -0,0 +1,132 @@
+//! Mate Score Module
+//!
+//! Mate scores encode the distance to mate: being mated at ply `n` scores
+//! `-MATE_SCORE + n`, so shorter mates score higher for the winning side. Because the
+//! distance is relative to the root, scores must be converted to "distance from the
+//! current node" before they are stored in the transposition table and converted back
+//! when they are read at a different ply.
+
+/// Score of a position where the side to move is checkmated at the root
+pub const MATE_SCORE: i32 = 100_000;
+
+/// Longest mate distance the score encoding can represent
+pub const MAX_MATE_PLY: i32 = 1_000;
+
+/// Scores with an absolute value at or above this threshold are mate scores
+pub const MATE_THRESHOLD: i32 = MATE_SCORE - MAX_MATE_PLY;
+
+/// Score for the side to move being checkmated `ply` plies from the root
+pub fn mated_in(ply: u8) -> i32 {
+    -MATE_SCORE + i32::from(ply)
+}
+
+/// Score for the side to move delivering checkmate `ply` plies from the root
+pub fn mate_in(ply: u8) -> i32 {
+    MATE_SCORE - i32::from(ply)
+}
+
+/// Whether a score encodes a forced mate (for either side)
+pub fn is_mate_score(score: i32) -> bool {
+    (MATE_THRESHOLD..=MATE_SCORE).contains(&score.abs())
+}
+
+/// Signed distance to mate in plies: positive when the side to move mates,
+/// negative when it gets mated. `None` for non-mate scores.
+pub fn mate_distance(score: i32) -> Option<i32> {
+    if !is_mate_score(score) {
+        return None;
+    }
+    Some(if score > 0 {
+        MATE_SCORE - score
+    } else {
+        -(MATE_SCORE + score)
+    })
+}
+
+/// Convert a search score at `ply` into the form stored in the transposition table,
+/// where mate distances are measured from the stored node rather than the root
+pub fn score_to_tt(score: i32, ply: u8) -> i32 {
+    if !is_mate_score(score) {
+        score
+    } else if score > 0 {
+        score + i32::from(ply)
+    } else {
+        score - i32::from(ply)
+    }
+}
+
+/// Convert a transposition table score back into a search score at `ply`
+pub fn score_from_tt(score: i32, ply: u8) -> i32 {
+    if !is_mate_score(score) {
+        score
+    } else if score > 0 {
+        score - i32::from(ply)
+    } else {
+        score + i32::from(ply)
+    }
+}
+
+/// Mate-distance pruning: narrow the window to scores that are still achievable at
+/// `ply`. If the returned `alpha >= beta`, a shorter mate has already been found and
+/// the node can return `alpha` immediately.
+pub fn mate_distance_bounds(alpha: i32, beta: i32, ply: u8) -> (i32, i32) {
+    let alpha = alpha.max(mated_in(ply));
+    let beta = beta.min(mate_in(ply.saturating_add(1)));
+    (alpha, beta)
+}
+
+/// Format a score for USI `info` output (`cp N` or `mate N` in plies)
+pub fn format_usi_score(score: i32) -> String {
+    match mate_distance(score) {
+        Some(plies) => format!("mate {}", plies),
+        None => format!("cp {}", score),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tt_round_trip_preserves_distance() {
+        // Mate found 3 plies below a node at ply 4: mate at ply 7 from the root
+        let score = mate_in(7);
+        let stored = score_to_tt(score, 4);
+        assert_eq!(stored, mate_in(3));
+
+        // The same position reached at ply 2 is a mate at ply 5 from the root
+        assert_eq!(score_from_tt(stored, 2), mate_in(5));
+
+        let mated = mated_in(6);
+        assert_eq!(score_from_tt(score_to_tt(mated, 6), 6), mated);
+        assert_eq!(score_from_tt(score_to_tt(mated, 6), 2), mated_in(2));
+    }
+
+    #[test]
+    fn test_non_mate_scores_are_unchanged() {
+        for score in [0, 150, -320, MATE_THRESHOLD - 1, i32::MAX - 1, i32::MIN + 1] {
+            assert_eq!(score_to_tt(score, 10), score);
+            assert_eq!(score_from_tt(score, 10), score);
+        }
+        assert_eq!(mate_distance(1234), None);
+    }
+
+    #[test]
+    fn test_mate_distance_and_formatting() {
+        assert_eq!(mate_distance(mate_in(3)), Some(3));
+        assert_eq!(mate_distance(mated_in(4)), Some(-4));
+        assert_eq!(format_usi_score(mate_in(1)), "mate 1");
+        assert_eq!(format_usi_score(mated_in(2)), "mate -2");
+        assert_eq!(format_usi_score(-45), "cp -45");
+    }
+
+    #[test]
+    fn test_mate_distance_pruning_window() {
+        // A mate in 3 is already known: nothing at ply 5 can improve on it
+        let (alpha, beta) = mate_distance_bounds(mate_in(3), MATE_SCORE, 5);
+        assert!(alpha >= beta);
+
+        // Ordinary windows are left alone
+        assert_eq!(mate_distance_bounds(-200, 300, 5), (-200, 300));
+    }
+}
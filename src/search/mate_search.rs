@@ -0,0 +1,306 @@
+//! Mate Search Module
+//!
+//! Checks-only mate solver used for `go mate` (tsume solving). The attacker only
+//! considers checking moves while the defender considers every legal reply, so the
+//! tree stays small enough to prove short mates exhaustively. Iterative deepening over
+//! odd plies means the first mate found is also the shortest one. The lone-king
+//! endgame solvers let the attacker play quiet moves too, as closing a mating net
+//! takes them, and bound the search by nodes instead of time.
+
+use crate::bitboards::BitboardBoard;
+use crate::moves::MoveGenerator;
+use crate::search::search_engine::make_move_with_hand;
+use crate::search::BoardTrait;
+use crate::types::board::CapturedPieces;
+use crate::types::core::{Move, PieceType, Player, Position};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Default ply limit when the caller doesn't specify one
+pub const DEFAULT_MATE_PLY_LIMIT: u8 = 15;
+
+/// Ply limit of the mate probe run before the main search: mates in up to 3 moves
+pub const ROOT_MATE_PLY_LIMIT: u8 = 5;
+
+/// The root mate probe gets this fraction of the move time...
+pub const ROOT_MATE_TIME_DIVISOR: u32 = 20;
+
+/// ...but never more than this many milliseconds
+pub const ROOT_MATE_MAX_TIME_MS: u32 = 200;
+
+/// Outcome of a mate search
+#[derive(Debug, Clone, PartialEq)]
+pub enum MateSearchResult {
+    /// Forced mate; the sequence alternates attacker and defender moves
+    Mate(Vec<Move>),
+    /// The search completed without finding a mate within the ply limit
+    NoMate,
+    /// The time limit or stop flag ended the search before it completed
+    Timeout,
+}
+
+/// Depth-limited AND/OR mate solver
+pub struct MateSearcher {
+    move_generator: MoveGenerator,
+    max_ply: u8,
+    deadline: Option<Instant>,
+    stop_flag: Option<Arc<AtomicBool>>,
+    /// Positions after which the search gives up, as it does at the deadline
+    node_limit: Option<u64>,
+    /// Whether the attacker may play moves that don't give check
+    quiet_moves: bool,
+    nodes: u64,
+    timed_out: bool,
+    /// Attacker-to-move positions proven not to mate within the stored plies
+    refuted: HashMap<u64, u8>,
+}
+
+impl MateSearcher {
+    /// Create a solver. `time_limit_ms` of `None` searches until the ply limit is
+    /// exhausted or the stop flag is raised.
+    pub fn new(max_ply: u8, time_limit_ms: Option<u32>, stop_flag: Option<Arc<AtomicBool>>) -> Self {
+        Self {
+            move_generator: MoveGenerator::new(),
+            max_ply,
+            deadline: time_limit_ms
+                .map(|ms| Instant::now() + Duration::from_millis(u64::from(ms))),
+            stop_flag,
+            node_limit: None,
+            quiet_moves: false,
+            nodes: 0,
+            timed_out: false,
+            refuted: HashMap::new(),
+        }
+    }
+
+    /// Let the attacker play quiet moves as well as checks. Needed for mates
+    /// that close a net first; the tree grows much faster.
+    pub fn with_quiet_moves(mut self) -> Self {
+        self.quiet_moves = true;
+        self
+    }
+
+    /// End the search with `Timeout` after `nodes` positions
+    pub fn with_node_limit(mut self, nodes: u64) -> Self {
+        self.node_limit = Some(nodes);
+        self
+    }
+
+    /// Number of positions visited by the last search
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    /// Search for a forced mate by `attacker`, who is to move in the given position
+    pub fn search(
+        &mut self,
+        board: &BitboardBoard,
+        captured_pieces: &CapturedPieces,
+        attacker: Player,
+    ) -> MateSearchResult {
+        self.nodes = 0;
+        self.timed_out = false;
+
+        let mut ply = 1;
+        while ply <= self.max_ply {
+            if let Some(line) = self.attack(board, captured_pieces, attacker, ply) {
+                return MateSearchResult::Mate(line);
+            }
+            if self.timed_out {
+                return MateSearchResult::Timeout;
+            }
+            ply += 2;
+        }
+        MateSearchResult::NoMate
+    }
+
+    fn should_stop(&mut self) -> bool {
+        if self.timed_out {
+            return true;
+        }
+        let stopped = self
+            .stop_flag
+            .as_ref()
+            .map_or(false, |flag| flag.load(Ordering::Relaxed));
+        let expired = self.deadline.map_or(false, |deadline| Instant::now() >= deadline);
+        let exhausted = self.node_limit.map_or(false, |limit| self.nodes >= limit);
+        self.timed_out = stopped || expired || exhausted;
+        self.timed_out
+    }
+
+    /// OR node: the attacker needs one move (a check unless quiet moves are on) that
+    /// mates within `remaining` plies
+    fn attack(
+        &mut self,
+        board: &BitboardBoard,
+        captured_pieces: &CapturedPieces,
+        attacker: Player,
+        remaining: u8,
+    ) -> Option<Vec<Move>> {
+        if self.should_stop() {
+            return None;
+        }
+        let key = board.get_position_hash(captured_pieces) ^ attacker as u64;
+        if self.refuted.get(&key).map_or(false, |&plies| plies >= remaining) {
+            return None;
+        }
+        let defender = attacker.opposite();
+
+        for (move_, next_board, next_captured) in
+            self.attacker_moves(board, captured_pieces, attacker)
+        {
+            self.nodes += 1;
+
+            let replies =
+                self.move_generator
+                    .generate_legal_moves(&next_board, defender, &next_captured);
+            if replies.is_empty() {
+                // Mating with a pawn drop (uchifuzume) is illegal
+                if move_.from.is_none() && move_.piece_type == PieceType::Pawn {
+                    continue;
+                }
+                return Some(vec![move_]);
+            }
+
+            if remaining >= 3 {
+                if let Some(mut line) =
+                    self.defend(&next_board, &next_captured, attacker, replies, remaining - 1)
+                {
+                    line.insert(0, move_);
+                    return Some(line);
+                }
+            }
+            if self.timed_out {
+                return None;
+            }
+        }
+        let refuted = self.refuted.entry(key).or_insert(0);
+        *refuted = (*refuted).max(remaining);
+        None
+    }
+
+    /// The attacker's candidate moves with the positions after them: checks, or
+    /// with quiet moves on every legal move, checks and moves nearing the king first
+    fn attacker_moves(
+        &self,
+        board: &BitboardBoard,
+        captured_pieces: &CapturedPieces,
+        attacker: Player,
+    ) -> Vec<(Move, BitboardBoard, CapturedPieces)> {
+        let defender = attacker.opposite();
+        if !self.quiet_moves {
+            // generate_checks is pseudo-legal, so discard checks that expose our own king
+            return self
+                .move_generator
+                .generate_checks(board, attacker, captured_pieces)
+                .into_iter()
+                .map(|check| {
+                    let (next_board, next_captured) = play(board, captured_pieces, &check);
+                    (check, next_board, next_captured)
+                })
+                .filter(|(_, next_board, next_captured)| {
+                    !next_board.is_king_in_check(attacker, next_captured)
+                })
+                .collect();
+        }
+
+        let Some(king) = board.find_king_position(defender) else {
+            return Vec::new();
+        };
+        let mut children: Vec<_> = self
+            .move_generator
+            .generate_legal_moves(board, attacker, captured_pieces)
+            .into_iter()
+            .map(|move_| {
+                let (next_board, next_captured) = play(board, captured_pieces, &move_);
+                let gives_check = next_board.is_king_in_check(defender, &next_captured);
+                (!gives_check, distance(move_.to, king), move_, next_board, next_captured)
+            })
+            .collect();
+        children.sort_by_key(|(quiet, distance, ..)| (*quiet, *distance));
+        children
+            .into_iter()
+            .map(|(_, _, move_, next_board, next_captured)| (move_, next_board, next_captured))
+            .collect()
+    }
+
+    /// AND node: every defender reply must lose; the longest refutation is reported
+    fn defend(
+        &mut self,
+        board: &BitboardBoard,
+        captured_pieces: &CapturedPieces,
+        attacker: Player,
+        replies: Vec<Move>,
+        remaining: u8,
+    ) -> Option<Vec<Move>> {
+        let mut longest: Option<Vec<Move>> = None;
+        for reply in replies {
+            let (next_board, next_captured) = play(board, captured_pieces, &reply);
+            self.nodes += 1;
+            let line = self.attack(&next_board, &next_captured, attacker, remaining - 1)?;
+            if longest.as_ref().map_or(true, |best| line.len() + 1 > best.len()) {
+                let mut full = Vec::with_capacity(line.len() + 1);
+                full.push(reply);
+                full.extend(line);
+                longest = Some(full);
+            }
+        }
+        longest
+    }
+}
+
+fn distance(a: Position, b: Position) -> u8 {
+    let dr = (a.row as i8 - b.row as i8).unsigned_abs();
+    let dc = (a.col as i8 - b.col as i8).unsigned_abs();
+    dr.max(dc)
+}
+
+/// Play a move on copies of the board and hands
+fn play(
+    board: &BitboardBoard,
+    captured_pieces: &CapturedPieces,
+    move_: &Move,
+) -> (BitboardBoard, CapturedPieces) {
+    let mut next_board = board.clone();
+    let (_, next_captured) =
+        make_move_with_hand(&mut next_board, captured_pieces, move_, move_.player);
+    (next_board, next_captured)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finds_gold_drop_mate_in_one() {
+        let (board, player, captured) =
+            BitboardBoard::from_fen("4k4/9/4P4/9/9/9/9/9/4K4 b G 1").unwrap();
+        let mut searcher = MateSearcher::new(DEFAULT_MATE_PLY_LIMIT, Some(10_000), None);
+        match searcher.search(&board, &captured, player) {
+            MateSearchResult::Mate(line) => {
+                assert_eq!(line.len(), 1);
+                assert_eq!(line[0].to_usi_string(), "G*5b");
+            }
+            other => panic!("expected mate, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_reports_no_mate_without_material() {
+        let (board, player, captured) =
+            BitboardBoard::from_fen("4k4/9/9/9/9/9/9/9/4K4 b - 1").unwrap();
+        let mut searcher = MateSearcher::new(3, Some(10_000), None);
+        assert_eq!(searcher.search(&board, &captured, player), MateSearchResult::NoMate);
+    }
+
+    #[test]
+    fn test_stop_flag_reports_timeout() {
+        let (board, player, captured) =
+            BitboardBoard::from_fen("4k4/9/4P4/9/9/9/9/9/4K4 b G 1").unwrap();
+        let stop_flag = Arc::new(AtomicBool::new(true));
+        let mut searcher = MateSearcher::new(DEFAULT_MATE_PLY_LIMIT, None, Some(stop_flag));
+        assert_eq!(searcher.search(&board, &captured, player), MateSearchResult::Timeout);
+    }
+}
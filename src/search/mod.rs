@@ -1,16 +1,36 @@
+pub mod analysis_cache;
+pub mod board_pool;
 pub mod board_trait;
+pub mod book_hash;
+pub mod clock_contempt;
+pub mod coach;
+pub mod drop_limiter;
+pub mod info_sink;
+pub mod info_verbosity;
 pub mod iterative_deepening;
+pub mod mate_score;
+pub mod mate_search;
 pub mod null_move;
 pub mod parallel_search;
+pub mod perspective;
+pub mod ply_guard;
 pub mod pvs;
 pub mod quiescence;
 pub mod reductions;
+pub mod repetition;
+pub mod root_moves;
+pub mod root_split;
+pub mod root_variety;
 pub mod search_engine;
+pub mod search_handle;
+pub mod search_watchdog;
 pub mod shogi_hash;
 pub mod shogi_position_tests;
 pub mod statistics;
 pub mod time_management;
 pub mod transposition_table;
+pub mod tt_move_check;
+pub mod win_probability;
 pub mod zobrist;
 pub use parallel_search::{
     ParallelSearchConfig, ParallelSearchEngine, ThreadLocalSearchContext, WorkDistributionStats,
@@ -43,7 +63,9 @@ pub mod performance_tuning;
 pub mod runtime_configuration;
 pub mod memory_tracking;
 
-// Web compatibility modules removed - no longer needed
+// Web compatibility shims for the wasm bindings
+#[cfg(feature = "wasm")]
+pub mod wasm_compatibility;
 
 // Advanced features modules
 pub mod advanced_cache_warming;
@@ -0,0 +1,130 @@
+//! Board-aware move scoring
+//!
+//! `MoveOrdering::score_move` only sees the move itself, so captures are scored from
+//! whatever the generator recorded and nothing is known about the surrounding position.
+//! The terms here need the position the move is played from: the real victim on the
+//! target square, static exchange evaluation, proximity to the opponent king and
+//! whether a capture adds a new piece type to the hand. They are added on top of the
+//! board-independent score, which stays cacheable per move.
+
+use super::capture_ordering::score_capture_move_inline;
+use super::see_calculation::calculate_see_internal;
+use crate::bitboards::BitboardBoard;
+use crate::types::board::CapturedPieces;
+use crate::types::core::{Move, Piece, PieceType, Player, Position};
+
+/// Bonus per square closer than `KING_PROXIMITY_RANGE` to the opponent king
+pub const KING_PROXIMITY_BONUS: i32 = 15;
+
+/// Chebyshev distance from the opponent king inside which moves get a proximity bonus
+pub const KING_PROXIMITY_RANGE: i32 = 3;
+
+/// Bonus for a capture that gives the side to move a piece type it has none of in hand
+pub const NEW_HAND_PIECE_BONUS: i32 = 30;
+
+/// The opponent piece `move_` captures, read from the board when the generator left it unset
+pub fn board_victim(move_: &Move, board: &BitboardBoard) -> Option<Piece> {
+    move_
+        .captured_piece
+        .or_else(|| board.get_piece(move_.to))
+        .filter(|piece| piece.player != move_.player)
+}
+
+/// Chebyshev distance between two squares
+pub fn king_distance(a: Position, b: Position) -> i32 {
+    let dr = (a.row as i32 - b.row as i32).abs();
+    let dc = (a.col as i32 - b.col as i32).abs();
+    dr.max(dc)
+}
+
+/// Score the position-dependent terms of `move_` for `player` to move
+///
+/// Depth 0 is quiescence, where only captures are ordered, so the king proximity
+/// term is skipped there.
+pub fn score_board_context(
+    move_: &Move,
+    board: &BitboardBoard,
+    captured_pieces: &CapturedPieces,
+    player: Player,
+    depth: u8,
+    capture_weight: i32,
+    see_weight: i32,
+) -> i32 {
+    let mut score = 0;
+
+    if let Some(victim) = board_victim(move_, board) {
+        let mut capture = move_.clone();
+        capture.is_capture = true;
+        capture.captured_piece = Some(victim);
+        if move_.captured_piece.is_none() {
+            // The board-independent score could not see this victim
+            score += score_capture_move_inline(&capture, capture_weight);
+        }
+        score += calculate_see_internal(&capture, board) * see_weight / 1000;
+
+        let hand_type = victim.unpromoted().piece_type;
+        if hand_type != PieceType::King && captured_pieces.count(hand_type, player) == 0 {
+            score += NEW_HAND_PIECE_BONUS;
+        }
+    }
+
+    if depth > 0 {
+        if let Some(king) = board.find_king_position(player.opposite()) {
+            let distance = king_distance(move_.to, king);
+            if distance < KING_PROXIMITY_RANGE {
+                score += (KING_PROXIMITY_RANGE - distance) * KING_PROXIMITY_BONUS;
+            }
+        }
+    }
+
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_board_victim_read_from_board() {
+        let board = BitboardBoard::new();
+        // Black pawn move onto White's pawn rank, generator left the victim unset
+        let mut move_ = Move::new_move(
+            Position::new(3, 4),
+            Position::new(2, 4),
+            PieceType::Pawn,
+            Player::Black,
+            false,
+        );
+        move_.is_capture = true;
+        let victim = board_victim(&move_, &board).expect("white pawn on the target square");
+        assert_eq!(victim.piece_type, PieceType::Pawn);
+        assert_eq!(victim.player, Player::White);
+
+        // Own pieces are never victims
+        let own = Move::new_move(
+            Position::new(7, 7),
+            Position::new(6, 7),
+            PieceType::Bishop,
+            Player::Black,
+            false,
+        );
+        assert!(board_victim(&own, &board).is_none());
+    }
+
+    #[test]
+    fn test_king_proximity_only_outside_quiescence() {
+        let board = BitboardBoard::new();
+        let captured = CapturedPieces::new();
+        // White king starts on 5a (row 0, col 4)
+        let near_king = Move::new_drop(PieceType::Gold, Position::new(1, 4), Player::Black);
+        let far_away = Move::new_drop(PieceType::Gold, Position::new(5, 0), Player::Black);
+
+        let near = score_board_context(&near_king, &board, &captured, Player::Black, 3, 1000, 800);
+        let far = score_board_context(&far_away, &board, &captured, Player::Black, 3, 1000, 800);
+        assert!(near > far);
+        assert_eq!(
+            score_board_context(&near_king, &board, &captured, Player::Black, 0, 1000, 800),
+            0
+        );
+    }
+}
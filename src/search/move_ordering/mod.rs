@@ -37,7 +37,7 @@ use crate::types::transposition::TranspositionEntry;
 use crate::types::TranspositionFlag;
 use std::collections::HashMap;
 use std::fmt;
-use std::ptr;
+use std::sync::Arc;
 
 // Task 1.22: Modularized move ordering - submodules are in the same directory
 mod statistics;
@@ -64,6 +64,13 @@ pub use capture_ordering::{
 
 mod see_calculation;
 
+mod board_context;
+
+pub use board_context::{
+    board_victim, king_distance, score_board_context, KING_PROXIMITY_BONUS,
+    KING_PROXIMITY_RANGE, NEW_HAND_PIECE_BONUS,
+};
+
 pub use see_calculation::{
     calculate_see_internal as calculate_see_internal_helper,
     piece_attacks_square as piece_attacks_square_helper, score_see_move as score_see_move_helper,
@@ -1485,8 +1492,8 @@ pub struct MoveOrdering {
     pub memory_usage: MemoryUsage,
     /// Move scoring cache for performance optimization (Task 1.22: extracted to cache module)
     move_score_cache: MoveScoreCache,
-    /// Transposition table reference for PV move retrieval
-    transposition_table: *const crate::search::ThreadSafeTranspositionTable,
+    /// Shared transposition table for PV move retrieval
+    transposition_table: Option<Arc<crate::search::ThreadSafeTranspositionTable>>,
     /// Hash calculator for position hashing
     hash_calculator: crate::search::ShogiHashHandler,
     /// PV ordering manager (Task 6.0: extracted to module)
@@ -2122,7 +2129,7 @@ impl MoveOrdering {
                 config.cache_config.max_cache_size,
                 64, // Fast cache size
             ),
-            transposition_table: ptr::null(),
+            transposition_table: None,
             hash_calculator: crate::search::ShogiHashHandler::new(
                 config.cache_config.max_cache_size,
             ),
@@ -2195,8 +2202,11 @@ impl MoveOrdering {
             move_scores.push((score, i));
         }
 
-        // OPTIMIZATION: Sort by score using stable sort for deterministic ordering
-        move_scores.sort_by(|a, b| b.0.cmp(&a.0));
+        // Equal scores fall back to a fixed key for deterministic ordering
+        move_scores.sort_by(|a, b| {
+            b.0.cmp(&a.0)
+                .then_with(|| tie_break_key(&moves[a.1]).cmp(&tie_break_key(&moves[b.1])))
+        });
 
         // OPTIMIZATION: Rebuild ordered moves using pre-computed scores
         for (_, index) in &move_scores {
@@ -2336,6 +2346,59 @@ impl MoveOrdering {
         Ok(score)
     }
 
+    /// Score a move in the position it is played from
+    ///
+    /// Adds the board-dependent terms (real victim, SEE, king proximity, new hand
+    /// piece) to the board-independent `score_move`. Only the latter is cached, since
+    /// the move score cache is keyed by the move alone.
+    pub fn score_move_in_position(
+        &mut self,
+        move_: &Move,
+        board: &crate::bitboards::BitboardBoard,
+        captured_pieces: &CapturedPieces,
+        player: Player,
+        depth: u8,
+    ) -> MoveOrderingResult<i32> {
+        let score = self.score_move(move_)?;
+        Ok(score
+            + score_board_context(
+                move_,
+                board,
+                captured_pieces,
+                player,
+                depth,
+                self.config.weights.capture_weight,
+                self.config.weights.see_weight,
+            ))
+    }
+
+    /// Order moves by `score_move_in_position`
+    ///
+    /// The board-aware counterpart of `order_moves`, without the search heuristics
+    /// (PV, killers, history) that `order_moves_with_all_heuristics` adds.
+    pub fn order_moves_in_position(
+        &mut self,
+        moves: &[Move],
+        board: &crate::bitboards::BitboardBoard,
+        captured_pieces: &CapturedPieces,
+        player: Player,
+        depth: u8,
+    ) -> MoveOrderingResult<Vec<Move>> {
+        self.stats.total_moves_ordered += moves.len() as u64;
+        self.stats.moves_sorted += moves.len() as u64;
+
+        let mut scored = Vec::with_capacity(moves.len());
+        for (index, move_) in moves.iter().enumerate() {
+            let score = self.score_move_in_position(move_, board, captured_pieces, player, depth)?;
+            scored.push((score, index));
+        }
+        scored.sort_by(|a, b| {
+            b.0.cmp(&a.0)
+                .then_with(|| tie_break_key(&moves[a.1]).cmp(&tie_break_key(&moves[b.1])))
+        });
+        Ok(scored.into_iter().map(|(_, index)| moves[index].clone()).collect())
+    }
+
     /// Update heuristic performance statistics
     fn update_heuristic_stats(
         &mut self,
@@ -3387,9 +3450,9 @@ impl MoveOrdering {
 
     // ==================== PV Move Ordering Methods ====================
 
-    /// Set the transposition table reference for PV move retrieval
-    pub fn set_transposition_table(&mut self, tt: &crate::search::ThreadSafeTranspositionTable) {
-        self.transposition_table = tt as *const crate::search::ThreadSafeTranspositionTable;
+    /// Share a transposition table for PV move retrieval
+    pub fn set_transposition_table(&mut self, tt: &Arc<crate::search::ThreadSafeTranspositionTable>) {
+        self.transposition_table = Some(Arc::clone(tt));
     }
 
     /// Score a move that matches the PV move from transposition table
@@ -3410,9 +3473,9 @@ impl MoveOrdering {
         player: Player,
         depth: u8,
     ) -> Option<Move> {
-        if self.transposition_table.is_null() {
+        let Some(tt) = self.transposition_table.clone() else {
             return None;
-        }
+        };
 
         // Calculate position hash
         let position_hash = self
@@ -3432,8 +3495,7 @@ impl MoveOrdering {
         // Query transposition table
         self.stats.tt_lookups += 1;
 
-        // Safe access to transposition table
-        let tt_entry = unsafe { (*self.transposition_table).probe(position_hash, depth) };
+        let tt_entry = tt.probe(position_hash, depth);
 
         let pv_move = if let Some(entry) = tt_entry {
             self.stats.tt_hits += 1;
@@ -3443,10 +3505,11 @@ impl MoveOrdering {
             None
         };
 
-        // Cache the result (Task 6.0: use PVOrdering module)
-        if !self
-            .pv_ordering
-            .is_cache_full(self.config.cache_config.max_cache_size)
+        // Cache hits only: the search may store a best move for this position later
+        if pv_move.is_some()
+            && !self
+                .pv_ordering
+                .is_cache_full(self.config.cache_config.max_cache_size)
         {
             self.pv_ordering
                 .cache_pv_move(position_hash, pv_move.clone());
@@ -3482,9 +3545,9 @@ impl MoveOrdering {
         best_move: Move,
         score: i32,
     ) {
-        if self.transposition_table.is_null() {
+        let Some(tt) = self.transposition_table.clone() else {
             return;
-        }
+        };
 
         // Calculate position hash
         let position_hash = self
@@ -3503,13 +3566,7 @@ impl MoveOrdering {
         };
 
         // Store in transposition table
-        unsafe {
-            if let Some(tt_ref) = self.transposition_table.as_ref() {
-                let tt_mut = tt_ref as *const crate::search::ThreadSafeTranspositionTable
-                    as *mut crate::search::ThreadSafeTranspositionTable;
-                (*tt_mut).store(entry);
-            }
-        }
+        tt.store(entry);
 
         // Update cache (Task 6.0: use PVOrdering module)
         if !self
@@ -5957,25 +6014,28 @@ impl MoveOrdering {
 
         // Task 3.0: Sort moves by score with all heuristics prioritization, including IID move
         // Task 2.6: Pass opponent's last move to move ordering for counter-move heuristic
-        ordered_moves.sort_by(|a, b| {
-            let score_a = self.score_move_with_all_heuristics(
-                a,
-                iid_move,
-                &pv_move,
-                &killer_moves,
-                opponent_last_move,
-                board,
-            );
-            let score_b = self.score_move_with_all_heuristics(
-                b,
-                iid_move,
-                &pv_move,
-                &killer_moves,
-                opponent_last_move,
-                board,
-            );
-            score_b.cmp(&score_a)
+        // Score each move once; board-aware scores are not cached per move
+        let mut scored: Vec<(i32, Move)> = ordered_moves
+            .drain(..)
+            .map(|move_| {
+                let score = self.score_move_with_all_heuristics(
+                    &move_,
+                    iid_move,
+                    &pv_move,
+                    &killer_moves,
+                    opponent_last_move,
+                    board,
+                    captured_pieces,
+                    player,
+                    depth,
+                );
+                (score, move_)
+            })
+            .collect();
+        scored.sort_by(|a, b| {
+            b.0.cmp(&a.0).then_with(|| tie_break_key(&a.1).cmp(&tie_break_key(&b.1)))
         });
+        ordered_moves.extend(scored.into_iter().map(|(_, move_)| move_));
 
         // Task 6.2: Cache the ordering result for this position and depth (Task 6.0: use cache_manager)
         // Task 3.0: Use improved eviction policy (LRU, depth-preferred, or hybrid)
@@ -6019,8 +6079,8 @@ impl MoveOrdering {
     /// 3. Killer moves (medium-high priority)
     /// 4. Counter-moves (medium-high priority, quiet moves only - Task 2.5)
     /// 5. History moves (medium priority)
-    /// 6. SEE moves (for captures - Task 1.0)
-    /// 7. Regular moves (normal priority)
+    /// 6. Everything else, scored in the position (MVV/LVA and SEE from the real
+    ///    victim, king proximity, new hand pieces)
     fn score_move_with_all_heuristics(
         &mut self,
         move_: &Move,
@@ -6029,6 +6089,9 @@ impl MoveOrdering {
         killer_moves: &[Move],
         opponent_last_move: Option<&Move>,
         board: &crate::bitboards::BitboardBoard,
+        captured_pieces: &CapturedPieces,
+        player: Player,
+        depth: u8,
     ) -> i32 {
         // Task 3.0: Check if this is the IID move (highest priority)
         if let Some(iid_mv) = iid_move {
@@ -6068,21 +6131,9 @@ impl MoveOrdering {
             return history_score;
         }
 
-        // Task 1.7: Use SEE for capture moves if enabled and board is available
-        if move_.is_capture && self.config.cache_config.enable_see_cache {
-            // Try to use SEE for capture ordering
-            if let Ok(see_score) = self.score_see_move(move_, board) {
-                if see_score != 0 {
-                    // SEE score is already scaled by see_weight, use it directly
-                    return see_score;
-                }
-            }
-            // Fall through to regular scoring if SEE fails or returns 0
-        }
-
-        // Use regular move scoring (MVV/LVA for captures)
         self.stats.killer_move_misses += 1;
-        self.score_move(move_).unwrap_or(0)
+        self.score_move_in_position(move_, board, captured_pieces, player, depth)
+            .unwrap_or(0)
     }
 
     // ==================== Transposition Table Integration ====================
@@ -6100,16 +6151,19 @@ impl MoveOrdering {
         depth: u8,
     ) -> MoveOrderingResult<()> {
         if let Some(entry) = tt_entry {
-            // Update PV move if we have a best move from the transposition table
+            // Remember the table's best move as the PV move. It already lives in the
+            // table, and writing it back as an exact entry would overwrite the bound.
             if let Some(ref best_move) = entry.best_move {
-                self.update_pv_move(
-                    board,
-                    captured_pieces,
-                    player,
-                    depth,
-                    best_move.clone(),
-                    entry.score,
-                );
+                let position_hash = self
+                    .hash_calculator
+                    .get_position_hash(board, player, captured_pieces);
+                if !self
+                    .pv_ordering
+                    .is_cache_full(self.config.cache_config.max_cache_size)
+                {
+                    self.pv_ordering
+                        .cache_pv_move(position_hash, Some(best_move.clone()));
+                }
                 self.stats.tt_integration_hits += 1;
 
                 // Update killer moves if this was a cutoff move
@@ -6985,6 +7039,14 @@ impl Default for MoveOrdering {
     }
 }
 
+/// Orders moves of equal score by their squares and piece rather than by the
+/// order they were generated in, so the result is the same on every run
+fn tie_break_key(move_: &Move) -> (u8, u8, u8, u8, u8, bool) {
+    let (from_row, from_col) = move_.from.map_or((u8::MAX, u8::MAX), |from| (from.row, from.col));
+    let piece = move_.piece_type as u8;
+    (from_row, from_col, move_.to.row, move_.to.col, piece, move_.is_promotion)
+}
+
 #[cfg(all(test, feature = "legacy-tests"))]
 mod tests {
     use super::*;
@@ -13679,6 +13741,7 @@ mod tests {
             moves_to_mate: Some(5),
             outcome: crate::tablebase::TablebaseOutcome::Win,
             confidence: 1.0,
+            exact: true,
         };
 
         // Integrate with tablebase
@@ -23,6 +23,19 @@ pub type SEEResult<T> = Result<T, String>;
 pub fn find_attackers_defenders(square: Position, board: &BitboardBoard) -> Vec<(Position, Piece)> {
     let mut all_attackers = Vec::new();
 
+    // Boards that track attack maps (search boards) already know the attackers
+    if let Some(maps) = board.tracked_attack_maps() {
+        for player in [Player::Black, Player::White] {
+            for position in board.iter_attack_targets(maps.attackers(player, square)) {
+                if let Some(piece) = board.get_piece(position) {
+                    all_attackers.push((position, piece));
+                }
+            }
+        }
+        all_attackers.sort_by_key(|(_, p)| p.piece_type.base_value());
+        return all_attackers;
+    }
+
     // Check both players' pieces
     for player in [Player::Black, Player::White] {
         // Get all pieces of this player that can attack the target square
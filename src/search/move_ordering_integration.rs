@@ -18,12 +18,13 @@
 //! use shogi_engine::search::{TranspositionMoveOrderer, ThreadSafeTranspositionTable};
 //! use shogi_engine::bitboards::BitboardBoard;
 //! use shogi_engine::types::{Move, Player, CapturedPieces};
+//! use std::sync::Arc;
 //!
 //! // Create move orderer
 //! let mut orderer = TranspositionMoveOrderer::new();
 //!
 //! // Set transposition table reference
-//! let tt = ThreadSafeTranspositionTable::new(Default::default());
+//! let tt = Arc::new(ThreadSafeTranspositionTable::new(Default::default()));
 //! orderer.set_transposition_table(&tt);
 //!
 //! // Order moves for a position
@@ -66,11 +67,12 @@ use crate::types::board::CapturedPieces;
 use crate::types::core::{Move, Player};
 use crate::types::search::TranspositionFlag;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Enhanced move ordering system with transposition table integration
 pub struct TranspositionMoveOrderer {
-    /// Transposition table for accessing stored best moves
-    transposition_table: *const ThreadSafeTranspositionTable,
+    /// Shared transposition table for accessing stored best moves
+    transposition_table: Option<Arc<ThreadSafeTranspositionTable>>,
     /// Hash calculator for position hashing
     pub hash_calculator: ShogiHashHandler,
     /// Move ordering statistics
@@ -119,7 +121,7 @@ impl TranspositionMoveOrderer {
     /// Create a new move orderer
     pub fn new() -> Self {
         Self {
-            transposition_table: std::ptr::null(),
+            transposition_table: None,
             hash_calculator: ShogiHashHandler::new(1000),
             stats: MoveOrderingStats::default(),
             history_table: [[0; 81]; 81],
@@ -128,9 +130,9 @@ impl TranspositionMoveOrderer {
         }
     }
 
-    /// Set the transposition table reference
-    pub fn set_transposition_table(&mut self, tt: &ThreadSafeTranspositionTable) {
-        self.transposition_table = tt as *const ThreadSafeTranspositionTable;
+    /// Share a transposition table with the orderer
+    pub fn set_transposition_table(&mut self, tt: &Arc<ThreadSafeTranspositionTable>) {
+        self.transposition_table = Some(Arc::clone(tt));
     }
 
     /// Order moves with transposition table integration
@@ -204,21 +206,18 @@ impl TranspositionMoveOrderer {
             .get_position_hash(board, player, captured_pieces);
 
         // Probe transposition table for best move and other hints
-        let (best_move, tt_depth, tt_score, tt_flag) = if !self.transposition_table.is_null() {
-            unsafe {
-                let tt = &*self.transposition_table;
-                if let Some(entry) = tt.probe(position_hash, depth) {
-                    self.stats.tt_hint_moves += 1;
-                    (
-                        entry.best_move,
-                        entry.depth,
-                        Some(entry.score),
-                        Some(entry.flag),
-                    )
-                } else {
-                    (None, 0, None, None)
-                }
-            }
+        let tt_entry = self
+            .transposition_table
+            .as_ref()
+            .and_then(|tt| tt.probe(position_hash, depth));
+        let (best_move, tt_depth, tt_score, tt_flag) = if let Some(entry) = tt_entry {
+            self.stats.tt_hint_moves += 1;
+            (
+                entry.best_move,
+                entry.depth,
+                Some(entry.score),
+                Some(entry.flag),
+            )
         } else {
             (None, 0, None, None)
         };
@@ -21,8 +21,12 @@
 use crate::bitboards::BitboardBoard;
 use crate::evaluation::PositionEvaluator;
 use crate::moves::MoveGenerator;
-use crate::search::search_engine::SearchEngine;
+use crate::search::info_sink;
+use crate::search::info_verbosity::InfoVerbosity;
+use crate::search::perspective::EvaluationPerspective;
+use crate::search::search_engine::{make_move_with_hand, SearchEngine};
 use crate::search::search_engine::GLOBAL_NODES_SEARCHED;
+use crate::search::win_probability::format_info_score;
 use crate::search::ThreadSafeTranspositionTable;
 use crate::utils::time::TimeSource;
 use crate::types::board::CapturedPieces;
@@ -604,6 +608,9 @@ impl ThreadLocalSearchContext {
     ) -> Option<i32> {
         let board = &mut self.board;
         let captured = &mut self.captured_pieces;
+        if mv.from.is_none() {
+            captured.remove_piece(mv.piece_type, player);
+        }
         if let Some(captured_piece) = board.make_move(mv) {
             captured.add_piece(captured_piece.piece_type, player);
         }
@@ -793,6 +800,15 @@ pub struct ParallelSearchEngine {
 
     /// Work distribution statistics.
     work_stats: Arc<WorkDistributionRecorder>,
+
+    /// Perspective of the scores in `info` lines.
+    score_perspective: EvaluationPerspective,
+
+    /// Append the win probability to `info` scores.
+    show_win_rate: bool,
+
+    /// How often `info` lines are sent and which fields they carry.
+    info_verbosity: InfoVerbosity,
 }
 
 impl ParallelSearchEngine {
@@ -846,6 +862,9 @@ impl ParallelSearchEngine {
             stop_flag: None,
             work_queues,
             work_stats: Arc::new(WorkDistributionRecorder::new(num_threads, metrics_mode)),
+            score_perspective: EvaluationPerspective::default(),
+            show_win_rate: false,
+            info_verbosity: InfoVerbosity::default(),
         })
     }
 
@@ -906,6 +925,9 @@ impl ParallelSearchEngine {
             stop_flag,
             work_queues,
             work_stats: Arc::new(WorkDistributionRecorder::new(num_threads, metrics_mode)),
+            score_perspective: EvaluationPerspective::default(),
+            show_win_rate: false,
+            info_verbosity: InfoVerbosity::default(),
         })
     }
 
@@ -919,6 +941,21 @@ impl ParallelSearchEngine {
         self.config.enable_parallel
     }
 
+    /// Report `info` scores from `perspective` instead of the side to move's.
+    pub fn set_score_perspective(&mut self, perspective: EvaluationPerspective) {
+        self.score_perspective = perspective;
+    }
+
+    /// Append `winrate` in permille to `info` scores.
+    pub fn set_show_win_rate(&mut self, show: bool) {
+        self.show_win_rate = show;
+    }
+
+    /// Throttle `info` output and choose its fields.
+    pub fn set_info_verbosity(&mut self, verbosity: InfoVerbosity) {
+        self.info_verbosity = verbosity;
+    }
+
     /// Create a thread-local search context for a worker thread.
     ///
     /// # Arguments
@@ -994,6 +1031,9 @@ impl ParallelSearchEngine {
             stop_flag,
             work_queues,
             work_stats: Arc::new(WorkDistributionRecorder::new(num_threads, metrics_mode)),
+            score_perspective: EvaluationPerspective::default(),
+            show_win_rate: false,
+            info_verbosity: InfoVerbosity::default(),
         })
     }
 
@@ -1077,8 +1117,17 @@ impl ParallelSearchEngine {
         let best_for_consumer = best_shared.clone();
 
         // Start consumer thread to stream info lines as results arrive
+        let score_perspective = self.score_perspective;
+        let show_win_rate = self.show_win_rate;
+        let verbosity = self.info_verbosity;
+        let thread_sink = info_sink::thread_info_sink();
+        let suppressed = info_sink::info_suppressed();
         let consumer = thread::spawn(move || {
+            // Report where the calling engine thread's lines go
+            info_sink::set_thread_info_sink(thread_sink);
+            info_sink::set_info_suppressed(suppressed);
             let mut best_pv = String::new();
+            let mut progress_throttle = verbosity.progress_throttle();
             while let Ok((mv, score, pv)) = rx.recv() {
                 // Update best-so-far
                 if let Ok(mut guard) = best_for_consumer.lock() {
@@ -1105,15 +1154,25 @@ impl ParallelSearchEngine {
                 };
                 // Emit real USI info line with score and PV (skip during silent benches)
                 if std::env::var("SHOGI_SILENT_BENCH").is_err() {
-                    if !best_pv.is_empty() {
-                        println!(
-                            "info depth {} seldepth {} multipv 1 score cp {} time {} nodes {} nps {} pv {}",
+                    if !best_pv.is_empty()
+                        && progress_throttle
+                            .as_mut()
+                            .is_some_and(|throttle| throttle.try_emit(nodes))
+                    {
+                        let line = format!(
+                            "info depth {} seldepth {} multipv 1 score {} time {} nodes {} nps {} pv {}",
                             depth, seldepth,
-                            if let Ok(g) = best_for_consumer.lock() { g.1 } else { score },
+                            format_info_score(
+                                score_perspective.orient(
+                                    if let Ok(g) = best_for_consumer.lock() { g.1 } else { score },
+                                    player,
+                                ),
+                                show_win_rate,
+                            ),
                             elapsed, nodes, nps, best_pv
                         );
+                        info_sink::emit_info(&verbosity.fields.filter(&line));
                     }
-                    let _ = std::io::Write::flush(&mut std::io::stdout());
                 }
             }
         });
@@ -1325,11 +1384,20 @@ impl ParallelSearchEngine {
                     .join(" ");
 
                 if !pv_string.is_empty() {
-                    println!(
-                        "info depth {} seldepth {} multipv 1 score cp {} time {} nodes {} nps {} pv {}",
-                        depth, seldepth_final, *best_score, elapsed, nodes, nps, pv_string
+                    let line = format!(
+                        "info depth {} seldepth {} multipv 1 score {} time {} nodes {} nps {} pv {}",
+                        depth,
+                        seldepth_final,
+                        format_info_score(
+                            self.score_perspective.orient(*best_score, player),
+                            self.show_win_rate
+                        ),
+                        elapsed,
+                        nodes,
+                        nps,
+                        pv_string
                     );
-                    let _ = std::io::Write::flush(&mut std::io::stdout());
+                    info_sink::emit_info(&self.info_verbosity.fields.filter(&line));
                 }
             }
         }
@@ -1472,11 +1540,8 @@ impl ParallelSearchEngine {
         for (idx, mv) in moves.iter().enumerate() {
             // Clone board and apply move
             let mut test_board = board.clone();
-            let mut test_captured = captured_pieces.clone();
-
-            if let Some(captured) = test_board.make_move(mv) {
-                test_captured.add_piece(captured.piece_type, player);
-            }
+            let (_, test_captured) =
+                make_move_with_hand(&mut test_board, captured_pieces, mv, player);
 
             let work_unit = WorkUnit {
                 board: test_board,
@@ -0,0 +1,75 @@
+//! Score Perspective
+//!
+//! The search scores positions for the side to move, which is also what the
+//! USI protocol specifies for `info ... score`. Graphs and commentary would
+//! rather have one fixed sign for the whole game, and flipping every other
+//! score on the GUI side goes wrong as soon as the side to move is guessed
+//! from anything but the position (handicap games, swapped players). The
+//! engine therefore reports scores in an explicit perspective and says which.
+
+use crate::types::core::Player;
+use serde::{Deserialize, Serialize};
+
+/// Whose point of view reported scores take
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvaluationPerspective {
+    /// Positive when the side to move is better (the USI convention)
+    #[default]
+    SideToMove,
+    /// Positive when sente (black) is better, whoever is to move
+    BlackPositive,
+}
+
+impl EvaluationPerspective {
+    /// Parse a `ScorePerspective` option value; case-insensitive
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "sidetomove" | "side_to_move" => Some(Self::SideToMove),
+            "blackpositive" | "black_positive" | "sentepositive" => Some(Self::BlackPositive),
+            _ => None,
+        }
+    }
+
+    /// Option value as advertised over USI
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::SideToMove => "SideToMove",
+            Self::BlackPositive => "BlackPositive",
+        }
+    }
+
+    /// Turn a search score for `side_to_move` into this perspective. Mate scores
+    /// stay mate scores; only their sign changes.
+    pub fn orient(self, score: i32, side_to_move: Player) -> i32 {
+        match (self, side_to_move) {
+            (Self::BlackPositive, Player::White) => -score,
+            _ => score,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::mate_score::{format_usi_score, mate_in};
+
+    #[test]
+    fn test_orient_and_parse() {
+        let side_to_move = EvaluationPerspective::SideToMove;
+        let black_positive = EvaluationPerspective::BlackPositive;
+        assert_eq!(side_to_move.orient(120, Player::White), 120);
+        assert_eq!(black_positive.orient(120, Player::Black), 120);
+        assert_eq!(black_positive.orient(120, Player::White), -120);
+        // Gote to move and mating in 3 is a mate against sente
+        assert_eq!(
+            format_usi_score(black_positive.orient(mate_in(3), Player::White)),
+            "mate -3"
+        );
+
+        assert_eq!(EvaluationPerspective::parse("BlackPositive"), Some(black_positive));
+        assert_eq!(EvaluationPerspective::parse("sidetomove"), Some(side_to_move));
+        assert_eq!(EvaluationPerspective::parse("gote"), None);
+        assert_eq!(EvaluationPerspective::parse(black_positive.as_str()), Some(black_positive));
+    }
+}
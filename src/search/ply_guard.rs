@@ -0,0 +1,126 @@
+//! Ply Guard
+//!
+//! The main search recurses once per ply, and check extensions and re-searches
+//! can take a line far past the nominal depth. On the native main thread that
+//! is harmless, but WASM and small worker stacks overflow long before the
+//! search would stop by itself. The guard counts plies as nodes are entered and
+//! left; a node past the limit is not searched and scores as its static
+//! evaluation, the same as a horizon node.
+//!
+//! The guard also records how deep searches actually go, in plies and in stack
+//! bytes, so the limit can be chosen from measurements rather than guessed.
+
+/// Default ply limit: far beyond any depth the search reaches in practice, so
+/// searches only stop on it when something has gone wrong
+pub const DEFAULT_MAX_PLY: u16 = 256;
+
+/// Lowest limit the engine accepts
+pub const MIN_MAX_PLY: u16 = 16;
+
+/// Counters since the engine started
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlyGuardStats {
+    /// Deepest ply any search entered
+    pub max_ply_reached: u16,
+    /// Most stack any search used below its first node, in bytes
+    pub max_stack_bytes: u64,
+    /// Nodes scored statically because they were past the limit
+    pub cutoffs: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct PlyGuard {
+    max_ply: u16,
+    ply: u16,
+    /// Stack address of the outermost node of the current search
+    stack_base: usize,
+    stats: PlyGuardStats,
+}
+
+impl Default for PlyGuard {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_PLY)
+    }
+}
+
+impl PlyGuard {
+    pub fn new(max_ply: u16) -> Self {
+        Self {
+            max_ply: max_ply.max(MIN_MAX_PLY),
+            ply: 0,
+            stack_base: 0,
+            stats: PlyGuardStats::default(),
+        }
+    }
+
+    pub fn max_ply(&self) -> u16 {
+        self.max_ply
+    }
+
+    pub fn set_max_ply(&mut self, max_ply: u16) {
+        self.max_ply = max_ply.max(MIN_MAX_PLY);
+    }
+
+    pub fn stats(&self) -> PlyGuardStats {
+        self.stats
+    }
+
+    /// Plies from the root to the node last entered; the root's children are ply 1
+    pub fn ply(&self) -> u16 {
+        self.ply
+    }
+
+    /// Enter a node; `false` when it is past the limit and must not be searched.
+    /// Every `true` must be matched by a `leave`.
+    #[inline(never)]
+    pub fn enter(&mut self) -> bool {
+        if self.ply >= self.max_ply {
+            self.stats.cutoffs += 1;
+            return false;
+        }
+        let marker = 0u8;
+        let address = std::ptr::addr_of!(marker) as usize;
+        if self.ply == 0 {
+            self.stack_base = address;
+        }
+        self.ply += 1;
+        self.stats.max_ply_reached = self.stats.max_ply_reached.max(self.ply);
+        let stack_bytes = self.stack_base.abs_diff(address) as u64;
+        self.stats.max_stack_bytes = self.stats.max_stack_bytes.max(stack_bytes);
+        true
+    }
+
+    pub fn leave(&mut self) {
+        self.ply = self.ply.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn descend(guard: &mut PlyGuard, plies: u16) -> u16 {
+        if plies == 0 || !guard.enter() {
+            return 0;
+        }
+        let reached = 1 + descend(guard, plies - 1);
+        guard.leave();
+        reached
+    }
+
+    #[test]
+    fn test_guard_stops_at_the_limit_and_records_depth() {
+        let mut guard = PlyGuard::new(20);
+        assert_eq!(descend(&mut guard, 12), 12);
+        assert_eq!(guard.stats().cutoffs, 0);
+
+        assert_eq!(descend(&mut guard, 50), 20);
+        let stats = guard.stats();
+        assert_eq!((stats.max_ply_reached, stats.cutoffs), (20, 1));
+        assert!(stats.max_stack_bytes > 0);
+
+        // Every node was left again, so the next search starts at the root
+        assert_eq!(descend(&mut guard, 5), 5);
+        assert_eq!(PlyGuard::new(1).max_ply(), MIN_MAX_PLY);
+    }
+}
@@ -0,0 +1,167 @@
+//! Repetition Module
+//!
+//! Sennichite (the same position four times) is normally a draw, but when one
+//! side gave check with every one of its moves during the repetition, that side
+//! loses. Telling the two apart needs more than position counts: each position
+//! on the path from the game start through the search also records whether the
+//! side to move was in check, i.e. whether the move into it was a check.
+//!
+//! The search treats the first repetition of a position on its path as the
+//! sennichite it would become, like most engines treat repetition draws. Only
+//! the perpetual check outcomes are scored here; plain repetitions keep the
+//! existing hash-count draw detection.
+
+use crate::bitboards::BitboardBoard;
+use crate::search::mate_score::MATE_THRESHOLD;
+use crate::search::shogi_hash::ShogiHashHandler;
+use crate::types::{CapturedPieces, Player};
+
+/// Score for winning by the opponent's perpetual check at the root. Decisive,
+/// but below the mate range so it is never reported as a mate.
+pub const PERPETUAL_CHECK_SCORE: i32 = MATE_THRESHOLD - 1_000;
+
+/// Score for the side to move losing by its own perpetual check `ply` plies from the root
+pub fn perpetual_check_loss(ply: u8) -> i32 {
+    -PERPETUAL_CHECK_SCORE + i32::from(ply)
+}
+
+/// Score for the side to move winning by the opponent's perpetual check `ply` plies from the root
+pub fn perpetual_check_win(ply: u8) -> i32 {
+    PERPETUAL_CHECK_SCORE - i32::from(ply)
+}
+
+/// A position on the game or search path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepetitionEntry {
+    /// Position hash, as the search engine computes it
+    pub hash: u64,
+    /// Whether the side to move is in check
+    pub in_check: bool,
+}
+
+impl RepetitionEntry {
+    pub fn new(
+        hash_handler: &ShogiHashHandler,
+        board: &BitboardBoard,
+        captured_pieces: &CapturedPieces,
+        player: Player,
+    ) -> Self {
+        Self {
+            hash: hash_handler.get_position_hash(board, player, captured_pieces),
+            in_check: board.is_king_in_check(player, captured_pieces),
+        }
+    }
+}
+
+/// How a repetition ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepetitionOutcome {
+    /// Plain sennichite
+    Draw,
+    /// The side to move checked with every move of the cycle and loses
+    PerpetualCheckByMover,
+    /// The opponent checked with every move of the cycle and loses
+    PerpetualCheckByOpponent,
+}
+
+/// Positions from the game start to the current search node
+#[derive(Debug, Clone, Default)]
+pub struct RepetitionPath {
+    entries: Vec<RepetitionEntry>,
+}
+
+impl RepetitionPath {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a new search from the positions played so far
+    pub fn reset(&mut self, game_history: &[RepetitionEntry]) {
+        self.entries.clear();
+        self.entries.extend_from_slice(game_history);
+    }
+
+    pub fn push(&mut self, entry: RepetitionEntry) {
+        self.entries.push(entry);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Drop the positions above `len`, e.g. when a search node returns
+    pub fn truncate(&mut self, len: usize) {
+        self.entries.truncate(len);
+    }
+
+    /// Outcome if `entry`, the position about to be searched, repeats one on
+    /// the path; `None` when it does not
+    pub fn classify(&self, entry: RepetitionEntry) -> Option<RepetitionOutcome> {
+        // The same side is to move only an even number of plies back, and a
+        // cycle takes at least four plies
+        let len = self.entries.len();
+        let start = (4..=len)
+            .step_by(2)
+            .map(|distance| len - distance)
+            .find(|&index| self.entries[index].hash == entry.hash)?;
+
+        // Walking back from `entry`: at even distances the mover is in check
+        // (the opponent's move checked), at odd distances the opponent is
+        let cycle = std::iter::once(entry).chain(self.entries[start + 1..].iter().rev().copied());
+        let mut opponent_always_checked = true;
+        let mut mover_always_checked = true;
+        for (distance, position) in cycle.enumerate() {
+            if distance % 2 == 0 {
+                opponent_always_checked &= position.in_check;
+            } else {
+                mover_always_checked &= position.in_check;
+            }
+        }
+
+        Some(match (mover_always_checked, opponent_always_checked) {
+            (true, false) => RepetitionOutcome::PerpetualCheckByMover,
+            (false, true) => RepetitionOutcome::PerpetualCheckByOpponent,
+            _ => RepetitionOutcome::Draw,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(hash: u64, in_check: bool) -> RepetitionEntry {
+        RepetitionEntry { hash, in_check }
+    }
+
+    #[test]
+    fn test_classify_perpetual_check_cycles() {
+        // Mover checks (opponent in check at odd distances), opponent evades
+        let mut path = RepetitionPath::new();
+        path.reset(&[entry(1, false), entry(2, true), entry(3, false), entry(4, true)]);
+        assert_eq!(
+            path.classify(entry(1, false)),
+            Some(RepetitionOutcome::PerpetualCheckByMover)
+        );
+
+        // The opponent checks every move instead
+        path.reset(&[entry(1, true), entry(2, false), entry(3, true), entry(4, false)]);
+        assert_eq!(
+            path.classify(entry(1, true)),
+            Some(RepetitionOutcome::PerpetualCheckByOpponent)
+        );
+
+        // One quiet move by the checking side makes it a plain repetition
+        path.reset(&[entry(1, false), entry(2, false), entry(3, false), entry(4, true)]);
+        assert_eq!(path.classify(entry(1, false)), Some(RepetitionOutcome::Draw));
+
+        // No earlier occurrence with the same side to move
+        assert_eq!(path.classify(entry(9, false)), None);
+        path.truncate(2);
+        assert_eq!(path.classify(entry(1, false)), None);
+    }
+}
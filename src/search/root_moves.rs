@@ -0,0 +1,246 @@
+//! Root Move List Module
+//!
+//! Root moves are generated and ordered once per search and carried across
+//! iterations together with their statistics. Later iterations search them best
+//! first (by the previous iteration's score) and use staged deepening: a move that
+//! has stayed clearly refuted for a few iterations is searched less deeply, and
+//! re-searched at full depth only if the reduced search says it might be best.
+//! Reductions are suspended while the best move is unstable.
+
+use crate::search::statistics::{RootMoveIteration, RootMoveReport};
+use crate::types::Move;
+
+/// A root move scoring this far below the best counts as refuted
+pub const REFUTATION_MARGIN_CP: i32 = 300;
+
+/// Consecutive refuted iterations before a root move is searched less deeply
+pub const REFUTED_ITERATIONS_BEFORE_REDUCTION: u8 = 2;
+
+/// Depth reduction applied to refuted root moves
+pub const STAGED_DEPTH_REDUCTION: u8 = 2;
+
+/// Iterations shallower than this are always searched at full depth
+pub const STAGED_MIN_DEPTH: u8 = 4;
+
+/// A best-score drop larger than this marks the best move as unstable
+pub const INSTABILITY_DROP_CP: i32 = 50;
+
+/// One root move and what the search has learned about it so far
+#[derive(Debug, Clone)]
+pub struct RootMove {
+    pub mv: Move,
+    pub nodes: u64,
+    pub history: Vec<RootMoveIteration>,
+    /// Consecutive completed iterations in which the move was refuted
+    pub refuted_iterations: u8,
+}
+
+impl RootMove {
+    fn last_score(&self) -> Option<i32> {
+        self.history.last().map(|iteration| iteration.score)
+    }
+}
+
+/// Root moves of the position being searched, kept across iterations
+#[derive(Debug, Clone)]
+pub struct RootMoveList {
+    position_hash: u64,
+    moves: Vec<RootMove>,
+    best: Option<(String, i32)>,
+    unstable: bool,
+}
+
+impl RootMoveList {
+    /// `moves` must already be in the preferred initial order
+    pub fn new(position_hash: u64, moves: Vec<Move>) -> Self {
+        Self {
+            position_hash,
+            moves: moves
+                .into_iter()
+                .map(|mv| RootMove {
+                    mv,
+                    nodes: 0,
+                    history: Vec::new(),
+                    refuted_iterations: 0,
+                })
+                .collect(),
+            best: None,
+            unstable: false,
+        }
+    }
+
+    /// Whether this list belongs to the position with `position_hash`
+    pub fn matches(&self, position_hash: u64) -> bool {
+        self.position_hash == position_hash
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Whether the best move changed or its score fell sharply in the last iteration
+    pub fn is_unstable(&self) -> bool {
+        self.unstable
+    }
+
+    /// Moves in search order: the previous best first, then by last score.
+    /// Unscored moves keep their initial order.
+    pub fn ordered_moves(&self) -> Vec<Move> {
+        let mut order: Vec<&RootMove> = self.moves.iter().collect();
+        order.sort_by_key(|root_move| std::cmp::Reverse(root_move.last_score().unwrap_or(i32::MIN)));
+        if let Some((best_usi, _)) = &self.best {
+            if let Some(index) = order.iter().position(|root_move| &root_move.mv.to_usi_string() == best_usi) {
+                let best = order.remove(index);
+                order.insert(0, best);
+            }
+        }
+        order.into_iter().map(|root_move| root_move.mv.clone()).collect()
+    }
+
+    /// Depth to search `mv` with in an iteration of nominal `depth`
+    pub fn search_depth(&self, mv: &Move, depth: u8) -> u8 {
+        if depth < STAGED_MIN_DEPTH || self.unstable {
+            return depth;
+        }
+        let refuted = self.find(mv).map_or(false, |root_move| {
+            root_move.refuted_iterations >= REFUTED_ITERATIONS_BEFORE_REDUCTION
+        });
+        if refuted {
+            depth - STAGED_DEPTH_REDUCTION
+        } else {
+            depth
+        }
+    }
+
+    /// Record the result of searching `mv` in the iteration of nominal `depth`
+    pub fn record(&mut self, mv: &Move, depth: u8, searched_depth: u8, score: i32, nodes: u64) {
+        let usi = mv.to_usi_string();
+        if let Some(root_move) = self.moves.iter_mut().find(|root_move| root_move.mv.to_usi_string() == usi) {
+            root_move.nodes += nodes;
+            root_move.history.retain(|iteration| iteration.depth != depth);
+            root_move.history.push(RootMoveIteration {
+                depth,
+                searched_depth,
+                score,
+            });
+        }
+    }
+
+    /// Close a fully searched iteration: update refutation counts and stability
+    pub fn finish_iteration(&mut self, depth: u8, best_move: &Move, best_score: i32) {
+        let best_usi = best_move.to_usi_string();
+        let refuted_below = best_score.saturating_sub(REFUTATION_MARGIN_CP);
+        for root_move in &mut self.moves {
+            let score = root_move
+                .history
+                .iter()
+                .find(|iteration| iteration.depth == depth)
+                .map(|iteration| iteration.score);
+            match score {
+                Some(score) if score < refuted_below => {
+                    root_move.refuted_iterations = root_move.refuted_iterations.saturating_add(1)
+                }
+                _ => root_move.refuted_iterations = 0,
+            }
+        }
+
+        self.unstable = match &self.best {
+            Some((previous_usi, previous_score)) => {
+                previous_usi != &best_usi || previous_score - best_score > INSTABILITY_DROP_CP
+            }
+            None => false,
+        };
+        self.best = Some((best_usi, best_score));
+    }
+
+    /// Per-root-move statistics for the analysis API
+    pub fn reports(&self) -> Vec<RootMoveReport> {
+        self.moves
+            .iter()
+            .map(|root_move| RootMoveReport {
+                move_usi: root_move.mv.to_usi_string(),
+                nodes: root_move.nodes,
+                score_history: root_move.history.clone(),
+                refuted_iterations: root_move.refuted_iterations,
+            })
+            .collect()
+    }
+
+    fn find(&self, mv: &Move) -> Option<&RootMove> {
+        let usi = mv.to_usi_string();
+        self.moves.iter().find(|root_move| root_move.mv.to_usi_string() == usi)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PieceType, Player, Position};
+
+    fn pawn_push(col: u8) -> Move {
+        Move::new_move(
+            Position::new(6, col),
+            Position::new(5, col),
+            PieceType::Pawn,
+            Player::Black,
+            false,
+        )
+    }
+
+    fn complete_iteration(list: &mut RootMoveList, depth: u8, scores: &[i32]) {
+        let moves: Vec<Move> = (0..scores.len() as u8).map(pawn_push).collect();
+        for (mv, score) in moves.iter().zip(scores) {
+            let searched_depth = list.search_depth(mv, depth);
+            list.record(mv, depth, searched_depth, *score, 10);
+        }
+        let (best_index, best_score) =
+            scores.iter().enumerate().max_by_key(|(_, score)| **score).unwrap();
+        list.finish_iteration(depth, &moves[best_index], *best_score);
+    }
+
+    #[test]
+    fn test_refuted_moves_are_reduced_after_stable_iterations() {
+        let mut list = RootMoveList::new(1, (0..3).map(pawn_push).collect());
+        for depth in 1..=4 {
+            complete_iteration(&mut list, depth, &[100, 50, -400]);
+        }
+        assert!(!list.is_unstable());
+        assert_eq!(list.search_depth(&pawn_push(0), 5), 5);
+        assert_eq!(list.search_depth(&pawn_push(1), 5), 5);
+        assert_eq!(list.search_depth(&pawn_push(2), 5), 5 - STAGED_DEPTH_REDUCTION);
+        // Shallow iterations are never reduced
+        assert_eq!(list.search_depth(&pawn_push(2), STAGED_MIN_DEPTH - 1), STAGED_MIN_DEPTH - 1);
+
+        let reports = list.reports();
+        assert_eq!(reports[2].nodes, 40);
+        assert_eq!(reports[2].score_history.len(), 4);
+    }
+
+    #[test]
+    fn test_unstable_best_move_suspends_reductions() {
+        let mut list = RootMoveList::new(1, (0..3).map(pawn_push).collect());
+        for depth in 1..=3 {
+            complete_iteration(&mut list, depth, &[100, 50, -400]);
+        }
+        complete_iteration(&mut list, 4, &[40, 60, -400]);
+        assert!(list.is_unstable());
+        assert_eq!(list.search_depth(&pawn_push(2), 5), 5);
+    }
+
+    #[test]
+    fn test_ordered_moves_puts_previous_best_first() {
+        let mut list = RootMoveList::new(1, (0..3).map(pawn_push).collect());
+        assert_eq!(list.ordered_moves(), (0..3).map(pawn_push).collect::<Vec<_>>());
+        complete_iteration(&mut list, 1, &[-20, 10, 30]);
+        assert_eq!(
+            list.ordered_moves(),
+            vec![pawn_push(2), pawn_push(1), pawn_push(0)]
+        );
+        assert!(list.matches(1));
+        assert!(!list.matches(2));
+    }
+}
@@ -0,0 +1,187 @@
+//! Root-Move Splitting for Analysis
+//!
+//! When every root move needs an exact score, as for MultiPV candidates, the
+//! root moves can be dealt out to worker threads that each own a
+//! `SearchEngine` and search their share one move at a time (`searchmoves` with
+//! a single move). The workers share nothing, so the speedup is close to the
+//! number of workers, at the cost of each move getting a full-width search of
+//! its own instead of a null-window test against the best. That trade only
+//! pays off in analysis, where time is not critical and all scores are wanted;
+//! play keeps the single search with its own parallelism.
+
+use crate::bitboards::BitboardBoard;
+use crate::search::info_sink::with_info_suppressed;
+use crate::search::search_engine::{make_move_with_hand, IterativeDeepening, SearchEngine};
+use crate::types::board::CapturedPieces;
+use crate::types::core::{Move, Player};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+/// Fewest root moves worth a worker of their own
+pub const MIN_MOVES_PER_WORKER: usize = 2;
+
+/// Shortest search any single root move is given
+const MIN_MOVE_TIME_MS: u32 = 20;
+
+/// Most hash a worker's engine is given. Each root move gets a short search and
+/// the table is dropped with the request, so a large one is never filled.
+pub const MAX_WORKER_HASH_MB: usize = 16;
+
+/// A root move with the result of its own search
+#[derive(Debug, Clone)]
+pub struct RootMoveResult {
+    pub mv: Move,
+    /// Score for the side to move
+    pub score: i32,
+    /// Deepest completed iteration
+    pub depth: u8,
+    /// Continuation, starting with the move itself
+    pub pv: Vec<Move>,
+}
+
+/// Workers to use for `move_count` root moves with at most `threads` threads
+pub fn worker_count(move_count: usize, threads: usize) -> usize {
+    (move_count / MIN_MOVES_PER_WORKER).clamp(1, threads.max(1))
+}
+
+/// Deal `moves` out round-robin to `workers` shares, so that when they are
+/// ordered best first each worker gets some of the promising ones
+pub fn split_moves(moves: &[Move], workers: usize) -> Vec<Vec<Move>> {
+    let workers = workers.max(1);
+    let mut shares = vec![Vec::new(); workers];
+    for (index, mv) in moves.iter().enumerate() {
+        shares[index % workers].push(mv.clone());
+    }
+    shares.retain(|share| !share.is_empty());
+    shares
+}
+
+/// Search every move of `moves` on its own, spread over one worker per engine in
+/// `engines`, within `time_limit_ms` per worker. Results are best first; moves
+/// whose search was stopped before finishing an iteration are left out.
+#[allow(clippy::too_many_arguments)]
+pub fn analyze(
+    engines: Vec<SearchEngine>,
+    board: &BitboardBoard,
+    captured_pieces: &CapturedPieces,
+    player: Player,
+    moves: &[Move],
+    max_depth: u8,
+    time_limit_ms: u32,
+    stop_flag: Option<Arc<AtomicBool>>,
+) -> Vec<RootMoveResult> {
+    let shares = split_moves(moves, engines.len());
+    let mut results: Vec<RootMoveResult> = std::thread::scope(|scope| {
+        let workers: Vec<_> = engines
+            .into_iter()
+            .zip(shares)
+            .map(|(mut engine, share)| {
+                let (board, captured_pieces) = (board.clone(), captured_pieces.clone());
+                let stop_flag = stop_flag.clone();
+                scope.spawn(move || {
+                    let move_time_ms =
+                        (time_limit_ms / share.len() as u32).max(MIN_MOVE_TIME_MS);
+                    share
+                        .iter()
+                        .filter_map(|mv| {
+                            // The caller's suppression does not reach this thread
+                            with_info_suppressed(|| {
+                                search_move(
+                                    &mut engine,
+                                    &board,
+                                    &captured_pieces,
+                                    player,
+                                    mv,
+                                    max_depth,
+                                    move_time_ms,
+                                    stop_flag.clone(),
+                                )
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .filter_map(|worker| worker.join().ok())
+            .flatten()
+            .collect()
+    });
+    results.sort_by_key(|result| std::cmp::Reverse(result.score));
+    results
+}
+
+#[allow(clippy::too_many_arguments)]
+fn search_move(
+    engine: &mut SearchEngine,
+    board: &BitboardBoard,
+    captured_pieces: &CapturedPieces,
+    player: Player,
+    mv: &Move,
+    max_depth: u8,
+    time_limit_ms: u32,
+    stop_flag: Option<Arc<AtomicBool>>,
+) -> Option<RootMoveResult> {
+    engine.set_search_moves(Some(vec![mv.to_usi_string()]));
+    let mut searcher = IterativeDeepening::new(max_depth, time_limit_ms, stop_flag);
+    let (best_move, score) = searcher.search(engine, board, captured_pieces, player)?;
+    let depth = engine.get_search_statistics().iteration_times_ms.len();
+
+    let mut next_board = board.clone();
+    let (_, next_captured) =
+        make_move_with_hand(&mut next_board, captured_pieces, &best_move, player);
+    let mut pv = vec![best_move.clone()];
+    pv.extend(engine.get_pv_for_reporting(
+        &next_board,
+        &next_captured,
+        player.opposite(),
+        max_depth.saturating_sub(1),
+    ));
+    Some(RootMoveResult {
+        mv: best_move,
+        score,
+        depth: u8::try_from(depth).unwrap_or(u8::MAX),
+        pv,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moves::MoveGenerator;
+
+    #[test]
+    fn test_split_moves_round_robin() {
+        let board = BitboardBoard::new();
+        let moves = MoveGenerator::new().generate_legal_moves(
+            &board,
+            Player::Black,
+            &CapturedPieces::new(),
+        );
+        assert_eq!(worker_count(moves.len(), 4), 4);
+        assert_eq!(worker_count(3, 8), 1);
+
+        let shares = split_moves(&moves, 4);
+        assert_eq!(shares.len(), 4);
+        assert_eq!(shares.iter().map(Vec::len).sum::<usize>(), moves.len());
+        assert_eq!(shares[1][0], moves[1]);
+        assert_eq!(split_moves(&moves[..2], 4).len(), 2);
+    }
+
+    #[test]
+    fn test_analyze_scores_every_move() {
+        let (board, player, captured) =
+            BitboardBoard::from_fen("4k4/9/9/9/9/9/9/9/4K4 b G 1").unwrap();
+        let moves: Vec<Move> = MoveGenerator::new()
+            .generate_legal_moves(&board, player, &captured)
+            .into_iter()
+            .take(6)
+            .collect();
+        let engines = (0..3).map(|_| SearchEngine::new(None, 1)).collect();
+        let results = analyze(engines, &board, &captured, player, &moves, 2, 600, None);
+        assert_eq!(results.len(), moves.len());
+        assert!(results.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        assert!(results.iter().all(|result| result.pv[0] == result.mv));
+    }
+}
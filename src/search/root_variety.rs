@@ -0,0 +1,138 @@
+//! Root Move Variety Module
+//!
+//! Controlled non-determinism at full strength: instead of always playing the best
+//! root move, pick among the root moves scoring within `margin_cp` of the best with
+//! softmax weights `exp((score - best) / temperature_cp)`. Moves far below the best
+//! are never played, so variety costs at most `margin_cp` per move.
+//!
+//! While variety is enabled the root is searched with alpha lowered by the margin,
+//! so every move inside the margin gets an exact score rather than an upper bound.
+
+use crate::search::mate_score::is_mate_score;
+use crate::types::Move;
+use rand::Rng;
+
+/// Default softmax temperature in centipawns
+pub const DEFAULT_TEMPERATURE_CP: i32 = 20;
+
+/// Root move selection settings (`RootVarietyMargin` / `RootVarietyTemperature` options)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RootVariety {
+    /// Candidate moves must score within this many centipawns of the best; 0 disables variety
+    pub margin_cp: i32,
+    /// Softmax temperature in centipawns; higher values spread the choice more evenly
+    pub temperature_cp: i32,
+}
+
+impl Default for RootVariety {
+    fn default() -> Self {
+        Self {
+            margin_cp: 0,
+            temperature_cp: DEFAULT_TEMPERATURE_CP,
+        }
+    }
+}
+
+impl RootVariety {
+    pub fn is_enabled(&self) -> bool {
+        self.margin_cp > 0
+    }
+
+    /// Root moves eligible for selection, with their scores
+    pub fn candidates<'a>(&self, root_scores: &'a [(Move, i32)]) -> Vec<&'a (Move, i32)> {
+        let Some(best) = root_scores.iter().map(|(_, score)| *score).max() else {
+            return Vec::new();
+        };
+        // Never gamble with a forced mate on the board, for either side
+        if is_mate_score(best) {
+            return root_scores.iter().filter(|(_, score)| *score == best).take(1).collect();
+        }
+        let floor = best.saturating_sub(self.margin_cp);
+        root_scores
+            .iter()
+            .filter(|(_, score)| *score >= floor && !is_mate_score(*score))
+            .collect()
+    }
+
+    /// Pick a root move by softmax over the candidates; `None` if there are no scores
+    pub fn select<R: Rng + ?Sized>(&self, root_scores: &[(Move, i32)], rng: &mut R) -> Option<Move> {
+        let candidates = self.candidates(root_scores);
+        let best = candidates.iter().map(|(_, score)| *score).max()?;
+        let temperature = f64::from(self.temperature_cp.max(1));
+        let weights: Vec<f64> = candidates
+            .iter()
+            .map(|(_, score)| (f64::from(score - best) / temperature).exp())
+            .collect();
+
+        let mut pick = rng.gen::<f64>() * weights.iter().sum::<f64>();
+        for ((mv, _), weight) in candidates.iter().zip(&weights) {
+            if pick < *weight {
+                return Some(mv.clone());
+            }
+            pick -= weight;
+        }
+        candidates.last().map(|(mv, _)| mv.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::mate_score::mate_in;
+    use crate::types::{PieceType, Player, Position};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn pawn_push(col: u8) -> Move {
+        Move::new_move(
+            Position::new(6, col),
+            Position::new(5, col),
+            PieceType::Pawn,
+            Player::Black,
+            false,
+        )
+    }
+
+    #[test]
+    fn test_candidates_within_margin() {
+        let variety = RootVariety {
+            margin_cp: 30,
+            ..RootVariety::default()
+        };
+        let scores = vec![(pawn_push(0), 100), (pawn_push(1), 75), (pawn_push(2), 60)];
+        let candidates = variety.candidates(&scores);
+        assert_eq!(candidates.len(), 2);
+        assert!(candidates.iter().all(|(_, score)| *score >= 70));
+    }
+
+    #[test]
+    fn test_select_only_plays_candidates() {
+        let variety = RootVariety {
+            margin_cp: 30,
+            temperature_cp: 20,
+        };
+        let scores = vec![(pawn_push(0), 100), (pawn_push(1), 90), (pawn_push(2), -200)];
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut picked = std::collections::HashSet::new();
+        for _ in 0..200 {
+            let mv = variety.select(&scores, &mut rng).unwrap();
+            assert_ne!(mv, pawn_push(2));
+            picked.insert(mv.to_usi_string());
+        }
+        assert_eq!(picked.len(), 2);
+    }
+
+    #[test]
+    fn test_mate_is_never_randomized() {
+        let variety = RootVariety {
+            margin_cp: 500,
+            temperature_cp: 500,
+        };
+        let scores = vec![(pawn_push(0), 100), (pawn_push(1), mate_in(3))];
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..20 {
+            assert_eq!(variety.select(&scores, &mut rng), Some(pawn_push(1)));
+        }
+        assert_eq!(variety.select(&[], &mut rng), None);
+    }
+}
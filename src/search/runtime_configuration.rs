@@ -195,7 +195,10 @@ impl RuntimeConfigurationManager {
 
         // Log warnings if any
         if !validation.warnings.is_empty() {
-            println!("Configuration warnings: {:?}", validation.warnings);
+            crate::utils::telemetry::debug_log(&format!(
+                "Configuration warnings: {:?}",
+                validation.warnings
+            ));
         }
 
         // Store current config in history
@@ -3,15 +3,32 @@ use crate::evaluation::pst_loader::{PieceSquareTableConfig, PieceSquareTablePres
 use crate::evaluation::*;
 use crate::moves::*;
 use crate::opening_book::OpeningBook;
+use crate::search::board_pool::{BoardPool, BoardPoolStats};
+use crate::search::drop_limiter::{DropLimiter, DropLimiterConfig};
+use crate::search::perspective::EvaluationPerspective;
+use crate::search::ply_guard::PlyGuard;
+use crate::search::repetition::{
+    perpetual_check_loss, perpetual_check_win, RepetitionEntry, RepetitionOutcome,
+    RepetitionPath,
+};
 use crate::search::move_ordering::MoveOrdering;
 use crate::search::tapered_search_integration::TaperedSearchEnhancer;
 use crate::search::{BoardTrait, ParallelSearchConfig, ParallelSearchEngine};
+use crate::search::info_verbosity::InfoVerbosity;
 use crate::search::iterative_deepening::IterativeDeepeningHelper;
+use crate::search::mate_score::{mate_distance_bounds, mated_in, score_from_tt, score_to_tt};
 use crate::search::null_move::NullMoveHelper;
 use crate::search::quiescence::QuiescenceHelper;
 use crate::search::reductions::ReductionsHelper;
+use crate::search::root_moves::{RootMoveList, INSTABILITY_DROP_CP};
+use crate::search::clock_contempt::{ClockContempt, ClockMode};
+use crate::search::coach::CoachConfig;
+use crate::search::root_variety::RootVariety;
+use crate::search::search_handle::{ProgressCallback, SearchProgress};
 use crate::search::statistics::SearchStatistics;
-use crate::search::time_management::TimeManager;
+use crate::search::time_management::{SearchInstability, TimeManager, INSTABILITY_MIN_DEPTH};
+use crate::search::tt_move_check::TtMoveCheck;
+use crate::search::win_probability::format_info_score;
 use crate::tablebase::MicroTablebase;
 use crate::utils::time::TimeSource;
 use crate::types::board::CapturedPieces;
@@ -42,7 +59,7 @@ use rayon::prelude::*;
 use std::collections::HashMap;
 use std::sync::{
     atomic::{AtomicBool, AtomicU64, Ordering},
-    Arc, RwLock,
+    Arc, Mutex, RwLock,
 };
 
 // Score constants to replace magic numbers (Task 5.5)
@@ -228,12 +245,30 @@ pub struct SearchEngine {
     evaluator: PositionEvaluator,
     move_generator: MoveGenerator,
     tablebase: MicroTablebase,
-    transposition_table: crate::search::ThreadSafeTranspositionTable,
+    transposition_table: Arc<crate::search::ThreadSafeTranspositionTable>,
     /// Optional shared transposition table for parallel search contexts
     shared_transposition_table: Option<Arc<RwLock<crate::search::ThreadSafeTranspositionTable>>>,
     hash_calculator: crate::search::ShogiHashHandler,
     move_orderer: crate::search::TranspositionMoveOrderer,
     advanced_move_orderer: MoveOrdering,
+    /// Reusable positions for the places that still copy the board
+    board_pool: BoardPool,
+    /// Positions from the game start to the current node, for perpetual check
+    repetition_path: RepetitionPath,
+    /// Positions played before the root, set by the caller
+    game_history: Vec<RepetitionEntry>,
+    /// Limits quiet drops under time pressure when the hand is large
+    drop_limiter: DropLimiter,
+    /// Screens TT best moves that hash collisions may have carried over
+    tt_move_check: TtMoveCheck,
+    /// Caps the recursion depth of the main search and measures it
+    ply_guard: PlyGuard,
+    /// Perspective of the scores in `info` lines
+    score_perspective: EvaluationPerspective,
+    /// Append the win probability to `info` scores
+    show_win_rate: bool,
+    /// How often `info` lines are sent and which fields they carry
+    info_verbosity: InfoVerbosity,
     quiescence_tt: HashMap<String, QuiescenceEntry>,
     quiescence_tt_age: u64, // Age counter for LRU tracking
     history_table: [[i32; 9]; 9],
@@ -332,11 +367,46 @@ pub struct SearchEngine {
     time_check_node_counter: u32,
     /// Nodes searched (cached for quick access)
     nodes_searched: u64,
+    /// Node budget for `go nodes` searches, checked alongside the time limit
+    node_limit: Option<u64>,
+    /// Cumulative node count when the node budget was set
+    node_limit_base: u64,
+    /// Root moves (USI notation) the search is restricted to by `go searchmoves`
+    search_moves: Option<Vec<String>>,
+    /// Root moves (USI notation) put first in the initial root ordering, such as
+    /// the shape book's plan out of book
+    preferred_root_moves: Vec<String>,
+    /// Root move variety settings (widened root window while enabled)
+    root_variety: RootVariety,
+    /// Coach mode settings (widened root window while enabled)
+    coach: CoachConfig,
+    /// Clock-aware play settings
+    clock_contempt: ClockContempt,
+    /// Bias of the current search from the clocks (widened root window unless neutral)
+    clock_mode: ClockMode,
+    /// Scores of every root move from the last fully searched iteration
+    root_move_scores: Vec<(Move, i32)>,
+    /// Root moves of the current iterative deepening search with their statistics
+    root_move_list: Option<RootMoveList>,
+    /// Beta cutoffs per ply from the root in the current search
+    cutoffs_by_ply: Vec<u64>,
+    /// Completion time of each iteration in the current search
+    iteration_times_ms: Vec<u32>,
+    /// Cumulative counters captured when the current search started
+    statistics_baseline: SearchStatisticsReport,
+    /// Receives a report after every completed iteration
+    progress_callback: Option<ProgressCallback>,
+    /// Copy of the search statistics refreshed after every iteration, readable
+    /// while a search holds the engine
+    published_statistics: Option<Arc<Mutex<SearchStatisticsReport>>>,
+    /// Current root move and hashfull, for this engine's `info` lines
+    live_progress: Arc<LiveProgress>,
 }
 
 // Global statistics are now in src/search/statistics.rs (Task 1.8)
 // Re-export for backward compatibility
 pub use crate::search::statistics::{GLOBAL_NODES_SEARCHED, GLOBAL_SELDEPTH};
+use crate::search::statistics::{InfoRateLimiter, LiveProgress, SearchStatisticsReport};
 // Global contention metrics for shared TT
 pub static TT_TRY_READS: AtomicU64 = AtomicU64::new(0);
 pub static TT_TRY_READ_SUCCESSES: AtomicU64 = AtomicU64::new(0);
@@ -423,6 +493,53 @@ pub fn print_and_reset_search_metrics(tag: &str) {
     let _ = std::io::Write::flush(&mut std::io::stdout());
 }
 
+/// Make `move_` for `player` and return what unmaking it needs with the hands
+/// after it: a dropped piece leaves the mover's hand and a captured one joins it
+pub(crate) fn make_move_with_hand(
+    board: &mut BitboardBoard,
+    captured_pieces: &CapturedPieces,
+    move_: &Move,
+    player: Player,
+) -> (MoveInfo, CapturedPieces) {
+    let before = crate::invariants::checks_enabled()
+        .then(|| crate::invariants::PieceCounts::of(board, captured_pieces));
+    let move_info = board.make_move_with_info(move_);
+    let mut new_captured = captured_pieces.clone();
+    if move_.from.is_none() {
+        new_captured.remove_piece(move_.piece_type, player);
+    }
+    if let Some(ref captured) = move_info.captured_piece {
+        new_captured.add_piece(captured.piece_type, player);
+    }
+    if let Some(before) = before {
+        let captured = move_info.captured_piece.as_ref().map(|piece| piece.piece_type);
+        let found = crate::invariants::PieceCounts::of(board, &new_captured);
+        if let Err(violation) = before.after_move(player, captured).expect(&found) {
+            crate::invariants::report(&format!("make {}", move_.to_usi_string()), &violation);
+        }
+    }
+    (move_info, new_captured)
+}
+
+/// Unmake a move made with `make_move_with_hand`, back to `captured_pieces`
+fn unmake_move_with_hand(
+    board: &mut BitboardBoard,
+    move_info: &MoveInfo,
+    new_captured: &CapturedPieces,
+    captured_pieces: &CapturedPieces,
+) {
+    let after = crate::invariants::checks_enabled()
+        .then(|| crate::invariants::PieceCounts::of(board, new_captured));
+    board.unmake_move(move_info);
+    if let Some(after) = after {
+        let captured = move_info.captured_piece.as_ref().map(|piece| piece.piece_type);
+        let found = crate::invariants::PieceCounts::of(board, captured_pieces);
+        if let Err(violation) = after.expect(&found.after_move(move_info.player, captured)) {
+            crate::invariants::report("unmake", &violation);
+        }
+    }
+}
+
 #[allow(dead_code)]
 // Conversion functions to convert between all:: config types and types::search:: config types
 // These are needed because EngineConfig uses all:: types but helper modules use types::search:: types
@@ -954,6 +1071,11 @@ fn convert_pruning_parameters(config: &crate::types::all::PruningParameters) ->
         position_dependent_margins: config.position_dependent_margins,
         late_move_pruning_enabled: config.late_move_pruning_enabled,
         late_move_pruning_move_threshold: config.late_move_pruning_move_threshold,
+        frontier_futility_enabled: config.frontier_futility_enabled,
+        frontier_futility_margins: config.frontier_futility_margins,
+        history_pruning_enabled: config.history_pruning_enabled,
+        history_pruning_depth_limit: config.history_pruning_depth_limit,
+        history_pruning_threshold: config.history_pruning_threshold,
     }
 }
 
@@ -1069,6 +1191,7 @@ impl SearchEngine {
         entry: TranspositionEntry,
         depth: u8,
         flag: TranspositionFlag,
+        verification_key: Option<u64>,
     ) {
         // Task 7.0.3.8-3.9: TT Entry Priority - Prevent auxiliary entries from overwriting deeper main entries
         // Check if we should skip storing this entry to preserve higher-quality entries
@@ -1127,7 +1250,7 @@ impl SearchEngine {
         
         // Automatic profiling for TT store (Task 26.0 - Task 3.0)
         let tt_store_start = if self.auto_profiling_enabled {
-            Some(std::time::Instant::now())
+            Some(crate::time_utils::Instant::now())
         } else {
             None
         };
@@ -1138,6 +1261,8 @@ impl SearchEngine {
             if self.tt_write_buffer.len() >= self.tt_write_buffer_capacity {
                 self.flush_tt_buffer();
             }
+        } else if let Some(key) = verification_key {
+            self.transposition_table.store_verified(entry, key);
         } else {
             self.transposition_table.store(entry);
         }
@@ -1168,11 +1293,20 @@ impl SearchEngine {
             evaluator: PositionEvaluator::new(),
             move_generator: MoveGenerator::new(),
             tablebase: MicroTablebase::new(),
-            transposition_table: crate::search::ThreadSafeTranspositionTable::new(config),
+            transposition_table: Arc::new(crate::search::ThreadSafeTranspositionTable::new(config)),
             shared_transposition_table: None,
             hash_calculator: crate::search::ShogiHashHandler::new(1000),
             move_orderer: crate::search::TranspositionMoveOrderer::new(),
             advanced_move_orderer: MoveOrdering::new(),
+            board_pool: BoardPool::default(),
+            repetition_path: RepetitionPath::new(),
+            game_history: Vec::new(),
+            drop_limiter: DropLimiter::default(),
+            tt_move_check: TtMoveCheck::default(),
+            ply_guard: PlyGuard::default(),
+            score_perspective: EvaluationPerspective::default(),
+            show_win_rate: false,
+            info_verbosity: InfoVerbosity::default(),
             quiescence_tt: HashMap::with_capacity(quiescence_capacity),
             quiescence_tt_age: 0,
             history_table: [[0; 9]; 9],
@@ -1259,6 +1393,22 @@ impl SearchEngine {
             time_budget_stats: TimeBudgetStats::default(),
             time_check_node_counter: 0,
             nodes_searched: 0,
+            node_limit: None,
+            node_limit_base: 0,
+            search_moves: None,
+            preferred_root_moves: Vec::new(),
+            root_variety: RootVariety::default(),
+            coach: CoachConfig::default(),
+            clock_contempt: ClockContempt::default(),
+            clock_mode: ClockMode::Neutral,
+            root_move_scores: Vec::new(),
+            root_move_list: None,
+            cutoffs_by_ply: Vec::new(),
+            iteration_times_ms: Vec::new(),
+            statistics_baseline: SearchStatisticsReport::default(),
+            progress_callback: None,
+            published_statistics: None,
+            live_progress: Arc::default(),
         };
         engine.parallel_options.hash_size_mb = hash_size_mb;
         if engine.debug_logging {
@@ -1270,10 +1420,12 @@ impl SearchEngine {
         engine
     }
 
-    /// Initialize the move orderer with the transposition table
+    /// Share the transposition table with both move orderers
     fn initialize_move_orderer(&mut self) {
         self.move_orderer
             .set_transposition_table(&self.transposition_table);
+        self.advanced_move_orderer
+            .set_transposition_table(&self.transposition_table);
     }
 
     /// Initialize advanced move ordering system
@@ -1337,6 +1489,364 @@ impl SearchEngine {
         self.search_statistics.get_nodes_searched()
     }
 
+    /// Limit the next search to a fixed number of nodes (`go nodes`), or clear the limit.
+    /// Unlike time limits, a node budget gives the same result regardless of machine speed.
+    pub fn set_node_limit(&mut self, node_limit: Option<u64>) {
+        self.node_limit = node_limit;
+        self.node_limit_base = self.search_statistics.get_core_metrics().total_nodes;
+    }
+
+    pub fn node_limit(&self) -> Option<u64> {
+        self.node_limit
+    }
+
+    /// Nodes searched since the node budget was set (across all iterations)
+    pub fn nodes_since_node_limit(&self) -> u64 {
+        self.search_statistics
+            .get_core_metrics()
+            .total_nodes
+            .saturating_sub(self.node_limit_base)
+    }
+
+    /// Whether the node budget, if any, has been used up
+    pub fn node_limit_reached(&self) -> bool {
+        self.node_limit
+            .map_or(false, |limit| self.nodes_since_node_limit() >= limit)
+    }
+
+    /// Restrict the root of the next searches to the given USI moves (`go searchmoves`),
+    /// or clear the restriction.
+    pub fn set_search_moves(&mut self, moves: Option<Vec<String>>) {
+        self.search_moves = moves.filter(|moves| !moves.is_empty());
+    }
+
+    pub fn search_moves(&self) -> Option<&[String]> {
+        self.search_moves.as_deref()
+    }
+
+    /// Search the given USI moves first at the root of the next searches; an empty
+    /// list leaves the move orderer's order alone
+    pub fn set_preferred_root_moves(&mut self, moves: Vec<String>) {
+        self.preferred_root_moves = moves;
+    }
+
+    /// Whether `move_` may be played at the root under the current `searchmoves` restriction
+    pub fn is_root_move_allowed(&self, move_: &Move) -> bool {
+        match &self.search_moves {
+            Some(allowed) => allowed.contains(&move_.to_usi_string()),
+            None => true,
+        }
+    }
+
+    pub fn set_root_variety(&mut self, root_variety: RootVariety) {
+        self.root_variety = root_variety;
+    }
+
+    pub fn root_variety(&self) -> RootVariety {
+        self.root_variety
+    }
+
+    pub fn set_coach(&mut self, coach: CoachConfig) {
+        self.coach = coach;
+    }
+
+    pub fn coach(&self) -> CoachConfig {
+        self.coach
+    }
+
+    pub fn set_clock_contempt(&mut self, clock_contempt: ClockContempt) {
+        self.clock_contempt = clock_contempt;
+    }
+
+    pub fn clock_contempt(&self) -> ClockContempt {
+        self.clock_contempt
+    }
+
+    /// Set the clocks' bias for the next search
+    pub fn set_clock_mode(&mut self, clock_mode: ClockMode) {
+        self.clock_mode = clock_mode;
+    }
+
+    pub fn clock_mode(&self) -> ClockMode {
+        self.clock_mode
+    }
+
+    /// How far below alpha later root moves are searched so that every move within
+    /// the margin gets an exact score; 0 while neither root variety, coach mode nor
+    /// a clock bias is on
+    pub fn root_score_margin(&self) -> i32 {
+        let variety = if self.root_variety.is_enabled() {
+            self.root_variety.margin_cp
+        } else {
+            0
+        };
+        let coach = if self.coach.is_enabled() {
+            self.coach.max_loss_cp
+        } else {
+            0
+        };
+        let clock = if self.clock_mode != ClockMode::Neutral {
+            self.clock_contempt.margin_cp
+        } else {
+            0
+        };
+        variety.max(coach).max(clock)
+    }
+
+    /// Root moves and scores of the last iteration that searched every root move.
+    /// Only filled while `root_score_margin` is above 0.
+    pub fn root_move_scores(&self) -> &[(Move, i32)] {
+        &self.root_move_scores
+    }
+
+    /// Order the root moves once for a new iterative deepening search. Later
+    /// `search_at_depth` calls on the same position reuse (and reorder) this list.
+    pub fn begin_root_moves(
+        &mut self,
+        board: &BitboardBoard,
+        captured_pieces: &CapturedPieces,
+        player: Player,
+        legal_moves: &[Move],
+    ) {
+        self.initialize_move_orderer();
+        let mut ordered = self.order_moves_for_negamax(
+            legal_moves,
+            board,
+            captured_pieces,
+            player,
+            1,
+            MIN_SCORE,
+            MAX_SCORE,
+            None,
+            None,
+        );
+        if !self.preferred_root_moves.is_empty() {
+            // Stable, so the preferred moves keep the orderer's order among themselves
+            ordered.sort_by_key(|mv| !self.preferred_root_moves.contains(&mv.to_usi_string()));
+        }
+        let position_hash = self
+            .hash_calculator
+            .get_position_hash(board, player, captured_pieces);
+        self.root_move_list = Some(RootMoveList::new(position_hash, ordered));
+    }
+
+    pub fn root_move_list(&self) -> Option<&RootMoveList> {
+        self.root_move_list.as_ref()
+    }
+
+    /// Keep only the root moves allowed by `searchmoves`. If none of the listed moves
+    /// is legal here the restriction is ignored rather than leaving nothing to play.
+    pub fn filter_root_moves(&self, moves: Vec<Move>) -> Vec<Move> {
+        if self.search_moves.is_none() {
+            return moves;
+        }
+        let allowed: Vec<Move> = moves
+            .iter()
+            .filter(|move_| self.is_root_move_allowed(move_))
+            .cloned()
+            .collect();
+        if allowed.is_empty() {
+            moves
+        } else {
+            allowed
+        }
+    }
+
+    /// Cumulative counters behind the search statistics report
+    fn statistics_counters(&self) -> SearchStatisticsReport {
+        SearchStatisticsReport {
+            nodes: self.core_search_metrics.total_nodes,
+            qnodes: self.quiescence_stats.nodes_searched,
+            tt_probes: self.core_search_metrics.total_tt_probes,
+            tt_hits: self.core_search_metrics.total_tt_hits,
+            beta_cutoffs: self.core_search_metrics.beta_cutoffs,
+            null_move_attempts: self.null_move_stats.attempts,
+            null_move_cutoffs: self.null_move_stats.cutoffs,
+            lmr_reductions: self.lmr_stats.reductions_applied,
+            lmr_researches: self.lmr_stats.researches_triggered,
+            drop_limited_nodes: self.drop_limiter.stats().nodes_limited,
+            drops_pruned: self.drop_limiter.stats().drops_pruned,
+            drop_limiter_fallbacks: self.drop_limiter.stats().fallbacks,
+            tt_moves_checked: self.tt_move_check.stats().checked,
+            tt_moves_rejected: self.tt_move_check.stats().rejected(),
+            max_ply_reached: self.ply_guard.stats().max_ply_reached,
+            max_stack_bytes: self.ply_guard.stats().max_stack_bytes,
+            ply_limit_cutoffs: self.ply_guard.stats().cutoffs,
+            ..SearchStatisticsReport::default()
+        }
+    }
+
+    /// Start collecting statistics for a new search
+    pub fn begin_search_statistics(&mut self) {
+        self.statistics_baseline = self.statistics_counters();
+        self.cutoffs_by_ply.clear();
+        self.iteration_times_ms.clear();
+        self.publish_search_statistics();
+    }
+
+    /// Statistics of the most recent (or current) search
+    pub fn get_search_statistics(&self) -> SearchStatisticsReport {
+        let mut current = self.statistics_counters();
+        current.cutoffs_per_ply = self.cutoffs_by_ply.clone();
+        current.iteration_times_ms = self.iteration_times_ms.clone();
+        current.hashfull = self.hashfull();
+        current.root_moves = self
+            .root_move_list
+            .as_ref()
+            .map(|root_moves| root_moves.reports())
+            .unwrap_or_default();
+        current.since(&self.statistics_baseline)
+    }
+
+    /// Move ordering, transposition table and tablebase counters plus the last
+    /// search report, for the statistics hub
+    pub fn statistics_snapshot(&self) -> crate::statistics_hub::StatisticsSnapshot {
+        use crate::statistics_hub::{
+            MagicCounters, MoveOrderingCounters, StatisticsSnapshot, TablebaseCounters,
+            TranspositionCounters,
+        };
+        let ordering = self.advanced_move_orderer.get_stats();
+        let tt = self.transposition_table.get_stats();
+        let key_verification = self.transposition_table.key_verification_stats();
+        StatisticsSnapshot {
+            search: self.get_search_statistics(),
+            move_ordering: MoveOrderingCounters {
+                moves_ordered: ordering.total_moves_ordered,
+                cache_hits: ordering.cache_hits,
+                cache_misses: ordering.cache_misses,
+                pv_move_hits: ordering.pv_move_hits,
+                killer_move_hits: ordering.killer_move_hits,
+                counter_move_hits: ordering.counter_move_hits,
+                see_calculations: ordering.see_calculations,
+            },
+            transposition_table: TranspositionCounters {
+                probes: tt.total_probes,
+                hits: tt.hits,
+                stores: tt.stores,
+                replacements: tt.replacements,
+                hashfull: self.hashfull(),
+                size: self.transposition_table.size(),
+                verified_probes: key_verification.verified_probes,
+                key_collisions: key_verification.collisions,
+            },
+            tablebase: TablebaseCounters::from(self.tablebase.get_stats()),
+            magic: MagicCounters::current(),
+            assets: self.tablebase.asset_stats(),
+        }
+    }
+
+    /// Install (or clear) the callback receiving per-iteration progress reports
+    pub fn set_progress_callback(&mut self, callback: Option<ProgressCallback>) {
+        self.progress_callback = callback;
+    }
+
+    /// Share a copy of the search statistics that is refreshed when a search
+    /// starts and after every iteration
+    pub fn set_statistics_publisher(
+        &mut self,
+        publisher: Option<Arc<Mutex<SearchStatisticsReport>>>,
+    ) {
+        self.published_statistics = publisher;
+        self.publish_search_statistics();
+    }
+
+    fn publish_search_statistics(&self) {
+        let Some(publisher) = &self.published_statistics else {
+            return;
+        };
+        let report = self.get_search_statistics();
+        if let Ok(mut published) = publisher.lock() {
+            *published = report;
+        }
+    }
+
+    /// Put a result known from an earlier search back into the transposition
+    /// table, unless the table already holds one at least as deep. Returns
+    /// whether the entry was stored.
+    pub fn seed_tt_entry(
+        &mut self,
+        board: &BitboardBoard,
+        captured_pieces: &CapturedPieces,
+        player: Player,
+        best_move: Move,
+        score: i32,
+        depth: u8,
+    ) -> bool {
+        let position_hash = self
+            .hash_calculator
+            .get_position_hash(board, player, captured_pieces);
+        if self.transposition_table.probe(position_hash, depth).is_some() {
+            return false;
+        }
+        let entry = TranspositionEntry::new(
+            score,
+            depth,
+            TranspositionFlag::Exact,
+            Some(best_move),
+            position_hash,
+            0,
+            crate::types::EntrySource::MainSearch,
+        );
+        match self.tt_verification_key(board, player, captured_pieces) {
+            Some(key) => self.transposition_table.store_verified(entry, key),
+            None => self.transposition_table.store(entry),
+        }
+        true
+    }
+
+    /// Hash the search keys this position by, for caches kept outside the search
+    pub fn position_hash(
+        &self,
+        board: &BitboardBoard,
+        captured_pieces: &CapturedPieces,
+        player: Player,
+    ) -> u64 {
+        self.hash_calculator
+            .get_position_hash(board, player, captured_pieces)
+    }
+
+    fn report_progress(&self, progress: SearchProgress) {
+        if let Some(callback) = &self.progress_callback {
+            callback(&progress);
+        }
+    }
+
+    fn record_cutoff_at_ply(&mut self, ply: u8) {
+        let ply = ply as usize;
+        if self.cutoffs_by_ply.len() <= ply {
+            self.cutoffs_by_ply.resize(ply + 1, 0);
+        }
+        self.cutoffs_by_ply[ply] += 1;
+    }
+
+    /// Store a second, independent key with each local TT entry and check it on
+    /// probes, counting the hits whose Zobrist key collided
+    pub fn set_tt_key_verification(&mut self, enabled: bool) {
+        self.transposition_table.set_key_verification(enabled);
+    }
+
+    /// The verification key for this position, when verification is on
+    fn tt_verification_key(
+        &self,
+        board: &BitboardBoard,
+        player: Player,
+        captured_pieces: &CapturedPieces,
+    ) -> Option<u64> {
+        self.transposition_table
+            .key_verification_enabled()
+            .then(|| crate::search::zobrist::verification_key(board, player, captured_pieces))
+    }
+
+    /// Transposition table occupancy in permille (prefers the shared TT when attached)
+    pub fn hashfull(&self) -> u32 {
+        if let Some(ref shared_tt) = self.shared_transposition_table {
+            if let Ok(guard) = shared_tt.try_read() {
+                return guard.hashfull();
+            }
+        }
+        self.transposition_table.hashfull()
+    }
+
     /// Set a shared transposition table for reporting and ordering in parallel contexts.
     pub fn set_shared_transposition_table(
         &mut self,
@@ -1360,10 +1870,8 @@ impl SearchEngine {
             .iter()
             .filter(|m| {
                 let mut test_board = board.clone();
-                let mut test_captured = captured_pieces.clone();
-                if let Some(captured) = test_board.make_move(m) {
-                    test_captured.add_piece(captured.piece_type, player);
-                }
+                let (_, test_captured) =
+                    make_move_with_hand(&mut test_board, captured_pieces, m, player);
                 test_board.is_king_in_check(player.opposite(), &test_captured)
             })
             .count();
@@ -1438,7 +1946,7 @@ impl SearchEngine {
         
         // Automatic profiling integration (Task 26.0 - Task 3.0)
         let start_time = if self.auto_profiling_enabled {
-            Some(std::time::Instant::now())
+            Some(crate::time_utils::Instant::now())
         } else {
             None
         };
@@ -1455,23 +1963,8 @@ impl SearchEngine {
             iid_move,
             opponent_last_move,
         ) {
-            Ok(ordered_moves) => {
-                // Task 6.2: If we have a TT hit, the ordering might already be cached
-                // Update PV move if we have a best move from transposition table
-                if let Some(best_move) =
-                    self.get_best_move_from_tt(board, captured_pieces, player, depth)
-                {
-                    self.advanced_move_orderer.update_pv_move(
-                        board,
-                        captured_pieces,
-                        player,
-                        depth,
-                        best_move,
-                        0,
-                    );
-                }
-                ordered_moves
-            }
+            // The advanced orderer reads the PV move from the shared table itself
+            Ok(ordered_moves) => ordered_moves,
             Err(_) => {
                 // Task 3.0: Fallback to traditional move ordering with IID move
                 // Task 6.4: Pass depth, alpha, beta for state-aware ordering
@@ -1596,7 +2089,7 @@ impl SearchEngine {
             .get_position_hash(board, player, captured_pieces);
 
         // Probe transposition table for best move
-        if let Some(entry) =
+        let best_move = if let Some(entry) =
             self.transposition_table
                 .probe_with_prefetch(position_hash, depth, None)
         {
@@ -1611,7 +2104,8 @@ impl SearchEngine {
             } else {
                 None
             }
-        }
+        };
+        self.tt_move_check.filter(board, captured_pieces, player, best_move)
     }
 
     /// Update move orderer with history
@@ -1636,11 +2130,20 @@ impl SearchEngine {
             evaluator: PositionEvaluator::new(),
             move_generator: MoveGenerator::new(),
             tablebase: MicroTablebase::new(),
-            transposition_table: crate::search::ThreadSafeTranspositionTable::new(tt_config),
+            transposition_table: Arc::new(crate::search::ThreadSafeTranspositionTable::new(tt_config)),
             shared_transposition_table: None,
             hash_calculator: crate::search::ShogiHashHandler::new(1000),
             move_orderer: crate::search::TranspositionMoveOrderer::new(),
             advanced_move_orderer: MoveOrdering::new(),
+            board_pool: BoardPool::default(),
+            repetition_path: RepetitionPath::new(),
+            game_history: Vec::new(),
+            drop_limiter: DropLimiter::default(),
+            tt_move_check: TtMoveCheck::default(),
+            ply_guard: PlyGuard::default(),
+            score_perspective: EvaluationPerspective::default(),
+            show_win_rate: false,
+            info_verbosity: InfoVerbosity::default(),
             quiescence_tt: HashMap::with_capacity(quiescence_capacity),
             quiescence_tt_age: 0,
             history_table: [[0; 9]; 9],
@@ -1728,6 +2231,22 @@ impl SearchEngine {
             time_budget_stats: TimeBudgetStats::default(),
             time_check_node_counter: 0,
             nodes_searched: 0,
+            node_limit: None,
+            node_limit_base: 0,
+            search_moves: None,
+            preferred_root_moves: Vec::new(),
+            root_variety: RootVariety::default(),
+            coach: CoachConfig::default(),
+            clock_contempt: ClockContempt::default(),
+            clock_mode: ClockMode::Neutral,
+            root_move_scores: Vec::new(),
+            root_move_list: None,
+            cutoffs_by_ply: Vec::new(),
+            iteration_times_ms: Vec::new(),
+            statistics_baseline: SearchStatisticsReport::default(),
+            progress_callback: None,
+            published_statistics: None,
+            live_progress: Arc::default(),
         };
         if engine.debug_logging {
             engine.evaluator.enable_integrated_statistics();
@@ -2385,12 +2904,8 @@ impl SearchEngine {
             }
 
             // Use move unmaking instead of board cloning
-            let move_info = board.make_move_with_info(move_);
-            let mut new_captured = captured_pieces.clone();
-
-            if let Some(ref captured) = move_info.captured_piece {
-                new_captured.add_piece(captured.piece_type, player);
-            }
+            let (move_info, new_captured) =
+                make_move_with_hand(board, captured_pieces, move_, player);
 
             // Shallow search for this move with null window for efficiency
             // Task 2.6: Pass None for opponent_last_move in IID search (not applicable)
@@ -2414,7 +2929,7 @@ impl SearchEngine {
             );
 
             // Restore board state by unmaking the move
-            board.unmake_move(&move_info);
+            unmake_move_with_hand(board, &move_info, &new_captured, captured_pieces);
 
             if score > best_score_tracked {
                 best_score_tracked = score;
@@ -3252,12 +3767,8 @@ impl SearchEngine {
             }
 
             // Use move unmaking instead of board cloning
-            let move_info = board.make_move_with_info(&move_);
-            let mut new_captured = captured_pieces.clone();
-
-            if let Some(ref captured) = move_info.captured_piece {
-                new_captured.add_piece(captured.piece_type, player);
-            }
+            let (move_info, new_captured) =
+                make_move_with_hand(board, captured_pieces, &move_, player);
 
             // Recursive search with reduced depth
             // Task 7.0.3.6: Tag as IID entry
@@ -3280,7 +3791,7 @@ impl SearchEngine {
             );
 
             // Restore board state by unmaking the move
-            board.unmake_move(&move_info);
+            unmake_move_with_hand(board, &move_info, &new_captured, captured_pieces);
 
             if score > best_score {
                 best_score = score;
@@ -3803,12 +4314,8 @@ impl SearchEngine {
                 }
 
                 // Use move unmaking instead of board cloning
-                let move_info = board.make_move_with_info(move_);
-                let mut new_captured = captured_pieces.clone();
-
-                if let Some(ref captured) = move_info.captured_piece {
-                    new_captured.add_piece(captured.piece_type, player);
-                }
+                let (move_info, new_captured) =
+                    make_move_with_hand(board, captured_pieces, move_, player);
 
                 // Use aspiration window for this PV
                 let window_size = if pv_index == 0 { 50 } else { 25 }; // Smaller window for secondary PVs
@@ -3836,7 +4343,7 @@ impl SearchEngine {
                 );
 
                 // Restore board state by unmaking the move
-                board.unmake_move(&move_info);
+                unmake_move_with_hand(board, &move_info, &new_captured, captured_pieces);
 
                 if score > best_score {
                     best_score = score;
@@ -4246,12 +4753,8 @@ impl SearchEngine {
             }
 
             // Use move unmaking instead of board cloning
-            let move_info = board.make_move_with_info(move_);
-            let mut new_captured = captured_pieces.clone();
-
-            if let Some(ref captured) = move_info.captured_piece {
-                new_captured.add_piece(captured.piece_type, player);
-            }
+            let (move_info, new_captured) =
+                make_move_with_hand(board, captured_pieces, move_, player);
 
             // Shallow search to evaluate move potential
             // Task 7.0.3.6: Tag as IID entry
@@ -4274,7 +4777,7 @@ impl SearchEngine {
             );
 
             // Restore board state by unmaking the move
-            board.unmake_move(&move_info);
+            unmake_move_with_hand(board, &move_info, &new_captured, captured_pieces);
 
             // Check if move is promising enough for deeper probing
             if score > current_alpha + promising_threshold {
@@ -4325,12 +4828,9 @@ impl SearchEngine {
             }
 
             // Use move unmaking instead of board cloning
-            let move_info = board.make_move_with_info(&convert_move_from_all(&promising_move.move_));
-            let mut new_captured = captured_pieces.clone();
-
-            if let Some(ref captured) = move_info.captured_piece {
-                new_captured.add_piece(captured.piece_type, player);
-            }
+            let move_ = convert_move_from_all(&promising_move.move_);
+            let (move_info, new_captured) =
+                make_move_with_hand(board, captured_pieces, &move_, player);
 
             // Deeper search for verification
             // Task 7.0.3.6: Tag as IID entry
@@ -4353,7 +4853,7 @@ impl SearchEngine {
             );
 
             // Restore board state by unmaking the move
-            board.unmake_move(&move_info);
+            unmake_move_with_hand(board, &move_info, &new_captured, captured_pieces);
 
             // Calculate verification metrics
             let score_difference = (deep_score - promising_move.shallow_score).abs();
@@ -5181,7 +5681,11 @@ impl SearchEngine {
         crate::debug_utils::start_timing("tablebase_probe");
         if let Some(tablebase_result) = self.tablebase.probe(board, player, captured_pieces) {
             crate::debug_utils::end_timing("tablebase_probe", "SEARCH_AT_DEPTH");
-            if let Some(ref best_move) = tablebase_result.best_move {
+            if let Some(best_move) = tablebase_result
+                .best_move
+                .as_ref()
+                .filter(|best_move| self.is_root_move_allowed(best_move))
+            {
                 crate::debug_utils::log_decision(
                     "SEARCH_AT_DEPTH",
                     "Tablebase hit",
@@ -5223,6 +5727,8 @@ impl SearchEngine {
         self.search_statistics.reset_nodes();
         self.current_depth = depth;
         let start_time = TimeSource::now();
+        // Bound of the stored result; `alpha` itself rises as moves improve on it
+        let original_alpha = alpha;
         let mut alpha = alpha;
 
         let mut best_move: Option<Move> = None;
@@ -5232,9 +5738,24 @@ impl SearchEngine {
 
         crate::utils::telemetry::trace_log("SEARCH_AT_DEPTH", "Generating legal moves");
         crate::debug_utils::start_timing("move_generation");
-        let legal_moves = self
-            .move_generator
-            .generate_legal_moves(board, player, captured_pieces);
+        // Within an iterative deepening search the root moves were generated and
+        // ordered once; reuse them in the order of the previous iteration's scores
+        let root_hash = self
+            .hash_calculator
+            .get_position_hash(board, player, captured_pieces);
+        let staged_moves = self
+            .root_move_list
+            .as_ref()
+            .filter(|root_moves| root_moves.matches(root_hash) && !root_moves.is_empty())
+            .map(|root_moves| root_moves.ordered_moves());
+        let staged = staged_moves.is_some();
+        let legal_moves = match staged_moves {
+            Some(moves) => moves,
+            None => self.filter_root_moves(
+                self.move_generator
+                    .generate_legal_moves(board, player, captured_pieces),
+            ),
+        };
         crate::debug_utils::end_timing("move_generation", "SEARCH_AT_DEPTH");
 
         if legal_moves.is_empty() {
@@ -5285,17 +5806,21 @@ impl SearchEngine {
         self.initialize_move_orderer();
 
         // Task 3.0: Use advanced move ordering for better performance (no IID at this level)
-        let sorted_moves = self.order_moves_for_negamax(
-            &legal_moves,
-            board,
-            captured_pieces,
-            player,
-            depth,
-            alpha,
-            beta,
-            None,
-            None,
-        );
+        let sorted_moves = if staged {
+            legal_moves.clone()
+        } else {
+            self.order_moves_for_negamax(
+                &legal_moves,
+                board,
+                captured_pieces,
+                player,
+                depth,
+                alpha,
+                beta,
+                None,
+                None,
+            )
+        };
         crate::debug_utils::end_timing("move_sorting", "SEARCH_AT_DEPTH");
 
         crate::utils::telemetry::trace_log("SEARCH_AT_DEPTH", "Starting move evaluation loop");
@@ -5312,10 +5837,15 @@ impl SearchEngine {
         );
 
         // Use hash-based history instead of FEN strings (Task 5.1-5.2)
-        let mut hash_history: Vec<u64> =
-            vec![self
-                .hash_calculator
-                .get_position_hash(board, player, captured_pieces)];
+        let mut hash_history: Vec<u64> = vec![root_hash];
+        self.repetition_path.reset(&self.game_history);
+        self.repetition_path.push(RepetitionEntry {
+            hash: root_hash,
+            in_check: board.is_king_in_check(player, captured_pieces),
+        });
+        let mut root_scores: Vec<(Move, i32)> = Vec::new();
+        let mut searched_moves = 0;
+        let root_score_margin = self.root_score_margin();
 
         for (move_index, move_) in sorted_moves.iter().enumerate() {
             if self.should_stop(&start_time, time_limit_ms) {
@@ -5326,6 +5856,10 @@ impl SearchEngine {
                 break;
             }
 
+            // Live progress for `info currmove`/`hashfull` (read by the info sender thread)
+            self.live_progress.publish_currmove(move_.to_usi_string(), move_index + 1);
+            self.live_progress.publish_hashfull(self.hashfull());
+
             crate::utils::telemetry::trace_log(
                 "SEARCH_AT_DEPTH",
                 &format!(
@@ -5343,29 +5877,69 @@ impl SearchEngine {
             crate::debug_utils::start_timing(&format!("move_eval_{}", move_index));
 
             // Use move unmaking instead of board cloning
-            let move_info = board.make_move_with_info(&move_);
-            let mut new_captured = captured_pieces.clone();
-
-            if let Some(ref captured) = move_info.captured_piece {
-                new_captured.add_piece(captured.piece_type, player);
-            }
+            let (move_info, new_captured) =
+                make_move_with_hand(board, captured_pieces, &move_, player);
 
-            let score = -self.negamax(
+            // With root variety or coach mode on, later moves are searched with alpha
+            // lowered by the margin so that every move close to the best gets an exact score
+            let window_alpha = if root_score_margin > 0 && move_index > 0 {
+                alpha.saturating_sub(root_score_margin)
+            } else {
+                alpha
+            };
+            // Staged deepening: clearly refuted moves get a shallower search
+            let mut searched_depth = match &self.root_move_list {
+                Some(root_moves) if staged && move_index > 0 => {
+                    root_moves.search_depth(move_, depth)
+                }
+                _ => depth,
+            };
+            let nodes_before = self.search_statistics.get_nodes_searched();
+            let mut score = -self.negamax(
                 &mut *board,
                 &new_captured,
                 player.opposite(),
-                depth - 1,
+                searched_depth - 1,
                 beta.saturating_neg(),
-                alpha.saturating_neg(),
+                window_alpha.saturating_neg(),
                 &start_time,
                 time_limit_ms,
                 &mut hash_history,
                 true,
             );
+            if searched_depth < depth && score > window_alpha {
+                // The reduced search says the move may be best after all
+                searched_depth = depth;
+                score = -self.negamax(
+                    &mut *board,
+                    &new_captured,
+                    player.opposite(),
+                    depth - 1,
+                    beta.saturating_neg(),
+                    window_alpha.saturating_neg(),
+                    &start_time,
+                    time_limit_ms,
+                    &mut hash_history,
+                    true,
+                );
+            }
             crate::debug_utils::end_timing(&format!("move_eval_{}", move_index), "SEARCH_AT_DEPTH");
+            searched_moves += 1;
+            if root_score_margin > 0 {
+                root_scores.push((move_.clone(), score));
+            }
+            if staged {
+                let nodes = self
+                    .search_statistics
+                    .get_nodes_searched()
+                    .saturating_sub(nodes_before);
+                if let Some(root_moves) = self.root_move_list.as_mut() {
+                    root_moves.record(move_, depth, searched_depth, score, nodes);
+                }
+            }
 
             // Restore board state by unmaking the move
-            board.unmake_move(&move_info);
+            unmake_move_with_hand(board, &move_info, &new_captured, captured_pieces);
 
             // Enhanced move evaluation logging
             crate::debug_utils::log_move_eval(
@@ -5442,6 +6016,7 @@ impl SearchEngine {
                 }
             }
             if self.ybwc_enabled
+                && root_score_margin == 0
                 && depth >= self.ybwc_min_depth
                 && move_index == 0
                 && sorted_moves.len() >= self.ybwc_min_branch
@@ -5456,17 +6031,13 @@ impl SearchEngine {
                 let stop_flag = self.stop_flag.clone();
                 let shared_tt = self.shared_transposition_table.clone();
                 let quiescence_cfg = self.quiescence_config.clone();
+                let max_search_ply = self.max_search_ply();
+                let root_path = &self.repetition_path;
                 let sibling_results: Vec<(i32, usize)> = siblings
                     .par_iter()
                     .enumerate()
                     .with_min_len(8)
                     .map(|(sib_idx, sib_mv)| {
-                        // Prepare child position
-                        let mut sib_board = board.clone();
-                        let mut sib_captured = captured_pieces.clone();
-                        if let Some(captured) = sib_board.make_move(sib_mv) {
-                            sib_captured.add_piece(captured.piece_type, player);
-                        }
                         // Reuse a per-thread engine from thread-local storage
                         let s = YBWC_ENGINE_TLS.with(|cell| {
                             let mut opt = cell.borrow_mut();
@@ -5481,9 +6052,23 @@ impl SearchEngine {
                                 }
                                 e.set_ybwc(true, self.ybwc_min_depth);
                                 e.set_ybwc_branch(self.ybwc_min_branch);
+                                e.set_max_search_ply(max_search_ply);
                                 *opt = Some(e);
                             }
                             let eng = opt.as_mut().unwrap();
+                            // Prepare child position in the thread's pooled buffers
+                            let (mut sib_board, mut sib_captured) =
+                                eng.board_pool.acquire(board, captured_pieces);
+                            let (_, child_captured) = make_move_with_hand(
+                                &mut sib_board,
+                                &sib_captured,
+                                sib_mv,
+                                player,
+                            );
+                            sib_captured.clone_from(&child_captured);
+                            let mut sib_history =
+                                eng.advanced_move_orderer.get_memory_pool_mut().get_hash_vec();
+                            eng.repetition_path.clone_from(root_path);
                             let score = -eng.negamax(
                                 &mut sib_board,
                                 &sib_captured,
@@ -5493,10 +6078,14 @@ impl SearchEngine {
                                 alpha.saturating_neg(),
                                 &start_time,
                                 time_limit_ms,
-                                &mut vec![],
+                                &mut sib_history,
                                 true,
                             );
                             eng.flush_tt_buffer();
+                            eng.board_pool.release(sib_board, sib_captured);
+                            eng.advanced_move_orderer
+                                .get_memory_pool_mut()
+                                .return_hash_vec(sib_history);
                             score
                         });
                         (s, sib_idx + 1) // store original index offset by 1
@@ -5552,7 +6141,8 @@ impl SearchEngine {
             let position_hash =
                 self.hash_calculator
                     .get_position_hash(board, player, captured_pieces);
-            let flag = if best_score <= alpha {
+            // A fail-low (no move above the original alpha) is only an upper bound
+            let flag = if best_score <= original_alpha {
                 TranspositionFlag::UpperBound
             } else if best_score >= beta {
                 TranspositionFlag::LowerBound
@@ -5566,7 +6156,20 @@ impl SearchEngine {
                 Some(best_move_ref.clone()),
                 position_hash,
             );
-            self.maybe_buffer_tt_store(entry, depth, flag);
+            let verification_key = self.tt_verification_key(board, player, captured_pieces);
+            self.maybe_buffer_tt_store(entry, depth, flag, verification_key);
+        }
+
+        // Root scores are only usable when every root move was searched to completion
+        let iteration_complete = searched_moves == sorted_moves.len()
+            && !self.should_stop_force(&start_time, time_limit_ms);
+        if root_score_margin > 0 && iteration_complete {
+            self.root_move_scores = root_scores;
+        }
+        if staged && iteration_complete {
+            if let (Some(root_moves), Some(best)) = (self.root_move_list.as_mut(), best_move.as_ref()) {
+                root_moves.finish_iteration(depth, best, best_score);
+            }
         }
 
         // Note: Total search time is tracked at the IterativeDeepening::search() level
@@ -5703,6 +6306,53 @@ impl SearchEngine {
     }
 
     fn negamax_with_context(
+        &mut self,
+        board: &mut BitboardBoard,
+        captured_pieces: &CapturedPieces,
+        player: Player,
+        depth: u8,
+        alpha: i32,
+        beta: i32,
+        start_time: &TimeSource,
+        time_limit_ms: u32,
+        hash_history: &mut Vec<u64>,
+        can_null_move: bool,
+        is_root: bool,
+        has_capture: bool,
+        has_check: bool,
+        opponent_last_move: Option<Move>,
+        entry_source: crate::types::EntrySource,
+    ) -> i32 {
+        // Past the ply limit the node scores like a horizon node
+        if !self.ply_guard.enter() {
+            return self.evaluate_position(board, player, captured_pieces);
+        }
+        // The node pushes its position onto the repetition path; pop it however
+        // the node returns
+        let path_len = self.repetition_path.len();
+        let score = self.negamax_node(
+            board,
+            captured_pieces,
+            player,
+            depth,
+            alpha,
+            beta,
+            start_time,
+            time_limit_ms,
+            hash_history,
+            can_null_move,
+            is_root,
+            has_capture,
+            has_check,
+            opponent_last_move,
+            entry_source,
+        );
+        self.repetition_path.truncate(path_len);
+        self.ply_guard.leave();
+        score
+    }
+
+    fn negamax_node(
         &mut self,
         board: &mut BitboardBoard,
         captured_pieces: &CapturedPieces,
@@ -5753,17 +6403,23 @@ impl SearchEngine {
         self.search_statistics.increment_nodes();
         // Track total nodes for metrics (Task 5.7)
         self.core_search_metrics.total_nodes += 1;
-        // Update seldepth (selective depth) - track maximum depth reached
-        // current_depth is the iteration depth (e.g., 5), depth is remaining depth (e.g., starts at 5, then 4, 3, 2...)
-        // Actual depth from root = current_depth - depth + 1 (when depth=5 at root, we're at ply 1)
-        // When depth=0, we've reached current_depth plies from root
-        // So depth_from_root = current_depth - depth + 1
-        // But actually, if we start with depth=5, that means we search 5 plies: depth goes 5->4->3->2->1->0
-        // When depth=0, we've searched 5 plies (current_depth)
-        // When depth=1, we've searched 4 plies
-        // So depth_from_root = current_depth - depth
-        let depth_from_root = self.current_depth.saturating_sub(depth);
-        self.search_statistics.update_seldepth(depth_from_root);
+        // Plies from the root, counting extensions, reductions and null moves;
+        // mate scores and the selective depth are measured in it
+        let ply = self.ply_guard.ply().min(u8::MAX as u16) as u8;
+        self.search_statistics.update_seldepth(ply);
+
+        // Mate-distance pruning: a mate found closer to the root can't be improved here
+        let (mdp_alpha, mdp_beta) = if is_root {
+            (alpha, beta)
+        } else {
+            mate_distance_bounds(alpha, beta, ply)
+        };
+        if mdp_alpha >= mdp_beta {
+            return mdp_alpha;
+        }
+        alpha = mdp_alpha;
+        let beta = mdp_beta;
+
         // Check transposition table and calculate position hash (Task 5.1-5.3)
         // Calculate position hash for repetition detection and TT
         let position_hash = self
@@ -5777,6 +6433,24 @@ impl SearchEngine {
         // Use hash_calculator's built-in repetition detection instead of FEN strings
         // Note: hash_calculator maintains its own global history via add_position_to_history
         // For search context, we track hashes locally in hash_history
+        // A repetition reached by continuous checks loses for the checking side
+        let repetition_entry = RepetitionEntry {
+            hash: position_hash,
+            in_check: board.is_king_in_check(player, captured_pieces),
+        };
+        if !is_root {
+            match self.repetition_path.classify(repetition_entry) {
+                Some(RepetitionOutcome::PerpetualCheckByMover) => {
+                    return perpetual_check_loss(ply);
+                }
+                Some(RepetitionOutcome::PerpetualCheckByOpponent) => {
+                    return perpetual_check_win(ply);
+                }
+                Some(RepetitionOutcome::Draw) | None => {}
+            }
+        }
+        self.repetition_path.push(repetition_entry);
+
         let repetition_state = self
             .hash_calculator
             .get_repetition_state_for_hash(position_hash);
@@ -5798,13 +6472,19 @@ impl SearchEngine {
 
         // Automatic profiling for TT probe (Task 26.0 - Task 3.0)
         let tt_probe_start = if self.auto_profiling_enabled {
-            Some(std::time::Instant::now())
+            Some(crate::time_utils::Instant::now())
         } else {
             None
         };
 
+        // A hit whose verification key disagrees came from another position
+        let verification_key = self.tt_verification_key(board, player, captured_pieces);
         let tt_entry = self.transposition_table
-            .probe_with_prefetch(position_hash, depth, None);
+            .probe_with_prefetch(position_hash, depth, None)
+            .filter(|_| match verification_key {
+                Some(key) => self.transposition_table.verify_probe(position_hash, key),
+                None => true,
+            });
 
         // Record TT probe profiling (Task 3.0)
         if let Some(start) = tt_probe_start {
@@ -5815,6 +6495,8 @@ impl SearchEngine {
         if let Some(entry) = tt_entry {
             // Track TT hit (Task 5.7)
             self.core_search_metrics.total_tt_hits += 1;
+            // Mate scores are stored relative to the node; re-anchor them at this ply
+            let tt_score = score_from_tt(entry.score, ply);
 
             // Track TT hit type (Task 5.7)
             match entry.flag {
@@ -5824,10 +6506,10 @@ impl SearchEngine {
                         "NEGAMAX",
                         &format!(
                             "Transposition table hit (Exact): depth={}, score={}",
-                            entry.depth, entry.score
+                            entry.depth, tt_score
                         ),
                     );
-                    return entry.score;
+                    return tt_score;
                 }
                 TranspositionFlag::LowerBound => {
                     self.core_search_metrics.tt_lower_bound_hits += 1;
@@ -5835,12 +6517,12 @@ impl SearchEngine {
                         "NEGAMAX",
                         &format!(
                             "Transposition table hit (LowerBound): depth={}, score={}",
-                            entry.depth, entry.score
+                            entry.depth, tt_score
                         ),
                     );
-                    if entry.score >= beta {
+                    if tt_score >= beta {
                         crate::utils::telemetry::trace_log("NEGAMAX", "TT lower bound cutoff");
-                        return entry.score;
+                        return tt_score;
                     }
                 }
                 TranspositionFlag::UpperBound => {
@@ -5849,12 +6531,12 @@ impl SearchEngine {
                         "NEGAMAX",
                         &format!(
                             "Transposition table hit (UpperBound): depth={}, score={}",
-                            entry.depth, entry.score
+                            entry.depth, tt_score
                         ),
                     );
-                    if entry.score <= alpha {
+                    if tt_score <= alpha {
                         crate::utils::telemetry::trace_log("NEGAMAX", "TT upper bound cutoff");
-                        return entry.score;
+                        return tt_score;
                     }
                 }
             }
@@ -6071,7 +6753,7 @@ impl SearchEngine {
             .generate_legal_moves(board, player, captured_pieces);
         if legal_moves.is_empty() {
             let is_check = board.is_king_in_check(player, captured_pieces);
-            let score = if is_check { -100000 } else { 0 };
+            let score = if is_check { mated_in(ply) } else { 0 };
             crate::debug_utils::trace_log(
                 "NEGAMAX",
                 &format!("No legal moves: check={}, score={}", is_check, score),
@@ -6079,6 +6761,14 @@ impl SearchEngine {
             return score;
         }
 
+        // Under time pressure with a full hand, only search the drops that matter
+        let legal_moves = if is_root || repetition_entry.in_check {
+            legal_moves
+        } else {
+            self.drop_limiter
+                .limit(board, captured_pieces, player, legal_moves, time_pressure)
+        };
+
         crate::debug_utils::trace_log(
             "NEGAMAX",
             &format!("Found {} legal moves", legal_moves.len()),
@@ -6090,6 +6780,7 @@ impl SearchEngine {
             .transposition_table
             .probe(position_hash, 255)
             .and_then(|entry| entry.best_move.clone());
+        let tt_move = self.tt_move_check.filter(board, captured_pieces, player, tt_move);
 
         // Task 7.0.2.6, 7.0.2.9: Skip IID at Medium/High time pressure, allow at Low/None
         let skip_iid_time_pressure = time_pressure == crate::types::TimePressure::Medium
@@ -6151,11 +6842,14 @@ impl SearchEngine {
             let initial_hash =
                 self.hash_calculator
                     .get_position_hash(board, player, captured_pieces);
-            let mut local_hash_history = vec![initial_hash];
+            let mut local_hash_history =
+                self.advanced_move_orderer.get_memory_pool_mut().get_hash_vec();
+            local_hash_history.push(initial_hash);
+            let (mut iid_board, iid_captured) = self.board_pool.acquire(board, captured_pieces);
             // Task 2.0: Receive (score, best_move) tuple from perform_iid_search
             let (iid_score_result, iid_move_result) = self.perform_iid_search(
-                &mut board.clone(),
-                captured_pieces,
+                &mut iid_board,
+                &iid_captured,
                 player,
                 iid_depth,
                 alpha,
@@ -6165,6 +6859,10 @@ impl SearchEngine {
                 &mut local_hash_history,
             );
             iid_move = iid_move_result;
+            self.board_pool.release(iid_board, iid_captured);
+            self.advanced_move_orderer
+                .get_memory_pool_mut()
+                .return_hash_vec(local_hash_history);
 
             let actual_iid_time = iid_start_time.elapsed_ms();
             self.iid_stats.iid_searches_performed += 1;
@@ -6296,6 +6994,11 @@ impl SearchEngine {
         let mut move_index = 0;
         let mut iid_move_improved_alpha = false;
 
+        // Static eval and position info are the same for every move at this node
+        let node_static_eval = self.evaluate_position(board, player, captured_pieces);
+        let node_position_hash = self.get_position_hash(board);
+        let node_game_phase = self.get_game_phase(board);
+
         crate::utils::telemetry::trace_log(
             "NEGAMAX",
             &format!(
@@ -6335,9 +7038,9 @@ impl SearchEngine {
             search_state.move_number = move_index as u8;
             search_state.update_fields(
                 has_check,
-                self.evaluate_position(board, player, captured_pieces),
-                self.get_position_hash(board),
-                self.get_game_phase(board),
+                node_static_eval,
+                node_position_hash,
+                node_game_phase,
             );
 
             // Check if move should be pruned using advanced pruning techniques with conditional logic
@@ -6387,6 +7090,38 @@ impl SearchEngine {
                 health_score: search_state.health_score,
             };
             let all_move = convert_move_to_all(move_.clone());
+
+            // Frontier futility and history-based late move pruning for quiet moves
+            if !is_root && depth <= 2 {
+                let history_score = move_
+                    .from
+                    .map(|from| self.history_table[from.row as usize][from.col as usize]);
+                let is_killer = self.is_killer_move(move_);
+                // Move generation leaves `gives_check` unset, so a move about to be
+                // pruned is made to find out whether it checks
+                let frontier_decision = self.pruning_manager.check_frontier_pruning(
+                    &all_search_state,
+                    &all_move,
+                    history_score,
+                    is_killer,
+                    || {
+                        let (move_info, new_captured) =
+                            make_move_with_hand(board, captured_pieces, move_, player);
+                        let gives_check =
+                            board.is_king_in_check(player.opposite(), &new_captured);
+                        unmake_move_with_hand(board, &move_info, &new_captured, captured_pieces);
+                        gives_check
+                    },
+                );
+                if frontier_decision.is_pruned() {
+                    crate::utils::telemetry::trace_log(
+                        "NEGAMAX",
+                        &format!("Move {} pruned at frontier", move_.to_usi_string()),
+                    );
+                    continue;
+                }
+            }
+
             let should_consider_pruning = self
                 .pruning_manager
                 .should_apply_conditional_pruning(&all_search_state, &all_move);
@@ -6403,12 +7138,8 @@ impl SearchEngine {
             }
 
             // Use move unmaking instead of board cloning
-            let move_info = board.make_move_with_info(move_);
-            let mut new_captured = captured_pieces.clone();
-
-            if let Some(ref captured) = move_info.captured_piece {
-                new_captured.add_piece(captured.piece_type, player);
-            }
+            let (move_info, new_captured) =
+                make_move_with_hand(board, captured_pieces, move_, player);
 
             crate::debug_utils::start_timing(&format!("move_search_{}", move_index));
             // Task 2.6: Pass current move as opponent_last_move to recursive call
@@ -6436,7 +7167,7 @@ impl SearchEngine {
             crate::debug_utils::end_timing(&format!("move_search_{}", move_index), "NEGAMAX");
 
             // Restore board state by unmaking the move
-            board.unmake_move(&move_info);
+            unmake_move_with_hand(board, &move_info, &new_captured, captured_pieces);
 
             crate::debug_utils::log_move_eval(
                 "NEGAMAX",
@@ -6513,6 +7244,7 @@ impl SearchEngine {
                     // Track beta cutoff (Task 5.7)
                     self.core_search_metrics.total_cutoffs += 1;
                     self.core_search_metrics.beta_cutoffs += 1;
+                    self.record_cutoff_at_ply(ply);
 
                     // Task 12.2: Track cutoffs from IID moves vs non-IID moves
                     self.iid_stats.total_cutoffs += 1;
@@ -6642,7 +7374,7 @@ impl SearchEngine {
         // Clone best_move_for_tt before passing to avoid move error (Task 5.12)
         // Task 7.0.3.7: Create entry with source tracking
         let entry = TranspositionEntry::new(
-            best_score,
+            score_to_tt(best_score, ply),
             depth,
             flag,
             best_move_for_tt.clone(),
@@ -6650,7 +7382,12 @@ impl SearchEngine {
             0,
             entry_source,
         );
-        self.maybe_buffer_tt_store(entry, depth, flag);
+        self.maybe_buffer_tt_store(entry, depth, flag, verification_key);
+
+        // Hand the move lists back so ordering at the next node reuses their buffers
+        let memory_pool = self.advanced_move_orderer.get_memory_pool_mut();
+        memory_pool.return_move_vec(sorted_moves);
+        memory_pool.return_move_vec(legal_moves);
 
         crate::utils::telemetry::trace_log(
             "NEGAMAX",
@@ -7162,12 +7899,8 @@ impl SearchEngine {
             }
 
             // Use move unmaking instead of board cloning
-            let move_info = board.make_move_with_info(&move_);
-            let mut new_captured = captured_pieces.clone();
-
-            if let Some(ref captured) = move_info.captured_piece {
-                new_captured.add_piece(captured.piece_type, player);
-            }
+            let (move_info, new_captured) =
+                make_move_with_hand(board, captured_pieces, &move_, player);
 
             // Task 7.6, 7.7: Extension logic and depth decrement behavior
             //
@@ -7224,7 +7957,7 @@ impl SearchEngine {
             );
 
             // Restore board state by unmaking the move
-            board.unmake_move(&move_info);
+            unmake_move_with_hand(board, &move_info, &new_captured, captured_pieces);
 
             // crate::debug_utils::log_move_eval("QUIESCENCE", &move_.to_usi_string(), score,
             //     &format!("move {} of {}", move_index + 1, sorted_noisy_moves.len()));
@@ -7368,6 +8101,10 @@ impl SearchEngine {
     /// Check if search should stop due to time limit or stop flag
     /// Delegates to TimeManager (Task 1.8)
     fn should_stop(&mut self, start_time: &TimeSource, time_limit_ms: u32) -> bool {
+        // The node budget is a plain counter comparison, so it is checked on every node
+        if self.node_limit_reached() {
+            return true;
+        }
         self.time_manager.should_stop(
             start_time,
             time_limit_ms,
@@ -7378,6 +8115,9 @@ impl SearchEngine {
     /// Force time check (bypasses frequency optimization) (Task 8.4)
     /// Used when we must check time regardless of frequency (e.g., at depth boundaries)
     fn should_stop_force(&self, start_time: &TimeSource, time_limit_ms: u32) -> bool {
+        if self.node_limit_reached() {
+            return true;
+        }
         if let Some(flag) = &self.stop_flag {
             if flag.load(Ordering::Relaxed) {
                 return true;
@@ -7435,8 +8175,14 @@ impl SearchEngine {
             moves.to_vec()
         };
 
-        // Try advanced move ordering for quiescence search
-        match self.advanced_move_orderer.order_moves(&ordered_moves) {
+        // Try advanced move ordering for quiescence search (depth 0: captures only)
+        match self.advanced_move_orderer.order_moves_in_position(
+            &ordered_moves,
+            board,
+            captured_pieces,
+            player,
+            0,
+        ) {
             Ok(advanced_ordered) => {
                 // Verify ordering is valid (same length, no duplicates)
                 if advanced_ordered.len() == moves.len() {
@@ -7833,6 +8579,13 @@ impl SearchEngine {
         self.lmr_stats.reset();
     }
 
+    /// Drop all state carried over from earlier searches (TT, history, killers and the
+    /// repetition history) so the next search depends only on its inputs
+    pub fn reset_search_state(&mut self) {
+        self.clear();
+        self.hash_calculator.clear_history();
+    }
+
     #[cfg(test)]
     pub fn transposition_table_len(&self) -> usize {
         self.transposition_table.size()
@@ -7872,11 +8625,20 @@ impl SearchEngine {
                     .probe_with_prefetch(position_hash, 0, next_hash)
             {
                 let _ = next_hash.take();
-                if let Some(move_) = &entry.best_move {
+                let best_move = self.tt_move_check.filter(
+                    &current_board,
+                    &current_captured,
+                    current_player,
+                    entry.best_move.clone(),
+                );
+                if let Some(move_) = &best_move {
                     pv.push(move_.clone());
-                    if let Some(captured) = current_board.make_move(move_) {
-                        current_captured.add_piece(captured.piece_type, current_player);
-                    }
+                    (_, current_captured) = make_move_with_hand(
+                        &mut current_board,
+                        &current_captured,
+                        move_,
+                        current_player,
+                    );
                     current_player = current_player.opposite();
                     let future_hash = self.hash_calculator.get_position_hash(
                         &current_board,
@@ -7925,11 +8687,20 @@ impl SearchEngine {
                     );
                     if let Some(entry) = tt.probe_with_prefetch(position_hash, 0, next_hash) {
                         let _ = next_hash.take();
-                        if let Some(move_) = &entry.best_move {
+                        let best_move = self.tt_move_check.filter(
+                            &current_board,
+                            &current_captured,
+                            current_player,
+                            entry.best_move.clone(),
+                        );
+                        if let Some(move_) = &best_move {
                             pv.push(move_.clone());
-                            if let Some(captured) = current_board.make_move(move_) {
-                                current_captured.add_piece(captured.piece_type, current_player);
-                            }
+                            (_, current_captured) = make_move_with_hand(
+                                &mut current_board,
+                                &current_captured,
+                                move_,
+                                current_player,
+                            );
                             current_player = current_player.opposite();
                             let future_hash = self.hash_calculator.get_position_hash(
                                 &current_board,
@@ -8751,7 +9522,7 @@ impl SearchEngine {
 
         for i in 0..iterations {
             self.reset_quiescence_stats();
-            let start_time = std::time::Instant::now();
+            let start_time = crate::time_utils::Instant::now();
 
             let _result = self.quiescence_search(
                 board,
@@ -9172,6 +9943,30 @@ impl SearchEngine {
     /// Delegates to TimeManager (Task 1.8)
     pub fn record_depth_completion(&mut self, depth: u8, completion_time_ms: u32) {
         self.time_manager.record_depth_completion(depth, completion_time_ms);
+        if depth > 0 {
+            let idx = (depth - 1) as usize;
+            if self.iteration_times_ms.len() <= idx {
+                self.iteration_times_ms.resize(idx + 1, 0);
+            }
+            self.iteration_times_ms[idx] = completion_time_ms;
+        }
+        self.publish_search_statistics();
+    }
+
+    /// Grant extra time for an unstable search; delegates to TimeManager
+    pub fn extend_for_instability(
+        &mut self,
+        instability: SearchInstability,
+        allotted_ms: u32,
+        hard_limit_ms: u32,
+    ) -> u32 {
+        self.time_manager
+            .extend_for_instability(instability, allotted_ms, hard_limit_ms)
+    }
+
+    /// Time added to the allotted time by instability extensions in the last search
+    pub fn instability_extension_ms(&self) -> u32 {
+        self.time_manager.instability_extension_ms()
     }
 
     /// Get time budget statistics for analysis (Task 4.10)
@@ -9225,6 +10020,67 @@ impl SearchEngine {
         self.core_search_metrics.reset();
     }
 
+    /// Reuse counters of this engine's board pool
+    pub fn get_board_pool_stats(&self) -> BoardPoolStats {
+        self.board_pool.stats()
+    }
+
+    /// Positions played before the next search's root, oldest first, for
+    /// perpetual check detection across the game
+    pub fn set_game_history(&mut self, game_history: Vec<RepetitionEntry>) {
+        self.game_history = game_history;
+    }
+
+    /// Configure the quiet-drop limiter used under time pressure
+    pub fn set_drop_limiter_config(&mut self, config: DropLimiterConfig) {
+        self.drop_limiter.set_config(config);
+    }
+
+    pub fn drop_limiter_config(&self) -> &DropLimiterConfig {
+        self.drop_limiter.config()
+    }
+
+    /// Deepest ply the main search may recurse to (`MaxSearchPly` option)
+    pub fn set_max_search_ply(&mut self, max_ply: u16) {
+        self.ply_guard.set_max_ply(max_ply);
+    }
+
+    pub fn max_search_ply(&self) -> u16 {
+        self.ply_guard.max_ply()
+    }
+
+    /// Report `info` scores from `perspective` instead of the side to move's
+    pub fn set_score_perspective(&mut self, perspective: EvaluationPerspective) {
+        self.score_perspective = perspective;
+    }
+
+    pub fn score_perspective(&self) -> EvaluationPerspective {
+        self.score_perspective
+    }
+
+    /// Append `winrate` in permille to `info` scores (`ShowWinRate` option)
+    pub fn set_show_win_rate(&mut self, show: bool) {
+        self.show_win_rate = show;
+    }
+
+    pub fn show_win_rate(&self) -> bool {
+        self.show_win_rate
+    }
+
+    /// Throttle `info` output and choose its fields (`Info*` options)
+    pub fn set_info_verbosity(&mut self, verbosity: InfoVerbosity) {
+        self.info_verbosity = verbosity;
+    }
+
+    pub fn info_verbosity(&self) -> InfoVerbosity {
+        self.info_verbosity
+    }
+
+    /// Resize the move-ordering score cache
+    pub fn set_move_ordering_cache_size(&mut self, size: usize) {
+        self.advanced_move_orderer.set_cache_size(size);
+    }
+
     /// Generate comprehensive core search metrics report (Task 5.9)
     pub fn generate_core_search_metrics_report(&self) -> String {
         self.core_search_metrics.generate_report()
@@ -10637,12 +11493,14 @@ impl SearchEngine {
         // Task 7.0.5.1-5.2: Monitor and alert if IID move somehow gets reduced (should never happen)
         if reduction > 0 && is_iid_move {
             self.lmr_stats.iid_move_reduced_count += 1;
-            eprintln!("⚠️  WARNING: IID move was reduced by LMR! This should never happen.");
-            eprintln!(
-                "    Move: {}, Reduction: {}, Depth: {}",
-                move_.to_usi_string(),
-                reduction,
-                depth
+            crate::utils::logging::warn(
+                "LMR",
+                &format!(
+                    "IID move was reduced by LMR, which should never happen. Move: {}, Reduction: {}, Depth: {}",
+                    move_.to_usi_string(),
+                    reduction,
+                    depth
+                ),
             );
             crate::utils::telemetry::trace_log("LMR_ALERT", &format!(
                 "CRITICAL: IID move {} was reduced by {} at depth {}. This indicates a bug in exemption logic!",
@@ -11691,7 +12549,7 @@ impl SearchEngine {
 
         for _ in 0..iterations {
             self.reset_lmr_stats();
-            let start_time = std::time::Instant::now();
+            let start_time = crate::time_utils::Instant::now();
 
             let mut test_board = board.clone();
             let _result =
@@ -13271,7 +14129,7 @@ impl SearchEngine {
         
         // Automatic profiling integration (Task 26.0 - Task 3.0)
         let start_time = if self.auto_profiling_enabled {
-            Some(std::time::Instant::now())
+            Some(crate::time_utils::Instant::now())
         } else {
             None
         };
@@ -13531,6 +14389,7 @@ impl SearchEngine {
             lmr_applied: self.pruning_manager.statistics.lmr_applied,
             re_searches: self.pruning_manager.statistics.re_searches,
             multi_cuts: self.pruning_manager.statistics.multi_cuts,
+            history_pruned: self.pruning_manager.statistics.history_pruned,
         }
     }
 
@@ -13600,6 +14459,8 @@ pub struct IterativeDeepening {
     /// Optional parallel search engine for root move search
     parallel_engine: Option<ParallelSearchEngine>,
     parallel_min_depth: u8,
+    /// Reproducible mode: no wall-clock safety cutoffs, so results depend only on inputs
+    deterministic: bool,
 }
 impl IterativeDeepening {
     pub fn new(max_depth: u8, time_limit_ms: u32, stop_flag: Option<Arc<AtomicBool>>) -> Self {
@@ -13610,6 +14471,7 @@ impl IterativeDeepening {
             thread_count: 1,
             parallel_engine: None,
             parallel_min_depth: 0,
+            deterministic: false,
         }
     }
 
@@ -13656,6 +14518,36 @@ impl IterativeDeepening {
             thread_count: threads,
             parallel_engine,
             parallel_min_depth,
+            deterministic: false,
+        }
+    }
+
+    /// Enable reproducible searches. Check-position time caps and the per-iteration
+    /// stuck guard are skipped, so only the depth, node and caller time limits apply.
+    pub fn set_deterministic(&mut self, enabled: bool) {
+        self.deterministic = enabled;
+    }
+
+    /// Ask the time manager for more time and raise `search_time_limit` by what it grants
+    fn extend_time_limit(
+        search_engine: &mut SearchEngine,
+        instability: SearchInstability,
+        depth: u8,
+        allotted_ms: u32,
+        hard_limit_ms: u32,
+        search_time_limit: &mut u32,
+    ) {
+        let granted_ms =
+            search_engine.extend_for_instability(instability, allotted_ms, hard_limit_ms);
+        if granted_ms > 0 {
+            *search_time_limit = search_time_limit.saturating_add(granted_ms);
+            crate::utils::telemetry::trace_log(
+                "ITERATIVE_DEEPENING",
+                &format!(
+                    "Depth {}: {:?}, extending time limit by {}ms to {}ms",
+                    depth, instability, granted_ms, *search_time_limit
+                ),
+            );
         }
     }
 
@@ -13673,6 +14565,22 @@ impl IterativeDeepening {
         let start_time = TimeSource::now();
         // Reset total search time at the start of a new search
         search_engine.iid_stats.total_search_time_ms = 0;
+        search_engine.begin_search_statistics();
+        search_engine.root_move_scores.clear();
+        search_engine.root_move_list = None;
+        search_engine.time_manager.begin_search();
+
+        // The search board keeps attack maps up to date, so evaluation, SEE and
+        // check detection read them instead of regenerating every piece's attacks
+        let tracked_board;
+        let board = if board.tracked_attack_maps().is_some() {
+            board
+        } else {
+            let mut copy = board.clone();
+            copy.enable_attack_maps();
+            tracked_board = copy;
+            &tracked_board
+        };
 
         let mut best_move: Option<Move> = None;
         let mut best_score = 0;
@@ -13687,14 +14595,17 @@ impl IterativeDeepening {
 
         // Check if we're in check and have few legal moves - optimize search parameters
         let is_in_check = board.is_king_in_check(player, captured_pieces);
-        let legal_moves =
+        let legal_moves = search_engine.filter_root_moves(
             search_engine
                 .move_generator
-                .generate_legal_moves(board, player, captured_pieces);
+                .generate_legal_moves(board, player, captured_pieces),
+        );
         let legal_move_count = legal_moves.len();
+        search_engine.begin_root_moves(board, captured_pieces, player, &legal_moves);
 
-        // Adjust search parameters for check positions with few moves (Task 4.3, 4.4)
-        let (effective_max_depth, effective_time_limit) = {
+        // Adjust search parameters for check positions with few moves (Task 4.3, 4.4).
+        // The third value is the most instability extensions may stretch the limit to.
+        let (effective_max_depth, effective_time_limit, hard_time_limit) = {
             let config = &search_engine.time_management_config;
             if config.enable_check_optimization && is_in_check && legal_move_count <= 10 {
                 // For check positions with ≤10 moves, use configurable limits
@@ -13703,7 +14614,9 @@ impl IterativeDeepening {
                 } else {
                     config.check_max_depth.min(5)
                 };
-                let time_limit = if legal_move_count <= 5 {
+                let time_limit = if self.deterministic {
+                    self.time_limit_ms
+                } else if legal_move_count <= 5 {
                     config.check_time_limit_ms.min(2000)
                 } else {
                     config.check_time_limit_ms.min(5000)
@@ -13715,7 +14628,8 @@ impl IterativeDeepening {
                         legal_move_count, max_depth, time_limit
                     ),
                 );
-                (max_depth, time_limit)
+                // Check positions have few replies; their caps are not extended
+                (max_depth, time_limit, time_limit)
             } else {
                 // Normal search parameters
                 // Task 8.2, 8.3: Use configurable absolute safety margin instead of hardcoded 100ms
@@ -13723,14 +14637,17 @@ impl IterativeDeepening {
                     (self.time_limit_ms as f64 * config.safety_margin) as u32;
                 let absolute_margin_ms = config.absolute_safety_margin_ms;
                 let total_safety_margin_ms = percentage_margin_ms.max(absolute_margin_ms);
+                let time_limit = self.time_limit_ms.saturating_sub(total_safety_margin_ms);
                 (
                     self.max_depth,
-                    self.time_limit_ms.saturating_sub(total_safety_margin_ms),
+                    time_limit,
+                    self.time_limit_ms.saturating_sub(absolute_margin_ms).max(time_limit),
                 )
             }
         };
 
-        let search_time_limit = effective_time_limit;
+        // Instability extensions raise this above the allotted time, up to the hard limit
+        let mut search_time_limit = effective_time_limit;
         crate::utils::telemetry::trace_log(
             "ITERATIVE_DEEPENING",
             &format!(
@@ -13741,6 +14658,8 @@ impl IterativeDeepening {
 
         crate::utils::telemetry::trace_log("ITERATIVE_DEEPENING", "Starting depth iteration loop");
 
+        // Completed iteration held back by `InfoDepthInterval`, sent if the search ends on it
+        let mut held_info: Option<String> = None;
         for depth in 1..=effective_max_depth {
             // Reset global node counter for this depth and start periodic reporter
             GLOBAL_NODES_SEARCHED.store(0, Ordering::Relaxed);
@@ -13778,7 +14697,7 @@ impl IterativeDeepening {
             let info_sender_cancel = Arc::new(AtomicBool::new(false));
             let info_sender_cancel_clone = info_sender_cancel.clone();
             let depth_clone = depth;
-            let depth_start_time_instant = std::time::Instant::now(); // Capture instant for elapsed time
+            let depth_start_time_instant = crate::time_utils::Instant::now(); // Capture instant for elapsed time
             let _board_clone = board.clone();
             let _captured_clone = captured_pieces.clone();
             let _player_clone = player;
@@ -13826,18 +14745,54 @@ impl IterativeDeepening {
                 Arc::new(std::sync::Mutex::new((None::<Move>, 0, String::new())))
             };
             let best_move_shared_clone = best_move_shared.clone();
+            let score_perspective = search_engine.score_perspective();
+            let show_win_rate = search_engine.show_win_rate();
+            let verbosity = search_engine.info_verbosity();
 
             // Spawn info sender thread that periodically sends updates
-            let info_sender_handle = std::thread::spawn(move || {
-                let mut last_info_time = std::time::Instant::now();
-                let info_interval = std::time::Duration::from_millis(1000); // Send every 1 second
+            let live_progress = search_engine.live_progress.clone();
+            live_progress.reset_currmove();
+            // wasm builds have no threads, so they only report completed iterations
+            let info_sink = crate::search::info_sink::thread_info_sink();
+            let info_suppressed = crate::search::info_sink::info_suppressed();
+            let info_sender_handle = (!cfg!(feature = "wasm")).then(|| std::thread::spawn(move || {
+                // Progress lines go where this engine thread's lines go
+                crate::search::info_sink::set_thread_info_sink(info_sink);
+                crate::search::info_sink::set_info_suppressed(info_suppressed);
+                // Full info lines as often as the verbosity allows (once a second by default);
+                // currmove lines at most twice a second and only once the iteration has run
+                // long enough for a GUI to show progress
+                let mut progress_throttle = verbosity.progress_throttle();
+                let mut currmove_limiter =
+                    InfoRateLimiter::starting_now(std::time::Duration::from_millis(500));
+                let mut last_reported_currmove: Option<(String, u64)> = None;
+                let silent = std::env::var("SHOGI_SILENT_BENCH").is_ok();
 
                 while !info_sender_cancel_clone.load(Ordering::Relaxed) {
                     std::thread::sleep(std::time::Duration::from_millis(100)); // Check every 100ms
 
-                    if last_info_time.elapsed() >= info_interval {
+                    if !silent
+                        && verbosity.currmove
+                        && depth_start_time_instant.elapsed().as_millis() >= 1000
+                        && currmove_limiter.try_emit()
+                    {
+                        if let Some(currmove) = live_progress.currmove() {
+                            if last_reported_currmove.as_ref() != Some(&currmove) {
+                                crate::search::info_sink::emit_info(&format!(
+                                    "info depth {} currmove {} currmovenumber {}",
+                                    depth_clone, currmove.0, currmove.1
+                                ));
+                                last_reported_currmove = Some(currmove);
+                            }
+                        }
+                    }
+
+                    let nodes = GLOBAL_NODES_SEARCHED.load(Ordering::Relaxed);
+                    if progress_throttle
+                        .as_mut()
+                        .is_some_and(|throttle| throttle.try_emit(nodes))
+                    {
                         let elapsed = depth_start_time_instant.elapsed().as_millis() as u32;
-                        let nodes = GLOBAL_NODES_SEARCHED.load(Ordering::Relaxed);
 
                         if nodes == 0 {
                             continue; // Skip if no nodes searched yet
@@ -13854,6 +14809,7 @@ impl IterativeDeepening {
                         } else {
                             0
                         };
+                        let hashfull = live_progress.hashfull();
 
                         // Get current best move/score/PV from shared state
                         let (current_move, current_score, current_pv) = best_move_shared_clone
@@ -13892,29 +14848,28 @@ impl IterativeDeepening {
                             }
 
                             let info_string = if !current_pv.is_empty() {
-                                format!("info depth {} seldepth {} score cp {} time {} nodes {} nps {} pv {}",
-                                    depth_clone, seldepth, current_score, elapsed, nodes, nps, current_pv)
+                                format!("info depth {} seldepth {} score {} time {} nodes {} nps {} hashfull {} pv {}",
+                                    depth_clone, seldepth, format_info_score(score_perspective.orient(current_score, player), show_win_rate), elapsed, nodes, nps, hashfull, current_pv)
                             } else if let Some(ref mv) = current_move {
                                 // Only use single move as PV if score is non-zero
                                 if current_score == 0 {
                                     continue; // Skip - score is 0, don't send
                                 }
                                 format!(
-                                    "info depth {} seldepth {} score cp {} time {} nodes {} nps {} pv {}",
-                                    depth_clone, seldepth, current_score, elapsed, nodes, nps, mv.to_usi_string()
+                                    "info depth {} seldepth {} score {} time {} nodes {} nps {} hashfull {} pv {}",
+                                    depth_clone, seldepth, format_info_score(score_perspective.orient(current_score, player), show_win_rate), elapsed, nodes, nps, hashfull, mv.to_usi_string()
                                 )
                             } else {
                                 // Skip if we don't have valid data
                                 continue;
                             };
-                            println!("{}", info_string);
-                            let _ = std::io::Write::flush(&mut std::io::stdout());
+                            crate::search::info_sink::emit_info(
+                                &verbosity.fields.filter(&info_string),
+                            );
                         }
-
-                        last_info_time = std::time::Instant::now();
                     }
                 }
-            });
+            }));
 
             let time_budget = if search_engine.time_management_config.enable_time_budget {
                 let budget = search_engine.calculate_time_budget(
@@ -14019,6 +14974,8 @@ impl IterativeDeepening {
             let mut search_result: Option<(Move, i32)> = None;
             let _ = search_result; // Suppress unused assignment warning
             let mut researches = 0;
+            let previous_iteration_best = best_move.clone();
+            let mut extended_for_fail_low = false;
             let mut current_alpha = alpha;
             let mut current_beta = beta;
 
@@ -14041,7 +14998,7 @@ impl IterativeDeepening {
             );
 
             // Track depth iteration start time to detect if we're stuck
-            let depth_iteration_start = std::time::Instant::now();
+            let depth_iteration_start = crate::time_utils::Instant::now();
             let max_depth_iteration_time_ms = 30000u32; // Max 30 seconds per depth to prevent getting stuck
 
             loop {
@@ -14062,7 +15019,7 @@ impl IterativeDeepening {
 
                 // CRITICAL: Detect if this depth iteration is taking too long (stuck)
                 let depth_iteration_elapsed = depth_iteration_start.elapsed().as_millis() as u32;
-                if depth_iteration_elapsed > max_depth_iteration_time_ms {
+                if !self.deterministic && depth_iteration_elapsed > max_depth_iteration_time_ms {
                     crate::utils::telemetry::trace_log(
                         "ASPIRATION_WINDOW",
                         &format!(
@@ -14129,8 +15086,19 @@ impl IterativeDeepening {
                     depth,
                 );
 
-                let parallel_result = if self.thread_count > 1 && depth >= self.parallel_min_depth {
-                    if let Some(ref parallel_engine) = self.parallel_engine {
+                // Helper threads don't share the node budget, so node-limited searches
+                // stay single-threaded to remain reproducible. Root variety and coach
+                // mode need the score of every root move, which only the sequential root
+                // search keeps.
+                let parallel_result = if self.thread_count > 1
+                    && depth >= self.parallel_min_depth
+                    && search_engine.node_limit().is_none()
+                    && search_engine.root_score_margin() == 0
+                {
+                    if let Some(ref mut parallel_engine) = self.parallel_engine {
+                        parallel_engine.set_score_perspective(search_engine.score_perspective());
+                        parallel_engine.set_show_win_rate(search_engine.show_win_rate());
+                        parallel_engine.set_info_verbosity(search_engine.info_verbosity());
                         parallel_engine.search_root_moves(
                             board,
                             captured_pieces,
@@ -14212,6 +15180,23 @@ impl IterativeDeepening {
                             ),
                             Some(score),
                         );
+                        // A sharp drop at a late iteration gets time to find a better move
+                        if !extended_for_fail_low && depth >= INSTABILITY_MIN_DEPTH {
+                            if let Some(&previous_score) = previous_scores.last() {
+                                let drop = previous_score.saturating_sub(score);
+                                if drop > INSTABILITY_DROP_CP {
+                                    Self::extend_time_limit(
+                                        search_engine,
+                                        SearchInstability::ScoreDrop(drop),
+                                        depth,
+                                        effective_time_limit,
+                                        hard_time_limit,
+                                        &mut search_time_limit,
+                                    );
+                                    extended_for_fail_low = true;
+                                }
+                            }
+                        }
                         search_engine.handle_fail_low(
                             &mut current_alpha,
                             &mut current_beta,
@@ -14386,6 +15371,24 @@ impl IterativeDeepening {
                 }
             }
 
+            // A best move that changed this late is not settled yet
+            if depth >= INSTABILITY_MIN_DEPTH {
+                if let (Some(previous), Some((current, _))) =
+                    (&previous_iteration_best, &search_result)
+                {
+                    if previous.to_usi_string() != current.to_usi_string() {
+                        Self::extend_time_limit(
+                            search_engine,
+                            SearchInstability::BestMoveChange,
+                            depth,
+                            effective_time_limit,
+                            hard_time_limit,
+                            &mut search_time_limit,
+                        );
+                    }
+                }
+            }
+
             // Task 7.1: Update statistics with position type tracking
             let game_phase = search_engine.get_game_phase(board);
             let window_size = if depth == 1 || !search_engine.aspiration_config.enabled {
@@ -14436,7 +15439,9 @@ impl IterativeDeepening {
 
             // Stop periodic info sender before building final info
             info_sender_cancel.store(true, Ordering::Relaxed);
-            let _ = info_sender_handle.join(); // Wait for thread to finish
+            if let Some(handle) = info_sender_handle {
+                let _ = handle.join(); // Wait for thread to finish
+            }
 
             crate::debug_utils::end_timing(&format!("depth_{}", depth), "ITERATIVE_DEEPENING");
 
@@ -14485,6 +15490,15 @@ impl IterativeDeepening {
                     &pv_string,
                 );
 
+                search_engine.report_progress(SearchProgress {
+                    depth,
+                    seldepth,
+                    score,
+                    nodes: nodes_for_info,
+                    time_ms: time_searched,
+                    pv: pv_string.split_whitespace().map(str::to_string).collect(),
+                });
+
                 // CRITICAL: Only send info if we have a valid PV or non-zero score
                 // Never send info with score 0 and no PV
                 if score == 0 && pv_string.is_empty() {
@@ -14495,15 +15509,30 @@ impl IterativeDeepening {
                     );
                 } else {
                     let info_string = format!(
-                        "info depth {} seldepth {} multipv 1 score cp {} time {} nodes {} nps {} pv {}",
-                        depth, seldepth, score, time_searched, nodes_for_info, nps, pv_string
+                        "info depth {} seldepth {} multipv 1 score {} time {} nodes {} nps {} hashfull {} pv {}",
+                        depth,
+                        seldepth,
+                        format_info_score(
+                            search_engine.score_perspective().orient(score, player),
+                            search_engine.show_win_rate()
+                        ),
+                        time_searched,
+                        nodes_for_info,
+                        nps,
+                        search_engine.hashfull(),
+                        pv_string
                     );
 
                     // Print the info message to stdout for USI protocol (skip during silent benches)
-                    if std::env::var("SHOGI_SILENT_BENCH").is_err() {
-                        println!("{}", info_string);
-                        // Explicitly flush stdout to ensure info messages are sent immediately
-                        let _ = std::io::Write::flush(&mut std::io::stdout());
+                    let verbosity = search_engine.info_verbosity();
+                    let info_string = verbosity.fields.filter(&info_string);
+                    if std::env::var("SHOGI_SILENT_BENCH").is_ok() {
+                        held_info = None;
+                    } else if verbosity.reports_depth(depth) {
+                        held_info = None;
+                        crate::search::info_sink::emit_info(&info_string);
+                    } else {
+                        held_info = Some(info_string);
                     }
                 }
 
@@ -14527,6 +15556,9 @@ impl IterativeDeepening {
                 break;
             }
         }
+        if let Some(info_string) = held_info {
+            crate::search::info_sink::emit_info(&info_string);
+        }
 
         crate::debug_utils::end_timing("iterative_deepening_total", "ITERATIVE_DEEPENING");
 
@@ -0,0 +1,199 @@
+//! Background Search Handles
+//!
+//! Non-blocking front end for the search. A search started with
+//! `ShogiEngine::start_search` runs on its own worker thread and hands back a
+//! `SearchHandle`: callers can poll it, block on it, `.await` it (it implements
+//! `Future`), or cancel it, and receive a `SearchProgress` report after every
+//! completed iteration.
+
+use crate::search::info_sink;
+use crate::types::core::Move;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Snapshot of a search after a completed iteration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchProgress {
+    pub depth: u8,
+    pub seldepth: u8,
+    /// Score in centipawns from the side to move's perspective
+    pub score: i32,
+    pub nodes: u64,
+    pub time_ms: u32,
+    /// Principal variation in USI notation
+    pub pv: Vec<String>,
+}
+
+/// Callback receiving progress reports from the search thread
+pub type ProgressCallback = Arc<dyn Fn(&SearchProgress) + Send + Sync>;
+
+struct SearchState<T> {
+    /// `Some` once the search has finished; `Err` holds the payload of a panic
+    outcome: Option<thread::Result<T>>,
+    waker: Option<Waker>,
+}
+
+struct Shared<T> {
+    state: Mutex<SearchState<T>>,
+    finished: Condvar,
+}
+
+impl<T> Shared<T> {
+    fn new(outcome: Option<thread::Result<T>>) -> Arc<Self> {
+        Arc::new(Self {
+            state: Mutex::new(SearchState { outcome, waker: None }),
+            finished: Condvar::new(),
+        })
+    }
+}
+
+/// Handle to a search running in the background. `T` is what the search
+/// returns: the best move by default, or e.g. a mate search result.
+pub struct SearchHandle<T = Option<Move>> {
+    stop_flag: Arc<AtomicBool>,
+    shared: Arc<Shared<T>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl<T: Send + 'static> SearchHandle<T> {
+    /// Run `search` on a worker thread; `stop_flag` must be the flag it polls.
+    /// The worker reports info lines to the calling thread's info sink.
+    pub(crate) fn spawn<F>(stop_flag: Arc<AtomicBool>, search: F) -> Self
+    where
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let shared = Shared::new(None);
+        let worker_shared = shared.clone();
+        let info_sink = info_sink::thread_info_sink();
+        let worker = thread::spawn(move || {
+            info_sink::set_thread_info_sink(info_sink);
+            let outcome = panic::catch_unwind(AssertUnwindSafe(search));
+            if let Ok(mut state) = worker_shared.state.lock() {
+                state.outcome = Some(outcome);
+                if let Some(waker) = state.waker.take() {
+                    waker.wake();
+                }
+            }
+            worker_shared.finished.notify_all();
+        });
+
+        Self { stop_flag, shared, worker: Some(worker) }
+    }
+}
+
+impl<T> SearchHandle<T> {
+    /// A handle for a search that was settled without a worker thread
+    pub(crate) fn settled(stop_flag: Arc<AtomicBool>, outcome: thread::Result<T>) -> Self {
+        Self { stop_flag, shared: Shared::new(Some(outcome)), worker: None }
+    }
+
+    /// Ask the search to stop; it finishes with the best move found so far
+    pub fn cancel(&self) {
+        self.stop_flag.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.shared.state.lock().map(|state| state.outcome.is_some()).unwrap_or(true)
+    }
+
+    /// Block until the search finishes or `timeout` passes; true if it finished
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        let Ok(state) = self.shared.state.lock() else {
+            return true;
+        };
+        self.shared
+            .finished
+            .wait_timeout_while(state, timeout, |state| state.outcome.is_none())
+            .map(|(state, _)| state.outcome.is_some())
+            .unwrap_or(true)
+    }
+
+    /// Block until the search finishes; `Err` carries the payload if it panicked
+    pub fn join(mut self) -> thread::Result<T> {
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+        let outcome = match self.shared.state.lock() {
+            Ok(mut state) => state.outcome.take(),
+            Err(_) => None,
+        };
+        outcome.unwrap_or_else(|| Err(Box::new("search state poisoned")))
+    }
+}
+
+impl<T: Clone> SearchHandle<T> {
+    /// The result if the search has finished without panicking, without blocking
+    pub fn try_result(&self) -> Option<T> {
+        let state = self.shared.state.lock().ok()?;
+        match &state.outcome {
+            Some(Ok(result)) => Some(result.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl SearchHandle {
+    /// Block until the search finishes and return its best move
+    pub fn wait(self) -> Option<Move> {
+        self.join().ok().flatten()
+    }
+}
+
+impl Future for SearchHandle {
+    type Output = Option<Move>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let Ok(mut state) = self.shared.state.lock() else {
+            return Poll::Ready(None);
+        };
+        match &state.outcome {
+            Some(Ok(best_move)) => Poll::Ready(best_move.clone()),
+            Some(Err(_)) => Poll::Ready(None),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ShogiEngine;
+
+    #[test]
+    fn test_search_reports_progress_and_finishes() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let mut engine = ShogiEngine::new();
+
+        let handle = engine.start_search(3, 10_000, move |progress: &SearchProgress| {
+            sink.lock().unwrap().push(progress.depth);
+        });
+        let best_move = handle.wait();
+
+        assert!(best_move.is_some());
+        let depths = reports.lock().unwrap();
+        assert!(!depths.is_empty());
+        assert!(depths.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn test_cancel_stops_search() {
+        let mut engine = ShogiEngine::new();
+        let handle = engine.start_search(0, 600_000, |_: &SearchProgress| {});
+        assert!(!handle.wait_timeout(Duration::from_millis(200)));
+        handle.cancel();
+
+        assert!(handle.wait_timeout(Duration::from_secs(30)));
+        assert!(handle.wait().is_some());
+    }
+}
@@ -9,9 +9,11 @@ use crate::evaluation::*;
 use crate::moves::*;
 use crate::search::advanced_statistics::AdvancedStatisticsManager;
 use crate::search::error_handling::{ComprehensiveErrorHandler, TranspositionResult};
+use crate::search::search_engine::make_move_with_hand;
 use crate::search::shogi_hash::*;
 use crate::search::thread_safe_table::{ThreadSafeStatsSnapshot, ThreadSafeTranspositionTable};
 use crate::search::transposition_config::TranspositionConfig;
+use crate::search::tt_move_check::check_tt_move;
 use crate::types::board::CapturedPieces;
 use crate::types::core::{Move, Player};
 use crate::types::search::TranspositionFlag;
@@ -153,11 +155,8 @@ impl EnhancedSearchEngine {
 
             // Create board copy and make move
             let mut new_board = board.clone();
-            let mut new_captured = captured_pieces.clone();
-
-            if let Some(captured_piece) = new_board.make_move(mv) {
-                new_captured.add_piece(captured_piece.piece_type, captured_piece.player);
-            }
+            let (_, new_captured) =
+                make_move_with_hand(&mut new_board, captured_pieces, mv, player);
 
             // Recursive search with negated bounds
             let score = -self.negamax_with_tt(
@@ -332,11 +331,8 @@ impl EnhancedSearchEngine {
 
             // Create board copy and make move
             let mut new_board = board.clone();
-            let mut new_captured = captured_pieces.clone();
-
-            if let Some(captured_piece) = new_board.make_move(mv) {
-                new_captured.add_piece(captured_piece.piece_type, captured_piece.player);
-            }
+            let (_, new_captured) =
+                make_move_with_hand(&mut new_board, captured_pieces, mv, player);
 
             let score = -self.quiescence_search_with_tt(
                 &mut new_board,
@@ -385,7 +381,10 @@ impl EnhancedSearchEngine {
             );
 
             if let Some(entry) = self.transposition_table.probe(position_hash, 255) {
-                if let Some(best_move) = entry.best_move {
+                let best_move = entry.best_move.filter(|mv| {
+                    check_tt_move(&current_board, &current_captured, current_player, mv).is_ok()
+                });
+                if let Some(best_move) = best_move {
                     pv.push(best_move.clone());
 
                     // Make the move to continue the PV
@@ -0,0 +1,88 @@
+//! Search Watchdog
+//!
+//! A panic inside the search must not take the engine process down mid-game.
+//! `ShogiEngine::get_best_move_guarded` runs the search under `catch_unwind`;
+//! this module turns a caught panic into a `SearchPanic` carrying the message,
+//! the position it happened in and a legal fallback move to play instead.
+
+use crate::bitboards::BitboardBoard;
+use crate::moves::MoveGenerator;
+use crate::types::{CapturedPieces, Move, Player};
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+
+/// A search that panicked
+#[derive(Debug, Clone)]
+pub struct SearchPanic {
+    /// Panic message
+    pub message: String,
+    /// Position searched, as SFEN
+    pub sfen: String,
+    /// Legal move to play instead; `None` when there is none
+    pub fallback: Option<Move>,
+}
+
+impl SearchPanic {
+    /// `info string` reporting the panic to the GUI
+    pub fn info_line(&self) -> String {
+        format!("info string error search panicked: {}", self.message)
+    }
+}
+
+/// Text of a panic payload from `catch_unwind`
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+/// Move to play after a failed search: a capture of the most valuable piece
+/// available, otherwise the first legal move. Move generation is itself
+/// guarded, since the panic may have come from there.
+pub fn fallback_move(
+    board: &BitboardBoard,
+    captured_pieces: &CapturedPieces,
+    player: Player,
+) -> Option<Move> {
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        let legal_moves =
+            MoveGenerator::new().generate_legal_moves(board, player, captured_pieces);
+        let best_capture = legal_moves
+            .iter()
+            .filter(|mv| mv.is_capture)
+            .max_by_key(|mv| {
+                mv.captured_piece
+                    .as_ref()
+                    .map_or(0, |piece| piece.piece_type.base_value())
+            })
+            .cloned();
+        best_capture.or_else(|| legal_moves.into_iter().next())
+    }))
+    .ok()
+    .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_panic_message_and_fallback_move() {
+        let payload = panic::catch_unwind(|| panic!("search exploded at depth {}", 7)).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "search exploded at depth 7");
+        let payload = panic::catch_unwind(|| panic!("static message")).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "static message");
+
+        let board = BitboardBoard::new();
+        let captured = CapturedPieces::new();
+        let fallback = fallback_move(&board, &captured, Player::Black).unwrap();
+        let legal = MoveGenerator::new().generate_legal_moves(&board, Player::Black, &captured);
+        assert!(legal
+            .iter()
+            .any(|mv| mv.to_usi_string() == fallback.to_usi_string()));
+    }
+}
@@ -4,7 +4,10 @@
 //! Extracted from `search_engine.rs` as part of Task 1.0: File Modularization and Structure Improvements.
 
 use crate::types::search::CoreSearchMetrics;
+use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 /// Global aggregate of nodes searched across all threads for live reporting.
 pub static GLOBAL_NODES_SEARCHED: AtomicU64 = AtomicU64::new(0);
@@ -12,6 +15,207 @@ pub static GLOBAL_NODES_SEARCHED: AtomicU64 = AtomicU64::new(0);
 /// Global maximum search depth reached (seldepth) across all threads for live reporting.
 pub static GLOBAL_SELDEPTH: AtomicU64 = AtomicU64::new(0);
 
+/// Root move and transposition table occupancy of one engine's running search,
+/// written by the search and read by its info sender thread. Each engine has its
+/// own, so engines searching side by side don't report each other's moves.
+#[derive(Debug, Default)]
+pub struct LiveProgress {
+    /// 1-based index of the root move currently being searched (0 = none yet)
+    currmove_number: AtomicU64,
+    /// USI string of the root move currently being searched
+    currmove: Mutex<String>,
+    /// Transposition table occupancy in permille
+    hashfull: AtomicU64,
+}
+
+impl LiveProgress {
+    /// Publish the root move the search is working on for `info currmove` reporting.
+    pub fn publish_currmove(&self, move_usi: String, move_number: usize) {
+        if let Ok(mut current) = self.currmove.lock() {
+            *current = move_usi;
+        }
+        self.currmove_number.store(move_number as u64, Ordering::Relaxed);
+    }
+
+    /// Snapshot of the root move currently being searched, if any.
+    pub fn currmove(&self) -> Option<(String, u64)> {
+        let number = self.currmove_number.load(Ordering::Relaxed);
+        if number == 0 {
+            return None;
+        }
+        self.currmove
+            .lock()
+            .ok()
+            .filter(|current| !current.is_empty())
+            .map(|current| (current.clone(), number))
+    }
+
+    /// Reset live root-move reporting at the start of an iteration.
+    pub fn reset_currmove(&self) {
+        self.currmove_number.store(0, Ordering::Relaxed);
+        if let Ok(mut current) = self.currmove.lock() {
+            current.clear();
+        }
+    }
+
+    pub fn publish_hashfull(&self, permille: u32) {
+        self.hashfull.store(u64::from(permille), Ordering::Relaxed);
+    }
+
+    pub fn hashfull(&self) -> u64 {
+        self.hashfull.load(Ordering::Relaxed)
+    }
+}
+
+/// Result of searching one root move in one iteration
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RootMoveIteration {
+    /// Nominal depth of the iteration
+    pub depth: u8,
+    /// Depth the move was actually searched to (lower when staged deepening reduced it)
+    pub searched_depth: u8,
+    pub score: i32,
+}
+
+/// Statistics of one root move over the iterations of the most recent search
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RootMoveReport {
+    pub move_usi: String,
+    pub nodes: u64,
+    pub score_history: Vec<RootMoveIteration>,
+    /// Consecutive iterations in which the move scored far below the best
+    pub refuted_iterations: u8,
+}
+
+/// Search internals of the most recent search, for the GUI's engine dashboard.
+///
+/// Counters cover only the last search: they are taken as the difference from a
+/// baseline captured when the search started.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchStatisticsReport {
+    pub nodes: u64,
+    pub qnodes: u64,
+    pub tt_probes: u64,
+    pub tt_hits: u64,
+    /// Percentage of TT probes that hit
+    pub tt_hit_rate: f64,
+    pub beta_cutoffs: u64,
+    /// Beta cutoffs indexed by ply from the root
+    pub cutoffs_per_ply: Vec<u64>,
+    pub null_move_attempts: u64,
+    pub null_move_cutoffs: u64,
+    pub lmr_reductions: u64,
+    pub lmr_researches: u64,
+    /// Wall-clock time of each completed iteration, indexed by depth - 1
+    pub iteration_times_ms: Vec<u32>,
+    /// Transposition table occupancy in permille
+    pub hashfull: u32,
+    /// Per-root-move nodes and score history, in generation order
+    #[serde(default)]
+    pub root_moves: Vec<RootMoveReport>,
+    /// Nodes where the drop limiter left quiet drops out under time pressure
+    #[serde(default)]
+    pub drop_limited_nodes: u64,
+    /// Quiet drops the limiter left out
+    #[serde(default)]
+    pub drops_pruned: u64,
+    /// Nodes where the limiter would have kept too few moves and searched all of them
+    #[serde(default)]
+    pub drop_limiter_fallbacks: u64,
+    /// TT best moves checked for pseudo-legality before use
+    #[serde(default)]
+    pub tt_moves_checked: u64,
+    /// TT best moves thrown away as belonging to another position
+    #[serde(default)]
+    pub tt_moves_rejected: u64,
+    /// Deepest ply the main search entered since the engine started
+    #[serde(default)]
+    pub max_ply_reached: u16,
+    /// Most stack the main search used, in bytes, since the engine started
+    #[serde(default)]
+    pub max_stack_bytes: u64,
+    /// Nodes scored statically because they were past the ply limit
+    #[serde(default)]
+    pub ply_limit_cutoffs: u64,
+}
+
+impl SearchStatisticsReport {
+    /// Counters accumulated since `baseline` was captured. Per-search vectors and
+    /// gauges are taken from `self` unchanged.
+    pub fn since(&self, baseline: &SearchStatisticsReport) -> SearchStatisticsReport {
+        let tt_probes = self.tt_probes.saturating_sub(baseline.tt_probes);
+        let tt_hits = self.tt_hits.saturating_sub(baseline.tt_hits);
+        SearchStatisticsReport {
+            nodes: self.nodes.saturating_sub(baseline.nodes),
+            qnodes: self.qnodes.saturating_sub(baseline.qnodes),
+            tt_probes,
+            tt_hits,
+            tt_hit_rate: if tt_probes > 0 {
+                tt_hits as f64 / tt_probes as f64 * 100.0
+            } else {
+                0.0
+            },
+            beta_cutoffs: self.beta_cutoffs.saturating_sub(baseline.beta_cutoffs),
+            cutoffs_per_ply: self.cutoffs_per_ply.clone(),
+            null_move_attempts: self
+                .null_move_attempts
+                .saturating_sub(baseline.null_move_attempts),
+            null_move_cutoffs: self.null_move_cutoffs.saturating_sub(baseline.null_move_cutoffs),
+            lmr_reductions: self.lmr_reductions.saturating_sub(baseline.lmr_reductions),
+            lmr_researches: self.lmr_researches.saturating_sub(baseline.lmr_researches),
+            iteration_times_ms: self.iteration_times_ms.clone(),
+            hashfull: self.hashfull,
+            root_moves: self.root_moves.clone(),
+            drop_limited_nodes: self.drop_limited_nodes.saturating_sub(baseline.drop_limited_nodes),
+            drops_pruned: self.drops_pruned.saturating_sub(baseline.drops_pruned),
+            drop_limiter_fallbacks: self
+                .drop_limiter_fallbacks
+                .saturating_sub(baseline.drop_limiter_fallbacks),
+            tt_moves_checked: self.tt_moves_checked.saturating_sub(baseline.tt_moves_checked),
+            tt_moves_rejected: self.tt_moves_rejected.saturating_sub(baseline.tt_moves_rejected),
+            max_ply_reached: self.max_ply_reached,
+            max_stack_bytes: self.max_stack_bytes,
+            ply_limit_cutoffs: self.ply_limit_cutoffs.saturating_sub(baseline.ply_limit_cutoffs),
+        }
+    }
+}
+
+/// Limits how often a kind of live `info` line is written so GUIs aren't flooded.
+#[derive(Debug, Clone)]
+pub struct InfoRateLimiter {
+    interval: Duration,
+    last_emit: Option<Instant>,
+}
+
+impl InfoRateLimiter {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_emit: None,
+        }
+    }
+
+    /// Start limiting from now, so the first line is only written after one interval.
+    pub fn starting_now(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_emit: Some(Instant::now()),
+        }
+    }
+
+    /// Returns true (and records the emission) if enough time has passed since the last line.
+    pub fn try_emit(&mut self) -> bool {
+        let now = Instant::now();
+        match self.last_emit {
+            Some(last) if now.duration_since(last) < self.interval => false,
+            _ => {
+                self.last_emit = Some(now);
+                true
+            }
+        }
+    }
+}
+
 // Global contention metrics for shared TT
 pub static TT_TRY_READS: AtomicU64 = AtomicU64::new(0);
 pub static TT_TRY_READ_SUCCESSES: AtomicU64 = AtomicU64::new(0);
@@ -199,6 +403,45 @@ mod tests {
         assert_eq!(stats.get_core_metrics().total_nodes, 0);
     }
 
+    #[test]
+    fn test_statistics_report_since_baseline() {
+        let baseline = SearchStatisticsReport {
+            nodes: 100,
+            tt_probes: 50,
+            tt_hits: 10,
+            ..SearchStatisticsReport::default()
+        };
+        let current = SearchStatisticsReport {
+            nodes: 400,
+            tt_probes: 150,
+            tt_hits: 60,
+            cutoffs_per_ply: vec![3, 5],
+            hashfull: 12,
+            ..SearchStatisticsReport::default()
+        };
+        let report = current.since(&baseline);
+        assert_eq!(report.nodes, 300);
+        assert_eq!(report.tt_probes, 100);
+        assert_eq!(report.tt_hits, 50);
+        assert!((report.tt_hit_rate - 50.0).abs() < f64::EPSILON);
+        assert_eq!(report.cutoffs_per_ply, vec![3, 5]);
+        assert_eq!(report.hashfull, 12);
+    }
+
+    #[test]
+    fn test_info_rate_limiter() {
+        let mut limiter = InfoRateLimiter::new(Duration::from_secs(60));
+        assert!(limiter.try_emit());
+        assert!(!limiter.try_emit());
+
+        let mut delayed = InfoRateLimiter::starting_now(Duration::from_secs(60));
+        assert!(!delayed.try_emit());
+
+        let mut unlimited = InfoRateLimiter::new(Duration::ZERO);
+        assert!(unlimited.try_emit());
+        assert!(unlimited.try_emit());
+    }
+
     #[test]
     fn test_increment_nodes() {
         let mut stats = SearchStatistics::new();
@@ -12,6 +12,8 @@
 //! - **Statistics Tracking**: Comprehensive performance and usage statistics (opt-in)
 //! - **Robust Under Failure**: Recovers from poisoned synchronization primitives without
 //!   crashing the engine
+//! - **Key Verification**: Optionally keeps a second, independent key per slot and
+//!   counts the hits it contradicts, to measure real 64-bit collision rates
 //!
 //! # Usage
 //!
@@ -59,6 +61,7 @@
 
 use crate::bitboards::BitboardBoard;
 use crate::opening_book::OpeningBook;
+use crate::search::advanced_statistics::{KeyVerificationMonitor, KeyVerificationStats};
 use crate::search::cache_management::CacheManager;
 use crate::search::replacement_policies::ReplacementDecision;
 use crate::search::replacement_policies::ReplacementPolicyHandler;
@@ -69,8 +72,10 @@ use crate::types::search::EntrySource;
 use crate::types::search::TranspositionFlag;
 use crate::types::transposition::TranspositionEntry;
 use log::warn;
-use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
-use std::sync::{Arc, LockResult, Mutex, MutexGuard, RwLock, RwLockWriteGuard};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{
+    Arc, LockResult, Mutex, MutexGuard, OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard,
+};
 
 #[cfg(all(feature = "tt-prefetch", target_arch = "x86"))]
 use core::arch::x86::{_mm_prefetch, _MM_HINT_T2};
@@ -366,6 +371,12 @@ pub struct ThreadSafeTranspositionTable {
     stats: Arc<Mutex<ThreadSafeStats>>,
     /// Number of poison recovery events observed
     poison_recoveries: AtomicU64,
+    /// Whether probes are checked against the second key
+    key_verification_enabled: AtomicBool,
+    /// Second key per slot, allocated the first time verification is enabled
+    verification_keys: OnceLock<Vec<AtomicU64>>,
+    /// Verified probes and the collisions found among them
+    key_verification: KeyVerificationMonitor,
 }
 
 /// Thread-safe statistics
@@ -429,6 +440,9 @@ impl ThreadSafeTranspositionTable {
             ))),
             stats: Arc::new(Mutex::new(ThreadSafeStats::default())),
             poison_recoveries: AtomicU64::new(0),
+            key_verification_enabled: AtomicBool::new(false),
+            verification_keys: OnceLock::new(),
+            key_verification: KeyVerificationMonitor::new(),
         }
     }
 
@@ -526,19 +540,79 @@ impl ThreadSafeTranspositionTable {
     /// synchronization based on the thread mode.
     #[inline(always)]
     pub fn store(&self, entry: TranspositionEntry) {
+        // An entry without a second key must not inherit the one in its slot
+        self.store_verified(entry, 0);
+    }
+
+    /// Store an entry together with the position's second key (0 for none),
+    /// kept only while key verification is enabled
+    #[inline(always)]
+    pub fn store_verified(&self, entry: TranspositionEntry, verification_key: u64) {
         let hash = entry.hash_key;
         let index = self.get_index(hash);
         let is_multi_threaded = self.thread_mode.is_multi_threaded();
 
         if is_multi_threaded {
-            self.store_with_synchronization(index, entry);
+            self.store_with_synchronization(index, entry, verification_key);
         } else {
-            self.store_atomic_only(index, entry);
+            self.store_atomic_only(index, entry, verification_key);
         }
 
         self.increment_stores();
     }
 
+    /// Check a probe hit for `hash` against the second key stored with the
+    /// entry. Returns false when the entry was stored for another position (a
+    /// collision); true when it matches, or when there is nothing to compare.
+    pub fn verify_probe(&self, hash: u64, verification_key: u64) -> bool {
+        if !self.key_verification_enabled() {
+            return true;
+        }
+        let Some(keys) = self.verification_keys.get() else {
+            return true;
+        };
+        let index = self.get_index(hash);
+        // Read the key under the bucket lock so it pairs with the entry beside it
+        let bucket_lock = Arc::clone(self.get_bucket_lock(hash));
+        let _read_guard = self.thread_mode.is_multi_threaded().then(|| {
+            self.recover_read_guard(bucket_lock.read(), || {
+                format!("bucket lock for hash 0x{:016x}", hash)
+            })
+        });
+        let stored = keys[index].load(Ordering::Acquire);
+        if stored == 0 || self.entries[index].hash_key.load(Ordering::Acquire) != hash {
+            return true;
+        }
+        let collided = stored != verification_key;
+        self.key_verification.record(collided);
+        if collided {
+            warn!(
+                "[TT] Key collision at slot {}: hash 0x{:016x} stored for another position",
+                index, hash
+            );
+        }
+        !collided
+    }
+
+    /// Turn key verification on or off. The key array (8 bytes per slot) is
+    /// allocated on first use and kept; entries stored before verification was
+    /// enabled are not checked.
+    pub fn set_key_verification(&self, enabled: bool) {
+        if enabled {
+            self.verification_keys
+                .get_or_init(|| (0..self.size).map(|_| AtomicU64::new(0)).collect());
+        }
+        self.key_verification_enabled.store(enabled, Ordering::Release);
+    }
+
+    pub fn key_verification_enabled(&self) -> bool {
+        self.key_verification_enabled.load(Ordering::Acquire)
+    }
+
+    pub fn key_verification_stats(&self) -> KeyVerificationStats {
+        self.key_verification.get_stats()
+    }
+
     fn recover_write_guard<'a, T, F>(
         &self,
         lock_result: LockResult<RwLockWriteGuard<'a, T>>,
@@ -557,6 +631,24 @@ impl ThreadSafeTranspositionTable {
         }
     }
 
+    fn recover_read_guard<'a, T, F>(
+        &self,
+        lock_result: LockResult<RwLockReadGuard<'a, T>>,
+        context: F,
+    ) -> RwLockReadGuard<'a, T>
+    where
+        F: FnOnce() -> String,
+    {
+        match lock_result {
+            Ok(guard) => guard,
+            Err(poisoned) => {
+                let message = context();
+                self.record_poison_recovery(&message);
+                poisoned.into_inner()
+            }
+        }
+    }
+
     fn recover_mutex_guard<'a, T, F>(
         &self,
         lock_result: LockResult<MutexGuard<'a, T>>,
@@ -609,17 +701,40 @@ impl ThreadSafeTranspositionTable {
     /// Uses bucketed locks for better parallel write performance.
     /// Only locks the specific bucket for this hash, not the entire table.
     #[inline(always)]
-    fn store_with_synchronization(&self, index: usize, entry: TranspositionEntry) {
+    fn store_with_synchronization(
+        &self,
+        index: usize,
+        entry: TranspositionEntry,
+        verification_key: u64,
+    ) -> bool {
         // Get the bucket lock for this hash (clone Arc to avoid borrow issues)
         let bucket_lock = Arc::clone(self.get_bucket_lock(entry.hash_key));
         let _write_guard = self.recover_write_guard(bucket_lock.write(), || {
             format!("bucket lock for hash 0x{:016x}", entry.hash_key)
         });
 
-        self.store_entry_core(index, entry);
+        self.store_entry_core(index, entry, verification_key)
+    }
+
+    /// Store `entry` at `index` if the replacement policy allows; returns whether it was written.
+    /// The verification key is written alongside, inside the caller's bucket lock.
+    fn store_entry_core(
+        &self,
+        index: usize,
+        entry: TranspositionEntry,
+        verification_key: u64,
+    ) -> bool {
+        let stored = self.store_entry_policy(index, entry);
+        if stored {
+            if let Some(keys) = self.verification_keys.get() {
+                keys[index].store(verification_key, Ordering::Release);
+            }
+        }
+        stored
     }
 
-    fn store_entry_core(&self, index: usize, entry: TranspositionEntry) {
+    /// Write `entry` at `index` if the replacement policy allows
+    fn store_entry_policy(&self, index: usize, entry: TranspositionEntry) -> bool {
         // Check if we should replace the existing entry
         let current_hash = self.entries[index].hash_key.load(Ordering::Acquire);
         if current_hash != 0 {
@@ -645,15 +760,20 @@ impl ThreadSafeTranspositionTable {
                     Self::store_atomic_entry_static(&self.entries[index], &entry);
                     self.increment_replacements();
                     self.increment_atomic_operations();
+                    true
                 }
                 ReplacementDecision::Keep => {
                     // Keep existing entry
+                    false
                 }
                 ReplacementDecision::ReplaceIfExact => {
                     if entry.is_exact() && !current_entry.is_exact() {
                         Self::store_atomic_entry_static(&self.entries[index], &entry);
                         self.increment_replacements();
                         self.increment_atomic_operations();
+                        true
+                    } else {
+                        false
                     }
                 }
             }
@@ -661,13 +781,19 @@ impl ThreadSafeTranspositionTable {
             // Empty slot, store directly
             Self::store_atomic_entry_static(&self.entries[index], &entry);
             self.increment_atomic_operations();
+            true
         }
     }
 
     /// Store with atomic operations only (single-threaded mode)
     #[inline(always)]
-    fn store_atomic_only(&self, index: usize, entry: TranspositionEntry) {
-        self.store_entry_core(index, entry);
+    fn store_atomic_only(
+        &self,
+        index: usize,
+        entry: TranspositionEntry,
+        verification_key: u64,
+    ) -> bool {
+        self.store_entry_core(index, entry, verification_key)
     }
 
     /// Store a batch of entries, grouping by bucket to minimise lock acquisitions.
@@ -710,7 +836,7 @@ impl ThreadSafeTranspositionTable {
             });
             for entry in bucket_entries {
                 let index = self.get_index(entry.hash_key);
-                self.store_entry_core(index, entry);
+                self.store_entry_core(index, entry, 0);
                 self.increment_stores();
             }
         }
@@ -719,7 +845,7 @@ impl ThreadSafeTranspositionTable {
     /// Prefill the table using entries from an opening book.
     ///
     /// Returns the number of entries inserted.
-    pub fn prefill_from_book(&mut self, book: &mut OpeningBook, depth: u8) -> usize {
+    pub fn prefill_from_book(&self, book: &mut OpeningBook, depth: u8) -> usize {
         let hasher = ZobristHasher::new();
         let mut inserted = 0usize;
 
@@ -796,6 +922,22 @@ impl ThreadSafeTranspositionTable {
         self.size
     }
 
+    /// Occupancy in permille, as reported by USI `info hashfull`.
+    ///
+    /// Samples the first 1000 slots rather than scanning the whole table, which is
+    /// accurate enough because entries are spread uniformly by hash.
+    pub fn hashfull(&self) -> u32 {
+        let sample = self.entries.len().min(1000);
+        if sample == 0 {
+            return 0;
+        }
+        let used = self.entries[..sample]
+            .iter()
+            .filter(|entry| entry.hash_key.load(Ordering::Relaxed) != 0)
+            .count();
+        (used * 1000 / sample) as u32
+    }
+
     /// Get the number of lock buckets
     ///
     /// Returns the number of independent lock buckets used for parallel write operations.
@@ -805,18 +947,23 @@ impl ThreadSafeTranspositionTable {
     }
 
     /// Clear the entire table
-    pub fn clear(&mut self) {
+    pub fn clear(&self) {
         if self.thread_mode.is_multi_threaded() {
             self.clear_with_synchronization();
         } else {
             self.clear_atomic_only();
         }
+        if let Some(keys) = self.verification_keys.get() {
+            for key in keys {
+                key.store(0, Ordering::Release);
+            }
+        }
     }
 
     /// Clear with synchronization
     ///
     /// Acquires all bucket locks to ensure no concurrent writes during clear.
-    fn clear_with_synchronization(&mut self) {
+    fn clear_with_synchronization(&self) {
         // Clone all bucket locks and acquire them to prevent writes during clear
         let locks: Vec<_> = self
             .bucket_locks
@@ -834,18 +981,18 @@ impl ThreadSafeTranspositionTable {
             .collect();
 
         // Clear entries directly here to avoid borrowing issues
-        for entry in &mut self.entries {
+        for entry in &self.entries {
             entry.hash_key.store(0, Ordering::Release);
-            entry.packed_data = AtomicPackedEntry::empty();
+            entry.packed_data.data.store(0, Ordering::Release);
             entry.age.store(0, Ordering::Release);
         }
     }
 
     /// Clear using atomic operations only
-    fn clear_atomic_only(&mut self) {
-        for entry in &mut self.entries {
+    fn clear_atomic_only(&self) {
+        for entry in &self.entries {
             entry.hash_key.store(0, Ordering::Release);
-            entry.packed_data = AtomicPackedEntry::empty();
+            entry.packed_data.data.store(0, Ordering::Release);
             entry.age.store(0, Ordering::Release);
         }
     }
@@ -1557,4 +1704,27 @@ mod tests {
         assert_eq!(retrieved.score, entry.score);
         assert_eq!(retrieved.depth, entry.depth);
     }
+
+    #[test]
+    fn test_key_verification_flags_collisions() {
+        let table = ThreadSafeTranspositionTable::new(create_test_config());
+        let entry = create_test_entry(100, 5, TranspositionFlag::Exact, 1, 0x2000);
+
+        // Off by default: nothing is checked
+        table.store_verified(entry.clone(), 7);
+        assert!(table.verify_probe(0x2000, 8));
+        assert_eq!(table.key_verification_stats().verified_probes, 0);
+
+        table.set_key_verification(true);
+        table.store_verified(entry.clone(), 7);
+        assert!(table.verify_probe(0x2000, 7));
+        assert!(!table.verify_probe(0x2000, 8));
+        let stats = table.key_verification_stats();
+        assert_eq!((stats.verified_probes, stats.collisions), (2, 1));
+
+        // A plain store leaves nothing to compare against
+        table.store(entry);
+        assert!(table.verify_probe(0x2000, 8));
+        assert_eq!(table.key_verification_stats().verified_probes, 2);
+    }
 }
@@ -10,6 +10,36 @@ use crate::types::search::{
     TimePressureThresholds,
 };
 
+/// Iterations shallower than this are too noisy for their instability to earn more time
+pub const INSTABILITY_MIN_DEPTH: u8 = 4;
+
+/// Total extension a search may receive, as a fraction of its allotted time
+pub const INSTABILITY_EXTENSION_BUDGET: f64 = 0.5;
+
+/// Extension for a best move that changed in the last iteration, as a fraction of the allotted time
+pub const BEST_MOVE_CHANGE_EXTENSION: f64 = 0.2;
+
+/// Extension for a sharp score drop (fail-low), as a fraction of the allotted time
+pub const SCORE_DROP_EXTENSION: f64 = 0.3;
+
+/// Why iterative deepening asked for more time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchInstability {
+    /// The best move of the last iteration differs from the one before
+    BestMoveChange,
+    /// The score fell by this many centipawns against the previous iteration
+    ScoreDrop(i32),
+}
+
+impl SearchInstability {
+    fn extension_fraction(self) -> f64 {
+        match self {
+            SearchInstability::BestMoveChange => BEST_MOVE_CHANGE_EXTENSION,
+            SearchInstability::ScoreDrop(_) => SCORE_DROP_EXTENSION,
+        }
+    }
+}
+
 /// Time management functionality for search engine
 #[derive(Debug, Clone)]
 pub struct TimeManager {
@@ -17,6 +47,8 @@ pub struct TimeManager {
     time_budget_stats: TimeBudgetStats,
     time_pressure_thresholds: TimePressureThresholds,
     time_check_node_counter: u32,
+    /// Time granted on top of the allotted time in the current search
+    instability_extension_ms: u32,
 }
 
 impl TimeManager {
@@ -30,9 +62,42 @@ impl TimeManager {
             time_budget_stats: TimeBudgetStats::default(),
             time_pressure_thresholds,
             time_check_node_counter: 0,
+            instability_extension_ms: 0,
         }
     }
 
+    /// Forget the extensions granted to the previous search
+    pub fn begin_search(&mut self) {
+        self.instability_extension_ms = 0;
+    }
+
+    /// Grant extra time for an unstable search and return the milliseconds added.
+    ///
+    /// `allotted_ms` is the time the search started with and `hard_limit_ms` the most
+    /// it may ever use; the extensions of one search never exceed
+    /// `INSTABILITY_EXTENSION_BUDGET` of the allotted time nor go past the hard limit.
+    pub fn extend_for_instability(
+        &mut self,
+        instability: SearchInstability,
+        allotted_ms: u32,
+        hard_limit_ms: u32,
+    ) -> u32 {
+        let budget_ms = (allotted_ms as f64 * INSTABILITY_EXTENSION_BUDGET) as u32;
+        let headroom_ms = hard_limit_ms
+            .saturating_sub(allotted_ms)
+            .min(budget_ms)
+            .saturating_sub(self.instability_extension_ms);
+        let requested_ms = (allotted_ms as f64 * instability.extension_fraction()) as u32;
+        let granted_ms = requested_ms.min(headroom_ms);
+        self.instability_extension_ms += granted_ms;
+        granted_ms
+    }
+
+    /// Time granted on top of the allotted time in the current search
+    pub fn instability_extension_ms(&self) -> u32 {
+        self.instability_extension_ms
+    }
+
     /// Calculate time pressure level based on remaining time
     pub fn calculate_time_pressure_level(
         &self,
@@ -318,5 +383,37 @@ mod tests {
         let stats = manager.get_time_budget_stats();
         assert!(stats.depths_completed >= 2);
     }
+
+    #[test]
+    fn test_instability_extension_stays_within_budget() {
+        let mut manager =
+            TimeManager::new(TimeManagementConfig::default(), TimePressureThresholds::default());
+
+        assert_eq!(
+            manager.extend_for_instability(SearchInstability::BestMoveChange, 1000, 10_000),
+            200
+        );
+        assert_eq!(
+            manager.extend_for_instability(SearchInstability::ScoreDrop(120), 1000, 10_000),
+            300
+        );
+        // Only 500ms of budget in total
+        assert_eq!(
+            manager.extend_for_instability(SearchInstability::ScoreDrop(120), 1000, 10_000),
+            0
+        );
+        assert_eq!(manager.instability_extension_ms(), 500);
+
+        // A new search gets a fresh budget, still capped by the hard limit
+        manager.begin_search();
+        assert_eq!(
+            manager.extend_for_instability(SearchInstability::ScoreDrop(80), 1000, 1100),
+            100
+        );
+        assert_eq!(
+            manager.extend_for_instability(SearchInstability::BestMoveChange, 1000, 1100),
+            0
+        );
+    }
 }
 
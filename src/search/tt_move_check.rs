@@ -0,0 +1,191 @@
+//! TT Move Check
+//!
+//! Transposition table entries are found by hash alone, so a key collision (or
+//! an entry overwritten by another thread while it was read) can hand the
+//! search a best move from a different position. Such a move must never reach
+//! `make_move`, which trusts what it is given: it moves whatever stands on the
+//! source square, drops pieces that are not in hand and overwrites pieces it
+//! was not told to capture.
+//!
+//! Before a TT move is played or trusted it goes through three stages,
+//! cheapest first and without generating moves:
+//! 1. ownership: the mover is the side to move, the piece on the source square
+//!    is the one the move names, and the target holds no own piece and agrees
+//!    with the capture flag;
+//! 2. drop rules: the piece is in hand, the target is empty and neither a dead
+//!    square nor a second pawn on the file;
+//! 3. reach: the piece attacks the target from its square, sliders included,
+//!    and promotes only where it may.
+//!
+//! A move that passes is pseudo-legal: it may still leave the king in check.
+
+use crate::bitboards::BitboardBoard;
+use crate::drop_rules::{has_unpromoted_pawn_on_file, is_dead_square};
+use crate::types::{is_bit_set, CapturedPieces, Move, PieceType, Player};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The stage a TT move failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TtMoveRejection {
+    Ownership,
+    DropRule,
+    Reach,
+}
+
+/// Whether `mv` is pseudo-legal for `player` in this position
+pub fn check_tt_move(
+    board: &BitboardBoard,
+    captured_pieces: &CapturedPieces,
+    player: Player,
+    mv: &Move,
+) -> Result<(), TtMoveRejection> {
+    if mv.player != player || board.is_occupied_by_player(mv.to, player) {
+        return Err(TtMoveRejection::Ownership);
+    }
+    let target_occupied = board.is_square_occupied(mv.to);
+
+    let Some(from) = mv.from else {
+        let droppable = mv.piece_type.unpromoted_version().is_none()
+            && mv.piece_type != PieceType::King
+            && !mv.is_promotion
+            && !target_occupied
+            && captured_pieces.count(mv.piece_type, player) > 0
+            && !is_dead_square(mv.piece_type, mv.to, player)
+            && !(mv.piece_type == PieceType::Pawn
+                && has_unpromoted_pawn_on_file(board, mv.to.col, player));
+        return if droppable { Ok(()) } else { Err(TtMoveRejection::DropRule) };
+    };
+
+    match board.get_piece(from) {
+        Some(piece) if piece.player == player && piece.piece_type == mv.piece_type => {}
+        _ => return Err(TtMoveRejection::Ownership),
+    }
+    if mv.is_capture != target_occupied {
+        return Err(TtMoveRejection::Ownership);
+    }
+
+    if !is_bit_set(board.attacks_from(from, mv.piece_type, player), mv.to) {
+        return Err(TtMoveRejection::Reach);
+    }
+    let promotion_ok = if mv.is_promotion {
+        mv.piece_type.can_promote()
+            && (from.is_in_promotion_zone(player.opposite())
+                || mv.to.is_in_promotion_zone(player.opposite()))
+    } else {
+        !is_dead_square(mv.piece_type, mv.to, player)
+    };
+    if promotion_ok {
+        Ok(())
+    } else {
+        Err(TtMoveRejection::Reach)
+    }
+}
+
+/// Counters since the engine started
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TtMoveStats {
+    pub checked: u64,
+    pub rejected_ownership: u64,
+    pub rejected_drop_rule: u64,
+    pub rejected_reach: u64,
+}
+
+impl TtMoveStats {
+    pub fn rejected(&self) -> u64 {
+        self.rejected_ownership + self.rejected_drop_rule + self.rejected_reach
+    }
+}
+
+/// Checks TT moves and counts what it throws away. Counters are atomic so the
+/// `&self` PV walks can use it too.
+#[derive(Debug, Default)]
+pub struct TtMoveCheck {
+    checked: AtomicU64,
+    rejected_ownership: AtomicU64,
+    rejected_drop_rule: AtomicU64,
+    rejected_reach: AtomicU64,
+}
+
+impl TtMoveCheck {
+    /// `mv` if it is pseudo-legal here, `None` otherwise
+    pub fn filter(
+        &self,
+        board: &BitboardBoard,
+        captured_pieces: &CapturedPieces,
+        player: Player,
+        mv: Option<Move>,
+    ) -> Option<Move> {
+        let mv = mv?;
+        self.checked.fetch_add(1, Ordering::Relaxed);
+        match check_tt_move(board, captured_pieces, player, &mv) {
+            Ok(()) => Some(mv),
+            Err(rejection) => {
+                let counter = match rejection {
+                    TtMoveRejection::Ownership => &self.rejected_ownership,
+                    TtMoveRejection::DropRule => &self.rejected_drop_rule,
+                    TtMoveRejection::Reach => &self.rejected_reach,
+                };
+                counter.fetch_add(1, Ordering::Relaxed);
+                crate::debug_utils::trace_log(
+                    "TT_MOVE",
+                    &format!("Rejected TT move {} ({:?})", mv.to_usi_string(), rejection),
+                );
+                None
+            }
+        }
+    }
+
+    pub fn stats(&self) -> TtMoveStats {
+        TtMoveStats {
+            checked: self.checked.load(Ordering::Relaxed),
+            rejected_ownership: self.rejected_ownership.load(Ordering::Relaxed),
+            rejected_drop_rule: self.rejected_drop_rule.load(Ordering::Relaxed),
+            rejected_reach: self.rejected_reach.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Position;
+
+    fn position(sfen: &str) -> (BitboardBoard, Player, CapturedPieces) {
+        BitboardBoard::from_fen(sfen).unwrap()
+    }
+
+    #[test]
+    fn test_stages_reject_moves_from_other_positions() {
+        let (board, player, captured) =
+            position("lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b P 1");
+        let check = |usi: &str| {
+            let mv = Move::from_usi_string(usi, player, &board).unwrap();
+            check_tt_move(&board, &captured, player, &mv)
+        };
+        assert_eq!(check("7g7f"), Ok(()));
+        assert_eq!(check("2h2c"), Err(TtMoveRejection::Reach));
+        assert_eq!(check("8h2b+"), Err(TtMoveRejection::Reach));
+        assert_eq!(check("P*5e"), Err(TtMoveRejection::DropRule));
+
+        // A gote move, as a colliding entry might hold
+        let gote = Move::new_move(
+            Position::new(2, 2),
+            Position::new(3, 2),
+            PieceType::Pawn,
+            Player::White,
+            false,
+        );
+        assert_eq!(
+            check_tt_move(&board, &captured, player, &gote),
+            Err(TtMoveRejection::Ownership)
+        );
+
+        // The source square holds a lance, not a silver
+        let (from, to) = (Position::new(8, 0), Position::new(7, 0));
+        let wrong_piece = Move::new_move(from, to, PieceType::Silver, player, false);
+        let tt_check = TtMoveCheck::default();
+        assert_eq!(tt_check.filter(&board, &captured, player, Some(wrong_piece)), None);
+        let stats = tt_check.stats();
+        assert_eq!((stats.checked, stats.rejected()), (1, 1));
+    }
+}
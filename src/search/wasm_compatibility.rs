@@ -0,0 +1,37 @@
+//! WASM Compatibility Shims
+//!
+//! `wasm32-unknown-unknown` has no system clock behind `std::time` and no
+//! threads, and a browser tab can't spare the hash sizes a desktop engine uses.
+//! These shims stand in for the pieces of `std` the search relies on when the
+//! engine is built with the `wasm` feature.
+
+use std::time::Duration;
+
+/// Largest transposition table the wasm build allocates, in MB
+pub const MAX_HASH_SIZE_MB: usize = 64;
+
+/// Clamp a requested hash size to what the wasm build can allocate
+pub fn clamp_hash_size(size_mb: usize) -> usize {
+    size_mb.clamp(1, MAX_HASH_SIZE_MB)
+}
+
+/// Milliseconds since the UNIX epoch from the JavaScript clock
+pub fn now_ms() -> f64 {
+    js_sys::Date::now()
+}
+
+/// Drop-in replacement for `std::time::Instant` backed by the JavaScript clock
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Instant {
+    millis: f64,
+}
+
+impl Instant {
+    pub fn now() -> Self {
+        Self { millis: now_ms() }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_secs_f64(((now_ms() - self.millis) / 1000.0).max(0.0))
+    }
+}
@@ -0,0 +1,115 @@
+//! Win Probability
+//!
+//! Centipawns say little to most players: +300 is a comfortable edge early on
+//! and nothing special with both hands full of pieces. The advantage bar and
+//! the analysis API therefore also report the chance of winning, mapped from
+//! the score by the logistic curve `1 / (1 + exp(-score / WIN_RATE_SCALE))`.
+//! At the default 600 centipawns per unit of logit, +600 wins about 73% of the
+//! time. The scale is a starting point, not a measured value: `fit-win-rate`
+//! fits it with `fit_scale` to self-play scores paired with game results.
+//! Mate scores map to certain wins and losses.
+//! Probabilities are given for the side the score is for, in whole permille
+//! where they leave the engine.
+
+use super::mate_score::mate_distance;
+
+/// Centipawns per unit of logit of the win probability curve
+pub const WIN_RATE_SCALE: f64 = 600.0;
+
+/// Scores beyond this many centipawns either way are ignored when fitting;
+/// they come from decided positions and only pull the scale upwards
+pub const FIT_SCORE_CAP: i32 = 3_000;
+
+/// Newton steps `fit_scale` takes at most
+const FIT_ITERATIONS: usize = 50;
+
+/// Chance of winning, from 0.0 to 1.0, for the side `score` is for
+pub fn win_probability(score: i32) -> f64 {
+    match mate_distance(score) {
+        Some(plies) if plies > 0 => 1.0,
+        Some(_) => 0.0,
+        None => 1.0 / (1.0 + (-f64::from(score) / WIN_RATE_SCALE).exp()),
+    }
+}
+
+/// `win_probability` in whole permille, as reported in `info` lines and results
+pub fn win_rate_permille(score: i32) -> u16 {
+    (win_probability(score) * 1000.0).round() as u16
+}
+
+/// Score for `info` lines: `cp N` or `mate N`, followed by `winrate P` in
+/// permille when `show_win_rate` is set
+pub fn format_info_score(score: i32, show_win_rate: bool) -> String {
+    let formatted = super::mate_score::format_usi_score(score);
+    if show_win_rate {
+        format!("{} winrate {}", formatted, win_rate_permille(score))
+    } else {
+        formatted
+    }
+}
+
+/// Maximum-likelihood scale of the win probability curve for `samples`, each a
+/// score and the result of the game for the side the score is for (1.0 win,
+/// 0.5 draw, 0.0 loss). Mate scores and scores past `FIT_SCORE_CAP` are
+/// skipped. `None` when the samples do not determine a positive scale, e.g.
+/// when winning scores lose as often as they win.
+pub fn fit_scale(samples: &[(i32, f64)]) -> Option<f64> {
+    let samples: Vec<(f64, f64)> = samples
+        .iter()
+        .filter(|(score, _)| score.abs() <= FIT_SCORE_CAP && mate_distance(*score).is_none())
+        .map(|&(score, result)| (f64::from(score), result.clamp(0.0, 1.0)))
+        .collect();
+
+    // Logistic regression on the slope k = 1 / scale, which has a convex loss
+    let mut k = 1.0 / WIN_RATE_SCALE;
+    for _ in 0..FIT_ITERATIONS {
+        let (mut gradient, mut curvature) = (0.0, 0.0);
+        for &(score, result) in &samples {
+            let p = 1.0 / (1.0 + (-k * score).exp());
+            gradient += (p - result) * score;
+            curvature += p * (1.0 - p) * score * score;
+        }
+        if curvature <= f64::EPSILON {
+            return None;
+        }
+        let step = gradient / curvature;
+        k -= step;
+        if step.abs() <= k.abs() * 1e-9 {
+            break;
+        }
+    }
+    (k.is_finite() && k > 0.0).then(|| 1.0 / k)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::mate_score::{mate_in, mated_in};
+
+    #[test]
+    fn test_win_probability_curve() {
+        assert_eq!(win_rate_permille(0), 500);
+        assert_eq!(win_rate_permille(600), 731);
+        assert_eq!(win_rate_permille(-600), 269);
+        assert_eq!(win_rate_permille(mate_in(5)), 1000);
+        assert_eq!(win_rate_permille(mated_in(2)), 0);
+        assert_eq!(format_info_score(-45, false), "cp -45");
+        assert_eq!(format_info_score(mate_in(3), true), "mate 3 winrate 1000");
+    }
+
+    #[test]
+    fn test_fit_scale_recovers_the_curve() {
+        let samples: Vec<(i32, f64)> = (-40..=40)
+            .map(|step| {
+                let score = step * 50;
+                (score, 1.0 / (1.0 + (-f64::from(score) / 400.0).exp()))
+            })
+            .chain([(mate_in(1), 0.0), (FIT_SCORE_CAP + 1, 0.0)])
+            .collect();
+        let scale = fit_scale(&samples).unwrap();
+        assert!((scale - 400.0).abs() < 0.01, "fitted {}", scale);
+
+        assert_eq!(fit_scale(&[(200, 0.0), (-200, 1.0)]), None);
+        assert_eq!(fit_scale(&[]), None);
+    }
+}
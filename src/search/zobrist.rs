@@ -12,16 +12,16 @@ use rand::{Rng, SeedableRng};
 /// side to move, and repetition states.
 #[derive(Debug, Clone)]
 pub struct ZobristTable {
-    /// Hash keys for piece positions [piece_type][position]
-    /// 14 piece types × 81 positions = 1134 keys
-    pub piece_keys: [[u64; 81]; 14],
+    /// Hash keys for piece positions [owner][piece_type][position]
+    /// 2 owners × 14 piece types × 81 positions = 2268 keys
+    pub piece_keys: [[[u64; 81]; 14]; 2],
 
     /// Hash key for side to move (Black vs White)
     pub side_to_move_key: u64,
 
-    /// Hash keys for pieces in hand [piece_type][count]
-    /// 14 piece types × 8 counts = 112 keys (max 8 of any piece type in hand)
-    pub hand_keys: [[u64; 8]; 14],
+    /// Hash keys for pieces in hand [owner][piece_type][count]
+    /// 2 owners × 14 piece types × 8 counts = 224 keys (max 8 of any piece type in hand)
+    pub hand_keys: [[[u64; 8]; 14]; 2],
 
     /// Hash keys for repetition tracking [state]
     /// 4 states: no repetition, 2-fold, 3-fold, 4-fold
@@ -36,22 +36,26 @@ impl ZobristTable {
     pub fn new(seed: u64) -> Self {
         let mut rng = StdRng::seed_from_u64(seed);
 
-        // Initialize piece position keys (14 piece types × 81 positions)
-        let mut piece_keys = [[0u64; 81]; 14];
-        for piece_type in 0..14 {
-            for position in 0..81 {
-                piece_keys[piece_type][position] = rng.gen::<u64>();
+        // Initialize piece position keys (2 owners × 14 piece types × 81 positions)
+        let mut piece_keys = [[[0u64; 81]; 14]; 2];
+        for owner in 0..2 {
+            for piece_type in 0..14 {
+                for position in 0..81 {
+                    piece_keys[owner][piece_type][position] = rng.gen::<u64>();
+                }
             }
         }
 
         // Initialize side to move key
         let side_to_move_key = rng.gen::<u64>();
 
-        // Initialize hand piece keys (14 piece types × 8 counts)
-        let mut hand_keys = [[0u64; 8]; 14];
-        for piece_type in 0..14 {
-            for count in 0..8 {
-                hand_keys[piece_type][count] = rng.gen::<u64>();
+        // Initialize hand piece keys (2 owners × 14 piece types × 8 counts)
+        let mut hand_keys = [[[0u64; 8]; 14]; 2];
+        for owner in 0..2 {
+            for piece_type in 0..14 {
+                for count in 0..8 {
+                    hand_keys[owner][piece_type][count] = rng.gen::<u64>();
+                }
             }
         }
 
@@ -75,11 +79,11 @@ impl ZobristTable {
         Self::new(0x1234567890ABCDEF)
     }
 
-    /// Get the hash key for a piece at a specific position
-    pub fn get_piece_key(&self, piece_type: PieceType, position: Position) -> u64 {
+    /// Get the hash key for `owner`'s piece at a specific position
+    pub fn get_piece_key(&self, piece_type: PieceType, owner: Player, position: Position) -> u64 {
         let piece_index = piece_type.to_u8() as usize;
         let pos_index = position.to_index() as usize;
-        self.piece_keys[piece_index][pos_index]
+        self.piece_keys[owner as usize][piece_index][pos_index]
     }
 
     /// Get the hash key for side to move
@@ -87,11 +91,11 @@ impl ZobristTable {
         self.side_to_move_key
     }
 
-    /// Get the hash key for a specific count of a piece type in hand
-    pub fn get_hand_key(&self, piece_type: PieceType, count: u8) -> u64 {
+    /// Get the hash key for a specific count of a piece type in `owner`'s hand
+    pub fn get_hand_key(&self, piece_type: PieceType, owner: Player, count: u8) -> u64 {
         let piece_index = piece_type.to_u8() as usize;
         let count_index = count.min(7) as usize; // Cap at 7 since array is 0-7
-        self.hand_keys[piece_index][count_index]
+        self.hand_keys[owner as usize][piece_index][count_index]
     }
 
     /// Get the hash key for a repetition state
@@ -168,7 +172,7 @@ impl ZobristHasher {
             for col in 0..9 {
                 let pos = Position::new(row, col);
                 if let Some(piece) = board.get_piece(pos) {
-                    hash ^= self.table.get_piece_key(piece.piece_type, pos);
+                    hash ^= self.table.get_piece_key(piece.piece_type, piece.player, pos);
                 }
             }
         }
@@ -190,7 +194,7 @@ impl ZobristHasher {
         ] {
             let count = captured_pieces.count(piece_type, Player::Black);
             if count > 0 {
-                hash ^= self.table.get_hand_key(piece_type, count as u8);
+                hash ^= self.table.get_hand_key(piece_type, Player::Black, count as u8);
             }
         }
 
@@ -206,7 +210,7 @@ impl ZobristHasher {
         ] {
             let count = captured_pieces.count(piece_type, Player::White);
             if count > 0 {
-                hash ^= self.table.get_hand_key(piece_type, count as u8);
+                hash ^= self.table.get_hand_key(piece_type, Player::White, count as u8);
             }
         }
 
@@ -232,12 +236,12 @@ impl ZobristHasher {
         // Check if this is a drop move (no from position)
         if move_.from.is_none() {
             // Drop move: Add dropped piece to board
-            hash ^= self.table.get_piece_key(move_.piece_type, move_.to);
+            hash ^= self.table.get_piece_key(move_.piece_type, move_.player, move_.to);
         } else {
             // Normal move: Remove piece from source square
             if let Some(from) = move_.from {
                 if let Some(piece) = board_before.get_piece(from) {
-                    hash ^= self.table.get_piece_key(piece.piece_type, from);
+                    hash ^= self.table.get_piece_key(piece.piece_type, piece.player, from);
                 }
             }
 
@@ -250,12 +254,13 @@ impl ZobristHasher {
             } else {
                 move_.piece_type
             };
-            hash ^= self.table.get_piece_key(piece_type, move_.to);
+            hash ^= self.table.get_piece_key(piece_type, move_.player, move_.to);
 
             // Handle capture
             if move_.is_capture {
                 if let Some(captured) = &move_.captured_piece {
-                    hash ^= self.table.get_piece_key(captured.piece_type, move_.to);
+                    hash ^=
+                        self.table.get_piece_key(captured.piece_type, captured.player, move_.to);
                 }
             }
         }
@@ -278,10 +283,10 @@ impl ZobristHasher {
 
             if count_before != count_after {
                 if count_before > 0 {
-                    hash ^= self.table.get_hand_key(piece_type, count_before as u8);
+                    hash ^= self.table.get_hand_key(piece_type, Player::Black, count_before as u8);
                 }
                 if count_after > 0 {
-                    hash ^= self.table.get_hand_key(piece_type, count_after as u8);
+                    hash ^= self.table.get_hand_key(piece_type, Player::Black, count_after as u8);
                 }
             }
 
@@ -292,10 +297,12 @@ impl ZobristHasher {
                 if count_before_white > 0 {
                     hash ^= self
                         .table
-                        .get_hand_key(piece_type, count_before_white as u8);
+                        .get_hand_key(piece_type, Player::White, count_before_white as u8);
                 }
                 if count_after_white > 0 {
-                    hash ^= self.table.get_hand_key(piece_type, count_after_white as u8);
+                    hash ^= self
+                        .table
+                        .get_hand_key(piece_type, Player::White, count_after_white as u8);
                 }
             }
         }
@@ -325,9 +332,9 @@ impl ZobristHasher {
         new_hash
     }
 
-    /// Get a piece key for a specific piece type and position
-    pub fn get_piece_key(&self, piece_type: PieceType, position: Position) -> u64 {
-        self.table.get_piece_key(piece_type, position)
+    /// Get a piece key for a specific piece type, owner and position
+    pub fn get_piece_key(&self, piece_type: PieceType, owner: Player, position: Position) -> u64 {
+        self.table.get_piece_key(piece_type, owner, position)
     }
 
     /// Get the side to move key
@@ -335,9 +342,9 @@ impl ZobristHasher {
         self.table.get_side_to_move_key()
     }
 
-    /// Get a hand key for a specific piece type and count
-    pub fn get_hand_key(&self, piece_type: PieceType, count: u8) -> u64 {
-        self.table.get_hand_key(piece_type, count)
+    /// Get a hand key for a specific piece type, owner and count
+    pub fn get_hand_key(&self, piece_type: PieceType, owner: Player, count: u8) -> u64 {
+        self.table.get_hand_key(piece_type, owner, count)
     }
 
     /// Get a repetition key for a specific state
@@ -364,6 +371,40 @@ pub fn create_hasher() -> ZobristHasher {
     ZobristHasher::new()
 }
 
+/// A second key for a position, independent of the Zobrist keys, for verifying
+/// transposition table hits. It hashes the board, side to move and both hands
+/// directly with SipHash, owners included, so positions that share a Zobrist key
+/// almost never share this one. Never 0, which the table reads as "no key".
+pub fn verification_key(
+    board: &BitboardBoard,
+    player: Player,
+    captured_pieces: &CapturedPieces,
+) -> u64 {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    for row in 0..9 {
+        for col in 0..9 {
+            board.get_piece(Position::new(row, col)).hash(&mut hasher);
+        }
+    }
+    player.hash(&mut hasher);
+    for owner in [Player::Black, Player::White] {
+        for piece_type in [
+            PieceType::Pawn,
+            PieceType::Lance,
+            PieceType::Knight,
+            PieceType::Silver,
+            PieceType::Gold,
+            PieceType::Bishop,
+            PieceType::Rook,
+        ] {
+            captured_pieces.count(piece_type, owner).hash(&mut hasher);
+        }
+    }
+    hasher.finish().max(1)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -375,17 +416,21 @@ mod tests {
         assert_eq!(table.get_seed(), 42);
 
         // Test that all keys are non-zero (very high probability)
-        for piece_type in 0..14 {
-            for position in 0..81 {
-                assert_ne!(table.piece_keys[piece_type][position], 0);
+        for owner in 0..2 {
+            for piece_type in 0..14 {
+                for position in 0..81 {
+                    assert_ne!(table.piece_keys[owner][piece_type][position], 0);
+                }
             }
         }
 
         assert_ne!(table.side_to_move_key, 0);
 
-        for piece_type in 0..14 {
-            for count in 0..8 {
-                assert_ne!(table.hand_keys[piece_type][count], 0);
+        for owner in 0..2 {
+            for piece_type in 0..14 {
+                for count in 0..8 {
+                    assert_ne!(table.hand_keys[owner][piece_type][count], 0);
+                }
             }
         }
 
@@ -509,4 +554,34 @@ mod tests {
         // Reversing repetition update should return to original
         assert_eq!(hash, back_to_original);
     }
+
+    #[test]
+    fn test_keys_tell_owners_apart() {
+        let hasher = create_hasher();
+        let captured_pieces = CapturedPieces::new();
+        let square = Position::new(4, 4);
+        let mut black_rook = BitboardBoard::empty();
+        black_rook.place_piece(crate::types::Piece::new(PieceType::Rook, Player::Black), square);
+        let mut white_rook = BitboardBoard::empty();
+        white_rook.place_piece(crate::types::Piece::new(PieceType::Rook, Player::White), square);
+
+        let zobrist = |board: &BitboardBoard| {
+            hasher.hash_position(board, Player::Black, &captured_pieces, RepetitionState::None)
+        };
+        assert_ne!(zobrist(&black_rook), zobrist(&white_rook));
+
+        let mut black_hand = CapturedPieces::new();
+        black_hand.add_piece(PieceType::Pawn, Player::Black);
+        let mut white_hand = CapturedPieces::new();
+        white_hand.add_piece(PieceType::Pawn, Player::White);
+        let empty = BitboardBoard::empty();
+        assert_ne!(
+            hasher.hash_position(&empty, Player::Black, &black_hand, RepetitionState::None),
+            hasher.hash_position(&empty, Player::Black, &white_hand, RepetitionState::None)
+        );
+        assert_ne!(
+            verification_key(&black_rook, Player::Black, &captured_pieces),
+            verification_key(&white_rook, Player::Black, &captured_pieces)
+        );
+    }
 }
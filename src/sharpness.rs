@@ -0,0 +1,101 @@
+//! Position Sharpness
+//!
+//! How harshly a position punishes a wrong move, from 0 to 1000 permille. Two
+//! things go into it: how far the win rates of the top candidate moves spread,
+//! since a position where only one move holds is sharp however quiet the board
+//! looks, and the tactical density of the board (`evaluation::complexity`),
+//! since pieces en prise and drops everywhere make misjudging easy even when
+//! several moves score alike. Win rates rather than centipawns keep a mate
+//! score from swamping the spread. Clock-aware play weighs the density half for
+//! each root move, and game review flags critical moments on the same gap
+//! between the best move and the second best (`score_trend`).
+
+use crate::evaluation::complexity::TacticalDensity;
+use serde::{Deserialize, Serialize};
+
+/// Candidates searched when the caller does not say
+pub const DEFAULT_SHARPNESS_CANDIDATES: usize = 4;
+
+/// Time for the whole estimate when the caller does not say
+pub const DEFAULT_SHARPNESS_TIME_MS: u32 = 800;
+
+/// Win rate in permille a move may fall short of the best and still count as good
+pub const GOOD_MOVE_MARGIN_PERMILLE: u16 = 50;
+
+/// Win-rate spread in permille at which the spread term reaches one half
+const SPREAD_SCALE: f64 = 100.0;
+
+/// Share of the spread term in the estimate; tactical density makes up the rest
+const SPREAD_WEIGHT: f64 = 0.6;
+
+/// Sharpness of one position with the figures behind it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sharpness {
+    /// 0 for a quiet position with many good moves, 1000 for the sharpest
+    pub sharpness: u16,
+    /// Standard deviation of the candidates' win rates in permille
+    pub spread: u16,
+    /// How far the second-best move's win rate falls short of the best's
+    pub best_gap: u16,
+    /// Candidates within `GOOD_MOVE_MARGIN_PERMILLE` of the best, the best included
+    pub good_moves: usize,
+    /// Candidates the estimate was taken over
+    pub candidates: usize,
+    pub density: TacticalDensity,
+}
+
+impl Sharpness {
+    /// Estimate from the candidates' win rates, best first and all from the same
+    /// side's perspective, and the board's tactical density
+    pub fn estimate(win_rates: &[u16], density: TacticalDensity) -> Self {
+        let best = win_rates.first().copied().unwrap_or_default();
+        let shortfalls: Vec<u16> = win_rates.iter().map(|rate| best.abs_diff(*rate)).collect();
+
+        let count = win_rates.len().max(1) as f64;
+        let mean = win_rates.iter().map(|rate| f64::from(*rate)).sum::<f64>() / count;
+        let variance =
+            win_rates.iter().map(|rate| (f64::from(*rate) - mean).powi(2)).sum::<f64>() / count;
+        let spread = variance.sqrt();
+
+        let spread_term = spread / (spread + SPREAD_SCALE);
+        let blended = SPREAD_WEIGHT * spread_term + (1.0 - SPREAD_WEIGHT) * density.complexity();
+        Self {
+            sharpness: (blended * 1000.0).round() as u16,
+            spread: spread.round() as u16,
+            best_gap: shortfalls.get(1).copied().unwrap_or_default(),
+            good_moves: shortfalls
+                .iter()
+                .filter(|shortfall| **shortfall <= GOOD_MOVE_MARGIN_PERMILLE)
+                .count(),
+            candidates: win_rates.len(),
+            density,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_move_is_sharper_than_many_good_ones() {
+        let calm = Sharpness::estimate(&[520, 510, 505, 500], TacticalDensity::default());
+        assert_eq!(calm.good_moves, 4);
+        assert_eq!(calm.best_gap, 10);
+
+        let only_move = Sharpness::estimate(&[600, 200, 150, 100], TacticalDensity::default());
+        assert_eq!(only_move.good_moves, 1);
+        assert_eq!(only_move.best_gap, 400);
+        assert!(only_move.sharpness > calm.sharpness);
+
+        // The same spread from the other side's perspective reads the same
+        let flipped = Sharpness::estimate(&[400, 800, 850, 900], TacticalDensity::default());
+        assert_eq!(flipped.sharpness, only_move.sharpness);
+        assert_eq!(flipped.best_gap, 400);
+
+        // Hanging pieces add to it even when the moves score alike
+        let density = TacticalDensity { attacked_pieces: 4, hanging_pieces: 2, ..calm.density };
+        assert!(Sharpness::estimate(&[520, 510, 505, 500], density).sharpness > calm.sharpness);
+        assert_eq!(Sharpness::estimate(&[], TacticalDensity::default()).sharpness, 0);
+    }
+}
@@ -0,0 +1,252 @@
+//! Statistics Hub
+//!
+//! One serializable snapshot of the statistics otherwise scattered across the
+//! engine: the last search, move ordering, the transposition table, tablebase
+//! probes, magic bitboard lookups and the memory held by opening book and
+//! tablebase files. Counters are cumulative since the engine
+//! started; the hub keeps a baseline so callers can ask for the change since the
+//! last reset without disturbing the counters other consumers read.
+
+use crate::asset_storage::AssetStats;
+use crate::search::statistics::SearchStatisticsReport;
+use crate::tablebase::TablebaseStats;
+use serde::{Deserialize, Serialize};
+
+/// Move ordering counters
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MoveOrderingCounters {
+    pub moves_ordered: u64,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    pub pv_move_hits: u64,
+    pub killer_move_hits: u64,
+    pub counter_move_hits: u64,
+    pub see_calculations: u64,
+}
+
+impl MoveOrderingCounters {
+    pub fn since(&self, baseline: &Self) -> Self {
+        Self {
+            moves_ordered: self.moves_ordered.saturating_sub(baseline.moves_ordered),
+            cache_hits: self.cache_hits.saturating_sub(baseline.cache_hits),
+            cache_misses: self.cache_misses.saturating_sub(baseline.cache_misses),
+            pv_move_hits: self.pv_move_hits.saturating_sub(baseline.pv_move_hits),
+            killer_move_hits: self.killer_move_hits.saturating_sub(baseline.killer_move_hits),
+            counter_move_hits: self.counter_move_hits.saturating_sub(baseline.counter_move_hits),
+            see_calculations: self.see_calculations.saturating_sub(baseline.see_calculations),
+        }
+    }
+}
+
+/// Transposition table counters and gauges
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranspositionCounters {
+    pub probes: u64,
+    pub hits: u64,
+    pub stores: u64,
+    pub replacements: u64,
+    /// Occupancy in permille (gauge)
+    pub hashfull: u32,
+    /// Number of slots (gauge)
+    pub size: usize,
+    /// Hits checked against the second key (only with key verification on)
+    pub verified_probes: u64,
+    /// Verified hits whose second key disagreed: real Zobrist collisions
+    pub key_collisions: u64,
+}
+
+impl TranspositionCounters {
+    pub fn since(&self, baseline: &Self) -> Self {
+        Self {
+            probes: self.probes.saturating_sub(baseline.probes),
+            hits: self.hits.saturating_sub(baseline.hits),
+            stores: self.stores.saturating_sub(baseline.stores),
+            replacements: self.replacements.saturating_sub(baseline.replacements),
+            hashfull: self.hashfull,
+            size: self.size,
+            verified_probes: self.verified_probes.saturating_sub(baseline.verified_probes),
+            key_collisions: self.key_collisions.saturating_sub(baseline.key_collisions),
+        }
+    }
+}
+
+/// Endgame tablebase probe counters
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TablebaseCounters {
+    pub probes: u64,
+    pub cache_hits: u64,
+    pub solver_hits: u64,
+    pub misses: u64,
+    /// Rejected by the material signature filter before reaching a solver
+    pub filtered: u64,
+}
+
+impl TablebaseCounters {
+    pub fn since(&self, baseline: &Self) -> Self {
+        Self {
+            probes: self.probes.saturating_sub(baseline.probes),
+            cache_hits: self.cache_hits.saturating_sub(baseline.cache_hits),
+            solver_hits: self.solver_hits.saturating_sub(baseline.solver_hits),
+            misses: self.misses.saturating_sub(baseline.misses),
+            filtered: self.filtered.saturating_sub(baseline.filtered),
+        }
+    }
+
+    /// Add the counters of another tablebase (the engine probes one at the root
+    /// and the search probes its own)
+    pub fn merge(&mut self, other: &Self) {
+        self.probes += other.probes;
+        self.cache_hits += other.cache_hits;
+        self.solver_hits += other.solver_hits;
+        self.misses += other.misses;
+        self.filtered += other.filtered;
+    }
+}
+
+impl From<&TablebaseStats> for TablebaseCounters {
+    fn from(stats: &TablebaseStats) -> Self {
+        Self {
+            probes: stats.total_probes,
+            cache_hits: stats.cache_hits,
+            solver_hits: stats.solver_hits,
+            misses: stats.misses,
+            filtered: stats.filtered_probes,
+        }
+    }
+}
+
+/// Magic bitboard lookup and board telemetry counters (process-wide)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MagicCounters {
+    pub magic_lookups: u64,
+    pub raycast_fallbacks: u64,
+    pub magic_unavailable: u64,
+    pub board_clones: u64,
+}
+
+impl MagicCounters {
+    /// Read the global magic bitboard telemetry
+    pub fn current() -> Self {
+        let (raycast_fallbacks, magic_lookups, magic_unavailable) =
+            crate::bitboards::get_magic_telemetry();
+        Self {
+            magic_lookups,
+            raycast_fallbacks,
+            magic_unavailable,
+            board_clones: crate::bitboards::get_board_telemetry().clone_count,
+        }
+    }
+
+    pub fn since(&self, baseline: &Self) -> Self {
+        Self {
+            magic_lookups: self.magic_lookups.saturating_sub(baseline.magic_lookups),
+            raycast_fallbacks: self.raycast_fallbacks.saturating_sub(baseline.raycast_fallbacks),
+            magic_unavailable: self.magic_unavailable.saturating_sub(baseline.magic_unavailable),
+            board_clones: self.board_clones.saturating_sub(baseline.board_clones),
+        }
+    }
+}
+
+/// Everything the hub reports, as one JSON object
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatisticsSnapshot {
+    /// The most recent search; already per-search, so never turned into a delta
+    pub search: SearchStatisticsReport,
+    pub move_ordering: MoveOrderingCounters,
+    pub transposition_table: TranspositionCounters,
+    pub tablebase: TablebaseCounters,
+    pub magic: MagicCounters,
+    /// Opening book and tablebase files read through `AssetStorage` (gauges)
+    pub assets: Vec<AssetStats>,
+}
+
+impl StatisticsSnapshot {
+    /// Counters accumulated since `baseline`; the search report and gauges are kept
+    pub fn since(&self, baseline: &StatisticsSnapshot) -> StatisticsSnapshot {
+        StatisticsSnapshot {
+            search: self.search.clone(),
+            move_ordering: self.move_ordering.since(&baseline.move_ordering),
+            transposition_table: self.transposition_table.since(&baseline.transposition_table),
+            tablebase: self.tablebase.since(&baseline.tablebase),
+            magic: self.magic.since(&baseline.magic),
+            assets: self.assets.clone(),
+        }
+    }
+}
+
+/// Baseline for `delta` requests
+#[derive(Debug, Clone, Default)]
+pub struct StatisticsHub {
+    baseline: Option<StatisticsSnapshot>,
+}
+
+impl StatisticsHub {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make `current` the baseline later deltas are measured from
+    pub fn reset(&mut self, current: StatisticsSnapshot) {
+        self.baseline = Some(current);
+    }
+
+    /// Change since the last reset, or `current` unchanged if never reset
+    pub fn delta(&self, current: StatisticsSnapshot) -> StatisticsSnapshot {
+        match &self.baseline {
+            Some(baseline) => current.since(baseline),
+            None => current,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(probes: u64, hashfull: u32) -> StatisticsSnapshot {
+        StatisticsSnapshot {
+            search: SearchStatisticsReport {
+                nodes: 1_000,
+                ..SearchStatisticsReport::default()
+            },
+            transposition_table: TranspositionCounters {
+                probes,
+                hashfull,
+                ..TranspositionCounters::default()
+            },
+            tablebase: TablebaseCounters {
+                probes,
+                ..TablebaseCounters::default()
+            },
+            ..StatisticsSnapshot::default()
+        }
+    }
+
+    #[test]
+    fn test_delta_since_reset() {
+        let mut hub = StatisticsHub::new();
+        assert_eq!(hub.delta(snapshot(10, 5)), snapshot(10, 5));
+
+        hub.reset(snapshot(10, 5));
+        let delta = hub.delta(snapshot(25, 7));
+        assert_eq!(delta.transposition_table.probes, 15);
+        assert_eq!(delta.tablebase.probes, 15);
+        // Gauges and the per-search report are not differenced
+        assert_eq!(delta.transposition_table.hashfull, 7);
+        assert_eq!(delta.search.nodes, 1_000);
+    }
+
+    #[test]
+    fn test_snapshot_round_trips_through_json() {
+        let original = snapshot(3, 1);
+        let json = serde_json::to_string(&original).unwrap();
+        assert!(json.contains("\"transposition_table\""));
+        let parsed: StatisticsSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, original);
+    }
+}
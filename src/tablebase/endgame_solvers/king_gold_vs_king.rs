@@ -4,6 +4,7 @@
 //! This is one of the most common and important endgames in Shogi, as it represents
 //! the minimum material needed to force a win against a lone king.
 
+use super::super::material_signature::MaterialSignature;
 use super::super::solver_traits::EndgameSolverHelper;
 use super::super::tablebase_config::KingGoldConfig;
 use super::super::{EndgameSolver, TablebaseResult};
@@ -652,6 +653,10 @@ impl EndgameSolver for KingGoldVsKingSolver {
     fn max_depth(&self) -> Option<u8> {
         Some(self.config.max_moves_to_mate)
     }
+
+    fn material_signatures(&self) -> Option<Vec<MaterialSignature>> {
+        Some(MaterialSignature::king_and_pieces_vs_king(&[PieceType::Gold]).to_vec())
+    }
 }
 
 // EndgameSolverHelper is already implemented for all types via blanket implementation
@@ -5,6 +5,7 @@
 //! on one side versus a lone king on the other side.
 
 use crate::bitboards::BitboardBoard;
+use crate::tablebase::material_signature::MaterialSignature;
 use crate::tablebase::solver_traits::EndgameSolver;
 use crate::tablebase::tablebase_config::KingRookConfig;
 use crate::tablebase::TablebaseResult;
@@ -671,6 +672,10 @@ impl EndgameSolver for KingRookVsKingSolver {
     fn name(&self) -> &'static str {
         "KingRookVsKing"
     }
+
+    fn material_signatures(&self) -> Option<Vec<MaterialSignature>> {
+        Some(MaterialSignature::king_and_pieces_vs_king(&[PieceType::Rook]).to_vec())
+    }
 }
 
 impl Default for KingRookVsKingSolver {
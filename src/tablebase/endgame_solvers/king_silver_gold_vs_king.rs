@@ -0,0 +1,152 @@
+//! King + Silver + Gold vs King endgame solver
+//!
+//! Silver and gold complement each other: the silver covers the diagonals the
+//! gold leaves open behind it, which is enough to drive a bare king to the edge.
+//!
+//! The defending king has no pieces and an empty hand, so it can neither block
+//! nor drop; the attacker's pieces may stand on the board or wait in hand. Mates
+//! are proven by the node-bounded search in `lone_king`, which also gives the exact
+//! distance to mate for either side to move.
+
+use super::super::material_signature::MaterialSignature;
+use super::super::tablebase_config::KingSilverGoldConfig;
+use super::super::{EndgameSolver, TablebaseResult};
+use super::lone_king::{has_material, lone_king_attacker, solve_lone_king};
+use crate::types::core::{PieceType, Player};
+use crate::BitboardBoard;
+use crate::CapturedPieces;
+
+/// Non-king material of the attacking side
+const MATERIAL: [PieceType; 2] = [PieceType::Silver, PieceType::Gold];
+
+/// Solver for King + Silver + Gold vs King endgames
+pub struct KingSilverGoldVsKingSolver {
+    /// Configuration for this solver
+    config: KingSilverGoldConfig,
+}
+
+impl KingSilverGoldVsKingSolver {
+    /// Create a new solver with default configuration
+    pub fn new() -> Self {
+        Self::with_config(KingSilverGoldConfig::default())
+    }
+
+    /// Create a new solver with specified configuration
+    pub fn with_config(config: KingSilverGoldConfig) -> Self {
+        Self { config }
+    }
+
+    /// The attacking side, if the position has exactly this material
+    fn attacker(&self, board: &BitboardBoard, captured_pieces: &CapturedPieces) -> Option<Player> {
+        let (attacker, pieces) = lone_king_attacker(board, captured_pieces)?;
+        has_material(&pieces, &MATERIAL).then_some(attacker)
+    }
+}
+
+impl EndgameSolver for KingSilverGoldVsKingSolver {
+    fn can_solve(
+        &self,
+        board: &BitboardBoard,
+        _player: Player,
+        captured_pieces: &CapturedPieces,
+    ) -> bool {
+        self.config.enabled && self.attacker(board, captured_pieces).is_some()
+    }
+
+    fn solve(
+        &self,
+        board: &BitboardBoard,
+        player: Player,
+        captured_pieces: &CapturedPieces,
+    ) -> Option<TablebaseResult> {
+        if !self.config.enabled {
+            return None;
+        }
+        let attacker = self.attacker(board, captured_pieces)?;
+        solve_lone_king(
+            board,
+            player,
+            captured_pieces,
+            attacker,
+            self.config.max_moves_to_mate,
+            self.config.node_limit,
+        )
+    }
+
+    fn priority(&self) -> u8 {
+        self.config.priority
+    }
+
+    fn name(&self) -> &'static str {
+        "KingSilverGoldVsKing"
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    fn max_depth(&self) -> Option<u8> {
+        Some(self.config.max_moves_to_mate)
+    }
+
+    fn material_signatures(&self) -> Option<Vec<MaterialSignature>> {
+        Some(MaterialSignature::king_and_pieces_vs_king(&MATERIAL).to_vec())
+    }
+}
+
+impl Default for KingSilverGoldVsKingSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::core::{Piece, Position};
+
+    fn build_board(pieces: &[(Player, PieceType, Position)]) -> BitboardBoard {
+        let mut board = BitboardBoard::empty();
+        for (player, piece_type, position) in pieces {
+            board.place_piece(Piece::new(*piece_type, *player), *position);
+        }
+        board
+    }
+
+    #[test]
+    fn test_can_solve_requires_silver_and_gold() {
+        let solver = KingSilverGoldVsKingSolver::new();
+        let board = build_board(&[
+            (Player::White, PieceType::King, Position::new(0, 4)),
+            (Player::White, PieceType::Silver, Position::new(1, 4)),
+            (Player::Black, PieceType::King, Position::new(8, 4)),
+        ]);
+        let mut captured = CapturedPieces::new();
+        captured.add_piece(PieceType::Gold, Player::White);
+        assert!(solver.can_solve(&board, Player::Black, &captured));
+
+        captured.add_piece(PieceType::Gold, Player::White);
+        assert!(!solver.can_solve(&board, Player::Black, &captured));
+    }
+
+    #[test]
+    fn test_white_mates_with_gold_drop() {
+        let solver = KingSilverGoldVsKingSolver::new();
+        let board = build_board(&[
+            (Player::White, PieceType::King, Position::new(6, 7)),
+            (Player::White, PieceType::Silver, Position::new(0, 0)),
+            (Player::Black, PieceType::King, Position::new(8, 8)),
+        ]);
+        let mut captured = CapturedPieces::new();
+        captured.add_piece(PieceType::Gold, Player::White);
+
+        let result = solver
+            .solve(&board, Player::White, &captured)
+            .expect("gold drop mates");
+        assert!(result.is_winning());
+        assert_eq!(result.moves_to_mate, Some(1));
+        let best_move = result.best_move.unwrap();
+        assert!(best_move.from.is_none());
+        assert_eq!(best_move.piece_type, PieceType::Gold);
+    }
+}
@@ -5,6 +5,7 @@
 //! on one side versus a lone king on the other side.
 
 use crate::bitboards::BitboardBoard;
+use crate::tablebase::material_signature::MaterialSignature;
 use crate::tablebase::solver_traits::EndgameSolver;
 use crate::tablebase::tablebase_config::KingSilverConfig;
 use crate::tablebase::TablebaseResult;
@@ -597,6 +598,10 @@ impl EndgameSolver for KingSilverVsKingSolver {
     fn name(&self) -> &'static str {
         "KingSilverVsKing"
     }
+
+    fn material_signatures(&self) -> Option<Vec<MaterialSignature>> {
+        Some(MaterialSignature::king_and_pieces_vs_king(&[PieceType::Silver]).to_vec())
+    }
 }
 
 impl Default for KingSilverVsKingSolver {
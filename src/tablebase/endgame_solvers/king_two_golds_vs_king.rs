@@ -0,0 +1,173 @@
+//! King + two Golds vs King endgame solver
+//!
+//! Two golds and a king are the most common practical material for mating a
+//! bare king: the golds form a wall the king cannot cross while their own king
+//! covers them.
+//!
+//! The defending king has no pieces and an empty hand, so it can neither block
+//! nor drop; the attacker's pieces may stand on the board or wait in hand. Mates
+//! are proven by the node-bounded search in `lone_king`, which also gives the exact
+//! distance to mate for either side to move.
+
+use super::super::material_signature::MaterialSignature;
+use super::super::tablebase_config::KingTwoGoldsConfig;
+use super::super::{EndgameSolver, TablebaseResult};
+use super::lone_king::{has_material, lone_king_attacker, solve_lone_king};
+use crate::types::core::{PieceType, Player};
+use crate::BitboardBoard;
+use crate::CapturedPieces;
+
+/// Non-king material of the attacking side
+const MATERIAL: [PieceType; 2] = [PieceType::Gold, PieceType::Gold];
+
+/// Solver for King + two Golds vs King endgames
+pub struct KingTwoGoldsVsKingSolver {
+    /// Configuration for this solver
+    config: KingTwoGoldsConfig,
+}
+
+impl KingTwoGoldsVsKingSolver {
+    /// Create a new solver with default configuration
+    pub fn new() -> Self {
+        Self::with_config(KingTwoGoldsConfig::default())
+    }
+
+    /// Create a new solver with specified configuration
+    pub fn with_config(config: KingTwoGoldsConfig) -> Self {
+        Self { config }
+    }
+
+    /// The attacking side, if the position has exactly this material
+    fn attacker(&self, board: &BitboardBoard, captured_pieces: &CapturedPieces) -> Option<Player> {
+        let (attacker, pieces) = lone_king_attacker(board, captured_pieces)?;
+        has_material(&pieces, &MATERIAL).then_some(attacker)
+    }
+}
+
+impl EndgameSolver for KingTwoGoldsVsKingSolver {
+    fn can_solve(
+        &self,
+        board: &BitboardBoard,
+        _player: Player,
+        captured_pieces: &CapturedPieces,
+    ) -> bool {
+        self.config.enabled && self.attacker(board, captured_pieces).is_some()
+    }
+
+    fn solve(
+        &self,
+        board: &BitboardBoard,
+        player: Player,
+        captured_pieces: &CapturedPieces,
+    ) -> Option<TablebaseResult> {
+        if !self.config.enabled {
+            return None;
+        }
+        let attacker = self.attacker(board, captured_pieces)?;
+        solve_lone_king(
+            board,
+            player,
+            captured_pieces,
+            attacker,
+            self.config.max_moves_to_mate,
+            self.config.node_limit,
+        )
+    }
+
+    fn priority(&self) -> u8 {
+        self.config.priority
+    }
+
+    fn name(&self) -> &'static str {
+        "KingTwoGoldsVsKing"
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    fn max_depth(&self) -> Option<u8> {
+        Some(self.config.max_moves_to_mate)
+    }
+
+    fn material_signatures(&self) -> Option<Vec<MaterialSignature>> {
+        Some(MaterialSignature::king_and_pieces_vs_king(&MATERIAL).to_vec())
+    }
+}
+
+impl Default for KingTwoGoldsVsKingSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::core::{Piece, Position};
+
+    fn build_board(pieces: &[(Player, PieceType, Position)]) -> BitboardBoard {
+        let mut board = BitboardBoard::empty();
+        for (player, piece_type, position) in pieces {
+            board.place_piece(Piece::new(*piece_type, *player), *position);
+        }
+        board
+    }
+
+    #[test]
+    fn test_can_solve_counts_golds_in_hand() {
+        let solver = KingTwoGoldsVsKingSolver::new();
+        let board = build_board(&[
+            (Player::Black, PieceType::King, Position::new(8, 4)),
+            (Player::Black, PieceType::Gold, Position::new(7, 4)),
+            (Player::White, PieceType::King, Position::new(0, 4)),
+        ]);
+        let mut captured = CapturedPieces::new();
+        assert!(!solver.can_solve(&board, Player::Black, &captured));
+
+        captured.add_piece(PieceType::Gold, Player::Black);
+        assert!(solver.can_solve(&board, Player::Black, &captured));
+        assert!(solver.can_solve(&board, Player::White, &captured));
+
+        // The defender must have an empty hand
+        captured.add_piece(PieceType::Pawn, Player::White);
+        assert!(!solver.can_solve(&board, Player::Black, &captured));
+    }
+
+    #[test]
+    fn test_mate_in_one_and_defender_distance() {
+        let solver = KingTwoGoldsVsKingSolver::new();
+        let board = build_board(&[
+            (Player::Black, PieceType::King, Position::new(2, 1)),
+            (Player::Black, PieceType::Gold, Position::new(2, 0)),
+            (Player::Black, PieceType::Gold, Position::new(8, 8)),
+            (Player::White, PieceType::King, Position::new(0, 0)),
+        ]);
+        let captured = CapturedPieces::new();
+
+        let result = solver
+            .solve(&board, Player::Black, &captured)
+            .expect("mate in one is found");
+        assert!(result.is_winning());
+        assert_eq!(result.moves_to_mate, Some(1));
+
+        let mut mated = board.clone();
+        mated.make_move(result.best_move.as_ref().unwrap());
+        assert!(mated.is_checkmate(Player::White, &captured));
+
+        let defence = solver
+            .solve(&mated, Player::White, &captured)
+            .expect("mated side is solved");
+        assert!(defence.is_losing());
+        assert_eq!(defence.distance_to_mate, Some(0));
+        assert!(defence.best_move.is_none());
+    }
+
+    #[test]
+    fn test_solver_configuration() {
+        let solver = KingTwoGoldsVsKingSolver::with_config(KingTwoGoldsConfig::memory_optimized());
+        assert_eq!(solver.name(), "KingTwoGoldsVsKing");
+        assert_eq!(solver.max_depth(), Some(8));
+        assert_eq!(solver.priority(), 95);
+    }
+}
@@ -0,0 +1,185 @@
+//! Lone-king endgames solved by search
+//!
+//! The single-piece solvers get by on mating patterns, but once the attacker has
+//! two pieces the mating net depends on where both of them stand. These positions
+//! are solved with the mate searcher, letting the attacker play quiet moves as
+//! well as checks, so the distance reported is exact. The search is bounded by a
+//! node budget rather than time.
+
+use super::super::{TablebaseOutcome, TablebaseResult};
+use crate::bitboards::BitboardBoard;
+use crate::moves::MoveGenerator;
+use crate::search::mate_search::{MateSearchResult, MateSearcher};
+use crate::search::search_engine::make_move_with_hand;
+use crate::types::board::CapturedPieces;
+use crate::types::core::{Move, PieceType, Player, Position};
+
+/// The side attacking a bare king and its pieces other than the king, on the
+/// board and in hand. The defender must have nothing but its king: no pieces on
+/// the board and an empty hand, so it can never interpose a drop.
+pub fn lone_king_attacker(
+    board: &BitboardBoard,
+    captured_pieces: &CapturedPieces,
+) -> Option<(Player, Vec<PieceType>)> {
+    let mut black = Vec::new();
+    let mut white = Vec::new();
+    let mut kings = 0;
+    for row in 0..9 {
+        for col in 0..9 {
+            if let Some(piece) = board.get_piece(Position::new(row, col)) {
+                if piece.piece_type == PieceType::King {
+                    kings += 1;
+                    continue;
+                }
+                match piece.player {
+                    Player::Black => black.push(piece.piece_type),
+                    Player::White => white.push(piece.piece_type),
+                }
+            }
+        }
+    }
+    if kings != 2 {
+        return None;
+    }
+    black.extend(captured_pieces.black.iter().copied());
+    white.extend(captured_pieces.white.iter().copied());
+
+    match (black.is_empty(), white.is_empty()) {
+        (false, true) => Some((Player::Black, black)),
+        (true, false) => Some((Player::White, white)),
+        _ => None,
+    }
+}
+
+/// Whether `pieces` holds exactly the pieces in `pattern`, in any order
+pub fn has_material(pieces: &[PieceType], pattern: &[PieceType]) -> bool {
+    let mut remaining = pieces.to_vec();
+    for piece_type in pattern {
+        match remaining.iter().position(|p| p == piece_type) {
+            Some(index) => {
+                remaining.swap_remove(index);
+            }
+            None => return false,
+        }
+    }
+    remaining.is_empty()
+}
+
+/// Solve a lone-king position by search: a win with the shortest mate when
+/// `player` is the attacker, a loss with the longest defence when `player` is
+/// the bare king. `None` when no mate is proven within the limits.
+pub fn solve_lone_king(
+    board: &BitboardBoard,
+    player: Player,
+    captured_pieces: &CapturedPieces,
+    attacker: Player,
+    max_moves_to_mate: u8,
+    node_limit: usize,
+) -> Option<TablebaseResult> {
+    if player == attacker {
+        let line = find_mate(board, captured_pieces, attacker, max_moves_to_mate, node_limit)?;
+        let moves_to_mate = attacker_moves(&line);
+        return Some(TablebaseResult::win(line.into_iter().next(), moves_to_mate).proven());
+    }
+
+    // A defender without legal moves has already lost
+    let mut longest: (Option<Move>, u8) = (None, 0);
+    for reply in MoveGenerator::new().generate_legal_moves(board, player, captured_pieces) {
+        let mut next_board = board.clone();
+        let (_, next_captured) =
+            make_move_with_hand(&mut next_board, captured_pieces, &reply, player);
+        let line =
+            find_mate(&next_board, &next_captured, attacker, max_moves_to_mate, node_limit)?;
+        let moves = attacker_moves(&line);
+        if longest.0.is_none() || moves > longest.1 {
+            longest = (Some(reply), moves);
+        }
+    }
+    let (reply, moves) = longest;
+    Some(TablebaseResult::new(reply, Some(-(moves as i32)), TablebaseOutcome::Loss, 1.0).proven())
+}
+
+/// Shortest forced mate for `attacker` to move within `max_moves` attacker moves
+fn find_mate(
+    board: &BitboardBoard,
+    captured_pieces: &CapturedPieces,
+    attacker: Player,
+    max_moves: u8,
+    node_limit: usize,
+) -> Option<Vec<Move>> {
+    let max_ply = max_moves.saturating_mul(2).saturating_sub(1);
+    let mut searcher = MateSearcher::new(max_ply, None, None)
+        .with_quiet_moves()
+        .with_node_limit(node_limit as u64);
+    match searcher.search(board, captured_pieces, attacker) {
+        MateSearchResult::Mate(line) => Some(line),
+        MateSearchResult::NoMate | MateSearchResult::Timeout => None,
+    }
+}
+
+/// Attacker moves in a mating line, which starts and ends with one
+fn attacker_moves(line: &[Move]) -> u8 {
+    ((line.len() + 1) / 2) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::core::Piece;
+
+    fn build_board(pieces: &[(Player, PieceType, Position)]) -> BitboardBoard {
+        let mut board = BitboardBoard::empty();
+        for (player, piece_type, position) in pieces {
+            board.place_piece(Piece::new(*piece_type, *player), *position);
+        }
+        board
+    }
+
+    #[test]
+    fn test_lone_king_attacker_counts_hand_pieces() {
+        let board = build_board(&[
+            (Player::Black, PieceType::King, Position::new(8, 4)),
+            (Player::Black, PieceType::Gold, Position::new(7, 4)),
+            (Player::White, PieceType::King, Position::new(0, 4)),
+        ]);
+        let mut captured = CapturedPieces::new();
+        captured.add_piece(PieceType::Silver, Player::Black);
+
+        let (attacker, pieces) = lone_king_attacker(&board, &captured).unwrap();
+        assert_eq!(attacker, Player::Black);
+        assert!(has_material(&pieces, &[PieceType::Silver, PieceType::Gold]));
+        assert!(!has_material(&pieces, &[PieceType::Gold, PieceType::Gold]));
+
+        // A defender with anything in hand is not a lone king
+        captured.add_piece(PieceType::Pawn, Player::White);
+        assert!(lone_king_attacker(&board, &captured).is_none());
+    }
+
+    #[test]
+    fn test_mate_in_one_by_drop() {
+        // White king in the corner; a gold dropped next to it is protected by the Black king
+        let board = build_board(&[
+            (Player::Black, PieceType::King, Position::new(2, 1)),
+            (Player::White, PieceType::King, Position::new(0, 0)),
+        ]);
+        let mut captured = CapturedPieces::new();
+        captured.add_piece(PieceType::Gold, Player::Black);
+
+        let win = solve_lone_king(&board, Player::Black, &captured, Player::Black, 3, 10_000)
+            .expect("gold drop mates");
+        assert_eq!(win.outcome, TablebaseOutcome::Win);
+        assert_eq!(win.distance_to_mate, Some(1));
+
+        let mut after = board.clone();
+        let best_move = win.best_move.unwrap();
+        let (_, after_captured) =
+            make_move_with_hand(&mut after, &captured, &best_move, Player::Black);
+        assert!(after.is_checkmate(Player::White, &after_captured));
+        assert!(after_captured.black.is_empty());
+
+        let loss = solve_lone_king(&after, Player::White, &after_captured, Player::Black, 3, 10_000)
+            .expect("already mated");
+        assert_eq!(loss.outcome, TablebaseOutcome::Loss);
+        assert_eq!((loss.best_move, loss.distance_to_mate), (None, Some(0)));
+    }
+}
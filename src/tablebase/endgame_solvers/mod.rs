@@ -8,10 +8,15 @@
 pub mod dtm_calculator;
 pub mod king_gold_vs_king;
 pub mod king_rook_vs_king;
+pub mod king_silver_gold_vs_king;
 pub mod king_silver_vs_king;
+pub mod king_two_golds_vs_king;
+pub mod lone_king;
 
 // Re-export solver types
 pub use dtm_calculator::{calculate_dtm, calculate_dtm_with_cache};
 pub use king_gold_vs_king::KingGoldVsKingSolver;
 pub use king_rook_vs_king::KingRookVsKingSolver;
+pub use king_silver_gold_vs_king::KingSilverGoldVsKingSolver;
 pub use king_silver_vs_king::KingSilverVsKingSolver;
+pub use king_two_golds_vs_king::KingTwoGoldsVsKingSolver;
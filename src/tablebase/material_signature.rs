@@ -0,0 +1,129 @@
+//! Material signatures for probe filtering
+//!
+//! The search probes the tablebase at every root, but the solvers only handle a
+//! handful of material configurations. A signature packs the piece counts of both
+//! sides, board and hand together, into one integer, so a probe whose material no
+//! solver handles is rejected with a set lookup before the cache or any solver is
+//! consulted. A match is only a precondition: `can_solve` still decides, for
+//! example whether a piece may be in hand.
+
+use crate::bitboards::BitboardBoard;
+use crate::types::board::CapturedPieces;
+use crate::types::core::{PieceType, Player};
+
+const BITS_PER_COUNT: u32 = 4;
+const MAX_COUNT: u128 = (1 << BITS_PER_COUNT) - 1;
+const PIECE_TYPES: u32 = 14;
+
+/// Piece counts per (player, piece type), 4 bits each and saturating at 15
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct MaterialSignature(u128);
+
+impl MaterialSignature {
+    fn shift(player: Player, piece_type: PieceType) -> u32 {
+        let player_offset = if player == Player::Black { 0 } else { PIECE_TYPES };
+        (player_offset + piece_type.to_u8() as u32) * BITS_PER_COUNT
+    }
+
+    /// Signature with no pieces at all
+    pub fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Signature of a position, counting pieces in hand with those on the board
+    pub fn from_position(board: &BitboardBoard, captured_pieces: &CapturedPieces) -> Self {
+        let mut signature = Self::empty();
+        for (player_idx, player) in [Player::Black, Player::White].into_iter().enumerate() {
+            for (piece_idx, bitboard) in board.get_pieces()[player_idx].iter().enumerate() {
+                let count = bitboard.count_ones() as u128;
+                if count > 0 {
+                    signature.add(player, PieceType::from_u8(piece_idx as u8), count);
+                }
+            }
+        }
+        for piece_type in &captured_pieces.black {
+            signature.add(Player::Black, *piece_type, 1);
+        }
+        for piece_type in &captured_pieces.white {
+            signature.add(Player::White, *piece_type, 1);
+        }
+        signature
+    }
+
+    fn add(&mut self, player: Player, piece_type: PieceType, count: u128) {
+        let shift = Self::shift(player, piece_type);
+        let current = (self.0 >> shift) & MAX_COUNT;
+        let updated = (current + count).min(MAX_COUNT);
+        self.0 = (self.0 & !(MAX_COUNT << shift)) | (updated << shift);
+    }
+
+    /// This signature with one more `piece_type` for `player`
+    pub fn with_piece(mut self, player: Player, piece_type: PieceType) -> Self {
+        self.add(player, piece_type, 1);
+        self
+    }
+
+    /// Number of `piece_type` pieces `player` has, board and hand together
+    pub fn count(&self, player: Player, piece_type: PieceType) -> u8 {
+        ((self.0 >> Self::shift(player, piece_type)) & MAX_COUNT) as u8
+    }
+
+    /// The same material with the colours swapped
+    pub fn mirrored(&self) -> Self {
+        let side_bits = PIECE_TYPES * BITS_PER_COUNT;
+        let side_mask = (1u128 << side_bits) - 1;
+        let black = self.0 & side_mask;
+        let white = (self.0 >> side_bits) & side_mask;
+        Self((black << side_bits) | white)
+    }
+
+    /// A king and `pieces` against a bare king, with either colour attacking
+    pub fn king_and_pieces_vs_king(pieces: &[PieceType]) -> [MaterialSignature; 2] {
+        let mut black = Self::empty()
+            .with_piece(Player::Black, PieceType::King)
+            .with_piece(Player::White, PieceType::King);
+        for piece_type in pieces {
+            black = black.with_piece(Player::Black, *piece_type);
+        }
+        [black, black.mirrored()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::core::{Piece, Position};
+
+    #[test]
+    fn test_signature_counts_board_and_hand() {
+        let mut board = BitboardBoard::empty();
+        board.place_piece(Piece::new(PieceType::King, Player::Black), Position::new(8, 4));
+        board.place_piece(Piece::new(PieceType::Gold, Player::Black), Position::new(7, 4));
+        board.place_piece(Piece::new(PieceType::King, Player::White), Position::new(0, 4));
+        let mut captured = CapturedPieces::new();
+        captured.add_piece(PieceType::Gold, Player::Black);
+
+        let signature = MaterialSignature::from_position(&board, &captured);
+        assert_eq!(signature.count(Player::Black, PieceType::Gold), 2);
+        assert_eq!(signature.count(Player::White, PieceType::King), 1);
+        assert_eq!(
+            signature,
+            MaterialSignature::king_and_pieces_vs_king(&[PieceType::Gold, PieceType::Gold])[0]
+        );
+        assert_eq!(signature.mirrored().count(Player::White, PieceType::Gold), 2);
+        assert_eq!(signature.mirrored().mirrored(), signature);
+    }
+
+    #[test]
+    fn test_counts_saturate() {
+        let board = BitboardBoard::new();
+        let mut captured = CapturedPieces::new();
+        for _ in 0..18 {
+            captured.add_piece(PieceType::Pawn, Player::White);
+        }
+        let signature = MaterialSignature::from_position(&board, &captured);
+        assert_eq!(signature.count(Player::White, PieceType::Pawn), 15);
+        assert_eq!(signature.count(Player::White, PieceType::Lance), 2);
+        assert_eq!(signature.count(Player::Black, PieceType::Pawn), 9);
+    }
+}
@@ -4,15 +4,20 @@
 //! all endgame solvers and provides the primary interface for tablebase
 //! functionality.
 
-use super::endgame_solvers::{KingGoldVsKingSolver, KingRookVsKingSolver, KingSilverVsKingSolver};
+use super::endgame_solvers::{
+    KingGoldVsKingSolver, KingRookVsKingSolver, KingSilverGoldVsKingSolver,
+    KingSilverVsKingSolver, KingTwoGoldsVsKingSolver,
+};
 use super::{
-    EndgameSolver, PositionAnalyzer, PositionCache, TablebaseConfig, TablebaseProfiler,
-    TablebaseResult, TablebaseStats,
+    EndgameSolver, MaterialSignature, PositionAnalyzer, PositionCache, TableFile, TablebaseConfig,
+    TablebaseProfiler, TablebaseResult, TablebaseStats,
 };
+use crate::asset_storage::AssetStats;
 use crate::utils::time::TimeSource;
 use crate::types::core::{Player, Position};
 use crate::BitboardBoard;
 use crate::CapturedPieces;
+use std::collections::HashSet;
 
 /// Main tablebase implementation
 ///
@@ -65,6 +70,7 @@ use crate::CapturedPieces;
 ///
 /// - Cache probe latency (warm cache): **< 1ms**
 /// - Solver computation latency (supported K+G/K+S/K+R endgames): **< 10ms**
+/// - K+2G and K+S+G are searched and bounded by their node limit instead
 /// - Move ordering cache should prevent repeated tablebase probes during a search iteration
 ///
 /// ## Memory Management
@@ -81,6 +87,9 @@ use crate::CapturedPieces;
 pub struct MicroTablebase {
     /// List of endgame solvers, sorted by priority
     solvers: Vec<Box<dyn EndgameSolver>>,
+    /// Material signatures of the enabled solvers; `None` when a solver
+    /// cannot be filtered by material and every probe has to reach it
+    material_filter: Option<HashSet<MaterialSignature>>,
     /// Position cache for storing results
     position_cache: PositionCache,
     /// Configuration for the tablebase system
@@ -94,6 +103,8 @@ pub struct MicroTablebase {
     /// Performance profiler for detailed timing analysis
     #[allow(dead_code)]
     profiler: TablebaseProfiler,
+    /// Solved positions read from disk, consulted before the solvers
+    table_files: Vec<TableFile>,
 }
 
 impl MicroTablebase {
@@ -111,6 +122,12 @@ impl MicroTablebase {
         solvers.push(Box::new(KingGoldVsKingSolver::new()));
         solvers.push(Box::new(KingSilverVsKingSolver::new()));
         solvers.push(Box::new(KingRookVsKingSolver::new()));
+        solvers.push(Box::new(KingTwoGoldsVsKingSolver::with_config(
+            config.solvers.king_two_golds_vs_king.clone(),
+        )));
+        solvers.push(Box::new(KingSilverGoldVsKingSolver::with_config(
+            config.solvers.king_silver_gold_vs_king.clone(),
+        )));
 
         // Sort by priority (highest first)
         solvers.sort_by_key(|s| std::cmp::Reverse(s.priority()));
@@ -124,14 +141,75 @@ impl MicroTablebase {
             enable_adaptive_eviction: config.performance.enable_adaptive_caching,
         };
 
+        let material_filter = Self::build_material_filter(&solvers);
+
         Self {
             solvers,
+            material_filter,
             position_cache: PositionCache::with_config(cache_config),
             config,
             stats: TablebaseStats::new(),
             last_memory_check: TimeSource::now(),
             position_analyzer: PositionAnalyzer::new(),
             profiler: TablebaseProfiler::new(),
+            table_files: Vec::new(),
+        }
+    }
+
+    /// Open a tablebase file and consult it on later probes; returns its record count
+    pub fn load_table_file(
+        &mut self,
+        path: impl AsRef<std::path::Path>,
+    ) -> std::io::Result<usize> {
+        let table = TableFile::open(path)?;
+        let records = table.len();
+        self.table_files.push(table);
+        Ok(records)
+    }
+
+    /// Memory use of the loaded tablebase files
+    pub fn asset_stats(&self) -> Vec<AssetStats> {
+        self.table_files.iter().map(TableFile::stats).collect()
+    }
+
+    /// Look the position up in the tablebase files, recording a hit as a solver hit
+    fn probe_table_files(
+        &mut self,
+        board: &BitboardBoard,
+        player: Player,
+        captured_pieces: &CapturedPieces,
+    ) -> Option<TablebaseResult> {
+        if self.table_files.is_empty() {
+            return None;
+        }
+        let start_time = TimeSource::now();
+        let result = self
+            .table_files
+            .iter()
+            .find_map(|table| table.probe(board, player, captured_pieces))?;
+        self.stats
+            .record_probe(false, true, Some("TableFile"), start_time.elapsed_ms() as u64);
+        Some(result)
+    }
+
+    /// Collect the material signatures of all enabled solvers
+    fn build_material_filter(
+        solvers: &[Box<dyn EndgameSolver>],
+    ) -> Option<HashSet<MaterialSignature>> {
+        let mut filter = HashSet::new();
+        for solver in solvers.iter().filter(|solver| solver.is_enabled()) {
+            filter.extend(solver.material_signatures()?);
+        }
+        Some(filter)
+    }
+
+    /// Whether no enabled solver handles the material of this position
+    pub fn is_filtered(&self, board: &BitboardBoard, captured_pieces: &CapturedPieces) -> bool {
+        match &self.material_filter {
+            Some(filter) => {
+                !filter.contains(&MaterialSignature::from_position(board, captured_pieces))
+            }
+            None => false,
         }
     }
 
@@ -237,6 +315,17 @@ impl MicroTablebase {
             return None;
         }
 
+        // Files may hold material no solver handles, so they come before the filter
+        if let Some(result) = self.probe_table_files(board, player, captured_pieces) {
+            return Some(result);
+        }
+
+        // Material no solver handles cannot be cached either, so skip everything
+        if self.is_filtered(board, captured_pieces) {
+            self.stats.record_filtered_probe();
+            return None;
+        }
+
         // Check memory usage before probing
         self.check_memory_usage();
 
@@ -323,6 +412,15 @@ impl MicroTablebase {
             return None;
         }
 
+        if let Some(result) = self.probe_table_files(board, player, captured_pieces) {
+            return Some(result);
+        }
+
+        if self.is_filtered(board, captured_pieces) {
+            self.stats.record_filtered_probe();
+            return None;
+        }
+
         // Check cache first
         if let Some(cached_result) = self.position_cache.get(board, player, captured_pieces) {
             let probe_time = start_time.elapsed_ms() as u64;
@@ -474,7 +572,7 @@ impl Clone for MicroTablebase {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::{Move, PieceType, Player, Position};
+    use crate::types::{Move, Piece, PieceType, Player, Position};
     use crate::BitboardBoard;
     use crate::CapturedPieces;
 
@@ -550,7 +648,7 @@ mod tests {
     fn test_micro_tablebase_creation() {
         let tablebase = MicroTablebase::new();
         assert!(tablebase.is_enabled());
-        assert_eq!(tablebase.solver_count(), 3); // Now we have all three solvers
+        assert_eq!(tablebase.solver_count(), 5);
         assert_eq!(tablebase.get_cache_stats(), (0, 0, 0.0));
     }
 
@@ -586,14 +684,38 @@ mod tests {
         let stats = tablebase.get_stats();
         assert_eq!(stats.total_probes, 0);
 
-        // Probe should return None (no solvers)
+        // No solver handles the starting material, so the probe is filtered
         let result = tablebase.probe_with_stats(&board, player, &captured_pieces);
         assert!(result.is_none());
 
-        // Should have recorded a probe
+        // Should have recorded a filtered probe rather than a miss
         let stats = tablebase.get_stats();
         assert_eq!(stats.total_probes, 1);
-        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.filtered_probes, 1);
+        assert_eq!(stats.misses, 0);
+    }
+
+    #[test]
+    fn test_probe_filtering_by_material() {
+        let mut tablebase = MicroTablebase::new();
+        assert!(tablebase.is_filtered(&BitboardBoard::new(), &CapturedPieces::new()));
+
+        // K+G vs K passes the filter and reaches the solvers
+        let mut board = BitboardBoard::empty();
+        board.place_piece(Piece::new(PieceType::King, Player::White), Position::new(0, 4));
+        board.place_piece(Piece::new(PieceType::King, Player::Black), Position::new(8, 4));
+        board.place_piece(Piece::new(PieceType::Gold, Player::Black), Position::new(7, 4));
+        let captured_pieces = CapturedPieces::new();
+        assert!(!tablebase.is_filtered(&board, &captured_pieces));
+        tablebase.probe(&board, Player::Black, &captured_pieces);
+        assert_eq!(tablebase.get_stats().filtered_probes, 0);
+
+        // A pawn for the defender matches no solver
+        board.place_piece(Piece::new(PieceType::Pawn, Player::White), Position::new(2, 0));
+        assert!(tablebase.probe(&board, Player::Black, &captured_pieces).is_none());
+        let stats = tablebase.get_stats();
+        assert_eq!(stats.filtered_probes, 1);
+        assert_eq!(stats.total_probes, 2);
     }
 
     #[test]
@@ -69,29 +69,35 @@
 //! Key components:
 //! - `micro_tablebase.rs`: Core tablebase implementation
 //! - `endgame_solvers/`: Individual endgame solvers for specific scenarios
+//! - `material_signature.rs`: Material signatures that filter probes before the solvers
 //! - `position_cache.rs`: Position caching system for performance
 //! - `solver_traits.rs`: Common traits for endgame solvers
 //! - `tablebase_config.rs`: Configuration management
+//! - `table_file.rs`: Solved positions read from disk through `AssetStorage`
 
 use crate::types::core::Move;
 use serde::{Deserialize, Serialize};
 
 pub mod endgame_solvers;
+pub mod material_signature;
 pub mod micro_tablebase;
 pub mod pattern_matching;
 pub mod performance_profiler;
 pub mod position_analysis;
 pub mod position_cache;
 pub mod solver_traits;
+pub mod table_file;
 pub mod tablebase_config;
 
 // Re-export commonly used types
+pub use material_signature::MaterialSignature;
 pub use micro_tablebase::MicroTablebase;
 pub use pattern_matching::PatternMatcher;
 pub use performance_profiler::{OperationProfiler, PerformanceMetrics, TablebaseProfiler};
 pub use position_analysis::{PositionAnalysis, PositionAnalyzer, PositionComplexity};
 pub use position_cache::PositionCache;
 pub use solver_traits::EndgameSolver;
+pub use table_file::TableFile;
 pub use tablebase_config::{TablebaseConfig, TablebaseStats};
 
 /// Result of a tablebase probe containing the optimal move and position analysis
@@ -107,6 +113,10 @@ pub struct TablebaseResult {
     pub outcome: TablebaseOutcome,
     /// Confidence level (0.0 to 1.0)
     pub confidence: f32,
+    /// Proven by a generated table or an exhaustive mate search, rather than
+    /// judged by a solver's heuristics
+    #[serde(default)]
+    pub exact: bool,
 }
 
 impl TablebaseResult {
@@ -128,9 +138,15 @@ impl TablebaseResult {
             moves_to_mate,
             outcome,
             confidence,
+            exact: false,
         }
     }
 
+    /// This result, marked as proven
+    pub fn proven(self) -> Self {
+        Self { exact: true, ..self }
+    }
+
     /// Create a winning result
     pub fn win(best_move: Option<Move>, moves_to_mate: u8) -> Self {
         Self::new(
@@ -242,11 +242,7 @@ impl PositionCache {
         player: Player,
         captured_pieces: &CapturedPieces,
     ) -> u64 {
-        let mut hash = board.get_position_hash(captured_pieces);
-        if player == Player::White {
-            hash ^= 0x9E37_79B1_85EB_CA87;
-        }
-        hash
+        super::table_file::position_key(board, player, captured_pieces)
     }
 
     /// Get current timestamp for LRU tracking
@@ -4,6 +4,7 @@
 //! It provides a trait-based architecture that allows for easy extension and
 //! modular implementation of different endgame scenarios.
 
+use super::material_signature::MaterialSignature;
 use super::TablebaseResult;
 use crate::types::core::Player;
 use crate::BitboardBoard;
@@ -95,6 +96,19 @@ pub trait EndgameSolver: Send + Sync {
         None
     }
 
+    /// Get the material configurations this solver handles
+    ///
+    /// The tablebase rejects probes whose material matches no enabled solver
+    /// before calling `can_solve`, so a solver must list every signature its
+    /// `can_solve` may accept.
+    ///
+    /// # Returns
+    /// The accepted signatures, or `None` if the solver cannot be filtered by
+    /// material and every probe must reach it
+    fn material_signatures(&self) -> Option<Vec<MaterialSignature>> {
+        None
+    }
+
     /// Get solver-specific configuration
     ///
     /// This method can be used to provide solver-specific configuration
@@ -0,0 +1,197 @@
+//! Tablebase files
+//!
+//! Solved positions stored on disk as fixed-size records sorted by position
+//! key (the same key the position cache uses), so a probe binary-searches the
+//! file through `AssetStorage` and reads only the pages it lands on. Files are
+//! written from solver results with `write_table`, letting positions that take
+//! the solvers long to work out be answered from disk instead.
+//!
+//! Layout, little-endian: `SBTB`, version `u32`, record count `u64`, then the
+//! records. Each record is the key `u64`, outcome `u8`, moves to mate `u8`
+//! (0xFF for none), move origin `u8` (0xFF for a drop), destination `u8`, piece
+//! type `u8`, flags `u8` (bit 0: has a move, bit 1: promotion) and two bytes of
+//! padding.
+
+use super::{TablebaseOutcome, TablebaseResult};
+use crate::asset_storage::{AssetStats, AssetStorage};
+use crate::bitboards::BitboardBoard;
+use crate::search::BoardTrait;
+use crate::types::board::CapturedPieces;
+use crate::types::core::{Move, PieceType, Player, Position};
+use std::io;
+use std::path::Path;
+
+const MAGIC: [u8; 4] = *b"SBTB";
+const VERSION: u32 = 1;
+const HEADER_SIZE: usize = 16;
+const RECORD_SIZE: usize = 16;
+const NONE: u8 = 0xFF;
+
+/// Key of a position in a table file
+pub fn position_key(
+    board: &BitboardBoard,
+    player: Player,
+    captured_pieces: &CapturedPieces,
+) -> u64 {
+    let mut key = board.get_position_hash(captured_pieces);
+    if player == Player::White {
+        key ^= 0x9E37_79B1_85EB_CA87;
+    }
+    key
+}
+
+/// A tablebase file of solved positions
+#[derive(Debug)]
+pub struct TableFile {
+    storage: AssetStorage,
+    records: usize,
+}
+
+impl TableFile {
+    /// Map the table file at `path`, or read it where mapping is unavailable
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_storage(AssetStorage::open(path)?)
+    }
+
+    pub fn from_storage(storage: AssetStorage) -> io::Result<Self> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message);
+        if storage.read(0, 4) != Some(&MAGIC[..]) {
+            return Err(invalid("not a tablebase file"));
+        }
+        if storage.read_u32(4) != Some(VERSION) {
+            return Err(invalid("unsupported tablebase file version"));
+        }
+        let records = storage.read_u64(8).unwrap_or(0) as usize;
+        if records
+            .checked_mul(RECORD_SIZE)
+            .map_or(true, |size| HEADER_SIZE + size > storage.len())
+        {
+            return Err(invalid("truncated tablebase file"));
+        }
+        Ok(Self { storage, records })
+    }
+
+    pub fn len(&self) -> usize {
+        self.records
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records == 0
+    }
+
+    pub fn stats(&self) -> AssetStats {
+        self.storage.stats()
+    }
+
+    /// The stored result for the position, if the file has it
+    pub fn probe(
+        &self,
+        board: &BitboardBoard,
+        player: Player,
+        captured_pieces: &CapturedPieces,
+    ) -> Option<TablebaseResult> {
+        let key = position_key(board, player, captured_pieces);
+        let (mut low, mut high) = (0, self.records);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let record = self.storage.read(HEADER_SIZE + mid * RECORD_SIZE, RECORD_SIZE)?;
+            match u64::from_le_bytes(record[..8].try_into().ok()?).cmp(&key) {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+                std::cmp::Ordering::Equal => return Some(decode(&record[8..], player)),
+            }
+        }
+        None
+    }
+}
+
+/// Table file bytes for `entries`, each a position key and its result
+pub fn write_table(entries: &[(u64, TablebaseResult)]) -> Vec<u8> {
+    let mut sorted: Vec<&(u64, TablebaseResult)> = entries.iter().collect();
+    sorted.sort_by_key(|(key, _)| *key);
+    sorted.dedup_by_key(|(key, _)| *key);
+
+    let mut bytes = Vec::with_capacity(HEADER_SIZE + sorted.len() * RECORD_SIZE);
+    bytes.extend_from_slice(&MAGIC);
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+    bytes.extend_from_slice(&(sorted.len() as u64).to_le_bytes());
+    for (key, result) in sorted {
+        bytes.extend_from_slice(&key.to_le_bytes());
+        bytes.extend_from_slice(&encode(result));
+    }
+    bytes
+}
+
+fn encode(result: &TablebaseResult) -> [u8; 8] {
+    let outcome = match result.outcome {
+        TablebaseOutcome::Win => 0,
+        TablebaseOutcome::Loss => 1,
+        TablebaseOutcome::Draw => 2,
+        TablebaseOutcome::Unknown => 3,
+    };
+    let moves_to_mate = result
+        .distance_to_mate
+        .and_then(|distance| u8::try_from(distance.unsigned_abs()).ok())
+        .filter(|&distance| distance != NONE)
+        .unwrap_or(NONE);
+    let mut record = [outcome, moves_to_mate, NONE, 0, 0, 0, 0, 0];
+    if let Some(mv) = &result.best_move {
+        record[2] = mv.from.map_or(NONE, Position::to_u8);
+        record[3] = mv.to.to_u8();
+        record[4] = mv.piece_type.to_u8();
+        record[5] = 0x01 | if mv.is_promotion { 0x02 } else { 0 };
+    }
+    record
+}
+
+fn decode(record: &[u8], player: Player) -> TablebaseResult {
+    let best_move = (record[5] & 0x01 != 0).then(|| {
+        let (to, piece_type) = (Position::from_u8(record[3]), PieceType::from_u8(record[4]));
+        match record[2] {
+            NONE => Move::new_drop(piece_type, to, player),
+            from => Move::new_move(
+                Position::from_u8(from),
+                to,
+                piece_type,
+                player,
+                record[5] & 0x02 != 0,
+            ),
+        }
+    });
+    let moves_to_mate = (record[1] != NONE).then_some(record[1]);
+    match (record[0], moves_to_mate) {
+        (0, Some(moves)) => TablebaseResult::win(best_move, moves).proven(),
+        (1, Some(moves)) => TablebaseResult { best_move, ..TablebaseResult::loss(moves) }.proven(),
+        (2, _) => TablebaseResult { best_move, ..TablebaseResult::draw() }.proven(),
+        (0, None) => TablebaseResult::new(best_move, None, TablebaseOutcome::Win, 1.0).proven(),
+        (1, None) => TablebaseResult::new(best_move, None, TablebaseOutcome::Loss, 1.0).proven(),
+        _ => TablebaseResult::new(best_move, None, TablebaseOutcome::Unknown, 1.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_written_results_probe_back() {
+        let (board, player, captured) =
+            BitboardBoard::from_fen("4k4/9/4G4/9/9/9/9/9/4K4 b G 1").unwrap();
+        let mate = Move::new_drop(PieceType::Gold, Position::new(1, 4), player);
+        let key = position_key(&board, player, &captured);
+        let other = position_key(&board, player.opposite(), &captured);
+        let bytes = write_table(&[
+            (key, TablebaseResult::win(Some(mate.clone()), 1)),
+            (other, TablebaseResult::draw()),
+        ]);
+
+        let table = TableFile::from_storage(AssetStorage::from_bytes("kg.sbtb", bytes)).unwrap();
+        assert_eq!(table.len(), 2);
+        let result = table.probe(&board, player, &captured).unwrap();
+        assert_eq!(result.outcome, TablebaseOutcome::Win);
+        assert_eq!(result.moves_to_mate, Some(1));
+        assert_eq!(result.best_move, Some(mate));
+        assert!(table.probe(&board, player.opposite(), &captured).unwrap().is_draw());
+        assert!(TableFile::from_storage(AssetStorage::from_bytes("x", vec![0; 16])).is_err());
+    }
+}
@@ -157,6 +157,12 @@ pub struct SolverConfig {
     pub king_silver_vs_king: KingSilverConfig,
     /// King + Rook vs King solver configuration
     pub king_rook_vs_king: KingRookConfig,
+    /// King + two Golds vs King solver configuration
+    #[serde(default)]
+    pub king_two_golds_vs_king: KingTwoGoldsConfig,
+    /// King + Silver + Gold vs King solver configuration
+    #[serde(default)]
+    pub king_silver_gold_vs_king: KingSilverGoldConfig,
 }
 
 impl Default for SolverConfig {
@@ -165,6 +171,8 @@ impl Default for SolverConfig {
             king_gold_vs_king: KingGoldConfig::default(),
             king_silver_vs_king: KingSilverConfig::default(),
             king_rook_vs_king: KingRookConfig::default(),
+            king_two_golds_vs_king: KingTwoGoldsConfig::default(),
+            king_silver_gold_vs_king: KingSilverGoldConfig::default(),
         }
     }
 }
@@ -176,6 +184,8 @@ impl SolverConfig {
             king_gold_vs_king: KingGoldConfig::performance_optimized(),
             king_silver_vs_king: KingSilverConfig::performance_optimized(),
             king_rook_vs_king: KingRookConfig::performance_optimized(),
+            king_two_golds_vs_king: KingTwoGoldsConfig::performance_optimized(),
+            king_silver_gold_vs_king: KingSilverGoldConfig::performance_optimized(),
         }
     }
 
@@ -185,6 +195,8 @@ impl SolverConfig {
             king_gold_vs_king: KingGoldConfig::memory_optimized(),
             king_silver_vs_king: KingSilverConfig::memory_optimized(),
             king_rook_vs_king: KingRookConfig::memory_optimized(),
+            king_two_golds_vs_king: KingTwoGoldsConfig::memory_optimized(),
+            king_silver_gold_vs_king: KingSilverGoldConfig::memory_optimized(),
         }
     }
 
@@ -193,6 +205,8 @@ impl SolverConfig {
         self.king_gold_vs_king.validate()?;
         self.king_silver_vs_king.validate()?;
         self.king_rook_vs_king.validate()?;
+        self.king_two_golds_vs_king.validate()?;
+        self.king_silver_gold_vs_king.validate()?;
         Ok(())
     }
 
@@ -202,6 +216,10 @@ impl SolverConfig {
         self.king_silver_vs_king
             .merge_with(&other.king_silver_vs_king);
         self.king_rook_vs_king.merge_with(&other.king_rook_vs_king);
+        self.king_two_golds_vs_king
+            .merge_with(&other.king_two_golds_vs_king);
+        self.king_silver_gold_vs_king
+            .merge_with(&other.king_silver_gold_vs_king);
     }
 }
 
@@ -418,6 +436,140 @@ impl KingRookConfig {
     }
 }
 
+/// Configuration for King + two Golds vs King solver
+///
+/// Solved by bounded mate search rather than patterns, so there is no pattern cache.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KingTwoGoldsConfig {
+    /// Whether this solver is enabled
+    pub enabled: bool,
+    /// Maximum moves to mate this solver can handle
+    pub max_moves_to_mate: u8,
+    /// Positions the mate search may visit before giving up
+    pub node_limit: usize,
+    /// Priority of this solver
+    pub priority: u8,
+}
+
+impl Default for KingTwoGoldsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_moves_to_mate: 15,
+            node_limit: 20_000,
+            priority: 95,
+        }
+    }
+}
+
+impl KingTwoGoldsConfig {
+    /// Create a performance-optimized configuration
+    pub fn performance_optimized() -> Self {
+        Self {
+            enabled: true,
+            max_moves_to_mate: 20,
+            node_limit: 100_000,
+            priority: 95,
+        }
+    }
+
+    /// Create a memory-optimized configuration
+    pub fn memory_optimized() -> Self {
+        Self {
+            enabled: true,
+            max_moves_to_mate: 8,
+            node_limit: 5_000,
+            priority: 95,
+        }
+    }
+
+    /// Validate the configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_moves_to_mate == 0 {
+            return Err("Max moves to mate must be greater than 0".to_string());
+        }
+        if self.node_limit == 0 {
+            return Err("Node limit must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+
+    /// Merge with another configuration
+    pub fn merge_with(&mut self, other: &KingTwoGoldsConfig) {
+        self.enabled = other.enabled;
+        self.max_moves_to_mate = other.max_moves_to_mate;
+        self.node_limit = other.node_limit;
+        self.priority = other.priority;
+    }
+}
+
+/// Configuration for King + Silver + Gold vs King solver
+///
+/// Solved by bounded mate search rather than patterns, so there is no pattern cache.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KingSilverGoldConfig {
+    /// Whether this solver is enabled
+    pub enabled: bool,
+    /// Maximum moves to mate this solver can handle
+    pub max_moves_to_mate: u8,
+    /// Positions the mate search may visit before giving up
+    pub node_limit: usize,
+    /// Priority of this solver
+    pub priority: u8,
+}
+
+impl Default for KingSilverGoldConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_moves_to_mate: 15,
+            node_limit: 20_000,
+            priority: 92,
+        }
+    }
+}
+
+impl KingSilverGoldConfig {
+    /// Create a performance-optimized configuration
+    pub fn performance_optimized() -> Self {
+        Self {
+            enabled: true,
+            max_moves_to_mate: 20,
+            node_limit: 100_000,
+            priority: 92,
+        }
+    }
+
+    /// Create a memory-optimized configuration
+    pub fn memory_optimized() -> Self {
+        Self {
+            enabled: true,
+            max_moves_to_mate: 8,
+            node_limit: 5_000,
+            priority: 92,
+        }
+    }
+
+    /// Validate the configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_moves_to_mate == 0 {
+            return Err("Max moves to mate must be greater than 0".to_string());
+        }
+        if self.node_limit == 0 {
+            return Err("Node limit must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+
+    /// Merge with another configuration
+    pub fn merge_with(&mut self, other: &KingSilverGoldConfig) {
+        self.enabled = other.enabled;
+        self.max_moves_to_mate = other.max_moves_to_mate;
+        self.node_limit = other.node_limit;
+        self.priority = other.priority;
+    }
+}
+
 /// Performance tuning configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PerformanceConfig {
@@ -598,8 +750,11 @@ pub struct TablebaseStats {
     pub cache_misses: u64,
     /// Number of solver hits
     pub solver_hits: u64,
-    /// Number of misses
+    /// Number of probes that reached the solvers without a result
     pub misses: u64,
+    /// Number of probes rejected by the material signature filter before the cache
+    /// and solvers; counted in `total_probes` but not in `misses` or `cache_misses`
+    pub filtered_probes: u64,
     /// Breakdown of hits by solver name
     pub solver_breakdown: HashMap<String, u64>,
     /// Average probe time in milliseconds
@@ -635,6 +790,7 @@ impl TablebaseStats {
             cache_misses: 0,
             solver_hits: 0,
             misses: 0,
+            filtered_probes: 0,
             solver_breakdown: HashMap::new(),
             average_probe_time_ms: 0.0,
             total_probe_time_ms: 0,
@@ -678,6 +834,22 @@ impl TablebaseStats {
         }
     }
 
+    /// Record a probe rejected by the material signature filter
+    pub fn record_filtered_probe(&mut self) {
+        self.total_probes += 1;
+        self.filtered_probes += 1;
+        self.average_probe_time_ms = self.total_probe_time_ms as f64 / self.total_probes as f64;
+    }
+
+    /// Get the share of probes rejected by the material signature filter
+    pub fn filter_rate(&self) -> f64 {
+        if self.total_probes == 0 {
+            0.0
+        } else {
+            self.filtered_probes as f64 / self.total_probes as f64
+        }
+    }
+
     /// Reset all statistics
     pub fn reset(&mut self) {
         self.total_probes = 0;
@@ -685,6 +857,7 @@ impl TablebaseStats {
         self.cache_misses = 0;
         self.solver_hits = 0;
         self.misses = 0;
+        self.filtered_probes = 0;
         self.solver_breakdown.clear();
         self.average_probe_time_ms = 0.0;
         self.total_probe_time_ms = 0;
@@ -751,6 +924,7 @@ impl TablebaseStats {
         format!(
             "Tablebase Performance Summary:\n\
             Total Probes: {}\n\
+            Filtered Probes: {}\n\
             Cache Hit Rate: {:.2}%\n\
             Solver Hit Rate: {:.2}%\n\
             Overall Hit Rate: {:.2}%\n\
@@ -759,6 +933,7 @@ impl TablebaseStats {
             Avg Position Analysis Time: {:.2}ms\n\
             Avg Solver Selection Time: {:.2}ms",
             self.total_probes,
+            self.filtered_probes,
             self.cache_hit_rate() * 100.0,
             self.solver_hit_rate() * 100.0,
             self.overall_hit_rate() * 100.0,
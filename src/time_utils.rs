@@ -1,15 +1,21 @@
 // Time utilities for standalone environments
 
+/// Monotonic clock: `std::time::Instant`, or the JavaScript clock in wasm builds
+#[cfg(not(feature = "wasm"))]
+pub use std::time::Instant;
+#[cfg(feature = "wasm")]
+pub use crate::search::wasm_compatibility::Instant;
+
 /// A time source for standalone environments
 pub struct TimeSource {
-    start_time: std::time::Instant,
+    start_time: Instant,
 }
 
 impl TimeSource {
     /// Create a new time source with the current time
     pub fn now() -> Self {
         Self {
-            start_time: std::time::Instant::now(),
+            start_time: Instant::now(),
         }
     }
 
@@ -25,9 +31,16 @@ impl TimeSource {
 }
 
 /// Get current time in milliseconds (for compatibility with existing code)
+#[cfg(not(feature = "wasm"))]
 pub fn current_time_ms() -> u32 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default()
         .as_millis() as u32
 }
+
+/// Get current time in milliseconds (for compatibility with existing code)
+#[cfg(feature = "wasm")]
+pub fn current_time_ms() -> u32 {
+    crate::search::wasm_compatibility::now_ms() as u64 as u32
+}
@@ -0,0 +1,256 @@
+//! Tsume Problems
+//!
+//! Built-in mating problems for the tsume trainer and verification of a user's
+//! move sequence. Any attacking line is accepted, not just the stored main line:
+//! each attacker move must be a legal check after which the mate searcher proves
+//! mate against every defence within the moves left. After a correct attacker move
+//! the trainer answers with the defence that holds out longest.
+
+use crate::bitboards::BitboardBoard;
+use crate::moves::MoveGenerator;
+use crate::search::mate_search::{MateSearchResult, MateSearcher};
+use crate::types::board::CapturedPieces;
+use crate::types::core::{Move, PieceType, Player};
+use serde::{Deserialize, Serialize};
+
+/// Time allowed for each mate search while verifying a move
+pub const TSUME_VERIFY_TIME_MS: u32 = 2_000;
+
+/// A mating problem: the side to move mates in `mate_plies` plies
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TsumeProblem {
+    pub id: String,
+    pub title: String,
+    pub sfen: String,
+    /// Length of the mate in plies, attacker and defender moves together
+    pub mate_plies: u8,
+}
+
+/// Outcome of checking a move sequence against a problem
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum TsumeVerdict {
+    /// The last move mates
+    Solved,
+    /// Every move so far is correct; `reply` is the defender's answer to the
+    /// last attacker move, `None` if the sequence already ends with a defence
+    Continue { reply: Option<String> },
+    /// The move at `ply` (0-based) is wrong
+    Wrong { ply: usize, reason: String },
+}
+
+const BUILTIN_PROBLEMS: &[(&str, &str, &str, u8)] = &[
+    ("gold-head", "Gold on the head", "4k4/9/4P4/9/9/9/9/9/4K4 b G 1", 1),
+    ("corner-pawn", "Corner with pawn support", "8k/9/8P/9/9/9/9/9/4K4 b G 1", 1),
+    ("corner-two-ways", "Two ways into the corner", "8k/9/7G1/9/9/9/9/9/4K4 b G 1", 1),
+    ("long-bishop", "Bishop from afar", "8k/8p/9/8P/9/9/9/9/4K4 b BG 1", 3),
+    ("silver-sacrifice", "Silver sacrifice", "8k/8p/9/7P1/9/9/9/9/4K4 b 2GS 1", 5),
+];
+
+/// The problems shipped with the trainer
+pub fn builtin_problems() -> Vec<TsumeProblem> {
+    BUILTIN_PROBLEMS
+        .iter()
+        .map(|(id, title, sfen, mate_plies)| TsumeProblem {
+            id: id.to_string(),
+            title: title.to_string(),
+            sfen: sfen.to_string(),
+            mate_plies: *mate_plies,
+        })
+        .collect()
+}
+
+impl TsumeProblem {
+    /// Check `moves` (USI notation, attacker first) against this problem. `Err`
+    /// when the problem itself can't be set up or a mate search times out.
+    pub fn verify(&self, moves: &[String]) -> Result<TsumeVerdict, String> {
+        let (mut board, attacker, mut captured) = BitboardBoard::from_fen(&self.sfen)
+            .map_err(|e| format!("Invalid problem SFEN: {}", e))?;
+        let move_generator = MoveGenerator::new();
+        let mut player = attacker;
+
+        for (ply, usi) in moves.iter().enumerate() {
+            if ply >= usize::from(self.mate_plies) {
+                return Ok(wrong(ply, "the problem is already over"));
+            }
+            let legal_moves = move_generator.generate_legal_moves(&board, player, &captured);
+            let Some(mv) = legal_moves.into_iter().find(|m| m.to_usi_string() == *usi) else {
+                return Ok(wrong(ply, "illegal move"));
+            };
+            play(&mut board, &mut captured, &mv);
+            player = player.opposite();
+
+            if player == attacker {
+                continue;
+            }
+            if !board.is_king_in_check(player, &captured) {
+                return Ok(wrong(ply, "every attacking move must give check"));
+            }
+            let replies = move_generator.generate_legal_moves(&board, player, &captured);
+            if replies.is_empty() {
+                if mv.from.is_none() && mv.piece_type == PieceType::Pawn {
+                    return Ok(wrong(ply, "mate by pawn drop is illegal"));
+                }
+                return Ok(if ply + 1 == moves.len() {
+                    TsumeVerdict::Solved
+                } else {
+                    wrong(ply + 1, "the defender is already mated")
+                });
+            }
+
+            let plies_left = self.mate_plies.saturating_sub(ply as u8 + 1);
+            let Some(longest) = longest_defence(&board, &captured, attacker, replies, plies_left)?
+            else {
+                return Ok(wrong(ply, "the defender escapes mate within the remaining moves"));
+            };
+            if ply + 1 == moves.len() {
+                return Ok(TsumeVerdict::Continue {
+                    reply: Some(longest.to_usi_string()),
+                });
+            }
+        }
+        Ok(TsumeVerdict::Continue { reply: None })
+    }
+}
+
+fn wrong(ply: usize, reason: &str) -> TsumeVerdict {
+    TsumeVerdict::Wrong {
+        ply,
+        reason: reason.to_string(),
+    }
+}
+
+/// The defence that postpones mate longest, or `None` if some defence avoids mate
+/// within `plies_left` plies (counting the defence itself)
+fn longest_defence(
+    board: &BitboardBoard,
+    captured_pieces: &CapturedPieces,
+    attacker: Player,
+    replies: Vec<Move>,
+    plies_left: u8,
+) -> Result<Option<Move>, String> {
+    if plies_left < 2 {
+        return Ok(None);
+    }
+    let mut longest: Option<(Move, usize)> = None;
+    for reply in replies {
+        let mut next_board = board.clone();
+        let mut next_captured = captured_pieces.clone();
+        play(&mut next_board, &mut next_captured, &reply);
+        let mut searcher = MateSearcher::new(plies_left - 1, Some(TSUME_VERIFY_TIME_MS), None);
+        match searcher.search(&next_board, &next_captured, attacker) {
+            MateSearchResult::Mate(line) => {
+                if longest.as_ref().map_or(true, |(_, plies)| line.len() > *plies) {
+                    longest = Some((reply, line.len()));
+                }
+            }
+            MateSearchResult::NoMate => return Ok(None),
+            MateSearchResult::Timeout => return Err("Mate search timed out".to_string()),
+        }
+    }
+    Ok(longest.map(|(reply, _)| reply))
+}
+
+fn play(board: &mut BitboardBoard, captured_pieces: &mut CapturedPieces, move_: &Move) {
+    if move_.from.is_none() {
+        captured_pieces.remove_piece(move_.piece_type, move_.player);
+    }
+    if let Some(captured) = board.make_move(move_) {
+        captured_pieces.add_piece(captured.piece_type, move_.player);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problem(id: &str) -> TsumeProblem {
+        builtin_problems().into_iter().find(|p| p.id == id).unwrap()
+    }
+
+    fn moves(usi: &[&str]) -> Vec<String> {
+        usi.iter().map(|m| m.to_string()).collect()
+    }
+
+    #[test]
+    fn test_builtin_problems_are_sound() {
+        for problem in builtin_problems() {
+            let (board, attacker, captured) = BitboardBoard::from_fen(&problem.sfen).unwrap();
+            let mut searcher = MateSearcher::new(problem.mate_plies, Some(10_000), None);
+            match searcher.search(&board, &captured, attacker) {
+                MateSearchResult::Mate(line) => {
+                    assert_eq!(line.len(), usize::from(problem.mate_plies), "{}", problem.id)
+                }
+                other => panic!("{} has no mate: {:?}", problem.id, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_accepts_every_mating_move() {
+        let problem = problem("corner-two-ways");
+        assert_eq!(problem.verify(&moves(&["G*1b"])), Ok(TsumeVerdict::Solved));
+        assert_eq!(problem.verify(&moves(&["G*2b"])), Ok(TsumeVerdict::Solved));
+    }
+
+    #[test]
+    fn test_rejects_wrong_moves() {
+        let problem = problem("gold-head");
+        assert_eq!(
+            problem.verify(&moves(&["G*4b"])),
+            Ok(wrong(0, "the defender escapes mate within the remaining moves"))
+        );
+        assert_eq!(
+            problem.verify(&moves(&["5i5h"])),
+            Ok(wrong(0, "every attacking move must give check"))
+        );
+        assert_eq!(problem.verify(&moves(&["R*5b"])), Ok(wrong(0, "illegal move")));
+        assert_eq!(problem.verify(&[]), Ok(TsumeVerdict::Continue { reply: None }));
+    }
+
+    #[test]
+    fn test_mate_in_three_with_drops() {
+        let problem = problem("long-bishop");
+        // The bishop checks from any square on the diagonal
+        for first in ["B*3c", "B*9i"] {
+            assert_eq!(
+                problem.verify(&moves(&[first])),
+                Ok(TsumeVerdict::Continue { reply: Some("1a2a".to_string()) })
+            );
+            assert_eq!(problem.verify(&moves(&[first, "1a2a", "G*2b"])), Ok(TsumeVerdict::Solved));
+        }
+        assert_eq!(
+            problem.verify(&moves(&["B*3c", "1a2a", "G*3b"])),
+            Ok(wrong(2, "the defender escapes mate within the remaining moves"))
+        );
+        assert_eq!(
+            problem.verify(&moves(&["G*2b"])),
+            Ok(wrong(0, "the defender escapes mate within the remaining moves"))
+        );
+    }
+
+    #[test]
+    fn test_mate_in_five_with_drops() {
+        let problem = problem("silver-sacrifice");
+        assert_eq!(
+            problem.verify(&moves(&["S*2b"])),
+            Ok(TsumeVerdict::Continue { reply: Some("1a2b".to_string()) })
+        );
+        assert_eq!(
+            problem.verify(&moves(&["S*2b", "1a2b", "G*2c"])),
+            Ok(TsumeVerdict::Continue { reply: Some("2b3a".to_string()) })
+        );
+        assert_eq!(
+            problem.verify(&moves(&["S*2b", "1a2b", "G*2c", "2b3a", "G*3b"])),
+            Ok(TsumeVerdict::Solved)
+        );
+        assert_eq!(
+            problem.verify(&moves(&["S*2b", "1a2b", "G*3b"])),
+            Ok(wrong(2, "the defender escapes mate within the remaining moves"))
+        );
+        assert_eq!(
+            problem.verify(&moves(&["G*2b"])),
+            Ok(wrong(0, "the defender escapes mate within the remaining moves"))
+        );
+    }
+}
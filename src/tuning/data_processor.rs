@@ -14,6 +14,7 @@
 use super::feature_extractor::FeatureExtractor;
 use super::types::{GameRecord, GameResult, PositionFilter, TimeControl, TrainingPosition};
 use crate::{
+    csa_parser::CsaGame,
     types::{CapturedPieces, Move, PieceType, Player, Position},
     BitboardBoard,
 };
@@ -329,53 +330,15 @@ impl DataProcessor {
 
     /// Load games from CSA format (Computer Shogi Association)
     fn load_csa_dataset(&self, path: &Path) -> Result<Vec<GameRecord>, String> {
-        let file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
-
-        let reader = BufReader::new(file);
+        let path = path.to_str().ok_or_else(|| "Invalid file path".to_string())?;
         let mut games = Vec::new();
-        let mut current_game = GameRecord::new(vec![], GameResult::Draw, TimeControl::new(600, 10));
-
-        for line in reader.lines() {
-            let line = line.map_err(|e| format!("Failed to read line: {}", e))?;
-            let line = line.trim();
-
-            if line.is_empty() {
-                if !current_game.moves.is_empty() {
-                    games.push(current_game.clone());
-                    current_game =
-                        GameRecord::new(vec![], GameResult::Draw, TimeControl::new(600, 10));
-                }
-                continue;
-            }
-
-            // Parse CSA header
-            if line.starts_with("N+") || line.starts_with("N-") {
-                // Player names
-            } else if line.starts_with("$") {
-                // Comments and metadata
-            } else if line.starts_with("%") {
-                // Game result
-                current_game.result = match line {
-                    "%TORYO" | "%CHUDAN" => GameResult::Draw,
-                    "%SENNICHITE" => GameResult::Draw,
-                    _ => GameResult::Draw,
-                };
-            } else if line.len() >= 4 && line.chars().next().unwrap().is_ascii_digit() {
-                // Parse CSA move format
-                match self.parse_csa_move(line) {
-                    Ok(Some(move_)) => {
-                        current_game.moves.push(move_);
-                    }
-                    Ok(None) => {} // Not a move line, skip
-                    Err(_) => {} // Parse error, skip this line
-                }
+        for game in CsaGame::from_file(path)? {
+            let record = game.to_game_record()?;
+            if !record.moves.is_empty() {
+                games.push(record);
             }
         }
 
-        if !current_game.moves.is_empty() {
-            games.push(current_game);
-        }
-
         Ok(games)
     }
 
@@ -577,6 +540,7 @@ impl DataProcessor {
     /// Piece: FU, KY, KE, GI, KI, KA, HI, OU, TO, NY, NK, NG, UM, RY
     ///
     /// **Implementation Status:** ✅ Fully implemented - supports all CSA move formats including drops
+    #[allow(dead_code)]
     fn parse_csa_move(&self, line: &str) -> Result<Option<Move>, String> {
         let trimmed = line.trim();
         
@@ -15,6 +15,7 @@
 //! - `feature_extractor.rs`: Feature extraction from positions
 //! - `data_processor.rs`: Game database processing and position filtering
 //! - `optimizer.rs`: Optimization algorithms (gradient descent, Adam, LBFGS, genetic)
+//! - `pst_features.rs`: Per-entry piece-square table features and table export
 //! - `validator.rs`: Validation framework and cross-validation
 //! - `performance.rs`: Performance monitoring and analysis
 
@@ -22,6 +23,7 @@ pub mod data_processor;
 pub mod feature_extractor;
 pub mod optimizer;
 pub mod performance;
+pub mod pst_features;
 pub mod types;
 pub mod validator;
 
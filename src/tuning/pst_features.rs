@@ -0,0 +1,169 @@
+//! Piece-square table features
+//!
+//! Lets Texel tuning work on the individual entries of the piece-square
+//! tables rather than on one weight per table. Every piece type but the king
+//! has a middlegame and an endgame table of 81 squares, and each entry is one
+//! weight. A position's features count the pieces standing on each entry, from
+//! the side to move's perspective and with White's squares mirrored as the
+//! evaluator mirrors them, scaled by how far the game phase leans toward the
+//! table's phase. The weighted sum is then exactly the tapered PST score, so
+//! tuned weights round straight back into tables that
+//! `PieceSquareTableLoader::save` writes out as a data file.
+
+use crate::bitboards::BitboardBoard;
+use crate::evaluation::piece_square_tables::{PiecePhaseTables, PieceSquareTables};
+use crate::tuning::types::TrainingPosition;
+use crate::types::core::{PieceType, Player, Position};
+use crate::types::evaluation::GAME_PHASE_MAX;
+use std::collections::HashMap;
+
+/// Piece types with tunable tables; the king's are fixed at zero
+pub const PST_PIECES: [PieceType; 13] = [
+    PieceType::Pawn,
+    PieceType::Lance,
+    PieceType::Knight,
+    PieceType::Silver,
+    PieceType::Gold,
+    PieceType::Bishop,
+    PieceType::Rook,
+    PieceType::PromotedPawn,
+    PieceType::PromotedLance,
+    PieceType::PromotedKnight,
+    PieceType::PromotedSilver,
+    PieceType::PromotedBishop,
+    PieceType::PromotedRook,
+];
+
+/// Length of a PST feature vector: both phases of every tunable table
+pub const PST_FEATURE_COUNT: usize = PST_PIECES.len() * 2 * 81;
+
+/// Logistic scale for centipawn scores: a 400 cp lead counts as 10:1 odds
+pub const DEFAULT_PST_K_FACTOR: f64 = std::f64::consts::LN_10 / 400.0;
+
+/// Feature index of a table entry; `None` for the king. Middlegame tables come
+/// first for each piece, then endgame, both row-major.
+pub fn pst_feature_index(piece: PieceType, endgame: bool, row: u8, col: u8) -> Option<usize> {
+    let piece_index = PST_PIECES.iter().position(|&p| p == piece)?;
+    let table = piece_index * 2 + usize::from(endgame);
+    Some(table * 81 + usize::from(row) * 9 + usize::from(col))
+}
+
+/// PST features of a position for `player`; `game_phase` runs from 0 (endgame)
+/// to `GAME_PHASE_MAX` (opening)
+pub fn extract_pst_features(board: &BitboardBoard, player: Player, game_phase: i32) -> Vec<f64> {
+    let mg_share = f64::from(game_phase.clamp(0, GAME_PHASE_MAX)) / f64::from(GAME_PHASE_MAX);
+    let mut features = vec![0.0; PST_FEATURE_COUNT];
+    for row in 0..9 {
+        for col in 0..9 {
+            let Some(piece) = board.get_piece(Position::new(row, col)) else {
+                continue;
+            };
+            let sign = if piece.player == player { 1.0 } else { -1.0 };
+            let (table_row, table_col) = match piece.player {
+                Player::Black => (row, col),
+                Player::White => (8 - row, 8 - col),
+            };
+            for (endgame, share) in [(false, mg_share), (true, 1.0 - mg_share)] {
+                if let Some(index) =
+                    pst_feature_index(piece.piece_type, endgame, table_row, table_col)
+                {
+                    features[index] += sign * share;
+                }
+            }
+        }
+    }
+    features
+}
+
+/// Rebuild training positions with PST features from their FEN, keeping the
+/// result and phase; positions without a readable FEN are dropped
+pub fn pst_training_positions(positions: &[TrainingPosition]) -> Vec<TrainingPosition> {
+    positions
+        .iter()
+        .filter_map(|position| {
+            let (board, _, _) = BitboardBoard::from_fen(position.fen.as_deref()?).ok()?;
+            let features =
+                extract_pst_features(&board, position.player_to_move, position.game_phase);
+            Some(TrainingPosition { features, ..position.clone() })
+        })
+        .collect()
+}
+
+/// Weights equal to the entries of `tables`, to start tuning from
+pub fn pst_weights(tables: &PieceSquareTables) -> Vec<f64> {
+    let mut weights = vec![0.0; PST_FEATURE_COUNT];
+    for piece in PST_PIECES {
+        let (mg, eg) = tables.get_tables(piece);
+        for (endgame, table) in [(false, mg), (true, eg)] {
+            for row in 0..9u8 {
+                for col in 0..9u8 {
+                    if let Some(index) = pst_feature_index(piece, endgame, row, col) {
+                        weights[index] = f64::from(table[usize::from(row)][usize::from(col)]);
+                    }
+                }
+            }
+        }
+    }
+    weights
+}
+
+/// Tables from tuned weights, rounded to whole centipawns
+pub fn tables_from_weights(weights: &[f64]) -> Result<PieceSquareTables, String> {
+    if weights.len() != PST_FEATURE_COUNT {
+        return Err(format!("expected {} PST weights, found {}", PST_FEATURE_COUNT, weights.len()));
+    }
+    let table = |piece: PieceType, endgame: bool| {
+        let mut table = [[0; 9]; 9];
+        for row in 0..9u8 {
+            for col in 0..9u8 {
+                if let Some(index) = pst_feature_index(piece, endgame, row, col) {
+                    table[usize::from(row)][usize::from(col)] = weights[index].round() as i32;
+                }
+            }
+        }
+        table
+    };
+    let phase_tables: HashMap<_, _> = PST_PIECES
+        .iter()
+        .map(|&piece| (piece, PiecePhaseTables { mg: table(piece, false), eg: table(piece, true) }))
+        .collect();
+    PieceSquareTables::from_phase_tables(&phase_tables).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::core::Piece;
+    use crate::types::evaluation::TaperedScore;
+
+    #[test]
+    fn test_features_reproduce_tapered_pst_score() {
+        let tables = PieceSquareTables::new();
+        let mut board = BitboardBoard::empty();
+        let silver = Position::new(6, 3);
+        let rook = Position::new(2, 7);
+        board.place_piece(Piece::new(PieceType::Silver, Player::Black), silver);
+        board.place_piece(Piece::new(PieceType::PromotedRook, Player::White), rook);
+
+        let phase = GAME_PHASE_MAX / 4;
+        let features = extract_pst_features(&board, Player::Black, phase);
+        let weights = pst_weights(&tables);
+        let predicted: f64 = features.iter().zip(&weights).map(|(f, w)| f * w).sum();
+
+        let expected: TaperedScore = tables.get_value(PieceType::Silver, silver, Player::Black)
+            - tables.get_value(PieceType::PromotedRook, rook, Player::White);
+        assert!((predicted - f64::from(expected.interpolate(phase))).abs() <= 1.0);
+    }
+
+    #[test]
+    fn test_weights_round_trip_to_tables() {
+        let tables = PieceSquareTables::new();
+        let rebuilt = tables_from_weights(&pst_weights(&tables)).unwrap();
+        assert_eq!(rebuilt.to_phase_tables().len(), tables.to_phase_tables().len());
+        assert_eq!(
+            rebuilt.get_tables(PieceType::PromotedBishop),
+            tables.get_tables(PieceType::PromotedBishop)
+        );
+        assert!(tables_from_weights(&[0.0; 3]).is_err());
+    }
+}
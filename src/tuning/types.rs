@@ -3,6 +3,7 @@
 //! This module defines all the essential types and structures used throughout
 //! the tuning process, from game records to optimization configuration.
 
+use crate::types::all::PruningParameters;
 use crate::types::core::{Move, Player};
 use crate::types::evaluation::NUM_EVAL_FEATURES;
 use serde::{Deserialize, Serialize};
@@ -294,6 +295,66 @@ impl Default for PerformanceConfig {
     }
 }
 
+/// Tunable search pruning margins
+///
+/// The frontier futility and history pruning knobs of the search, in a form
+/// that can be saved to and loaded from JSON and handed to strength-test games
+/// (see `ShogiEngineGamePlayer::with_pruning_margins`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PruningMargins {
+    /// Enable futility pruning of quiet moves at depth 1-2
+    pub frontier_futility_enabled: bool,
+    /// Futility margins in centipawns for depth 1 and depth 2
+    pub frontier_futility_margins: [i32; 2],
+    /// Enable history-based late move pruning
+    pub history_pruning_enabled: bool,
+    /// Deepest remaining depth at which history pruning applies
+    pub history_pruning_depth_limit: u8,
+    /// Late quiet moves with a history score at or below this are pruned
+    pub history_pruning_threshold: i32,
+    /// Quiet moves searched per ply of depth before history pruning starts
+    pub late_move_pruning_move_threshold: u8,
+}
+
+impl Default for PruningMargins {
+    fn default() -> Self {
+        Self::from(&PruningParameters::default())
+    }
+}
+
+impl From<&PruningParameters> for PruningMargins {
+    fn from(params: &PruningParameters) -> Self {
+        Self {
+            frontier_futility_enabled: params.frontier_futility_enabled,
+            frontier_futility_margins: params.frontier_futility_margins,
+            history_pruning_enabled: params.history_pruning_enabled,
+            history_pruning_depth_limit: params.history_pruning_depth_limit,
+            history_pruning_threshold: params.history_pruning_threshold,
+            late_move_pruning_move_threshold: params.late_move_pruning_move_threshold,
+        }
+    }
+}
+
+impl PruningMargins {
+    /// Overwrite the corresponding fields of `params`, leaving the rest untouched
+    pub fn apply_to(&self, params: &mut PruningParameters) {
+        params.frontier_futility_enabled = self.frontier_futility_enabled;
+        params.frontier_futility_margins = self.frontier_futility_margins;
+        params.history_pruning_enabled = self.history_pruning_enabled;
+        params.history_pruning_depth_limit = self.history_pruning_depth_limit;
+        params.history_pruning_threshold = self.history_pruning_threshold;
+        params.late_move_pruning_move_threshold = self.late_move_pruning_move_threshold;
+    }
+
+    /// Default pruning parameters with these margins applied
+    pub fn to_pruning_parameters(&self) -> PruningParameters {
+        let mut params = PruningParameters::default();
+        self.apply_to(&mut params);
+        params
+    }
+}
+
 /// Type of line search algorithm for LBFGS
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum LineSearchType {
@@ -1261,6 +1322,29 @@ mod tests {
         assert_eq!(results.worst_fold, Some(2));
     }
 
+    #[test]
+    fn test_pruning_margins_round_trip() {
+        let margins = PruningMargins {
+            frontier_futility_margins: [120, 260],
+            history_pruning_threshold: 16,
+            ..PruningMargins::default()
+        };
+        let params = margins.to_pruning_parameters();
+        assert_eq!(params.frontier_futility_margins, [120, 260]);
+        assert_eq!(params.history_pruning_threshold, 16);
+        assert_eq!(params.razoring_margin, PruningParameters::default().razoring_margin);
+        assert_eq!(PruningMargins::from(&params), margins);
+
+        // Missing fields fall back to the engine defaults
+        let partial: PruningMargins =
+            serde_json::from_str(r#"{"frontier_futility_margins": [100, 200]}"#).unwrap();
+        assert_eq!(partial.frontier_futility_margins, [100, 200]);
+        assert_eq!(
+            partial.history_pruning_depth_limit,
+            PruningParameters::default().history_pruning_depth_limit
+        );
+    }
+
     #[test]
     fn test_utility_functions() {
         assert!((sigmoid(0.0) - 0.5).abs() < 1e-10);
@@ -40,8 +40,8 @@
 
 use super::optimizer::Optimizer;
 use super::types::{
-    FoldResult, GameResult as TuningGameResult, MatchResult, OptimizationMethod, TrainingPosition,
-    ValidationConfig, ValidationResults,
+    FoldResult, GameResult as TuningGameResult, MatchResult, OptimizationMethod, PruningMargins,
+    TrainingPosition, ValidationConfig, ValidationResults,
 };
 use crate::types::core::{Move, Player};
 // Note: GameResult is not yet extracted to a sub-module, using root import
@@ -449,6 +449,8 @@ pub struct ShogiEngineGamePlayer {
     pub search_depth: u8,
     /// Whether to enable verbose logging
     pub verbose: bool,
+    /// Search pruning margins used by the engine in every game
+    pub pruning_margins: PruningMargins,
 }
 
 impl ShogiEngineGamePlayer {
@@ -457,9 +459,16 @@ impl ShogiEngineGamePlayer {
         Self {
             search_depth,
             verbose,
+            pruning_margins: PruningMargins::default(),
         }
     }
 
+    /// Play games with the given pruning margins, e.g. to compare margin candidates
+    pub fn with_pruning_margins(mut self, pruning_margins: PruningMargins) -> Self {
+        self.pruning_margins = pruning_margins;
+        self
+    }
+
     /// Convert engine GameResult to tuning GameResult from a player's perspective
     fn convert_game_result(
         engine_result: GameResult,
@@ -488,6 +497,7 @@ impl GamePlayer for ShogiEngineGamePlayer {
         // This requires integration with the evaluation system to apply feature weights
         // For now, we use a single engine to establish the game playing infrastructure
         let mut engine = ShogiEngine::new();
+        engine.set_pruning_parameters(self.pruning_margins.to_pruning_parameters());
 
         // TODO: Apply player1_weights and player2_weights to engine configurations
         // This requires:
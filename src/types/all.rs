@@ -6982,7 +6982,7 @@ pub enum MagicError {
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct MagicBitboard {
     /// The magic number used for hashing
-    pub magic_number: u64,
+    pub magic_number: u128,
     /// Bitmask of relevant occupied squares
     pub mask: Bitboard,
     /// Number of bits to shift the hash result
@@ -7056,7 +7056,7 @@ impl Default for MemoryPool {
 /// Magic number generation result
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct MagicGenerationResult {
-    pub magic_number: u64,
+    pub magic_number: u128,
     pub mask: Bitboard,
     pub shift: u8,
     pub table_size: usize,
@@ -7373,6 +7373,15 @@ pub struct PruningParameters {
     // Late move pruning parameters
     pub late_move_pruning_enabled: bool,
     pub late_move_pruning_move_threshold: u8,
+
+    // Frontier futility pruning parameters (quiet moves at depth 1-2)
+    pub frontier_futility_enabled: bool,
+    pub frontier_futility_margins: [i32; 2],
+
+    // History-based late move pruning parameters
+    pub history_pruning_enabled: bool,
+    pub history_pruning_depth_limit: u8,
+    pub history_pruning_threshold: i32,
 }
 
 impl Default for PruningParameters {
@@ -7399,6 +7408,11 @@ impl Default for PruningParameters {
             razoring_enabled: true,
             late_move_pruning_enabled: true,
             late_move_pruning_move_threshold: 4,
+            frontier_futility_enabled: true,
+            frontier_futility_margins: [150, 300],
+            history_pruning_enabled: true,
+            history_pruning_depth_limit: 2,
+            history_pruning_threshold: 0,
         }
     }
 }
@@ -7414,6 +7428,7 @@ pub struct PruningStatistics {
     pub lmr_applied: u64,
     pub re_searches: u64,
     pub multi_cuts: u64,
+    pub history_pruned: u64,
 }
 
 impl PruningStatistics {
@@ -7723,6 +7738,74 @@ impl PruningManager {
         decision
     }
 
+    /// Prune hopeless quiet moves at frontier nodes (depth 1-2)
+    ///
+    /// `should_prune` leaves depth 1 alone, so this covers the last plies before
+    /// quiescence with two cheap tests:
+    /// - futility: static eval plus the depth margin cannot reach alpha
+    /// - history: a late move whose history score never rose above the threshold
+    ///
+    /// `history_score` is `None` for drops, which have no history entry and are
+    /// only subject to the futility test. `gives_check` is asked only about a
+    /// move that would otherwise be pruned, since testing it means making the move.
+    pub fn check_frontier_pruning(
+        &mut self,
+        state: &SearchState,
+        mv: &Move,
+        history_score: Option<i32>,
+        is_killer_move: bool,
+        gives_check: impl FnOnce() -> bool,
+    ) -> PruningDecision {
+        if state.depth == 0 || state.depth > 2 || state.is_in_check || state.move_number <= 1 {
+            return PruningDecision::Search;
+        }
+
+        // Tactical, killer and TT moves are never frontier candidates
+        if mv.is_capture || mv.is_promotion || mv.gives_check || is_killer_move {
+            return PruningDecision::Search;
+        }
+        if state.tt_move.as_ref().is_some_and(|tt| self.moves_equal(tt, mv)) {
+            return PruningDecision::Search;
+        }
+
+        // Don't prune while a mate score (or an unbounded window) is at stake
+        let mate_threshold = crate::search::mate_score::MATE_THRESHOLD;
+        if state.alpha.saturating_abs() >= mate_threshold
+            || state.beta.saturating_abs() >= mate_threshold
+        {
+            return PruningDecision::Search;
+        }
+
+        let futile = self.parameters.frontier_futility_enabled && {
+            let margin = self.parameters.frontier_futility_margins[state.depth as usize - 1];
+            state.static_eval.saturating_add(margin) <= state.alpha
+        };
+        let history_prunable = !futile
+            && self.parameters.history_pruning_enabled
+            && state.depth <= self.parameters.history_pruning_depth_limit
+            && history_score.is_some_and(|score| {
+                let move_limit = self.parameters.late_move_pruning_move_threshold as u16
+                    * state.depth as u16;
+                state.move_number as u16 > move_limit
+                    && score <= self.parameters.history_pruning_threshold
+            });
+
+        // Checks are never frontier candidates either
+        let decision = if (futile || history_prunable) && !gives_check() {
+            if futile {
+                self.statistics.futility_pruned += 1;
+            } else {
+                self.statistics.history_pruned += 1;
+            }
+            PruningDecision::Skip
+        } else {
+            PruningDecision::Search
+        };
+
+        self.statistics.record_decision(decision);
+        decision
+    }
+
     /// Fast check to skip pruning for obvious cases
     fn should_skip_pruning(&self, state: &SearchState, mv: &Move) -> bool {
         // Skip if depth is too shallow
@@ -9233,6 +9316,113 @@ impl PruningManager {
     }
 }
 
+#[cfg(test)]
+mod frontier_pruning_tests {
+    use super::*;
+
+    fn quiet_move() -> Move {
+        Move::new_move(
+            Position::new(6, 2),
+            Position::new(5, 2),
+            PieceType::Pawn,
+            Player::Black,
+            false,
+        )
+    }
+
+    fn frontier_state(depth: u8, move_number: u8, static_eval: i32, alpha: i32) -> SearchState {
+        let mut state = SearchState::new(depth, alpha, alpha + 1);
+        state.move_number = move_number;
+        state.static_eval = static_eval;
+        state
+    }
+
+    #[test]
+    fn test_frontier_futility_prunes_hopeless_quiet_moves() {
+        let mut manager = PruningManager::new(PruningParameters::default());
+        let margin = manager.parameters.frontier_futility_margins[0];
+
+        let hopeless = frontier_state(1, 2, -margin - 50, 0);
+        assert!(manager
+            .check_frontier_pruning(&hopeless, &quiet_move(), Some(100), false, || false)
+            .is_pruned());
+        assert_eq!(manager.statistics.futility_pruned, 1);
+
+        // Within the margin, and never for the first move or captures
+        let close = frontier_state(1, 2, -margin + 50, 0);
+        assert!(!manager
+            .check_frontier_pruning(&close, &quiet_move(), Some(100), false, || false)
+            .is_pruned());
+        let first = frontier_state(1, 1, -margin - 50, 0);
+        assert!(!manager
+            .check_frontier_pruning(&first, &quiet_move(), Some(100), false, || false)
+            .is_pruned());
+        let mut capture = quiet_move();
+        capture.is_capture = true;
+        assert!(!manager
+            .check_frontier_pruning(&hopeless, &capture, Some(100), false, || false)
+            .is_pruned());
+
+        // The check test runs only for a move that would be pruned, and keeps checks
+        assert!(!manager
+            .check_frontier_pruning(&hopeless, &quiet_move(), Some(100), false, || true)
+            .is_pruned());
+        assert!(!manager
+            .check_frontier_pruning(&close, &quiet_move(), Some(100), false, || {
+                panic!("check test for a move that is searched anyway")
+            })
+            .is_pruned());
+        assert_eq!(manager.statistics.futility_pruned, 1);
+    }
+
+    #[test]
+    fn test_history_pruning_skips_late_moves_without_history() {
+        let mut manager = PruningManager::new(PruningParameters::default());
+        let limit = manager.parameters.late_move_pruning_move_threshold * 2;
+
+        let late = frontier_state(2, limit + 1, 0, 0);
+        assert!(manager
+            .check_frontier_pruning(&late, &quiet_move(), Some(0), false, || false)
+            .is_pruned());
+        assert_eq!(manager.statistics.history_pruned, 1);
+
+        // Good history, killers, drops and early moves are kept
+        assert!(!manager
+            .check_frontier_pruning(&late, &quiet_move(), Some(500), false, || false)
+            .is_pruned());
+        assert!(!manager
+            .check_frontier_pruning(&late, &quiet_move(), Some(0), true, || false)
+            .is_pruned());
+        assert!(!manager
+            .check_frontier_pruning(&late, &quiet_move(), None, false, || false)
+            .is_pruned());
+        let early = frontier_state(2, limit, 0, 0);
+        assert!(!manager
+            .check_frontier_pruning(&early, &quiet_move(), Some(0), false, || false)
+            .is_pruned());
+
+        manager.parameters.history_pruning_enabled = false;
+        assert!(!manager
+            .check_frontier_pruning(&late, &quiet_move(), Some(0), false, || false)
+            .is_pruned());
+    }
+
+    #[test]
+    fn test_frontier_pruning_ignores_deeper_nodes_and_mate_windows() {
+        let mut manager = PruningManager::new(PruningParameters::default());
+        let deep = frontier_state(3, 20, -5000, 0);
+        assert!(!manager
+            .check_frontier_pruning(&deep, &quiet_move(), Some(0), false, || false)
+            .is_pruned());
+
+        let mate_alpha = crate::search::mate_score::MATE_THRESHOLD + 10;
+        let mating = frontier_state(1, 20, -5000, mate_alpha);
+        assert!(!manager
+            .check_frontier_pruning(&mating, &quiet_move(), Some(0), false, || false)
+            .is_pruned());
+    }
+}
+
 /// Adaptive parameters for position-dependent pruning
 #[derive(Debug, PartialEq)]
 pub struct AdaptiveParameters {
@@ -9604,6 +9794,12 @@ impl AdaptiveParameters {
             return false;
         }
 
+        // Frontier margins must be positive and widen with depth
+        let [frontier_depth1, frontier_depth2] = params.frontier_futility_margins;
+        if frontier_depth1 < 25 || frontier_depth2 > 1000 || frontier_depth1 > frontier_depth2 {
+            return false;
+        }
+
         true
     }
 
@@ -6,7 +6,8 @@
 use serde::{Deserialize, Serialize};
 use super::core::{PieceType, Player};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Pieces in each player's hand. Serde support is in `crate::interchange`.
+#[derive(Debug, Clone)]
 pub struct CapturedPieces {
     pub black: Vec<PieceType>,
     pub white: Vec<PieceType>,
@@ -3,6 +3,7 @@
 //! This module contains the fundamental domain types for shogi: Player, PieceType, Position, Piece, and Move.
 //! Extracted from `types.rs` as part of Task 1.0: File Modularization and Structure Improvements.
 
+use crate::error::ShogiError;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -382,8 +383,9 @@ impl Piece {
     }
 }
 
-/// A move in USI terms. `Display` delegates to `to_usi_string()`.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// A move in USI terms. `Display` delegates to `to_usi_string()`. Serde
+/// support is in `crate::interchange`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Move {
     pub from: Option<Position>, // None for drops
     pub to: Position,
@@ -462,16 +464,16 @@ impl Move {
         usi_str: &str,
         player: Player,
         board: &crate::bitboards::BitboardBoard,
-    ) -> Result<Move, &'static str> {
+    ) -> Result<Move, ShogiError> {
         if usi_str.len() < 4 {
-            return Err("Invalid USI move string length");
+            return Err(ShogiError::parse("Invalid USI move string length"));
         }
 
         if usi_str.contains('*') {
             // Drop move, e.g., "P*5e"
             let parts: Vec<&str> = usi_str.split('*').collect();
             if parts.len() != 2 {
-                return Err("Invalid drop move format");
+                return Err(ShogiError::parse("Invalid drop move format"));
             }
 
             let piece_type = match parts[0] {
@@ -482,11 +484,11 @@ impl Move {
                 "G" => PieceType::Gold,
                 "B" => PieceType::Bishop,
                 "R" => PieceType::Rook,
-                _ => return Err("Invalid piece type for drop"),
+                _ => return Err(ShogiError::parse("Invalid piece type for drop")),
             };
 
-            let to =
-                Position::from_usi_string(parts[1]).map_err(|_| "Invalid position in drop move")?;
+            let to = Position::from_usi_string(parts[1])
+                .map_err(|_| ShogiError::parse("Invalid position in drop move"))?;
             Ok(Move::new_drop(piece_type, to, player))
         } else {
             // Normal move, e.g., "7g7f" or "2b8h+"
@@ -494,12 +496,16 @@ impl Move {
             let to_str = &usi_str[2..4];
             let is_promotion = usi_str.ends_with('+');
 
-            let from = Position::from_usi_string(from_str).map_err(|_| "Invalid from position")?;
-            let to = Position::from_usi_string(to_str).map_err(|_| "Invalid to position")?;
+            let from = Position::from_usi_string(from_str)
+                .map_err(|_| ShogiError::parse("Invalid from position"))?;
+            let to = Position::from_usi_string(to_str)
+                .map_err(|_| ShogiError::parse("Invalid to position"))?;
 
-            let piece_to_move = board.get_piece(from).ok_or("No piece at source square")?;
+            let piece_to_move = board
+                .get_piece(from)
+                .ok_or_else(|| ShogiError::rule("No piece at source square"))?;
             if piece_to_move.player != player {
-                return Err("Attempting to move opponent's piece");
+                return Err(ShogiError::rule("Attempting to move opponent's piece"));
             }
 
             let mut mv = Move::new_move(from, to, piece_to_move.piece_type, player, is_promotion);
@@ -623,6 +629,11 @@ mod tests {
         assert!(mv.to_string().ends_with("3f"));
         let parsed = Move::from_usi_string(&mv.to_string(), Player::Black, &board);
         assert!(parsed.is_ok());
+
+        let error = Move::from_usi_string("5e5d", Player::Black, &board).unwrap_err();
+        assert_eq!(error.category(), crate::error::ErrorCategory::Rule);
+        let error = Move::from_usi_string("5e", Player::Black, &board).unwrap_err();
+        assert_eq!(error.category(), crate::error::ErrorCategory::Parse);
     }
 }
 
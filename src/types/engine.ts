@@ -17,10 +17,32 @@ export interface EngineOption {
   var: string[];
 }
 
+export interface EngineCapabilities {
+  ponder: boolean;
+  mate_search: boolean;
+  multipv: boolean;
+  search_stats: boolean;
+}
+
 export interface EngineMetadata {
   name: string;
   author?: string;
   options: EngineOption[];
+  /** Absent for engines validated before capabilities were probed */
+  capabilities?: EngineCapabilities | null;
+  /** Nodes per second measured from the start position */
+  nps?: number | null;
+}
+
+/** Protocol an engine speaks; UCI engines play shogi as a variant */
+export type EngineProtocol = "usi" | "uci_shogi";
+
+/** Caps on what an external engine may use; null fields impose none */
+export interface EngineResourceLimits {
+  max_threads: number | null;
+  priority: "normal" | "below_normal" | "idle";
+  /** Resident memory in MiB past which the engine is killed */
+  memory_limit_mb: number | null;
 }
 
 export interface EngineConfig {
@@ -34,6 +56,9 @@ export interface EngineConfig {
   last_used?: string;
   created_at: string;
   is_favorite: boolean;
+  /** Absent for engines added before UCI support */
+  protocol?: EngineProtocol;
+  limits?: EngineResourceLimits;
 }
 
 export interface CommandResponse<T = any> {
@@ -2207,6 +2207,15 @@ pub struct PruningParameters {
     // Late move pruning parameters
     pub late_move_pruning_enabled: bool,
     pub late_move_pruning_move_threshold: u8,
+
+    // Frontier futility pruning parameters (quiet moves at depth 1-2)
+    pub frontier_futility_enabled: bool,
+    pub frontier_futility_margins: [i32; 2],
+
+    // History-based late move pruning parameters
+    pub history_pruning_enabled: bool,
+    pub history_pruning_depth_limit: u8,
+    pub history_pruning_threshold: i32,
 }
 
 impl Default for PruningParameters {
@@ -2233,6 +2242,11 @@ impl Default for PruningParameters {
             razoring_enabled: true,
             late_move_pruning_enabled: true,
             late_move_pruning_move_threshold: 4,
+            frontier_futility_enabled: true,
+            frontier_futility_margins: [150, 300],
+            history_pruning_enabled: true,
+            history_pruning_depth_limit: 2,
+            history_pruning_threshold: 0,
         }
     }
 }
@@ -2248,6 +2262,7 @@ pub struct PruningStatistics {
     pub lmr_applied: u64,
     pub re_searches: u64,
     pub multi_cuts: u64,
+    pub history_pruned: u64,
 }
 
 impl PruningStatistics {
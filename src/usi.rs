@@ -1,18 +1,294 @@
+use crate::notation::NotationStyle;
+use crate::search::clock_contempt::Clocks;
+use crate::search::mate_search::MateSearchResult;
+use crate::search::search_handle::SearchHandle;
+use crate::search::search_watchdog::SearchPanic;
+use crate::types::{Move, Player};
 use crate::ShogiEngine;
 use num_cpus;
 use std::io::{self, BufRead, Write};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Time limit handed to the search when a go command sets no clock (depth, nodes,
+/// infinite and ponder searches are ended by their own limit or by "stop").
+pub(crate) const UNBOUNDED_TIME_MS: u32 = 24 * 60 * 60 * 1000;
+
+/// Time used when a go command carries no limits at all
+const DEFAULT_MOVE_TIME_MS: u32 = 5000;
+
+/// Fraction of the remaining main time spent on a single move
+pub(crate) const MOVES_TO_GO: u32 = 40;
+
+/// Time limit of a `go mate` command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MateLimit {
+    TimeMs(u32),
+    Infinite,
+}
+
+/// Arguments of a USI `go` command
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GoParams {
+    pub btime: u32,
+    pub wtime: u32,
+    pub binc: u32,
+    pub winc: u32,
+    pub byoyomi: u32,
+    pub depth: Option<u8>,
+    pub nodes: Option<u64>,
+    pub movetime: Option<u32>,
+    pub mate: Option<MateLimit>,
+    pub infinite: bool,
+    pub ponder: bool,
+    /// Root moves to restrict the search to (`searchmoves`); empty searches all moves
+    pub searchmoves: Vec<String>,
+}
+
+/// Tokens that start a new `go` argument and so end a `searchmoves` list
+const GO_KEYWORDS: [&str; 13] = [
+    "btime", "wtime", "binc", "winc", "byoyomi", "movetime", "depth", "nodes", "mate",
+    "infinite", "ponder", "searchmoves", "movestogo",
+];
+
+impl GoParams {
+    /// Parse the tokens following `go`. Unknown or malformed tokens are skipped.
+    pub fn parse(parts: &[&str]) -> Self {
+        let mut params = Self::default();
+        let value = |i: usize| parts.get(i + 1).and_then(|v| v.parse::<u64>().ok());
+
+        let mut i = 0;
+        while i < parts.len() {
+            match parts[i] {
+                "btime" | "wtime" | "binc" | "winc" | "byoyomi" | "movetime" | "depth"
+                | "nodes" => {
+                    if let Some(v) = value(i) {
+                        let ms = v.min(u64::from(u32::MAX)) as u32;
+                        match parts[i] {
+                            "btime" => params.btime = ms,
+                            "wtime" => params.wtime = ms,
+                            "binc" => params.binc = ms,
+                            "winc" => params.winc = ms,
+                            "byoyomi" => params.byoyomi = ms,
+                            "movetime" => params.movetime = Some(ms),
+                            "depth" => params.depth = Some(v.min(u64::from(u8::MAX)) as u8),
+                            _ => params.nodes = Some(v),
+                        }
+                        i += 2;
+                    } else {
+                        i += 1;
+                    }
+                }
+                "mate" => match parts.get(i + 1) {
+                    Some(&"infinite") => {
+                        params.mate = Some(MateLimit::Infinite);
+                        i += 2;
+                    }
+                    Some(v) => {
+                        params.mate = Some(match v.parse::<u32>() {
+                            Ok(ms) => MateLimit::TimeMs(ms),
+                            Err(_) => MateLimit::Infinite,
+                        });
+                        i += 2;
+                    }
+                    None => {
+                        params.mate = Some(MateLimit::Infinite);
+                        i += 1;
+                    }
+                },
+                "infinite" => {
+                    params.infinite = true;
+                    i += 1;
+                }
+                "ponder" => {
+                    params.ponder = true;
+                    i += 1;
+                }
+                "searchmoves" => {
+                    i += 1;
+                    while i < parts.len() && !GO_KEYWORDS.contains(&parts[i]) {
+                        params.searchmoves.push(parts[i].to_string());
+                        i += 1;
+                    }
+                }
+                _ => i += 1,
+            }
+        }
+        params
+    }
+
+    fn has_clock(&self) -> bool {
+        self.btime > 0 || self.wtime > 0 || self.byoyomi > 0 || self.binc > 0 || self.winc > 0
+    }
+
+    /// Depth limit for the search; an explicit `go depth` overrides the configured depth
+    pub fn search_depth(&self, configured_depth: u8) -> u8 {
+        self.depth.unwrap_or(configured_depth)
+    }
+
+    /// A search that ends by itself and answers with `bestmove`, rather than
+    /// running until `stop` (infinite, ponder) or answering `checkmate`
+    pub fn is_timed(&self) -> bool {
+        !self.infinite && !self.ponder && self.mate.is_none()
+    }
+
+    /// Time to spend on this move when `player` is to move
+    pub fn time_budget_ms(&self, player: Player) -> u32 {
+        if let Some(movetime) = self.movetime {
+            return movetime;
+        }
+        if !self.has_clock() {
+            // depth/nodes searches are bounded by their own limit
+            return if self.depth.is_some() || self.nodes.is_some() {
+                UNBOUNDED_TIME_MS
+            } else {
+                DEFAULT_MOVE_TIME_MS
+            };
+        }
+        if self.byoyomi > 0 {
+            return self.byoyomi;
+        }
+        let (remaining, increment) = if player == Player::Black {
+            (self.btime, self.binc)
+        } else {
+            (self.wtime, self.winc)
+        };
+        (remaining / MOVES_TO_GO).saturating_add(increment).max(1)
+    }
+
+    /// Both clocks from `player`'s side; `None` without a clock
+    pub fn clocks(&self, player: Player) -> Option<Clocks> {
+        if !self.has_clock() {
+            return None;
+        }
+        let ((own_ms, own_increment_ms), (opponent_ms, opponent_increment_ms)) =
+            if player == Player::Black {
+                ((self.btime, self.binc), (self.wtime, self.winc))
+            } else {
+                ((self.wtime, self.winc), (self.btime, self.binc))
+            };
+        Some(Clocks {
+            own_ms,
+            own_increment_ms,
+            opponent_ms,
+            opponent_increment_ms,
+            byoyomi_ms: self.byoyomi,
+        })
+    }
+}
+
+/// Search running on a worker thread for `go infinite`, `go ponder` or
+/// `go mate infinite`
+struct BackgroundSearch {
+    job: BackgroundJob,
+    params: GoParams,
+    started_at: Instant,
+}
+
+enum BackgroundJob {
+    Search(SearchHandle),
+    Mate(SearchHandle<MateSearchResult>),
+}
+
+pub(crate) fn format_bestmove(best_move: Option<Move>) -> Vec<String> {
+    format_bestmove_with_ponder(best_move, None)
+}
+
+/// `bestmove`, with the expected reply appended as `ponder <move>` when known
+pub(crate) fn format_bestmove_with_ponder(
+    best_move: Option<Move>,
+    ponder_move: Option<Move>,
+) -> Vec<String> {
+    if let Some(mv) = best_move {
+        crate::utils::telemetry::trace_log(
+            "USI_GO",
+            &format!("Best move found: {}", mv.to_usi_string()),
+        );
+        match ponder_move {
+            Some(reply) => vec![format!(
+                "bestmove {} ponder {}",
+                mv.to_usi_string(),
+                reply.to_usi_string()
+            )],
+            None => vec![format!("bestmove {}", mv.to_usi_string())],
+        }
+    } else {
+        crate::utils::telemetry::trace_log("USI_GO", "No legal moves found, resigning");
+        vec!["bestmove resign".to_string()]
+    }
+}
+
+/// Answer to `go mate`
+fn checkmate_line(result: &MateSearchResult) -> String {
+    match result {
+        MateSearchResult::Mate(moves) => format!(
+            "checkmate {}",
+            moves
+                .iter()
+                .map(|mv| mv.to_usi_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        ),
+        MateSearchResult::NoMate => "checkmate nomate".to_string(),
+        MateSearchResult::Timeout => "checkmate timeout".to_string(),
+    }
+}
 
 pub struct UsiHandler {
     engine: ShogiEngine,
+    background_search: Option<BackgroundSearch>,
+    /// Run timed searches on a worker thread too, see `set_background_timed_search`
+    background_timed_search: bool,
 }
 
 impl UsiHandler {
     pub fn new() -> Self {
         Self {
             engine: ShogiEngine::new(),
+            background_search: None,
+            background_timed_search: false,
+        }
+    }
+
+    /// Run timed searches on a worker thread like `go infinite`, with their
+    /// `bestmove` returned by `poll_timed_search`. Embedders that feed commands
+    /// from the thread calling `handle_command` use it so `isready` and other
+    /// commands are still answered while the engine thinks.
+    pub fn set_background_timed_search(&mut self, enabled: bool) {
+        self.background_timed_search = enabled;
+    }
+
+    /// Whether a timed search started in the background is still to report
+    pub fn timed_search_pending(&self) -> bool {
+        self.background_search
+            .as_ref()
+            .is_some_and(|search| search.params.is_timed())
+    }
+
+    /// `bestmove` of a background timed search once it has finished; nothing
+    /// while it runs or when there is none
+    pub fn poll_timed_search(&mut self) -> Vec<String> {
+        let finished = match &self.background_search {
+            Some(BackgroundSearch { job: BackgroundJob::Search(handle), params, .. }) => {
+                params.is_timed() && handle.is_finished()
+            }
+            _ => false,
+        };
+        if finished {
+            self.finish_background_search()
+        } else {
+            Vec::new()
         }
     }
 
+    /// Flag polled by the running search. Embedders that feed commands from the
+    /// same thread as `handle_command` set it to stop a timed search early, since
+    /// a queued `stop` is only read once that search has returned.
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        self.engine.stop_flag.clone()
+    }
+
     pub fn handle_command(&mut self, command_str: &str) -> Vec<String> {
         let parts: Vec<&str> = command_str.trim().split_whitespace().collect();
 
@@ -30,12 +306,28 @@ impl UsiHandler {
             "debug" => self.engine.handle_debug(&parts[1..]),
             "position" => self.engine.handle_position(&parts[1..]),
             "go" => self.handle_go(&parts[1..]),
-            "stop" => self.engine.handle_stop(),
-            "ponderhit" => self.engine.handle_ponderhit(),
+            "stop" => self.handle_stop(),
+            "ponderhit" => self.handle_ponderhit(),
             "setoption" => self.engine.handle_setoption(&parts[1..]),
+            "reloadweights" => self.engine.handle_reloadweights(),
             "usinewgame" => self.engine.handle_usinewgame(),
             "gameover" => self.engine.handle_gameover(&parts[1..]),
+            "stats" => self.handle_stats(),
+            "statshub" => self.handle_statshub(&parts[1..]),
+            "log" => self.handle_log(&parts[1..]),
+            "hints" => self.handle_hints(&parts[1..]),
+            "hint" => self.handle_hint(&parts[1..]),
+            "candidates" => self.handle_candidates(&parts[1..]),
+            "matemeter" => self.handle_matemeter(&parts[1..]),
+            "sharpness" => self.handle_sharpness(&parts[1..]),
+            "gamestatus" => self.handle_gamestatus(),
+            "moveinfo" => self.handle_moveinfo(&parts[1..]),
+            "why" => self.handle_why(&parts[1..]),
+            "notation" => self.handle_notation(&parts[1..]),
+            "opening" => self.handle_opening(),
             "quit" => Vec::new(), // quit is handled by the caller
+            // Comment, such as the correlation ID the app sends before `position`
+            comment if comment.starts_with('#') => Vec::new(),
             _ => vec![format!("info string Unknown command: {}", parts.join(" "))],
         }
     }
@@ -45,96 +337,219 @@ impl UsiHandler {
         crate::debug_utils::set_search_start_time();
         crate::debug_utils::start_timing("go_command_parsing");
 
-        let mut btime = 0;
-        let mut wtime = 0;
-        let mut byoyomi = 0;
+        // A new go supersedes any infinite/ponder search that was never stopped
+        if self.background_search.is_some() {
+            self.finish_background_search();
+        }
 
-        let mut i = 0;
-        while i < parts.len() {
-            match parts[i] {
-                "btime" => {
-                    if i + 1 < parts.len() {
-                        btime = parts[i + 1].parse().unwrap_or(0);
-                        i += 2;
-                    } else {
-                        i += 1;
-                    }
-                }
-                "wtime" => {
-                    if i + 1 < parts.len() {
-                        wtime = parts[i + 1].parse().unwrap_or(0);
-                        i += 2;
-                    } else {
-                        i += 1;
-                    }
-                }
-                "byoyomi" => {
-                    if i + 1 < parts.len() {
-                        byoyomi = parts[i + 1].parse().unwrap_or(0);
-                        i += 2;
-                    } else {
-                        i += 1;
-                    }
-                }
-                _ => i += 1,
-            }
+        let mut params = GoParams::parse(parts);
+        if params.ponder && !self.engine.ponder_enabled() {
+            // USI_Ponder is off: search the position on our own clock instead
+            params.ponder = false;
         }
 
         crate::debug_utils::end_timing("go_command_parsing", "USI_GO");
-        crate::utils::telemetry::trace_log(
-            "USI_GO",
-            &format!(
-                "Parsed time controls: btime={}ms wtime={}ms byoyomi={}ms",
-                btime, wtime, byoyomi
-            ),
-        );
+        crate::utils::telemetry::trace_log("USI_GO", &format!("Parsed go parameters: {:?}", params));
 
-        let time_to_use = if byoyomi > 0 {
-            byoyomi
-        } else {
-            let time_for_player = if self.engine.current_player == crate::types::Player::Black {
-                btime
-            } else {
-                wtime
-            };
-            if time_for_player > 0 {
-                time_for_player / 40 // Use a fraction of the remaining time
-            } else {
-                5000 // Default to 5 seconds if no time control is given
+        self.engine
+            .stop_flag
+            .store(false, std::sync::atomic::Ordering::Relaxed);
+
+        if let Some(limit) = params.mate {
+            if limit == MateLimit::Infinite {
+                // Only "stop" ends it, so it runs on a worker thread like go infinite
+                let stop_flag = self.engine.stop_flag.clone();
+                let handle = self.engine.spawn_mate_search(stop_flag);
+                self.background_search = Some(BackgroundSearch {
+                    job: BackgroundJob::Mate(handle),
+                    params,
+                    started_at: Instant::now(),
+                });
+                return Vec::new();
             }
-        };
+            return self.handle_go_mate(limit);
+        }
+
+        let depth = params.search_depth(self.engine.depth);
+        self.engine.set_node_limit(params.nodes);
+        self.engine.set_clocks(params.clocks(self.engine.current_player));
+        self.engine
+            .set_search_moves(Some(params.searchmoves.clone()).filter(|moves| !moves.is_empty()));
+
+        if params.infinite || params.ponder {
+            if params.infinite {
+                // Analysis of a new position: refresh the GUI's mate meter
+                crate::search::info_sink::emit_info(&self.mate_meter_line(
+                    crate::evaluation::king_danger::DEFAULT_MATE_THREAT_TIME_MS,
+                ));
+                // and show what an earlier visit found while the search catches up.
+                // A searchmoves analysis does not hold for the whole position.
+                if params.searchmoves.is_empty() {
+                    if let Some(cached) = self.engine.begin_cached_analysis() {
+                        let line = cached.info_line(
+                            self.engine.score_perspective(),
+                            self.engine.current_player,
+                            self.engine.show_win_rate(),
+                        );
+                        crate::search::info_sink::emit_info(
+                            &self.engine.info_verbosity().fields.filter(&line),
+                        );
+                    }
+                }
+            }
+            // The USI loop must keep reading commands so "stop"/"ponderhit" can end the
+            // search, so these modes run on a worker thread and bestmove is held until then.
+            // The thread takes only the search engine and a copy of the position.
+            let stop_flag = self.engine.stop_flag.clone();
+            let handle = self.engine.spawn_search(depth, UNBOUNDED_TIME_MS, stop_flag);
+            crate::utils::telemetry::trace_log(
+                "USI_GO",
+                if params.ponder {
+                    "Started ponder search"
+                } else {
+                    "Started infinite search"
+                },
+            );
+            self.background_search = Some(BackgroundSearch {
+                job: BackgroundJob::Search(handle),
+                params,
+                started_at: Instant::now(),
+            });
+            return Vec::new();
+        }
+
+        let time_to_use = params.time_budget_ms(self.engine.current_player);
 
         crate::debug_utils::log_decision(
             "USI_GO",
             "Time allocation",
             &format!(
-                "Player: {:?}, Allocated time: {}ms",
-                self.engine.current_player, time_to_use
+                "Player: {:?}, Depth: {}, Allocated time: {}ms",
+                self.engine.current_player, depth, time_to_use
             ),
             Some(time_to_use as i32),
         );
 
-        self.engine
-            .stop_flag
-            .store(false, std::sync::atomic::Ordering::Relaxed);
+        if self.background_timed_search {
+            let stop_flag = self.engine.stop_flag.clone();
+            let handle = self.engine.spawn_search(depth, time_to_use, stop_flag);
+            self.background_search = Some(BackgroundSearch {
+                job: BackgroundJob::Search(handle),
+                params,
+                started_at: Instant::now(),
+            });
+            return Vec::new();
+        }
 
         crate::debug_utils::start_timing("best_move_search");
-        let best_move = self.engine.get_best_move(
-            self.engine.depth,
+        let result = self.engine.get_best_move_guarded(
+            depth,
             time_to_use,
             Some(self.engine.stop_flag.clone()),
         );
         crate::debug_utils::end_timing("best_move_search", "USI_GO");
 
-        if let Some(mv) = best_move {
-            crate::utils::telemetry::trace_log(
-                "USI_GO",
-                &format!("Best move found: {}", mv.to_usi_string()),
-            );
-            vec![format!("bestmove {}", mv.to_usi_string())]
+        self.search_result_output(result)
+    }
+
+    /// `bestmove` for a finished search; a panicked search is reported with an
+    /// error info string and answered with its fallback move
+    fn search_result_output(&self, result: Result<Option<Move>, SearchPanic>) -> Vec<String> {
+        match result {
+            Ok(best_move) => self.bestmove_output(best_move),
+            Err(search_panic) => {
+                let mut output = vec![search_panic.info_line()];
+                output.extend(format_bestmove(search_panic.fallback));
+                output
+            }
+        }
+    }
+
+    /// `bestmove` line, naming the reply to ponder on when `USI_Ponder` is enabled
+    fn bestmove_output(&self, best_move: Option<Move>) -> Vec<String> {
+        let ponder_move = best_move
+            .as_ref()
+            .filter(|_| self.engine.ponder_enabled())
+            .and_then(|mv| self.engine.ponder_move(mv));
+        format_bestmove_with_ponder(best_move, ponder_move)
+    }
+
+    fn handle_go_mate(&mut self, limit: MateLimit) -> Vec<String> {
+        let time_limit_ms = match limit {
+            MateLimit::TimeMs(ms) => Some(ms),
+            MateLimit::Infinite => None,
+        };
+        let result = self
+            .engine
+            .solve_mate(time_limit_ms, Some(self.engine.stop_flag.clone()));
+        vec![checkmate_line(&result)]
+    }
+
+    fn handle_stop(&mut self) -> Vec<String> {
+        if self.background_search.is_some() {
+            self.finish_background_search()
         } else {
-            crate::utils::telemetry::trace_log("USI_GO", "No legal moves found, resigning");
-            vec!["bestmove resign".to_string()]
+            self.engine.handle_stop()
+        }
+    }
+
+    fn handle_ponderhit(&mut self) -> Vec<String> {
+        let mut output = self.engine.handle_ponderhit();
+        let Some(search) = self.background_search.as_ref() else {
+            return output;
+        };
+        if !search.params.ponder {
+            return output;
+        }
+
+        // The opponent played the expected move: the ponder search becomes a normal
+        // timed search, with the clock starting now.
+        let budget = Duration::from_millis(u64::from(
+            search.params.time_budget_ms(self.engine.current_player),
+        ));
+        if let BackgroundJob::Search(handle) = &search.job {
+            handle.wait_timeout(budget);
+        }
+        crate::utils::telemetry::trace_log(
+            "USI_GO",
+            &format!(
+                "Ponderhit after {}ms of pondering",
+                search.started_at.elapsed().as_millis()
+            ),
+        );
+
+        output.extend(self.finish_background_search());
+        output
+    }
+
+    /// Stop the worker-thread search and report its best move
+    fn finish_background_search(&mut self) -> Vec<String> {
+        let Some(search) = self.background_search.take() else {
+            return Vec::new();
+        };
+        self.engine
+            .stop_flag
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+        match search.job {
+            BackgroundJob::Search(handle) => {
+                let result = handle.join().map_err(|payload| self.engine.search_panic(payload));
+                self.engine.end_cached_analysis();
+                self.search_result_output(result)
+            }
+            BackgroundJob::Mate(handle) => match handle.join() {
+                Ok(result) => vec![checkmate_line(&result)],
+                Err(payload) => {
+                    let message = crate::search::search_watchdog::panic_message(payload.as_ref());
+                    crate::utils::logging::error(
+                        "USI_GO",
+                        &format!("Mate search panicked: {}", message),
+                    );
+                    vec![
+                        format!("info string error mate search panicked: {}", message),
+                        checkmate_line(&MateSearchResult::Timeout),
+                    ]
+                }
+            },
         }
     }
 
@@ -144,7 +559,10 @@ impl UsiHandler {
         vec![
             "id name Shogi Engine".to_string(),
             "id author Gemini".to_string(),
-            "option name USI_Hash type spin default 16 min 1 max 1024".to_string(),
+            format!(
+                "option name USI_Hash type spin default {} min 1 max 1024",
+                self.engine.hash_size_mb()
+            ),
             format!(
                 "option name ParallelEnable type check default {}",
                 if parallel_options.enable_parallel {
@@ -164,6 +582,9 @@ impl UsiHandler {
             "option name PSTPreset type combo default Builtin var Builtin var Default var Custom"
                 .to_string(),
             "option name PSTPath type string default".to_string(),
+            "option name WeightsFile type string default".to_string(),
+            "option name BookFile type string default".to_string(),
+            "option name TablebaseFile type string default".to_string(),
             format!(
                 "option name ParallelMetrics type check default {}",
                 if parallel_options.enable_metrics {
@@ -207,6 +628,14 @@ impl UsiHandler {
             // Fixed: MaxDepth now allows 0-100 (0 = unlimited/adaptive), default 0
             "option name MaxDepth type spin default 0 min 0 max 100".to_string(),
             format!("option name USI_Threads type spin default {} min 1 max 32", thread_count),
+            format!(
+                "option name USI_Ponder type check default {}",
+                self.engine.ponder_enabled()
+            ),
+            format!(
+                "option name USI_OwnBook type check default {}",
+                self.engine.own_book()
+            ),
             // Time Management Options (Task 8.0, 4.0)
             "option name TimeCheckFrequency type spin default 1024 min 1 max 100000".to_string(),
             "option name TimeSafetyMargin type spin default 100 min 0 max 10000".to_string(),
@@ -217,19 +646,345 @@ impl UsiHandler {
             "option name EnableAspirationWindows type check default true".to_string(),
             "option name AspirationWindowSize type spin default 25 min 10 max 500".to_string(),
             "option name EnablePositionTypeTracking type check default true".to_string(),
+            "option name Determinism type check default false".to_string(),
+            "option name TTKeyVerification type check default false".to_string(),
+            "option name AnalysisRootSplit type check default false".to_string(),
+            format!(
+                "option name MaxSearchPly type spin default {} min {} max 1024",
+                crate::search::ply_guard::DEFAULT_MAX_PLY,
+                crate::search::ply_guard::MIN_MAX_PLY
+            ),
+            "option name RootVarietyMargin type spin default 0 min 0 max 500".to_string(),
+            format!(
+                "option name RootVarietyTemperature type spin default {} min 1 max 500",
+                crate::search::root_variety::DEFAULT_TEMPERATURE_CP
+            ),
+            format!(
+                "option name CoachLevel type spin default 0 min 0 max {}",
+                crate::search::coach::MAX_COACH_LEVEL
+            ),
+            "option name ClockContemptMargin type spin default 0 min 0 max 500".to_string(),
+            format!(
+                "option name ClockTroubleTime type spin default {} min 0 max 60000",
+                crate::search::clock_contempt::DEFAULT_TROUBLE_MS
+            ),
+            "option name Handicap type combo default Even var Even var Lance var Bishop var Rook var TwoPiece var FourPiece var SixPiece".to_string(),
+            "option name ScorePerspective type combo default SideToMove var SideToMove var BlackPositive".to_string(),
+            "option name ShowWinRate type check default false".to_string(),
+            "option name WarmUp type check default true".to_string(),
+            "option name InfoDepthInterval type spin default 1 min 1 max 64".to_string(),
+            format!(
+                "option name InfoInterval type spin default {} min 0 max 60000",
+                crate::search::info_verbosity::DEFAULT_INFO_INTERVAL_MS
+            ),
+            "option name InfoNodeInterval type spin default 0 min 0 max 1000000000".to_string(),
+            "option name InfoCurrMove type check default true".to_string(),
+            "option name InfoFields type string default all".to_string(),
+            "option name Language type combo default en var en var ja".to_string(),
             // Legacy depth option (for backward compatibility, maps to MaxDepth)
             "option name depth type spin default 0 min 0 max 100".to_string(),
             "usiok".to_string(),
         ]
     }
 
-    fn handle_isready(&self) -> Vec<String> {
-        vec!["readyok".to_string()]
+    /// Non-standard `stats` command: report search statistics as JSON for the GUI
+    fn handle_stats(&self) -> Vec<String> {
+        match serde_json::to_string(&self.engine.get_search_statistics()) {
+            Ok(json) => vec![format!("info string stats {}", json)],
+            Err(e) => vec![format!("info string stats error: {}", e)],
+        }
+    }
+
+    /// Non-standard `statshub [delta|reset]` debug command: every statistics source
+    /// in one JSON snapshot. `reset` replies with the snapshot taken as the new
+    /// baseline and `delta` with the counters accumulated since.
+    fn handle_statshub(&mut self, parts: &[&str]) -> Vec<String> {
+        let snapshot = match parts.first().copied() {
+            None => self.engine.statistics_snapshot(),
+            Some("delta") => self.engine.statistics_delta(),
+            Some("reset") => self.engine.reset_statistics(),
+            Some(other) => {
+                return vec![format!(
+                    "info string statshub error: unknown mode '{}', expected delta or reset",
+                    other
+                )]
+            }
+        };
+        match snapshot {
+            Some(snapshot) => match serde_json::to_string(&snapshot) {
+                Ok(json) => vec![format!("info string statshub {}", json)],
+                Err(e) => vec![format!("info string statshub error: {}", e)],
+            },
+            None => vec!["info string statshub unavailable while searching".to_string()],
+        }
+    }
+
+    /// Non-standard `log` command over the structured log:
+    /// `log [count] [level] [category]` shows records as JSON, one per line;
+    /// `log level <level>`, `log file <path|off>` and `log clear` configure it
+    fn handle_log(&self, parts: &[&str]) -> Vec<String> {
+        use crate::utils::logging::{self, LogLevel};
+        const DEFAULT_LOG_LINES: usize = 20;
+
+        match parts.first().copied() {
+            Some("level") => match parts.get(1).and_then(|name| LogLevel::parse(name)) {
+                Some(level) => {
+                    logging::set_level(level);
+                    vec![format!("info string log level {}", level.as_str())]
+                }
+                None => vec![
+                    "info string log error: expected error, warn, info, debug or trace".to_string(),
+                ],
+            },
+            Some("file") => {
+                let target = parts[1..].join(" ");
+                let path = match target.as_str() {
+                    "" => return vec!["info string log error: expected a path or off".to_string()],
+                    "off" => None,
+                    path => Some(std::path::Path::new(path)),
+                };
+                match logging::set_log_file(path) {
+                    Ok(()) => vec![format!("info string log file {}", target)],
+                    Err(e) => vec![format!("info string log error: {}", e)],
+                }
+            }
+            Some("clear") => {
+                logging::clear();
+                vec!["info string log cleared".to_string()]
+            }
+            _ => {
+                let mut rest = parts;
+                let count = match rest.first().and_then(|text| text.parse::<usize>().ok()) {
+                    Some(count) => {
+                        rest = &rest[1..];
+                        count
+                    }
+                    None => DEFAULT_LOG_LINES,
+                };
+                let min_level = match rest.first().and_then(|name| LogLevel::parse(name)) {
+                    Some(level) => {
+                        rest = &rest[1..];
+                        level
+                    }
+                    None => LogLevel::Trace,
+                };
+                logging::recent(count, min_level, rest.first().copied())
+                    .into_iter()
+                    .filter_map(|record| serde_json::to_string(&record).ok())
+                    .map(|json| format!("info string log {}", json))
+                    .collect()
+            }
+        }
+    }
+
+    /// Non-standard `hints <square|piece*>` command: legal destinations as JSON for the GUI
+    fn handle_hints(&self, parts: &[&str]) -> Vec<String> {
+        let source = parts
+            .first()
+            .and_then(|text| crate::move_hints::HintSource::parse(text));
+        let Some(source) = source else {
+            return vec!["info string hints error: expected a square or hand piece".to_string()];
+        };
+        match serde_json::to_string(&self.engine.legal_move_hints(source)) {
+            Ok(json) => vec![format!("info string hints {}", json)],
+            Err(e) => vec![format!("info string hints error: {}", e)],
+        }
+    }
+
+    /// Non-standard `hint [level]` command: suggested move with a reason as JSON
+    /// for the GUI's hint button and best-move arrow
+    fn handle_hint(&self, parts: &[&str]) -> Vec<String> {
+        let level = match parts.first() {
+            None => crate::hint::MAX_HINT_LEVEL,
+            Some(text) => match text.parse::<u8>() {
+                Ok(level) if (1..=crate::hint::MAX_HINT_LEVEL).contains(&level) => level,
+                _ => {
+                    return vec![format!(
+                        "info string hint error: level must be between 1 and {}",
+                        crate::hint::MAX_HINT_LEVEL
+                    )]
+                }
+            },
+        };
+        match self.engine.get_hint(level) {
+            Some(hint) => match serde_json::to_string(&hint) {
+                Ok(json) => vec![format!("info string hint {}", json)],
+                Err(e) => vec![format!("info string hint error: {}", e)],
+            },
+            None => vec!["info string hint unavailable".to_string()],
+        }
+    }
+
+    /// Non-standard `candidates [count] [time_ms]` command: the best moves of the
+    /// current position with scores and continuations as JSON, for analysis arrows
+    fn handle_candidates(&self, parts: &[&str]) -> Vec<String> {
+        let count = match parts.first().map(|text| text.parse::<usize>()) {
+            None => crate::candidates::DEFAULT_CANDIDATES,
+            Some(Ok(count)) if (1..=crate::candidates::MAX_CANDIDATES).contains(&count) => count,
+            Some(_) => {
+                return vec![format!(
+                    "info string candidates error: count must be between 1 and {}",
+                    crate::candidates::MAX_CANDIDATES
+                )]
+            }
+        };
+        let time_limit_ms = match parts.get(1).map(|text| text.parse::<u32>()) {
+            None => crate::candidates::DEFAULT_CANDIDATE_TIME_MS,
+            Some(Ok(time_limit_ms)) => time_limit_ms,
+            Some(Err(_)) => {
+                return vec!["info string candidates error: invalid time".to_string()]
+            }
+        };
+        match self.engine.get_candidate_moves(count, time_limit_ms) {
+            Some(candidates) => match serde_json::to_string(&candidates) {
+                Ok(json) => vec![format!("info string candidates {}", json)],
+                Err(e) => vec![format!("info string candidates error: {}", e)],
+            },
+            None => vec!["info string candidates unavailable".to_string()],
+        }
+    }
+
+    /// Non-standard `matemeter [time_ms]` command: each king's danger level with
+    /// the attack summary behind it and any short mate found, as JSON
+    fn handle_matemeter(&self, parts: &[&str]) -> Vec<String> {
+        let time_limit_ms = match parts.first().map(|text| text.parse::<u32>()) {
+            None => crate::evaluation::king_danger::DEFAULT_MATE_THREAT_TIME_MS,
+            Some(Ok(time_limit_ms)) => time_limit_ms,
+            Some(Err(_)) => return vec!["info string matemeter error: invalid time".to_string()],
+        };
+        vec![self.mate_meter_line(time_limit_ms)]
+    }
+
+    fn mate_meter_line(&self, time_limit_ms: u32) -> String {
+        match serde_json::to_string(&self.engine.mate_meter(time_limit_ms)) {
+            Ok(json) => format!("info string matemeter {}", json),
+            Err(e) => format!("info string matemeter error: {}", e),
+        }
+    }
+
+    /// Non-standard `sharpness [count] [time_ms]` command: how sharp the current
+    /// position is, from the win rates of its best `count` moves and its tactical
+    /// density, as JSON
+    fn handle_sharpness(&self, parts: &[&str]) -> Vec<String> {
+        let count = match parts.first().map(|text| text.parse::<usize>()) {
+            None => crate::sharpness::DEFAULT_SHARPNESS_CANDIDATES,
+            Some(Ok(count)) if (2..=crate::candidates::MAX_CANDIDATES).contains(&count) => count,
+            Some(_) => {
+                return vec![format!(
+                    "info string sharpness error: count must be between 2 and {}",
+                    crate::candidates::MAX_CANDIDATES
+                )]
+            }
+        };
+        let time_limit_ms = match parts.get(1).map(|text| text.parse::<u32>()) {
+            None => crate::sharpness::DEFAULT_SHARPNESS_TIME_MS,
+            Some(Ok(time_limit_ms)) => time_limit_ms,
+            Some(Err(_)) => return vec!["info string sharpness error: invalid time".to_string()],
+        };
+        match self.engine.sharpness(count, time_limit_ms) {
+            Some(sharpness) => match serde_json::to_string(&sharpness) {
+                Ok(json) => vec![format!("info string sharpness {}", json)],
+                Err(e) => vec![format!("info string sharpness error: {}", e)],
+            },
+            None => vec!["info string sharpness unavailable".to_string()],
+        }
+    }
+
+    /// Non-standard `gamestatus` command: whether the game has ended in the
+    /// current position and how, as JSON
+    fn handle_gamestatus(&self) -> Vec<String> {
+        match serde_json::to_string(&self.engine.game_status()) {
+            Ok(json) => vec![format!("info string gamestatus {}", json)],
+            Err(e) => vec![format!("info string gamestatus error: {}", e)],
+        }
+    }
+
+    /// Non-standard `moveinfo <move>` command: what a move would do in the
+    /// current position (check, mate, promotion, capture) as JSON
+    fn handle_moveinfo(&self, parts: &[&str]) -> Vec<String> {
+        let Some(usi) = parts.first() else {
+            return vec!["info string moveinfo error: missing move".to_string()];
+        };
+        match self.engine.move_metadata(usi) {
+            Some(metadata) => match serde_json::to_string(&metadata) {
+                Ok(json) => vec![format!("info string moveinfo {}", json)],
+                Err(e) => vec![format!("info string moveinfo error: {}", e)],
+            },
+            None => vec![format!("info string moveinfo error: illegal move {}", usi)],
+        }
+    }
+
+    /// Non-standard `why <move> [time_ms]` debug command: search the current
+    /// position and report why the move was or was not chosen, one reason per
+    /// line followed by the full explanation as JSON
+    fn handle_why(&self, parts: &[&str]) -> Vec<String> {
+        let Some(usi) = parts.first() else {
+            return vec!["info string why error: missing move".to_string()];
+        };
+        let time_limit_ms = match parts.get(1).map(|text| text.parse::<u32>()) {
+            None => crate::explain::DEFAULT_EXPLAIN_TIME_MS,
+            Some(Ok(time_limit_ms)) => time_limit_ms,
+            Some(Err(_)) => return vec!["info string why error: invalid time".to_string()],
+        };
+        let explanation = match self.engine.explain_move(usi, time_limit_ms) {
+            Ok(explanation) => explanation,
+            Err(e) => return vec![format!("info string why error: {}", e)],
+        };
+        let mut output: Vec<String> = explanation
+            .reasons
+            .iter()
+            .map(|reason| format!("info string why {}: {}", usi, reason))
+            .collect();
+        match serde_json::to_string(&explanation) {
+            Ok(json) => output.push(format!("info string why {}", json)),
+            Err(e) => output.push(format!("info string why error: {}", e)),
+        }
+        output
+    }
+
+    /// Non-standard `notation <from> <to> <move>` command: rewrite a move in the
+    /// current position between `usi`, `kif`, `ki2` and `western` notation
+    fn handle_notation(&self, parts: &[&str]) -> Vec<String> {
+        if parts.len() < 3 {
+            return vec![
+                "info string notation error: usage notation <from> <to> <move>".to_string()
+            ];
+        }
+        let (Some(from), Some(to)) =
+            (NotationStyle::parse(parts[0]), NotationStyle::parse(parts[1]))
+        else {
+            return vec![format!(
+                "info string notation error: unknown style in {} {}",
+                parts[0], parts[1]
+            )];
+        };
+        match self.engine.convert_notation(&parts[2..].join(" "), from, to) {
+            Ok(text) => vec![format!("info string notation {}", text)],
+            Err(e) => vec![format!("info string notation error: {}", e)],
+        }
+    }
+
+    /// Non-standard `opening` command: the named opening of the current game as
+    /// JSON, `null` when the game is not in a known book line
+    fn handle_opening(&mut self) -> Vec<String> {
+        match serde_json::to_string(&self.engine.current_opening()) {
+            Ok(json) => vec![format!("info string opening {}", json)],
+            Err(e) => vec![format!("info string opening error: {}", e)],
+        }
+    }
+
+    /// `isready`, running the engine's one-time warm-up search first if enabled
+    fn handle_isready(&mut self) -> Vec<String> {
+        let mut output: Vec<String> = self.engine.warm_up().into_iter().collect();
+        output.push("readyok".to_string());
+        output
     }
 }
 
 pub fn run_usi_loop() {
     let mut handler = UsiHandler::new();
+    // Calibrates on the first run on this machine, before the GUI's `usi`
+    handler.engine.auto_configure();
     let mut stdout = io::stdout();
 
     for line in io::stdin().lock().lines() {
@@ -251,3 +1006,489 @@ pub fn run_usi_loop() {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::perspective::EvaluationPerspective;
+    use std::thread;
+
+    #[test]
+    fn test_stats_command_reports_json() {
+        let mut handler = UsiHandler::new();
+        handler.handle_command("position startpos");
+        handler.handle_command("go depth 2");
+        let output = handler.handle_command("stats");
+        assert_eq!(output.len(), 1);
+        let json = output[0].strip_prefix("info string stats ").unwrap();
+        let report: crate::search::statistics::SearchStatisticsReport =
+            serde_json::from_str(json).unwrap();
+        assert!(report.nodes > 0);
+        assert!(!report.iteration_times_ms.is_empty());
+    }
+
+    #[test]
+    fn test_statshub_reports_all_sources_and_deltas() {
+        fn parse(output: &[String]) -> crate::statistics_hub::StatisticsSnapshot {
+            assert_eq!(output.len(), 1);
+            let json = output[0].strip_prefix("info string statshub ").unwrap();
+            serde_json::from_str(json).unwrap()
+        }
+
+        let mut handler = UsiHandler::new();
+        handler.handle_command("position startpos");
+        handler.handle_command("go depth 2");
+        let snapshot = parse(&handler.handle_command("statshub"));
+        assert!(snapshot.search.nodes > 0);
+        assert!(snapshot.move_ordering.moves_ordered > 0);
+        assert!(snapshot.transposition_table.size > 0);
+
+        let baseline = parse(&handler.handle_command("statshub reset"));
+        assert_eq!(baseline.move_ordering, snapshot.move_ordering);
+        let delta = parse(&handler.handle_command("statshub delta"));
+        assert_eq!(delta.move_ordering.moves_ordered, 0);
+
+        let output = handler.handle_command("statshub bogus");
+        assert!(output[0].starts_with("info string statshub error"));
+    }
+
+    #[test]
+    fn test_usi_ponder_and_own_book_options() {
+        let mut handler = UsiHandler::new();
+        let options = handler.handle_command("usi");
+        assert!(options.contains(&"option name USI_Ponder type check default false".to_string()));
+        assert!(options.contains(&"option name USI_OwnBook type check default true".to_string()));
+
+        let output = handler.handle_command("setoption name USI_OwnBook value false");
+        assert_eq!(output, vec!["info string Disabled opening book".to_string()]);
+        assert!(!handler.engine.own_book());
+        let output = handler.handle_command("setoption name USI_Ponder value true");
+        assert_eq!(output, vec!["info string Enabled pondering".to_string()]);
+
+        handler.handle_command("position startpos");
+        let output = handler.handle_command("go depth 3");
+        let tokens: Vec<&str> = output[0].split_whitespace().collect();
+        assert_eq!(tokens[0], "bestmove");
+        if tokens.len() > 2 {
+            assert_eq!(tokens.len(), 4);
+            assert_eq!(tokens[2], "ponder");
+        }
+
+        // With pondering off, `go ponder` is answered like a normal search
+        handler.handle_command("setoption name USI_Ponder value false");
+        let output = handler.handle_command("go ponder byoyomi 100");
+        assert!(output[0].starts_with("bestmove ") && !output[0].contains(" ponder "));
+    }
+
+    #[test]
+    fn test_background_timed_search_answers_isready() {
+        let mut handler = UsiHandler::new();
+        handler.set_background_timed_search(true);
+        handler.handle_command("position startpos");
+        assert!(handler.handle_command("go byoyomi 300").is_empty());
+        assert!(handler.timed_search_pending());
+        let output = handler.handle_command("isready");
+        assert_eq!(output.last().map(String::as_str), Some("readyok"));
+
+        let mut output = Vec::new();
+        while output.is_empty() {
+            thread::sleep(Duration::from_millis(10));
+            output = handler.poll_timed_search();
+        }
+        assert!(output[0].starts_with("bestmove "));
+        assert!(!handler.timed_search_pending());
+    }
+
+    #[test]
+    fn test_log_command_queries_structured_log() {
+        let mut handler = UsiHandler::new();
+        crate::utils::logging::warn("usi_log_test", "first");
+        crate::utils::logging::error("usi_log_test", "second");
+
+        let output = handler.handle_command("log 1 warn usi_log_test");
+        assert_eq!(output.len(), 1);
+        let json = output[0].strip_prefix("info string log ").unwrap();
+        let record: crate::utils::logging::LogRecord = serde_json::from_str(json).unwrap();
+        assert_eq!(record.message, "second");
+
+        let output = handler.handle_command("log level loud");
+        assert!(output[0].starts_with("info string log error"));
+    }
+
+    #[test]
+    fn test_search_engine_recovers_from_poisoned_lock() {
+        let mut handler = UsiHandler::new();
+        let search_engine = handler.engine.search_engine.clone();
+        let _ = thread::spawn(move || {
+            let _guard = search_engine.lock().unwrap();
+            panic!("simulated search panic");
+        })
+        .join();
+        assert!(handler.engine.search_engine.is_poisoned());
+
+        handler.engine.recover_search_engine();
+        assert!(!handler.engine.search_engine.is_poisoned());
+        handler.handle_command("position startpos");
+        let output = handler.handle_command("go depth 1");
+        assert!(output[0].starts_with("bestmove ") && output[0] != "bestmove resign");
+    }
+
+    #[test]
+    fn test_go_searchmoves_restricts_root_moves() {
+        let mut handler = UsiHandler::new();
+        handler.handle_command("position startpos");
+        let output = handler.handle_command("go depth 2 searchmoves 1g1f");
+        assert_eq!(output, vec!["bestmove 1g1f".to_string()]);
+
+        // Illegal candidates are ignored rather than leaving nothing to search
+        let output = handler.handle_command("go depth 1 searchmoves 5e5d");
+        assert!(output[0].starts_with("bestmove ") && output[0] != "bestmove resign");
+    }
+
+    #[test]
+    fn test_root_variety_scores_every_root_move() {
+        let mut handler = UsiHandler::new();
+        handler.handle_command("setoption name RootVarietyMargin value 50");
+        handler.handle_command("position sfen 4k4/9/9/9/9/9/9/9/4K4 b P 1");
+        let output = handler.handle_command("go depth 2");
+        assert!(output[0].starts_with("bestmove ") && output[0] != "bestmove resign");
+
+        let search_engine = handler.engine.search_engine.lock().unwrap();
+        assert!(search_engine.root_variety().is_enabled());
+        assert!(!search_engine.root_move_scores().is_empty());
+    }
+
+    #[test]
+    fn test_coach_level_scores_every_root_move() {
+        let mut handler = UsiHandler::new();
+        let output = handler.handle_command("setoption name CoachLevel value 3");
+        assert!(output.iter().any(|line| line.contains("Set CoachLevel to 3")));
+        handler.handle_command("position sfen 4k4/9/9/9/9/9/9/9/4K4 b P 1");
+        let output = handler.handle_command("go depth 2");
+        assert!(output[0].starts_with("bestmove ") && output[0] != "bestmove resign");
+
+        let search_engine = handler.engine.search_engine.lock().unwrap();
+        assert_eq!(search_engine.coach().level, 3);
+        assert!(!search_engine.root_move_scores().is_empty());
+    }
+
+    #[test]
+    fn test_hint_reports_move_and_reason() {
+        let mut handler = UsiHandler::new();
+        handler.handle_command("position sfen 4k4/9/4P4/9/9/9/9/9/4K4 b G 1");
+        let output = handler.handle_command("hint 3");
+        let json = output[0].strip_prefix("info string hint ").expect("hint reply");
+        let hint: crate::hint::Hint = serde_json::from_str(json).unwrap();
+        assert_eq!(hint.usi, "G*5b");
+        assert_eq!(hint.from, None);
+        assert_eq!(hint.reason, "mates immediately");
+
+        let output = handler.handle_command("hint 9");
+        assert!(output[0].contains("hint error"));
+    }
+
+    #[test]
+    fn test_candidates_are_ranked_and_distinct() {
+        let mut handler = UsiHandler::new();
+        handler.handle_command("position sfen 4k4/9/4P4/9/9/9/9/9/4K4 b G 1");
+        let output = handler.handle_command("candidates 3 300");
+        let json = output[0]
+            .strip_prefix("info string candidates ")
+            .expect("candidates reply");
+        let candidates: Vec<crate::candidates::CandidateMove> = serde_json::from_str(json).unwrap();
+        assert_eq!(candidates.len(), 3);
+        assert_eq!(candidates[0].usi, "G*5b");
+        assert_eq!(candidates[0].mate, Some(1));
+        assert_eq!(candidates[0].pv[0], "G*5b");
+        for (index, candidate) in candidates.iter().enumerate() {
+            assert_eq!(candidate.rank, index + 1);
+            assert_eq!(candidate.pv[0], candidate.usi);
+        }
+        let distinct: std::collections::HashSet<&str> =
+            candidates.iter().map(|candidate| candidate.usi.as_str()).collect();
+        assert_eq!(distinct.len(), 3);
+        assert!(candidates[0].score >= candidates[1].score);
+        // The temporary root restriction is gone afterwards
+        assert!(handler.engine.search_engine.lock().unwrap().search_moves().is_none());
+
+        let output = handler.handle_command("candidates 0");
+        assert!(output[0].contains("candidates error"));
+    }
+
+    #[test]
+    fn test_why_explains_a_losing_move() {
+        let mut handler = UsiHandler::new();
+        handler.handle_command("position sfen 4k4/9/4P4/9/9/9/9/9/4K4 b G 1");
+        let output = handler.handle_command("why G*1a 400");
+        let json = output
+            .last()
+            .and_then(|line| line.strip_prefix("info string why {"))
+            .expect("why reply");
+        let explanation: crate::explain::MoveExplanation =
+            serde_json::from_str(&format!("{{{}", json)).unwrap();
+        assert!(!explanation.chosen);
+        assert_eq!(explanation.best_move, "G*5b");
+        assert!(explanation.score_loss > 0);
+        assert_eq!(output.len(), explanation.reasons.len() + 1);
+        assert!(output[0].starts_with("info string why G*1a: scores"));
+        assert!(handler.engine.search_engine.lock().unwrap().search_moves().is_none());
+
+        let output = handler.handle_command("why 1a1b");
+        assert!(output[0].contains("why error: illegal move"));
+    }
+
+    #[test]
+    fn test_matemeter_reports_both_kings() {
+        let mut handler = UsiHandler::new();
+        handler.handle_command("position sfen 4k4/9/4P4/9/9/9/9/9/4K4 b G 1");
+        let output = handler.handle_command("matemeter 500");
+        let json = output[0]
+            .strip_prefix("info string matemeter ")
+            .expect("matemeter reply");
+        let meter: crate::evaluation::king_danger::MateMeter = serde_json::from_str(json).unwrap();
+        assert_eq!(meter.white.level, 100);
+        assert_eq!(meter.white.mate_line, vec!["G*5b"]);
+        assert_eq!(meter.black.mate_in, None);
+
+        let output = handler.handle_command("matemeter soon");
+        assert!(output[0].contains("matemeter error"));
+    }
+
+    #[test]
+    fn test_gamestatus_after_position_moves() {
+        let mut handler = UsiHandler::new();
+        handler.handle_command("position startpos moves 7g7f");
+        assert_eq!(
+            handler.handle_command("gamestatus"),
+            vec![r#"info string gamestatus "ongoing""#]
+        );
+
+        handler.handle_command("position sfen 4k4/9/4P4/9/9/9/9/9/4K4 b G 1 moves G*5b");
+        assert_eq!(
+            handler.handle_command("gamestatus"),
+            vec![r#"info string gamestatus {"checkmate":"Black"}"#]
+        );
+
+        let shuffle = ["2h3h", "8b7b", "3h2h", "7b8b"].repeat(3).join(" ");
+        handler.handle_command(&format!("position startpos moves {}", shuffle));
+        assert_eq!(handler.engine.game_status(), crate::game_status::GameStatus::Repetition);
+    }
+
+    #[test]
+    fn test_moveinfo_reports_mate_and_same_square() {
+        let mut handler = UsiHandler::new();
+        handler.handle_command("position sfen 4k4/9/4P4/9/9/9/9/9/4K4 b G 1");
+        let output = handler.handle_command("moveinfo G*5b");
+        let json = output[0].strip_prefix("info string moveinfo ").expect("moveinfo reply");
+        let metadata: crate::move_metadata::MoveMetadata = serde_json::from_str(json).unwrap();
+        assert!(metadata.is_drop && metadata.is_mate);
+
+        handler.handle_command("position startpos moves 7g7f 3c3d");
+        let metadata = handler.engine.move_metadata("8h2b+").unwrap();
+        assert_eq!(metadata.captured, Some(crate::types::PieceType::Bishop));
+        assert!(metadata.is_promotion && !metadata.same_square);
+
+        assert!(handler.handle_command("moveinfo 5a5b")[0].contains("moveinfo error"));
+    }
+
+    #[test]
+    fn test_notation_uses_current_position() {
+        let mut handler = UsiHandler::new();
+        handler.handle_command("position startpos moves 7g7f 3c3d 8h2b+");
+        assert_eq!(
+            handler.handle_command("notation usi ki2 3a2b"),
+            vec!["info string notation 同銀"]
+        );
+        assert_eq!(
+            handler.handle_command("notation kif western 同　銀(31)"),
+            vec!["info string notation Sx2b"]
+        );
+        assert!(handler.handle_command("notation usi kif 7g7f")[0].contains("notation error"));
+    }
+
+    #[test]
+    fn test_opening_follows_position_moves() {
+        let mut handler = UsiHandler::new();
+        handler.handle_command("position startpos");
+        assert_eq!(handler.handle_command("opening"), vec!["info string opening null"]);
+
+        handler.handle_command("position sfen 4k4/9/9/9/9/9/9/9/4K4 b P 1 moves 5i5h");
+        assert_eq!(handler.handle_command("opening"), vec!["info string opening null"]);
+        assert!(handler.engine.game_moves.is_none());
+
+        handler.handle_command("position startpos moves 7g7f 3c3d");
+        assert_eq!(
+            handler.engine.game_moves.as_deref(),
+            Some(&["7g7f".to_string(), "3c3d".to_string()][..])
+        );
+    }
+
+    #[test]
+    fn test_search_keeps_root_move_statistics() {
+        let mut handler = UsiHandler::new();
+        handler.handle_command("position sfen 4k4/9/9/9/9/9/9/9/4K4 b P 1");
+        handler.handle_command("go depth 3");
+
+        let search_engine = handler.engine.search_engine.lock().unwrap();
+        let root_moves = search_engine.root_move_list().expect("root moves are kept after a search");
+        let reports = root_moves.reports();
+        assert!(!reports.is_empty());
+        assert!(reports.iter().any(|report| report.nodes > 0 && !report.score_history.is_empty()));
+    }
+
+    #[test]
+    fn test_handicap_option_sets_start_position() {
+        let mut handler = UsiHandler::new();
+        let output = handler.handle_command("setoption name Handicap value TwoPiece");
+        assert_eq!(output, vec!["info string Set Handicap to TwoPiece".to_string()]);
+        handler.handle_command("position startpos moves 5a4b");
+        assert_eq!(handler.engine.current_player(), Player::Black);
+        // White moves first from the handicap setup
+        assert!(handler.engine.get_fen().starts_with("lnsg1gsnl/5k3/ppppppppp/"));
+
+        let output = handler.handle_command("setoption name Handicap value Queen");
+        assert!(output[0].starts_with("info string error"));
+    }
+
+    #[test]
+    fn test_score_perspective_option() {
+        let mut handler = UsiHandler::new();
+        assert!(handler.handle_command("usi").contains(
+            &"option name ScorePerspective type combo default SideToMove var SideToMove var BlackPositive"
+                .to_string()
+        ));
+        let output = handler.handle_command("setoption name ScorePerspective value BlackPositive");
+        assert_eq!(output, vec!["info string Set ScorePerspective to BlackPositive".to_string()]);
+        assert_eq!(handler.engine.score_perspective(), EvaluationPerspective::BlackPositive);
+
+        // Gote to move after 7g7f: candidate scores come back for sente
+        handler.handle_command("position startpos moves 7g7f");
+        let candidates = handler.engine.get_candidate_moves(2, 200).unwrap();
+        assert!(candidates
+            .iter()
+            .all(|candidate| candidate.perspective == EvaluationPerspective::BlackPositive));
+        assert!(candidates[0].score <= candidates[1].score);
+
+        let output = handler.handle_command("setoption name ScorePerspective value Gote");
+        assert!(output[0].starts_with("info string error"));
+        assert_eq!(handler.engine.score_perspective(), EvaluationPerspective::BlackPositive);
+    }
+
+    #[test]
+    fn test_rejected_position_keeps_the_previous_one() {
+        let mut handler = UsiHandler::new();
+        handler.handle_command("position startpos moves 7g7f");
+        let fen = handler.engine.get_fen();
+
+        let output = handler.handle_command("position startpos moves 7g7f 3c3d 5e5d");
+        assert_eq!(output.len(), 1);
+        assert!(output[0].starts_with("info string error rule: Failed to parse move '5e5d'"));
+        assert_eq!(handler.engine.get_fen(), fen);
+
+        let output = handler.handle_command("position sfen 9/9/9 x");
+        assert!(output[0].starts_with("info string error parse: Failed to parse FEN"));
+        assert_eq!(handler.engine.get_fen(), fen);
+    }
+
+    #[test]
+    fn test_position_drops_keep_piece_counts() {
+        let mut handler = UsiHandler::new();
+        handler.handle_command("position sfen 4k4/9/9/9/9/9/9/9/4K4 b G 1 moves G*5b");
+        assert!(handler.engine.get_fen().starts_with("4k4/4G4/9/9/9/9/9/9/4K4 w -"));
+        assert_eq!(handler.handle_command("debug check"), vec!["info string invariants hold"]);
+
+        let output = handler
+            .handle_command("position sfen 4k4/9/9/9/9/9/9/9/4K4 b G 1 moves G*5b 5a4a G*5c");
+        assert!(output[0].starts_with("info string error rule:"), "{:?}", output);
+    }
+
+    #[test]
+    fn test_isready_warms_up_once() {
+        let mut handler = UsiHandler::new();
+        let output = handler.handle_command("isready");
+        assert_eq!(output.len(), 2);
+        assert!(output[0].starts_with("info string Warmed up in"));
+        assert_eq!(output[1], "readyok");
+        assert_eq!(handler.handle_command("isready"), vec!["readyok".to_string()]);
+
+        let mut handler = UsiHandler::new();
+        handler.handle_command("setoption name WarmUp value false");
+        assert_eq!(handler.handle_command("isready"), vec!["readyok".to_string()]);
+    }
+
+    #[test]
+    fn test_info_verbosity_options() {
+        let mut handler = UsiHandler::new();
+        handler.handle_command("setoption name InfoDepthInterval value 2");
+        handler.handle_command("setoption name InfoNodeInterval value 500000");
+        handler.handle_command("setoption name InfoCurrMove value false");
+        let output = handler.handle_command("setoption name InfoFields value Nodes,PV");
+        assert_eq!(output, vec!["info string Set InfoFields to nodes,pv".to_string()]);
+
+        let verbosity = handler.engine.info_verbosity();
+        assert_eq!(verbosity.depth_interval, 2);
+        assert_eq!(verbosity.node_interval, 500_000);
+        assert!(!verbosity.currmove);
+        assert!(verbosity.fields.contains("pv") && !verbosity.fields.contains("nps"));
+
+        let output = handler.handle_command("setoption name InfoFields value nodes,eval");
+        assert!(output[0].starts_with("info string error InfoFields"));
+        let output = handler.handle_command("setoption name InfoDepthInterval value 0");
+        assert!(output[0].starts_with("info string error"));
+        assert_eq!(handler.engine.info_verbosity().depth_interval, 2);
+    }
+
+    #[test]
+    fn test_parse_clock_arguments() {
+        let params = GoParams::parse(&[
+            "btime", "60000", "wtime", "30000", "binc", "1000", "winc", "2000", "byoyomi", "0",
+        ]);
+        assert_eq!(params.btime, 60000);
+        assert_eq!(params.wtime, 30000);
+        assert_eq!(params.time_budget_ms(Player::Black), 60000 / MOVES_TO_GO + 1000);
+        assert_eq!(params.time_budget_ms(Player::White), 30000 / MOVES_TO_GO + 2000);
+        let clocks = params.clocks(Player::White).unwrap();
+        assert_eq!((clocks.own_ms, clocks.own_increment_ms), (30000, 2000));
+        assert_eq!((clocks.opponent_ms, clocks.opponent_increment_ms), (60000, 1000));
+
+        let params = GoParams::parse(&["btime", "0", "wtime", "0", "byoyomi", "3000"]);
+        assert_eq!(params.time_budget_ms(Player::Black), 3000);
+        assert_eq!(GoParams::parse(&["depth", "5"]).clocks(Player::Black), None);
+    }
+
+    #[test]
+    fn test_parse_search_modes() {
+        let params = GoParams::parse(&["depth", "7"]);
+        assert_eq!(params.search_depth(0), 7);
+        assert_eq!(params.time_budget_ms(Player::Black), UNBOUNDED_TIME_MS);
+
+        let params = GoParams::parse(&["movetime", "1500"]);
+        assert_eq!(params.time_budget_ms(Player::White), 1500);
+
+        let params = GoParams::parse(&["nodes", "100000"]);
+        assert_eq!(params.nodes, Some(100000));
+        assert_eq!(params.time_budget_ms(Player::Black), UNBOUNDED_TIME_MS);
+        assert!(GoParams::parse(&["infinite"]).infinite);
+
+        let params = GoParams::parse(&["ponder", "btime", "1000", "wtime", "1000", "byoyomi", "500"]);
+        assert!(params.ponder);
+        assert_eq!(params.byoyomi, 500);
+
+        assert_eq!(GoParams::parse(&[]).time_budget_ms(Player::Black), DEFAULT_MOVE_TIME_MS);
+    }
+
+    #[test]
+    fn test_parse_mate_limits() {
+        assert_eq!(GoParams::parse(&["mate", "2000"]).mate, Some(MateLimit::TimeMs(2000)));
+        assert_eq!(GoParams::parse(&["mate", "infinite"]).mate, Some(MateLimit::Infinite));
+        assert!(!GoParams::parse(&["mate", "infinite"]).infinite);
+    }
+
+    #[test]
+    fn test_parse_searchmoves() {
+        let params = GoParams::parse(&["searchmoves", "7g7f", "P*5e", "byoyomi", "1000"]);
+        assert_eq!(params.searchmoves, vec!["7g7f".to_string(), "P*5e".to_string()]);
+        assert_eq!(params.byoyomi, 1000);
+        assert!(GoParams::parse(&["infinite"]).searchmoves.is_empty());
+    }
+}
@@ -0,0 +1,244 @@
+//! Structured engine log: levelled, categorised records kept in a ring buffer
+//! and optionally appended to a file as JSON lines.
+//!
+//! Nothing here writes to stdout or stderr, so logging never interleaves with
+//! USI output. Records are read back with the `log` USI command. Debug and
+//! trace records come from `debug_utils`, which compiles them out without the
+//! `verbose-debug` feature, so release hot paths pay nothing for them.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
+
+/// Records kept in memory unless configured otherwise
+pub const DEFAULT_LOG_CAPACITY: usize = 1024;
+
+/// Severity of a log record, most severe first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+
+    /// Parse a level name, case-insensitively
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "error" => Some(LogLevel::Error),
+            "warn" | "warning" => Some(LogLevel::Warn),
+            "info" => Some(LogLevel::Info),
+            "debug" => Some(LogLevel::Debug),
+            "trace" => Some(LogLevel::Trace),
+            _ => None,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => LogLevel::Error,
+            1 => LogLevel::Warn,
+            2 => LogLevel::Info,
+            3 => LogLevel::Debug,
+            _ => LogLevel::Trace,
+        }
+    }
+}
+
+/// One log entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogRecord {
+    /// Wall-clock time, milliseconds since the UNIX epoch
+    pub timestamp_ms: u64,
+    /// Milliseconds since the current search started (0 outside a search)
+    pub search_elapsed_ms: u64,
+    pub level: LogLevel,
+    /// Subsystem the record comes from, e.g. `USI_GO` or `weights`
+    pub category: String,
+    pub message: String,
+}
+
+struct LogState {
+    records: VecDeque<LogRecord>,
+    capacity: usize,
+    file: Option<File>,
+}
+
+/// Most verbose level recorded, as `LogLevel as u8`; checked before locking
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Info as u8);
+
+lazy_static::lazy_static! {
+    static ref LOG_STATE: Mutex<LogState> = Mutex::new(LogState {
+        records: VecDeque::with_capacity(DEFAULT_LOG_CAPACITY),
+        capacity: DEFAULT_LOG_CAPACITY,
+        file: None,
+    });
+}
+
+/// Most verbose level currently recorded
+pub fn level() -> LogLevel {
+    LogLevel::from_u8(MAX_LEVEL.load(Ordering::Relaxed))
+}
+
+/// Record `level` and everything more severe
+pub fn set_level(level: LogLevel) {
+    MAX_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// Whether a record at `level` would be kept
+#[inline]
+pub fn enabled(level: LogLevel) -> bool {
+    level as u8 <= MAX_LEVEL.load(Ordering::Relaxed)
+}
+
+/// Record a message
+pub fn log(level: LogLevel, category: &str, message: &str) {
+    if !enabled(level) {
+        return;
+    }
+    let record = LogRecord {
+        timestamp_ms: crate::utils::time::current_time_ms(),
+        search_elapsed_ms: crate::debug_utils::get_search_elapsed_ms(),
+        level,
+        category: category.to_string(),
+        message: message.to_string(),
+    };
+
+    let Ok(mut state) = LOG_STATE.lock() else {
+        return;
+    };
+    if let Some(file) = state.file.as_mut() {
+        if let Ok(line) = serde_json::to_string(&record) {
+            // A failing log file must not take the engine down; the ring buffer still has it
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+    if state.capacity == 0 {
+        return;
+    }
+    if state.records.len() == state.capacity {
+        state.records.pop_front();
+    }
+    state.records.push_back(record);
+}
+
+pub fn error(category: &str, message: &str) {
+    log(LogLevel::Error, category, message);
+}
+
+pub fn warn(category: &str, message: &str) {
+    log(LogLevel::Warn, category, message);
+}
+
+pub fn info(category: &str, message: &str) {
+    log(LogLevel::Info, category, message);
+}
+
+/// Append records to `path` as JSON lines from now on, or stop with `None`
+pub fn set_log_file(path: Option<&Path>) -> std::io::Result<()> {
+    let file = match path {
+        Some(path) => Some(OpenOptions::new().create(true).append(true).open(path)?),
+        None => None,
+    };
+    if let Ok(mut state) = LOG_STATE.lock() {
+        state.file = file;
+    }
+    Ok(())
+}
+
+/// Keep at most `capacity` records in memory, dropping the oldest
+pub fn set_capacity(capacity: usize) {
+    if let Ok(mut state) = LOG_STATE.lock() {
+        state.capacity = capacity;
+        while state.records.len() > capacity {
+            state.records.pop_front();
+        }
+    }
+}
+
+/// The latest `limit` records at `min_level` or more severe, optionally only
+/// from `category`, oldest first
+pub fn recent(limit: usize, min_level: LogLevel, category: Option<&str>) -> Vec<LogRecord> {
+    let Ok(state) = LOG_STATE.lock() else {
+        return Vec::new();
+    };
+    let mut records: Vec<LogRecord> = state
+        .records
+        .iter()
+        .rev()
+        .filter(|record| record.level <= min_level)
+        .filter(|record| category.map_or(true, |c| record.category.eq_ignore_ascii_case(c)))
+        .take(limit)
+        .cloned()
+        .collect();
+    records.reverse();
+    records
+}
+
+/// Drop all records held in memory
+pub fn clear() {
+    if let Ok(mut state) = LOG_STATE.lock() {
+        state.records.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_are_filtered_by_level_and_category() {
+        let category = "logging_test_filter";
+        warn(category, "kept");
+        error(category, "also kept");
+        info("logging_test_other", "other category");
+
+        let records = recent(10, LogLevel::Trace, Some(category));
+        let messages: Vec<&str> = records.iter().map(|r| r.message.as_str()).collect();
+        assert_eq!(messages, vec!["kept", "also kept"]);
+
+        let errors = recent(10, LogLevel::Error, Some(category));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].level, LogLevel::Error);
+
+        assert_eq!(LogLevel::parse("WARNING"), Some(LogLevel::Warn));
+        assert_eq!(LogLevel::parse("loud"), None);
+    }
+
+    #[test]
+    fn test_log_file_receives_json_lines() {
+        let path = std::env::temp_dir()
+            .join(format!("shogi_engine_log_test_{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        set_log_file(Some(&path)).unwrap();
+        warn("logging_test_file", "to file");
+        set_log_file(None).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let record: LogRecord = contents
+            .lines()
+            .filter_map(|line| serde_json::from_str::<LogRecord>(line).ok())
+            .find(|record| record.category == "logging_test_file")
+            .unwrap();
+        assert_eq!(record.message, "to file");
+        assert_eq!(record.level, LogLevel::Warn);
+    }
+}
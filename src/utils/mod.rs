@@ -7,10 +7,12 @@
 //! Submodules:
 //! - time: time sources and convenience helpers
 //! - telemetry: lightweight debug/trace logging integration points
+//! - logging: structured log records (levels, categories, ring buffer, file sink)
 //! - common: small general-purpose helpers
 
 pub mod time;
 pub mod telemetry;
+pub mod logging;
 pub mod common;
 
 
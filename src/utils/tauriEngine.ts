@@ -342,11 +342,13 @@ export function parseBestMove(usiMessage: string): {
 /**
  * Parse engine info messages
  * Example: "info depth 5 seldepth 8 score cp 120 nodes 1234 nps 5000 time 1000 multipv 1 pv 7g7f 3c3d"
+ * With ShowWinRate on, the score is followed by "winrate 550" (permille)
  */
 export function parseEngineInfo(usiMessage: string): {
   depth?: number;
   seldepth?: number;
   score?: number;
+  winRate?: number;
   nodes?: number;
   nps?: number;
   pv?: string[];
@@ -374,6 +376,9 @@ export function parseEngineInfo(usiMessage: string): {
           i += 2;
         }
         break;
+      case 'winrate':
+        info.winRate = parseInt(parts[++i]);
+        break;
       case 'nodes':
         info.nodes = parseInt(parts[++i]);
         break;
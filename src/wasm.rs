@@ -0,0 +1,145 @@
+//! WebAssembly Bindings
+//!
+//! Thin `wasm-bindgen` wrapper for embedding the engine in a web page, built with
+//! the `wasm` feature by `scripts/build_wasm.sh`. A browser has no stdout and
+//! wasm32 has no threads, so searches run to completion on the calling thread
+//! and report their `info` lines through a JavaScript callback.
+//!
+//! ```js
+//! const engine = new WasmEngine();
+//! const stop = engine.stopHandle();
+//! engine.setPosition("startpos", "7g7f 3c3d");
+//! const bestmove = engine.go("movetime 1000", (line) => console.log(line));
+//! ```
+
+use crate::search::info_sink;
+use crate::search::wasm_compatibility::clamp_hash_size;
+use crate::usi::{format_bestmove, GoParams};
+use crate::ShogiEngine;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use wasm_bindgen::prelude::*;
+
+thread_local! {
+    /// JavaScript function receiving the info lines of the running search
+    static INFO_CALLBACK: RefCell<Option<js_sys::Function>> = const { RefCell::new(None) };
+}
+
+/// Pass an info line to this thread's JavaScript callback
+fn call_info_callback(line: &str) {
+    INFO_CALLBACK.with(|callback| {
+        if let Some(callback) = callback.borrow().as_ref() {
+            let _ = callback.call1(&JsValue::NULL, &JsValue::from_str(line));
+        }
+    });
+}
+
+/// Engine instance exposed to JavaScript
+#[wasm_bindgen]
+pub struct WasmEngine {
+    engine: ShogiEngine,
+}
+
+/// Stops a running search. `go` holds the engine for the whole search, so
+/// stopping from an info callback goes through this handle.
+#[wasm_bindgen]
+pub struct StopHandle {
+    stop_flag: Arc<AtomicBool>,
+}
+
+#[wasm_bindgen]
+impl StopHandle {
+    /// Ask the running search to finish with its best move so far
+    pub fn stop(&self) {
+        self.stop_flag.store(true, Ordering::Relaxed);
+    }
+}
+
+#[wasm_bindgen]
+impl WasmEngine {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmEngine {
+        WasmEngine { engine: ShogiEngine::new() }
+    }
+
+    /// Set the position from `startpos` or an SFEN, followed by space-separated USI moves
+    #[wasm_bindgen(js_name = setPosition)]
+    pub fn set_position(&mut self, position: &str, moves: &str) -> Result<(), JsValue> {
+        let mut command = match position.trim() {
+            "startpos" => "startpos".to_string(),
+            sfen => format!("sfen {}", sfen),
+        };
+        if !moves.trim().is_empty() {
+            command.push_str(" moves ");
+            command.push_str(moves.trim());
+        }
+
+        let parts: Vec<&str> = command.split_whitespace().collect();
+        self.engine
+            .apply_position_command(&parts)
+            .map_err(|e| JsValue::from_str(&e.to_usi_info()))
+    }
+
+    /// Set a USI option, returning the engine's response lines joined by newlines.
+    /// `USI_Hash` is capped at what a browser tab can allocate.
+    #[wasm_bindgen(js_name = setOption)]
+    pub fn set_option(&mut self, name: &str, value: &str) -> String {
+        let value = match (name, value.parse::<usize>()) {
+            ("USI_Hash", Ok(size)) => clamp_hash_size(size).to_string(),
+            _ => value.to_string(),
+        };
+        self.engine.handle_setoption(&["name", name, "value", &value]).join("\n")
+    }
+
+    /// Search the current position with USI `go` arguments (`depth 8`,
+    /// `movetime 1000`, `btime 60000 wtime 60000 byoyomi 10000`), calling `on_info`
+    /// with each info line. Returns the `bestmove` line. `infinite` and `ponder`
+    /// need a second thread and are rejected.
+    pub fn go(&mut self, args: &str, on_info: js_sys::Function) -> Result<String, JsValue> {
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        let params = GoParams::parse(&parts);
+        if params.infinite || params.ponder {
+            return Err(JsValue::from_str(
+                "infinite and ponder searches are not supported in wasm builds",
+            ));
+        }
+
+        let depth = params.search_depth(self.engine.depth);
+        let time_limit_ms = params.time_budget_ms(self.engine.current_player);
+        self.engine.set_node_limit(params.nodes);
+        self.engine.stop_flag.store(false, Ordering::Relaxed);
+
+        INFO_CALLBACK.with(|callback| *callback.borrow_mut() = Some(on_info));
+        info_sink::set_thread_info_sink(Some(Arc::new(call_info_callback)));
+        let stop_flag = self.engine.stop_flag.clone();
+        let best_move = self.engine.get_best_move(depth, time_limit_ms, Some(stop_flag));
+        info_sink::set_thread_info_sink(None);
+        INFO_CALLBACK.with(|callback| *callback.borrow_mut() = None);
+
+        Ok(format_bestmove(best_move).remove(0))
+    }
+
+    /// Handle for stopping a search from inside an info callback
+    #[wasm_bindgen(js_name = stopHandle)]
+    pub fn stop_handle(&self) -> StopHandle {
+        StopHandle { stop_flag: self.engine.stop_flag.clone() }
+    }
+
+    /// Stop a search; only reachable between searches, see `StopHandle`
+    pub fn stop(&self) {
+        self.engine.stop_flag.store(true, Ordering::Relaxed);
+    }
+
+    /// Current position in SFEN
+    #[wasm_bindgen(js_name = getSfen)]
+    pub fn get_sfen(&self) -> String {
+        self.engine.get_fen()
+    }
+}
+
+impl Default for WasmEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
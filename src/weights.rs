@@ -2,11 +2,17 @@
 //!
 //! This module provides functionality for loading, managing, and applying
 //! tuned evaluation weights to the engine.
+//!
+//! Weight files come in two forms: the checksummed JSON the tuner writes, and a
+//! plain `weights = [...]` list in JSON or TOML for editing by hand while tuning.
+//! The manager remembers the file it loaded so `reload_weights` can pick up
+//! edits without a restart; the built-in defaults apply whenever no file is
+//! loaded.
 
 use serde::{Deserialize, Serialize};
 use std::fs::File;
-use std::io::{BufReader, BufWriter};
-use std::path::Path;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 use crate::types::evaluation::{NUM_EG_FEATURES, NUM_EVAL_FEATURES, NUM_MG_FEATURES};
@@ -51,6 +57,12 @@ pub struct WeightFile {
     pub weights: Vec<f64>,
 }
 
+/// Hand-edited weights: only the values, no header or checksum
+#[derive(Debug, Clone, Deserialize)]
+struct PlainWeights {
+    weights: Vec<f64>,
+}
+
 /// Weight application statistics
 #[derive(Debug, Clone, Default)]
 pub struct WeightStats {
@@ -100,6 +112,8 @@ pub struct WeightManager {
     stats: WeightStats,
     /// Whether tuned weights are enabled
     enabled: bool,
+    /// File the current weights came from, for `reload_weights`
+    source: Option<PathBuf>,
 }
 
 impl WeightManager {
@@ -111,6 +125,7 @@ impl WeightManager {
             metadata: None,
             stats: WeightStats::default(),
             enabled: false,
+            source: None,
         }
     }
 
@@ -119,40 +134,69 @@ impl WeightManager {
         let path = path.as_ref();
 
         // Try to load the weight file
-        match self.load_weight_file(path) {
-            Ok(weight_file) => {
-                // Validate the weight file
-                self.validate_weight_file(&weight_file)?;
-
+        match self.read_weights(path) {
+            Ok((header, weights)) => {
                 // Set the weights
-                self.weights = Some(weight_file.weights);
-                self.metadata = Some(weight_file.header);
+                self.weights = Some(weights);
+                self.metadata = header;
                 self.enabled = true;
+                self.source = Some(path.to_path_buf());
 
                 Ok(())
             }
             Err(e) => {
                 // Log the error and fall back to default weights
-                eprintln!("Failed to load weights from {:?}: {}", path, e);
+                crate::utils::logging::warn(
+                    "weights",
+                    &format!("Failed to load weights from {:?}: {}", path, e),
+                );
                 self.fallback_to_default();
                 Err(e)
             }
         }
     }
 
-    /// Load weight file from path
-    fn load_weight_file<P: AsRef<Path>>(&self, path: P) -> Result<WeightFile, WeightError> {
-        let file = File::open(path)?;
-        let reader = BufReader::new(file);
+    /// Re-read the file the current weights came from. If it no longer loads,
+    /// the weights in use are kept, so a half-saved edit does not end a session.
+    pub fn reload_weights(&mut self) -> Result<(), WeightError> {
+        let path = self.source.clone().ok_or(WeightError::NoWeightsFile)?;
+        let (header, weights) = self.read_weights(&path)?;
+        self.weights = Some(weights);
+        self.metadata = header;
+        self.enabled = true;
+        Ok(())
+    }
+
+    /// File the current weights were loaded from
+    pub fn weights_path(&self) -> Option<&Path> {
+        self.source.as_deref()
+    }
 
-        // Try to parse as JSON first
-        match serde_json::from_reader(reader) {
-            Ok(weight_file) => Ok(weight_file),
-            Err(_) => {
-                // If JSON fails, try binary format (for future compatibility)
-                Err(WeightError::UnsupportedFormat)
-            }
+    /// Read and validate weights, with the header when the file has one
+    fn read_weights(
+        &self,
+        path: &Path,
+    ) -> Result<(Option<WeightFileHeader>, Vec<f64>), WeightError> {
+        let is_toml = path.extension().map_or(false, |ext| ext.eq_ignore_ascii_case("toml"));
+        if is_toml {
+            let plain: PlainWeights = toml::from_str(&std::fs::read_to_string(path)?)?;
+            self.validate_weights(&plain.weights)?;
+            return Ok((None, plain.weights));
+        }
+
+        let text = std::fs::read_to_string(path)?;
+        if let Ok(weight_file) = self.load_weight_file(&text) {
+            self.validate_weight_file(&weight_file)?;
+            return Ok((Some(weight_file.header), weight_file.weights));
         }
+        let plain: PlainWeights = serde_json::from_str(&text)?;
+        self.validate_weights(&plain.weights)?;
+        Ok((None, plain.weights))
+    }
+
+    /// Parse the tuner's JSON weight file
+    fn load_weight_file(&self, text: &str) -> Result<WeightFile, WeightError> {
+        serde_json::from_str(text).map_err(|_| WeightError::UnsupportedFormat)
     }
 
     /// Validate a weight file for compatibility
@@ -178,16 +222,32 @@ impl WeightManager {
             });
         }
 
+        self.validate_weights(&weight_file.weights)?;
+
+        // Verify checksum
+        let calculated_checksum = self.calculate_checksum(&weight_file.weights);
+        if calculated_checksum != weight_file.header.checksum {
+            return Err(WeightError::ChecksumMismatch {
+                file_checksum: weight_file.header.checksum,
+                calculated_checksum,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Check the count and values of a weight list
+    fn validate_weights(&self, weights: &[f64]) -> Result<(), WeightError> {
         // Check weight count
-        if weight_file.weights.len() != NUM_EVAL_FEATURES {
+        if weights.len() != NUM_EVAL_FEATURES {
             return Err(WeightError::WeightCountMismatch {
-                file_weights: weight_file.weights.len(),
+                file_weights: weights.len(),
                 expected_weights: NUM_EVAL_FEATURES,
             });
         }
 
         // Validate weight values
-        for (i, &weight) in weight_file.weights.iter().enumerate() {
+        for (i, &weight) in weights.iter().enumerate() {
             if !weight.is_finite() {
                 return Err(WeightError::InvalidWeight {
                     index: i,
@@ -195,16 +255,6 @@ impl WeightManager {
                 });
             }
         }
-
-        // Verify checksum
-        let calculated_checksum = self.calculate_checksum(&weight_file.weights);
-        if calculated_checksum != weight_file.header.checksum {
-            return Err(WeightError::ChecksumMismatch {
-                file_checksum: weight_file.header.checksum,
-                calculated_checksum,
-            });
-        }
-
         Ok(())
     }
 
@@ -322,7 +372,8 @@ impl WeightManager {
         self.weights = None;
         self.metadata = None;
         self.enabled = false;
-        eprintln!("Falling back to default evaluation weights");
+        self.source = None;
+        crate::utils::logging::info("weights", "Falling back to default evaluation weights");
     }
 
     /// Create default weights (all 1.0)
@@ -369,6 +420,9 @@ pub enum WeightError {
     #[error("JSON serialization error: {0}")]
     Json(#[from] serde_json::Error),
 
+    #[error("TOML error: {0}")]
+    Toml(#[from] toml::de::Error),
+
     #[error("Invalid magic number in weight file")]
     InvalidMagic,
 
@@ -406,6 +460,9 @@ pub enum WeightError {
 
     #[error("No weights loaded")]
     NoWeightsLoaded,
+
+    #[error("No weights file to reload")]
+    NoWeightsFile,
 }
 
 #[cfg(test)]
@@ -470,6 +527,32 @@ mod tests {
         assert_eq!(metadata.training_positions, 1000);
     }
 
+    #[test]
+    fn test_plain_weights_reload() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("weights.toml");
+        let write = |value: f64| {
+            let values = vec![value.to_string(); NUM_EVAL_FEATURES].join(", ");
+            std::fs::write(&path, format!("weights = [{}]\n", values)).unwrap();
+        };
+
+        let mut manager = WeightManager::new();
+        assert!(matches!(manager.reload_weights(), Err(WeightError::NoWeightsFile)));
+        write(0.5);
+        manager.load_weights(&path).unwrap();
+        assert!(manager.is_enabled() && manager.get_metadata().is_none());
+        assert_eq!(manager.weights_path(), Some(path.as_path()));
+
+        write(2.0);
+        manager.reload_weights().unwrap();
+        assert_eq!(manager.weights.as_ref().unwrap()[0], 2.0);
+
+        // A broken edit keeps the weights in use
+        std::fs::write(&path, "weights = [").unwrap();
+        assert!(manager.reload_weights().is_err());
+        assert_eq!(manager.weights.as_ref().unwrap()[0], 2.0);
+    }
+
     #[test]
     fn test_weight_validation() {
         let mut manager = WeightManager::new();
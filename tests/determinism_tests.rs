@@ -0,0 +1,59 @@
+//! Tests for the Determinism option
+
+use shogi_engine::bitboards::BitboardBoard;
+use shogi_engine::moves::MoveGenerator;
+use shogi_engine::search::move_ordering::MoveOrdering;
+use shogi_engine::types::{CapturedPieces, Player};
+use shogi_engine::ShogiEngine;
+
+const POSITION: [&str; 5] = ["sfen", "3gk4/9/2p1p4/9/4P4/9/9/4G4/4K4", "b", "RS", "1"];
+
+fn deterministic_best_move(depth: u8) -> Option<String> {
+    let mut engine = ShogiEngine::new();
+    engine.set_deterministic(true);
+    engine.handle_position(&POSITION);
+    engine.get_best_move(depth, 1_000, None).map(|mv| mv.to_usi_string())
+}
+
+#[test]
+fn test_fixed_depth_search_is_reproducible() {
+    let first = deterministic_best_move(3);
+    assert!(first.is_some());
+    assert_eq!(first, deterministic_best_move(3));
+}
+
+#[test]
+fn test_repeated_searches_on_one_engine_match() {
+    let mut engine = ShogiEngine::new();
+    engine.set_deterministic(true);
+    engine.handle_position(&POSITION);
+
+    // Earlier searches must not leak TT or history state into later ones
+    let first = engine.get_best_move(3, 1_000, None);
+    let second = engine.get_best_move(3, 1_000, None);
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_determinism_option() {
+    let mut engine = ShogiEngine::new();
+    assert!(!engine.is_deterministic());
+    let output = engine.handle_setoption(&["name", "Determinism", "value", "true"]);
+    assert!(engine.is_deterministic());
+    assert!(output.iter().any(|line| line.contains("deterministic")));
+}
+
+#[test]
+fn test_move_order_does_not_depend_on_generation_order() {
+    let board = BitboardBoard::new();
+    let moves = MoveGenerator::new().generate_legal_moves(
+        &board,
+        Player::Black,
+        &CapturedPieces::new(),
+    );
+    let mut reversed = moves.clone();
+    reversed.reverse();
+
+    let mut ordering = MoveOrdering::new();
+    assert_eq!(ordering.order_moves(&moves).unwrap(), ordering.order_moves(&reversed).unwrap());
+}
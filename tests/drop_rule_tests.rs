@@ -0,0 +1,148 @@
+//! Drop rules against a reference implementation on random positions
+//!
+//! The reference works on a plain 9x9 array and states each rule directly, so
+//! it shares no code with the bitboard implementation. Positions have no kings,
+//! which rules out pawn-drop mate; `uchifuzume_tests` covers that rule.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use shogi_engine::bitboards::BitboardBoard;
+use shogi_engine::drop_rules::{check_drop, DropRuleViolation};
+use shogi_engine::moves::MoveGenerator;
+use shogi_engine::types::{CapturedPieces, Piece, PieceType, Player, Position};
+
+const POSITIONS: usize = 300;
+
+const BOARD_PIECES: [PieceType; 13] = [
+    PieceType::Pawn,
+    PieceType::Lance,
+    PieceType::Knight,
+    PieceType::Silver,
+    PieceType::Gold,
+    PieceType::Bishop,
+    PieceType::Rook,
+    PieceType::PromotedPawn,
+    PieceType::PromotedLance,
+    PieceType::PromotedKnight,
+    PieceType::PromotedSilver,
+    PieceType::PromotedBishop,
+    PieceType::PromotedRook,
+];
+
+const HAND_PIECES: [PieceType; 7] = [
+    PieceType::Pawn,
+    PieceType::Lance,
+    PieceType::Knight,
+    PieceType::Silver,
+    PieceType::Gold,
+    PieceType::Bishop,
+    PieceType::Rook,
+];
+
+type Grid = [[Option<(PieceType, Player)>; 9]; 9];
+
+fn random_position(rng: &mut StdRng) -> (BitboardBoard, Grid) {
+    let mut board = BitboardBoard::empty();
+    let mut grid: Grid = [[None; 9]; 9];
+    for _ in 0..rng.gen_range(0..30) {
+        let (row, col) = (rng.gen_range(0..9u8), rng.gen_range(0..9u8));
+        let piece_type = BOARD_PIECES[rng.gen_range(0..BOARD_PIECES.len())];
+        let player = if rng.gen_bool(0.5) { Player::Black } else { Player::White };
+        let pos = Position::new(row, col);
+        board.remove_piece(pos);
+        board.place_piece(Piece::new(piece_type, player), pos);
+        grid[row as usize][col as usize] = Some((piece_type, player));
+    }
+    (board, grid)
+}
+
+/// The drop rules as stated, rank by rank from the dropping player's side
+fn reference_check(
+    grid: &Grid,
+    piece_type: PieceType,
+    row: usize,
+    col: usize,
+    player: Player,
+) -> Result<(), DropRuleViolation> {
+    if grid[row][col].is_some() {
+        return Err(DropRuleViolation::Occupied);
+    }
+    // Ranks counted from the far side: 1 is the last rank
+    let rank_from_far_side = if player == Player::Black { row + 1 } else { 9 - row };
+    let dead = match piece_type {
+        PieceType::Pawn | PieceType::Lance => rank_from_far_side == 1,
+        PieceType::Knight => rank_from_far_side <= 2,
+        _ => false,
+    };
+    if dead {
+        return Err(DropRuleViolation::NoLegalMoves);
+    }
+    if piece_type == PieceType::Pawn
+        && (0..9).any(|r| grid[r][col] == Some((PieceType::Pawn, player)))
+    {
+        return Err(DropRuleViolation::Nifu);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_drop_rules_match_reference_on_random_positions() {
+    let mut rng = StdRng::seed_from_u64(0xD120_5EED);
+    for _ in 0..POSITIONS {
+        let (board, grid) = random_position(&mut rng);
+        for player in [Player::Black, Player::White] {
+            for piece_type in HAND_PIECES {
+                for row in 0..9 {
+                    for col in 0..9 {
+                        let pos = Position::new(row as u8, col as u8);
+                        assert_eq!(
+                            check_drop(&board, piece_type, pos, player),
+                            reference_check(&grid, piece_type, row, col, player),
+                            "{:?} {:?} drop on {}{}",
+                            player,
+                            piece_type,
+                            9 - col,
+                            (b'a' + row as u8) as char
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_generated_drops_follow_the_rules() {
+    let mut rng = StdRng::seed_from_u64(0xD120_0002);
+    let generator = MoveGenerator::new();
+    for _ in 0..POSITIONS / 3 {
+        let (mut board, mut grid) = random_position(&mut rng);
+        // Legal move generation needs both kings; they replace whatever was on 5i and 5a
+        let kings = [
+            (Player::Black, Position::new(8, 4)),
+            (Player::White, Position::new(0, 4)),
+        ];
+        for (player, pos) in kings {
+            board.remove_piece(pos);
+            board.place_piece(Piece::new(PieceType::King, player), pos);
+            grid[pos.row as usize][pos.col as usize] = Some((PieceType::King, player));
+        }
+        let mut captured = CapturedPieces::new();
+        for piece_type in HAND_PIECES {
+            captured.add_piece(piece_type, Player::Black);
+        }
+
+        for mv in generator.generate_legal_moves(&board, Player::Black, &captured) {
+            if mv.from.is_some() {
+                continue;
+            }
+            let (row, col) = (mv.to.row as usize, mv.to.col as usize);
+            assert_eq!(
+                reference_check(&grid, mv.piece_type, row, col, Player::Black),
+                Ok(()),
+                "generated illegal drop {}",
+                mv.to_usi_string()
+            );
+        }
+    }
+}
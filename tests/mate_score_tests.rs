@@ -0,0 +1,60 @@
+//! Mate score handling on known tsume positions
+
+use shogi_engine::bitboards::BitboardBoard;
+use shogi_engine::search::mate_score::{mate_distance, mate_in, MATE_SCORE};
+use shogi_engine::search::info_sink::set_info_sink;
+use shogi_engine::search::search_engine::SearchEngine;
+use shogi_engine::ShogiEngine;
+use std::sync::{Arc, Mutex};
+
+/// Gote king on 5a, sente pawn on 5c, sente has a gold in hand: G*5b mates
+const GOLD_DROP_MATE_IN_ONE: &str = "4k4/9/4P4/9/9/9/9/9/4K4 b G 1";
+
+#[test]
+fn test_mate_in_one_reports_exact_distance() {
+    let (mut board, player, captured) = BitboardBoard::from_fen(GOLD_DROP_MATE_IN_ONE).unwrap();
+    let mut engine = SearchEngine::new(None, 4);
+
+    let (best_move, score) = engine
+        .search_at_depth_legacy(&mut board, &captured, player, 3, 10_000)
+        .expect("search result");
+    assert_eq!(best_move.to_usi_string(), "G*5b");
+    assert_eq!(score, mate_in(1));
+    assert_eq!(mate_distance(score), Some(1));
+}
+
+#[test]
+fn test_mate_distance_survives_transposition_table() {
+    let (mut board, player, captured) = BitboardBoard::from_fen(GOLD_DROP_MATE_IN_ONE).unwrap();
+    let mut engine = SearchEngine::new(None, 4);
+
+    // The deeper iteration reads mate scores stored by the shallower one from the TT;
+    // without ply adjustment the mate would be reported at the wrong distance.
+    for depth in [3, 5] {
+        let (_, score) = engine
+            .search_at_depth_legacy(&mut board, &captured, player, depth, 10_000)
+            .expect("search result");
+        assert_eq!(score, mate_in(1), "depth {} reported wrong mate distance", depth);
+        assert!(score < MATE_SCORE);
+    }
+}
+
+#[test]
+fn test_root_mate_probe_plays_mate_immediately() {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink_received = received.clone();
+    set_info_sink(Some(Box::new(move |line| {
+        sink_received.lock().unwrap().push(line.to_string());
+    })));
+
+    let mut engine = ShogiEngine::new();
+    engine.handle_position(&["sfen", "4k4/9/4P4/9/9/9/9/9/4K4", "b", "G", "1"]);
+    let best_move = engine.get_best_move(1, 1_000, None);
+    set_info_sink(None);
+
+    assert_eq!(best_move.map(|mv| mv.to_usi_string()).as_deref(), Some("G*5b"));
+    let lines = received.lock().unwrap();
+    assert!(lines
+        .iter()
+        .any(|line| line.contains("score mate 1") && line.ends_with("pv G*5b")));
+}
@@ -0,0 +1,123 @@
+//! Tests for move orderers sharing one transposition table across threads
+
+use shogi_engine::bitboards::BitboardBoard;
+use shogi_engine::search::move_ordering::MoveOrdering;
+use shogi_engine::search::{
+    ThreadSafeTranspositionTable, ThreadSafetyMode, TranspositionConfig, TranspositionMoveOrderer,
+};
+use shogi_engine::types::{CapturedPieces, Move, PieceType, Player, Position};
+use std::sync::{Arc, Barrier};
+use std::thread;
+
+const POSITIONS: [&str; 4] = [
+    "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+    "lnsgkgsnl/1r5b1/ppppppppp/9/9/2P6/PP1PPPPPP/1B5R1/LNSGKGSNL w - 2",
+    "lnsgkgsnl/1r5b1/pppppp1pp/6p2/9/2P6/PP1PPPPPP/1B5R1/LNSGKGSNL b - 3",
+    "lnsgkgsnl/1r5b1/pppppp1pp/6p2/9/2P4P1/PP1PPPP1P/1B5R1/LNSGKGSNL w - 4",
+];
+
+fn shared_table() -> Arc<ThreadSafeTranspositionTable> {
+    Arc::new(ThreadSafeTranspositionTable::with_thread_mode(
+        TranspositionConfig::default(),
+        ThreadSafetyMode::MultiThreaded,
+    ))
+}
+
+fn position(index: usize) -> (BitboardBoard, Player, CapturedPieces) {
+    BitboardBoard::from_fen(POSITIONS[index]).expect("valid sfen")
+}
+
+fn pv_move(index: usize, player: Player) -> Move {
+    let (from_row, to_row) = match player {
+        Player::Black => (6, 5),
+        Player::White => (2, 3),
+    };
+    Move::new_move(
+        Position::new(from_row, index as u8),
+        Position::new(to_row, index as u8),
+        PieceType::Pawn,
+        player,
+        false,
+    )
+}
+
+#[test]
+fn test_move_orderers_are_send() {
+    fn assert_send<T: Send>() {}
+    assert_send::<MoveOrdering>();
+    assert_send::<TranspositionMoveOrderer>();
+}
+
+#[test]
+fn test_pv_moves_stored_on_other_threads_are_visible() {
+    let tt = shared_table();
+    let barrier = Arc::new(Barrier::new(POSITIONS.len()));
+
+    let handles: Vec<_> = (0..POSITIONS.len())
+        .map(|index| {
+            let tt = Arc::clone(&tt);
+            let barrier = Arc::clone(&barrier);
+            thread::spawn(move || {
+                let mut orderer = MoveOrdering::new();
+                orderer.set_transposition_table(&tt);
+                let (board, player, captured) = position(index);
+                barrier.wait();
+                orderer.update_pv_move(&board, &captured, player, 3, pv_move(index, player), 50);
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().expect("writer thread panicked");
+    }
+
+    let mut reader = MoveOrdering::new();
+    reader.set_transposition_table(&tt);
+    for index in 0..POSITIONS.len() {
+        let (board, player, captured) = position(index);
+        let found = reader
+            .get_pv_move(&board, &captured, player, 3)
+            .expect("PV move stored by another thread");
+        assert_eq!(found.to_usi_string(), pv_move(index, player).to_usi_string());
+    }
+}
+
+#[test]
+fn test_concurrent_probes_while_storing() {
+    let tt = shared_table();
+    let (board, player, captured) = position(0);
+    let expected = pv_move(0, player).to_usi_string();
+
+    let readers: Vec<_> = (0..3)
+        .map(|_| {
+            let tt = Arc::clone(&tt);
+            let (board, player, captured) = (board.clone(), player, captured.clone());
+            let expected = expected.clone();
+            thread::spawn(move || {
+                let mut orderer = TranspositionMoveOrderer::new();
+                orderer.set_transposition_table(&tt);
+                for _ in 0..1_000 {
+                    // Either nothing yet or the complete move, never a torn entry
+                    let hints = orderer.get_move_ordering_hints(&board, &captured, player, 1);
+                    if let Some(best_move) = hints.best_move {
+                        assert_eq!(best_move.to_usi_string(), expected);
+                    }
+                }
+            })
+        })
+        .collect();
+
+    let mut writer = MoveOrdering::new();
+    writer.set_transposition_table(&tt);
+    for _ in 0..100 {
+        writer.update_pv_move(&board, &captured, player, 3, pv_move(0, player), 50);
+    }
+    for reader in readers {
+        reader.join().expect("reader thread panicked");
+    }
+
+    // Clearing through the shared handle is seen by every orderer
+    tt.clear();
+    let mut orderer = MoveOrdering::new();
+    orderer.set_transposition_table(&tt);
+    assert!(orderer.get_pv_move(&board, &captured, player, 3).is_none());
+}
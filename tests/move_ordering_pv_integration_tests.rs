@@ -9,6 +9,7 @@ use shogi_engine::bitboards::BitboardBoard;
 use shogi_engine::search::move_ordering::{MoveOrdering, OrderingWeights};
 use shogi_engine::search::{ThreadSafeTranspositionTable, ThreadSafetyMode, TranspositionConfig};
 use shogi_engine::types::*;
+use std::sync::Arc;
 
 #[cfg(test)]
 mod integration_tests {
@@ -37,10 +38,10 @@ mod integration_tests {
     fn test_pv_move_integration_with_transposition_table() {
         // Create transposition table
         let config = TranspositionConfig::default();
-        let mut tt = ThreadSafeTranspositionTable::with_thread_mode(
+        let tt = Arc::new(ThreadSafeTranspositionTable::with_thread_mode(
             config,
             ThreadSafetyMode::SingleThreaded,
-        );
+        ));
 
         // Create move orderer
         let mut orderer = MoveOrdering::new();
@@ -96,10 +97,10 @@ mod integration_tests {
     fn test_pv_move_storage_and_retrieval() {
         // Create transposition table
         let config = TranspositionConfig::default();
-        let mut tt = ThreadSafeTranspositionTable::with_thread_mode(
+        let tt = Arc::new(ThreadSafeTranspositionTable::with_thread_mode(
             config,
             ThreadSafetyMode::SingleThreaded,
-        );
+        ));
 
         // Create move orderer
         let mut orderer = MoveOrdering::new();
@@ -150,10 +151,10 @@ mod integration_tests {
     fn test_pv_move_prioritization_in_ordering() {
         // Create transposition table
         let config = TranspositionConfig::default();
-        let mut tt = ThreadSafeTranspositionTable::with_thread_mode(
+        let tt = Arc::new(ThreadSafeTranspositionTable::with_thread_mode(
             config,
             ThreadSafetyMode::SingleThreaded,
-        );
+        ));
 
         // Create move orderer
         let mut orderer = MoveOrdering::new();
@@ -224,10 +225,10 @@ mod integration_tests {
     fn test_pv_move_cache_effectiveness() {
         // Create transposition table
         let config = TranspositionConfig::default();
-        let mut tt = ThreadSafeTranspositionTable::with_thread_mode(
+        let tt = Arc::new(ThreadSafeTranspositionTable::with_thread_mode(
             config,
             ThreadSafetyMode::SingleThreaded,
-        );
+        ));
 
         // Create move orderer
         let mut orderer = MoveOrdering::new();
@@ -275,10 +276,10 @@ mod integration_tests {
     fn test_pv_move_clear_functionality() {
         // Create transposition table
         let config = TranspositionConfig::default();
-        let mut tt = ThreadSafeTranspositionTable::with_thread_mode(
+        let tt = Arc::new(ThreadSafeTranspositionTable::with_thread_mode(
             config,
             ThreadSafetyMode::SingleThreaded,
-        );
+        ));
 
         // Create move orderer
         let mut orderer = MoveOrdering::new();
@@ -320,10 +321,10 @@ mod integration_tests {
     fn test_pv_move_with_different_positions() {
         // Create transposition table
         let config = TranspositionConfig::default();
-        let mut tt = ThreadSafeTranspositionTable::with_thread_mode(
+        let tt = Arc::new(ThreadSafeTranspositionTable::with_thread_mode(
             config,
             ThreadSafetyMode::SingleThreaded,
-        );
+        ));
 
         // Create move orderer
         let mut orderer = MoveOrdering::new();
@@ -386,10 +387,10 @@ mod integration_tests {
     fn test_pv_move_statistics_accuracy() {
         // Create transposition table
         let config = TranspositionConfig::default();
-        let mut tt = ThreadSafeTranspositionTable::with_thread_mode(
+        let tt = Arc::new(ThreadSafeTranspositionTable::with_thread_mode(
             config,
             ThreadSafetyMode::SingleThreaded,
-        );
+        ));
 
         // Create move orderer
         let mut orderer = MoveOrdering::new();
@@ -0,0 +1,41 @@
+//! Tests for node-count-limited search (`go nodes`)
+
+use shogi_engine::bitboards::BitboardBoard;
+use shogi_engine::search::search_engine::SearchEngine;
+use shogi_engine::types::*;
+
+const NODE_LIMIT: u64 = 3_000;
+
+fn search_with_node_limit(node_limit: u64) -> (Option<Move>, u64) {
+    let mut engine = SearchEngine::new(None, 4);
+    let mut board = BitboardBoard::new();
+    let captured = CapturedPieces::new();
+    engine.set_node_limit(Some(node_limit));
+    let result = engine.search_at_depth_legacy(&mut board, &captured, Player::Black, 8, 600_000);
+    (result.map(|(mv, _)| mv), engine.nodes_since_node_limit())
+}
+
+#[test]
+fn test_node_limit_bounds_search() {
+    let (best_move, nodes) = search_with_node_limit(NODE_LIMIT);
+    assert!(best_move.is_some(), "A node-limited search should still return a move");
+    // A few nodes may be counted after the budget runs out while the search unwinds
+    assert!(nodes < NODE_LIMIT * 2, "Searched {} nodes with a budget of {}", nodes, NODE_LIMIT);
+}
+
+#[test]
+fn test_node_limit_is_reproducible() {
+    let (first, _) = search_with_node_limit(NODE_LIMIT);
+    let (second, _) = search_with_node_limit(NODE_LIMIT);
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_clearing_node_limit() {
+    let mut engine = SearchEngine::new(None, 4);
+    engine.set_node_limit(Some(10));
+    assert_eq!(engine.node_limit(), Some(10));
+    engine.set_node_limit(None);
+    assert_eq!(engine.node_limit(), None);
+    assert!(!engine.node_limit_reached());
+}
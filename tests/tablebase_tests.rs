@@ -439,7 +439,7 @@ mod micro_tablebase_tests {
     fn test_micro_tablebase_creation() {
         let tablebase = MicroTablebase::new();
         assert!(tablebase.is_enabled());
-        assert_eq!(tablebase.solver_count(), 3); // KingGoldVsKing solver
+        assert_eq!(tablebase.solver_count(), 5);
     }
 
     #[test]
@@ -917,7 +917,7 @@ mod endgame_comprehensive_tests {
             let board = empty_board_with(&[
                 (Player::Black, PieceType::King, (4, 4)),
                 (Player::Black, PieceType::Gold, (4, 3)),
-                (Player::Black, PieceType::Silver, (4, 5)),
+                (Player::Black, PieceType::Knight, (4, 5)),
                 (Player::White, PieceType::King, (0, 4)),
             ]);
             PositionFixture::new("invalid_extra_piece_position", Player::Black, board)
@@ -0,0 +1,55 @@
+//! Uchifuzume (打ち歩詰め): a pawn drop that delivers immediate checkmate is illegal
+
+use shogi_engine::bitboards::BitboardBoard;
+use shogi_engine::moves::MoveGenerator;
+
+/// Legal moves of the side to move in `sfen`, as USI strings
+fn legal_moves(sfen: &str) -> Vec<String> {
+    let (board, player, captured) = BitboardBoard::from_fen(sfen).unwrap();
+    MoveGenerator::new()
+        .generate_legal_moves(&board, player, &captured)
+        .iter()
+        .map(|mv| mv.to_usi_string())
+        .collect()
+}
+
+#[test]
+fn test_pawn_drop_mate_is_excluded() {
+    // Gote king on 1a; the gold on 1c guards 1b and the rook on 2i covers 2a and 2b
+    let moves = legal_moves("8k/9/8G/9/9/9/9/9/4K2R1 b P 1");
+    assert!(!moves.contains(&"P*1b".to_string()));
+    // Other pawn drops stay legal
+    assert!(moves.contains(&"P*5e".to_string()));
+}
+
+#[test]
+fn test_pawn_drop_mate_is_excluded_for_gote() {
+    // Mirror image: sente king on 9i, gote gold on 9g, gote rook on 8a
+    let moves = legal_moves("1r2k4/9/9/9/9/9/g8/9/K8 w p 1");
+    assert!(!moves.contains(&"P*9h".to_string()));
+}
+
+#[test]
+fn test_pawn_drop_check_that_can_be_captured_is_legal() {
+    // The gote silver on 2a can take the pawn, so the drop is only check
+    let moves = legal_moves("7sk/9/8G/9/9/9/9/9/4K2R1 b P 1");
+    assert!(moves.contains(&"P*1b".to_string()));
+
+    // Unguarded pawn: the king takes it
+    let moves = legal_moves("8k/9/9/9/9/9/9/9/4K2R1 b P 1");
+    assert!(moves.contains(&"P*1b".to_string()));
+}
+
+#[test]
+fn test_pawn_drop_mate_with_pinned_defender_is_excluded() {
+    // The silver on 2a could take the pawn, but it is pinned by the rook on 9a
+    let moves = legal_moves("R6sk/9/8G/9/9/9/9/9/4K2R1 b P 1");
+    assert!(!moves.contains(&"P*1b".to_string()));
+}
+
+#[test]
+fn test_other_drop_mates_stay_legal() {
+    // A gold drop mate is fine; only pawns are restricted
+    let moves = legal_moves("4k4/9/4P4/9/9/9/9/9/4K4 b G 1");
+    assert!(moves.contains(&"G*5b".to_string()));
+}